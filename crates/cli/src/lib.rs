@@ -71,8 +71,10 @@ pub const FLAG_STDIN: &str = "stdin";
 pub const FLAG_STDOUT: &str = "stdout";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
 pub const FLAG_OUTPUT: &str = "output";
+pub const FLAG_FORMAT: &str = "format";
 pub const FLAG_FUZZ: &str = "fuzz";
 pub const FLAG_MAIN: &str = "main";
+pub const FLAG_PACKAGE: &str = "package";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_DIR: &str = "GLUE_DIR";
@@ -245,6 +247,14 @@ pub fn build_app() -> Command {
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_fuzz.clone())
+            .arg(
+                Arg::new(FLAG_TARGET)
+                    .long(FLAG_TARGET)
+                    .help("Choose a different target to run the tests on\n(`wasm32` runs them in the Wasm interpreter.)")
+                    .default_value(Into::<&'static str>::into(Target::default()))
+                    .value_parser(build_target_values_parser.clone())
+                    .required(false),
+            )
             .arg(
                 Arg::new(FLAG_VERBOSE)
                     .long(FLAG_VERBOSE)
@@ -263,6 +273,14 @@ pub fn build_app() -> Command {
         )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
+            .arg(
+                Arg::new(FLAG_PACKAGE)
+                    .long(FLAG_PACKAGE)
+                    .help("A package or platform whose modules can be imported in the REPL, given as `shorthand=path/or/url/main.roc`\n(The shorthand defaults to `pkg`, as in `import pkg.Module`. This flag can be repeated.)")
+                    .value_parser(value_parser!(String))
+                    .action(ArgAction::Append)
+                    .required(false)
+            )
         )
         .subcommand(Command::new(CMD_RUN)
             .about("Run a .roc file even if it has build errors")
@@ -350,6 +368,13 @@ pub fn build_app() -> Command {
                     .required(false)
                     .default_value(DEFAULT_GENERATED_DOCS_DIR),
                 )
+                .arg(Arg::new(FLAG_FORMAT)
+                    .long(FLAG_FORMAT)
+                    .help("Generate html pages, or a docs.json file for other tools to read")
+                    .value_parser(["html", "json"])
+                    .required(false)
+                    .default_value("html"),
+                )
                 .arg(Arg::new(ROC_FILE)
                     .help("The package's main .roc file")
                     .value_parser(value_parser!(PathBuf))
@@ -454,6 +479,14 @@ pub fn test(_matches: &ArgMatches, _target: Target) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
 }
 
+/// The toplevel expects of a module, compiled for the target that `roc test` runs them on
+#[cfg(not(windows))]
+enum TestProgram {
+    Dylib(libloading::Library),
+    #[cfg(feature = "run-wasm32")]
+    Wasm32(std::vec::Vec<u8>),
+}
+
 struct ModuleTestResults {
     module_id: ModuleId,
     failed_count: usize,
@@ -541,15 +574,37 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
     let interns = loaded.interns.clone();
     let sources = loaded.sources.clone();
 
-    let (dyn_lib, expects_by_module, layout_interner) =
-        roc_repl_expect::run::expect_mono_module_to_dylib(
-            arena,
-            target,
-            loaded,
-            opt_level,
-            LlvmBackendMode::CliTest,
-        )
-        .unwrap();
+    let (test_program, expects_by_module, layout_interner) = match (target, opt_level) {
+        (Target::Wasm32, _) => wasm32_test_program(arena, loaded),
+        (_, OptLevel::Development) => {
+            let (dyn_lib, expects_by_module, layout_interner) =
+                roc_repl_expect::run::expect_mono_module_to_dylib_dev(arena, target, loaded)
+                    .unwrap();
+
+            (
+                TestProgram::Dylib(dyn_lib),
+                expects_by_module,
+                layout_interner,
+            )
+        }
+        (_, OptLevel::Normal | OptLevel::Size | OptLevel::Optimize) => {
+            let (dyn_lib, expects_by_module, layout_interner) =
+                roc_repl_expect::run::expect_mono_module_to_dylib(
+                    arena,
+                    target,
+                    loaded,
+                    opt_level,
+                    LlvmBackendMode::CliTest,
+                )
+                .unwrap();
+
+            (
+                TestProgram::Dylib(dyn_lib),
+                expects_by_module,
+                layout_interner,
+            )
+        }
+    };
 
     // Print warnings before running tests.
     {
@@ -580,16 +635,29 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
     for (module_id, expects) in expects_by_module.into_iter() {
         let test_start_time = Instant::now();
 
-        let (failed_count, passed_count) = roc_repl_expect::run::run_toplevel_expects(
-            &mut writer,
-            roc_reporting::report::RenderTarget::ColorTerminal,
-            arena,
-            interns,
-            &global_layout_interner,
-            &dyn_lib,
-            &mut expectations,
-            expects,
-        )
+        let (failed_count, passed_count) = match &test_program {
+            TestProgram::Dylib(dyn_lib) => roc_repl_expect::run::run_toplevel_expects(
+                &mut writer,
+                roc_reporting::report::RenderTarget::ColorTerminal,
+                arena,
+                interns,
+                &global_layout_interner,
+                dyn_lib,
+                &mut expectations,
+                expects,
+            ),
+            #[cfg(feature = "run-wasm32")]
+            TestProgram::Wasm32(wasm_bytes) => roc_repl_expect::run::run_toplevel_expects_wasm32(
+                &mut writer,
+                roc_reporting::report::RenderTarget::ColorTerminal,
+                arena,
+                interns,
+                &global_layout_interner,
+                wasm_bytes,
+                &mut expectations,
+                expects,
+            ),
+        }
         .unwrap();
 
         let tests_duration = test_start_time.elapsed();
@@ -633,6 +701,37 @@ pub fn test(matches: &ArgMatches, target: Target) -> io::Result<i32> {
     }
 }
 
+#[cfg(all(not(windows), feature = "run-wasm32"))]
+fn wasm32_test_program<'a>(
+    arena: &'a Bump,
+    loaded: roc_load::MonomorphizedModule<'a>,
+) -> (
+    TestProgram,
+    MutMap<ModuleId, roc_repl_expect::run::ExpectFunctions<'a>>,
+    roc_mono::layout::STLayoutInterner<'a>,
+) {
+    let (wasm_bytes, expects_by_module, layout_interner) =
+        roc_repl_expect::run::expect_mono_module_to_wasm32(arena, loaded);
+
+    (
+        TestProgram::Wasm32(wasm_bytes),
+        expects_by_module,
+        layout_interner,
+    )
+}
+
+#[cfg(all(not(windows), not(feature = "run-wasm32")))]
+fn wasm32_test_program<'a>(
+    _arena: &'a Bump,
+    _loaded: roc_load::MonomorphizedModule<'a>,
+) -> (
+    TestProgram,
+    MutMap<ModuleId, roc_repl_expect::run::ExpectFunctions<'a>>,
+    roc_mono::layout::STLayoutInterner<'a>,
+) {
+    user_error!("Running tests on wasm32 is not supported by this build of roc.")
+}
+
 fn print_test_results(
     module_test_results: ModuleTestResults,
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
@@ -795,7 +894,14 @@ pub fn build(
         if matches!(target.architecture(), Architecture::Wasm32) {
             CodeGenBackend::Wasm
         } else {
-            CodeGenBackend::Assembly(AssemblyBackendMode::Binary)
+            let backend_mode = match opt_level {
+                OptLevel::Development => AssemblyBackendMode::BinaryDev,
+                OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => {
+                    AssemblyBackendMode::Binary
+                }
+            };
+
+            CodeGenBackend::Assembly(backend_mode)
         }
     } else {
         let backend_mode = match opt_level {
@@ -1404,12 +1510,13 @@ fn run_wasm<I: Iterator<Item = S>, S: AsRef<[u8]>>(wasm_path: &std::path::Path,
 
     let mut instance = Instance::from_bytes(&arena, &bytes, import_dispatcher, false).unwrap();
 
-    instance
-        .call_export("_start", [])
-        .unwrap()
-        .unwrap()
-        .expect_i32()
-        .unwrap();
+    let result = instance.call_export("_start", []).unwrap();
+
+    if let Some(exit_code) = instance.import_dispatcher.wasi.exit_code {
+        std::process::exit(exit_code);
+    }
+
+    result.unwrap().expect_i32().unwrap();
 }
 
 #[cfg(not(feature = "run-wasm32"))]
@@ -5,11 +5,11 @@ use roc_build::program::{check_file, CodeGenBackend};
 use roc_cli::{
     build_app, format_files, format_src, test, BuildConfig, FormatMode, CMD_BUILD, CMD_CHECK,
     CMD_DEV, CMD_DOCS, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_PREPROCESS_HOST, CMD_REPL,
-    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_LIB, FLAG_MAIN,
-    FLAG_NO_LINK, FLAG_OUTPUT, FLAG_STDIN, FLAG_STDOUT, FLAG_TARGET, FLAG_TIME, GLUE_DIR,
-    GLUE_SPEC, ROC_FILE,
+    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_FORMAT,
+    FLAG_LIB, FLAG_MAIN, FLAG_NO_LINK, FLAG_OUTPUT, FLAG_PACKAGE, FLAG_STDIN, FLAG_STDOUT,
+    FLAG_TARGET, FLAG_TIME, GLUE_DIR, GLUE_SPEC, ROC_FILE,
 };
-use roc_docs::generate_docs_html;
+use roc_docs::{generate_docs_html, generate_docs_json};
 use roc_error_macros::user_error;
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
@@ -77,7 +77,12 @@ fn main() -> io::Result<()> {
         }
         Some((CMD_TEST, matches)) => {
             if matches.contains_id(ROC_FILE) {
-                test(matches, Triple::host().into())
+                let target = matches
+                    .get_one::<String>(FLAG_TARGET)
+                    .and_then(|s| Target::from_str(s).ok())
+                    .unwrap_or_default();
+
+                test(matches, target)
             } else {
                 eprintln!("What .roc file do you want to test? Specify it at the end of the `roc test` command.");
 
@@ -225,14 +230,32 @@ fn main() -> io::Result<()> {
                 }
             }
         }
-        Some((CMD_REPL, _)) => Ok(roc_repl_cli::main()),
+        Some((CMD_REPL, matches)) => {
+            let packages: Vec<&str> = matches
+                .get_many::<String>(FLAG_PACKAGE)
+                .unwrap_or_default()
+                .map(String::as_str)
+                .collect();
+
+            Ok(roc_repl_cli::main(&packages))
+        }
         Some((CMD_DOCS, matches)) => {
             let root_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
             let out_dir = matches.get_one::<OsString>(FLAG_OUTPUT).unwrap();
 
-            generate_docs_html(root_path.to_owned(), out_dir.as_ref());
+            let generated = match matches.get_one::<String>(FLAG_FORMAT).unwrap().as_str() {
+                "json" => generate_docs_json(root_path.to_owned(), out_dir.as_ref()),
+                _ => generate_docs_html(root_path.to_owned(), out_dir.as_ref()),
+            };
 
-            Ok(0)
+            match generated {
+                Ok(()) => Ok(0),
+                Err(report) => {
+                    eprintln!("{report}");
+
+                    Ok(1)
+                }
+            }
         }
         Some((CMD_FORMAT, matches)) => {
             let from_stdin = matches.get_flag(FLAG_STDIN);
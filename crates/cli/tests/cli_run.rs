@@ -647,6 +647,64 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[cfg(feature = "wasm32-cli-run")]
+    fn expects_test_wasm32() {
+        test_roc_expect(
+            "crates/cli/tests/expects",
+            "expects.roc",
+            &[concatcp!(TARGET_FLAG, "=wasm32")],
+            indoc!(
+                r#"
+                ── EXPECT FAILED in tests/expects/expects.roc ──────────────────────────────────
+
+                This expectation failed:
+
+                9│      expect a == 2
+                               ^^^^^^
+
+                When it failed, these variables had these values:
+
+                a : Num *
+                a = 1
+
+                ── EXPECT FAILED in tests/expects/expects.roc ──────────────────────────────────
+
+                This expectation failed:
+
+                10│      expect a == 3
+                                ^^^^^^
+
+                When it failed, these variables had these values:
+
+                a : Num *
+                a = 1
+
+                ── EXPECT FAILED in tests/expects/expects.roc ──────────────────────────────────
+
+                This expectation failed:
+
+                14│>  expect
+                15│>      a = makeA
+                16│>      b = 2i64
+                17│>
+                18│>      a == b
+
+                When it failed, these variables had these values:
+
+                a : Int Signed64
+                a = 1
+
+                b : I64
+                b = 2
+
+
+                1 failed and 0 passed in <ignored for test> ms.
+                "#
+            ),
+        );
+    }
+
     #[test]
     #[cfg_attr(windows, ignore)]
     fn test_module_imports_pkg_w_flag() {
@@ -1623,7 +1681,10 @@ fn run_wasm(wasm_path: &std::path::Path, stdin: &[&str]) -> String {
         Ok(Some(val)) => {
             format!("WASI _start returned an unexpected number type {:?}", val)
         }
-        Ok(None) => "WASI _start returned no value".into(),
+        Ok(None) => match instance.import_dispatcher.wasi.exit_code {
+            Some(exit_code) => format!("WASI app exit code {}", exit_code),
+            None => "WASI _start returned no value".into(),
+        },
         Err(e) => {
             format!("WASI error {}", e)
         }
@@ -1299,11 +1299,45 @@ pub fn llvm_module_to_dylib(
         .write_to_file(module, FileType::Object, &app_o_file)
         .expect("Writing .o file failed");
 
+    app_o_file_to_dylib(target, &app_o_file, &[app_o_file.to_str().unwrap()])
+}
+
+/// Link an object file built by the dev backend into a dylib, together with the zig builtins
+pub fn dev_object_to_dylib(module_out: &[u8], target: Target) -> Result<Library, Error> {
+    let dir = tempfile::tempdir().unwrap();
+    let app_o_file = dir.path().join("app.o");
+
+    std::fs::write(&app_o_file, module_out).expect("Writing .o file failed");
+
+    // Unlike the LLVM backend, the dev backend calls the builtins rather than including them
+    let builtins_host_tempfile =
+        roc_bitcode::host_tempfile().expect("failed to write host builtins object to tempfile");
+
+    let result = app_o_file_to_dylib(
+        target,
+        &app_o_file,
+        &[
+            app_o_file.to_str().unwrap(),
+            builtins_host_tempfile.path().to_str().unwrap(),
+        ],
+    );
+
+    // Only delete the builtins once the linker is done with them
+    drop(builtins_host_tempfile);
+
+    result
+}
+
+fn app_o_file_to_dylib(
+    target: Target,
+    app_o_file: &Path,
+    input_paths: &[&str],
+) -> Result<Library, Error> {
     // Link app.o into a dylib - e.g. app.so or app.dylib
     let (mut child, dylib_path) = link(
         target,
-        app_o_file.clone(),
-        &[app_o_file.to_str().unwrap()],
+        app_o_file.to_path_buf(),
+        input_paths,
         LinkType::Dylib,
     )
     .unwrap();
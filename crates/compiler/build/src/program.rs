@@ -27,7 +27,7 @@ use std::{
 };
 
 #[cfg(feature = "target-wasm32")]
-use roc_collections::all::MutSet;
+use roc_collections::all::{MutMap, MutSet};
 
 pub const DEFAULT_ROC_FILENAME: &str = "main.roc";
 
@@ -42,6 +42,8 @@ pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Proble
     report_problems(
         &loaded.sources,
         &loaded.interns,
+        // Only checking recovers from syntax errors, so there are none by the time of codegen
+        &mut MutMap::default(),
         &mut loaded.can_problems,
         &mut loaded.type_problems,
     )
@@ -51,6 +53,7 @@ pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
     report_problems(
         &loaded.sources,
         &loaded.interns,
+        &mut loaded.syntax_problems,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
     )
@@ -196,7 +199,14 @@ fn gen_from_mono_module_llvm<'a>(
     }
 
     let builder = context.create_builder();
-    let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let (root_path, _) = &loaded.sources[&loaded.module_id];
+    let (dibuilder, compile_unit) =
+        roc_gen_llvm::llvm::build::Env::new_debug_info(module, root_path);
+    let debug_sources = roc_gen_llvm::llvm::debug_info::DebugSources::new(
+        &dibuilder,
+        &loaded.sources,
+        &loaded.symbol_regions,
+    );
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
     // Compile and add all the Procs before adding main
@@ -205,6 +215,7 @@ fn gen_from_mono_module_llvm<'a>(
         builder: &builder,
         dibuilder: &dibuilder,
         compile_unit: &compile_unit,
+        debug_sources: &debug_sources,
         context: &context,
         interns: loaded.interns,
         module,
@@ -555,6 +566,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
         mut interns,
         exposed_to_host,
         mut layout_interner,
+        sources,
         ..
     } = loaded;
 
@@ -564,6 +576,7 @@ fn gen_from_mono_module_dev_assembly<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: backend_mode,
+        sources,
     };
 
     let module_object =
@@ -100,6 +100,11 @@ comptime {
     exportNumFn(num.shiftRightZeroFillI128, "shift_right_zero_fill.i128");
     exportNumFn(num.shiftRightZeroFillU128, "shift_right_zero_fill.u128");
 
+    exportNumFn(num.shiftLeftByI128, "shift_left_by.i128");
+    exportNumFn(num.shiftLeftByU128, "shift_left_by.u128");
+    exportNumFn(num.shiftRightByI128, "shift_right_by.i128");
+    exportNumFn(num.shiftRightByU128, "shift_right_by.u128");
+
     exportNumFn(num.compareI128, "compare.i128");
     exportNumFn(num.compareU128, "compare.u128");
 
@@ -120,6 +125,8 @@ comptime {
     inline for (INTEGERS, 0..) |T, i| {
         num.exportPow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".pow_int.");
         num.exportDivCeil(T, ROC_BUILTINS ++ "." ++ NUM ++ ".div_ceil.");
+        num.exportDivTrunc(T, ROC_BUILTINS ++ "." ++ NUM ++ ".div_trunc.");
+        num.exportRem(T, ROC_BUILTINS ++ "." ++ NUM ++ ".rem.");
 
         num.exportRound(f32, T, ROC_BUILTINS ++ "." ++ NUM ++ ".round_f32.");
         num.exportRound(f64, T, ROC_BUILTINS ++ "." ++ NUM ++ ".round_f64.");
@@ -134,16 +141,21 @@ comptime {
         num.exportAddWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_with_overflow.");
         num.exportAddOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_or_panic.");
         num.exportAddSaturatedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_saturated.");
+        num.exportAddWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_wrapped.");
 
         num.exportSubWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_with_overflow.");
         num.exportSubOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_or_panic.");
         num.exportSubSaturatedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_saturated.");
+        num.exportSubWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_wrapped.");
 
         num.exportMulWithOverflow(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_with_overflow.");
         num.exportMulOrPanic(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_or_panic.");
         num.exportMulSaturatedInt(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_saturated.");
         num.exportMulWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".mul_wrapped.");
 
+        num.exportAbsOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".abs_or_panic.");
+        num.exportNegOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".neg_or_panic.");
+
         num.exportIsMultipleOf(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_multiple_of.");
 
         num.exportCountLeadingZeroBits(T, ROC_BUILTINS ++ "." ++ NUM ++ ".count_leading_zero_bits.");
@@ -241,14 +253,15 @@ comptime {
     @export(panic_utils.panic, .{ .name = "roc_builtins.utils." ++ "panic", .linkage = .Weak });
     @export(dbg_utils.dbg_impl, .{ .name = "roc_builtins.utils." ++ "dbg_impl", .linkage = .Weak });
 
+    exportUtilsFn(expect.expectFailedStartSharedBuffer, "expect_failed_start_shared_buffer");
+
+    // sets the buffer used for expect failures
+    @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
+
     if (builtin.target.cpu.arch != .wasm32) {
-        exportUtilsFn(expect.expectFailedStartSharedBuffer, "expect_failed_start_shared_buffer");
         exportUtilsFn(expect.expectFailedStartSharedFile, "expect_failed_start_shared_file");
         exportUtilsFn(expect.notifyParentExpect, "notify_parent_expect");
 
-        // sets the buffer used for expect failures
-        @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
-
         exportUtilsFn(expect.readSharedBufferEnv, "read_env_shared_buffer");
     }
 
@@ -262,6 +262,24 @@ pub fn exportDivCeil(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportDivTrunc(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(a: T, b: T) callconv(.C) T {
+            return @divTrunc(a, b);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportRem(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(a: T, b: T) callconv(.C) T {
+            return @rem(a, b);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 pub fn ToIntCheckedResult(comptime T: type) type {
     // On the Roc side we sort by alignment; putting the errorcode last
     // always works out (no number with smaller alignment than 1).
@@ -375,6 +393,15 @@ pub fn exportAddOrPanic(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportAddWrappedInt(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) T {
+            return self +% other;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 fn subWithOverflow(comptime T: type, self: T, other: T) WithOverflow(T) {
     switch (@typeInfo(T)) {
         .Int => {
@@ -432,6 +459,15 @@ pub fn exportSubOrPanic(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportSubWrappedInt(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) T {
+            return self -% other;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 fn mulWithOverflow(comptime T: type, comptime W: type, self: T, other: T) WithOverflow(T) {
     switch (@typeInfo(T)) {
         .Int => {
@@ -534,6 +570,41 @@ pub fn exportMulWrappedInt(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportAbsOrPanic(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) T {
+            if (@typeInfo(T).Int.signedness == .unsigned) {
+                return self;
+            } else if (self == std.math.minInt(T)) {
+                roc_panic("Integer absolute overflowed because its argument is the minimum value", 0);
+            } else if (self < 0) {
+                return -self;
+            } else {
+                return self;
+            }
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportNegOrPanic(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) T {
+            if (@typeInfo(T).Int.signedness == .unsigned) {
+                if (self != 0) {
+                    roc_panic("Integer negation overflowed because its argument is the minimum value", 0);
+                }
+                return 0;
+            } else if (self == std.math.minInt(T)) {
+                roc_panic("Integer negation overflowed because its argument is the minimum value", 0);
+            } else {
+                return -self;
+            }
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 pub fn shiftRightZeroFillI128(self: i128, other: u8) callconv(.C) i128 {
     if (other & 0b1000_0000 > 0) {
         return 0;
@@ -550,6 +621,36 @@ pub fn shiftRightZeroFillU128(self: u128, other: u8) callconv(.C) u128 {
     }
 }
 
+pub fn shiftLeftByI128(self: i128, other: u8) callconv(.C) i128 {
+    if (other & 0b1000_0000 > 0) {
+        return 0;
+    } else {
+        return self << @as(u7, @intCast(other));
+    }
+}
+
+pub fn shiftLeftByU128(self: u128, other: u8) callconv(.C) u128 {
+    if (other & 0b1000_0000 > 0) {
+        return 0;
+    } else {
+        return self << @as(u7, @intCast(other));
+    }
+}
+
+pub fn shiftRightByI128(self: i128, other: u8) callconv(.C) i128 {
+    if (other & 0b1000_0000 > 0) {
+        // every bit is a copy of the sign bit
+        return self >> 127;
+    } else {
+        return self >> @as(u7, @intCast(other));
+    }
+}
+
+// Roc's shiftRightBy is an arithmetic shift, even for unsigned integers
+pub fn shiftRightByU128(self: u128, other: u8) callconv(.C) u128 {
+    return @as(u128, @bitCast(shiftRightByI128(@as(i128, @bitCast(self)), other)));
+}
+
 pub fn compareI128(self: i128, other: i128) callconv(.C) Ordering {
     if (self == other) {
         return Ordering.EQ;
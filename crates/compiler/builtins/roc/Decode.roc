@@ -23,6 +23,8 @@ module [
     list,
     record,
     tuple,
+    tag,
+    tagAsRecord,
     custom,
     decodeWith,
     fromBytesPartial,
@@ -112,6 +114,42 @@ DecoderFormatting implements
     ## `finalizer` should produce the tuple value from the decoded `state`.
     tuple : state, (state, U64 -> [Next (Decoder state fmt), TooLong]), (state -> Result val DecodeError) -> Decoder val fmt where fmt implements DecoderFormatting
 
+    ## `tag stepTag` decodes a value of a tag union.
+    ##
+    ## `stepTag` returns a decoder for the tag with the given name, or
+    ## `UnknownTag` if the tag is not a part of the decoded union. The returned
+    ## decoder reads the tag's payload the same way [tuple] reads its elements.
+    ##
+    ## This member was added after the others, so existing formats must now
+    ## implement it. A format that has no tag syntax of its own can use
+    ## [tagAsRecord], which decodes a tag as a single-field record:
+    ## ```roc
+    ## tag: Decode.tagAsRecord,
+    ## ```
+    tag : (Str -> [Next (Decoder val fmt), UnknownTag]) -> Decoder val fmt where fmt implements DecoderFormatting
+
+## Decode a tag union value as a record with a single field, named after the
+## tag, whose value is the tag's payload. For example `A 1 2` is decoded from
+## the format's equivalent of `{ A: (1, 2) }`.
+##
+## This is a ready-made [tag] implementation for formats which only know how to
+## decode records and tuples.
+tagAsRecord : (Str -> [Next (Decoder val fmt), UnknownTag]) -> Decoder val fmt where fmt implements DecoderFormatting
+tagAsRecord = \stepTag ->
+    stepField = \_state, name ->
+        when stepTag name is
+            Next payloadDecoder ->
+                Keep (custom \bytes, fmt -> decodeWith bytes payloadDecoder fmt |> mapResult Ok)
+
+            UnknownTag -> Skip
+
+    finalizer = \state, _fmt ->
+        when state is
+            Ok val -> Ok val
+            Err NoTag -> Err TooShort
+
+    record (Err NoTag) stepField finalizer
+
 ## Build a custom [Decoder] function. For example the implementation of
 ## `decodeBool` could be defined as follows;
 ##
@@ -58,6 +58,7 @@ Json := {}
             list: decodeList,
             record: decodeRecord,
             tuple: decodeTuple,
+            tag: decodeTag,
         },
     ]
 
@@ -582,6 +583,65 @@ expect
 
     actual.result == Ok ("The Answer is", 42)
 
+decodeTag = \stepTag -> Decode.custom \bytes, @Json {} ->
+        # Idea: decode `{"A": [v1, v2]}` as `A v1 v2`, mirroring `encodeTag`
+        countBytesBeforeName =
+            when List.walkUntil bytes (BeforeOpeningBrace 0) objectHelp is
+                ObjectFieldNameStart n -> n
+                _ -> 0
+
+        if countBytesBeforeName == 0 then
+            # Invalid tag, expected opening brace '{' followed by the tag name
+            { result: Err TooShort, rest: bytes }
+        else
+            bytesBeforeName = List.dropFirst bytes countBytesBeforeName
+
+            { val: tagName, rest: bytesAfterName } <- Decode.decodeWith bytesBeforeName decodeString json |> tryDecode
+
+            # Count the bytes until the payload
+            countBytesBeforePayload =
+                when List.walkUntil bytesAfterName (BeforeColon 0) objectHelp is
+                    AfterColon n -> n
+                    _ -> 0
+
+            payloadBytes = List.dropFirst bytesAfterName countBytesBeforePayload
+
+            when stepTag tagName is
+                UnknownTag -> { result: Err TooShort, rest: bytes }
+                Next payloadDecoder ->
+                    { val, rest: bytesAfterPayload } <- Decode.decodeWith payloadBytes payloadDecoder json |> tryDecode
+
+                    when List.walkUntil bytesAfterPayload (AfterObjectValue 0) objectHelp is
+                        AfterClosingBrace n -> { result: Ok val, rest: List.dropFirst bytesAfterPayload n }
+                        _ -> { result: Err TooShort, rest: bytesAfterPayload }
+
+# Test decode of tag with a payload
+expect
+    input = Str.toUtf8 "{\"Answer\": [\"The Answer is\",42]}"
+
+    actual : DecodeResult [Answer Str U8, Question]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok (Answer "The Answer is" 42)
+
+# Test decode of tag without a payload
+expect
+    input = Str.toUtf8 "{\"Question\":[]}"
+
+    actual : DecodeResult [Answer Str U8, Question]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok Question
+
+# Test decode of unknown tag
+expect
+    input = Str.toUtf8 "{\"Statement\":[]}"
+
+    actual : DecodeResult [Answer Str U8, Question]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Err TooShort
+
 parseExactChar : List U8, U8 -> DecodeResult {}
 parseExactChar = \bytes, char ->
     when List.get bytes 0 is
@@ -286,6 +286,8 @@ pub const NUM_SQRT: IntrinsicName = float_intrinsic!("roc_builtins.num.sqrt");
 
 pub const NUM_POW_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.pow_int");
 pub const NUM_DIV_CEIL: IntrinsicName = int_intrinsic!("roc_builtins.num.div_ceil");
+pub const NUM_DIV_TRUNC: IntrinsicName = int_intrinsic!("roc_builtins.num.div_trunc");
+pub const NUM_REM: IntrinsicName = int_intrinsic!("roc_builtins.num.rem");
 pub const NUM_CEILING_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.ceiling_f32");
 pub const NUM_CEILING_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.ceiling_f64");
 pub const NUM_FLOOR_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.floor_f32");
@@ -299,12 +301,14 @@ pub const INT_TO_FLOAT_CAST_F64: IntrinsicName =
 
 pub const NUM_ADD_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_or_panic");
 pub const NUM_ADD_SATURATED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_saturated");
+pub const NUM_ADD_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_wrapped");
 pub const NUM_ADD_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_with_overflow");
 pub const NUM_ADD_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.add_with_overflow");
 
 pub const NUM_SUB_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_or_panic");
 pub const NUM_SUB_SATURATED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_saturated");
+pub const NUM_SUB_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_wrapped");
 pub const NUM_SUB_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_with_overflow");
 pub const NUM_SUB_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.sub_with_overflow");
@@ -316,8 +320,13 @@ pub const NUM_MUL_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.
 pub const NUM_MUL_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.mul_with_overflow");
 
+pub const NUM_ABS_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.abs_or_panic");
+pub const NUM_NEG_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.neg_or_panic");
+
 pub const NUM_IS_MULTIPLE_OF: IntrinsicName = int_intrinsic!("roc_builtins.num.is_multiple_of");
 
+pub const NUM_SHIFT_LEFT_BY: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_left_by");
+pub const NUM_SHIFT_RIGHT_BY: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_right_by");
 pub const NUM_SHIFT_RIGHT_ZERO_FILL: IntrinsicName =
     int_intrinsic!("roc_builtins.num.shift_right_zero_fill");
 
@@ -419,6 +428,7 @@ pub const DEC_SUB_OR_PANIC: &str = "roc_builtins.dec.sub_or_panic";
 pub const DEC_SUB_SATURATED: &str = "roc_builtins.dec.sub_saturated";
 pub const DEC_SUB_WITH_OVERFLOW: &str = "roc_builtins.dec.sub_with_overflow";
 pub const DEC_TAN: &str = "roc_builtins.dec.tan";
+pub const DEC_TO_F64: &str = "roc_builtins.dec.to_f64";
 pub const DEC_TO_I128: &str = "roc_builtins.dec.to_i128";
 pub const DEC_FROM_I128: &str = "roc_builtins.dec.from_i128";
 pub const DEC_TO_STR: &str = "roc_builtins.dec.to_str";
@@ -1124,7 +1124,13 @@ fn canonicalize_value_defs<'a>(
     for (def_index, pending_def) in pending_value_defs.iter().enumerate() {
         let mut new_bindings = BindingsFromPattern::new(pending_def.loc_pattern()).peekable();
 
-        if new_bindings.peek().is_none() {
+        // A def skipped over by a syntax error already has that error reported
+        let is_malformed = matches!(
+            pending_def,
+            PendingValueDef::Body(_, body) if matches!(body.value, ast::Expr::Malformed(_))
+        );
+
+        if new_bindings.peek().is_none() && !is_malformed {
             env.problem(Problem::NoIdentifiersIntroduced(
                 pending_def.loc_pattern().region,
             ));
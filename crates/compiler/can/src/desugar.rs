@@ -299,6 +299,7 @@ pub fn desugar_expr<'a>(
         | MalformedIdent(_, _)
         | MalformedClosure
         | MalformedSuffixed(..)
+        | Malformed(_)
         | PrecedenceConflict { .. }
         | MultipleRecordBuilders { .. }
         | UnappliedRecordBuilder { .. }
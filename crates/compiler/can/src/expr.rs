@@ -1343,6 +1343,12 @@ pub fn canonicalize_expr<'a>(
             use roc_problem::can::RuntimeError::*;
             (RuntimeError(MalformedSuffixed(region)), Output::default())
         }
+        ast::Expr::Malformed(_) => {
+            use roc_problem::can::RuntimeError::*;
+
+            // The syntax error was already reported by the parser
+            (RuntimeError(MalformedExpr(region)), Output::default())
+        }
         ast::Expr::MultipleRecordBuilders(sub_expr) => {
             use roc_problem::can::RuntimeError::*;
 
@@ -2382,7 +2388,8 @@ pub fn is_valid_interpolation(expr: &ast::Expr<'_>) -> bool {
         | ast::Expr::MalformedIdent(_, _)
         | ast::Expr::Tag(_)
         | ast::Expr::OpaqueRef(_)
-        | ast::Expr::MalformedClosure => true,
+        | ast::Expr::MalformedClosure
+        | ast::Expr::Malformed(_) => true,
         // Newlines are disallowed inside interpolation, and these all require newlines
         ast::Expr::Dbg(_, _)
         | ast::Expr::LowLevelDbg(_, _, _)
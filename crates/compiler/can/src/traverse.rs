@@ -2,7 +2,10 @@
 
 use roc_module::{ident::Lowercase, symbol::Symbol};
 use roc_region::all::{Loc, Position, Region};
-use roc_types::{subs::Variable, types::MemberImpl};
+use roc_types::{
+    subs::Variable,
+    types::{IndexOrField, MemberImpl},
+};
 
 use crate::{
     abilities::AbilitiesStore,
@@ -963,3 +966,266 @@ pub fn find_declaration(symbol: Symbol, decls: &'_ Declarations) -> Option<Found
         }
     }
 }
+
+/// How a [Symbol] appears at the region of a reference found by [find_symbol_references].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolReference {
+    /// The region ends with the name of the symbol, e.g. a definition or lookup of `foo`, a
+    /// qualified lookup `Module.foo`, or `pattern as foo`.
+    Name,
+    /// The symbol is the record being updated in `{ foo & field: value }`.
+    RecordUpdate,
+    /// The symbol is both a record field label and the name of a variable, as in the record
+    /// literal `{ foo }` or the destructure `{ foo } = record`.
+    RecordPun,
+    /// The symbol is bound by an optional record field destructure `{ foo ? default }`.
+    OptionalRecordField,
+    /// The region is the type annotation of the symbol; the name of the symbol precedes it, as
+    /// in `foo : Str`.
+    Annotation,
+}
+
+/// Finds every occurrence of `symbol` in `decls`, including its definition.
+pub fn find_symbol_references(symbol: Symbol, decls: &Declarations) -> Vec<Loc<SymbolReference>> {
+    let mut visitor = Finder {
+        symbol,
+        found: Vec::new(),
+    };
+
+    // The annotations of top-level functions aren't part of their [DeclarationInfo].
+    for (index, loc_symbol) in decls.symbols.iter().enumerate() {
+        if loc_symbol.value == symbol {
+            if let Some(annotation) = &decls.annotations[index] {
+                visitor.push(annotation.region, SymbolReference::Annotation);
+            }
+        }
+    }
+
+    visitor.visit_decls(decls);
+
+    let mut found = visitor.found;
+    found.sort_by_key(|loc| loc.region.start());
+    found.dedup_by_key(|loc| loc.region);
+    return found;
+
+    struct Finder {
+        symbol: Symbol,
+        found: Vec<Loc<SymbolReference>>,
+    }
+
+    impl Finder {
+        fn push(&mut self, region: Region, reference: SymbolReference) {
+            self.found.push(Loc::at(region, reference));
+        }
+    }
+
+    impl Visitor for Finder {
+        fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+            if let DeclarationInfo::Destructure {
+                loc_pattern,
+                annotation: Some(annotation),
+                ..
+            } = &decl
+            {
+                if matches!(loc_pattern.value, Pattern::Identifier(s) if s == self.symbol) {
+                    self.push(annotation.region, SymbolReference::Annotation);
+                }
+            }
+
+            walk_decl(self, decl);
+        }
+
+        fn visit_def(&mut self, def: &Def) {
+            if let Some(annotation) = &def.annotation {
+                if matches!(def.loc_pattern.value, Pattern::Identifier(s) if s == self.symbol) {
+                    self.push(annotation.region, SymbolReference::Annotation);
+                }
+            }
+
+            walk_def(self, def);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Var(symbol, _) | Expr::AbilityMember(symbol, _, _)
+                    if *symbol == self.symbol =>
+                {
+                    self.push(region, SymbolReference::Name);
+                }
+                Expr::RecordUpdate { symbol, .. } if *symbol == self.symbol => {
+                    self.push(region, SymbolReference::RecordUpdate);
+                }
+                Expr::Record { fields, .. } => {
+                    for field in fields.values() {
+                        // `{ foo }` is desugared to `{ foo: foo }`, where the variable has the
+                        // region of the label.
+                        if is_pun(field)
+                            && matches!(field.loc_expr.value, Expr::Var(s, _) if s == self.symbol)
+                        {
+                            self.push(field.loc_expr.region, SymbolReference::RecordPun);
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::Shadowed(_, _, symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+                | Pattern::As(_, symbol)
+                    if *symbol == self.symbol =>
+                {
+                    self.push(region, SymbolReference::Name);
+                }
+                _ => {}
+            }
+
+            walk_pattern(self, pattern);
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            if destruct.symbol == self.symbol {
+                match destruct.typ {
+                    DestructType::Required => self.push(region, SymbolReference::RecordPun),
+                    DestructType::Optional(..) => {
+                        self.push(region, SymbolReference::OptionalRecordField)
+                    }
+                    DestructType::Guard(..) => { /* the symbol is bound in the guard pattern */ }
+                }
+            }
+
+            walk_record_destruct(self, destruct);
+        }
+    }
+}
+
+fn is_pun(field: &Field) -> bool {
+    field.region.start() == field.loc_expr.region.start()
+}
+
+/// How a record field label appears at the region of a [RecordFieldReference].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldLabelAt {
+    /// The region starts with the label, as in `{ foo: value }` or `{ foo: pattern }`.
+    Start,
+    /// The region ends with the label, as in `record.foo` or the accessor `.foo`.
+    End,
+    /// The label is also the name of a variable, as in `{ foo }`.
+    Pun,
+    /// The label is also the name of a variable bound by `{ foo ? default }`.
+    OptionalPun,
+}
+
+/// An occurrence of a record field label found by [find_record_field_references].
+#[derive(Debug)]
+pub struct RecordFieldReference {
+    pub region: Region,
+    pub label_at: FieldLabelAt,
+    /// The type of the record the field belongs to.
+    pub record_var: Variable,
+}
+
+/// Finds every record field labelled `label` that is constructed, updated, accessed or
+/// destructured in `decls`.
+pub fn find_record_field_references(
+    label: &Lowercase,
+    decls: &Declarations,
+) -> Vec<RecordFieldReference> {
+    let mut visitor = Finder {
+        label,
+        found: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct Finder<'a> {
+        label: &'a Lowercase,
+        found: Vec<RecordFieldReference>,
+    }
+
+    impl Finder<'_> {
+        fn push(&mut self, region: Region, label_at: FieldLabelAt, record_var: Variable) {
+            self.found.push(RecordFieldReference {
+                region,
+                label_at,
+                record_var,
+            });
+        }
+
+        fn push_fields<'a>(
+            &mut self,
+            record_var: Variable,
+            fields: impl Iterator<Item = (&'a Lowercase, &'a Field)>,
+        ) {
+            for (label, field) in fields {
+                if label == self.label {
+                    let label_at = if is_pun(field) {
+                        FieldLabelAt::Pun
+                    } else {
+                        FieldLabelAt::Start
+                    };
+                    self.push(field.region, label_at, record_var);
+                }
+            }
+        }
+    }
+
+    impl Visitor for Finder<'_> {
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Record { record_var, fields } => self.push_fields(*record_var, fields.iter()),
+                Expr::RecordUpdate {
+                    record_var,
+                    updates,
+                    ..
+                } => self.push_fields(*record_var, updates.iter()),
+                Expr::RecordAccess {
+                    record_var, field, ..
+                } if field == self.label => {
+                    self.push(region, FieldLabelAt::End, *record_var);
+                }
+                Expr::RecordAccessor(StructAccessorData {
+                    record_var,
+                    field: IndexOrField::Field(field),
+                    ..
+                }) if field == self.label => {
+                    self.push(region, FieldLabelAt::End, *record_var);
+                }
+                _ => {}
+            }
+
+            walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(
+            &mut self,
+            pattern: &Pattern,
+            _region: Region,
+            _opt_var: Option<Variable>,
+        ) {
+            if let Pattern::RecordDestructure {
+                whole_var,
+                destructs,
+                ..
+            } = pattern
+            {
+                let label = self.label;
+                for destruct in destructs.iter().filter(|d| &d.value.label == label) {
+                    let label_at = match destruct.value.typ {
+                        DestructType::Required => FieldLabelAt::Pun,
+                        DestructType::Optional(..) => FieldLabelAt::OptionalPun,
+                        DestructType::Guard(..) => FieldLabelAt::Start,
+                    };
+                    self.push(destruct.region, label_at, *whole_var);
+                }
+            }
+
+            walk_pattern(self, pattern);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use roc_can::expr::{AnnotatedMark, ClosureData, Expr, Recursive, WhenBranch, WhenBranchPattern};
+use roc_can::pattern::Pattern;
+use roc_module::called_via::CalledVia;
+use roc_module::ident::TagName;
+use roc_module::symbol::Symbol;
+use roc_region::all::{Loc, Region};
+use roc_types::subs::{
+    Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RedundantMark,
+    SubsSlice, TagExt, UnionLambdas, UnionTags, Variable, VariableSubsSlice,
+};
+
+use crate::synth_var;
+use crate::util::{Env, ExtensionKind};
+
+use super::tuple::{decode_tuple, Finalized};
+use super::wrap_in_decode_custom_decode_with;
+
+/// Implements decoding of a tag union. For example, for
+///
+/// ```text
+///   [A a b, B c]
+/// ```
+///
+/// we'd like to generate an impl like
+///
+/// ```roc
+/// decoder : Decoder [A a b, B c] fmt where a implements Decoding, b implements Decoding, c implements Decoding, fmt implements DecoderFormatting
+/// decoder =
+///     stepTag = \tagName ->
+///         when tagName is
+///             "A" ->
+///                 # The finalizer of this tuple decoder returns `Ok (A e0 e1)`.
+///                 Next (Decode.custom \bytes, fmt ->
+///                     Decode.decodeWith bytes (Decode.tuple initialState stepElem finalizer) fmt)
+///             "B" ->
+///                 Next (Decode.custom \bytes, fmt ->
+///                     Decode.decodeWith bytes (Decode.tuple initialState stepElem finalizer) fmt)
+///             _ -> UnknownTag
+///
+///     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tag stepTag) fmt
+/// ```
+///
+/// The payload of each tag is decoded like a tuple of the tag's arguments; see [super::tuple].
+pub(crate) fn decoder(
+    env: &mut Env,
+    _def_symbol: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Expr, Variable) {
+    // Generalized tag union var so we can reuse this impl between many unions:
+    // if tags = [ A arity=2, B arity=1 ], this is [ A t1 t2, B t3 ] for fresh t1, t2, t3
+    let flex_tag_labels = tags
+        .into_iter()
+        .map(|(label, arity)| {
+            let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+            for var_index in variables_slice {
+                env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+            }
+            (label, variables_slice)
+        })
+        .collect::<Vec<_>>();
+    let union_tags = UnionTags::insert_slices_into_subs(env.subs, flex_tag_labels.clone());
+    let tag_union_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(
+            union_tags,
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        )),
+    );
+
+    // stepTag = ...
+    let (step_tag, step_var) = step_tag(env, tag_union_var, flex_tag_labels);
+
+    // Build up the type of `Decode.tag` we expect
+    let tag_decoder_var = env.subs.fresh_unnamed_flex_var();
+    let decode_tag_lambda_set = env.subs.fresh_unnamed_flex_var();
+    let decode_tag_var = env.import_builtin_symbol_var(Symbol::DECODE_TAG);
+    let this_decode_tag_var = {
+        let flat_type = FlatType::Func(
+            SubsSlice::insert_into_subs(env.subs, [step_var]),
+            decode_tag_lambda_set,
+            tag_decoder_var,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    env.unify(decode_tag_var, this_decode_tag_var);
+
+    // Decode.tag stepTag
+    let call_decode_tag = Expr::Call(
+        Box::new((
+            this_decode_tag_var,
+            Loc::at_zero(Expr::AbilityMember(
+                Symbol::DECODE_TAG,
+                None,
+                this_decode_tag_var,
+            )),
+            decode_tag_lambda_set,
+            tag_decoder_var,
+        )),
+        vec![(step_var, Loc::at_zero(step_tag))],
+        CalledVia::Space,
+    );
+
+    let bytes_sym = env.new_symbol("bytes");
+    let fmt_sym = env.new_symbol("fmt");
+    let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+    wrap_in_decode_custom_decode_with(
+        env,
+        bytes_sym,
+        (fmt_sym, fmt_var),
+        vec![],
+        (call_decode_tag, tag_decoder_var),
+    )
+}
+
+// Example:
+// stepTag = \tagName ->
+//     when tagName is
+//         "A" ->
+//             Next (Decode.custom \bytes, fmt ->
+//                 Decode.decodeWith bytes (Decode.tuple initialState stepElem finalizer) fmt)
+//         "B" ->
+//             Next (Decode.custom \bytes, fmt ->
+//                 Decode.decodeWith bytes (Decode.tuple initialState stepElem finalizer) fmt)
+//         _ -> UnknownTag
+fn step_tag(
+    env: &mut Env,
+    tag_union_var: Variable,
+    tags: Vec<(TagName, VariableSubsSlice)>,
+) -> (Expr, Variable) {
+    let tag_name_arg_symbol = env.new_symbol("tagName");
+
+    // +1 because of the default branch.
+    let mut branches = Vec::with_capacity(tags.len() + 1);
+    let next_payload_var = env.subs.fresh_unnamed_flex_var();
+    let next_or_unknown_var = {
+        let next_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [next_payload_var]);
+        let flat_type = FlatType::TagUnion(
+            UnionTags::insert_slices_into_subs(
+                env.subs,
+                [
+                    ("Next".into(), next_payload_subs_slice),
+                    ("UnknownTag".into(), Default::default()),
+                ],
+            ),
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    for (tag_name, payload_vars) in tags {
+        let payload_vars = env.subs.get_subs_slice(payload_vars).to_vec();
+
+        // Decode.tuple initialState stepElem finalizer
+        let (call_decode_tuple, tuple_decoder_var) = decode_tuple(
+            env,
+            &payload_vars,
+            Finalized::Tag(tag_name.clone(), tag_union_var),
+        );
+
+        // Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tuple ...) fmt
+        let (decode_custom, decode_custom_var) = {
+            let bytes_sym = env.new_symbol("bytes");
+            let fmt_sym = env.new_symbol("fmt");
+            let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+            wrap_in_decode_custom_decode_with(
+                env,
+                bytes_sym,
+                (fmt_sym, fmt_var),
+                vec![],
+                (call_decode_tuple, tuple_decoder_var),
+            )
+        };
+
+        env.unify(next_payload_var, decode_custom_var);
+
+        // "A" -> Next (Decode.custom \bytes, fmt -> ...)
+        let branch = WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag_name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(Expr::Tag {
+                tag_union_var: next_or_unknown_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: "Next".into(),
+                arguments: vec![(decode_custom_var, Loc::at_zero(decode_custom))],
+            }),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        };
+
+        branches.push(branch);
+    }
+
+    // Example: `_ -> UnknownTag`
+    let default_branch = WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(Expr::Tag {
+            tag_union_var: next_or_unknown_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "UnknownTag".into(),
+            arguments: Vec::new(),
+        }),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    };
+
+    branches.push(default_branch);
+
+    // when tagName is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(tag_name_arg_symbol, Variable::STR))),
+        cond_var: Variable::STR,
+        expr_var: next_or_unknown_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let step_tag_closure = env.new_symbol("stepTag");
+    let function_type = env.subs.fresh_unnamed_flex_var();
+    let closure_type = {
+        let lambda_set = LambdaSet {
+            solved: UnionLambdas::tag_without_arguments(env.subs, step_tag_closure),
+            recursion_var: OptVariable::NONE,
+            unspecialized: Default::default(),
+            ambient_function: function_type,
+        };
+
+        synth_var(env.subs, Content::LambdaSet(lambda_set))
+    };
+
+    {
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [Variable::STR]);
+
+        env.subs.set_content(
+            function_type,
+            Content::Structure(FlatType::Func(
+                args_slice,
+                closure_type,
+                next_or_unknown_var,
+            )),
+        )
+    };
+
+    let expr = Expr::Closure(ClosureData {
+        function_type,
+        closure_type,
+        return_type: next_or_unknown_var,
+        name: step_tag_closure,
+        captured_symbols: Vec::new(),
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            Variable::STR,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(Pattern::Identifier(tag_name_arg_symbol)),
+        )],
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    (expr, function_type)
+}
@@ -5,7 +5,7 @@ use roc_can::num::{IntBound, IntLitWidth};
 use roc_can::pattern::Pattern;
 use roc_collections::SendMap;
 use roc_module::called_via::CalledVia;
-use roc_module::ident::Lowercase;
+use roc_module::ident::{Lowercase, TagName};
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{
@@ -59,24 +59,59 @@ use super::wrap_in_decode_custom_decode_with;
 /// ```
 pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr, Variable) {
     // The decoded type of each index in the tuple, e.g. (a, b).
-    let mut index_vars = Vec::with_capacity(arity as _);
+    let index_vars: Vec<_> = (0..arity)
+        .map(|_| env.subs.fresh_unnamed_flex_var())
+        .collect();
+
+    // Decode.tuple initialState stepElem finalizer
+    let (call_decode_tuple, tuple_decoder_var) = decode_tuple(env, &index_vars, Finalized::Tuple);
+
+    let (call_decode_custom, decode_custom_ret_var) = {
+        let bytes_sym = env.new_symbol("bytes");
+        let fmt_sym = env.new_symbol("fmt");
+        let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+        let (decode_custom, decode_custom_var) = wrap_in_decode_custom_decode_with(
+            env,
+            bytes_sym,
+            (fmt_sym, fmt_var),
+            vec![],
+            (call_decode_tuple, tuple_decoder_var),
+        );
+
+        (decode_custom, decode_custom_var)
+    };
+
+    (call_decode_custom, decode_custom_ret_var)
+}
+
+/// The value a tuple decoder's finalizer builds from the decoded elements.
+pub(super) enum Finalized {
+    /// The tuple `(e0, e1)`.
+    Tuple,
+    /// The tag `A e0 e1`, whose tag union has the given type.
+    Tag(TagName, Variable),
+}
+
+/// `Decode.tuple initialState stepElem finalizer`, decoding one element for each of `index_vars`.
+pub(super) fn decode_tuple(
+    env: &mut Env,
+    index_vars: &[Variable],
+    finalized: Finalized,
+) -> (Expr, Variable) {
     // The type of each index in the decoding state, e.g. {e0: Result a [NoElem], e1: Result b [NoElem]}
-    let mut state_fields = Vec::with_capacity(arity as _);
-    let mut state_field_vars = Vec::with_capacity(arity as _);
+    let mut state_fields = Vec::with_capacity(index_vars.len());
+    let mut state_field_vars = Vec::with_capacity(index_vars.len());
 
     // initialState = ...
-    let (state_var, initial_state) = initial_state(
-        env,
-        arity,
-        &mut index_vars,
-        &mut state_fields,
-        &mut state_field_vars,
-    );
+    let (state_var, initial_state) =
+        initial_state(env, index_vars, &mut state_fields, &mut state_field_vars);
 
     // finalizer = ...
     let (finalizer, finalizer_var, decode_err_var) = finalizer(
         env,
-        &index_vars,
+        index_vars,
+        finalized,
         state_var,
         &state_fields,
         &state_field_vars,
@@ -85,7 +120,7 @@ pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr,
     // stepElem = ...
     let (step_elem, step_var) = step_elem(
         env,
-        &index_vars,
+        index_vars,
         state_var,
         &state_fields,
         &state_field_vars,
@@ -128,23 +163,7 @@ pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr,
         CalledVia::Space,
     );
 
-    let (call_decode_custom, decode_custom_ret_var) = {
-        let bytes_sym = env.new_symbol("bytes");
-        let fmt_sym = env.new_symbol("fmt");
-        let fmt_var = env.subs.fresh_unnamed_flex_var();
-
-        let (decode_custom, decode_custom_var) = wrap_in_decode_custom_decode_with(
-            env,
-            bytes_sym,
-            (fmt_sym, fmt_var),
-            vec![],
-            (call_decode_record, tuple_decoder_var),
-        );
-
-        (decode_custom, decode_custom_var)
-    };
-
-    (call_decode_custom, decode_custom_ret_var)
+    (call_decode_record, tuple_decoder_var)
 }
 
 // Example:
@@ -743,6 +762,7 @@ fn step_elem(
 fn finalizer(
     env: &mut Env,
     index_vars: &[Variable],
+    finalized: Finalized,
     state_record_var: Variable,
     state_fields: &[Lowercase],
     state_field_vars: &[Variable],
@@ -769,36 +789,55 @@ fn finalizer(
         tuple_elems.push((index_var, Box::new(Loc::at_zero(index_expr))));
     }
 
-    // The bottom of the happy path - return the decoded tuple (a, b) wrapped with
-    // "Ok".
+    // The bottom of the happy path - return the decoded tuple (a, b), or tag A a b, wrapped
+    // with "Ok".
     let return_type_var;
     let mut body = {
-        let subs = &mut env.subs;
-        let tuple_indices_iter = index_vars.iter().copied().enumerate();
-        let flat_type = FlatType::Tuple(
-            TupleElems::insert_into_subs(subs, tuple_indices_iter),
-            Variable::EMPTY_TUPLE,
-        );
-        let done_tuple_var = synth_var(subs, Content::Structure(flat_type));
-        let done_record = Expr::Tuple {
-            tuple_var: done_tuple_var,
-            elems: tuple_elems,
+        let (done_var, done_expr) = match finalized {
+            Finalized::Tuple => {
+                let subs = &mut env.subs;
+                let tuple_indices_iter = index_vars.iter().copied().enumerate();
+                let flat_type = FlatType::Tuple(
+                    TupleElems::insert_into_subs(subs, tuple_indices_iter),
+                    Variable::EMPTY_TUPLE,
+                );
+                let done_tuple_var = synth_var(subs, Content::Structure(flat_type));
+                let done_tuple = Expr::Tuple {
+                    tuple_var: done_tuple_var,
+                    elems: tuple_elems,
+                };
+
+                (done_tuple_var, done_tuple)
+            }
+            Finalized::Tag(tag_name, tag_union_var) => {
+                let done_tag = Expr::Tag {
+                    tag_union_var,
+                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                    name: tag_name,
+                    arguments: tuple_elems
+                        .into_iter()
+                        .map(|(var, elem)| (var, *elem))
+                        .collect(),
+                };
+
+                (tag_union_var, done_tag)
+            }
         };
 
         return_type_var = {
             let flat_type = FlatType::TagUnion(
-                UnionTags::for_result(subs, done_tuple_var, decode_err_var),
+                UnionTags::for_result(env.subs, done_var, decode_err_var),
                 TagExt::Any(Variable::EMPTY_TAG_UNION),
             );
 
-            synth_var(subs, Content::Structure(flat_type))
+            synth_var(env.subs, Content::Structure(flat_type))
         };
 
         Expr::Tag {
             tag_union_var: return_type_var,
             ext_var: env.new_ext_var(ExtensionKind::TagUnion),
             name: "Ok".into(),
-            arguments: vec![(done_tuple_var, Loc::at_zero(done_record))],
+            arguments: vec![(done_var, Loc::at_zero(done_expr))],
         }
     };
 
@@ -916,18 +955,14 @@ fn finalizer(
 // initialState = {e0: Err NoElem, e1: Err NoElem}
 fn initial_state(
     env: &mut Env<'_>,
-    arity: u32,
-    index_vars: &mut Vec<Variable>,
+    index_vars: &[Variable],
     state_fields: &mut Vec<Lowercase>,
     state_field_vars: &mut Vec<Variable>,
 ) -> (Variable, Expr) {
     let mut initial_state_fields = SendMap::default();
 
-    for i in 0..arity {
+    for (i, &index_var) in index_vars.iter().enumerate() {
         let subs = &mut env.subs;
-        let index_var = subs.fresh_unnamed_flex_var();
-
-        index_vars.push(index_var);
 
         let state_field = Lowercase::from(format!("e{i}"));
         state_fields.push(state_field.clone());
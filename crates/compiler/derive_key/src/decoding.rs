@@ -1,8 +1,11 @@
-use roc_module::{ident::Lowercase, symbol::Symbol};
-use roc_types::subs::{Content, FlatType, Subs, Variable};
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
+use roc_types::subs::{Content, FlatType, GetSubsSlice, Subs, Variable};
 
 use crate::{
-    util::{check_derivable_ext_var, debug_name_record, debug_name_tuple},
+    util::{check_derivable_ext_var, debug_name_record, debug_name_tag, debug_name_tuple},
     DeriveError,
 };
 
@@ -19,6 +22,7 @@ pub enum FlatDecodableKey {
     // Unfortunate that we must allocate here, c'est la vie
     Record(Vec<Lowercase>),
     Tuple(u32),
+    TagUnion(Vec<(TagName, u16)>),
 }
 
 impl FlatDecodableKey {
@@ -27,6 +31,7 @@ impl FlatDecodableKey {
             FlatDecodableKey::List() => "list".to_string(),
             FlatDecodableKey::Record(fields) => debug_name_record(fields),
             FlatDecodableKey::Tuple(arity) => debug_name_tuple(*arity),
+            FlatDecodableKey::TagUnion(tags) => debug_name_tag(tags),
         }
     }
 }
@@ -72,17 +77,39 @@ impl FlatDecodable {
 
                     Ok(Key(FlatDecodableKey::Tuple(elems_iter.count() as _)))
                 }
-                FlatType::TagUnion(_tags, _ext) | FlatType::RecursiveTagUnion(_, _tags, _ext) => {
-                    Err(Underivable) // yet
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext) => {
+                    // As with encoding, the recursion var doesn't matter: the derived decoder only
+                    // looks at the surface of the tag union, and leaves the payload types generic.
+                    let (tags_iter, ext) = tags.unsorted_tags_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext.var(), |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTagUnion))
+                    })?;
+
+                    let mut tag_names_and_payload_sizes: Vec<_> = tags_iter
+                        .tags
+                        .into_iter()
+                        .map(|(name, payload_slice)| (name.clone(), payload_slice.len() as _))
+                        .collect();
+
+                    tag_names_and_payload_sizes.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+
+                    Ok(Key(FlatDecodableKey::TagUnion(tag_names_and_payload_sizes)))
                 }
-                FlatType::FunctionOrTagUnion(_name_index, _, _) => {
-                    Err(Underivable) // yet
+                FlatType::FunctionOrTagUnion(names_index, _, _) => {
+                    let mut tag_names: Vec<_> = subs
+                        .get_subs_slice(names_index)
+                        .iter()
+                        .map(|t| (t.clone(), 0))
+                        .collect();
+
+                    tag_names.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+
+                    Ok(Key(FlatDecodableKey::TagUnion(tag_names)))
                 }
                 FlatType::EmptyRecord => Ok(Key(FlatDecodableKey::Record(vec![]))),
                 FlatType::EmptyTuple => todo!(),
-                FlatType::EmptyTagUnion => {
-                    Err(Underivable) // yet
-                }
+                FlatType::EmptyTagUnion => Ok(Key(FlatDecodableKey::TagUnion(vec![]))),
                 //
                 FlatType::Func(..) => Err(Underivable),
             },
@@ -32,6 +32,7 @@ impl<'a> Formattable for Expr<'a> {
             }
 
             MalformedSuffixed(loc_expr) => loc_expr.is_multiline(),
+            Malformed(text) => text.contains('\n'),
 
             // These expressions never have newlines
             Float(..)
@@ -525,6 +526,17 @@ impl<'a> Formattable for Expr<'a> {
                 buf.indent(indent);
                 loc_expr.format_with_options(buf, parens, newlines, indent);
             }
+            Malformed(text) => {
+                // This could not be parsed, so it stays exactly as it was written
+                for (index, line) in text.lines().enumerate() {
+                    if index > 0 {
+                        buf.newline();
+                    }
+
+                    buf.indent(if index == 0 { indent } else { 0 });
+                    buf.push_str_allow_spaces(line.trim_end());
+                }
+            }
             MalformedClosure => {}
             PrecedenceConflict { .. } => {}
             MultipleRecordBuilders { .. } => {}
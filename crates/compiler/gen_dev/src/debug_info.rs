@@ -0,0 +1,496 @@
+//! DWARF debug info for the object files produced by the dev backend.
+//!
+//! Every object gets a single compile unit. Each procedure becomes a `DW_TAG_subprogram`, and
+//! the line program maps the start of each procedure back to the line of its body in the Roc
+//! source, so that debuggers and profilers can name and locate code in `--dev` builds.
+//! We don't track source locations per instruction (yet), so all the code of a procedure is
+//! attributed to the line where its body starts.
+
+use gimli::write::{
+    Address, AttributeValue, DwarfUnit, EndianVec, FileId, LineProgram, LineString, Range,
+    RangeList, Sections, Writer,
+};
+use gimli::{Encoding, Format, LineEncoding, LittleEndian};
+use object::write::{self, Object, SectionId, StandardSegment, SymbolId};
+use object::{BinaryFormat, RelocationEncoding, RelocationKind, SectionKind};
+use roc_collections::all::MutMap;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_region::all::{LineInfo, Region};
+use std::path::PathBuf;
+
+/// What we need to remember about a procedure after its machine code has been generated.
+pub(crate) struct DebugProc {
+    /// The human-readable name, e.g. `List.map`.
+    pub name: String,
+    /// The name of the symbol in the object file.
+    pub linkage_name: String,
+    pub symbol: SymbolId,
+    pub size: u64,
+    pub module_id: ModuleId,
+    pub region: Region,
+}
+
+impl DebugProc {
+    pub fn new(
+        interns: &Interns,
+        sym: Symbol,
+        linkage_name: String,
+        symbol: SymbolId,
+        region: Region,
+    ) -> Self {
+        let module_id = sym.module_id();
+        let module_name = interns.module_ids.get_name(module_id);
+        let ident = interns
+            .all_ident_ids
+            .get(&module_id)
+            .and_then(|ident_ids| ident_ids.get_name(sym.ident_id()));
+
+        // Compiler-generated procs may not have a readable name; fall back to the linkage name
+        let name = match (module_name, ident) {
+            (Some(module_name), Some(ident)) => format!("{}.{}", module_name.as_str(), ident),
+            _ => linkage_name.clone(),
+        };
+
+        Self {
+            name,
+            linkage_name,
+            symbol,
+            // filled in once the machine code has been generated
+            size: 0,
+            module_id,
+            region,
+        }
+    }
+}
+
+/// Add `.debug_info`, `.debug_line` and friends describing `procs` to `output`.
+///
+/// Only ELF and Mach-O objects are supported; other formats are left untouched.
+pub(crate) fn add_debug_info(
+    output: &mut Object,
+    interns: &Interns,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    main_module: ModuleId,
+    procs: &[DebugProc],
+) -> gimli::write::Result<()> {
+    // Mach-O relies on each object having one section of each kind, and does not
+    // relocate offsets between debug sections. ELF needs relocations for both.
+    let relocate_offsets = match output.format() {
+        BinaryFormat::Elf => true,
+        BinaryFormat::MachO => false,
+        _ => return Ok(()),
+    };
+
+    if procs.is_empty() {
+        return Ok(());
+    }
+
+    let encoding = Encoding {
+        format: Format::Dwarf32,
+        version: 4,
+        address_size: 8,
+    };
+
+    let comp_dir = std::env::current_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| String::from("."));
+    let comp_file = module_file_name(interns, sources, main_module);
+
+    let mut dwarf = DwarfUnit::new(encoding);
+    dwarf.unit.line_program = LineProgram::new(
+        encoding,
+        LineEncoding::default(),
+        LineString::String(comp_dir.clone().into_bytes()),
+        LineString::String(comp_file.clone().into_bytes()),
+        None,
+    );
+
+    // The line info and file entry of every module we have seen so far
+    let mut files: MutMap<ModuleId, Option<(FileId, LineInfo)>> = MutMap::default();
+    let mut ranges = Vec::with_capacity(procs.len());
+
+    let root = dwarf.unit.root();
+
+    for (index, proc) in procs.iter().enumerate() {
+        let address = Address::Symbol {
+            symbol: index,
+            addend: 0,
+        };
+
+        ranges.push(Range::StartLength {
+            begin: address,
+            length: proc.size,
+        });
+
+        let location = if proc.region == Region::zero() {
+            None
+        } else {
+            files
+                .entry(proc.module_id)
+                .or_insert_with(|| {
+                    let (path, src) = sources.get(&proc.module_id)?;
+                    let file_name = path.to_string_lossy().into_owned();
+
+                    if file_name.is_empty() || file_name.contains('\0') {
+                        return None;
+                    }
+
+                    let line_program = &mut dwarf.unit.line_program;
+                    let directory = line_program.default_directory();
+                    let file_id = line_program.add_file(
+                        LineString::String(file_name.into_bytes()),
+                        directory,
+                        None,
+                    );
+
+                    Some((file_id, LineInfo::new(src)))
+                })
+                .as_ref()
+                .map(|(file_id, line_info)| {
+                    // DWARF lines are 1-based
+                    let line = line_info.convert_pos(proc.region.start()).line as u64 + 1;
+
+                    (*file_id, line)
+                })
+        };
+
+        let name = dwarf.strings.add(proc.name.as_bytes());
+        let linkage_name = dwarf.strings.add(proc.linkage_name.as_bytes());
+
+        let subprogram = dwarf.unit.add(root, gimli::DW_TAG_subprogram);
+        let entry = dwarf.unit.get_mut(subprogram);
+        entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+        entry.set(
+            gimli::DW_AT_linkage_name,
+            AttributeValue::StringRef(linkage_name),
+        );
+        entry.set(gimli::DW_AT_external, AttributeValue::Flag(true));
+        entry.set(gimli::DW_AT_low_pc, AttributeValue::Address(address));
+        entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(proc.size));
+
+        if let Some((file_id, line)) = location {
+            entry.set(
+                gimli::DW_AT_decl_file,
+                AttributeValue::FileIndex(Some(file_id)),
+            );
+            entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+
+            let line_program = &mut dwarf.unit.line_program;
+            line_program.begin_sequence(Some(address));
+
+            let row = line_program.row();
+            row.file = file_id;
+            row.line = line;
+            row.address_offset = 0;
+            line_program.generate_row();
+
+            line_program.end_sequence(proc.size);
+        }
+    }
+
+    let range_list = dwarf.unit.ranges.add(RangeList(ranges));
+
+    let producer = dwarf.strings.add("roc dev backend");
+    let name = dwarf.strings.add(comp_file);
+    let comp_dir = dwarf.strings.add(comp_dir);
+
+    let entry = dwarf.unit.get_mut(root);
+    entry.set(gimli::DW_AT_producer, AttributeValue::StringRef(producer));
+    entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+    entry.set(gimli::DW_AT_comp_dir, AttributeValue::StringRef(comp_dir));
+    entry.set(
+        gimli::DW_AT_low_pc,
+        AttributeValue::Address(Address::Constant(0)),
+    );
+    entry.set(
+        gimli::DW_AT_ranges,
+        AttributeValue::RangeListRef(range_list),
+    );
+
+    let mut sections = Sections::new(DebugSection::new(relocate_offsets));
+    dwarf.write(&mut sections)?;
+
+    // Create all the sections up front, so that relocations can refer to any of them
+    let mut section_ids: MutMap<gimli::SectionId, SectionId> = MutMap::default();
+
+    sections.for_each(|id, section| -> gimli::write::Result<()> {
+        if !section.data.slice().is_empty() {
+            let name = match output.format() {
+                BinaryFormat::MachO => format!("__{}", &id.name()[1..]),
+                _ => id.name().to_string(),
+            };
+
+            let section_id = output.add_section(
+                output.segment_name(StandardSegment::Debug).to_vec(),
+                name.into_bytes(),
+                SectionKind::Debug,
+            );
+
+            section_ids.insert(id, section_id);
+        }
+
+        Ok(())
+    })?;
+
+    sections.for_each(|id, section| -> gimli::write::Result<()> {
+        let Some(&section_id) = section_ids.get(&id) else {
+            return Ok(());
+        };
+
+        output.append_section_data(section_id, section.data.slice(), 1);
+
+        for reloc in section.relocs.iter() {
+            let symbol = match reloc.target {
+                RelocTarget::Proc(index) => procs[index].symbol,
+                RelocTarget::Section(target) => match section_ids.get(&target) {
+                    Some(&target_id) => output.section_symbol(target_id),
+                    None => return Err(gimli::write::Error::InvalidReference),
+                },
+            };
+
+            let relocation = write::Relocation {
+                offset: reloc.offset,
+                size: reloc.size * 8,
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend: reloc.addend,
+            };
+
+            if output.add_relocation(section_id, relocation).is_err() {
+                return Err(gimli::write::Error::InvalidReference);
+            }
+        }
+
+        Ok(())
+    })
+}
+
+fn module_file_name(
+    interns: &Interns,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    module_id: ModuleId,
+) -> String {
+    let from_path = sources
+        .get(&module_id)
+        .map(|(path, _)| path.to_string_lossy().into_owned());
+
+    let from_module_name = || {
+        interns
+            .module_ids
+            .get_name(module_id)
+            .map(|name| name.as_str().to_string())
+    };
+
+    from_path
+        .or_else(from_module_name)
+        .filter(|name| !name.is_empty() && !name.contains('\0'))
+        .unwrap_or_else(|| String::from("app.roc"))
+}
+
+#[derive(Clone, Copy)]
+enum RelocTarget {
+    /// The address of the procedure at this index
+    Proc(usize),
+    /// An offset into another debug section
+    Section(gimli::SectionId),
+}
+
+#[derive(Clone)]
+struct DebugReloc {
+    offset: u64,
+    /// in bytes
+    size: u8,
+    target: RelocTarget,
+    addend: i64,
+}
+
+/// A debug section that records its relocations, rather than resolving them.
+#[derive(Clone)]
+struct DebugSection {
+    data: EndianVec<LittleEndian>,
+    relocs: Vec<DebugReloc>,
+    relocate_offsets: bool,
+}
+
+impl DebugSection {
+    fn new(relocate_offsets: bool) -> Self {
+        Self {
+            data: EndianVec::new(LittleEndian),
+            relocs: Vec::new(),
+            relocate_offsets,
+        }
+    }
+}
+
+impl Writer for DebugSection {
+    type Endian = LittleEndian;
+
+    fn endian(&self) -> Self::Endian {
+        LittleEndian
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.data.write(bytes)
+    }
+
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.data.write_at(offset, bytes)
+    }
+
+    fn write_address(&mut self, address: Address, size: u8) -> gimli::write::Result<()> {
+        match address {
+            Address::Constant(value) => self.write_udata(value, size),
+            Address::Symbol { symbol, addend } => {
+                self.relocs.push(DebugReloc {
+                    offset: self.len() as u64,
+                    size,
+                    target: RelocTarget::Proc(symbol),
+                    addend,
+                });
+
+                // the addend lives in the relocation; object writes it into the data if needed
+                self.write_udata(0, size)
+            }
+        }
+    }
+
+    fn write_offset(
+        &mut self,
+        val: usize,
+        section: gimli::SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        if !self.relocate_offsets {
+            return self.write_udata(val as u64, size);
+        }
+
+        self.relocs.push(DebugReloc {
+            offset: self.len() as u64,
+            size,
+            target: RelocTarget::Section(section),
+            addend: val as i64,
+        });
+
+        self.write_udata(0, size)
+    }
+
+    fn write_offset_at(
+        &mut self,
+        offset: usize,
+        val: usize,
+        section: gimli::SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        if !self.relocate_offsets {
+            return self.write_udata_at(offset, val as u64, size);
+        }
+
+        self.relocs.push(DebugReloc {
+            offset: offset as u64,
+            size,
+            target: RelocTarget::Section(section),
+            addend: val as i64,
+        });
+
+        self.write_udata_at(offset, 0, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object::read::{Object as _, ObjectSection as _};
+    use object::write::{StandardSection, Symbol as ObjectSymbol, SymbolSection};
+    use object::{Architecture, Endianness, SymbolFlags, SymbolKind, SymbolScope};
+    use roc_region::all::Position;
+
+    fn object_with_debug_info(format: BinaryFormat) -> std::vec::Vec<u8> {
+        let mut output = Object::new(format, Architecture::X86_64, Endianness::Little);
+        let text = output.section_id(StandardSection::Text);
+
+        let symbol = output.add_symbol(ObjectSymbol {
+            name: b"roc_foo".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        output.add_symbol_data(symbol, text, &[0xC3; 4], 16);
+
+        let src = "x = 1\n\nfoo = \\y ->\n    y\n";
+        let body_start = src.find("    y").unwrap() as u32;
+
+        let mut sources = MutMap::default();
+        sources.insert(ModuleId::NUM, (PathBuf::from("Num.roc"), src.into()));
+
+        let mut proc = DebugProc::new(
+            &Interns::default(),
+            Symbol::NUM_ADD,
+            String::from("roc_foo"),
+            symbol,
+            Region::new(Position::new(body_start), Position::new(body_start + 5)),
+        );
+        proc.size = 4;
+
+        add_debug_info(
+            &mut output,
+            &Interns::default(),
+            &sources,
+            ModuleId::NUM,
+            &[proc],
+        )
+        .unwrap();
+
+        output.write().unwrap()
+    }
+
+    #[test]
+    fn elf_debug_sections() {
+        let bytes = object_with_debug_info(BinaryFormat::Elf);
+        let file = object::File::parse(bytes.as_slice()).unwrap();
+
+        for name in [".debug_info", ".debug_abbrev", ".debug_line", ".debug_str"] {
+            let section = file.section_by_name(name).unwrap();
+            assert!(section.size() > 0);
+        }
+
+        // the subprogram and compile unit addresses must point at the proc
+        let debug_info = file.section_by_name(".debug_info").unwrap();
+        assert!(debug_info.relocations().count() > 0);
+
+        let debug_line = file.section_by_name(".debug_line").unwrap();
+        assert!(debug_line.relocations().count() > 0);
+    }
+
+    #[test]
+    fn macho_debug_sections() {
+        let bytes = object_with_debug_info(BinaryFormat::MachO);
+        let file = object::File::parse(bytes.as_slice()).unwrap();
+
+        for name in [
+            "__debug_info",
+            "__debug_abbrev",
+            "__debug_line",
+            "__debug_str",
+        ] {
+            let section = file.section_by_name(name).unwrap();
+            assert_eq!(section.segment_name().unwrap(), Some("__DWARF"));
+        }
+    }
+
+    #[test]
+    fn coff_has_no_debug_sections() {
+        let bytes = object_with_debug_info(BinaryFormat::Coff);
+        let file = object::File::parse(bytes.as_slice()).unwrap();
+
+        assert!(file.section_by_name(".debug_info").is_none());
+    }
+}
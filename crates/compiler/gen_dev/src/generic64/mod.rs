@@ -1313,11 +1313,6 @@ impl<
 
     fn build_num_abs(&mut self, dst: &Symbol, src: &Symbol, layout: &InLayout<'a>) {
         match self.interner().get_repr(*layout) {
-            LayoutRepr::Builtin(Builtin::Int(IntWidth::I64 | IntWidth::U64)) => {
-                let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
-                let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
-                ASM::abs_reg64_reg64(&mut self.buf, dst_reg, src_reg);
-            }
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
                 let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
@@ -1328,7 +1323,21 @@ impl<
                 let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
                 ASM::abs_freg32_freg32(&mut self.buf, &mut self.relocs, dst_reg, src_reg);
             }
-            x => todo!("NumAbs: layout, {:?}", x),
+            LayoutRepr::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                dst,
+                bitcode::NUM_ABS_OR_PANIC_INT[int_width].to_string(),
+                &[*src],
+                &[*layout],
+                layout,
+            ),
+            LayoutRepr::DEC => self.build_fn_call(
+                dst,
+                bitcode::DEC_ABS.to_string(),
+                &[*src],
+                &[Layout::DEC],
+                &Layout::DEC,
+            ),
+            x => unreachable!("NumAbs for layout {x:?}"),
         }
     }
 
@@ -1385,6 +1394,15 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::add_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            LayoutRepr::Builtin(Builtin::Int(int_width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_ADD_WRAP_INT[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                )
+            }
 
             LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
                 let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
@@ -1419,7 +1437,7 @@ impl<
         layout: InLayout<'a>,
     ) {
         match self.layout_interner.get_repr(layout) {
-            LayoutRepr::Builtin(Builtin::Int(width @ quadword_and_smaller!())) => {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
                 let intrinsic = bitcode::NUM_ADD_SATURATED_INT[width].to_string();
                 self.build_fn_call(&dst, intrinsic, &[src1, src2], &[layout, layout], &layout);
             }
@@ -1439,7 +1457,7 @@ impl<
                 let intrinsic = bitcode::DEC_ADD_SATURATED.to_string();
                 self.build_fn_call(&dst, intrinsic, &[src1, src2], &[layout, layout], &layout);
             }
-            x => todo!("NumAddSaturated: layout, {:?}", x),
+            x => unreachable!("NumAddSaturated for layout {x:?}"),
         }
     }
 
@@ -1453,7 +1471,16 @@ impl<
     ) {
         let function_name = match self.interner().get_repr(*num_layout) {
             LayoutRepr::Builtin(Builtin::Int(width)) => &bitcode::NUM_ADD_CHECKED_INT[width],
-            LayoutRepr::Builtin(Builtin::Float(width)) => &bitcode::NUM_ADD_CHECKED_FLOAT[width],
+            LayoutRepr::Builtin(Builtin::Float(_)) => {
+                return self.build_float_checked(
+                    dst,
+                    src1,
+                    src2,
+                    num_layout,
+                    return_layout,
+                    Self::build_num_add,
+                );
+            }
             LayoutRepr::Builtin(Builtin::Decimal) => bitcode::DEC_ADD_WITH_OVERFLOW,
             x => internal_error!("NumAddChecked is not defined for {:?}", x),
         };
@@ -1477,7 +1504,16 @@ impl<
     ) {
         let function_name = match self.interner().get_repr(*num_layout) {
             LayoutRepr::Builtin(Builtin::Int(width)) => &bitcode::NUM_SUB_CHECKED_INT[width],
-            LayoutRepr::Builtin(Builtin::Float(width)) => &bitcode::NUM_SUB_CHECKED_FLOAT[width],
+            LayoutRepr::Builtin(Builtin::Float(_)) => {
+                return self.build_float_checked(
+                    dst,
+                    src1,
+                    src2,
+                    num_layout,
+                    return_layout,
+                    Self::build_num_sub,
+                );
+            }
             LayoutRepr::Builtin(Builtin::Decimal) => bitcode::DEC_SUB_WITH_OVERFLOW,
             x => internal_error!("NumSubChecked is not defined for {:?}", x),
         };
@@ -1594,7 +1630,7 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::mul_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
-            x => todo!("NumMulWrap: layout, {:?}", x),
+            x => unreachable!("NumMulWrap for layout {x:?}"),
         }
     }
 
@@ -1606,7 +1642,7 @@ impl<
         layout: InLayout<'a>,
     ) {
         match self.layout_interner.get_repr(layout) {
-            LayoutRepr::Builtin(Builtin::Int(width @ quadword_and_smaller!())) => {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
                 let intrinsic = bitcode::NUM_MUL_SATURATED_INT[width].to_string();
                 self.build_fn_call(&dst, intrinsic, &[src1, src2], &[layout, layout], &layout);
             }
@@ -1626,7 +1662,7 @@ impl<
                 let intrinsic = bitcode::DEC_MUL_SATURATED.to_string();
                 self.build_fn_call(&dst, intrinsic, &[src1, src2], &[layout, layout], &layout);
             }
-            x => todo!("NumMulSaturated: layout, {:?}", x),
+            x => unreachable!("NumMulSaturated for layout {x:?}"),
         }
     }
 
@@ -1640,7 +1676,16 @@ impl<
     ) {
         let function_name = match self.interner().get_repr(*num_layout) {
             LayoutRepr::Builtin(Builtin::Int(width)) => &bitcode::NUM_MUL_CHECKED_INT[width],
-            LayoutRepr::Builtin(Builtin::Float(width)) => &bitcode::NUM_MUL_CHECKED_FLOAT[width],
+            LayoutRepr::Builtin(Builtin::Float(_)) => {
+                return self.build_float_checked(
+                    dst,
+                    src1,
+                    src2,
+                    num_layout,
+                    return_layout,
+                    Self::build_num_mul,
+                );
+            }
             LayoutRepr::Builtin(Builtin::Decimal) => bitcode::DEC_MUL_WITH_OVERFLOW,
             x => internal_error!("NumMulChecked is not defined for {:?}", x),
         };
@@ -1715,7 +1760,16 @@ impl<
                     layout,
                 );
             }
-            x => todo!("NumDiv: layout, {:?}", x),
+            LayoutRepr::Builtin(Builtin::Int(int_width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_DIV_TRUNC[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
+            x => unreachable!("NumDiv for layout {x:?}"),
         }
     }
 
@@ -1734,7 +1788,7 @@ impl<
                 &[*layout, *layout],
                 layout,
             ),
-            x => todo!("NumDivCeilUnchecked: layout, {:?}", x),
+            x => unreachable!("NumDivCeilUnchecked for layout {x:?}"),
         }
     }
 
@@ -1778,18 +1832,54 @@ impl<
                     src2_reg,
                 );
             }
-            x => todo!("NumDiv: layout, {:?}", x),
+            LayoutRepr::Builtin(Builtin::Int(int_width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_REM[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
+            x => unreachable!("NumRem for layout {x:?}"),
         }
     }
 
     fn build_num_neg(&mut self, dst: &Symbol, src: &Symbol, layout: &InLayout<'a>) {
         match self.layout_interner.get_repr(*layout) {
-            LayoutRepr::Builtin(Builtin::Int(IntWidth::I64 | IntWidth::U64)) => {
-                let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
-                let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
-                ASM::neg_reg64_reg64(&mut self.buf, dst_reg, src_reg);
+            LayoutRepr::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                dst,
+                bitcode::NUM_NEG_OR_PANIC_INT[int_width].to_string(),
+                &[*src],
+                &[*layout],
+                layout,
+            ),
+            LayoutRepr::Builtin(Builtin::Float(float_width)) => {
+                // multiplying by -1 flips the sign bit, also for zero and NaN
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
+
+                let relocs = &mut self.relocs;
+                self.storage_manager
+                    .with_tmp_float_reg(&mut self.buf, |_, buf, tmp| match float_width {
+                        FloatWidth::F64 => {
+                            ASM::mov_freg64_imm64(buf, relocs, tmp, -1.0);
+                            ASM::mul_freg64_freg64_freg64(buf, dst_reg, src_reg, tmp);
+                        }
+                        FloatWidth::F32 => {
+                            ASM::mov_freg32_imm32(buf, relocs, tmp, -1.0);
+                            ASM::mul_freg32_freg32_freg32(buf, dst_reg, src_reg, tmp);
+                        }
+                    });
             }
-            x => todo!("NumNeg: layout, {:?}", x),
+            LayoutRepr::DEC => self.build_fn_call(
+                dst,
+                bitcode::DEC_NEGATE.to_string(),
+                &[*src],
+                &[Layout::DEC],
+                &Layout::DEC,
+            ),
+            x => unreachable!("NumNeg for layout {x:?}"),
         }
     }
 
@@ -1846,7 +1936,16 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::sub_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
-            x => todo!("NumSubWrap: layout, {:?}", x),
+            LayoutRepr::Builtin(Builtin::Int(int_width @ (IntWidth::I128 | IntWidth::U128))) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SUB_WRAP_INT[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                )
+            }
+            x => unreachable!("NumSubWrap for layout {x:?}"),
         }
     }
 
@@ -2080,7 +2179,7 @@ impl<
             Layout::F64 => self.num_to_f64(dst, src, arg_layout),
             Layout::DEC => self.num_to_dec(dst, src, arg_layout),
 
-            other => todo!("NumToFrac: layout {other:?} is not Frac"),
+            other => unreachable!("NumToFrac: layout {other:?} is not Frac"),
         }
     }
 
@@ -4645,11 +4744,13 @@ impl<
         src2: &Symbol,
         int_width: IntWidth,
     ) {
-        let buf = &mut self.buf;
-
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int_bitwise_128(dst, src1, src2, int_width, ASM::and_reg64_reg64_reg64)
+            }
             _ => {
+                let buf = &mut self.buf;
+
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
                 let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
@@ -4665,11 +4766,13 @@ impl<
         src2: &Symbol,
         int_width: IntWidth,
     ) {
-        let buf = &mut self.buf;
-
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int_bitwise_128(dst, src1, src2, int_width, ASM::or_reg64_reg64_reg64)
+            }
             _ => {
+                let buf = &mut self.buf;
+
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
                 let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
@@ -4685,11 +4788,13 @@ impl<
         src2: &Symbol,
         int_width: IntWidth,
     ) {
-        let buf = &mut self.buf;
-
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                self.build_int_bitwise_128(dst, src1, src2, int_width, ASM::xor_reg64_reg64_reg64)
+            }
             _ => {
+                let buf = &mut self.buf;
+
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
                 let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
@@ -4705,11 +4810,21 @@ impl<
         src2: &Symbol,
         int_width: IntWidth,
     ) {
-        let buf = &mut self.buf;
-
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                let layout = Layout::from_int_width(int_width);
+
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SHIFT_LEFT_BY[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[layout, Layout::U8],
+                    &layout,
+                );
+            }
             _ => {
+                let buf = &mut self.buf;
+
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
                 let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
@@ -4732,11 +4847,21 @@ impl<
         src2: &Symbol,
         int_width: IntWidth,
     ) {
-        let buf = &mut self.buf;
-
         match int_width {
-            IntWidth::U128 | IntWidth::I128 => todo!(),
+            IntWidth::U128 | IntWidth::I128 => {
+                let layout = Layout::from_int_width(int_width);
+
+                self.build_fn_call(
+                    dst,
+                    bitcode::NUM_SHIFT_RIGHT_BY[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[layout, Layout::U8],
+                    &layout,
+                );
+            }
             _ => {
+                let buf = &mut self.buf;
+
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
                 let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
@@ -4799,25 +4924,21 @@ impl<
         src2: &Symbol,
         int_width: IntWidth,
     ) {
-        let buf = &mut self.buf;
-
         match int_width {
             IntWidth::U128 | IntWidth::I128 => {
-                let layout = match int_width {
-                    IntWidth::I128 => Layout::I128,
-                    IntWidth::U128 => Layout::U128,
-                    _ => unreachable!(),
-                };
+                let layout = Layout::from_int_width(int_width);
 
                 self.build_fn_call(
                     dst,
                     bitcode::NUM_SHIFT_RIGHT_ZERO_FILL[int_width].to_string(),
                     &[*src1, *src2],
-                    &[layout, layout],
+                    &[layout, Layout::U8],
                     &layout,
                 );
             }
             _ => {
+                let buf = &mut self.buf;
+
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
                 let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
                 let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
@@ -4854,51 +4975,72 @@ impl<
     ) {
         use IntWidth::*;
 
-        let buf = &mut self.buf;
-
         match (source, target) {
-            (U128, U64) => {
+            (U128 | I128, U128 | I128) => {
+                let buf = &mut self.buf;
+
+                let to_offset = self.storage_manager.claim_stack_area_layout(
+                    self.layout_interner,
+                    *dst,
+                    Layout::from_int_width(target),
+                );
+
+                let (from_offset, size) = self.storage_manager.stack_offset_and_size(src);
+
+                self.storage_manager
+                    .copy_to_stack_offset(buf, size, from_offset, to_offset);
+
+                return;
+            }
+            (U128 | I128, _) => {
+                let buf = &mut self.buf;
+
                 let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
 
                 let (offset, _size) = self.storage_manager.stack_offset_and_size(src);
 
-                ASM::mov_reg64_base32(buf, dst_reg, offset + 8);
+                // the lower 8 bytes hold the truncated value
+                ASM::mov_reg64_base32(buf, dst_reg, offset);
 
                 return;
             }
-            (U64, U128) => {
-                let src_reg = self.storage_manager.load_to_general_reg(buf, src);
-
+            (_, U128 | I128) => {
                 let base_offset = self.storage_manager.claim_stack_area_layout(
                     self.layout_interner,
                     *dst,
-                    Layout::U128,
+                    Layout::from_int_width(target),
                 );
 
+                // first extend to the 64-bit integer with the signedness of the target
                 let tmp = Symbol::DEV_TMP;
-                let tmp_reg = self.storage_manager.claim_general_reg(buf, &tmp);
-
-                // move a zero into the lower 8 bytes
-                ASM::mov_reg64_imm64(buf, tmp_reg, 0x0);
-                ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
+                let wide = if target.is_signed() { I64 } else { U64 };
+                self.build_num_int_cast(&tmp, src, source, wide);
 
-                ASM::mov_base32_reg64(buf, base_offset, src_reg);
+                let buf = &mut self.buf;
 
-                self.free_symbol(&tmp);
+                let tmp_reg = self.storage_manager.load_to_general_reg(buf, &tmp);
+                ASM::mov_base32_reg64(buf, base_offset, tmp_reg);
 
-                return;
-            }
-            (U128, I128) | (I128, U128) => {
-                let to_offset = self.storage_manager.claim_stack_area_layout(
-                    self.layout_interner,
-                    *dst,
-                    Layout::from_int_width(target),
-                );
+                // the upper 8 bytes are copies of the sign bit, or zero for unsigned targets
+                if target.is_signed() {
+                    let shift = Symbol::DEV_TMP2;
+                    let shift_reg = self.storage_manager.claim_general_reg(buf, &shift);
+                    ASM::mov_reg64_imm64(buf, shift_reg, 63);
+                    ASM::sar_reg64_reg64_reg64(
+                        buf,
+                        &mut self.storage_manager,
+                        tmp_reg,
+                        tmp_reg,
+                        shift_reg,
+                    );
+                    self.free_symbol(&shift);
+                } else {
+                    ASM::mov_reg64_imm64(buf, tmp_reg, 0);
+                }
 
-                let (from_offset, size) = self.storage_manager.stack_offset_and_size(src);
+                ASM::mov_base32_reg64(&mut self.buf, base_offset + 8, tmp_reg);
 
-                self.storage_manager
-                    .copy_to_stack_offset(buf, size, from_offset, to_offset);
+                self.free_symbol(&tmp);
 
                 return;
             }
@@ -4906,6 +5048,8 @@ impl<
             _ => {}
         }
 
+        let buf = &mut self.buf;
+
         let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
         let src_reg = self.storage_manager.load_to_general_reg(buf, src);
 
@@ -4915,37 +5059,33 @@ impl<
                 4 => ASM::mov_reg32_reg32(buf, dst_reg, src_reg),
                 2 => ASM::mov_reg16_reg16(buf, dst_reg, src_reg),
                 1 => ASM::mov_reg8_reg8(buf, dst_reg, src_reg),
-                _ => todo!("int cast from {source:?} to {target:?}"),
+                _ => unreachable!("int cast from {source:?} to {target:?}"),
             }
         } else {
             match (source, target) {
                 // -- CASTING UP --
-                (I8 | U8, U16 | U32 | U64) => {
-                    // zero  out the register
-                    ASM::xor_reg64_reg64_reg64(buf, dst_reg, dst_reg, dst_reg);
-
-                    // move the 8-bit integer
-                    ASM::mov_reg_reg(buf, RegisterWidth::W8, dst_reg, src_reg);
-                }
-                (U16, U32 | U64) => {
-                    // zero  out the register
-                    ASM::xor_reg64_reg64_reg64(buf, dst_reg, dst_reg, dst_reg);
+                (I8 | U8 | I16 | U16 | I32 | U32, U16 | U32 | U64)
+                    if source.stack_size() < target.stack_size() =>
+                {
+                    let width = match source.stack_size() {
+                        4 => RegisterWidth::W32,
+                        2 => RegisterWidth::W16,
+                        _ => RegisterWidth::W8,
+                    };
 
-                    // move the 16-bit integer
-                    ASM::mov_reg_reg(buf, RegisterWidth::W16, dst_reg, src_reg);
-                }
-                (U32, U64) => {
                     // zero  out the register
                     ASM::xor_reg64_reg64_reg64(buf, dst_reg, dst_reg, dst_reg);
 
-                    // move the 32-bit integer
-                    ASM::mov_reg_reg(buf, RegisterWidth::W32, dst_reg, src_reg);
+                    // move the smaller integer
+                    ASM::mov_reg_reg(buf, width, dst_reg, src_reg);
                 }
-                (I8, I16 | I32 | I64) => {
+                (I8 | U8, I16 | I32 | I64) => {
                     ASM::movsx_reg_reg(buf, RegisterWidth::W8, dst_reg, src_reg)
                 }
-                (I16, I32 | I64) => ASM::movsx_reg_reg(buf, RegisterWidth::W16, dst_reg, src_reg),
-                (I32, I64) => ASM::movsx_reg_reg(buf, RegisterWidth::W32, dst_reg, src_reg),
+                (I16 | U16, I32 | I64) => {
+                    ASM::movsx_reg_reg(buf, RegisterWidth::W16, dst_reg, src_reg)
+                }
+                (I32 | U32, I64) => ASM::movsx_reg_reg(buf, RegisterWidth::W32, dst_reg, src_reg),
                 // -- CASTING DOWN --
                 (U64 | I64, I32 | U32) => {
                     // move as a 32-bit integer (leaving any other bits behind)
@@ -4959,7 +5099,7 @@ impl<
                     // move as an 8-bit integer (leaving any other bits behind)
                     ASM::mov_reg_reg(buf, RegisterWidth::W8, dst_reg, src_reg);
                 }
-                _ => todo!("int cast from {source:?} to {target:?}"),
+                _ => unreachable!("int cast from {source:?} to {target:?}"),
             }
         }
     }
@@ -5045,7 +5185,17 @@ impl<
                 let int_width = arg_layout.to_int_width();
                 self.build_int_to_float_cast(dst, src, int_width, FloatWidth::F32);
             }
-            arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F32),
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                let tmp = self.debug_symbol("dec_as_f64");
+                self.num_to_f64(&tmp, src, arg_layout);
+
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let tmp_reg = self.storage_manager.load_to_float_reg(&mut self.buf, &tmp);
+                ASM::to_float_freg32_freg64(&mut self.buf, dst_reg, tmp_reg);
+
+                self.free_symbol(&tmp);
+            }
+            arg => unreachable!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F32),
         }
     }
 
@@ -5070,7 +5220,16 @@ impl<
                 let int_width = arg_layout.to_int_width();
                 self.build_int_to_float_cast(dst, src, int_width, FloatWidth::F64);
             }
-            arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F64),
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_TO_F64.to_string(),
+                    &[*src],
+                    &[*arg_layout],
+                    &Layout::F64,
+                );
+            }
+            arg => unreachable!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::F64),
         }
     }
 
@@ -5086,8 +5245,70 @@ impl<
                 );
             }
 
-            arg => todo!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::DEC),
+            LayoutRepr::Builtin(Builtin::Float(float_width)) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_FROM_FLOAT[float_width].to_string(),
+                    &[*src],
+                    &[*arg_layout],
+                    &Layout::DEC,
+                );
+            }
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                let to_offset = self.storage_manager.claim_stack_area_layout(
+                    self.layout_interner,
+                    *dst,
+                    Layout::DEC,
+                );
+
+                let (from_offset, size) = self.storage_manager.stack_offset_and_size(src);
+
+                self.storage_manager.copy_to_stack_offset(
+                    &mut self.buf,
+                    size,
+                    from_offset,
+                    to_offset,
+                );
+            }
+            arg => unreachable!("NumToFrac: layout, arg {arg:?}, ret {:?}", Layout::DEC),
+        }
+    }
+
+    fn build_int_bitwise_128(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        int_width: IntWidth,
+        op: fn(&mut Vec<'a, u8>, GeneralReg, GeneralReg, GeneralReg),
+    ) {
+        let dst_offset = self.storage_manager.claim_stack_area_layout(
+            self.layout_interner,
+            *dst,
+            Layout::from_int_width(int_width),
+        );
+
+        let (src1_offset, _) = self.storage_manager.stack_offset_and_size(src1);
+        let (src2_offset, _) = self.storage_manager.stack_offset_and_size(src2);
+
+        let tmp1_symbol = self.debug_symbol("bitwise_tmp1");
+        let tmp2_symbol = self.debug_symbol("bitwise_tmp2");
+
+        let buf = &mut self.buf;
+
+        let tmp1 = self.storage_manager.claim_general_reg(buf, &tmp1_symbol);
+        let tmp2 = self.storage_manager.claim_general_reg(buf, &tmp2_symbol);
+
+        // bitwise operations act on both 8-byte halves independently
+        for half in [0, 8] {
+            ASM::mov_reg64_base32(buf, tmp1, src1_offset + half);
+            ASM::mov_reg64_base32(buf, tmp2, src2_offset + half);
+            op(buf, tmp1, tmp1, tmp2);
+            ASM::mov_base32_reg64(buf, dst_offset + half, tmp1);
         }
+
+        self.storage_manager.free_symbol(&tmp1_symbol);
+        self.storage_manager.free_symbol(&tmp2_symbol);
     }
 
     fn compare_128bit(
@@ -5114,6 +5335,35 @@ impl<
         );
     }
 
+    /// Float operations don't overflow, but like the zig builtins we report an infinite or NaN
+    /// result as one. We don't call the builtins here: they return `{ value, has_overflowed }`
+    /// in `xmm0` and `rax` on x86_64, which our calling convention doesn't model.
+    fn build_float_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        float_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+        build_op: fn(&mut Self, &Symbol, &Symbol, &Symbol, &InLayout<'a>),
+    ) {
+        let value = self.debug_symbol("checked_float_value");
+        let is_finite = self.debug_symbol("checked_float_is_finite");
+        let has_overflowed = self.debug_symbol("checked_float_has_overflowed");
+
+        build_op(self, &value, src1, src2, float_layout);
+        self.build_num_is_finite(&is_finite, &value, float_layout);
+        self.build_not(&has_overflowed, &is_finite, &Layout::BOOL);
+
+        // the record `{ a : Frac a, b : Bool }` has its fields sorted by alignment
+        let fields = self.env.arena.alloc([value, has_overflowed]);
+        self.create_struct(dst, return_layout, fields);
+
+        self.free_symbol(&value);
+        self.free_symbol(&is_finite);
+        self.free_symbol(&has_overflowed);
+    }
+
     fn compare(
         &mut self,
         op: CompareOperation,
@@ -5171,7 +5421,7 @@ impl<
             LayoutRepr::Builtin(Builtin::Decimal) => {
                 self.compare_128bit(op, dst, src1, src2, IntWidth::I128);
             }
-            x => todo!("NumLt: layout, {:?}", x),
+            x => unreachable!("NumLt for layout {x:?}"),
         }
     }
 
@@ -319,7 +319,6 @@ impl<
         self.general_free_regs.push(reg);
     }
 
-    #[allow(dead_code)]
     /// This claims a temporary float register and enables is used in the passed in function.
     /// Temporary registers are not safe across call instructions.
     pub fn with_tmp_float_reg<F: FnOnce(&mut Self, &mut Vec<'a, u8>, FloatReg)>(
@@ -6,6 +6,7 @@
 #![allow(clippy::large_enum_variant, clippy::upper_case_acronyms)]
 
 use std::collections::hash_map::Entry;
+use std::path::PathBuf;
 
 use bumpalo::{collections::Vec, Bump};
 use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
@@ -14,7 +15,7 @@ use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::ident::ModuleName;
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
-use roc_mono::code_gen_help::{CallerProc, CodeGenHelp};
+use roc_mono::code_gen_help::{CallerProc, CodeGenHelp, ExpectBuffer};
 use roc_mono::ir::{
     BranchInfo, CallType, CrashTag, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement,
     Literal, ModifyRc, Param, Proc, ProcLayout, SelfRecursive, Stmt,
@@ -25,9 +26,10 @@ use roc_mono::layout::{
 };
 use roc_mono::list_element_layout;
 
+mod debug_info;
 mod generic64;
 mod object_builder;
-pub use object_builder::build_module;
+pub use object_builder::{build_module, toplevel_expect_name};
 use roc_target::Target;
 mod run_roc;
 
@@ -35,34 +37,61 @@ mod run_roc;
 pub enum AssemblyBackendMode {
     /// Assumes primitives (roc_alloc, roc_panic, etc) are provided by the host
     Binary,
+    /// Like `Binary`, but also runs `dbg` and `expect`, reporting failures to the parent process
+    BinaryDev,
     /// Provides a testing implementation of primitives (roc_alloc, roc_panic, etc)
     Test,
     /// Provides a testing implementation of primitives (roc_alloc, roc_panic, etc)
     Repl,
+    /// Like `Test`, but also runs `dbg` and `expect`, writing failures to the buffer that
+    /// `roc test` passes to `set_shared_buffer`. Every exposed symbol is a toplevel expect.
+    CliTest,
 }
 
 impl AssemblyBackendMode {
     fn generate_allocators(self) -> bool {
         match self {
-            AssemblyBackendMode::Binary => false,
+            AssemblyBackendMode::Binary | AssemblyBackendMode::BinaryDev => false,
             AssemblyBackendMode::Test => true,
             AssemblyBackendMode::Repl => true,
+            AssemblyBackendMode::CliTest => true,
         }
     }
 
     fn generate_roc_panic(self) -> bool {
         match self {
-            AssemblyBackendMode::Binary => false,
+            AssemblyBackendMode::Binary | AssemblyBackendMode::BinaryDev => false,
             AssemblyBackendMode::Test => true,
             AssemblyBackendMode::Repl => true,
+            AssemblyBackendMode::CliTest => true,
         }
     }
 
     fn generate_roc_dbg(self) -> bool {
         match self {
-            AssemblyBackendMode::Binary => false,
+            AssemblyBackendMode::Binary | AssemblyBackendMode::BinaryDev => false,
             AssemblyBackendMode::Test => true,
             AssemblyBackendMode::Repl => true,
+            AssemblyBackendMode::CliTest => true,
+        }
+    }
+
+    fn generate_debug_info(self) -> bool {
+        match self {
+            AssemblyBackendMode::Binary | AssemblyBackendMode::BinaryDev => true,
+            AssemblyBackendMode::Test => false,
+            AssemblyBackendMode::Repl => false,
+            AssemblyBackendMode::CliTest => false,
+        }
+    }
+
+    fn runs_expects(self) -> bool {
+        match self {
+            AssemblyBackendMode::Binary => false,
+            AssemblyBackendMode::BinaryDev => true,
+            AssemblyBackendMode::Test => false,
+            AssemblyBackendMode::Repl => false,
+            AssemblyBackendMode::CliTest => true,
         }
     }
 }
@@ -73,6 +102,8 @@ pub struct Env<'a> {
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub mode: AssemblyBackendMode,
+    /// The path and source of every module, used to map procedures back to their source lines
+    pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
 }
 
 // These relocations likely will need a length.
@@ -276,9 +307,30 @@ impl<'a> LastSeenMap<'a> {
                 }
             }
 
-            Stmt::Dbg { .. } => todo!("dbg not implemented in the dev backend"),
-            Stmt::Expect { .. } => todo!("expect is not implemented in the dev backend"),
-            Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the dev backend"),
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                self.set_last_seen(*symbol, stmt);
+                self.scan_ast_help(remainder);
+            }
+            Stmt::Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            }
+            | Stmt::ExpectFx {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                self.set_last_seen(*condition, stmt);
+                for sym in *lookups {
+                    self.set_last_seen(*sym, stmt);
+                }
+                self.scan_ast_help(remainder);
+            }
 
             Stmt::Crash(msg, _crash_tag) => {
                 self.set_last_seen(*msg, stmt);
@@ -648,11 +700,113 @@ trait Backend<'a> {
                 self.build_jump(id, args, arg_layouts.into_bump_slice(), ret_layout);
                 self.free_symbols(stmt);
             }
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable: _,
+                remainder,
+            } => {
+                if self.env().mode.runs_expects() {
+                    self.roc_dbg(source_location, source, *symbol);
+                }
+                self.free_symbols(stmt);
+                self.build_stmt(layout_ids, remainder, ret_layout);
+            }
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            }
+            | Stmt::ExpectFx {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => {
+                if self.env().mode.runs_expects() {
+                    let expect_buffer = match self.env().mode {
+                        // `roc test` reads the failures once the toplevel expect has returned
+                        AssemblyBackendMode::CliTest => ExpectBuffer::SharedBuffer,
+                        // Only `expect` stops the program to report a failure right away
+                        _ => ExpectBuffer::SharedFile {
+                            notify_parent: matches!(stmt, Stmt::Expect { .. }),
+                        },
+                    };
+
+                    let arena = self.env().arena;
+                    let layout_map = self.layout_map();
+                    let lookup_layouts = Vec::from_iter_in(
+                        lookups.iter().map(|sym| *layout_map.get(sym).unwrap()),
+                        arena,
+                    );
+
+                    // Copying the looked-up values into the shared buffer is done by a helper proc
+                    let (expect_call, new_specializations) = {
+                        let (module_id, layout_interner, interns, helper_proc_gen, _) =
+                            self.module_interns_helpers_mut();
+                        let ident_ids = interns.all_ident_ids.get_mut(&module_id).unwrap();
+
+                        helper_proc_gen.call_expect_failed(
+                            ident_ids,
+                            layout_interner,
+                            *condition,
+                            *region,
+                            lookups,
+                            lookup_layouts.into_bump_slice(),
+                            variables,
+                            expect_buffer,
+                        )
+                    };
+
+                    for spec in new_specializations.into_iter() {
+                        self.helper_proc_symbols_mut().push(spec);
+                    }
+
+                    self.build_expr(&Symbol::DEV_TMP, &expect_call, &Layout::UNIT);
+                    self.free_symbol(&Symbol::DEV_TMP);
+                }
+                self.free_symbols(stmt);
+                self.build_stmt(layout_ids, remainder, ret_layout);
+            }
             Stmt::Crash(msg, crash_tag) => self.roc_panic(*msg, *crash_tag),
-            x => todo!("the statement, {:?}", x),
         }
     }
 
+    fn roc_dbg(&mut self, source_location: &'a str, source: &'a str, msg: Symbol) {
+        let location = self.debug_symbol("dbg_location");
+        let source_str = self.debug_symbol("dbg_source");
+        self.load_literal(&location, &Layout::STR, &Literal::Str(source_location));
+        self.load_literal(&source_str, &Layout::STR, &Literal::Str(source));
+        self.load_literal_symbols(&[msg]);
+
+        // roc_dbg expects `*RocStr` arguments, in the order (location, message, source)
+        let mut arguments = [location, msg, source_str];
+        for (i, argument) in arguments.iter_mut().enumerate() {
+            let ptr = self.debug_symbol(&format!("dbg_arg_{i}"));
+            self.build_alloca(ptr, Some(*argument), Layout::STR);
+            *argument = ptr;
+        }
+
+        self.build_fn_call(
+            &Symbol::DEV_TMP2,
+            String::from("roc_dbg"),
+            &arguments,
+            &[Layout::U64, Layout::U64, Layout::U64],
+            &Layout::UNIT,
+        );
+
+        self.free_symbol(&location);
+        self.free_symbol(&source_str);
+        for ptr in arguments {
+            self.free_symbol(&ptr);
+        }
+        self.free_symbol(&Symbol::DEV_TMP2);
+    }
+
     fn roc_panic(&mut self, msg: Symbol, crash_tag: CrashTag) {
         let error_message = self.debug_symbol("error_message");
 
@@ -1195,10 +1349,13 @@ trait Backend<'a> {
                     // saturated sub is just normal sub
                     self.build_num_sub(sym, &args[0], &args[1], ret_layout)
                 }
-                LayoutRepr::Builtin(Builtin::Decimal) => {
-                    // self.load_args_and_call_zig(backend, bitcode::DEC_SUB_SATURATED)
-                    todo!()
-                }
+                LayoutRepr::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_SUB_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
                 _ => internal_error!("invalid return type"),
             },
             LowLevel::NumBitwiseAnd => {
@@ -1843,6 +2000,28 @@ trait Backend<'a> {
 
                 self.build_num_int_cast(sym, &args[0], source_width, target_width)
             }
+            LowLevel::NumToIntChecked => {
+                // return_layout : Result N [OutOfBounds]* ~ { result: N, out_of_bounds: bool }
+                let source_width = arg_layouts[0].to_int_width();
+
+                let target_width = match self.interner().get_repr(*ret_layout) {
+                    LayoutRepr::Struct(field_layouts) if field_layouts.len() == 2 => {
+                        field_layouts[0].to_int_width()
+                    }
+                    layout => internal_error!(
+                        "There can only be a result layout here, found {:?}!",
+                        layout
+                    ),
+                };
+
+                let intrinsic = if source_width.is_signed() {
+                    &bitcode::NUM_INT_TO_INT_CHECKING_MAX_AND_MIN[target_width][source_width]
+                } else {
+                    &bitcode::NUM_INT_TO_INT_CHECKING_MAX[target_width][source_width]
+                };
+
+                self.build_fn_call(sym, intrinsic.to_string(), args, arg_layouts, ret_layout)
+            }
             LowLevel::NumIsMultipleOf => {
                 let int_width = arg_layouts[0].try_int_width().unwrap();
                 let intrinsic = bitcode::NUM_IS_MULTIPLE_OF[int_width].to_string();
@@ -2036,6 +2215,64 @@ trait Backend<'a> {
                 }
             }
 
+            LowLevel::NumToFloatChecked => {
+                // return_layout : Result F [OutOfBounds]* ~ { result: F, out_of_bounds: bool }
+                let float_layout = match self.interner().get_repr(*ret_layout) {
+                    LayoutRepr::Struct(field_layouts) if field_layouts.len() == 2 => {
+                        field_layouts[0]
+                    }
+                    layout => internal_error!(
+                        "There can only be a result layout here, found {:?}!",
+                        layout
+                    ),
+                };
+
+                let value = self.debug_symbol("checked_float_value");
+                let is_infinite = self.debug_symbol("checked_float_is_infinite");
+                let arg_is_finite = self.debug_symbol("checked_float_arg_is_finite");
+                let became_infinite = self.debug_symbol("checked_float_became_infinite");
+
+                match arg_layouts[0].try_to_int_width() {
+                    Some(int_width) => {
+                        let float_width = match float_layout {
+                            Layout::F64 => FloatWidth::F64,
+                            Layout::F32 => FloatWidth::F32,
+                            _ => unreachable!("invalid return layout for NumToFloatChecked"),
+                        };
+                        self.build_int_to_float_cast(&value, &args[0], int_width, float_width);
+                    }
+                    None => {
+                        self.build_num_to_frac(&value, &args[0], &arg_layouts[0], &float_layout);
+                    }
+                }
+
+                // A finite number that converts to infinity is out of bounds for the float type.
+                // Integers and decimals are always finite.
+                self.build_num_is_infinite(&is_infinite, &value, &float_layout);
+                let out_of_bounds = match arg_layouts[0] {
+                    Layout::F32 | Layout::F64 => {
+                        self.build_num_is_finite(&arg_is_finite, &args[0], &arg_layouts[0]);
+                        self.build_int_bitwise_and(
+                            &became_infinite,
+                            &is_infinite,
+                            &arg_is_finite,
+                            IntWidth::U8,
+                        );
+                        became_infinite
+                    }
+                    _ => is_infinite,
+                };
+
+                // the record `{ result : Frac a, out_of_bounds : Bool }` has its fields sorted by alignment
+                let fields = self.env().arena.alloc([value, out_of_bounds]);
+                self.create_struct(sym, ret_layout, fields);
+
+                self.free_symbol(&value);
+                self.free_symbol(&is_infinite);
+                self.free_symbol(&arg_is_finite);
+                self.free_symbol(&became_infinite);
+            }
+
             LowLevel::NumWithoutDecimalPoint => {
                 let intrinsic = bitcode::DEC_TO_I128.to_string();
                 self.build_fn_call(sym, intrinsic, args, arg_layouts, ret_layout)
@@ -1,3 +1,4 @@
+use crate::debug_info::{self, DebugProc};
 use crate::generic64::{aarch64, new_backend_64bit, x86_64};
 use crate::{AssemblyBackendMode, Backend, Env, Relocation};
 use bumpalo::collections::Vec;
@@ -7,13 +8,14 @@ use object::{
     Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationKind, SectionKind,
     SymbolFlags, SymbolKind, SymbolScope,
 };
+use roc_builtins::bitcode;
 use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_module::symbol;
 use roc_module::symbol::Interns;
 use roc_mono::ir::{Call, CallSpecId, Expr, UpdateModeId};
 use roc_mono::ir::{Proc, ProcLayout, Stmt};
-use roc_mono::layout::{LambdaName, Layout, LayoutIds, LayoutInterner, STLayoutInterner};
+use roc_mono::layout::{LambdaName, Layout, LayoutIds, LayoutInterner, Niche, STLayoutInterner};
 use roc_target::Target;
 
 // This is used by some code below which is currently commented out.
@@ -454,7 +456,17 @@ fn build_object<'a, B: Backend<'a>>(
     }
 
     if backend.env().mode.generate_roc_dbg() {
-        generate_roc_dbg(&mut backend, &mut output);
+        if backend.env().mode.runs_expects() {
+            // `roc test` prints the values of `dbg`, like the LLVM backend does
+            generate_wrapper(
+                &mut backend,
+                &mut output,
+                "roc_dbg".into(),
+                bitcode::UTILS_DBG_IMPL.into(),
+            );
+        } else {
+            generate_roc_dbg(&mut backend, &mut output);
+        }
     }
 
     if backend.env().mode.generate_allocators() {
@@ -586,7 +598,26 @@ fn build_object<'a, B: Backend<'a>>(
                         Exposed::TestMain,
                     );
                 }
-                AssemblyBackendMode::Binary => { /* do nothing */ }
+                AssemblyBackendMode::CliTest => {
+                    // Catch any crash in the expect, so `roc test` can report it
+                    let expect_helper = roc_mono::code_gen_help::test_helper(
+                        code_gen_help,
+                        ident_ids,
+                        layout_interner,
+                        &proc,
+                    );
+
+                    build_proc_symbol(
+                        &mut output,
+                        &mut layout_ids,
+                        &mut procs,
+                        &mut backend,
+                        layout,
+                        expect_helper,
+                        Exposed::Expect(sym),
+                    );
+                }
+                AssemblyBackendMode::Binary | AssemblyBackendMode::BinaryDev => { /* do nothing */ }
             }
 
             build_proc_symbol(
@@ -623,12 +654,14 @@ fn build_object<'a, B: Backend<'a>>(
 
     // Build procedures from user code
     let mut relocations = bumpalo::vec![in arena];
+    let mut debug_procs = bumpalo::vec![in arena];
     for (fn_name, section_id, proc_id, proc) in procs {
         build_proc(
             &mut output,
             &mut backend,
             &mut relocations,
             &mut layout_ids,
+            &mut debug_procs,
             data_section,
             fn_name,
             section_id,
@@ -720,6 +753,7 @@ fn build_object<'a, B: Backend<'a>>(
             &mut backend,
             &mut relocations,
             &mut layout_ids,
+            &mut debug_procs,
             data_section,
             fn_name,
             section_id,
@@ -735,6 +769,21 @@ fn build_object<'a, B: Backend<'a>>(
             Err(e) => internal_error!("{:?}", e),
         }
     }
+
+    if backend.env().mode.generate_debug_info() {
+        let env = backend.env();
+
+        if let Err(e) = debug_info::add_debug_info(
+            &mut output,
+            backend.interns(),
+            &env.sources,
+            env.module_id,
+            &debug_procs,
+        ) {
+            internal_error!("failed to generate debug info: {:?}", e)
+        }
+    }
+
     output
 }
 
@@ -777,6 +826,7 @@ fn build_exposed_proc<'a, B: Backend<'a>>(backend: &mut B, proc: &Proc<'a>) -> P
         ret_layout: proc.ret_layout,
         is_self_recursive: roc_mono::ir::SelfRecursive::NotSelfRecursive,
         is_erased: proc.is_erased,
+        region: proc.region,
     }
 }
 
@@ -858,15 +908,32 @@ fn build_exposed_generic_proc<'a, B: Backend<'a>>(backend: &mut B, proc: &Proc<'
         ret_layout: roc_mono::layout::Layout::UNIT,
         is_self_recursive: roc_mono::ir::SelfRecursive::NotSelfRecursive,
         is_erased: proc.is_erased,
+        region: proc.region,
     }
 }
 
+/// The name of the function that runs a toplevel `expect` in a `CliTest` build.
+/// Like the LLVM backend, it returns a `RocCallResult`, holding the message of any crash.
+pub fn toplevel_expect_name(interns: &Interns, symbol: symbol::Symbol) -> String {
+    let layout = ProcLayout {
+        arguments: &[],
+        result: Layout::UNIT,
+        niche: Niche::NONE,
+    };
+    let name = LayoutIds::default()
+        .get_toplevel(symbol, &layout)
+        .to_exposed_symbol_string(symbol, interns);
+
+    format!("Expect_{name}")
+}
+
 #[allow(clippy::enum_variant_names)]
 enum Exposed {
     ExposedGeneric,
     Exposed,
     NotExposed,
     TestMain,
+    Expect(symbol::Symbol),
 }
 
 fn build_proc_symbol<'a, B: Backend<'a>>(
@@ -900,6 +967,7 @@ fn build_proc_symbol<'a, B: Backend<'a>>(
             layout.result,
         ),
         Exposed::TestMain => String::from("test_main"),
+        Exposed::Expect(expect) => toplevel_expect_name(backend.interns(), expect),
     };
 
     let proc_symbol = Symbol {
@@ -910,7 +978,9 @@ fn build_proc_symbol<'a, B: Backend<'a>>(
         // TODO: Depending on whether we are building a static or dynamic lib, this should change.
         // We should use Dynamic -> anyone, Linkage -> static link, Compilation -> this module only.
         scope: match exposed {
-            Exposed::ExposedGeneric | Exposed::Exposed | Exposed::TestMain => SymbolScope::Dynamic,
+            Exposed::ExposedGeneric | Exposed::Exposed | Exposed::TestMain | Exposed::Expect(_) => {
+                SymbolScope::Dynamic
+            }
             Exposed::NotExposed => SymbolScope::Linkage,
         },
         weak: false,
@@ -927,6 +997,7 @@ fn build_proc<'a, B: Backend<'a>>(
     backend: &mut B,
     relocations: &mut Vec<'a, (SectionId, object::write::Relocation)>,
     layout_ids: &mut LayoutIds<'a>,
+    debug_procs: &mut Vec<'a, DebugProc>,
     data_section: SectionId,
     fn_name: String,
     section_id: SectionId,
@@ -935,8 +1006,22 @@ fn build_proc<'a, B: Backend<'a>>(
 ) {
     let mut local_data_index = 0;
     let target = backend.target();
+    let debug_proc = backend.env().mode.generate_debug_info().then(|| {
+        DebugProc::new(
+            backend.interns(),
+            proc.name.name(),
+            fn_name.clone(),
+            proc_id,
+            proc.region,
+        )
+    });
     let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids);
     let proc_offset = output.add_symbol_data(proc_id, section_id, &proc_data, 16);
+    if let Some(mut debug_proc) = debug_proc {
+        debug_proc.size = proc_data.len() as u64;
+        debug_procs.push(debug_proc);
+    }
+
     for reloc in relocs.iter() {
         let elfreloc = match reloc {
             Relocation::LocalData { offset, data } => {
@@ -3,6 +3,7 @@ use crate::llvm::build_list::{self, allocate_list, empty_polymorphic_list};
 use crate::llvm::convert::{
     argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_str_type,
 };
+use crate::llvm::debug_info::{self, DebugSources};
 use crate::llvm::expect::{clone_to_shared_memory, SharedMemoryPointer};
 use crate::llvm::memcpy::build_memcpy;
 use crate::llvm::refcounting::{
@@ -24,7 +25,7 @@ use inkwell::module::{Linkage, Module};
 use inkwell::passes::{PassManager, PassManagerBuilder};
 use inkwell::types::{
     AnyType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FloatMathType, FunctionType,
-    IntMathType, IntType, PointerMathType, StructType,
+    IntMathType, IntType, PointerMathType, PointerType, StructType,
 };
 use inkwell::values::{
     BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue, FloatMathValue,
@@ -661,16 +662,23 @@ macro_rules! debug_info_init {
         use inkwell::debug_info::AsDIScope;
 
         let func_scope = $function_value.get_subprogram().expect("subprogram");
+
+        // procs from source know where they were defined; generated helpers use line 0
+        let (file, line) = $env
+            .debug_sources
+            .function_location($function_value)
+            .unwrap_or(($env.compile_unit.get_file(), 0));
+
         let lexical_block = $env.dibuilder.create_lexical_block(
             /* scope */ func_scope.as_debug_info_scope(),
-            /* file */ $env.compile_unit.get_file(),
-            /* line_no */ 0,
+            /* file */ file,
+            /* line_no */ line,
             /* column_no */ 0,
         );
 
         let loc = $env.dibuilder.create_debug_location(
             $env.context,
-            /* line */ 0,
+            /* line */ line,
             /* column */ 0,
             /* current_scope */ lexical_block.as_debug_info_scope(),
             /* inlined_at */ None,
@@ -734,6 +742,7 @@ pub struct Env<'a, 'ctx, 'env> {
     pub builder: &'env Builder<'ctx>,
     pub dibuilder: &'env DebugInfoBuilder<'ctx>,
     pub compile_unit: &'env DICompileUnit<'ctx>,
+    pub debug_sources: &'env DebugSources<'ctx>,
     pub module: &'ctx Module<'ctx>,
     pub interns: Interns,
     pub target: Target,
@@ -947,19 +956,26 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         }
     }
 
-    pub fn new_debug_info(module: &Module<'ctx>) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
+    /// `root_path` is the `.roc` file of the module that the compile unit is built from
+    pub fn new_debug_info(
+        module: &Module<'ctx>,
+        root_path: &Path,
+    ) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
         let debug_metadata_version = module.get_context().i32_type().const_int(3, false);
         module.add_basic_value_flag(
             "Debug Info Version",
             inkwell::module::FlagBehavior::Warning,
             debug_metadata_version,
         );
+        let (directory, file_name) = debug_info::split_path(root_path);
+        // DWARF has no language code for Roc. Our types are described as C structs and
+        // unions, so C is what lets debuggers print values and evaluate expressions.
         module.create_debug_info_builder(
             true,
             /* language */ inkwell::debug_info::DWARFSourceLanguage::C,
-            /* filename */ "roc_app",
-            /* directory */ ".",
-            /* producer */ "my llvm compiler frontend",
+            /* filename */ &file_name,
+            /* directory */ &directory,
+            /* producer */ "roc",
             /* is_optimized */ false,
             /* compiler command line flags */ "",
             /* runtime_ver */ 0,
@@ -3243,6 +3259,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                     LayoutRepr::RecursivePointer(_)
                 ));
 
+                debug_info::set_symbol_debug_location(env, parent, *symbol);
+
                 let val = build_exp_expr(
                     env,
                     layout_interner,
@@ -3254,6 +3272,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
                     expr,
                 );
 
+                debug_info::declare_local(env, layout_interner, parent, *symbol, *layout, val);
+
                 // Make a new scope which includes the binding we just encountered.
                 // This should be done *after* compiling the bound expr, since any
                 // recursive (in the LetRec sense) bindings should already have
@@ -3282,6 +3302,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             result
         }
         Ret(symbol) => {
+            debug_info::set_symbol_debug_location(env, parent, *symbol);
+
             let (value, layout) = scope.load_symbol_and_layout(symbol);
 
             build_return(
@@ -3302,6 +3324,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             cond_layout,
             cond_symbol,
         } => {
+            debug_info::set_symbol_debug_location(env, parent, *cond_symbol);
+
             let ret_type =
                 basic_type_from_layout(env, layout_interner, layout_interner.get_repr(*ret_layout));
 
@@ -3404,6 +3428,10 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
         }
 
         Jump(join_point, arguments) => {
+            if let Some(argument) = arguments.first() {
+                debug_info::set_symbol_debug_location(env, parent, *argument);
+            }
+
             let builder = env.builder;
             let context = env.context;
             let (cont_block, argument_phi_values) = scope.get_join_point(*join_point).unwrap();
@@ -3580,6 +3608,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             variable: _,
             remainder,
         } => {
+            debug_info::set_symbol_debug_location(env, parent, *symbol);
+
             if env.mode.runs_expects() {
                 let location = build_string_literal(env, parent, source_location);
                 let source = build_string_literal(env, parent, source);
@@ -3608,6 +3638,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             let bd = env.builder;
             let context = env.context;
 
+            debug_info::set_region_debug_location(env, parent, *region);
+
             let (cond, _cond_layout) = scope.load_symbol_and_layout(cond_symbol);
 
             let condition = bd.new_build_int_compare(
@@ -3659,6 +3691,9 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
 
             bd.position_at_end(then_block);
 
+            // the remainder is attributed to the enclosing proc again
+            debug_info_init!(env, parent);
+
             build_exp_stmt(
                 env,
                 layout_interner,
@@ -3680,6 +3715,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
             let bd = env.builder;
             let context = env.context;
 
+            debug_info::set_region_debug_location(env, parent, *region);
+
             let (cond, _cond_layout) = scope.load_symbol_and_layout(cond_symbol);
 
             let condition = bd.new_build_int_compare(
@@ -3727,6 +3764,9 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
 
             bd.position_at_end(then_block);
 
+            // the remainder is attributed to the enclosing proc again
+            debug_info_init!(env, parent);
+
             build_exp_stmt(
                 env,
                 layout_interner,
@@ -3739,6 +3779,8 @@ pub(crate) fn build_exp_stmt<'a, 'ctx>(
         }
 
         Crash(sym, tag) => {
+            debug_info::set_symbol_debug_location(env, parent, *sym);
+
             throw_exception(env, scope, sym, *tag);
 
             // unused value (must return a BasicValue)
@@ -5937,7 +5979,7 @@ fn build_proc_header<'a, 'ctx>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = debug_info::new_proc_subprogram(env, layout_interner, &fn_name, fn_val, proc);
     fn_val.set_subprogram(subprogram);
 
     debug_info_init!(env, fn_val);
@@ -6174,6 +6216,73 @@ fn build_closure_caller<'a, 'ctx>(
         alias_symbol,
         lambda_set.runtime_representation(),
     );
+
+    // STEP 5: build a function that releases the captured values of a closure which the host
+    // drops without calling, e.g. `roc__mainForHost_0_drop` (def_name is `mainForHost_0`)
+    build_closure_drop(
+        env,
+        layout_interner,
+        def_name,
+        closure_argument_type,
+        closure_layout,
+    );
+}
+
+fn build_closure_drop<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    def_name: &str,
+    closure_argument_type: PointerType<'ctx>,
+    closure_layout: InLayout<'a>,
+) {
+    let function_name = format!("roc__{def_name}_drop");
+
+    let function_spec =
+        FunctionSpec::cconv(env, CCReturn::Void, None, &[closure_argument_type.into()]);
+
+    let function_value = add_func(
+        env.context,
+        env.module,
+        function_name.as_str(),
+        function_spec,
+        Linkage::External,
+    );
+
+    let subprogram = env.new_subprogram(&function_name);
+    function_value.set_subprogram(subprogram);
+
+    let entry = env.context.append_basic_block(function_value, "entry");
+    env.builder.position_at_end(entry);
+
+    debug_info_init!(env, function_value);
+
+    let closure_ptr = function_value
+        .get_nth_param(0)
+        .unwrap()
+        .into_pointer_value();
+    let closure_data = if layout_interner.is_passed_by_reference(closure_layout) {
+        closure_ptr.into()
+    } else {
+        let basic_type = basic_type_from_layout(
+            env,
+            layout_interner,
+            layout_interner.get_repr(closure_layout),
+        );
+
+        env.builder
+            .new_build_load(basic_type, closure_ptr, "load_closure")
+    };
+
+    let mut layout_ids = LayoutIds::default();
+    decrement_refcount_layout(
+        env,
+        layout_interner,
+        &mut layout_ids,
+        closure_data,
+        closure_layout,
+    );
+
+    env.builder.new_build_return(None);
 }
 
 fn build_host_exposed_alias_size<'a, 'r>(
@@ -6268,6 +6377,8 @@ fn build_proc<'a, 'ctx>(
             builder.new_build_return(Some(&body));
         }
     }
+
+    debug_info::declare_proc_arguments(env, layout_interner, fn_val, proc);
 }
 
 pub fn verify_fn(fn_val: FunctionValue<'_>) {
@@ -0,0 +1,700 @@
+//! Source-level debug info for the LLVM backend.
+//!
+//! Procedures get a `DISubprogram` in the `.roc` file they were defined in, at the line where
+//! their body starts, and their arguments get `DIType`s built from their layouts so they can be
+//! inspected in a debugger. Mono statements don't carry regions, so each `let` is attributed to
+//! the line where mono saw its value defined, and each `expect` to its own region. Statements
+//! which use a symbol, like `ret`, `switch` and `jump`, are attributed to the line where that
+//! symbol was defined, and the others keep the line of the statement before them. Locals bound
+//! by a `let` in the source are declared as variables, so their values can be inspected too.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use inkwell::debug_info::{
+    AsDIScope, DIFile, DIFlags, DIFlagsConstants, DIScope, DISubprogram, DIType, DebugInfoBuilder,
+};
+use inkwell::values::{BasicValueEnum, FunctionValue};
+use inkwell::AddressSpace;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_collections::all::MutMap;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_mono::ir::Proc;
+use roc_mono::layout::{
+    round_up_to_alignment, Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, STLayoutInterner,
+    UnionLayout,
+};
+use roc_region::all::{LineInfo, Region};
+
+use crate::llvm::build::{create_entry_block_alloca, BuilderExt, Env};
+use crate::llvm::convert::basic_type_from_layout;
+
+// DWARF base type encodings, see section 7.8 of the DWARF 5 spec
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x08;
+
+/// The source files of a program, and where each of its functions was defined in them.
+#[derive(Default)]
+pub struct DebugSources<'ctx> {
+    files: MutMap<ModuleId, (DIFile<'ctx>, LineInfo)>,
+    symbol_regions: MutMap<Symbol, Region>,
+    functions: RefCell<MutMap<FunctionValue<'ctx>, SourceLocation<'ctx>>>,
+}
+
+#[derive(Clone, Copy)]
+struct SourceLocation<'ctx> {
+    module_id: ModuleId,
+    file: DIFile<'ctx>,
+    line: u32,
+}
+
+impl<'ctx> DebugSources<'ctx> {
+    pub fn new(
+        dibuilder: &DebugInfoBuilder<'ctx>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+        symbol_regions: &MutMap<Symbol, Region>,
+    ) -> Self {
+        let files = sources
+            .iter()
+            .map(|(module_id, (path, src))| {
+                let (directory, file_name) = split_path(path);
+                let file = dibuilder.create_file(&file_name, &directory);
+
+                (*module_id, (file, LineInfo::new(src)))
+            })
+            .collect();
+
+        Self {
+            files,
+            symbol_regions: symbol_regions.clone(),
+            functions: RefCell::new(MutMap::default()),
+        }
+    }
+
+    fn location(&self, module_id: ModuleId, region: Region) -> Option<SourceLocation<'ctx>> {
+        if region == Region::zero() {
+            return None;
+        }
+
+        let (file, line_info) = self.files.get(&module_id)?;
+
+        // DWARF lines are 1-based
+        let line = line_info.convert_pos(region.start()).line + 1;
+
+        Some(SourceLocation {
+            module_id,
+            file: *file,
+            line,
+        })
+    }
+
+    /// The file and line where `function` was defined, if we know it
+    pub fn function_location(&self, function: FunctionValue<'ctx>) -> Option<(DIFile<'ctx>, u32)> {
+        let functions = self.functions.borrow();
+        let location = functions.get(&function)?;
+
+        Some((location.file, location.line))
+    }
+
+    /// The line of `region`, which must be in the same module as `function`
+    pub fn line_in_function(&self, function: FunctionValue<'ctx>, region: Region) -> Option<u32> {
+        let module_id = self.functions.borrow().get(&function)?.module_id;
+
+        self.location(module_id, region)
+            .map(|location| location.line)
+    }
+
+    /// The line where the value of `symbol` was defined, if it was defined in `function`'s module
+    pub fn line_of_symbol(&self, function: FunctionValue<'ctx>, symbol: Symbol) -> Option<u32> {
+        let region = *self.symbol_regions.get(&symbol)?;
+        let module_id = self.functions.borrow().get(&function)?.module_id;
+
+        if symbol.module_id() != module_id {
+            return None;
+        }
+
+        self.location(module_id, region)
+            .map(|location| location.line)
+    }
+}
+
+pub(crate) fn split_path(path: &Path) -> (String, String) {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().into_owned(),
+        _ => String::from("."),
+    };
+
+    let file_name = match path.file_name() {
+        Some(file_name) => file_name.to_string_lossy().into_owned(),
+        None => path.to_string_lossy().into_owned(),
+    };
+
+    (directory, file_name)
+}
+
+/// A readable name like `List.map`, falling back to the LLVM function name for procs that the
+/// compiler generated without one.
+fn proc_display_name(env: &Env<'_, '_, '_>, symbol: Symbol, fn_name: &str) -> String {
+    let module_id = symbol.module_id();
+    let module_name = env.interns.module_ids.get_name(module_id);
+    let ident = env
+        .interns
+        .all_ident_ids
+        .get(&module_id)
+        .and_then(|ident_ids| ident_ids.get_name(symbol.ident_id()));
+
+    match (module_name, ident) {
+        (Some(module_name), Some(ident)) => format!("{}.{}", module_name.as_str(), ident),
+        _ => fn_name.to_string(),
+    }
+}
+
+/// Create a `DISubprogram` for `proc`, pointing at the source file and line it came from.
+pub(crate) fn new_proc_subprogram<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    fn_name: &str,
+    fn_val: FunctionValue<'ctx>,
+    proc: &Proc<'a>,
+) -> DISubprogram<'ctx> {
+    let symbol = proc.name.name();
+    let location = env.debug_sources.location(symbol.module_id(), proc.region);
+
+    let (file, line) = match location {
+        Some(location) => {
+            env.debug_sources
+                .functions
+                .borrow_mut()
+                .insert(fn_val, location);
+
+            (location.file, location.line)
+        }
+        None => (env.compile_unit.get_file(), 0),
+    };
+
+    let return_type = ditype_from_layout(env, layout_interner, proc.ret_layout);
+    let mut parameter_types = Vec::with_capacity(proc.args.len());
+
+    for (arg_val, (layout, _)) in fn_val.get_param_iter().zip(proc.args) {
+        parameter_types.push(argument_ditype(env, layout_interner, arg_val, *layout));
+    }
+
+    let subroutine_type = env.dibuilder.create_subroutine_type(
+        file,
+        /* return type */ Some(return_type),
+        /* parameter types */ &parameter_types,
+        DIFlags::PUBLIC,
+    );
+
+    env.dibuilder.create_function(
+        /* scope */ file.as_debug_info_scope(),
+        /* func name */ &proc_display_name(env, symbol, fn_name),
+        /* linkage_name */ Some(fn_name),
+        /* file */ file,
+        /* line_no */ line,
+        /* DIType */ subroutine_type,
+        /* is_local_to_unit */ true,
+        /* is_definition */ true,
+        /* scope_line */ line,
+        /* flags */ DIFlags::PUBLIC,
+        /* is_optimized */ false,
+    )
+}
+
+/// Describe the arguments of `proc` to the debugger. Must be called once the body of `fn_val`
+/// has been built, because the variables are attached to its first instruction.
+pub(crate) fn declare_proc_arguments<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    fn_val: FunctionValue<'ctx>,
+    proc: &Proc<'a>,
+) {
+    let Some(subprogram) = fn_val.get_subprogram() else {
+        return;
+    };
+
+    let Some(first_instruction) = fn_val
+        .get_first_basic_block()
+        .and_then(|entry| entry.get_first_instruction())
+    else {
+        return;
+    };
+
+    let (file, line) = env
+        .debug_sources
+        .function_location(fn_val)
+        .unwrap_or((env.compile_unit.get_file(), 0));
+
+    let scope = subprogram.as_debug_info_scope();
+    let debug_loc = env
+        .dibuilder
+        .create_debug_location(env.context, line, 0, scope, None);
+
+    for (index, (arg_val, (layout, symbol))) in fn_val.get_param_iter().zip(proc.args).enumerate() {
+        let ditype = argument_ditype(env, layout_interner, arg_val, *layout);
+
+        let variable = env.dibuilder.create_parameter_variable(
+            scope,
+            symbol.as_str(&env.interns),
+            /* arg_no */ index as u32 + 1,
+            file,
+            line,
+            ditype,
+            /* always_preserve */ true,
+            DIFlags::ZERO,
+        );
+
+        env.dibuilder.insert_dbg_value_before(
+            arg_val,
+            variable,
+            None,
+            debug_loc,
+            first_instruction,
+        );
+    }
+}
+
+/// Describe a `let`-bound local to the debugger, if it was defined in the source of `fn_val`'s
+/// module. Must be called right after `value` was built, because the declaration is added to the
+/// current block.
+pub(crate) fn declare_local<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    fn_val: FunctionValue<'ctx>,
+    symbol: Symbol,
+    layout: InLayout<'a>,
+    value: BasicValueEnum<'ctx>,
+) {
+    let Some(line) = env.debug_sources.line_of_symbol(fn_val, symbol) else {
+        return;
+    };
+
+    let Some(subprogram) = fn_val.get_subprogram() else {
+        return;
+    };
+
+    let Some(block) = env.builder.get_insert_block() else {
+        return;
+    };
+
+    let (size, alignment) = layout_interner.stack_size_and_alignment(layout);
+
+    if size == 0 {
+        return;
+    }
+
+    let (file, _) = env
+        .debug_sources
+        .function_location(fn_val)
+        .unwrap_or((env.compile_unit.get_file(), 0));
+
+    let scope = subprogram.as_debug_info_scope();
+    let debug_loc = env
+        .dibuilder
+        .create_debug_location(env.context, line, 0, scope, None);
+
+    let variable = env.dibuilder.create_auto_variable(
+        scope,
+        symbol.as_str(&env.interns),
+        file,
+        line,
+        ditype_from_layout(env, layout_interner, layout),
+        /* always_preserve */ true,
+        DIFlags::ZERO,
+        alignment * 8,
+    );
+
+    // Values which are passed by reference already live on the stack. Other values get a stack
+    // slot of their own, which optimizations turn back into a register.
+    let storage = if layout_interner.is_passed_by_reference(layout) && value.is_pointer_value() {
+        value.into_pointer_value()
+    } else {
+        let name = symbol.as_str(&env.interns);
+        let storage = create_entry_block_alloca(env, fn_val, value.get_type(), name);
+
+        env.builder.new_build_store(storage, value);
+
+        storage
+    };
+
+    env.dibuilder
+        .insert_declare_at_end(storage, Some(variable), None, debug_loc, block);
+}
+
+/// Attribute the code that is built next to the line of `region`, if we know where it is.
+pub(crate) fn set_region_debug_location<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    fn_val: FunctionValue<'ctx>,
+    region: Region,
+) {
+    if let Some(line) = env.debug_sources.line_in_function(fn_val, region) {
+        set_line_debug_location(env, fn_val, line);
+    }
+}
+
+/// Attribute the code that is built next to the line where `symbol` was defined, if we know it.
+pub(crate) fn set_symbol_debug_location<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    fn_val: FunctionValue<'ctx>,
+    symbol: Symbol,
+) {
+    if let Some(line) = env.debug_sources.line_of_symbol(fn_val, symbol) {
+        set_line_debug_location(env, fn_val, line);
+    }
+}
+
+fn set_line_debug_location<'ctx>(env: &Env<'_, 'ctx, '_>, fn_val: FunctionValue<'ctx>, line: u32) {
+    let Some(subprogram) = fn_val.get_subprogram() else {
+        return;
+    };
+
+    let debug_loc = env.dibuilder.create_debug_location(
+        env.context,
+        line,
+        /* column */ 0,
+        subprogram.as_debug_info_scope(),
+        /* inlined_at */ None,
+    );
+
+    env.builder.set_current_debug_location(debug_loc);
+}
+
+/// Large values are passed by reference; in that case the argument is a pointer to the layout.
+fn argument_ditype<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    arg_val: BasicValueEnum<'ctx>,
+    layout: InLayout<'a>,
+) -> DIType<'ctx> {
+    let ditype = ditype_from_layout(env, layout_interner, layout);
+    let basic_type = basic_type_from_layout(env, layout_interner, layout_interner.get_repr(layout));
+
+    if arg_val.is_pointer_value() && !basic_type.is_pointer_type() {
+        pointer_to(env, ditype)
+    } else {
+        ditype
+    }
+}
+
+/// The `DIType` matching the in-memory representation of `layout`.
+pub(crate) fn ditype_from_layout<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    layout: InLayout<'a>,
+) -> DIType<'ctx> {
+    let (size, alignment) = layout_interner.stack_size_and_alignment(layout);
+
+    match layout_interner.get_repr(layout) {
+        LayoutRepr::Builtin(builtin) => ditype_from_builtin(env, layout_interner, builtin),
+        LayoutRepr::Struct(field_layouts) => {
+            let members = struct_members(env, layout_interner, field_layouts, |index| {
+                index.to_string()
+            });
+
+            struct_type(env, "Record", size, alignment, &members)
+        }
+        LayoutRepr::Ptr(inner) => {
+            let pointee = ditype_from_layout(env, layout_interner, inner);
+
+            pointer_to(env, pointee)
+        }
+        LayoutRepr::Union(union_layout) => {
+            ditype_from_union_layout(env, layout_interner, union_layout)
+        }
+        LayoutRepr::LambdaSet(lambda_set) => {
+            ditype_from_layout(env, layout_interner, lambda_set.runtime_representation())
+        }
+        LayoutRepr::RecursivePointer(_) | LayoutRepr::FunctionPointer(_) => {
+            // we stop here, so that recursive layouts don't lead to infinitely deep types
+            let byte = basic_type(env, IntWidth::U8.type_name(), 1, DW_ATE_UNSIGNED);
+
+            pointer_to(env, byte)
+        }
+        LayoutRepr::Erased(_) => struct_type(env, "Erased", size, alignment, &[]),
+    }
+}
+
+fn ditype_from_builtin<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    builtin: Builtin<'a>,
+) -> DIType<'ctx> {
+    match builtin {
+        Builtin::Int(int_width) => {
+            let encoding = if int_width.is_signed() {
+                DW_ATE_SIGNED
+            } else {
+                DW_ATE_UNSIGNED
+            };
+
+            basic_type(env, int_width.type_name(), int_width.stack_size(), encoding)
+        }
+        Builtin::Float(float_width) => {
+            let size = match float_width {
+                FloatWidth::F32 => 4,
+                FloatWidth::F64 => 8,
+            };
+
+            basic_type(env, float_width.type_name(), size, DW_ATE_FLOAT)
+        }
+        Builtin::Bool => basic_type(env, "bool", 1, DW_ATE_BOOLEAN),
+        // the fixed-point representation, 10^18 times the actual value
+        Builtin::Decimal => basic_type(env, "dec", 16, DW_ATE_SIGNED),
+        Builtin::Str => {
+            let byte = ditype_from_layout(env, layout_interner, Layout::U8);
+
+            heap_collection_type(env, layout_interner, "Str", "bytes", byte)
+        }
+        Builtin::List(element_layout) => {
+            let element = ditype_from_layout(env, layout_interner, element_layout);
+
+            heap_collection_type(env, layout_interner, "List", "elements", element)
+        }
+    }
+}
+
+/// Str and List share a representation: a pointer to the elements, a length and a capacity.
+fn heap_collection_type<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    name: &str,
+    elements_name: &str,
+    element: DIType<'ctx>,
+) -> DIType<'ctx> {
+    let ptr_bytes = layout_interner.target().ptr_width() as u32;
+    let usize_type = ditype_from_layout(env, layout_interner, Layout::usize(env.target));
+
+    let members = [
+        member(
+            env,
+            elements_name,
+            ptr_bytes,
+            ptr_bytes,
+            0,
+            pointer_to(env, element),
+        ),
+        member(env, "length", ptr_bytes, ptr_bytes, ptr_bytes, usize_type),
+        member(
+            env,
+            "capacity_or_alloc_ptr",
+            ptr_bytes,
+            ptr_bytes,
+            2 * ptr_bytes,
+            usize_type,
+        ),
+    ];
+
+    struct_type(env, name, 3 * ptr_bytes, ptr_bytes, &members)
+}
+
+fn ditype_from_union_layout<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    union_layout: UnionLayout<'a>,
+) -> DIType<'ctx> {
+    // the tags are described as a C union, plus the tag id if it is stored next to the data
+    let tags_type = |tags: &[&'a [InLayout<'a>]]| {
+        let mut variants = Vec::with_capacity(tags.len());
+
+        for (tag_id, field_layouts) in tags.iter().enumerate() {
+            let repr = LayoutRepr::struct_(field_layouts);
+            let (size, alignment) = repr.stack_size_and_alignment(layout_interner);
+            let members = struct_members(env, layout_interner, field_layouts, |index| {
+                index.to_string()
+            });
+            let payload = struct_type(env, "Payload", size, alignment, &members);
+
+            variants.push(member(
+                env,
+                &tag_id.to_string(),
+                size,
+                alignment,
+                0,
+                payload,
+            ));
+        }
+
+        let (size, alignment) = Layout::stack_size_and_alignment_slices(layout_interner, tags);
+
+        union_type(env, "Tags", size, alignment, &variants)
+    };
+
+    let data = match union_layout {
+        UnionLayout::NonRecursive(tags)
+        | UnionLayout::Recursive(tags)
+        | UnionLayout::NullableWrapped {
+            other_tags: tags, ..
+        } => tags_type(tags),
+        UnionLayout::NonNullableUnwrapped(fields)
+        | UnionLayout::NullableUnwrapped {
+            other_fields: fields,
+            ..
+        } => {
+            let repr = LayoutRepr::struct_(fields);
+            let (size, alignment) = repr.stack_size_and_alignment(layout_interner);
+            let members = struct_members(env, layout_interner, fields, |index| index.to_string());
+
+            struct_type(env, "Payload", size, alignment, &members)
+        }
+    };
+
+    let (data_size, data_alignment) = union_layout.data_size_and_alignment(layout_interner);
+
+    let data = match union_layout.tag_id_offset(layout_interner) {
+        Some(tag_id_offset) if union_layout.stores_tag_id_as_data(env.target) => {
+            let tag_id_layout = union_layout.tag_id_layout();
+            let (tag_id_size, tag_id_alignment) =
+                layout_interner.stack_size_and_alignment(tag_id_layout);
+            let tag_id_type = ditype_from_layout(env, layout_interner, tag_id_layout);
+
+            let members = [
+                member(env, "data", tag_id_offset, data_alignment, 0, data),
+                member(
+                    env,
+                    "tag_id",
+                    tag_id_size,
+                    tag_id_alignment,
+                    tag_id_offset,
+                    tag_id_type,
+                ),
+            ];
+
+            struct_type(env, "TagUnion", data_size, data_alignment, &members)
+        }
+        _ => data,
+    };
+
+    if union_layout.is_recursive() {
+        // recursive unions are pointers to heap-allocated data
+        pointer_to(env, data)
+    } else {
+        data
+    }
+}
+
+/// Members for the fields of a struct, laid out the way LLVM lays out our struct types.
+fn struct_members<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &STLayoutInterner<'a>,
+    field_layouts: &[InLayout<'a>],
+    field_name: impl Fn(usize) -> String,
+) -> Vec<DIType<'ctx>> {
+    let mut offset = 0;
+    let mut members = Vec::with_capacity(field_layouts.len());
+
+    for (index, field_layout) in field_layouts.iter().enumerate() {
+        let (size, alignment) = layout_interner.stack_size_and_alignment(*field_layout);
+        let ditype = ditype_from_layout(env, layout_interner, *field_layout);
+
+        offset = round_up_to_alignment(offset, alignment);
+        members.push(member(
+            env,
+            &field_name(index),
+            size,
+            alignment,
+            offset,
+            ditype,
+        ));
+        offset += size;
+    }
+
+    members
+}
+
+fn scope<'ctx>(env: &Env<'_, 'ctx, '_>) -> DIScope<'ctx> {
+    env.compile_unit.as_debug_info_scope()
+}
+
+fn basic_type<'ctx>(env: &Env<'_, 'ctx, '_>, name: &str, size: u32, encoding: u32) -> DIType<'ctx> {
+    env.dibuilder
+        .create_basic_type(name, size as u64 * 8, encoding, DIFlags::PUBLIC)
+        .unwrap_or_else(|err| roc_error_macros::internal_error!("invalid basic type {name}: {err}"))
+        .as_type()
+}
+
+fn pointer_to<'ctx>(env: &Env<'_, 'ctx, '_>, pointee: DIType<'ctx>) -> DIType<'ctx> {
+    let ptr_bits = env.target.ptr_width() as u32 * 8;
+
+    env.dibuilder
+        .create_pointer_type(
+            "",
+            pointee,
+            ptr_bits as u64,
+            ptr_bits,
+            AddressSpace::default(),
+        )
+        .as_type()
+}
+
+fn member<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    name: &str,
+    size: u32,
+    alignment: u32,
+    offset: u32,
+    ditype: DIType<'ctx>,
+) -> DIType<'ctx> {
+    env.dibuilder
+        .create_member_type(
+            scope(env),
+            name,
+            env.compile_unit.get_file(),
+            /* line_no */ 0,
+            size as u64 * 8,
+            alignment * 8,
+            offset as u64 * 8,
+            DIFlags::PUBLIC,
+            ditype,
+        )
+        .as_type()
+}
+
+fn struct_type<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    name: &str,
+    size: u32,
+    alignment: u32,
+    members: &[DIType<'ctx>],
+) -> DIType<'ctx> {
+    env.dibuilder
+        .create_struct_type(
+            scope(env),
+            name,
+            env.compile_unit.get_file(),
+            /* line_number */ 0,
+            size as u64 * 8,
+            alignment * 8,
+            DIFlags::PUBLIC,
+            /* derived_from */ None,
+            members,
+            /* runtime_language */ 0,
+            /* vtable_holder */ None,
+            /* unique_id */ "",
+        )
+        .as_type()
+}
+
+fn union_type<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    name: &str,
+    size: u32,
+    alignment: u32,
+    members: &[DIType<'ctx>],
+) -> DIType<'ctx> {
+    env.dibuilder
+        .create_union_type(
+            scope(env),
+            name,
+            env.compile_unit.get_file(),
+            /* line_no */ 0,
+            size as u64 * 8,
+            alignment * 8,
+            DIFlags::PUBLIC,
+            members,
+            /* runtime_language */ 0,
+            /* unique_id */ "",
+        )
+        .as_type()
+}
@@ -6,15 +6,16 @@ use roc_collections::all::MutMap;
 use roc_error_macros::{internal_error, todo_lambda_erasure};
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, Symbol};
-use roc_mono::code_gen_help::{CodeGenHelp, HelperOp, REFCOUNT_MAX};
+use roc_mono::code_gen_help::{CodeGenHelp, ExpectBuffer, HelperOp, REFCOUNT_MAX};
 use roc_mono::ir::{
-    BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc,
-    Param, Proc, ProcLayout, Stmt,
+    BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal, LookupType,
+    ModifyRc, Param, Proc, ProcLayout, Stmt,
 };
 use roc_mono::layout::{
     Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
     TagIdIntType, UnionLayout,
 };
+use roc_region::all::Region;
 use roc_std::RocDec;
 
 use roc_wasm_module::linking::{DataSymbol, WasmObjectSymbol};
@@ -726,9 +727,28 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 _ => self.stmt_refcounting(modify, following),
             },
 
-            Stmt::Dbg { .. } => todo!("dbg is not implemented in the wasm backend"),
-            Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
-            Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the wasm backend"),
+            Stmt::Dbg {
+                source_location,
+                source,
+                symbol,
+                variable: _,
+                remainder,
+            } => self.stmt_dbg(source_location, source, *symbol, remainder),
+
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            }
+            | Stmt::ExpectFx {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => self.stmt_expect(*condition, *region, lookups, variables, remainder),
 
             Stmt::Crash(sym, tag) => self.stmt_crash(*sym, *tag),
         }
@@ -1015,23 +1035,134 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         self.stmt(following);
     }
 
-    pub fn stmt_internal_error(&mut self, msg: &'a str) {
-        let msg_sym = self.create_symbol("panic_str");
-        let msg_storage = self.storage.allocate_var(
+    fn stmt_dbg(
+        &mut self,
+        source_location: &'a str,
+        source: &'a str,
+        msg: Symbol,
+        following: &'a Stmt<'a>,
+    ) {
+        let location_sym = self.str_literal_symbol("dbg_location", source_location);
+        let source_sym = self.str_literal_symbol("dbg_source", source);
+
+        // The message was already rendered to a Str by `Inspect.toStr` during canonicalization.
+        // All three RocStrs are passed to the host by pointer: roc_dbg(loc, msg, src)
+        self.storage
+            .load_symbols(&mut self.code_builder, &[location_sym, msg, source_sym]);
+        self.call_host_fn_after_loading_args("roc_dbg");
+
+        self.stmt(following);
+    }
+
+    fn stmt_expect(
+        &mut self,
+        condition: Symbol,
+        region: Region,
+        lookups: &'a [Symbol],
+        variables: &'a [LookupType],
+        following: &'a Stmt<'a>,
+    ) {
+        // Skip over the failure report if the condition is true
+        self.start_block();
+        self.storage
+            .load_symbols(&mut self.code_builder, &[condition]);
+        self.code_builder.br_if(0);
+
+        let host_reports_expects = self
+            .host_lookup
+            .iter()
+            .any(|(fn_name, _)| *fn_name == "roc_expect_failed");
+
+        if host_reports_expects {
+            // Copy the looked-up values into the buffer the host passed to `set_shared_buffer`,
+            // in the same format as the LLVM backend, so the host can render them.
+            self.expr_expect_failed(condition, region, lookups, variables);
+
+            // Then tell the host where the failed expect is in the source code
+            // roc_expect_failed(region_start: u32, region_end: u32, module_id: u32)
+            let module_id = condition.module_id().to_u32();
+            self.code_builder.i32_const(region.start().offset as i32);
+            self.code_builder.i32_const(region.end().offset as i32);
+            self.code_builder.i32_const(module_id as i32);
+            self.call_host_fn_after_loading_args("roc_expect_failed");
+        } else {
+            // This host has no way to report a failed expectation, so crash instead
+            self.stmt_internal_error("An expectation failed!");
+        }
+
+        self.end_block();
+
+        self.stmt(following);
+    }
+
+    /// Call a helper procedure that writes a failed `expect` and its lookups to the shared buffer.
+    /// If this is the first call for these lookup Layouts, it will generate the IR for the procedure.
+    fn expr_expect_failed(
+        &mut self,
+        condition: Symbol,
+        region: Region,
+        lookups: &'a [Symbol],
+        variables: &'a [LookupType],
+    ) {
+        let lookup_layouts = Vec::from_iter_in(
+            lookups.iter().map(|sym| self.storage.symbol_layouts[sym]),
+            self.env.arena,
+        );
+
+        let ident_ids = self
+            .interns
+            .all_ident_ids
+            .get_mut(&self.env.module_id)
+            .unwrap();
+
+        let (expect_failed_expr, new_specializations) = self.helper_proc_gen.call_expect_failed(
+            ident_ids,
+            self.layout_interner,
+            condition,
+            region,
+            lookups,
+            lookup_layouts.into_bump_slice(),
+            variables,
+            ExpectBuffer::SharedBuffer,
+        );
+
+        for (spec_sym, spec_layout) in new_specializations.into_iter() {
+            self.register_helper_proc(spec_sym, spec_layout, ProcSource::Helper);
+        }
+
+        let ret_symbol = self.create_symbol("expect_failed");
+        self.stmt_let_store_expr(
+            ret_symbol,
+            Layout::UNIT,
+            self.env.arena.alloc(expect_failed_expr),
+            StoredVarKind::Variable,
+        );
+    }
+
+    /// Allocate a new Str variable on the stack and initialise it with a literal
+    fn str_literal_symbol(&mut self, debug_name: &str, string: &'a str) -> Symbol {
+        let sym = self.create_symbol(debug_name);
+        let storage = self.storage.allocate_var(
             self.layout_interner,
             Layout::STR,
-            msg_sym,
+            sym,
             StoredVarKind::Variable,
         );
 
-        // Store the message as a RocStr on the stack
-        let (local_id, offset) = match msg_storage {
+        let (local_id, offset) = match storage {
             StoredValue::StackMemory { location, .. } => {
                 location.local_and_offset(self.storage.stack_frame_pointer)
             }
             _ => internal_error!("String must always have stack memory"),
         };
-        self.expr_string_literal(msg, local_id, offset);
+        self.expr_string_literal(string, local_id, offset);
+
+        sym
+    }
+
+    pub fn stmt_internal_error(&mut self, msg: &'a str) {
+        // Store the message as a RocStr on the stack
+        let msg_sym = self.str_literal_symbol("panic_str", msg);
 
         self.stmt_crash(msg_sym, CrashTag::Roc);
     }
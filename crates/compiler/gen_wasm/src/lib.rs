@@ -92,11 +92,31 @@ pub fn build_app_module<'a, 'r>(
     host_module: WasmModule<'a>,
     procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
 ) -> (WasmModule<'a>, BitVec<usize>, u32) {
+    let (module, called_fns, exposed_fns) =
+        build_app_module_exposing_all(env, layout_interner, interns, host_module, procedures);
+
+    let main_function_index = exposed_fns
+        .last()
+        .map(|(_, fn_index)| *fn_index)
+        .expect("The app must expose at least one value to the host");
+
+    (module, called_fns, main_function_index)
+}
+
+/// Like [build_app_module], but returns the function index of every value exposed to the host,
+/// rather than just one `main`. Used by `roc test` to call each of the toplevel expects.
+pub fn build_app_module_exposing_all<'a, 'r>(
+    env: &'r Env<'a>,
+    layout_interner: &'r mut STLayoutInterner<'a>,
+    interns: &'r mut Interns,
+    host_module: WasmModule<'a>,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> (WasmModule<'a>, BitVec<usize>, Vec<'a, (Symbol, u32)>) {
     let mut layout_ids = LayoutIds::default();
     let mut procs = Vec::with_capacity_in(procedures.len(), env.arena);
     let mut proc_lookup = Vec::with_capacity_in(procedures.len() * 2, env.arena);
     let mut host_to_app_map = Vec::with_capacity_in(env.exposed_to_host.len(), env.arena);
-    let mut maybe_main_fn_index = None;
+    let mut exposed_fns = Vec::with_capacity_in(env.exposed_to_host.len(), env.arena);
 
     // Adjust Wasm function indices to account for functions from the object file
     let fn_index_offset: u32 =
@@ -108,7 +128,7 @@ pub fn build_app_module<'a, 'r>(
         let fn_index = fn_index_offset + i as u32;
         procs.push(proc);
         if env.exposed_to_host.contains(&sym) {
-            maybe_main_fn_index = Some(fn_index);
+            exposed_fns.push((sym, fn_index));
 
             let exposed_name = layout_ids
                 .get_toplevel(sym, &proc_layout)
@@ -184,10 +204,8 @@ pub fn build_app_module<'a, 'r>(
     }
 
     let (module, called_fns) = backend.finalize();
-    let main_function_index =
-        maybe_main_fn_index.expect("The app must expose at least one value to the host");
 
-    (module, called_fns, main_function_index)
+    (module, called_fns, exposed_fns)
 }
 
 pub struct CopyMemoryConfig {
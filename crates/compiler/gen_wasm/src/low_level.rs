@@ -10,6 +10,7 @@ use roc_mono::layout::{Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, Un
 use roc_mono::low_level::HigherOrder;
 
 use crate::backend::{ProcLookupData, ProcSource, WasmBackend};
+use crate::code_builder::CodeBuilder;
 use crate::layout::{StackMemoryFormat, WasmLayout};
 use crate::storage::{AddressValue, StackMemoryLocation, StoredValue};
 use crate::PTR_TYPE;
@@ -169,6 +170,231 @@ impl<'a> LowLevelCall<'a> {
         }
     }
 
+    fn ret_int_width(&self) -> IntWidth {
+        match self.ret_layout_raw {
+            LayoutRepr::Builtin(Builtin::Int(width)) => width,
+            _ => internal_error!(
+                "Invalid return layout for {:?}: {:?}",
+                self.lowlevel,
+                self.ret_layout
+            ),
+        }
+    }
+
+    fn ret_local_and_offset(&self, backend: &WasmBackend<'a, '_>) -> (LocalId, u32) {
+        match &self.ret_storage {
+            StoredValue::StackMemory { location, .. } => {
+                location.local_and_offset(backend.storage.stack_frame_pointer)
+            }
+            _ => internal_error!("{:?} should return a value in stack memory", self.lowlevel),
+        }
+    }
+
+    /// Load the low and high 64-bit halves of a 128-bit number into new locals.
+    /// Wasm has no 128-bit instructions, so simple operations are done on the halves.
+    fn load_num128_halves(backend: &mut WasmBackend<'a, '_>, symbol: Symbol) -> (LocalId, LocalId) {
+        let (local_id, offset) = match backend.storage.get(&symbol) {
+            StoredValue::StackMemory { location, .. } => {
+                location.local_and_offset(backend.storage.stack_frame_pointer)
+            }
+            _ => internal_error!("128-bit numbers should be in stack memory"),
+        };
+        let lo = backend.storage.create_anonymous_local(ValueType::I64);
+        let hi = backend.storage.create_anonymous_local(ValueType::I64);
+
+        backend.code_builder.get_local(local_id);
+        backend.code_builder.i64_load(Align::Bytes8, offset);
+        backend.code_builder.set_local(lo);
+        backend.code_builder.get_local(local_id);
+        backend.code_builder.i64_load(Align::Bytes8, offset + 8);
+        backend.code_builder.set_local(hi);
+
+        (lo, hi)
+    }
+
+    fn store_num128_halves(&self, backend: &mut WasmBackend<'a, '_>, lo: LocalId, hi: LocalId) {
+        let (ret_local, ret_offset) = self.ret_local_and_offset(backend);
+        backend.code_builder.get_local(ret_local);
+        backend.code_builder.get_local(lo);
+        backend.code_builder.i64_store(Align::Bytes8, ret_offset);
+        backend.code_builder.get_local(ret_local);
+        backend.code_builder.get_local(hi);
+        backend
+            .code_builder
+            .i64_store(Align::Bytes8, ret_offset + 8);
+    }
+
+    fn bitwise_num128(&self, backend: &mut WasmBackend<'a, '_>, op: fn(&mut CodeBuilder<'a>)) {
+        let (a_lo, a_hi) = Self::load_num128_halves(backend, self.arguments[0]);
+        let (b_lo, b_hi) = Self::load_num128_halves(backend, self.arguments[1]);
+        for (a, b) in [(a_lo, b_lo), (a_hi, b_hi)] {
+            backend.code_builder.get_local(a);
+            backend.code_builder.get_local(b);
+            op(&mut backend.code_builder);
+            backend.code_builder.set_local(a);
+        }
+        self.store_num128_halves(backend, a_lo, a_hi);
+    }
+
+    fn add_wrap_num128(&self, backend: &mut WasmBackend<'a, '_>) {
+        let (a_lo, a_hi) = Self::load_num128_halves(backend, self.arguments[0]);
+        let (b_lo, b_hi) = Self::load_num128_halves(backend, self.arguments[1]);
+        let lo = backend.storage.create_anonymous_local(ValueType::I64);
+        let hi = backend.storage.create_anonymous_local(ValueType::I64);
+
+        // lo = a_lo + b_lo
+        backend.code_builder.get_local(a_lo);
+        backend.code_builder.get_local(b_lo);
+        backend.code_builder.i64_add();
+        backend.code_builder.set_local(lo);
+
+        // hi = a_hi + b_hi + carry
+        backend.code_builder.get_local(a_hi);
+        backend.code_builder.get_local(b_hi);
+        backend.code_builder.i64_add();
+        backend.code_builder.get_local(lo);
+        backend.code_builder.get_local(a_lo);
+        backend.code_builder.i64_lt_u(); // the low half wrapped around
+        backend.code_builder.i64_extend_u_i32();
+        backend.code_builder.i64_add();
+        backend.code_builder.set_local(hi);
+
+        self.store_num128_halves(backend, lo, hi);
+    }
+
+    fn sub_wrap_num128(&self, backend: &mut WasmBackend<'a, '_>) {
+        let (a_lo, a_hi) = Self::load_num128_halves(backend, self.arguments[0]);
+        let (b_lo, b_hi) = Self::load_num128_halves(backend, self.arguments[1]);
+        let lo = backend.storage.create_anonymous_local(ValueType::I64);
+        let hi = backend.storage.create_anonymous_local(ValueType::I64);
+
+        // lo = a_lo - b_lo
+        backend.code_builder.get_local(a_lo);
+        backend.code_builder.get_local(b_lo);
+        backend.code_builder.i64_sub();
+        backend.code_builder.set_local(lo);
+
+        // hi = a_hi - b_hi - borrow
+        backend.code_builder.get_local(a_hi);
+        backend.code_builder.get_local(b_hi);
+        backend.code_builder.i64_sub();
+        backend.code_builder.get_local(a_lo);
+        backend.code_builder.get_local(b_lo);
+        backend.code_builder.i64_lt_u(); // the low half wrapped around
+        backend.code_builder.i64_extend_u_i32();
+        backend.code_builder.i64_sub();
+        backend.code_builder.set_local(hi);
+
+        self.store_num128_halves(backend, lo, hi);
+    }
+
+    /// Two's complement negation of a 128-bit number, returning new locals for the halves
+    fn negate_num128(
+        backend: &mut WasmBackend<'a, '_>,
+        lo: LocalId,
+        hi: LocalId,
+    ) -> (LocalId, LocalId) {
+        let neg_lo = backend.storage.create_anonymous_local(ValueType::I64);
+        let neg_hi = backend.storage.create_anonymous_local(ValueType::I64);
+
+        // neg_lo = 0 - lo
+        backend.code_builder.i64_const(0);
+        backend.code_builder.get_local(lo);
+        backend.code_builder.i64_sub();
+        backend.code_builder.set_local(neg_lo);
+
+        // neg_hi = 0 - hi - borrow
+        backend.code_builder.i64_const(0);
+        backend.code_builder.get_local(hi);
+        backend.code_builder.i64_sub();
+        backend.code_builder.get_local(lo);
+        backend.code_builder.i64_const(0);
+        backend.code_builder.i64_ne();
+        backend.code_builder.i64_extend_u_i32();
+        backend.code_builder.i64_sub();
+        backend.code_builder.set_local(neg_hi);
+
+        (neg_lo, neg_hi)
+    }
+
+    fn panic_if_num128_min(
+        backend: &mut WasmBackend<'a, '_>,
+        lo: LocalId,
+        hi: LocalId,
+        message: &'a str,
+    ) {
+        backend.code_builder.get_local(hi);
+        backend.code_builder.i64_const(i64::MIN);
+        backend.code_builder.i64_eq();
+        backend.code_builder.get_local(lo);
+        backend.code_builder.i64_eqz();
+        backend.code_builder.i32_and();
+        backend.code_builder.if_();
+        backend.stmt_internal_error(message);
+        backend.code_builder.end();
+    }
+
+    /// Load the first argument, converted to a float of the given width
+    fn load_arg_as_float(&self, backend: &mut WasmBackend<'a, '_>, float_width: FloatWidth) {
+        let arg = self.arguments[0];
+        let arg_layout = backend.storage.symbol_layouts[&arg];
+        backend
+            .storage
+            .load_symbols(&mut backend.code_builder, &[arg]);
+
+        match backend.layout_interner.get_repr(arg_layout) {
+            LayoutRepr::Builtin(Builtin::Int(width)) => {
+                let is_signed = width.is_signed();
+                match (float_width, width) {
+                    (FloatWidth::F32, IntWidth::I128 | IntWidth::U128) => backend
+                        .call_host_fn_after_loading_args(&bitcode::INT_TO_FLOAT_CAST_F32[width]),
+                    (FloatWidth::F64, IntWidth::I128 | IntWidth::U128) => backend
+                        .call_host_fn_after_loading_args(&bitcode::INT_TO_FLOAT_CAST_F64[width]),
+                    (FloatWidth::F32, IntWidth::I64 | IntWidth::U64) => {
+                        if is_signed {
+                            backend.code_builder.f32_convert_s_i64()
+                        } else {
+                            backend.code_builder.f32_convert_u_i64()
+                        }
+                    }
+                    (FloatWidth::F64, IntWidth::I64 | IntWidth::U64) => {
+                        if is_signed {
+                            backend.code_builder.f64_convert_s_i64()
+                        } else {
+                            backend.code_builder.f64_convert_u_i64()
+                        }
+                    }
+                    (FloatWidth::F32, _) => {
+                        if is_signed {
+                            backend.code_builder.f32_convert_s_i32()
+                        } else {
+                            backend.code_builder.f32_convert_u_i32()
+                        }
+                    }
+                    (FloatWidth::F64, _) => {
+                        if is_signed {
+                            backend.code_builder.f64_convert_s_i32()
+                        } else {
+                            backend.code_builder.f64_convert_u_i32()
+                        }
+                    }
+                }
+            }
+            LayoutRepr::Builtin(Builtin::Float(width)) => match (float_width, width) {
+                (FloatWidth::F32, FloatWidth::F64) => backend.code_builder.f32_demote_f64(),
+                (FloatWidth::F64, FloatWidth::F32) => backend.code_builder.f64_promote_f32(),
+                _ => {}
+            },
+            LayoutRepr::Builtin(Builtin::Decimal) => {
+                backend.call_host_fn_after_loading_args(bitcode::DEC_TO_F64);
+                if float_width == FloatWidth::F32 {
+                    backend.code_builder.f32_demote_f64();
+                }
+            }
+            x => internal_error!("{:?} is not defined for {:?}", self.lowlevel, x),
+        }
+    }
+
     ///  Main entrypoint from WasmBackend
     pub fn generate(&self, backend: &mut WasmBackend<'a, '_>) {
         use CodeGenNumType::*;
@@ -787,10 +1013,7 @@ impl<'a> LowLevelCall<'a> {
 
             NumAddWrap => match self.ret_layout_raw {
                 LayoutRepr::Builtin(Builtin::Int(width)) => match width {
-                    IntWidth::I128 | IntWidth::U128 => {
-                        // TODO: don't panic
-                        self.load_args_and_call_zig(backend, &bitcode::NUM_ADD_OR_PANIC_INT[width])
-                    }
+                    IntWidth::I128 | IntWidth::U128 => self.add_wrap_num128(backend),
                     IntWidth::I64 | IntWidth::U64 => {
                         self.load_args(backend);
                         backend.code_builder.i64_add()
@@ -878,10 +1101,7 @@ impl<'a> LowLevelCall<'a> {
 
             NumSubWrap => match self.ret_layout_raw {
                 LayoutRepr::Builtin(Builtin::Int(width)) => match width {
-                    IntWidth::I128 | IntWidth::U128 => {
-                        // TODO: don't panic
-                        self.load_args_and_call_zig(backend, &bitcode::NUM_SUB_OR_PANIC_INT[width])
-                    }
+                    IntWidth::I128 | IntWidth::U128 => self.sub_wrap_num128(backend),
                     IntWidth::I64 | IntWidth::U64 => {
                         self.load_args(backend);
                         backend.code_builder.i64_sub()
@@ -1058,12 +1278,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_GREATER_THAN[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_GREATER_THAN[IntWidth::I128],
                         );
                     }
@@ -1095,12 +1314,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_GREATER_THAN_OR_EQUAL[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_GREATER_THAN_OR_EQUAL[IntWidth::I128],
                         );
                     }
@@ -1132,12 +1350,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_LESS_THAN[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_LESS_THAN[IntWidth::I128],
                         );
                     }
@@ -1170,12 +1387,11 @@ impl<'a> LowLevelCall<'a> {
                             &bitcode::NUM_LESS_THAN_OR_EQUAL[IntWidth::U128]
                         };
 
-                        self.load_args_and_call_zig(backend, intrinsic);
+                        backend.call_host_fn_after_loading_args(intrinsic);
                     }
                     Decimal => {
                         // same as i128
-                        self.load_args_and_call_zig(
-                            backend,
+                        backend.call_host_fn_after_loading_args(
                             &bitcode::NUM_LESS_THAN_OR_EQUAL[IntWidth::I128],
                         );
                     }
@@ -1228,8 +1444,16 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_lt();
                         backend.code_builder.i32_add();
                     }
-                    I128 | Decimal => {
-                        self.load_args(backend);
+                    I128 => {
+                        let intrinsic = if is_signed {
+                            &bitcode::NUM_COMPARE[IntWidth::I128]
+                        } else {
+                            &bitcode::NUM_COMPARE[IntWidth::U128]
+                        };
+                        self.load_args_and_call_zig(backend, intrinsic);
+                    }
+                    Decimal => {
+                        // same as i128
                         self.load_args_and_call_zig(backend, &bitcode::NUM_COMPARE[IntWidth::I128]);
                     }
                 }
@@ -1239,8 +1463,8 @@ impl<'a> LowLevelCall<'a> {
                 match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
                     F32 => backend.code_builder.f32_div(),
                     F64 => backend.code_builder.f64_div(),
-                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_DIV),
-                    x => todo!("{:?} for {:?}", self.lowlevel, x),
+                    Decimal => backend.call_host_fn_after_loading_args(bitcode::DEC_DIV),
+                    x => internal_error!("NumDivFrac is not defined for {:?}", x),
                 }
             }
             NumDivTruncUnchecked => {
@@ -1261,7 +1485,10 @@ impl<'a> LowLevelCall<'a> {
                             backend.code_builder.i64_div_u()
                         }
                     }
-                    x => todo!("{:?} for {:?}", self.lowlevel, x),
+                    I128 => backend.call_host_fn_after_loading_args(
+                        &bitcode::NUM_DIV_TRUNC[self.ret_int_width()],
+                    ),
+                    x => internal_error!("NumDivTruncUnchecked is not defined for {:?}", x),
                 }
             }
             NumDivCeilUnchecked => match self.ret_layout_raw {
@@ -1273,10 +1500,25 @@ impl<'a> LowLevelCall<'a> {
 
             NumRemUnchecked => {
                 self.load_args(backend);
+                let is_signed = symbol_is_signed_int(backend, self.arguments[0]);
                 match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
-                    I32 => backend.code_builder.i32_rem_s(),
-                    I64 => backend.code_builder.i64_rem_s(),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    I32 => {
+                        if is_signed {
+                            backend.code_builder.i32_rem_s()
+                        } else {
+                            backend.code_builder.i32_rem_u()
+                        }
+                    }
+                    I64 => {
+                        if is_signed {
+                            backend.code_builder.i64_rem_s()
+                        } else {
+                            backend.code_builder.i64_rem_u()
+                        }
+                    }
+                    I128 => backend
+                        .call_host_fn_after_loading_args(&bitcode::NUM_REM[self.ret_int_width()]),
+                    x => internal_error!("NumRemUnchecked is not defined for {:?}", x),
                 }
             }
             NumIsMultipleOf => {
@@ -1375,6 +1617,14 @@ impl<'a> LowLevelCall<'a> {
                         code_builder.get_local(tmp);
                     }
 
+                    I128 => {
+                        let width = match backend.layout_interner.get_repr(layout) {
+                            LayoutRepr::Builtin(Builtin::Int(width)) => width,
+                            x => internal_error!("NumIsMultipleOf is not defined for {:?}", x),
+                        };
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_IS_MULTIPLE_OF[width])
+                    }
+
                     _ => panic_ret_type(),
                 }
             }
@@ -1382,10 +1632,9 @@ impl<'a> LowLevelCall<'a> {
                 const PANIC_MSG: &str =
                     "Integer absolute overflowed because its argument is the minimum value";
 
-                self.load_args(backend);
-
                 match CodeGenNumType::from(self.ret_layout) {
                     I32 => {
+                        self.load_args(backend);
                         if !layout_is_signed_int(self.ret_layout) {
                             return;
                         }
@@ -1412,6 +1661,7 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.select();
                     }
                     I64 => {
+                        self.load_args(backend);
                         if !layout_is_signed_int(self.ret_layout) {
                             return;
                         }
@@ -1437,18 +1687,51 @@ impl<'a> LowLevelCall<'a> {
                         // (x >= 0) ? x : -x
                         backend.code_builder.select();
                     }
-                    F32 => backend.code_builder.f32_abs(),
-                    F64 => backend.code_builder.f64_abs(),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    F32 => {
+                        self.load_args(backend);
+                        backend.code_builder.f32_abs()
+                    }
+                    F64 => {
+                        self.load_args(backend);
+                        backend.code_builder.f64_abs()
+                    }
+                    I128 => {
+                        let (lo, hi) = Self::load_num128_halves(backend, self.arguments[0]);
+                        if !layout_is_signed_int(self.ret_layout) {
+                            self.store_num128_halves(backend, lo, hi);
+                            return;
+                        }
+                        Self::panic_if_num128_min(backend, lo, hi, PANIC_MSG);
+
+                        let (neg_lo, neg_hi) = Self::negate_num128(backend, lo, hi);
+
+                        // x < 0
+                        let is_negative = backend.storage.create_anonymous_local(ValueType::I32);
+                        backend.code_builder.get_local(hi);
+                        backend.code_builder.i64_const(0);
+                        backend.code_builder.i64_lt_s();
+                        backend.code_builder.set_local(is_negative);
+
+                        // (x < 0) ? -x : x, one half at a time
+                        for (neg, pos) in [(neg_lo, lo), (neg_hi, hi)] {
+                            backend.code_builder.get_local(neg);
+                            backend.code_builder.get_local(pos);
+                            backend.code_builder.get_local(is_negative);
+                            backend.code_builder.select();
+                            backend.code_builder.set_local(pos);
+                        }
+                        self.store_num128_halves(backend, lo, hi);
+                    }
+                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_ABS),
                 }
             }
             NumNeg => {
                 const PANIC_MSG: &str =
                     "Integer negation overflowed because its argument is the minimum value";
 
-                self.load_args(backend);
                 match CodeGenNumType::from(self.ret_layout) {
                     I32 => {
+                        self.load_args(backend);
                         backend.code_builder.i32_const(i32::MIN);
                         backend.code_builder.i32_eq();
                         backend.code_builder.if_();
@@ -1460,6 +1743,7 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.i32_sub();
                     }
                     I64 => {
+                        self.load_args(backend);
                         backend.code_builder.i64_const(i64::MIN);
                         backend.code_builder.i64_eq();
                         backend.code_builder.if_();
@@ -1470,9 +1754,21 @@ impl<'a> LowLevelCall<'a> {
                         self.load_args(backend);
                         backend.code_builder.i64_sub();
                     }
-                    F32 => backend.code_builder.f32_neg(),
-                    F64 => backend.code_builder.f64_neg(),
-                    _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
+                    F32 => {
+                        self.load_args(backend);
+                        backend.code_builder.f32_neg()
+                    }
+                    F64 => {
+                        self.load_args(backend);
+                        backend.code_builder.f64_neg()
+                    }
+                    I128 => {
+                        let (lo, hi) = Self::load_num128_halves(backend, self.arguments[0]);
+                        Self::panic_if_num128_min(backend, lo, hi, PANIC_MSG);
+                        let (neg_lo, neg_hi) = Self::negate_num128(backend, lo, hi);
+                        self.store_num128_halves(backend, neg_lo, neg_hi);
+                    }
+                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_NEGATE),
                 }
             }
             NumSin => match self.ret_layout_raw {
@@ -1521,52 +1817,33 @@ impl<'a> LowLevelCall<'a> {
                 _ => panic_ret_type(),
             },
             NumToFrac => {
-                self.load_args(backend);
-                let ret_type = CodeGenNumType::from(self.ret_layout);
-                let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
-                let arg_is_signed = symbol_is_signed_int(backend, self.arguments[0]);
-                match (ret_type, arg_type) {
-                    (F32, I32) => backend.code_builder.f32_convert_s_i32(),
-                    (F32, I64) => backend.code_builder.f32_convert_s_i64(),
-                    (F32, F32) => {}
-                    (F32, F64) => backend.code_builder.f32_demote_f64(),
-
-                    (F64, I32) => backend.code_builder.f64_convert_s_i32(),
-                    (F64, I64) => backend.code_builder.f64_convert_s_i64(),
-                    (F64, F32) => backend.code_builder.f64_promote_f32(),
-                    (F64, F64) => {}
-
-                    (Decimal, I32) => {
-                        let int_width = match arg_is_signed {
-                            true => IntWidth::I32,
-                            false => IntWidth::U32,
-                        };
-
-                        self.load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[int_width]);
-                    }
-                    (Decimal, I64) => {
-                        let int_width = match arg_is_signed {
-                            true => IntWidth::I64,
-                            false => IntWidth::U64,
-                        };
-
-                        self.load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[int_width]);
-                    }
-                    (Decimal, F32) => {
-                        self.load_args_and_call_zig(
-                            backend,
-                            &bitcode::DEC_FROM_FLOAT[FloatWidth::F32],
-                        );
+                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
+                match self.ret_layout_raw {
+                    LayoutRepr::Builtin(Builtin::Float(width)) => {
+                        self.load_arg_as_float(backend, width)
                     }
-                    (Decimal, F64) => {
-                        self.load_args_and_call_zig(
-                            backend,
-                            &bitcode::DEC_FROM_FLOAT[FloatWidth::F64],
-                        );
+                    LayoutRepr::Builtin(Builtin::Decimal) => {
+                        match backend.layout_interner.get_repr(arg_layout) {
+                            LayoutRepr::Builtin(Builtin::Int(int_width)) => self
+                                .load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[int_width]),
+                            LayoutRepr::Builtin(Builtin::Float(float_width)) => self
+                                .load_args_and_call_zig(
+                                    backend,
+                                    &bitcode::DEC_FROM_FLOAT[float_width],
+                                ),
+                            LayoutRepr::Builtin(Builtin::Decimal) => {
+                                let (ret_local, ret_offset) = self.ret_local_and_offset(backend);
+                                backend.storage.copy_value_to_memory(
+                                    &mut backend.code_builder,
+                                    ret_local,
+                                    ret_offset,
+                                    self.arguments[0],
+                                );
+                            }
+                            x => internal_error!("NumToFrac is not defined for {:?}", x),
+                        }
                     }
-                    (Decimal, Decimal) => {}
-
-                    _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
+                    _ => panic_ret_type(),
                 }
             }
             NumPow => match self.ret_layout_raw {
@@ -1621,14 +1898,14 @@ impl<'a> LowLevelCall<'a> {
                 let width = match ret_type {
                     CodeGenNumType::I32 => IntWidth::I32,
                     CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
+                    CodeGenNumType::I128 => self.ret_int_width(),
                     _ => internal_error!("Invalid return type for round: {:?}", ret_type),
                 };
 
                 match arg_type {
-                    F32 => self.load_args_and_call_zig(backend, &bitcode::NUM_ROUND_F32[width]),
-                    F64 => self.load_args_and_call_zig(backend, &bitcode::NUM_ROUND_F64[width]),
-                    Decimal => self.load_args_and_call_zig(backend, &bitcode::DEC_ROUND[width]),
+                    F32 => backend.call_host_fn_after_loading_args(&bitcode::NUM_ROUND_F32[width]),
+                    F64 => backend.call_host_fn_after_loading_args(&bitcode::NUM_ROUND_F64[width]),
+                    Decimal => backend.call_host_fn_after_loading_args(&bitcode::DEC_ROUND[width]),
                     _ => internal_error!("Invalid argument type for round: {:?}", arg_type),
                 }
             }
@@ -1640,10 +1917,24 @@ impl<'a> LowLevelCall<'a> {
                 let width = match ret_type {
                     CodeGenNumType::I32 => IntWidth::I32,
                     CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
+                    CodeGenNumType::I128 => self.ret_int_width(),
                     _ => internal_error!("Invalid return type for round: {:?}", ret_type),
                 };
 
+                // Wasm has no instructions to truncate a float to a 128-bit integer
+                if ret_type == I128 {
+                    let intrinsic = match (arg_type, self.lowlevel) {
+                        (F32, NumCeiling) => &bitcode::NUM_CEILING_F32[width],
+                        (F64, NumCeiling) => &bitcode::NUM_CEILING_F64[width],
+                        (F32, NumFloor) => &bitcode::NUM_FLOOR_F32[width],
+                        (F64, NumFloor) => &bitcode::NUM_FLOOR_F64[width],
+                        (Decimal, NumCeiling) => &bitcode::DEC_CEILING[width],
+                        (Decimal, NumFloor) => &bitcode::DEC_FLOOR[width],
+                        _ => internal_error!("Invalid argument type for ceiling: {:?}", arg_type),
+                    };
+                    return backend.call_host_fn_after_loading_args(intrinsic);
+                }
+
                 match (arg_type, self.lowlevel) {
                     (F32, NumCeiling) => {
                         backend.code_builder.f32_ceil();
@@ -1652,7 +1943,8 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_ceil();
                     }
                     (Decimal, NumCeiling) => {
-                        return self.load_args_and_call_zig(backend, &bitcode::DEC_CEILING[width]);
+                        return backend
+                            .call_host_fn_after_loading_args(&bitcode::DEC_CEILING[width]);
                     }
                     (F32, NumFloor) => {
                         backend.code_builder.f32_floor();
@@ -1661,7 +1953,7 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_floor();
                     }
                     (Decimal, NumFloor) => {
-                        return self.load_args_and_call_zig(backend, &bitcode::DEC_FLOOR[width]);
+                        return backend.call_host_fn_after_loading_args(&bitcode::DEC_FLOOR[width]);
                     }
                     _ => internal_error!("Invalid argument type for ceiling: {:?}", arg_type),
                 }
@@ -1672,12 +1964,10 @@ impl<'a> LowLevelCall<'a> {
                     (I32, F64) => backend.code_builder.i32_trunc_s_f64(),
                     (I64, F32) => backend.code_builder.i64_trunc_s_f32(),
                     (I64, F64) => backend.code_builder.i64_trunc_s_f64(),
-                    (I128, _) => todo!("{:?} for I128", self.lowlevel),
                     _ => panic_ret_type(),
                 }
             }
             NumPowInt => {
-                self.load_args(backend);
                 let base_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
                 let exponent_type = CodeGenNumType::for_symbol(backend, self.arguments[1]);
                 let ret_type = CodeGenNumType::from(self.ret_layout);
@@ -1688,7 +1978,7 @@ impl<'a> LowLevelCall<'a> {
                 let width = match ret_type {
                     CodeGenNumType::I32 => IntWidth::I32,
                     CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
+                    CodeGenNumType::I128 => self.ret_int_width(),
                     _ => internal_error!("Invalid return type for pow: {:?}", ret_type),
                 };
 
@@ -1726,46 +2016,63 @@ impl<'a> LowLevelCall<'a> {
                 }
                 _ => panic_ret_type(),
             },
-            NumBitwiseAnd => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_and(),
-                    I64 => backend.code_builder.i64_and(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => panic_ret_type(),
+            NumBitwiseAnd => match CodeGenNumType::from(self.ret_layout) {
+                I32 => {
+                    self.load_args(backend);
+                    backend.code_builder.i32_and()
                 }
-            }
-            NumBitwiseXor => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_xor(),
-                    I64 => backend.code_builder.i64_xor(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => panic_ret_type(),
+                I64 => {
+                    self.load_args(backend);
+                    backend.code_builder.i64_and()
                 }
-            }
-            NumBitwiseOr => {
-                self.load_args(backend);
-                match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_or(),
-                    I64 => backend.code_builder.i64_or(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
-                    _ => panic_ret_type(),
+                I128 => self.bitwise_num128(backend, CodeBuilder::i64_and),
+                _ => panic_ret_type(),
+            },
+            NumBitwiseXor => match CodeGenNumType::from(self.ret_layout) {
+                I32 => {
+                    self.load_args(backend);
+                    backend.code_builder.i32_xor()
                 }
-            }
+                I64 => {
+                    self.load_args(backend);
+                    backend.code_builder.i64_xor()
+                }
+                I128 => self.bitwise_num128(backend, CodeBuilder::i64_xor),
+                _ => panic_ret_type(),
+            },
+            NumBitwiseOr => match CodeGenNumType::from(self.ret_layout) {
+                I32 => {
+                    self.load_args(backend);
+                    backend.code_builder.i32_or()
+                }
+                I64 => {
+                    self.load_args(backend);
+                    backend.code_builder.i64_or()
+                }
+                I128 => self.bitwise_num128(backend, CodeBuilder::i64_or),
+                _ => panic_ret_type(),
+            },
             NumShiftLeftBy => {
                 let num = self.arguments[0];
                 let bits = self.arguments[1];
-                backend
-                    .storage
-                    .load_symbols(&mut backend.code_builder, &[num, bits]);
                 match CodeGenNumType::from(self.ret_layout) {
-                    I32 => backend.code_builder.i32_shl(),
+                    I32 => {
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[num, bits]);
+                        backend.code_builder.i32_shl();
+                    }
                     I64 => {
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[num, bits]);
                         backend.code_builder.i64_extend_u_i32();
                         backend.code_builder.i64_shl();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => self.load_args_and_call_zig(
+                        backend,
+                        &bitcode::NUM_SHIFT_LEFT_BY[self.ret_int_width()],
+                    ),
                     _ => panic_ret_type(),
                 }
             }
@@ -1812,7 +2119,10 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.i64_extend_u_i32();
                         backend.code_builder.i64_shr_s();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => self.load_args_and_call_zig(
+                        backend,
+                        &bitcode::NUM_SHIFT_RIGHT_BY[self.ret_int_width()],
+                    ),
                     _ => panic_ret_type(),
                 }
             }
@@ -1853,7 +2163,10 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.i64_extend_u_i32();
                         backend.code_builder.i64_shr_u();
                     }
-                    I128 => self.load_args_and_call_zig(backend, "__lshrti3"), // from compiler_rt
+                    I128 => self.load_args_and_call_zig(
+                        backend,
+                        &bitcode::NUM_SHIFT_RIGHT_ZERO_FILL[self.ret_int_width()],
+                    ),
                     _ => panic_ret_type(),
                 }
             }
@@ -1883,8 +2196,15 @@ impl<'a> LowLevelCall<'a> {
                         self.wrap_small_int(backend, ret_width);
                     }
                     (I32, I128) => {
-                        self.load_args(backend);
-                        backend.code_builder.i32_load(Align::Bytes4, 0);
+                        let (frame_ptr, offset) = match backend.storage.get(&self.arguments[0]) {
+                            StoredValue::StackMemory { location, .. } => {
+                                location.local_and_offset(backend.storage.stack_frame_pointer)
+                            }
+                            _ => internal_error!("I128 should be in stack memory"),
+                        };
+                        backend.code_builder.get_local(frame_ptr);
+                        backend.code_builder.i32_load(Align::Bytes4, offset);
+                        self.wrap_small_int(backend, ret_width);
                     }
                     (I64, I32) => {
                         self.load_args(backend);
@@ -1907,73 +2227,52 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.get_local(frame_ptr);
                         backend.code_builder.i64_load(Align::Bytes8, offset);
                     }
-                    (I128, I64) => {
-                        // Symbols are loaded as if for a call, so the i128 "return address" and i64 value are on the value stack
-                        self.load_args(backend);
-                        backend.code_builder.i64_store(Align::Bytes8, 0);
-
-                        // Zero the most significant 64 bits
-                        let (frame_ptr, offset) = match &self.ret_storage {
-                            StoredValue::StackMemory { location, .. } => {
-                                location.local_and_offset(backend.storage.stack_frame_pointer)
+                    (I128, I32) | (I128, I64) => {
+                        let lo = backend.storage.create_anonymous_local(ValueType::I64);
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[self.arguments[0]]);
+                        if arg_type == I32 {
+                            if arg_width.is_signed() {
+                                backend.code_builder.i64_extend_s_i32()
+                            } else {
+                                backend.code_builder.i64_extend_u_i32()
                             }
-                            _ => internal_error!("I128 should be in stack memory"),
-                        };
-                        backend.code_builder.get_local(frame_ptr);
-                        backend.code_builder.i64_const(0);
-                        backend.code_builder.i64_store(Align::Bytes8, offset + 8);
-                    }
-
-                    _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
-                }
-            }
-            NumToFloatCast => {
-                self.load_args(backend);
-                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
-                let arg_signed = match backend.layout_interner.get_repr(arg_layout) {
-                    LayoutRepr::Builtin(Builtin::Int(w)) => w.is_signed(),
-                    LayoutRepr::Builtin(Builtin::Float(_)) => true, // unused
-                    LayoutRepr::Builtin(Builtin::Decimal) => true,
-                    x => internal_error!("Num.intCast is not defined for {:?}", x),
-                };
-                let ret_type = CodeGenNumType::from(self.ret_layout);
-                let arg_type = CodeGenNumType::from(arg_layout);
-
-                match (ret_type, arg_type) {
-                    (F32, F32) => {}
-                    (F32, F64) => backend.code_builder.f32_demote_f64(),
-                    (F32, I32) => {
-                        if arg_signed {
-                            backend.code_builder.f32_convert_s_i32()
-                        } else {
-                            backend.code_builder.f32_convert_u_i32()
                         }
-                    }
-                    (F32, I64) => {
-                        if arg_signed {
-                            backend.code_builder.f32_convert_s_i64()
-                        } else {
-                            backend.code_builder.f32_convert_u_i64()
-                        }
-                    }
-                    (F64, F64) => {}
-                    (F64, I32) => {
-                        if arg_signed {
-                            backend.code_builder.f64_convert_s_i32()
+                        backend.code_builder.set_local(lo);
+
+                        // The most significant 64 bits are all copies of the sign bit, or zero
+                        let hi = backend.storage.create_anonymous_local(ValueType::I64);
+                        if arg_width.is_signed() {
+                            backend.code_builder.get_local(lo);
+                            backend.code_builder.i64_const(63);
+                            backend.code_builder.i64_shr_s();
                         } else {
-                            backend.code_builder.f64_convert_u_i32()
+                            backend.code_builder.i64_const(0);
                         }
+                        backend.code_builder.set_local(hi);
+
+                        self.store_num128_halves(backend, lo, hi);
                     }
-                    (F64, I64) => {
-                        if arg_signed {
-                            backend.code_builder.f64_convert_s_i64()
-                        } else {
-                            backend.code_builder.f64_convert_u_i64()
-                        }
+                    (I128, I128) => {
+                        let (ret_local, ret_offset) = self.ret_local_and_offset(backend);
+                        backend.storage.copy_value_to_memory(
+                            &mut backend.code_builder,
+                            ret_local,
+                            ret_offset,
+                            self.arguments[0],
+                        );
                     }
-                    _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
+
+                    _ => internal_error!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
+            NumToFloatCast => match self.ret_layout_raw {
+                LayoutRepr::Builtin(Builtin::Float(width)) => {
+                    self.load_arg_as_float(backend, width)
+                }
+                _ => panic_ret_type(),
+            },
             NumToIntChecked => {
                 let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
 
@@ -2016,7 +2315,58 @@ impl<'a> LowLevelCall<'a> {
                 }
             }
             NumToFloatChecked => {
-                todo!("implement toF32Checked and toF64Checked");
+                // The return value is a struct { converted_val: F, out_of_bounds: bool }
+                let width = match self.ret_layout_raw {
+                    LayoutRepr::Struct(&[value_layout, ..]) => {
+                        match backend.layout_interner.get_repr(value_layout) {
+                            LayoutRepr::Builtin(Builtin::Float(width)) => width,
+                            _ => internal_error!(
+                                "NumToFloatChecked is not defined for {:?}",
+                                self.ret_layout
+                            ),
+                        }
+                    }
+                    _ => internal_error!(
+                        "NumToFloatChecked is not defined for {:?}",
+                        self.ret_layout
+                    ),
+                };
+                let (ret_local, ret_offset) = self.ret_local_and_offset(backend);
+
+                let value_type = match width {
+                    FloatWidth::F32 => ValueType::F32,
+                    FloatWidth::F64 => ValueType::F64,
+                };
+                let value = backend.storage.create_anonymous_local(value_type);
+                self.load_arg_as_float(backend, width);
+                backend.code_builder.set_local(value);
+                backend.code_builder.get_local(ret_local);
+                backend.code_builder.get_local(value);
+                match width {
+                    FloatWidth::F32 => backend.code_builder.f32_store(Align::Bytes4, ret_offset),
+                    FloatWidth::F64 => backend.code_builder.f64_store(Align::Bytes8, ret_offset),
+                }
+
+                // A finite number that converts to infinity is out of bounds for the float type
+                backend.code_builder.get_local(ret_local);
+                backend.code_builder.get_local(value);
+                match width {
+                    FloatWidth::F32 => {
+                        backend.code_builder.f32_abs();
+                        backend.code_builder.f32_const(f32::INFINITY);
+                        backend.code_builder.f32_eq();
+                    }
+                    FloatWidth::F64 => {
+                        backend.code_builder.f64_abs();
+                        backend.code_builder.f64_const(f64::INFINITY);
+                        backend.code_builder.f64_eq();
+                    }
+                }
+                num_is_finite(backend, self.arguments[0]);
+                backend.code_builder.i32_and();
+                backend
+                    .code_builder
+                    .i32_store8(Align::Bytes1, ret_offset + width.stack_size());
             }
             NumWithoutDecimalPoint => self.load_args_and_call_zig(backend, bitcode::DEC_TO_I128),
             NumWithDecimalPoint => self.load_args_and_call_zig(backend, bitcode::DEC_FROM_I128),
@@ -2084,7 +2434,10 @@ impl<'a> LowLevelCall<'a> {
                 backend.code_builder.i32_and();
             }
 
-            Hash => todo!("{:?}", self.lowlevel),
+            Hash => unreachable!(
+                "The {:?} lowlevel is never generated. Hashing is implemented by the Hash ability.",
+                self.lowlevel
+            ),
 
             Eq | NotEq => self.eq_or_neq(backend),
 
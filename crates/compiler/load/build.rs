@@ -99,6 +99,7 @@ fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path:
     let problems = report_problems(
         &module.sources,
         &module.interns,
+        &mut module.syntax_problems,
         &mut module.can_problems,
         &mut module.type_problems,
     );
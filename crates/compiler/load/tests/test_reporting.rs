@@ -154,6 +154,7 @@ mod test_reporting {
     ) -> Result<
         (
             String,
+            Vec<String>,
             Vec<TypeError>,
             Vec<roc_problem::can::Problem>,
             ModuleId,
@@ -164,16 +165,26 @@ mod test_reporting {
         let (module_src, result) = run_load_and_infer(subdir, arena, expr_src);
         let LoadedModule {
             module_id: home,
+            mut syntax_problems,
             mut can_problems,
             mut type_problems,
             interns,
             ..
         } = result?;
 
+        // Syntax errors are rendered as soon as they are recovered from
+        let syntax_problems = syntax_problems.remove(&home).unwrap_or_default();
         let can_problems = can_problems.remove(&home).unwrap_or_default();
         let type_problems = type_problems.remove(&home).unwrap_or_default();
 
-        Ok((module_src, type_problems, can_problems, home, interns))
+        Ok((
+            module_src,
+            syntax_problems,
+            type_problems,
+            can_problems,
+            home,
+            interns,
+        ))
     }
 
     fn list_reports_new<F>(subdir: &str, arena: &Bump, src: &str, finalize_render: F) -> String
@@ -188,7 +199,7 @@ mod test_reporting {
 
         match infer_expr_help_new(subdir, arena, src) {
             Err(LoadingProblem::FormattedReport(fail)) => fail,
-            Ok((module_src, type_problems, can_problems, home, interns)) => {
+            Ok((module_src, syntax_problems, type_problems, can_problems, home, interns)) => {
                 let lines = LineInfo::new(&module_src);
                 let src_lines: Vec<&str> = module_src.split('\n').collect();
                 let mut reports = Vec::new();
@@ -210,6 +221,13 @@ mod test_reporting {
 
                 let has_reports = !reports.is_empty();
 
+                // Syntax errors are already rendered, so they go before the rest
+                buf.push_str(&syntax_problems.join("\n\n"));
+
+                if has_reports && !syntax_problems.is_empty() {
+                    buf.push_str("\n\n");
+                }
+
                 let doc = alloc
                     .stack(reports.into_iter().map(|v| v.pretty(&alloc)))
                     .append(if has_reports {
@@ -5424,6 +5442,19 @@ mod test_reporting {
 
     6│       2 -> 2
              ^
+
+    ── UNSAFE PATTERN in /code/proj/Main.roc ───────────────────────────────────────
+
+    This `when` does not cover all the possibilities:
+
+    4│>      when 4 is
+    5│>          5 -> 2
+
+    Other possibilities include:
+
+        _
+
+    I would have to crash if I saw one of those! Add branches for them!
     "
     );
 
@@ -11441,6 +11472,23 @@ In roc, functions are always written as a lambda, like{}
     "###
     );
 
+    test_no_problem!(
+        derive_decoding_for_tag_union,
+        indoc!(
+            r#"
+             app "test" imports [] provides [main] to "./platform"
+
+             import Decode exposing [decoder]
+
+             main =
+                 myDecoder : Decoder [A Str, B U8 U16, C] fmt where fmt implements DecoderFormatting
+                 myDecoder = decoder
+
+                 myDecoder
+             "#
+        )
+    );
+
     test_report!(
         uninhabited_type_is_trivially_exhaustive,
         indoc!(
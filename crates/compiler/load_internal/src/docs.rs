@@ -7,6 +7,7 @@ use roc_module::symbol::{IdentIds, ModuleId, ModuleIds, Symbol};
 use roc_parse::ast::AssignedField;
 use roc_parse::ast::{self, ExtractSpaces, TypeHeader};
 use roc_parse::ast::{CommentOrNewline, TypeDef, ValueDef};
+use roc_region::all::Region;
 
 // Documentation generation requirements
 
@@ -43,6 +44,8 @@ pub struct DocDef {
     pub type_vars: Vec<String>,
     pub type_annotation: TypeAnnotation,
     pub docs: Option<String>,
+    /// Where this def is in its module's source code
+    pub region: Region,
 }
 
 #[derive(Debug, Clone)]
@@ -113,6 +116,8 @@ pub struct AbilityMember {
     pub type_annotation: TypeAnnotation,
     pub able_variables: Vec<(String, Vec<TypeAnnotation>)>,
     pub docs: Option<String>,
+    /// Where this member is in its module's source code
+    pub region: Region,
 }
 
 #[derive(Debug, Clone)]
@@ -208,6 +213,7 @@ fn generate_entry_docs(
         scratchpad.extend(spaces_before);
 
         let docs = comments_or_new_lines_to_docs(&scratchpad);
+        let region = defs.regions[index];
 
         match either_index.split() {
             Err(value_index) => match &defs.value_defs[value_index.index()] {
@@ -222,6 +228,7 @@ fn generate_entry_docs(
                                 type_annotation: type_to_docs(false, loc_ann.value),
                                 type_vars: Vec::new(),
                                 docs,
+                                region,
                             };
                             doc_entries.push(DocEntry::DocDef(doc_def));
                         }
@@ -242,6 +249,7 @@ fn generate_entry_docs(
                                 type_vars: Vec::new(),
                                 symbol: Symbol::new(home, ident_id),
                                 docs,
+                                region,
                             };
                             doc_entries.push(DocEntry::DocDef(doc_def));
                         }
@@ -258,6 +266,7 @@ fn generate_entry_docs(
                                 type_vars: Vec::new(),
                                 symbol: Symbol::new(home, ident_id),
                                 docs,
+                                region,
                             };
                             doc_entries.push(DocEntry::DocDef(doc_def));
                         }
@@ -295,6 +304,7 @@ fn generate_entry_docs(
                                 type_vars: Vec::new(),
                                 symbol: Symbol::new(home, ident_id),
                                 docs,
+                                region,
                             };
                             doc_entries.push(DocEntry::DocDef(doc_def));
                         }
@@ -333,6 +343,7 @@ fn generate_entry_docs(
                         type_vars,
                         docs,
                         symbol: Symbol::new(home, ident_id),
+                        region,
                     };
                     doc_entries.push(DocEntry::DocDef(doc_def));
                 }
@@ -356,6 +367,7 @@ fn generate_entry_docs(
                         type_vars,
                         docs,
                         symbol: Symbol::new(home, ident_id),
+                        region,
                     };
                     doc_entries.push(DocEntry::DocDef(doc_def));
                 }
@@ -385,6 +397,7 @@ fn generate_entry_docs(
                                 type_annotation,
                                 able_variables,
                                 docs: comments_or_new_lines_to_docs(extracted.before),
+                                region: Region::span_across(&mem.name.region, &mem.typ.region),
                             }
                         })
                         .collect();
@@ -396,6 +409,7 @@ fn generate_entry_docs(
                         symbol: Symbol::new(home, ident_id),
                         type_vars,
                         docs,
+                        region,
                     };
                     doc_entries.push(DocEntry::DocDef(doc_def));
                 }
@@ -52,7 +52,7 @@ use roc_parse::header::{
     self, AppHeader, ExposedName, HeaderType, ImportsKeywordItem, PackageEntry, PackageHeader,
     PlatformHeader, To, TypedIdent,
 };
-use roc_parse::module::parse_module_defs;
+use roc_parse::module::{parse_module_defs, parse_module_defs_recovering};
 use roc_parse::parser::{FileError, SourceError, SyntaxError};
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Loc, Region};
@@ -138,6 +138,13 @@ impl ExecutionMode {
     fn build_if_checks(&self) -> bool {
         matches!(self, Self::ExecutableIfCheck | Self::Test)
     }
+
+    /// Whether to report all the syntax errors in a module and check the rest of it, rather than
+    /// stopping at the first one. Nothing gets built from a module with syntax errors, so this is
+    /// only done when checking.
+    fn recovers_from_syntax_errors(&self) -> bool {
+        matches!(self, Self::Check)
+    }
 }
 
 type SharedIdentIdsByModule = Arc<Mutex<roc_module::symbol::IdentIdsByModule>>;
@@ -201,6 +208,7 @@ fn start_phase<'a>(
                     module_ids: Arc::clone(&state.arc_modules),
                     ident_ids_by_module: Arc::clone(&state.ident_ids_by_module),
                     root_type: state.root_type.clone(),
+                    exec_mode: state.exec_mode,
                 }
             }
             Phase::CanonicalizeAndConstrain => {
@@ -613,6 +621,7 @@ enum Msg<'a> {
         procs_base: ProcsBase<'a>,
         procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
         host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+        symbol_regions: MutMap<Symbol, Region>,
         update_mode_ids: UpdateModeIds,
         module_timing: ModuleTiming,
         subs: Subs,
@@ -710,6 +719,7 @@ struct State<'a> {
     pub dependencies: Dependencies<'a>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+    pub symbol_regions: MutMap<Symbol, Region>,
     pub toplevel_expects: MutMap<ModuleId, ToplevelExpects>,
     pub exposed_to_host: ExposedToHost,
 
@@ -794,6 +804,7 @@ impl<'a> State<'a> {
             dependencies,
             procedures: MutMap::default(),
             host_exposed_lambda_sets: std::vec::Vec::new(),
+            symbol_regions: MutMap::default(),
             toplevel_expects: MutMap::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_modules: &[],
@@ -879,6 +890,7 @@ enum BuildTask<'a> {
         module_ids: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
         root_type: RootType,
+        exec_mode: ExecutionMode,
     },
     CanonicalizeAndConstrain {
         parsed: ParsedModule<'a>,
@@ -2370,6 +2382,28 @@ fn update<'a>(
                 .sources
                 .insert(parsed.module_id, (parsed.module_path.clone(), parsed.src));
 
+            if !parsed.syntax_errors.is_empty() {
+                let module_ids = (*state.arc_modules).lock().clone().into_module_ids();
+
+                let reports = std::mem::take(&mut parsed.syntax_errors)
+                    .into_iter()
+                    .map(|problem| {
+                        to_parse_problem_report(
+                            problem,
+                            module_ids.clone(),
+                            IdentIds::exposed_builtins(0),
+                            state.render,
+                            state.palette,
+                        )
+                    })
+                    .collect();
+
+                state
+                    .module_cache
+                    .syntax_problems
+                    .insert(module_id, reports);
+            }
+
             state.module_cache.parsed.insert(module_id, parsed);
 
             let work = state.dependencies.notify(module_id, Phase::Parse);
@@ -2686,6 +2720,7 @@ fn update<'a>(
             procs_base,
             procedures,
             host_exposed_lambda_sets,
+            symbol_regions,
             external_specializations_requested,
             module_timing,
             layout_cache,
@@ -2706,6 +2741,7 @@ fn update<'a>(
             state
                 .host_exposed_lambda_sets
                 .extend(host_exposed_lambda_sets);
+            state.symbol_regions.extend(symbol_regions);
             state.module_cache.late_specializations.insert(
                 module_id,
                 LateSpecializationsModule {
@@ -3205,6 +3241,7 @@ fn finish_specialization<'a>(
         toplevel_expects,
         procedures,
         host_exposed_lambda_sets,
+        symbol_regions,
         module_cache,
         platform_data,
         ..
@@ -3241,6 +3278,7 @@ fn finish_specialization<'a>(
         layout_interner,
         procedures,
         host_exposed_lambda_sets,
+        symbol_regions,
         entry_point,
         sources,
         timings: state.timings,
@@ -3321,6 +3359,7 @@ fn finish(
         filename: state.root_path,
         interns,
         solved,
+        syntax_problems: state.module_cache.syntax_problems,
         can_problems: state.module_cache.can_problems,
         type_problems: state.module_cache.type_problems,
         declarations_by_id,
@@ -4039,6 +4078,7 @@ fn load_packages<'a>(
     for Loc { value: entry, .. } in packages.iter() {
         let PackageEntry {
             shorthand,
+            platform_marker,
             package_name:
                 Loc {
                     value: package_name,
@@ -4049,6 +4089,11 @@ fn load_packages<'a>(
 
         let src = package_name.to_str();
 
+        // Only the app's own platform requires values from the app. Any other platform is
+        // loaded like a package, for the sake of its exposed modules (e.g. in the REPL), since
+        // the app doesn't provide what it requires.
+        let app_module_id = app_module_id.filter(|_| platform_marker.is_some());
+
         // find the `package` or `platform` module on disk,
         // downloading it into a cache dir first if necessary.
         let root_module_path = if src.starts_with("https://") {
@@ -5112,6 +5157,7 @@ fn parse<'a>(
     module_ids: Arc<Mutex<PackageModuleIds<'a>>>,
     ident_ids_by_module: SharedIdentIdsByModule,
     root_type: RootType,
+    exec_mode: ExecutionMode,
 ) -> Result<Msg<'a>, LoadingProblem<'a>> {
     let mut module_timing = header.module_timing;
     let parse_start = Instant::now();
@@ -5121,12 +5167,27 @@ fn parse<'a>(
     let header_import_defs =
         roc_parse::ast::Module::header_imports_to_defs(arena, header.header_imports);
 
-    let parsed_defs = match parse_module_defs(arena, parse_state.clone(), header_import_defs) {
-        Ok(success) => success,
-        Err(fail) => {
-            return Err(LoadingProblem::ParsingFailed(
-                fail.into_file_error(header.module_path, &parse_state),
-            ));
+    let (parsed_defs, syntax_errors) = if exec_mode.recovers_from_syntax_errors() {
+        let (parsed_defs, syntax_errors) =
+            parse_module_defs_recovering(arena, parse_state.clone(), header_import_defs);
+
+        let syntax_errors = syntax_errors
+            .into_iter()
+            .map(|fail| {
+                fail.value
+                    .into_file_error(header.module_path.clone(), &parse_state)
+            })
+            .collect();
+
+        (parsed_defs, syntax_errors)
+    } else {
+        match parse_module_defs(arena, parse_state.clone(), header_import_defs) {
+            Ok(success) => (success, Vec::new()),
+            Err(fail) => {
+                return Err(LoadingProblem::ParsingFailed(
+                    fail.into_file_error(header.module_path, &parse_state),
+                ));
+            }
         }
     };
 
@@ -5399,6 +5460,7 @@ fn parse<'a>(
         initial_scope: scope,
         exposes: exposed,
         parsed_defs,
+        syntax_errors,
         symbols_from_requires,
         header_type,
         header_comments: header_docs,
@@ -5440,6 +5502,7 @@ fn make_specializations<'a>(
         exposed_by_module,
         derived_module: &derived_module,
         struct_indexing: UsageTrackingMap::default(),
+        symbol_regions: MutMap::default(),
     };
 
     let mut procs = Procs::new_in(arena);
@@ -5474,6 +5537,8 @@ fn make_specializations<'a>(
     // Turn `Bytes.Decode.IdentId(238)` into `Bytes.Decode.238`, we rely on this in mono tests
     mono_env.home.register_debug_idents(mono_env.ident_ids);
 
+    let symbol_regions = mono_env.symbol_regions;
+
     let make_specializations_end = Instant::now();
     module_timing
         .make_specializations
@@ -5486,6 +5551,7 @@ fn make_specializations<'a>(
         procs_base: restored_procs_base,
         procedures,
         host_exposed_lambda_sets,
+        symbol_regions,
         update_mode_ids,
         subs,
         expectations,
@@ -5543,6 +5609,7 @@ fn build_pending_specializations<'a>(
         exposed_by_module,
         derived_module: &derived_module,
         struct_indexing: UsageTrackingMap::default(),
+        symbol_regions: MutMap::default(),
     };
 
     let layout_cache_snapshot = layout_cache.snapshot();
@@ -5634,6 +5701,7 @@ fn build_pending_specializations<'a>(
                             body_var: expr_var,
                             // This is a 0-arity thunk, so it cannot be recursive
                             is_self_recursive: false,
+                            region: body.region,
                         };
 
                         procs_base.partial_procs.insert(symbol, proc);
@@ -5770,6 +5838,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 procs_base.partial_procs.insert(symbol, proc);
@@ -5838,6 +5907,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -5910,6 +5980,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -6024,6 +6095,7 @@ fn load_derived_partial_procs<'a>(
             exposed_by_module,
             derived_module,
             struct_indexing: UsageTrackingMap::default(),
+            symbol_regions: MutMap::default(),
         };
 
         let partial_proc = match derived_expr {
@@ -6061,6 +6133,7 @@ fn load_derived_partial_procs<'a>(
                     body_var: derived_expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: Region::zero(),
                 }
             }
         };
@@ -6113,6 +6186,7 @@ fn run_task<'a>(
             module_ids,
             ident_ids_by_module,
             root_type,
+            exec_mode,
         } => parse(
             arena,
             header,
@@ -6120,6 +6194,7 @@ fn run_task<'a>(
             module_ids,
             ident_ids_by_module,
             root_type,
+            exec_mode,
         ),
         CanonicalizeAndConstrain {
             parsed,
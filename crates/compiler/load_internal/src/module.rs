@@ -15,6 +15,7 @@ use roc_mono::ir::{GlueLayouts, HostExposedLambdaSets, LambdaSetId, Proc, ProcLa
 use roc_mono::layout::{LayoutCache, STLayoutInterner};
 use roc_parse::ast::{CommentOrNewline, Defs, TypeAnnotation};
 use roc_parse::header::{HeaderType, PackageName};
+use roc_parse::parser::{FileError, SyntaxError};
 use roc_region::all::{Loc, Region};
 use roc_solve::module::Solved;
 use roc_solve_problem::TypeError;
@@ -33,6 +34,8 @@ pub struct LoadedModule {
     pub filename: PathBuf,
     pub interns: Interns,
     pub solved: Solved<Subs>,
+    /// Reports of the syntax errors that were recovered from, already rendered.
+    pub syntax_problems: MutMap<ModuleId, Vec<String>>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub declarations_by_id: MutMap<ModuleId, Declarations>,
@@ -65,6 +68,10 @@ impl LoadedModule {
     pub fn total_problems(&self) -> usize {
         let mut total = 0;
 
+        for problems in self.syntax_problems.values() {
+            total += problems.len();
+        }
+
         for problems in self.can_problems.values() {
             total += problems.len();
         }
@@ -180,6 +187,8 @@ pub struct MonomorphizedModule<'a> {
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
     pub host_exposed_lambda_sets: HostExposedLambdaSets<'a>,
+    /// Where the values bound by `let`s in `procedures` were defined
+    pub symbol_regions: MutMap<Symbol, Region>,
     pub toplevel_expects: MutMap<ModuleId, ToplevelExpects>,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
@@ -199,6 +208,8 @@ pub struct ParsedModule<'a> {
     pub deps_by_name: MutMap<PQModuleName<'a>, ModuleId>,
     pub exposed_ident_ids: IdentIds,
     pub parsed_defs: Defs<'a>,
+    /// The syntax errors in the defs that were skipped over, if it was parsed with recovery.
+    pub syntax_errors: Vec<FileError<'a, SyntaxError<'a>>>,
     pub symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     pub header_type: HeaderType<'a>,
     pub header_comments: &'a [CommentOrNewline<'a>],
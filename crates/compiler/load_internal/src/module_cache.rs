@@ -39,6 +39,7 @@ pub(crate) struct ModuleCache<'a> {
     pub(crate) exposed_imports: MutMap<ModuleId, MutMap<Symbol, Region>>,
     pub(crate) top_level_thunks: MutMap<ModuleId, MutSet<Symbol>>,
     pub(crate) documentation: VecMap<ModuleId, ModuleDocumentation>,
+    pub(crate) syntax_problems: MutMap<ModuleId, Vec<String>>,
     pub(crate) can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub(crate) type_problems: MutMap<ModuleId, Vec<TypeError>>,
 
@@ -111,6 +112,7 @@ impl Default for ModuleCache<'_> {
             exposes: Default::default(),
             top_level_thunks: Default::default(),
             documentation: Default::default(),
+            syntax_problems: Default::default(),
             can_problems: Default::default(),
             type_problems: Default::default(),
             sources: Default::default(),
@@ -1,6 +1,6 @@
 ## An interface for docs tests
 interface Docs
-    exposes [makeUser, getNameExposed]
+    exposes [makeUser, getNameExposed, Greet]
     imports []
 
 ## This is a user
@@ -18,3 +18,7 @@ getName = \a -> a.name
 
 getNameExposed = getName
 
+## Things which can greet
+Greet implements
+    ## Says hello
+    greet : a -> Str where a implements Greet
@@ -21,7 +21,7 @@ use roc_load_internal::docs::DocDef;
 use roc_load_internal::file::{
     ExecutionMode, LoadConfig, LoadResult, LoadStart, LoadingProblem, Threading,
 };
-use roc_load_internal::module::LoadedModule;
+use roc_load_internal::module::{LoadedModule, MonomorphizedModule};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_packaging::cache::RocCacheDir;
@@ -77,6 +77,42 @@ fn load_and_typecheck(
     }
 }
 
+fn load_and_monomorphize(
+    arena: &Bump,
+    filename: PathBuf,
+) -> Result<MonomorphizedModule<'_>, LoadingProblem<'_>> {
+    use LoadResult::*;
+
+    let load_start = LoadStart::from_path(
+        arena,
+        filename,
+        None,
+        RenderTarget::Generic,
+        RocCacheDir::Disallowed,
+        DEFAULT_PALETTE,
+    )?;
+    let load_config = LoadConfig {
+        target: TARGET,
+        function_kind: FunctionKind::LambdaSet,
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Executable,
+    };
+
+    match roc_load_internal::file::load(
+        arena,
+        load_start,
+        Default::default(),
+        Default::default(), // these tests will re-compile the builtins
+        RocCacheDir::Disallowed,
+        load_config,
+    )? {
+        Monomorphized(module) => Ok(module),
+        TypeChecked(_) => unreachable!(""),
+    }
+}
+
 const TARGET: Target = Target::LinuxX64;
 
 // HELPERS
@@ -111,7 +147,17 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
     let arena = Bump::new();
     let arena = &arena;
 
-    match multiple_modules_help(subdir, arena, files) {
+    let load = |arena, full_file_path| {
+        load_and_typecheck(
+            arena,
+            full_file_path,
+            Default::default(),
+            TARGET,
+            FunctionKind::LambdaSet,
+        )
+    };
+
+    match multiple_modules_help(subdir, arena, files, load) {
         Err(io_error) => panic!("IO trouble: {io_error:?}"),
         Ok(Err(LoadingProblem::FormattedReport(buf))) => Err(buf),
         Ok(Err(loading_problem)) => Err(format!("{loading_problem:?}")),
@@ -119,6 +165,17 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
             let home = loaded_module.module_id;
             let (filepath, src) = loaded_module.sources.get(&home).unwrap();
 
+            // Syntax errors in any module would have stopped the load if it wasn't only checking
+            if !loaded_module.syntax_problems.is_empty() {
+                let reports: Vec<_> = loaded_module.syntax_problems.values().flatten().collect();
+
+                return Err(reports
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join("\n"));
+            }
+
             let can_problems = loaded_module.can_problems.remove(&home).unwrap_or_default();
             if !can_problems.is_empty() {
                 return Err(format_can_problems(
@@ -141,11 +198,39 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
     }
 }
 
-fn multiple_modules_help<'a>(
+/// Loads the modules of an app like [multiple_modules], but monomorphizes it, and fails if any
+/// module (including the platform) has problems.
+fn multiple_modules_monomorphized(subdir: &str, files: Vec<(&str, &str)>) -> Result<(), String> {
+    let arena = Bump::new();
+    let result = multiple_modules_help(subdir, &arena, files, load_and_monomorphize);
+
+    match result {
+        Err(io_error) => panic!("IO trouble: {io_error:?}"),
+        Ok(Err(LoadingProblem::FormattedReport(buf))) => Err(buf),
+        Ok(Err(loading_problem)) => Err(format!("{loading_problem:?}")),
+        Ok(Ok(loaded_module)) => {
+            let can_problems = loaded_module.can_problems.values().flatten();
+            let type_problems = loaded_module.type_problems.values().flatten();
+
+            if can_problems.clone().count() > 0 || type_problems.clone().count() > 0 {
+                return Err(format!(
+                    "can problems: {:?}, type problems: {:?}",
+                    can_problems.collect::<Vec<_>>(),
+                    type_problems.collect::<Vec<_>>()
+                ));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn multiple_modules_help<'a, T>(
     subdir: &str,
     arena: &'a Bump,
     mut files: Vec<(&str, &str)>,
-) -> Result<Result<LoadedModule, roc_load_internal::file::LoadingProblem<'a>>, std::io::Error> {
+    load: impl FnOnce(&'a Bump, PathBuf) -> Result<T, LoadingProblem<'a>>,
+) -> Result<Result<T, LoadingProblem<'a>>, std::io::Error> {
     use std::fs::{self, File};
     use std::io::Write;
 
@@ -182,13 +267,7 @@ fn multiple_modules_help<'a>(
         writeln!(file, "{source}")?;
         file_handles.push(file);
 
-        load_and_typecheck(
-            arena,
-            full_file_path,
-            Default::default(),
-            TARGET,
-            FunctionKind::LambdaSet,
-        )
+        load(arena, full_file_path)
     };
 
     Ok(result)
@@ -472,6 +551,7 @@ fn load_docs() {
         ),
         (Some("getName"), Some("Gets the user's name\n")),
         (Some("getNameExposed"), None),
+        (Some("Greet"), Some("Things which can greet\n")),
     ]
     .into_iter()
     .map(|(ident_str_opt, doc_str_opt)| {
@@ -487,6 +567,49 @@ fn load_docs() {
     assert_eq!(expected, all_docs);
 }
 
+#[test]
+fn load_docs_regions() {
+    let subs_by_module = Default::default();
+    let loaded_module = load_fixture("no_deps", "Docs", subs_by_module);
+
+    let module_docs = loaded_module
+        .docs_by_module
+        .get(&loaded_module.module_id)
+        .expect("module should have docs");
+
+    let (_, src) = loaded_module
+        .sources
+        .get(&loaded_module.module_id)
+        .expect("module should have a source");
+    let line_info = LineInfo::new(src);
+
+    let def_lines = module_docs
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            roc_load_internal::docs::DocEntry::DocDef(DocDef { name, region, .. }) => {
+                let region = line_info.convert_region(*region);
+
+                Some((name.as_str(), region.start.line, region.end.line))
+            }
+
+            roc_load_internal::docs::DocEntry::ModuleDoc(_)
+            | roc_load_internal::docs::DocEntry::DetachedDoc(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Lines are zero-based, and an annotated def spans its annotation and its body.
+    let expected = vec![
+        ("User", 6, 6),
+        ("makeUser", 11, 13),
+        ("getName", 16, 16),
+        ("getNameExposed", 18, 18),
+        ("Greet", 21, 23),
+    ];
+
+    assert_eq!(expected, def_lines);
+}
+
 #[test]
 fn import_alias() {
     let subs_by_module = Default::default();
@@ -726,6 +849,32 @@ fn parse_problem() {
     }
 }
 
+#[test]
+fn parse_problems_in_several_defs() {
+    let modules = vec![(
+        "Main.roc",
+        indoc!(
+            r"
+                module [main]
+
+                broken = [
+
+                alsoBroken = (1,
+
+                main = 1
+                "
+        ),
+    )];
+
+    match multiple_modules("parse_problems_in_several_defs", modules) {
+        Err(report) => {
+            assert_eq!(report.matches("── UNFINISHED LIST").count(), 1);
+            assert_eq!(report.matches("── UNFINISHED PARENTHESES").count(), 1);
+        }
+        Ok(_) => unreachable!("we expect failure here"),
+    }
+}
+
 #[test]
 #[should_panic(expected = "FILE NOT FOUND")]
 fn file_not_found() {
@@ -1663,6 +1812,123 @@ fn import_builtin_in_platform_and_check_app() {
     assert!(result.is_ok(), "should check");
 }
 
+#[test]
+fn import_module_from_platform_used_as_package() {
+    let modules = vec![
+        (
+            "platform/main.roc",
+            indoc!(
+                r#"
+                    platform "testplatform"
+                        requires {} { main : Str }
+                        exposes []
+                        packages {}
+                        imports []
+                        provides [mainForHost]
+
+                    mainForHost : Str
+                    mainForHost = main
+                    "#
+            ),
+        ),
+        (
+            "other/main.roc",
+            indoc!(
+                r#"
+                    platform "other"
+                        requires {} { run : Str }
+                        exposes [Greeting]
+                        packages {}
+                        imports []
+                        provides [runForHost]
+
+                    runForHost : Str
+                    runForHost = run
+                    "#
+            ),
+        ),
+        (
+            "other/Greeting.roc",
+            indoc!(
+                r#"
+                    module [hello]
+
+                    hello : Str
+                    hello = "Hello!"
+                    "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                    app "test"
+                        packages { pf: "platform/main.roc", other: "other/main.roc" }
+                        provides [main] to pf
+
+                    import other.Greeting
+
+                    main = Greeting.hello
+                    "#
+            ),
+        ),
+    ];
+
+    // Only `pf` is the app's platform, so `other` doesn't require `run` from the app
+    multiple_modules_monomorphized("import_module_from_platform_used_as_package", modules).unwrap();
+}
+
+#[test]
+fn import_module_from_platform_without_app_platform() {
+    let modules = vec![
+        (
+            "other/main.roc",
+            indoc!(
+                r#"
+                    platform "other"
+                        requires {} { run : Str }
+                        exposes [Greeting]
+                        packages {}
+                        imports []
+                        provides [runForHost]
+
+                    runForHost : Str
+                    runForHost = run
+                    "#
+            ),
+        ),
+        (
+            "other/Greeting.roc",
+            indoc!(
+                r#"
+                    module [hello]
+
+                    hello : Str
+                    hello = "Hello!"
+                    "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                    app "test"
+                        packages { other: "other/main.roc" }
+                        provides [replOutput] to "./platform"
+
+                    import other.Greeting
+
+                    replOutput = Greeting.hello
+                    "#
+            ),
+        ),
+    ];
+
+    // This is how the REPL imports modules from a platform given with `--package`
+    multiple_modules_monomorphized("import_module_from_platform_without_app_platform", modules)
+        .unwrap();
+}
+
 #[test]
 fn module_cyclic_import_itself() {
     let modules = vec![(
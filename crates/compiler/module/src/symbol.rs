@@ -375,6 +375,19 @@ impl ModuleId {
         (self.0.get() - 1) as usize
     }
 
+    /// The id as a number, for passing it to a host, as in `roc_expect_failed`
+    pub const fn to_u32(self) -> u32 {
+        self.0.get()
+    }
+
+    /// Get back an id that was passed to a host with [ModuleId::to_u32]
+    pub const fn from_u32(id: u32) -> Option<Self> {
+        match NonZeroU32::new(id) {
+            Some(id) => Some(ModuleId(id)),
+            None => None,
+        }
+    }
+
     #[cfg(any(debug_assertions, feature = "debug-symbols"))]
     pub fn register_debug_idents(self, ident_ids: &IdentIds) {
         let mut all = DEBUG_IDENT_IDS_BY_MODULE_ID.lock().expect("Failed to acquire lock for Debug interning into DEBUG_MODULE_ID_NAMES, presumably because a thread panicked.");
@@ -1678,6 +1691,8 @@ define_builtins! {
         26 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
         27 DECODE_FROM_BYTES: "fromBytes"
         28 DECODE_MAP_RESULT: "mapResult"
+        29 DECODE_TAG: "tag"
+        30 DECODE_TAG_AS_RECORD: "tagAsRecord"
     }
     13 HASH: "Hash" => {
         0 HASH_HASH_ABILITY: "Hash" exposed_type=true
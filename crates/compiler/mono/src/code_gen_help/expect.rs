@@ -0,0 +1,825 @@
+use bumpalo::collections::vec::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode;
+use roc_error_macros::todo_lambda_erasure;
+use roc_module::ident::ForeignSymbol;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_region::all::Region;
+
+use crate::ir::{
+    BranchInfo, Call, CallType, Expr, JoinPointId, Literal, LookupType, Param, Proc, ProcLayout,
+    SelfRecursive, Stmt, UpdateModeId,
+};
+use crate::layout::{
+    InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr, Niche, STLayoutInterner,
+    TagIdIntType, UnionLayout,
+};
+
+use super::{
+    CodeGenHelp, Context, ExpectBuffer, HelperOp, Specialization, LAYOUT_BOOL, LAYOUT_UNIT,
+};
+
+// Arguments of a Clone helper proc
+const BUFFER: Symbol = Symbol::ARG_1;
+const OFFSET: Symbol = Symbol::ARG_2;
+const EXTRA_OFFSET: Symbol = Symbol::ARG_3;
+const VALUE: Symbol = Symbol::ARG_4;
+
+/// A straight-line sequence of `let`s, finished off with a terminating statement.
+/// Writing into the expect buffer takes a lot of address arithmetic, which is
+/// much easier to follow in this form than as nested closures.
+struct Block<'a> {
+    arena: &'a Bump,
+    home: ModuleId,
+    layout_isize: InLayout<'a>,
+    bindings: Vec<'a, (Symbol, Expr<'a>, InLayout<'a>)>,
+}
+
+impl<'a> Block<'a> {
+    fn new(root: &CodeGenHelp<'a>) -> Self {
+        Block {
+            arena: root.arena,
+            home: root.home,
+            layout_isize: root.layout_isize,
+            bindings: Vec::new_in(root.arena),
+        }
+    }
+
+    fn let_expr(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        name: &str,
+        expr: Expr<'a>,
+        layout: InLayout<'a>,
+    ) -> Symbol {
+        let symbol = Symbol::new(self.home, ident_ids.add_str(name));
+        self.bindings.push((symbol, expr, layout));
+        symbol
+    }
+
+    fn int(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        name: &str,
+        layout: InLayout<'a>,
+        value: i128,
+    ) -> Symbol {
+        let expr = Expr::Literal(Literal::Int(value.to_ne_bytes()));
+        self.let_expr(ident_ids, name, expr, layout)
+    }
+
+    fn lowlevel(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        name: &str,
+        layout: InLayout<'a>,
+        op: LowLevel,
+        arguments: &[Symbol],
+    ) -> Symbol {
+        let expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op,
+                update_mode: UpdateModeId::BACKEND_DUMMY,
+            },
+            arguments: self.arena.alloc_slice_copy(arguments),
+        });
+        self.let_expr(ident_ids, name, expr, layout)
+    }
+
+    fn foreign(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        name: &str,
+        ret_layout: InLayout<'a>,
+        function: &str,
+        arguments: &[Symbol],
+    ) -> Symbol {
+        let expr = Expr::Call(Call {
+            call_type: CallType::Foreign {
+                foreign_symbol: ForeignSymbol::from(function),
+                ret_layout,
+            },
+            arguments: self.arena.alloc_slice_copy(arguments),
+        });
+        self.let_expr(ident_ids, name, expr, ret_layout)
+    }
+
+    /// `offset + amount` for a constant amount
+    fn add_const(&mut self, ident_ids: &mut IdentIds, offset: Symbol, amount: u32) -> Symbol {
+        if amount == 0 {
+            return offset;
+        }
+        let layout_isize = self.layout_isize;
+        let amount = self.int(ident_ids, "amount", layout_isize, amount as i128);
+        self.lowlevel(
+            ident_ids,
+            "offset",
+            layout_isize,
+            LowLevel::NumAdd,
+            &[offset, amount],
+        )
+    }
+
+    /// Write `value` into the buffer, `offset` bytes from its start
+    fn store(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        layout_interner: &mut STLayoutInterner<'a>,
+        buffer: Symbol,
+        offset: Symbol,
+        value: Symbol,
+        layout: InLayout<'a>,
+    ) {
+        let layout_isize = self.layout_isize;
+        let ptr_layout = layout_interner.insert_direct_no_semantic(LayoutRepr::Ptr(layout));
+        let addr = self.lowlevel(
+            ident_ids,
+            "addr",
+            layout_isize,
+            LowLevel::NumAdd,
+            &[buffer, offset],
+        );
+        let ptr = self.lowlevel(ident_ids, "ptr", ptr_layout, LowLevel::PtrCast, &[addr]);
+        let store = Expr::ptr_store(self.arena.alloc([ptr, value]));
+        self.let_expr(ident_ids, "stored", store, ptr_layout);
+    }
+
+    /// Clone `value` into the buffer at `offset`, putting any heap data at `extra_offset`.
+    /// Returns the symbol for the new `extra_offset`.
+    fn clone_value(
+        &mut self,
+        root: &mut CodeGenHelp<'a>,
+        ident_ids: &mut IdentIds,
+        ctx: &mut Context<'a>,
+        layout_interner: &mut STLayoutInterner<'a>,
+        buffer: Symbol,
+        (offset, extra_offset): (Symbol, Symbol),
+        value: Symbol,
+        layout: InLayout<'a>,
+    ) -> Symbol {
+        use crate::layout::Builtin::*;
+
+        let layout_isize = self.layout_isize;
+        match layout_interner.get_repr(layout) {
+            LayoutRepr::Builtin(Int(_) | Float(_) | Bool | Decimal) => {
+                self.store(ident_ids, layout_interner, buffer, offset, value, layout);
+                extra_offset
+            }
+            LayoutRepr::Builtin(Str) => self.foreign(
+                ident_ids,
+                "extra_offset",
+                layout_isize,
+                bitcode::STR_CLONE_TO,
+                &[value, buffer, offset, extra_offset],
+            ),
+            // Functions are never displayed, so we don't write anything for them
+            LayoutRepr::LambdaSet(_) => extra_offset,
+            // There are no values of an empty tag union to write
+            LayoutRepr::Union(UnionLayout::NonRecursive(&[])) => extra_offset,
+            _ => {
+                let arguments = self.arena.alloc([buffer, offset, extra_offset, value]);
+                let call = root
+                    .call_specialized_op(ident_ids, ctx, layout_interner, layout, arguments)
+                    .unwrap();
+                self.let_expr(ident_ids, "extra_offset", call, layout_isize)
+            }
+        }
+    }
+
+    fn finish(self, tail: Stmt<'a>) -> Stmt<'a> {
+        let arena = self.arena;
+        self.bindings
+            .into_iter()
+            .rev()
+            .fold(tail, |next, (symbol, expr, layout)| {
+                Stmt::Let(symbol, expr, layout, arena.alloc(next))
+            })
+    }
+}
+
+/// Body of a proc that clones a value into the expect buffer, for `roc_repl_expect` to read.
+/// Arguments are the buffer address, the offset at which to write the value, the offset at which
+/// to write any data it points to, and the value itself. Returns the next free `extra_offset`.
+/// This matches the format written by `build_clone` in the LLVM backend.
+pub fn clone_generic<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout: InLayout<'a>,
+) -> Stmt<'a> {
+    use crate::layout::Builtin::*;
+    use LayoutRepr::*;
+
+    let repr = layout_interner.get_repr(layout);
+    match repr {
+        Builtin(Int(_) | Float(_) | Bool | Decimal | Str) | LambdaSet(_) => {
+            unreachable!(
+                "No generated proc for cloning {:?}. Use direct code gen.",
+                layout
+            )
+        }
+        Builtin(List(elem_layout)) => {
+            clone_list(root, ident_ids, ctx, layout_interner, elem_layout)
+        }
+        Struct(_) | Union(_) if repr.safe_to_memcpy(layout_interner) => {
+            let mut block = Block::new(root);
+            block.store(ident_ids, layout_interner, BUFFER, OFFSET, VALUE, layout);
+            block.finish(Stmt::Ret(EXTRA_OFFSET))
+        }
+        Struct(field_layouts) => clone_struct(root, ident_ids, ctx, layout_interner, field_layouts),
+        Union(union_layout) => clone_tag_union(root, ident_ids, ctx, layout_interner, union_layout),
+        Ptr(_) => unreachable!("Ptr values are for internal use only, and never cloned"),
+        RecursivePointer(_) => {
+            unreachable!(
+                "Can't clone a RecursivePointer. Should have been replaced by a tag union."
+            )
+        }
+        FunctionPointer(_) | Erased(_) => todo_lambda_erasure!(),
+    }
+}
+
+fn clone_struct<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    field_layouts: &'a [InLayout<'a>],
+) -> Stmt<'a> {
+    let mut block = Block::new(root);
+    let extra_offset = clone_fields(
+        &mut block,
+        root,
+        ident_ids,
+        ctx,
+        layout_interner,
+        (OFFSET, EXTRA_OFFSET),
+        field_layouts,
+        |i| Expr::StructAtIndex {
+            index: i as u64,
+            field_layouts,
+            structure: VALUE,
+        },
+    );
+    block.finish(Stmt::Ret(extra_offset))
+}
+
+/// Clone each field of a struct or tag payload, laid out one after the other from `offset`
+fn clone_fields<'a>(
+    block: &mut Block<'a>,
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    (offset, mut extra_offset): (Symbol, Symbol),
+    field_layouts: &'a [InLayout<'a>],
+    field_expr: impl Fn(usize) -> Expr<'a>,
+) -> Symbol {
+    let mut field_offset = 0;
+    for (i, field_layout) in field_layouts.iter().enumerate() {
+        let field = block.let_expr(
+            ident_ids,
+            &format!("field_{i}"),
+            field_expr(i),
+            *field_layout,
+        );
+        let offset = block.add_const(ident_ids, offset, field_offset);
+        extra_offset = block.clone_value(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            BUFFER,
+            (offset, extra_offset),
+            field,
+            *field_layout,
+        );
+        field_offset += layout_interner.stack_size(*field_layout);
+    }
+    extra_offset
+}
+
+fn clone_list<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    elem_layout: InLayout<'a>,
+) -> Stmt<'a> {
+    use LowLevel::*;
+    let layout_isize = root.layout_isize;
+    let ptr_size = root.target.ptr_size() as u32;
+    let ptr_layout = layout_interner.insert_direct_no_semantic(LayoutRepr::Ptr(elem_layout));
+    let elem_size = layout_interner.stack_size(elem_layout);
+
+    // Write the list struct. We only copy the elements we actually have, so capacity = length.
+    // The elements go at extra_offset, and any data they point to goes after all of them.
+    let mut block = Block::new(root);
+    let len = block.lowlevel(ident_ids, "len", layout_isize, ListLenUsize, &[VALUE]);
+    let elements = block.let_expr(
+        ident_ids,
+        "elements",
+        Expr::StructAtIndex {
+            index: 0,
+            field_layouts: root.arena.alloc([ptr_layout, layout_isize]),
+            structure: VALUE,
+        },
+        ptr_layout,
+    );
+    let start = block.lowlevel(ident_ids, "start", layout_isize, PtrCast, &[elements]);
+    let size = block.int(ident_ids, "size", layout_isize, elem_size as i128);
+    let list_size = block.lowlevel(ident_ids, "list_size", layout_isize, NumMul, &[len, size]);
+    let end = block.lowlevel(ident_ids, "end", layout_isize, NumAdd, &[start, list_size]);
+
+    block.store(
+        ident_ids,
+        layout_interner,
+        BUFFER,
+        OFFSET,
+        EXTRA_OFFSET,
+        layout_isize,
+    );
+    let len_offset = block.add_const(ident_ids, OFFSET, ptr_size);
+    block.store(
+        ident_ids,
+        layout_interner,
+        BUFFER,
+        len_offset,
+        len,
+        layout_isize,
+    );
+    let cap_offset = block.add_const(ident_ids, OFFSET, 2 * ptr_size);
+    block.store(
+        ident_ids,
+        layout_interner,
+        BUFFER,
+        cap_offset,
+        len,
+        layout_isize,
+    );
+
+    let rest_start = block.lowlevel(
+        ident_ids,
+        "rest_start",
+        layout_isize,
+        NumAdd,
+        &[EXTRA_OFFSET, list_size],
+    );
+
+    // Loop over the elements, cloning each one
+    let elems_loop = JoinPointId(root.create_symbol(ident_ids, "elems_loop"));
+    let addr = root.create_symbol(ident_ids, "addr");
+    let dst = root.create_symbol(ident_ids, "dst");
+    let rest = root.create_symbol(ident_ids, "rest");
+    let params = [addr, dst, rest].map(|symbol| Param {
+        symbol,
+        layout: layout_isize,
+    });
+
+    let mut loop_body = Block::new(root);
+    let is_end = loop_body.lowlevel(ident_ids, "is_end", LAYOUT_BOOL, NumGte, &[addr, end]);
+
+    let mut next_elem = Block::new(root);
+    let ptr = next_elem.lowlevel(ident_ids, "ptr", ptr_layout, PtrCast, &[addr]);
+    let elem = next_elem.let_expr(
+        ident_ids,
+        "elem",
+        Expr::ptr_load(root.arena.alloc(ptr)),
+        elem_layout,
+    );
+    let next_rest = next_elem.clone_value(
+        root,
+        ident_ids,
+        ctx,
+        layout_interner,
+        BUFFER,
+        (dst, rest),
+        elem,
+        elem_layout,
+    );
+    let next_addr = next_elem.lowlevel(ident_ids, "next_addr", layout_isize, NumAdd, &[addr, size]);
+    let next_dst = next_elem.lowlevel(ident_ids, "next_dst", layout_isize, NumAdd, &[dst, size]);
+    let jump_back = Stmt::Jump(
+        elems_loop,
+        root.arena.alloc([next_addr, next_dst, next_rest]),
+    );
+
+    let if_end_of_list = Stmt::if_then_else(
+        root.arena,
+        is_end,
+        layout_isize,
+        Stmt::Ret(rest),
+        root.arena.alloc(next_elem.finish(jump_back)),
+    );
+
+    let joinpoint_loop = Stmt::Join {
+        id: elems_loop,
+        parameters: root.arena.alloc(params),
+        body: root.arena.alloc(loop_body.finish(if_end_of_list)),
+        remainder: root.arena.alloc(Stmt::Jump(
+            elems_loop,
+            root.arena.alloc([start, EXTRA_OFFSET, rest_start]),
+        )),
+    };
+
+    block.finish(joinpoint_loop)
+}
+
+fn clone_tag_union<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    union_layout: UnionLayout<'a>,
+) -> Stmt<'a> {
+    use UnionLayout::*;
+
+    let parent_rec_ptr_layout = ctx.recursive_union;
+    if !matches!(union_layout, NonRecursive(_)) {
+        ctx.recursive_union = Some(union_layout);
+    }
+
+    // The non-null tags, with their IDs
+    let tags: std::vec::Vec<(TagIdIntType, &'a [InLayout<'a>])> = match union_layout {
+        NonRecursive(tags) | Recursive(tags) => tags
+            .iter()
+            .enumerate()
+            .map(|(i, fields)| (i as TagIdIntType, *fields))
+            .collect(),
+        NonNullableUnwrapped(fields) => vec![(0, fields)],
+        NullableWrapped {
+            nullable_id,
+            other_tags,
+        } => (0..other_tags.len() + 1)
+            .filter(|i| *i != nullable_id as usize)
+            .map(|i| {
+                let fields = if i > nullable_id as usize {
+                    other_tags[i - 1]
+                } else {
+                    other_tags[i]
+                };
+                (i as TagIdIntType, fields)
+            })
+            .collect(),
+        NullableUnwrapped {
+            nullable_id,
+            other_fields,
+        } => vec![(!nullable_id as TagIdIntType, other_fields)],
+    };
+
+    let null_id = match union_layout {
+        NullableWrapped { nullable_id, .. } => Some(nullable_id),
+        NullableUnwrapped { nullable_id, .. } => Some(nullable_id as TagIdIntType),
+        NonRecursive(_) | Recursive(_) | NonNullableUnwrapped(_) => None,
+    };
+
+    let body = if tags.is_empty() {
+        // cannot be reached at runtime, but we need to generate valid code
+        Stmt::Ret(EXTRA_OFFSET)
+    } else {
+        clone_tag_union_help(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            union_layout,
+            &tags,
+            null_id,
+        )
+    };
+
+    ctx.recursive_union = parent_rec_ptr_layout;
+
+    body
+}
+
+fn clone_tag_union_help<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    union_layout: UnionLayout<'a>,
+    tags: &[(TagIdIntType, &'a [InLayout<'a>])],
+    null_id: Option<TagIdIntType>,
+) -> Stmt<'a> {
+    let layout_isize = root.layout_isize;
+    let tag_id_layout = union_layout.tag_id_layout();
+    let is_recursive = !matches!(union_layout, UnionLayout::NonRecursive(_));
+
+    let tag_id_sym = root.create_symbol(ident_ids, "tag_id");
+    let tag_id_expr = Expr::GetTagId {
+        structure: VALUE,
+        union_layout,
+    };
+
+    let mut branches = Vec::with_capacity_in(tags.len() + 1, root.arena);
+
+    if let Some(null_id) = null_id {
+        // A null pointer is written as a zero
+        let mut block = Block::new(root);
+        let zero = block.int(ident_ids, "zero", layout_isize, 0);
+        block.store(
+            ident_ids,
+            layout_interner,
+            BUFFER,
+            OFFSET,
+            zero,
+            layout_isize,
+        );
+        let branch = block.finish(Stmt::Ret(EXTRA_OFFSET));
+        branches.push((null_id as u64, BranchInfo::None, branch));
+    }
+
+    for (tag_id, field_layouts) in tags.iter().copied() {
+        let mut block = Block::new(root);
+
+        // Recursive tag data goes at extra_offset, with its own pointers after it.
+        // The pointer is written as the offset of the data, plus the tag ID if it lives in the pointer.
+        let (data_offset, extra_offset) = if is_recursive {
+            if union_layout.stores_tag_id_in_pointer(root.target) {
+                let id = block.int(ident_ids, "id", Layout::U32, tag_id as i128);
+                block.store(ident_ids, layout_interner, BUFFER, OFFSET, id, Layout::U32);
+                let data_addr = block.lowlevel(
+                    ident_ids,
+                    "data_addr",
+                    Layout::U32,
+                    LowLevel::NumIntCast,
+                    &[EXTRA_OFFSET],
+                );
+                let addr_offset = block.add_const(ident_ids, OFFSET, 4);
+                block.store(
+                    ident_ids,
+                    layout_interner,
+                    BUFFER,
+                    addr_offset,
+                    data_addr,
+                    Layout::U32,
+                );
+            } else {
+                block.store(
+                    ident_ids,
+                    layout_interner,
+                    BUFFER,
+                    OFFSET,
+                    EXTRA_OFFSET,
+                    layout_isize,
+                );
+            }
+            let (data_width, _) = union_layout.data_size_and_alignment(layout_interner);
+            let extra_offset = block.add_const(ident_ids, EXTRA_OFFSET, data_width);
+            (EXTRA_OFFSET, extra_offset)
+        } else {
+            (OFFSET, EXTRA_OFFSET)
+        };
+
+        let extra_offset = clone_fields(
+            &mut block,
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            (data_offset, extra_offset),
+            field_layouts,
+            |i| Expr::UnionAtIndex {
+                structure: VALUE,
+                tag_id,
+                union_layout,
+                index: i as u64,
+            },
+        );
+
+        if let Some(tag_id_offset) = union_layout.data_size_without_tag_id(layout_interner) {
+            if layout_interner.stack_size(tag_id_layout) > 0 {
+                let id_offset = block.add_const(ident_ids, data_offset, tag_id_offset);
+                block.store(
+                    ident_ids,
+                    layout_interner,
+                    BUFFER,
+                    id_offset,
+                    tag_id_sym,
+                    tag_id_layout,
+                );
+            }
+        }
+
+        branches.push((
+            tag_id as u64,
+            BranchInfo::None,
+            block.finish(Stmt::Ret(extra_offset)),
+        ));
+    }
+
+    let body = if branches.len() == 1 {
+        branches.pop().unwrap().2
+    } else {
+        let (_, default_info, default_stmt) = branches.pop().unwrap();
+        Stmt::Switch {
+            cond_symbol: tag_id_sym,
+            cond_layout: tag_id_layout,
+            branches: branches.into_bump_slice(),
+            default_branch: (default_info, root.arena.alloc(default_stmt)),
+            ret_layout: layout_isize,
+        }
+    };
+
+    Stmt::Let(
+        tag_id_sym,
+        tag_id_expr,
+        tag_id_layout,
+        root.arena.alloc(body),
+    )
+}
+
+/// Generate a proc for an `expect` or `expect-fx`. If the condition is false, it copies the
+/// looked-up values into the expect buffer, and notifies the parent process if requested.
+pub fn expect_failed_proc<'a>(
+    root: &mut CodeGenHelp<'a>,
+    ident_ids: &mut IdentIds,
+    ctx: &mut Context<'a>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    module_id: ModuleId,
+    region: Region,
+    lookup_layouts: &'a [InLayout<'a>],
+    variables: &[LookupType],
+    expect_buffer: ExpectBuffer,
+) -> Symbol {
+    use LowLevel::*;
+    let arena = root.arena;
+    let layout_isize = root.layout_isize;
+    let ptr_size = root.target.ptr_size() as u32;
+
+    let proc_symbol = root.create_symbol(
+        ident_ids,
+        &format!("#help{}_ExpectFailed", root.specializations.len()),
+    );
+
+    let mut arg_layouts = Vec::with_capacity_in(1 + lookup_layouts.len(), arena);
+    arg_layouts.push(LAYOUT_BOOL);
+    arg_layouts.extend(lookup_layouts.iter().copied());
+    let arg_layouts = arg_layouts.into_bump_slice();
+
+    let proc_layout = ProcLayout {
+        arguments: arg_layouts,
+        result: LAYOUT_UNIT,
+        niche: Niche::NONE,
+    };
+
+    // Keep the linker data in the same order as the procs, like find_or_create_proc does
+    ctx.new_linker_data.push((proc_symbol, proc_layout));
+    let spec_index = root.specializations.len();
+    root.specializations.push(Specialization {
+        op: HelperOp::ExpectFailed,
+        layout: LAYOUT_UNIT,
+        symbol: proc_symbol,
+        proc: None,
+    });
+
+    let condition = root.create_symbol(ident_ids, "condition");
+    let lookups = Vec::from_iter_in(
+        (0..lookup_layouts.len()).map(|i| root.create_symbol(ident_ids, &format!("lookup_{i}"))),
+        arena,
+    );
+
+    let unit = root.create_symbol(ident_ids, "unit");
+
+    // The buffer starts with the number of failed expects, and the offset of the next free byte
+    let start_function = match expect_buffer {
+        ExpectBuffer::SharedFile { .. } => bitcode::UTILS_EXPECT_FAILED_START_SHARED_FILE,
+        ExpectBuffer::SharedBuffer => bitcode::UTILS_EXPECT_FAILED_START_SHARED_BUFFER,
+    };
+    let mut block = Block::new(root);
+    let buffer = block.foreign(ident_ids, "buffer", layout_isize, start_function, &[]);
+    let isize_ptr_layout = layout_interner.insert_direct_no_semantic(LayoutRepr::Ptr(layout_isize));
+    let count_ptr = block.lowlevel(ident_ids, "count_ptr", isize_ptr_layout, PtrCast, &[buffer]);
+    let count = block.let_expr(
+        ident_ids,
+        "count",
+        Expr::ptr_load(arena.alloc(count_ptr)),
+        layout_isize,
+    );
+    let offset_addr = block.add_const(ident_ids, buffer, ptr_size);
+    let offset_ptr = block.lowlevel(
+        ident_ids,
+        "offset_ptr",
+        isize_ptr_layout,
+        PtrCast,
+        &[offset_addr],
+    );
+    let offset = block.let_expr(
+        ident_ids,
+        "offset",
+        Expr::ptr_load(arena.alloc(offset_ptr)),
+        layout_isize,
+    );
+
+    // Header: region and module of the expect
+    let module_id = module_id.to_u32();
+    let header = [
+        region.start().offset as i128,
+        region.end().offset as i128,
+        module_id as i128,
+    ];
+    for (i, value) in header.into_iter().enumerate() {
+        let value = block.int(ident_ids, "header", Layout::U32, value);
+        let at = block.add_const(ident_ids, offset, 4 * i as u32);
+        block.store(ident_ids, layout_interner, buffer, at, value, Layout::U32);
+    }
+    let after_header = block.add_const(ident_ids, offset, 12);
+
+    // A table of (value offset, type variable) for each lookup, followed by the values
+    let table_entry_size = ptr_size + 4;
+    let mut next = block.add_const(
+        ident_ids,
+        after_header,
+        table_entry_size * lookup_layouts.len() as u32,
+    );
+    let mut lookup_starts = Vec::with_capacity_in(lookups.len(), arena);
+    for (lookup, layout) in lookups.iter().zip(lookup_layouts.iter()) {
+        lookup_starts.push(next);
+        let stack_size = layout_interner.stack_size(*layout);
+        let extra_offset = block.add_const(ident_ids, next, stack_size);
+        next = block.clone_value(
+            root,
+            ident_ids,
+            ctx,
+            layout_interner,
+            buffer,
+            (next, extra_offset),
+            *lookup,
+            *layout,
+        );
+    }
+    for (i, (start, var)) in lookup_starts.iter().zip(variables.iter()).enumerate() {
+        let entry = block.add_const(ident_ids, after_header, table_entry_size * i as u32);
+        block.store(
+            ident_ids,
+            layout_interner,
+            buffer,
+            entry,
+            *start,
+            layout_isize,
+        );
+        let var_offset = block.add_const(ident_ids, entry, ptr_size);
+        let var = block.int(ident_ids, "var", Layout::U32, var.index() as i128);
+        block.store(
+            ident_ids,
+            layout_interner,
+            buffer,
+            var_offset,
+            var,
+            Layout::U32,
+        );
+    }
+
+    let one = block.int(ident_ids, "one", layout_isize, 1);
+    let new_count = block.lowlevel(ident_ids, "new_count", layout_isize, NumAdd, &[count, one]);
+    let store_count = Expr::ptr_store(arena.alloc([count_ptr, new_count]));
+    block.let_expr(ident_ids, "stored", store_count, isize_ptr_layout);
+    let store_offset = Expr::ptr_store(arena.alloc([offset_ptr, next]));
+    block.let_expr(ident_ids, "stored", store_offset, isize_ptr_layout);
+
+    if let ExpectBuffer::SharedFile {
+        notify_parent: true,
+    } = expect_buffer
+    {
+        block.foreign(
+            ident_ids,
+            "notified",
+            LAYOUT_UNIT,
+            bitcode::NOTIFY_PARENT_EXPECT,
+            &[buffer],
+        );
+    }
+
+    let if_failed = block.finish(Stmt::Ret(unit));
+    let body = Stmt::Let(
+        unit,
+        Expr::Struct(&[]),
+        LAYOUT_UNIT,
+        arena.alloc(Stmt::if_then_else(
+            arena,
+            condition,
+            LAYOUT_UNIT,
+            Stmt::Ret(unit),
+            arena.alloc(if_failed),
+        )),
+    );
+
+    let mut args = Vec::with_capacity_in(arg_layouts.len(), arena);
+    args.push((LAYOUT_BOOL, condition));
+    args.extend(lookup_layouts.iter().copied().zip(lookups.iter().copied()));
+
+    root.specializations[spec_index].proc = Some(Proc {
+        name: LambdaName::no_niche(proc_symbol),
+        args: args.into_bump_slice(),
+        body,
+        closure_data_layout: None,
+        ret_layout: LAYOUT_UNIT,
+        is_self_recursive: SelfRecursive::NotSelfRecursive,
+        is_erased: false,
+        region: Region::zero(),
+    });
+
+    proc_symbol
+}
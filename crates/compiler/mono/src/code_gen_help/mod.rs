@@ -3,11 +3,12 @@ use bumpalo::collections::CollectIn;
 use bumpalo::Bump;
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_region::all::Region;
 use roc_target::Target;
 
 use crate::ir::{
-    BranchInfo, Call, CallSpecId, CallType, Expr, JoinPointId, Literal, ModifyRc, PassedFunction,
-    Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeId,
+    BranchInfo, Call, CallSpecId, CallType, Expr, JoinPointId, Literal, LookupType, ModifyRc,
+    PassedFunction, Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeId,
 };
 use crate::layout::{
     Builtin, InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr, LayoutWrapper, Niche,
@@ -15,6 +16,7 @@ use crate::layout::{
 };
 
 mod equality;
+mod expect;
 mod refcount;
 
 const LAYOUT_BOOL: InLayout = Layout::BOOL;
@@ -41,6 +43,17 @@ pub enum HelperOp {
     Reset,
     ResetRef,
     Eq,
+    Clone,
+    ExpectFailed,
+}
+
+/// Where the helper proc of a failed `expect` writes the values it looks up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpectBuffer {
+    /// The file shared with the parent process of a dev build, which can be notified of the failure
+    SharedFile { notify_parent: bool },
+    /// The buffer the host passes to `set_shared_buffer`, as `roc test` does
+    SharedBuffer,
 }
 
 impl HelperOp {
@@ -76,7 +89,7 @@ pub struct Context<'a> {
 /// ----------------------------------------------
 ///
 /// Some low level operations need specialized helper procs to traverse data structures at runtime.
-/// This includes refcounting, hashing, and equality checks.
+/// This includes refcounting, hashing, equality checks, and copying values for failed `expect`s.
 ///
 /// For example, when checking List equality, we need to visit each element and compare them.
 /// Depending on the type of the list elements, we may need to recurse deeper into each element.
@@ -270,6 +283,59 @@ impl<'a> CodeGenHelp<'a> {
         (expr, ctx.new_linker_data)
     }
 
+    /// Replace an `expect` or `expect-fx` with a call to a helper proc. If the condition is false,
+    /// the proc copies the looked-up values into the given expect buffer.
+    /// The helper procs themselves are to be generated later with `generate_procs`
+    pub fn call_expect_failed(
+        &mut self,
+        ident_ids: &mut IdentIds,
+        layout_interner: &mut STLayoutInterner<'a>,
+        condition: Symbol,
+        region: Region,
+        lookups: &'a [Symbol],
+        lookup_layouts: &'a [InLayout<'a>],
+        variables: &[LookupType],
+        expect_buffer: ExpectBuffer,
+    ) -> (Expr<'a>, Vec<'a, (Symbol, ProcLayout<'a>)>) {
+        let mut ctx = Context {
+            new_linker_data: Vec::new_in(self.arena),
+            recursive_union: None,
+            op: HelperOp::Clone,
+        };
+
+        let proc_name = expect::expect_failed_proc(
+            self,
+            ident_ids,
+            &mut ctx,
+            layout_interner,
+            condition.module_id(),
+            region,
+            lookup_layouts,
+            variables,
+            expect_buffer,
+        );
+
+        let mut arguments = Vec::with_capacity_in(1 + lookups.len(), self.arena);
+        arguments.push(condition);
+        arguments.extend(lookups.iter().copied());
+
+        let mut arg_layouts = Vec::with_capacity_in(1 + lookups.len(), self.arena);
+        arg_layouts.push(LAYOUT_BOOL);
+        arg_layouts.extend(lookup_layouts.iter().copied());
+
+        let expr = Expr::Call(Call {
+            call_type: CallType::ByName {
+                name: LambdaName::no_niche(proc_name),
+                ret_layout: LAYOUT_UNIT,
+                arg_layouts: arg_layouts.into_bump_slice(),
+                specialization_id: CallSpecId::BACKEND_DUMMY,
+            },
+            arguments: arguments.into_bump_slice(),
+        });
+
+        (expr, ctx.new_linker_data)
+    }
+
     // ============================================================================
     //
     //              CALL SPECIALIZED OP
@@ -314,6 +380,11 @@ impl<'a> CodeGenHelp<'a> {
                     IndirectDec => (LAYOUT_UNIT, arena.alloc([ptr_arg])),
                     IndirectInc => (LAYOUT_UNIT, arena.alloc([ptr_arg, self.layout_isize])),
                     Eq => (LAYOUT_BOOL, self.arena.alloc([arg, arg])),
+                    Clone => {
+                        let isize = self.layout_isize;
+                        (isize, arena.alloc([isize, isize, isize, arg]))
+                    }
+                    ExpectFailed => unreachable!("ExpectFailed procs are called directly"),
                 }
             };
 
@@ -423,6 +494,11 @@ impl<'a> CodeGenHelp<'a> {
                 LAYOUT_BOOL,
                 equality::eq_generic(self, ident_ids, ctx, layout_interner, layout),
             ),
+            Clone => (
+                self.layout_isize,
+                expect::clone_generic(self, ident_ids, ctx, layout_interner, layout),
+            ),
+            ExpectFailed => unreachable!("ExpectFailed procs are generated by call_expect_failed"),
         };
 
         let args: &'a [(InLayout<'a>, Symbol)] = {
@@ -445,6 +521,16 @@ impl<'a> CodeGenHelp<'a> {
                     self.arena.alloc([(ptr_layout, ARG_1)])
                 }
                 Eq => self.arena.alloc([roc_value, (layout, ARG_2)]),
+                Clone => {
+                    let isize = self.layout_isize;
+                    self.arena.alloc([
+                        (isize, ARG_1),
+                        (isize, ARG_2),
+                        (isize, ARG_3),
+                        (layout, ARG_4),
+                    ])
+                }
+                ExpectFailed => unreachable!(),
             }
         };
 
@@ -456,6 +542,7 @@ impl<'a> CodeGenHelp<'a> {
             ret_layout,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         });
 
         proc_symbol
@@ -517,6 +604,15 @@ impl<'a> CodeGenHelp<'a> {
                 result: LAYOUT_BOOL,
                 niche: Niche::NONE,
             },
+            HelperOp::Clone => {
+                let isize = self.layout_isize;
+                ProcLayout {
+                    arguments: self.arena.alloc([isize, isize, isize, layout]),
+                    result: isize,
+                    niche: Niche::NONE,
+                }
+            }
+            HelperOp::ExpectFailed => unreachable!("No generated Proc for ExpectFailed"),
         };
 
         (proc_symbol, proc_layout)
@@ -829,6 +925,7 @@ impl<'a> CallerProc<'a> {
             ret_layout: Layout::UNIT,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         if false {
@@ -971,6 +1068,7 @@ impl<'a> CallerProc<'a> {
             ret_layout: Layout::BOOL,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         if false {
@@ -1075,6 +1173,7 @@ pub fn test_helper<'a>(
         ret_layout: output_layout,
         is_self_recursive: main_proc.is_self_recursive,
         is_erased: false,
+        region: Region::zero(),
     }
 }
 
@@ -1275,6 +1374,7 @@ pub fn repl_helper<'a>(
         ret_layout: Layout::UNIT,
         is_self_recursive: main_proc.is_self_recursive,
         is_erased: false,
+        region: Region::zero(),
     }
 }
 
@@ -210,6 +210,8 @@ pub struct PartialProc<'a> {
     pub body: roc_can::expr::Expr,
     pub body_var: Variable,
     pub is_self_recursive: bool,
+    /// Where the body of this function lives in its source module
+    pub region: Region,
 }
 
 impl<'a> PartialProc<'a> {
@@ -239,6 +241,7 @@ impl<'a> PartialProc<'a> {
                     body: body.value,
                     body_var: ret_var,
                     is_self_recursive,
+                    region: body.region,
                 }
             }
 
@@ -256,6 +259,7 @@ impl<'a> PartialProc<'a> {
                     body: roc_can::expr::Expr::RuntimeError(error.value),
                     body_var: ret_var,
                     is_self_recursive: false,
+                    region: error.region,
                 }
             }
         }
@@ -308,6 +312,9 @@ pub struct Proc<'a> {
     pub ret_layout: InLayout<'a>,
     pub is_self_recursive: SelfRecursive,
     pub is_erased: bool,
+    /// Where this procedure came from in its source module, or `Region::zero()`
+    /// for procedures generated by the compiler
+    pub region: Region,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -1099,6 +1106,7 @@ impl<'a> Procs<'a> {
                                         body: body.value,
                                         body_var: ret_var,
                                         is_self_recursive,
+                                        region: body.region,
                                     };
 
                                     self.partial_procs.insert(name.name(), partial_proc);
@@ -1127,6 +1135,7 @@ impl<'a> Procs<'a> {
                                     body: body.value,
                                     body_var: ret_var,
                                     is_self_recursive,
+                                    region: body.region,
                                 };
 
                                 self.partial_procs.insert(name.name(), partial_proc)
@@ -1362,6 +1371,8 @@ pub struct Env<'a, 'i> {
     pub exposed_by_module: &'i ExposedByModule,
     pub derived_module: &'i SharedDerivedModule,
     pub struct_indexing: UsageTrackingMap<(Symbol, u64), Symbol>,
+    /// Where the values bound by `let`s were defined, for debug info.
+    pub symbol_regions: MutMap<Symbol, Region>,
 }
 
 impl<'a, 'i> Env<'a, 'i> {
@@ -2456,6 +2467,10 @@ fn from_can_let<'a>(
         };
     }
 
+    for symbol in def.pattern_vars.keys() {
+        env.symbol_regions.insert(*symbol, def.loc_pattern.region);
+    }
+
     if let roc_can::pattern::Pattern::Identifier(symbol) = &def.loc_pattern.value {
         return match def.loc_expr.value {
             Closure(closure_data) => {
@@ -3317,6 +3332,7 @@ fn generate_runtime_error_function<'a>(
         ret_layout,
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         is_erased,
+        region: Region::zero(),
     }
 }
 
@@ -3412,6 +3428,7 @@ fn generate_host_exposed_function<'a>(
                 ret_layout: result,
                 is_self_recursive: SelfRecursive::NotSelfRecursive,
                 is_erased: false,
+                region: Region::zero(),
             };
 
             let top_level = ProcLayout::from_raw_named(env.arena, lambda_name, layout);
@@ -3476,6 +3493,7 @@ fn generate_host_exposed_lambda_set<'a>(
         ret_layout: return_layout,
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         is_erased: false,
+        region: Region::zero(),
     };
 
     let top_level = ProcLayout::new(
@@ -3502,6 +3520,7 @@ fn specialize_proc_help<'a>(
 ) -> Result<Proc<'a>, LayoutProblem> {
     let partial_proc = procs.partial_procs.get_id(partial_proc_id);
     let captured_symbols = partial_proc.captured_symbols;
+    let region = partial_proc.region;
 
     let _unified = env.unify(
         procs.externals_we_need.values_mut(),
@@ -3570,6 +3589,7 @@ fn specialize_proc_help<'a>(
                 ret_layout,
                 is_self_recursive: recursivity,
                 is_erased,
+                region,
             }
         }
         SpecializedLayout::FunctionBody {
@@ -3785,6 +3805,7 @@ fn specialize_proc_help<'a>(
                 ret_layout,
                 is_self_recursive: recursivity,
                 is_erased,
+                region,
             }
         }
     };
@@ -10360,6 +10381,7 @@ where
             ret_layout: *field,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         answer.push(GlueProc {
@@ -10455,6 +10477,7 @@ where
             ret_layout: *field,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: false,
+            region: Region::zero(),
         };
 
         answer.push(GlueProc {
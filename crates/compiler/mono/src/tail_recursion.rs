@@ -807,6 +807,7 @@ impl<'a> TrmcEnv<'a> {
             ret_layout: proc.ret_layout,
             is_self_recursive: SelfRecursive::NotSelfRecursive,
             is_erased: proc.is_erased,
+            region: proc.region,
         }
     }
 
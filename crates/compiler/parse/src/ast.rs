@@ -502,6 +502,8 @@ pub enum Expr<'a> {
     MalformedIdent(&'a str, crate::ident::BadIdent),
     MalformedClosure,
     MalformedSuffixed(&'a Loc<Expr<'a>>),
+    /// The source of a top-level def that could not be parsed, kept when recovering from syntax errors.
+    Malformed(&'a str),
     // Both operators were non-associative, e.g. (True == False == False).
     // We should tell the author to disambiguate by grouping them with parens.
     PrecedenceConflict(&'a PrecedenceConflict<'a>),
@@ -628,6 +630,7 @@ pub fn is_expr_suffixed(expr: &Expr) -> bool {
         Expr::MalformedIdent(_, _) => false,
         Expr::MalformedClosure => false,
         Expr::MalformedSuffixed(_) => false,
+        Expr::Malformed(_) => false,
         Expr::PrecedenceConflict(_) => false,
         Expr::MultipleRecordBuilders(_) => false,
         Expr::UnappliedRecordBuilder(_) => false,
@@ -976,6 +979,7 @@ impl<'a, 'b> RecursiveValueDefIter<'a, 'b> {
                 | MalformedClosure
                 | PrecedenceConflict(_)
                 | MalformedSuffixed(_)
+                | Malformed(_)
                 | EmptyDefsFinal => { /* terminal */ }
             }
         }
@@ -2430,6 +2434,7 @@ impl<'a> Malformed for Expr<'a> {
             MalformedIdent(_, _) |
             MalformedClosure |
             MalformedSuffixed(..) |
+            Malformed(_) |
             PrecedenceConflict(_) |
             MultipleRecordBuilders(_) |
             UnappliedRecordBuilder(_) => true,
@@ -1,6 +1,8 @@
-use crate::ast::{Collection, CommentOrNewline, Defs, Header, Module, Spaced, Spaces};
+use crate::ast::{
+    Collection, CommentOrNewline, Defs, Expr, Header, Module, Pattern, Spaced, Spaces, ValueDef,
+};
 use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
-use crate::expr::merge_spaces;
+use crate::expr::{merge_spaces, parse_single_def, push_single_def, ExprParseOptions, SingleDef};
 use crate::header::{
     package_entry, package_name, AppHeader, ExposedName, ExposesKeyword, GeneratesKeyword,
     HostedHeader, ImportsCollection, ImportsEntry, ImportsKeyword, ImportsKeywordItem, Keyword,
@@ -13,8 +15,9 @@ use crate::parser::Progress::{self, *};
 use crate::parser::{
     and, backtrackable, byte, collection_trailing_sep_e, increment_min_indent, loc, map,
     map_with_arena, optional, reset_min_indent, skip_first, skip_second, specialize_err, succeed,
-    two_bytes, zero_or_more, EExposes, EGenerates, EGeneratesWith, EHeader, EImports, EPackages,
-    EParams, EProvides, ERequires, ETypedIdent, Parser, SourceError, SpaceProblem, SyntaxError,
+    two_bytes, zero_or_more, EExposes, EExpr, EGenerates, EGeneratesWith, EHeader, EImports,
+    EPackages, EParams, EProvides, ERequires, ETypedIdent, Either, Parser, SourceError,
+    SpaceProblem, SyntaxError,
 };
 use crate::pattern::record_pattern_fields;
 use crate::state::State;
@@ -47,6 +50,206 @@ pub fn parse_module_defs<'a>(
     }
 }
 
+/// Parses the defs of a module like [parse_module_defs], but instead of stopping at the first
+/// syntax error, skips the top-level def it is in and carries on with the defs after it.
+///
+/// The source of each def that was skipped is kept as an [Expr::Malformed]. If it looks like
+/// `name = ...`, it becomes the body of a def of that name, so uses of `name` elsewhere in the
+/// module still resolve. Each error comes with the region of the def it made us skip.
+pub fn parse_module_defs_recovering<'a>(
+    arena: &'a bumpalo::Bump,
+    state: State<'a>,
+    defs: Defs<'a>,
+) -> (Defs<'a>, std::vec::Vec<Loc<SyntaxError<'a>>>) {
+    if let Ok(defs) = parse_module_defs(arena, state.clone(), defs.clone()) {
+        return (defs, std::vec::Vec::new());
+    }
+
+    let options = ExprParseOptions {
+        accept_multi_backpassing: true,
+        check_for_arrow: true,
+    };
+
+    let mut defs = defs;
+    let mut errors = std::vec::Vec::new();
+    let mut state = state;
+
+    while !state.has_reached_end() {
+        let def_start = state.clone();
+
+        let fail = match parse_single_def(options, 0, arena, state) {
+            Ok((_, Some(single_def), next_state)) => {
+                push_single_def(arena, &mut defs, single_def);
+                state = next_state;
+
+                continue;
+            }
+            Ok((_, None, next_state)) => {
+                // Either only whitespace is left, or something that can't start a def
+                match space0_e(EExpr::IndentEnd).parse(arena, next_state, 0) {
+                    Ok((_, _, next_state)) if next_state.has_reached_end() => break,
+                    Ok((_, _, next_state)) => SyntaxError::NotEndOfFile(next_state.pos()),
+                    Err((_, _)) => SyntaxError::NotEndOfFile(def_start.pos()),
+                }
+            }
+            Err((_, fail)) => SyntaxError::Expr(fail, def_start.pos()),
+        };
+
+        let def_len = top_level_def_len(def_start.bytes());
+        let next_state = advance_lines(def_start.clone(), def_len);
+
+        // A def can fail because it ran into the defs after it, like an unclosed bracket does.
+        // Parsing its own lines on their own tells us what's wrong with it, if anything.
+        let fail = match fail {
+            SyntaxError::Expr(..) => {
+                match parse_single_def(options, 0, arena, def_start.clone().truncate(def_len)) {
+                    Ok((_, Some(single_def), rest))
+                        if rest.bytes().iter().all(u8::is_ascii_whitespace) =>
+                    {
+                        push_single_def(arena, &mut defs, single_def);
+                        state = next_state;
+
+                        continue;
+                    }
+                    Err((_, fail)) => SyntaxError::Expr(fail, def_start.pos()),
+                    Ok(_) => fail,
+                }
+            }
+            _ => fail,
+        };
+
+        let (region, malformed) = malformed_def(arena, def_start, def_len);
+
+        errors.push(Loc::at(region, fail));
+
+        if let Some(single_def) = malformed {
+            push_single_def(arena, &mut defs, single_def);
+        }
+
+        state = next_state;
+    }
+
+    (defs, errors)
+}
+
+/// The length of the whitespace and comments at the start of `bytes`.
+fn leading_trivia_len(bytes: &[u8]) -> usize {
+    let mut len = 0;
+
+    loop {
+        match bytes.get(len) {
+            Some(b' ' | b'\t' | b'\r' | b'\n') => len += 1,
+            Some(b'#') => {
+                while bytes.get(len).map_or(false, |byte| *byte != b'\n') {
+                    len += 1;
+                }
+            }
+            _ => return len,
+        }
+    }
+}
+
+/// The length of the top-level def at the start of `bytes`, up to the next line that starts with
+/// something that could begin a def. Defs continue on indented lines.
+fn top_level_def_len(bytes: &[u8]) -> usize {
+    let mut end = leading_trivia_len(bytes);
+
+    while let Some(newline) = bytes[end..].iter().position(|byte| *byte == b'\n') {
+        end += newline + 1;
+
+        if bytes
+            .get(end)
+            .map_or(false, |byte| byte.is_ascii_alphabetic() || *byte == b'_')
+        {
+            return end;
+        }
+    }
+
+    bytes.len()
+}
+
+fn advance_lines(state: State<'_>, length: usize) -> State<'_> {
+    let mut state = state;
+
+    for line in state.bytes()[..length].split_inclusive(|byte| *byte == b'\n') {
+        state = match line.split_last() {
+            Some((b'\n', rest)) => state.advance(rest.len()).advance_newline(),
+            _ => state.advance(line.len()),
+        };
+    }
+
+    state
+}
+
+/// The region of the top-level def of length `def_len` that failed to parse, and the def as a
+/// malformed one if it wasn't just whitespace and comments.
+fn malformed_def<'a>(
+    arena: &'a bumpalo::Bump,
+    state: State<'a>,
+    def_len: usize,
+) -> (Region, Option<SingleDef<'a>>) {
+    let bytes = &state.bytes()[..def_len];
+
+    // The whitespace and comments before the def aren't part of it
+    let start = leading_trivia_len(bytes);
+
+    let text = std::str::from_utf8(&bytes[start..])
+        .unwrap_or_default()
+        .trim_end();
+
+    let offset = state.pos().offset + start as u32;
+    let region = Region::new(
+        Position::new(offset),
+        Position::new(offset + text.len() as u32),
+    );
+
+    let malformed = (!text.is_empty()).then(|| SingleDef {
+        type_or_value: Either::Second(malformed_value_def(arena, text, region)),
+        region,
+        spaces_before: &[],
+        spaces_after: &[],
+    });
+
+    (region, malformed)
+}
+
+/// A def of `name` with a malformed body if `text` looks like `name = ...`, and a malformed
+/// statement otherwise.
+fn malformed_value_def<'a>(
+    arena: &'a bumpalo::Bump,
+    text: &'a str,
+    region: Region,
+) -> ValueDef<'a> {
+    let ident_len = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    let (ident, rest) = text.split_at(ident_len);
+    let rest = rest.trim_start();
+
+    let is_assignment = ident.starts_with(|c: char| c.is_ascii_lowercase())
+        && rest.starts_with('=')
+        && !rest.starts_with("==");
+
+    if is_assignment {
+        let body = rest[1..].trim_start();
+        let ident_end = region.start().bump_column(ident_len as u32);
+        let body_start = Position::new(region.end().offset - body.len() as u32);
+
+        ValueDef::Body(
+            arena.alloc(Loc::at(
+                Region::new(region.start(), ident_end),
+                Pattern::Identifier { ident },
+            )),
+            arena.alloc(Loc::at(
+                Region::new(body_start, region.end()),
+                Expr::Malformed(body),
+            )),
+        )
+    } else {
+        ValueDef::Stmt(arena.alloc(Loc::at(region, Expr::Malformed(text))))
+    }
+}
+
 pub fn parse_header<'a>(
     arena: &'a bumpalo::Bump,
     state: State<'a>,
@@ -112,6 +112,13 @@ impl<'a> State<'a> {
         self
     }
 
+    /// Cuts the input off `length` bytes after the current position, so parsing stops there.
+    #[must_use]
+    pub(crate) fn truncate(mut self, length: usize) -> State<'a> {
+        self.original_bytes = &self.original_bytes[..self.offset + length];
+        self
+    }
+
     /// Returns the current position
     pub const fn pos(&self) -> Position {
         Position::new(self.offset as u32)
@@ -19,10 +19,11 @@ mod test_parse {
     use bumpalo::collections::vec::Vec;
     use bumpalo::{self, Bump};
     use roc_parse::ast::Expr::{self, *};
+    use roc_parse::ast::Pattern;
     use roc_parse::ast::StrSegment::*;
     use roc_parse::ast::{self, EscapedChar};
     use roc_parse::ast::{CommentOrNewline, StrLiteral::*};
-    use roc_parse::module::parse_module_defs;
+    use roc_parse::module::{parse_module_defs, parse_module_defs_recovering};
     use roc_parse::parser::SyntaxError;
     use roc_parse::state::State;
     use roc_parse::test_helpers::parse_expr_with;
@@ -381,6 +382,90 @@ mod test_parse {
         }
     }
 
+    #[test]
+    fn recover_from_syntax_errors_in_module_defs() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r"
+            first = [1, 2
+
+            second = 2
+
+            # comments before a def aren't part of it
+            third =
+                x = 3 +
+
+                x
+
+            fourth = second
+            "
+        );
+
+        let state = State::new(src.as_bytes());
+        let (defs, errors) = parse_module_defs_recovering(arena, state, ast::Defs::default());
+
+        let error_lines: std::vec::Vec<_> = errors
+            .iter()
+            .map(|error| {
+                &src[error.region.start().offset as usize..error.region.end().offset as usize]
+            })
+            .collect();
+
+        assert_eq!(
+            error_lines,
+            ["first = [1, 2", "third =\n    x = 3 +\n\n    x"]
+        );
+
+        let bodies: std::vec::Vec<_> = defs
+            .value_defs
+            .iter()
+            .map(|def| match def {
+                ast::ValueDef::Body(pattern, body) => (pattern.value, body.value),
+                other => panic!("expected a body, got {other:?}"),
+            })
+            .collect();
+
+        assert_eq!(
+            bodies,
+            [
+                (Pattern::Identifier { ident: "first" }, Malformed("[1, 2")),
+                (Pattern::Identifier { ident: "second" }, Num("2")),
+                (
+                    Pattern::Identifier { ident: "third" },
+                    Malformed("x = 3 +\n\n    x")
+                ),
+                (
+                    Pattern::Identifier { ident: "fourth" },
+                    Var {
+                        module_name: "",
+                        ident: "second"
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn recovering_parse_of_valid_module_defs() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r"
+            # a comment
+            x : Str
+            x = 1
+
+            y = x
+            "
+        );
+
+        let expected = parse_module_defs(arena, State::new(src.as_bytes()), ast::Defs::default());
+        let actual =
+            parse_module_defs_recovering(arena, State::new(src.as_bytes()), ast::Defs::default());
+
+        assert_eq!(expected, Ok(actual.0));
+        assert!(actual.1.is_empty());
+    }
+
     #[test]
     fn parse_expr_size() {
         assert_eq!(std::mem::size_of::<roc_parse::ast::Expr>(), 40);
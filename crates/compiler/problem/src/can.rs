@@ -403,6 +403,7 @@ impl Problem {
             | Problem::RuntimeError(RuntimeError::MalformedTypeName(_, region))
             | Problem::RuntimeError(RuntimeError::MalformedClosure(region))
             | Problem::RuntimeError(RuntimeError::MalformedSuffixed(region))
+            | Problem::RuntimeError(RuntimeError::MalformedExpr(region))
             | Problem::RuntimeError(RuntimeError::InvalidRecordUpdate { region })
             | Problem::RuntimeError(RuntimeError::InvalidFloat(_, region, _))
             | Problem::RuntimeError(RuntimeError::InvalidInt(_, _, region, _))
@@ -667,6 +668,9 @@ pub enum RuntimeError {
     UnappliedRecordBuilder(Region),
 
     MalformedSuffixed(Region),
+
+    /// A top-level def that could not be parsed, kept while recovering from the syntax error.
+    MalformedExpr(Region),
 }
 
 impl RuntimeError {
@@ -701,6 +705,7 @@ impl RuntimeError {
             | RuntimeError::MalformedTypeName(_, region)
             | RuntimeError::MalformedClosure(region)
             | RuntimeError::MalformedSuffixed(region)
+            | RuntimeError::MalformedExpr(region)
             | RuntimeError::InvalidRecordUpdate { region }
             | RuntimeError::InvalidFloat(_, region, _)
             | RuntimeError::InvalidInt(_, _, region, _)
@@ -1443,6 +1443,82 @@ fn decode_tuple_of_tuples() {
     )
 }
 
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_union() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "{\"Answer\":[\"ab\",10]}" |> Decode.fromBytes TotallyNotJson.json is
+                    Ok (Answer "ab" 10u8) -> "ab10"
+                    Ok Question -> "question"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("ab10"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_without_payload() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "{\"Question\":[]}" |> Decode.fromBytes TotallyNotJson.json is
+                    Ok (Answer "ab" 10u8) -> "ab10"
+                    Ok Question -> "question"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("question"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_recursive_tag_union() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [TotallyNotJson] provides [main] to "./platform"
+
+            Peano : [Zero, Succ Peano]
+
+            toStr : Peano -> Str
+            toStr = \n ->
+                when n is
+                    Zero -> "Z"
+                    Succ inner -> Str.concat "S" (toStr inner)
+
+            main =
+                when Str.toUtf8 "{\"Succ\":[{\"Succ\":[{\"Zero\":[]}]}]}" |> Decode.fromBytes TotallyNotJson.json is
+                    Ok n -> toStr n
+                    Err _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("SSZ"),
+        RocStr
+    )
+}
+
 #[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
 mod hash {
     #[cfg(feature = "gen-llvm")]
@@ -0,0 +1,170 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::object_with_debug_info;
+
+#[cfg(feature = "gen-llvm")]
+use indoc::indoc;
+
+/// The DWARF sections of an object file
+#[cfg(feature = "gen-llvm")]
+fn load_dwarf(object_bytes: &[u8]) -> (gimli::Dwarf<Vec<u8>>, gimli::RunTimeEndian) {
+    use object::{Object, ObjectSection, ObjectSymbol, RelocationTarget};
+
+    let file = object::File::parse(object_bytes).unwrap();
+    let endian = if file.is_little_endian() {
+        gimli::RunTimeEndian::Little
+    } else {
+        gimli::RunTimeEndian::Big
+    };
+
+    let load_section = |id: gimli::SectionId| -> Result<Vec<u8>, gimli::Error> {
+        let Some(section) = file.section_by_name(id.name()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut data = section.uncompressed_data().unwrap().into_owned();
+
+        // In an object file, references into other sections (like the names of variables,
+        // which are offsets into .debug_str) are still relocations, so apply them here.
+        for (offset, relocation) in section.relocations() {
+            let RelocationTarget::Symbol(symbol_index) = relocation.target() else {
+                continue;
+            };
+
+            let symbol_address = file.symbol_by_index(symbol_index).unwrap().address();
+            let value = (symbol_address as i64 + relocation.addend()) as u64;
+            let offset = offset as usize;
+
+            match relocation.size() {
+                32 => data[offset..offset + 4].copy_from_slice(&match endian {
+                    gimli::RunTimeEndian::Little => (value as u32).to_le_bytes(),
+                    gimli::RunTimeEndian::Big => (value as u32).to_be_bytes(),
+                }),
+                64 => data[offset..offset + 8].copy_from_slice(&match endian {
+                    gimli::RunTimeEndian::Little => value.to_le_bytes(),
+                    gimli::RunTimeEndian::Big => value.to_be_bytes(),
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(data)
+    };
+
+    (gimli::Dwarf::load(load_section).unwrap(), endian)
+}
+
+/// The lines in the line table of an object file that belong to `file_name`
+#[cfg(feature = "gen-llvm")]
+fn line_table_lines(object_bytes: &[u8], file_name: &str) -> Vec<u64> {
+    let (dwarf_sections, endian) = load_dwarf(object_bytes);
+    let dwarf = dwarf_sections.borrow(|section| gimli::EndianSlice::new(section, endian));
+
+    let mut lines = Vec::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().unwrap() {
+        let unit = dwarf.unit(header).unwrap();
+        let Some(program) = unit.line_program.clone() else {
+            continue;
+        };
+
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row().unwrap() {
+            let (Some(file), Some(line)) = (row.file(header), row.line()) else {
+                continue;
+            };
+
+            let path = dwarf.attr_string(&unit, file.path_name()).unwrap();
+            if path.to_string_lossy() == file_name {
+                lines.push(line.get());
+            }
+        }
+    }
+
+    lines
+}
+
+/// The name and line of each `DW_TAG_variable` in an object file, i.e. of its local variables
+#[cfg(feature = "gen-llvm")]
+fn local_variables(object_bytes: &[u8]) -> Vec<(String, u64)> {
+    let (dwarf_sections, endian) = load_dwarf(object_bytes);
+    let dwarf = dwarf_sections.borrow(|section| gimli::EndianSlice::new(section, endian));
+
+    let mut variables = Vec::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().unwrap() {
+        let unit = dwarf.unit(header).unwrap();
+
+        let mut entries = unit.entries();
+        while let Some((_, entry)) = entries.next_dfs().unwrap() {
+            if entry.tag() != gimli::DW_TAG_variable {
+                continue;
+            }
+
+            let name = entry.attr_value(gimli::DW_AT_name).unwrap();
+            let line = entry.attr(gimli::DW_AT_decl_line).unwrap();
+            let (Some(name), Some(line)) = (name, line.and_then(|attr| attr.udata_value())) else {
+                continue;
+            };
+
+            let name = dwarf.attr_string(&unit, name).unwrap();
+            variables.push((name.to_string_lossy().into_owned(), line));
+        }
+    }
+
+    variables
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn let_statements_have_their_own_lines() {
+    let object_bytes = object_with_debug_info(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            x = Num.add 1i64 2
+
+            y = Num.mul x 3
+
+            Num.sub y 4
+        "#
+    ));
+
+    let lines = line_table_lines(&object_bytes, "Test.roc");
+
+    // `x` and `y` are defined on lines 4 and 6
+    for line in [4, 6] {
+        assert!(
+            lines.contains(&line),
+            "line {line} is missing from {lines:?}"
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn let_bound_locals_are_variables() {
+    let object_bytes = object_with_debug_info(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            x = Num.add 1i64 2
+
+            y = Num.mul x 3
+
+            Num.sub y 4
+        "#
+    ));
+
+    let variables = local_variables(&object_bytes);
+
+    for variable in [("x", 4), ("y", 6)] {
+        assert!(
+            variables
+                .iter()
+                .any(|(name, line)| (name.as_str(), *line) == variable),
+            "{variable:?} is missing from {variables:?}"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::run_toplevel_expects;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::run_toplevel_expects;
+
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+use crate::helpers::ExpectOutcome;
+
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+use indoc::indoc;
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn expect_pass() {
+    let outcomes = run_toplevel_expects(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = 0
+
+        expect
+            a = 1
+            b = 1
+
+            a == b
+        "#
+    ));
+
+    assert_eq!(
+        outcomes,
+        [ExpectOutcome {
+            failures: 0,
+            lookups: vec![],
+        }]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn expect_fail_with_lookups() {
+    let outcomes = run_toplevel_expects(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = 0
+
+        expect
+            a = 1
+            b = 2
+
+            a == b
+        "#
+    ));
+
+    assert_eq!(
+        outcomes,
+        [ExpectOutcome {
+            failures: 1,
+            lookups: vec![1, 2],
+        }]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn inline_expect_fail_with_lookups() {
+    let outcomes = run_toplevel_expects(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = 0
+
+        double = \x ->
+            y = x + x
+            expect y == 4
+
+            y
+
+        expect double 3 == 6
+        "#
+    ));
+
+    assert_eq!(
+        outcomes,
+        [ExpectOutcome {
+            failures: 1,
+            lookups: vec![6],
+        }]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn dbg_then_expect_fail() {
+    let outcomes = run_toplevel_expects(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = 0
+
+        expect
+            a = 40
+            b = a + 2
+            dbg b
+
+            b == 41
+        "#
+    ));
+
+    assert_eq!(
+        outcomes,
+        [ExpectOutcome {
+            failures: 1,
+            lookups: vec![42],
+        }]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn multiple_expects() {
+    let outcomes = run_toplevel_expects(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main = 0
+
+        expect
+            x = 3
+            dbg x
+
+            x == 3
+
+        expect
+            x = 5
+
+            x == 3
+        "#
+    ));
+
+    assert_eq!(outcomes.iter().map(|o| o.failures).sum::<usize>(), 1);
+}
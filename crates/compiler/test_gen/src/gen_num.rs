@@ -480,13 +480,13 @@ fn f64_sqrt_checked_0() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn f64_sqrt_checked_positive() {
     assert_evals_to!("Num.sqrtChecked 100f64", RocResult::ok(10.0), RocResult<f64, ()>);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn f64_sqrt_checked_negative() {
     assert_evals_to!("Num.sqrtChecked -1f64", RocResult::err(()), RocResult<f64, ()>);
 }
@@ -498,19 +498,19 @@ fn f64_log() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn f64_log_checked_one() {
     assert_evals_to!("Num.logChecked 1f64", RocResult::ok(0.0), RocResult<f64, ()>);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn f64_log_checked_zero() {
     assert_evals_to!("Num.logChecked 0f64", RocResult::err(()), RocResult<f64, ()>);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn f64_log_negative() {
     assert_evals_to!("Num.log -1f64", true, f64, |f: f64| f.is_nan());
 }
@@ -546,7 +546,7 @@ fn f32_abs() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn i64_abs() {
     assert_evals_to!("Num.abs -6", 6, i64);
     assert_evals_to!("Num.abs 7", 7, i64);
@@ -561,26 +561,22 @@ fn i64_abs() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn various_sized_abs() {
     assert_evals_to!("Num.abs -6i8", 6, i8);
     assert_evals_to!("Num.abs -6i16", 6, i16);
     assert_evals_to!("Num.abs -6i32", 6, i32);
     assert_evals_to!("Num.abs -6i64", 6, i64);
-    if !cfg!(feature = "gen-wasm") {
-        assert_evals_to!("Num.abs -6i128", I128::from(6), I128);
-    }
+    assert_evals_to!("Num.abs -6i128", I128::from(6), I128);
     assert_evals_to!("Num.abs 6u8", 6, u8);
     assert_evals_to!("Num.abs 6u16", 6, u16);
     assert_evals_to!("Num.abs 6u32", 6, u32);
     assert_evals_to!("Num.abs 6u64", 6, u64);
-    if !cfg!(feature = "gen-wasm") {
-        assert_evals_to!("Num.abs 6u128", U128::from(6), U128);
-    }
+    assert_evals_to!("Num.abs 6u128", U128::from(6), U128);
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 #[should_panic(
     expected = r#"Roc failed with message: "Integer absolute overflowed because its argument is the minimum value"#
 )]
@@ -621,7 +617,7 @@ fn gen_if_fn() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn gen_float_eq() {
     assert_evals_to!(
         indoc!(
@@ -635,7 +631,7 @@ fn gen_float_eq() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn gen_add_dec() {
     assert_evals_to!(
         indoc!(
@@ -696,7 +692,7 @@ fn gen_div_f32() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn gen_div_checked_f64() {
     assert_evals_to!(
         indoc!(
@@ -712,7 +708,7 @@ fn gen_div_checked_f64() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn gen_div_checked_by_zero_f64() {
     assert_evals_to!(
         indoc!(
@@ -1345,6 +1341,22 @@ fn bitwise_or() {
     assert_evals_to!("Num.bitwiseOr 1 2", 3, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn bitwise_i128() {
+    assert_evals_to!(
+        "Num.bitwiseAnd 0xffff_0000_0000_0000_0000_0000_0000_ffffi128 0xff00_0000_0000_0000_ffff_0000_0000_ff00i128",
+        I128::from(0xff00_0000_0000_0000_0000_0000_0000_ff00i128),
+        I128
+    );
+    assert_evals_to!(
+        "Num.bitwiseOr 0xf000_0000_0000_0000_0000_0000_0000_000fu128 0x0000_0000_0000_00f0_0f00_0000_0000_0000u128",
+        U128::from(0xf000_0000_0000_00f0_0f00_0000_0000_000fu128),
+        U128
+    );
+    assert_evals_to!("Num.bitwiseXor -1i128 1", I128::from(-2), I128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn lt_u8() {
@@ -1625,7 +1637,7 @@ fn int_negate() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 #[should_panic(
     expected = r#"Roc failed with message: "Integer negation overflowed because its argument is the minimum value"#
 )]
@@ -1687,7 +1699,7 @@ fn num_to_frac() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn num_to_frac_f64_to_f32() {
     assert_evals_to!(
         indoc!(
@@ -1706,7 +1718,7 @@ fn num_to_frac_f64_to_f32() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn num_to_frac_f32_to_f32() {
     assert_evals_to!(
         indoc!(
@@ -1939,7 +1951,18 @@ fn int_add_wrap() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn int_add_wrap_i128() {
+    assert_evals_to!("Num.addWrap Num.maxI128 1", I128::from(i128::MIN), I128);
+    assert_evals_to!(
+        "Num.addWrap 0xffff_ffff_ffff_ffffu128 1",
+        U128::from(1u128 << 64),
+        U128
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn float_add_checked_pass() {
     assert_evals_to!(
         "Num.addChecked 1.0 0.0f64",
@@ -1949,7 +1972,7 @@ fn float_add_checked_pass() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn float_add_checked_fail() {
     assert_evals_to!(
         "Num.addChecked 1.7976931348623157e308f64 1.7976931348623157e308",
@@ -1959,7 +1982,7 @@ fn float_add_checked_fail() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn float_add_overflow() {
     assert_evals_to!(
         "1.7976931348623157e308f64 + 1.7976931348623157e308",
@@ -1987,6 +2010,18 @@ fn int_sub_wrap() {
     assert_evals_to!("Num.subWrap -128i8 1", std::i8::MAX, i8);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn int_sub_wrap_i128() {
+    assert_evals_to!("Num.subWrap Num.minI128 1", I128::from(i128::MAX), I128);
+    assert_evals_to!("Num.subWrap 0u128 1", U128::from(u128::MAX), U128);
+    assert_evals_to!(
+        "Num.subWrap 0x1_0000_0000_0000_0000u128 1",
+        U128::from(u64::MAX as u128),
+        U128
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn float_sub_overflow() {
@@ -2014,7 +2049,7 @@ fn int_sub_checked() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn float_sub_checked() {
     assert_evals_to!(
         "Num.subChecked 1.0 0.0f64",
@@ -2084,7 +2119,7 @@ fn int_mul_checked() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn float_mul_checked() {
     assert_evals_to!(
         "Num.mulChecked 20.0 2.0f64",
@@ -2190,6 +2225,33 @@ fn shift_right_cast_i8() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn shift_i128() {
+    assert_evals_to!("Num.shiftLeftBy 1i128 100", I128::from(1i128 << 100), I128);
+    assert_evals_to!("Num.shiftLeftBy 1u128 200", U128::from(0), U128);
+    assert_evals_to!("Num.shiftRightBy -256i128 4", I128::from(-16), I128);
+    assert_evals_to!("Num.shiftRightBy Num.minI128 200", I128::from(-1), I128);
+    assert_evals_to!(
+        "Num.shiftRightBy 0x8000_0000_0000_0000_0000_0000_0000_0000u128 64",
+        U128::from(0xffff_ffff_ffff_ffff_8000_0000_0000_0000u128),
+        U128
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn div_trunc_and_rem_i128() {
+    assert_evals_to!("Num.divTrunc -7i128 2", I128::from(-3), I128);
+    assert_evals_to!("Num.rem -7i128 2", I128::from(-1), I128);
+    assert_evals_to!(
+        "Num.divTrunc Num.maxU128 2",
+        U128::from(u128::MAX / 2),
+        U128
+    );
+    assert_evals_to!("Num.rem Num.maxU128 10", U128::from(u128::MAX % 10), U128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn min_i128() {
@@ -2360,12 +2422,12 @@ num_conversion_tests! {
         to_i64_sign_extend_i8, "-15i8", -15, ["gen-wasm", "gen-dev"]
         to_i64_sign_extend_i16, "-15i16", -15, ["gen-wasm", "gen-dev"]
         to_i64_sign_extend_i32, "-15i32", -15, ["gen-wasm", "gen-dev"]
-        to_i64_truncate, "115i128", 115
-        to_i64_truncate_wraps, "10_000_000_000_000_000_000i128", -8446744073709551616
+        to_i64_truncate, "115i128", 115, ["gen-wasm", "gen-dev"]
+        to_i64_truncate_wraps, "10_000_000_000_000_000_000i128", -8446744073709551616, ["gen-wasm", "gen-dev"]
     )
     "Num.toI128", i128, (
-        to_i128_same_width, "15u128", 15, ["gen-dev"]
-        to_i128_extend, "15i8", 15
+        to_i128_same_width, "15u128", 15, ["gen-wasm", "gen-dev"]
+        to_i128_extend, "15i8", 15, ["gen-wasm", "gen-dev"]
     )
     "Num.toU8", u8, (
         to_u8_same_width, "15i8", 15, ["gen-wasm", "gen-dev"]
@@ -2387,25 +2449,25 @@ num_conversion_tests! {
     "Num.toU64", u64, (
         to_u64_same_width, "15i64", 15, ["gen-wasm", "gen-dev"]
         to_u64_extend, "15i8", 15, ["gen-wasm", "gen-dev"]
-        to_u64_truncate, "115i128", 115
-        to_u64_truncate_wraps, "10_000_000_000_000_000_000_000i128", 1864712049423024128
+        to_u64_truncate, "115i128", 115, ["gen-wasm", "gen-dev"]
+        to_u64_truncate_wraps, "10_000_000_000_000_000_000_000i128", 1864712049423024128, ["gen-wasm", "gen-dev"]
     )
     "Num.toU128", u128, (
-        to_u128_same_width, "15i128", 15, ["gen-dev"]
-        to_u128_extend, "15i8", 15
-        to_u128_big, "11562537357600483583u64", 11562537357600483583, ["gen-dev"]
+        to_u128_same_width, "15i128", 15, ["gen-wasm", "gen-dev"]
+        to_u128_extend, "15i8", 15, ["gen-wasm", "gen-dev"]
+        to_u128_big, "11562537357600483583u64", 11562537357600483583, ["gen-wasm", "gen-dev"]
     )
     "Num.toF32", f32, (
         to_f32_from_i8, "15i8", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_i16, "15i16", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_i32, "15i32", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_i64, "15i64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f32_from_i128, "15i128", 15.0, ["gen-dev"]
+        to_f32_from_i128, "15i128", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u8, "15u8", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u16, "15u16", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u32, "15u32", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_u64, "15u64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f32_from_u128, "15u128", 15.0, ["gen-dev"]
+        to_f32_from_u128, "15u128", 15.0, ["gen-wasm", "gen-dev"]
         to_f32_from_f32, "1.5f32", 1.5, ["gen-wasm", "gen-dev"]
         to_f32_from_f64, "1.5f64", 1.5, ["gen-wasm", "gen-dev"]
     )
@@ -2414,21 +2476,46 @@ num_conversion_tests! {
         to_f64_from_i16, "15i16", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_i32, "15i32", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_i64, "15i64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f64_from_i128, "15i128", 15.0, ["gen-dev"]
+        to_f64_from_i128, "15i128", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u8, "15u8", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u16, "15u16", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u32, "15u32", 15.0, ["gen-wasm", "gen-dev"]
         to_f64_from_u64, "15u64", 15.0, ["gen-wasm", "gen-dev"]
-        to_f64_from_u128, "15u128", 15.0, ["gen-dev"]
-        to_f64_from_f32, "1.5f32", 1.5, ["gen-dev"]
+        to_f64_from_u128, "15u128", 15.0, ["gen-wasm", "gen-dev"]
+        to_f64_from_f32, "1.5f32", 1.5, ["gen-wasm", "gen-dev"]
         to_f64_from_f64, "1.5f64", 1.5, ["gen-wasm", "gen-dev"]
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn to_float_checked() {
+    assert_evals_to!(
+        "Num.toF32Checked 1.5f64",
+        RocResult::ok(1.5),
+        RocResult<f32, ()>
+    );
+    assert_evals_to!(
+        "Num.toF32Checked Num.maxF64",
+        RocResult::err(()),
+        RocResult<f32, ()>
+    );
+    assert_evals_to!(
+        "Num.toF64Checked 15i128",
+        RocResult::ok(15.0),
+        RocResult<f64, ()>
+    );
+    assert_evals_to!(
+        "Num.toF64Checked Num.maxU128",
+        RocResult::ok(u128::MAX as f64),
+        RocResult<f64, ()>
+    );
+}
+
 macro_rules! to_int_checked_tests {
     ($($fn:expr, $typ:ty, ($($test_name:ident, $input:expr, $output:expr)*))*) => {$($(
         #[test]
-        #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+        #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
         fn $test_name() {
             let sentinel = 23;
             // Some n = Ok n, None = OutOfBounds
@@ -2549,35 +2636,35 @@ fn wrap_with_default(test_roc_code: &str) -> String {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_smaller_width_pos() {
     let test_roc_code = wrap_with_default("Num.toI128Checked 15i8");
     assert_evals_to!(&test_roc_code, I128::from(15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_smaller_width_neg() {
     let test_roc_code = wrap_with_default("Num.toI128Checked -15i8");
     assert_evals_to!(&test_roc_code, I128::from(-15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_same() {
     let test_roc_code = wrap_with_default("Num.toI128Checked 15i128");
     assert_evals_to!(&test_roc_code, I128::from(15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_same_width_unsigned_fits() {
     let test_roc_code = wrap_with_default("Num.toI128Checked 15u128");
     assert_evals_to!(&test_roc_code, I128::from(15), I128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_i128_checked_same_width_unsigned_oob() {
     let test_roc_code =
         "Result.isErr (Num.toI128Checked 170141183460469231731687303715884105728u128)";
@@ -2585,35 +2672,35 @@ fn to_i128_checked_same_width_unsigned_oob() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_smaller_width_pos() {
     let test_roc_code = wrap_with_default("Num.toU128Checked 15i8");
     assert_evals_to!(&test_roc_code, U128::from(15), U128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_smaller_width_neg_oob() {
     let test_roc_code = "Result.isErr (Num.toU128Checked -15i8)";
     assert_evals_to!(&test_roc_code, true, bool)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_same() {
     let test_roc_code = wrap_with_default("Num.toU128Checked 15u128");
     assert_evals_to!(&test_roc_code, U128::from(15), U128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_same_width_signed_fits() {
     let test_roc_code = wrap_with_default("Num.toU128Checked 15i128");
     assert_evals_to!(&test_roc_code, U128::from(15), U128)
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn to_u128_checked_same_width_signed_oob() {
     let test_roc_code = "Result.isErr (Num.toU128Checked -1i128)";
     assert_evals_to!(&test_roc_code, true, bool)
@@ -3269,7 +3356,7 @@ fn upcast_of_int_is_zext() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 // https://github.com/roc-lang/roc/issues/2696
 fn upcast_of_int_checked_is_zext() {
     assert_evals_to!(
@@ -3558,7 +3645,7 @@ fn num_abs_diff_int() {
 }
 
 #[test]
-#[cfg(feature = "gen-llvm")]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn num_abs_diff_large_bits() {
     assert_evals_to!(r"Num.absDiff 0u128 0u128", U128::from(0), U128);
     assert_evals_to!(r"Num.absDiff 1u128 2u128", U128::from(1), U128);
@@ -3595,7 +3682,7 @@ fn num_abs_int_min_overflow() {
 }
 
 #[test]
-#[cfg(feature = "gen-llvm")]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 #[should_panic(expected = r#"Roc failed with message: "Integer subtraction overflowed!"#)]
 fn num_abs_large_bits_min_overflow() {
     assert_evals_to!(r"Num.absDiff Num.minI128 0", I128::from(0), I128);
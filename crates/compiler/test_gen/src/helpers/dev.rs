@@ -10,7 +10,8 @@ use roc_std::RocStr;
 use std::mem::MaybeUninit;
 use tempfile::tempdir;
 
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+use super::{decode_expect_buffer, reset_expect_buffer, ExpectOutcome};
+
 use roc_collections::all::MutMap;
 
 #[allow(unused_imports)]
@@ -196,6 +197,7 @@ pub fn helper(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Test,
+        sources: MutMap::default(),
     };
 
     let target = target_lexicon::Triple::host().into();
@@ -239,6 +241,96 @@ pub fn helper(
     (main_fn_name, delayed_errors, lib)
 }
 
+/// Build the toplevel expects of a module like `roc test --dev` does, and run each of them
+#[allow(dead_code)]
+pub fn run_toplevel_expects(src: &str) -> Vec<ExpectOutcome> {
+    use std::path::PathBuf;
+
+    let arena = bumpalo::Bump::new();
+    let arena = &arena;
+    let target = target_lexicon::Triple::host().into();
+
+    let load_config = LoadConfig {
+        target,
+        render: roc_reporting::report::RenderTarget::ColorTerminal,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        function_kind: FunctionKind::LambdaSet,
+    };
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        src,
+        PathBuf::from("fake/test/path"),
+        None,
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .expect("failed to load module");
+
+    let roc_load::MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        mut layout_interner,
+        toplevel_expects,
+        ..
+    } = loaded;
+
+    let expects: Vec<_> = toplevel_expects
+        .values()
+        .flat_map(|expects| expects.pure.keys().copied())
+        .collect();
+
+    let env = roc_gen_dev::Env {
+        arena,
+        module_id,
+        exposed_to_host: expects.iter().copied().collect(),
+        lazy_literals: false,
+        mode: roc_gen_dev::AssemblyBackendMode::CliTest,
+        sources: MutMap::default(),
+    };
+
+    let module_object =
+        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures);
+    let module_out = module_object
+        .write()
+        .expect("failed to build output object");
+
+    let lib = roc_build::link::dev_object_to_dylib(&module_out, target)
+        .expect("failed to load shared library");
+
+    let ptr_size = std::mem::size_of::<usize>();
+    let mut buffer = vec![0u8; 1024];
+
+    unsafe {
+        let set_shared_buffer: libloading::Symbol<unsafe extern "C" fn(*mut u8, usize) -> usize> =
+            lib.get(b"set_shared_buffer").unwrap();
+        set_shared_buffer(buffer.as_mut_ptr(), buffer.len());
+    }
+
+    let mut outcomes = Vec::with_capacity(expects.len());
+    for expect in expects {
+        reset_expect_buffer(&mut buffer, ptr_size);
+
+        let name = roc_gen_dev::toplevel_expect_name(&interns, expect);
+        let result = unsafe {
+            let expect_fn: libloading::Symbol<unsafe extern "C" fn() -> RocCallResult<()>> =
+                lib.get(name.as_bytes()).unwrap();
+            expect_fn().into_result()
+        };
+
+        if let Err((msg, _)) = result {
+            panic!(r#"Roc failed with message: "{msg}""#);
+        }
+
+        outcomes.push(decode_expect_buffer(&buffer, ptr_size));
+    }
+
+    outcomes
+}
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct RocCallResult<T> {
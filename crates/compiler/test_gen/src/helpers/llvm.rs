@@ -79,7 +79,7 @@ fn create_llvm_module<'a>(
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
-        filename,
+        filename.clone(),
         module_src,
         src_dir,
         None,
@@ -111,9 +111,10 @@ fn create_llvm_module<'a>(
     // errors whose reporting we delay (so we can see that code gen generates runtime errors)
     let mut delayed_errors = Vec::new();
 
-    for (home, (module_path, src)) in loaded.sources {
+    for (home, (module_path, src)) in loaded.sources.iter() {
         use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 
+        let home = *home;
         let can_problems = loaded.can_problems.remove(&home).unwrap_or_default();
         let type_problems = loaded.type_problems.remove(&home).unwrap_or_default();
 
@@ -123,7 +124,7 @@ fn create_llvm_module<'a>(
             continue;
         }
 
-        let line_info = LineInfo::new(&src);
+        let line_info = LineInfo::new(src);
         let src_lines: Vec<&str> = src.split('\n').collect();
         let palette = DEFAULT_PALETTE;
 
@@ -188,7 +189,13 @@ fn create_llvm_module<'a>(
     let (module_pass, function_pass) =
         roc_gen_llvm::llvm::build::construct_optimization_passes(module, config.opt_level);
 
-    let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let (dibuilder, compile_unit) =
+        roc_gen_llvm::llvm::build::Env::new_debug_info(module, &filename);
+    let debug_sources = roc_gen_llvm::llvm::debug_info::DebugSources::new(
+        &dibuilder,
+        &loaded.sources,
+        &loaded.symbol_regions,
+    );
 
     // mark our zig-defined builtins as internal
     use inkwell::attributes::{Attribute, AttributeLoc};
@@ -223,6 +230,7 @@ fn create_llvm_module<'a>(
         builder: &builder,
         dibuilder: &dibuilder,
         compile_unit: &compile_unit,
+        debug_sources: &debug_sources,
         context,
         interns,
         module,
@@ -349,6 +357,44 @@ pub fn helper<'a>(
     (main_fn_name, delayed_errors, lib)
 }
 
+/// Compile a module with debug info to an object file for the host
+#[allow(dead_code)]
+pub fn object_with_debug_info(src: &str) -> Vec<u8> {
+    use inkwell::targets::{FileType, RelocMode};
+
+    let arena = bumpalo::Bump::new();
+    let context = inkwell::context::Context::create();
+    let target = target_lexicon::Triple::host().into();
+    let config = HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        ignore_problems: false,
+        emit_debug_info: true,
+        opt_level: OptLevel::Development,
+    };
+
+    let (_main_fn_name, _delayed_errors, module) = create_llvm_module(
+        &arena,
+        src,
+        config,
+        &context,
+        target,
+        FunctionKind::LambdaSet,
+    );
+
+    let target_machine = roc_build::target::target_machine(
+        target,
+        roc_build::target::convert_opt_level(config.opt_level),
+        RelocMode::PIC,
+    )
+    .unwrap();
+
+    target_machine
+        .write_to_memory_buffer(module, FileType::Object)
+        .unwrap()
+        .as_slice()
+        .to_vec()
+}
+
 #[allow(dead_code)]
 fn write_final_wasm() -> bool {
     #[allow(unused_imports)]
@@ -96,3 +96,50 @@ pub enum RefCount {
     Deallocated,
     Constant,
 }
+
+/// The outcome of a toplevel `expect`: how often it failed, and the values
+/// that the first failure looked up. The tests only look up `I64`s.
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExpectOutcome {
+    pub failures: usize,
+    pub lookups: Vec<i64>,
+}
+
+/// Clear the header of an expect buffer: no failures yet, and the next free byte
+/// comes right after the header (the number of failures, the next offset, and a lock)
+#[allow(dead_code)]
+pub fn reset_expect_buffer(buffer: &mut [u8], ptr_size: usize) {
+    buffer[..3 * ptr_size].fill(0);
+    buffer[ptr_size..][..ptr_size].copy_from_slice(&(3 * ptr_size).to_le_bytes()[..ptr_size]);
+}
+
+/// Decode an expect buffer written by an app whose pointers are `ptr_size` bytes.
+/// Each failure is a region and module id, followed by a table of
+/// (value offset, type variable) for its lookups, and the values themselves.
+#[allow(dead_code)]
+pub fn decode_expect_buffer(buffer: &[u8], ptr_size: usize) -> ExpectOutcome {
+    let read_usize = |offset: usize| {
+        let mut bytes = [0; 8];
+        bytes[..ptr_size].copy_from_slice(&buffer[offset..][..ptr_size]);
+        u64::from_le_bytes(bytes) as usize
+    };
+
+    let failures = read_usize(0);
+    let next_offset = read_usize(ptr_size);
+
+    let mut lookups = Vec::new();
+    let table = 3 * ptr_size + 12;
+    let table_entry_size = ptr_size + 4;
+    if failures > 0 && next_offset > table {
+        // The values come right after the table
+        let lookup_count = (read_usize(table) - table) / table_entry_size;
+        for i in 0..lookup_count {
+            let value_offset = read_usize(table + i * table_entry_size);
+            let bytes = buffer[value_offset..][..8].try_into().unwrap();
+            lookups.push(i64::from_le_bytes(bytes));
+        }
+    }
+
+    ExpectOutcome { failures, lookups }
+}
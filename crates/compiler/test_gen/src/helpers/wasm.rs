@@ -1,4 +1,4 @@
-use super::RefCount;
+use super::{decode_expect_buffer, ExpectOutcome, RefCount};
 use crate::helpers::from_wasm32_memory::FromWasm32Memory;
 use bumpalo::Bump;
 use roc_collections::all::MutSet;
@@ -16,6 +16,7 @@ use std::path::PathBuf;
 
 const TEST_WRAPPER_NAME: &str = "test_wrapper";
 const INIT_REFCOUNT_NAME: &str = "init_refcount_test";
+const INIT_EXPECT_NAME: &str = "init_expect_test";
 
 macro_rules! host_bytes_path {
     () => {
@@ -189,6 +190,16 @@ where
 
 struct TestDispatcher<'a> {
     wasi: WasiDispatcher<'a>,
+    expect_failures: usize,
+}
+
+impl<'a> TestDispatcher<'a> {
+    fn new() -> Self {
+        TestDispatcher {
+            wasi: wasi::WasiDispatcher::default(),
+            expect_failures: 0,
+        }
+    }
 }
 
 impl<'a> ImportDispatcher for TestDispatcher<'a> {
@@ -211,6 +222,15 @@ impl<'a> ImportDispatcher for TestDispatcher<'a> {
                 _ => format!(r#"Got an invald panic tag: "{panic_tag}""#),
             };
             panic!("{}", msg)
+        } else if module_name == "env" && function_name == "send_expect_failed_to_rust" {
+            // The app has already written the failure to the expect buffer
+            self.expect_failures += 1;
+            None
+        } else if module_name == "env" && function_name == "roc_dbg" {
+            let [loc, msg, src] =
+                [0, 1, 2].map(|i| RocStr::decode(memory, arguments[i].expect_i32().unwrap() as _));
+            eprintln!("[{}] {} = {}", loc, src, msg);
+            None
         } else {
             panic!(
                 "TestDispatcher does not implement {}.{}",
@@ -242,12 +262,16 @@ pub(crate) fn run_wasm_test_module<'a, T>(
 where
     T: FromWasm32Memory + Wasm32Result,
 {
-    let dispatcher = TestDispatcher {
-        wasi: wasi::WasiDispatcher::default(),
-    };
+    let dispatcher = TestDispatcher::new();
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
     let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
     let opt_value = inst.call_export(test_wrapper_name, [])?;
+    if inst.import_dispatcher.expect_failures > 0 {
+        return Err(format!(
+            "{} Roc expectation(s) failed",
+            inst.import_dispatcher.expect_failures
+        ));
+    }
     let addr_value = opt_value.ok_or("No return address from Wasm test")?;
     let addr = addr_value.expect_i32().map_err(|e| format!("{:?}", e))?;
     let output = <T as FromWasm32Memory>::decode(&inst.memory, addr as u32);
@@ -271,9 +295,7 @@ where
     let module = WasmModule::preload(&arena, &wasm_bytes, require_relocatable)
         .map_err(|e| format!("{:?}", e))?;
 
-    let dispatcher = TestDispatcher {
-        wasi: wasi::WasiDispatcher::default(),
-    };
+    let dispatcher = TestDispatcher::new();
     let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
     let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode)?;
 
@@ -323,6 +345,125 @@ where
     Ok(refcounts)
 }
 
+/// Build the toplevel expects of a module with the Wasm backend, and run each of them
+#[allow(dead_code)]
+pub fn run_toplevel_expects(src: &str) -> Vec<ExpectOutcome> {
+    const EXPECT_BUFFER_SIZE: usize = 1024;
+    let platform_bytes = include_bytes!(host_bytes_path!());
+
+    let mut outcomes = Vec::new();
+    for index in 0.. {
+        let arena = Bump::new();
+        let Some(wasm_bytes) = compile_toplevel_expect(&arena, platform_bytes, src, index) else {
+            break;
+        };
+
+        let require_relocatable = false;
+        let module = WasmModule::preload(&arena, &wasm_bytes, require_relocatable).unwrap();
+        let dispatcher = TestDispatcher::new();
+        let is_debug_mode = roc_debug_flags::dbg_set!(roc_debug_flags::ROC_LOG_WASM_INTERP);
+        let mut inst = Instance::for_module(&arena, &module, dispatcher, is_debug_mode).unwrap();
+
+        // Allocate the buffer in the test host that failed expects write into
+        let buffer_addr = inst
+            .call_export(INIT_EXPECT_NAME, [Value::I32(EXPECT_BUFFER_SIZE as i32)])
+            .unwrap()
+            .and_then(|value| value.expect_i32().ok())
+            .unwrap_or_else(|| panic!("No return address from {}", INIT_EXPECT_NAME));
+
+        inst.call_export(TEST_WRAPPER_NAME, [])
+            .unwrap_or_else(|e| panic!("{}", e));
+
+        let buffer = &inst.memory[buffer_addr as usize..][..EXPECT_BUFFER_SIZE];
+        let outcome = decode_expect_buffer(buffer, 4);
+        assert_eq!(outcome.failures, inst.import_dispatcher.expect_failures);
+        outcomes.push(outcome);
+    }
+
+    outcomes
+}
+
+/// Compile a module in test mode, with a wrapper around its toplevel expect at `index`.
+/// Returns `None` once there are no more toplevel expects.
+fn compile_toplevel_expect<'a>(
+    arena: &'a Bump,
+    host_bytes: &[u8],
+    src: &str,
+    index: usize,
+) -> Option<Vec<u8>> {
+    let load_config = LoadConfig {
+        target: roc_target::Target::Wasm32,
+        render: roc_reporting::report::RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE_HTML,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        function_kind: FunctionKind::LambdaSet,
+    };
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        PathBuf::from("Test.roc"),
+        src,
+        PathBuf::from("fake/test/path"),
+        None,
+        RocCacheDir::Disallowed,
+        load_config,
+    )
+    .expect("failed to load module");
+
+    let roc_load::MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        mut layout_interner,
+        toplevel_expects,
+        ..
+    } = loaded;
+
+    let expect = toplevel_expects
+        .values()
+        .flat_map(|expects| expects.pure.keys().copied())
+        .nth(index)?;
+
+    let env = roc_gen_wasm::Env {
+        arena,
+        module_id,
+        exposed_to_host: MutSet::from_iter([expect]),
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+    };
+
+    let host_module = roc_gen_wasm::parse_host(env.arena, host_bytes).unwrap();
+    let (mut module, mut called_fns, expect_fn_index) = roc_gen_wasm::build_app_module(
+        &env,
+        &mut layout_interner,
+        &mut interns,
+        host_module,
+        procedures,
+    );
+
+    <() as Wasm32Result>::insert_wrapper(arena, &mut module, TEST_WRAPPER_NAME, expect_fn_index);
+    called_fns.push(true);
+
+    let init_expect_idx = module
+        .names
+        .function_names
+        .iter()
+        .find(|(_, name)| *name == INIT_EXPECT_NAME)
+        .map(|(i, _)| *i)
+        .unwrap();
+    module.export.append(Export {
+        name: INIT_EXPECT_NAME,
+        ty: ExportType::Func,
+        index: init_expect_idx,
+    });
+
+    module.eliminate_dead_code(env.arena, called_fns);
+
+    let mut app_module_bytes = std::vec::Vec::with_capacity(module.size());
+    module.serialize(&mut app_module_bytes);
+
+    Some(app_module_bytes)
+}
+
 fn read_i32(memory: &[u8], addr: i32) -> i32 {
     let index = addr as usize;
     let mut bytes = [0; 4];
@@ -31,6 +31,24 @@ Vector *init_refcount_test(size_t capacity)
     return rc_pointers;
 }
 
+// Globals for expect testing
+extern size_t set_shared_buffer(uint8_t *ptr, size_t length);
+
+// The rust test passes us the size of the buffer that failed expects write their lookups to,
+// and we tell it where the buffer is, so it can decode the failures when the test is done.
+uint8_t *init_expect_test(size_t length)
+{
+    uint8_t *buffer = calloc(length, 1);
+
+    // The header is the number of failures, the offset of the next free byte, and a lock
+    size_t *header = (size_t *)buffer;
+    header[1] = 3 * sizeof(size_t);
+
+    set_shared_buffer(buffer, length);
+
+    return buffer;
+}
+
 #if ENABLE_PRINTF
 #define ASSERT(condition, format, ...)                       \
     if (!(condition))                                        \
@@ -132,8 +150,14 @@ void roc_panic(void* msg, unsigned int panic_tag)
     exit(101);
 }
 
-// TODO: add a way to send dbg to rust.
-void roc_debug(void* loc, void* msg) {}
+// roc_dbg is left as an import, and implemented by the test's ImportDispatcher
+
+extern void send_expect_failed_to_rust(unsigned int region_start, unsigned int region_end, unsigned int module_id);
+
+void roc_expect_failed(unsigned int region_start, unsigned int region_end, unsigned int module_id)
+{
+    send_expect_failed_to_rust(region_start, region_end, module_id);
+}
 
 //--------------------------
 
@@ -6,9 +6,11 @@
 
 pub mod gen_abilities;
 pub mod gen_compare;
+pub mod gen_debug_info;
 pub mod gen_definitions;
 pub mod gen_dict;
 pub mod gen_erased;
+pub mod gen_expect;
 pub mod gen_list;
 pub mod gen_num;
 pub mod gen_panic;
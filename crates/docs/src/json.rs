@@ -0,0 +1,308 @@
+//! A machine-readable form of a package's documentation, for tools like search indexes,
+//! API diffing and editor plugins which shouldn't have to scrape the generated HTML.
+//!
+//! The JSON is versioned with [SCHEMA_VERSION], so consumers can tell when its shape changes.
+use roc_collections::VecSet;
+use roc_load::docs::{self, DocEntry, ModuleDocumentation};
+use roc_module::symbol::Symbol;
+use roc_region::all::{LineInfo, Region};
+use serde::Serialize;
+use std::path::Path;
+
+/// Bump this whenever the JSON changes in a way existing consumers could trip over,
+/// such as renaming, removing or changing the meaning of a field.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Debug)]
+pub struct PackageDocs {
+    pub schema_version: u32,
+    pub modules: Vec<ModuleDocs>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ModuleDocs {
+    pub name: String,
+    /// The module's source file, relative to the directory of the package's main .roc file
+    pub path: String,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type")]
+pub enum Entry {
+    Def {
+        name: String,
+        type_vars: Vec<String>,
+        type_annotation: TypeAnnotation,
+        docs: Option<String>,
+        location: Location,
+    },
+    DetachedDoc {
+        docs: String,
+    },
+    ModuleDoc {
+        docs: String,
+    },
+}
+
+/// Where something is in its module's source file. Lines and columns start at 1.
+#[derive(Serialize, Debug)]
+pub struct Location {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type")]
+pub enum TypeAnnotation {
+    TagUnion {
+        tags: Vec<Tag>,
+        extension: Box<TypeAnnotation>,
+    },
+    Function {
+        args: Vec<TypeAnnotation>,
+        output: Box<TypeAnnotation>,
+    },
+    ObscuredTagUnion,
+    ObscuredRecord,
+    BoundVariable {
+        name: String,
+    },
+    Apply {
+        name: String,
+        parts: Vec<TypeAnnotation>,
+    },
+    Record {
+        fields: Vec<RecordField>,
+        extension: Box<TypeAnnotation>,
+    },
+    Tuple {
+        elems: Vec<TypeAnnotation>,
+        extension: Box<TypeAnnotation>,
+    },
+    Ability {
+        members: Vec<AbilityMember>,
+    },
+    Wildcard,
+    NoTypeAnn,
+    Where {
+        ann: Box<TypeAnnotation>,
+        implements: Vec<ImplementsClause>,
+    },
+    As {
+        ann: Box<TypeAnnotation>,
+        name: String,
+        vars: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Debug)]
+pub struct Tag {
+    pub name: String,
+    pub values: Vec<TypeAnnotation>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type")]
+pub enum RecordField {
+    RecordField {
+        name: String,
+        type_annotation: TypeAnnotation,
+    },
+    OptionalField {
+        name: String,
+        type_annotation: TypeAnnotation,
+    },
+    LabelOnly {
+        name: String,
+    },
+}
+
+#[derive(Serialize, Debug)]
+pub struct AbilityMember {
+    pub name: String,
+    pub type_annotation: TypeAnnotation,
+    pub able_variables: Vec<AbleVariable>,
+    pub docs: Option<String>,
+    pub location: Location,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AbleVariable {
+    pub name: String,
+    pub abilities: Vec<TypeAnnotation>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImplementsClause {
+    pub name: String,
+    pub abilities: Vec<TypeAnnotation>,
+}
+
+impl ModuleDocs {
+    /// Only the defs in `exposed_symbols` are included, like in the HTML docs.
+    pub fn new(
+        module: &ModuleDocumentation,
+        path: &Path,
+        src: &str,
+        exposed_symbols: &VecSet<Symbol>,
+    ) -> Self {
+        let line_info = LineInfo::new(src);
+
+        let entries = module
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                DocEntry::DocDef(doc_def) => {
+                    if exposed_symbols.contains(&doc_def.symbol) {
+                        Some(Entry::Def {
+                            name: doc_def.name.clone(),
+                            type_vars: doc_def.type_vars.clone(),
+                            type_annotation: TypeAnnotation::new(
+                                &line_info,
+                                &doc_def.type_annotation,
+                            ),
+                            docs: doc_def.docs.clone(),
+                            location: Location::new(&line_info, doc_def.region),
+                        })
+                    } else {
+                        None
+                    }
+                }
+                DocEntry::DetachedDoc(docs) => Some(Entry::DetachedDoc { docs: docs.clone() }),
+                DocEntry::ModuleDoc(docs) => Some(Entry::ModuleDoc { docs: docs.clone() }),
+            })
+            .collect();
+
+        Self {
+            name: module.name.clone(),
+            path: path.to_string_lossy().replace('\\', "/"),
+            entries,
+        }
+    }
+}
+
+impl Location {
+    fn new(line_info: &LineInfo, region: Region) -> Self {
+        let region = line_info.convert_region(region);
+
+        Self {
+            start: Position {
+                line: region.start.line + 1,
+                column: region.start.column + 1,
+            },
+            end: Position {
+                line: region.end.line + 1,
+                column: region.end.column + 1,
+            },
+        }
+    }
+}
+
+impl TypeAnnotation {
+    fn new(line_info: &LineInfo, type_ann: &docs::TypeAnnotation) -> Self {
+        match type_ann {
+            docs::TypeAnnotation::TagUnion { tags, extension } => TypeAnnotation::TagUnion {
+                tags: tags
+                    .iter()
+                    .map(|tag| Tag {
+                        name: tag.name.clone(),
+                        values: Self::new_all(line_info, &tag.values),
+                    })
+                    .collect(),
+                extension: Box::new(Self::new(line_info, extension)),
+            },
+            docs::TypeAnnotation::Function { args, output } => TypeAnnotation::Function {
+                args: Self::new_all(line_info, args),
+                output: Box::new(Self::new(line_info, output)),
+            },
+            docs::TypeAnnotation::ObscuredTagUnion => TypeAnnotation::ObscuredTagUnion,
+            docs::TypeAnnotation::ObscuredRecord => TypeAnnotation::ObscuredRecord,
+            docs::TypeAnnotation::BoundVariable(name) => {
+                TypeAnnotation::BoundVariable { name: name.clone() }
+            }
+            docs::TypeAnnotation::Apply { name, parts } => TypeAnnotation::Apply {
+                name: name.clone(),
+                parts: Self::new_all(line_info, parts),
+            },
+            docs::TypeAnnotation::Record { fields, extension } => TypeAnnotation::Record {
+                fields: fields
+                    .iter()
+                    .map(|field| match field {
+                        docs::RecordField::RecordField {
+                            name,
+                            type_annotation,
+                        } => RecordField::RecordField {
+                            name: name.clone(),
+                            type_annotation: Self::new(line_info, type_annotation),
+                        },
+                        docs::RecordField::OptionalField {
+                            name,
+                            type_annotation,
+                        } => RecordField::OptionalField {
+                            name: name.clone(),
+                            type_annotation: Self::new(line_info, type_annotation),
+                        },
+                        docs::RecordField::LabelOnly { name } => {
+                            RecordField::LabelOnly { name: name.clone() }
+                        }
+                    })
+                    .collect(),
+                extension: Box::new(Self::new(line_info, extension)),
+            },
+            docs::TypeAnnotation::Tuple { elems, extension } => TypeAnnotation::Tuple {
+                elems: Self::new_all(line_info, elems),
+                extension: Box::new(Self::new(line_info, extension)),
+            },
+            docs::TypeAnnotation::Ability { members } => TypeAnnotation::Ability {
+                members: members
+                    .iter()
+                    .map(|member| AbilityMember {
+                        name: member.name.clone(),
+                        type_annotation: Self::new(line_info, &member.type_annotation),
+                        able_variables: member
+                            .able_variables
+                            .iter()
+                            .map(|(name, abilities)| AbleVariable {
+                                name: name.clone(),
+                                abilities: Self::new_all(line_info, abilities),
+                            })
+                            .collect(),
+                        docs: member.docs.clone(),
+                        location: Location::new(line_info, member.region),
+                    })
+                    .collect(),
+            },
+            docs::TypeAnnotation::Wildcard => TypeAnnotation::Wildcard,
+            docs::TypeAnnotation::NoTypeAnn => TypeAnnotation::NoTypeAnn,
+            docs::TypeAnnotation::Where { ann, implements } => TypeAnnotation::Where {
+                ann: Box::new(Self::new(line_info, ann)),
+                implements: implements
+                    .iter()
+                    .map(|clause| ImplementsClause {
+                        name: clause.name.clone(),
+                        abilities: Self::new_all(line_info, &clause.abilities),
+                    })
+                    .collect(),
+            },
+            docs::TypeAnnotation::As { ann, name, vars } => TypeAnnotation::As {
+                ann: Box::new(Self::new(line_info, ann)),
+                name: name.clone(),
+                vars: vars.clone(),
+            },
+        }
+    }
+
+    fn new_all(line_info: &LineInfo, type_anns: &[docs::TypeAnnotation]) -> Vec<Self> {
+        type_anns
+            .iter()
+            .map(|type_ann| Self::new(line_info, type_ann))
+            .collect()
+    }
+}
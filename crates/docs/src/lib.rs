@@ -1,5 +1,6 @@
 //! Generates html documentation from Roc files. Used for
 //! [roc-lang.org/builtins/Num](https://www.roc-lang.org/builtins/Num).
+//! Can also generate the same documentation as JSON, for other tools to use.
 extern crate pulldown_cmark;
 extern crate roc_load;
 use bumpalo::Bump;
@@ -15,13 +16,18 @@ use roc_parse::keyword;
 use roc_parse::state::State;
 use roc_problem::Severity;
 use roc_region::all::Region;
+use roc_reporting::report::to_file_problem_report_string;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+pub mod json;
+
 const LINK_SVG: &str = include_str!("./static/link.svg");
 
-pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
-    let mut loaded_module = load_module_for_docs(root_file);
+/// Writes the docs as html to `build_dir`. Fails with the report to show if the package could
+/// not be loaded.
+pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) -> Result<(), String> {
+    let mut loaded_module = load_module_for_docs(root_file)?;
     let exposed_module_docs = get_exposed_module_docs(&mut loaded_module);
 
     // TODO get these from the platform's source file rather than hardcoding them!
@@ -114,15 +120,7 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
             render_sidebar(exposed_module_docs.iter().map(|(_, docs)| docs)).as_str(),
         );
 
-    let all_exposed_symbols = {
-        let mut set = VecSet::default();
-
-        for (_, docs) in exposed_module_docs.iter() {
-            set.insert_all(docs.exposed_symbols.iter().copied());
-        }
-
-        set
-    };
+    let all_exposed_symbols = get_all_exposed_symbols(&exposed_module_docs);
 
     // TODO fix: as is, this overrides an existing index.html
     // Write index.html for package (/index.html)
@@ -179,16 +177,69 @@ pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
     }
 
     println!("🎉 Docs generated in {}", build_dir.display());
+
+    Ok(())
+}
+
+/// Writes the docs to `docs.json` in `build_dir`, following the schema in [json]. Fails with
+/// the report to show if the package could not be loaded.
+pub fn generate_docs_json(root_file: PathBuf, build_dir: &Path) -> Result<(), String> {
+    let root_dir = root_file.parent().unwrap_or(Path::new("")).to_path_buf();
+    let mut loaded_module = load_module_for_docs(root_file)?;
+    let exposed_module_docs = get_exposed_module_docs(&mut loaded_module);
+
+    let all_exposed_symbols = get_all_exposed_symbols(&exposed_module_docs);
+
+    let modules = exposed_module_docs
+        .iter()
+        .map(|(module_id, module_docs)| {
+            let (path, src) = loaded_module.sources.get(module_id).unwrap_or_else(|| {
+                panic!("A module was exposed but its source was not loaded somehow: {module_id:?}")
+            });
+            let path = path.strip_prefix(&root_dir).unwrap_or(path);
+
+            json::ModuleDocs::new(module_docs, path, src, &all_exposed_symbols)
+        })
+        .collect();
+
+    let package_docs = json::PackageDocs {
+        schema_version: json::SCHEMA_VERSION,
+        modules,
+    };
+
+    fs::create_dir_all(build_dir).expect("TODO gracefully handle being unable to create build dir");
+
+    let json_path = build_dir.join("docs.json");
+    let contents = serde_json::to_string_pretty(&package_docs)
+        .expect("Docs should always be serializable as JSON");
+
+    fs::write(&json_path, contents).unwrap_or_else(|error| {
+        panic!(
+            "Attempted to write {} but failed with this error: {}",
+            json_path.display(),
+            error
+        )
+    });
+
+    println!("🎉 Docs generated in {}", json_path.display());
+
+    Ok(())
 }
 
 /// Gives only the module docs for modules that are exposed by the platform or package.
+/// A root module which isn't a platform or package exposes only itself.
 fn get_exposed_module_docs(
     loaded_module: &mut LoadedModule,
 ) -> Vec<(ModuleId, ModuleDocumentation)> {
-    let mut exposed_docs = Vec::with_capacity(loaded_module.exposed_modules.len());
+    let exposed_modules = if loaded_module.exposed_modules.is_empty() {
+        vec![loaded_module.module_id]
+    } else {
+        loaded_module.exposed_modules.clone()
+    };
+    let mut exposed_docs = Vec::with_capacity(exposed_modules.len());
     // let mut docs_by_module = Vec::with_capacity(state.exposed_modules.len());
 
-    for module_id in loaded_module.exposed_modules.iter() {
+    for module_id in exposed_modules.iter() {
         let docs =
             loaded_module.docs_by_module.remove(module_id).unwrap_or_else(|| {
                 panic!("A module was exposed but didn't have an entry in `documentation` somehow: {module_id:?}");
@@ -199,6 +250,18 @@ fn get_exposed_module_docs(
     exposed_docs
 }
 
+fn get_all_exposed_symbols(
+    exposed_module_docs: &[(ModuleId, ModuleDocumentation)],
+) -> VecSet<Symbol> {
+    let mut set = VecSet::default();
+
+    for (_, docs) in exposed_module_docs.iter() {
+        set.insert_all(docs.exposed_symbols.iter().copied());
+    }
+
+    set
+}
+
 fn page_title(package_name: &str, module_name: &str) -> String {
     format!("<title>{module_name} - {package_name}</title>")
 }
@@ -469,7 +532,9 @@ fn render_sidebar<'a, I: Iterator<Item = &'a ModuleDocumentation>>(modules: I) -
     buf
 }
 
-pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
+/// Loads and type-checks the package, failing with the report to show if it has any syntax
+/// errors or could not be loaded.
+pub fn load_module_for_docs(filename: PathBuf) -> Result<LoadedModule, String> {
     let arena = Bump::new();
     let load_config = LoadConfig {
         target: roc_target::Target::LinuxX64, // This is just type-checking for docs, so "target" doesn't matter
@@ -486,10 +551,15 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
         load_config,
     ) {
-        Ok(loaded) => loaded,
-        Err(LoadingProblem::FormattedReport(report)) => {
-            eprintln!("{report}");
-            std::process::exit(1);
+        Ok(loaded) if !loaded.syntax_problems.is_empty() => {
+            let reports: Vec<_> = loaded.syntax_problems.into_values().flatten().collect();
+
+            Err(reports.join("\n"))
+        }
+        Ok(loaded) => Ok(loaded),
+        Err(LoadingProblem::FormattedReport(report)) => Err(report),
+        Err(LoadingProblem::FileProblem { filename, error }) => {
+            Err(to_file_problem_report_string(filename, error))
         }
         Err(e) => panic!("{e:?}"),
     }
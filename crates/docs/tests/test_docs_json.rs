@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod test_docs_json {
+    use pretty_assertions::assert_eq;
+    use roc_docs::{generate_docs_json, json::SCHEMA_VERSION};
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// Consumers rely on the shape of the JSON staying the same for a given [SCHEMA_VERSION], so
+    /// each version has its own snapshot. When this fails because the JSON changed on purpose,
+    /// bump [SCHEMA_VERSION] and add a snapshot for the new version.
+    #[test]
+    fn no_deps_docs() {
+        let root_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../compiler/load_internal/tests/fixtures/build/no_deps/Docs.roc");
+        let build_dir = tempfile::tempdir().unwrap();
+
+        generate_docs_json(root_file, build_dir.path()).unwrap();
+
+        let actual = fs::read_to_string(build_dir.path().join("docs.json")).unwrap();
+        let snapshot_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/snapshots")
+            .join(format!("no_deps_docs_v{SCHEMA_VERSION}.json"));
+        let expected = fs::read_to_string(&snapshot_path).unwrap_or_else(|error| {
+            panic!(
+                "Could not read the snapshot {}: {error}",
+                snapshot_path.display()
+            )
+        });
+
+        assert_eq!(expected.trim_end(), actual.trim_end());
+    }
+}
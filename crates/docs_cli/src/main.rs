@@ -20,11 +20,17 @@ fn main() -> io::Result<()> {
         .get_matches();
 
     // Populate roc_files
-    generate_docs_html(
+    let generated = generate_docs_html(
         matches.get_one::<PathBuf>(ROC_FILE).unwrap().to_owned(),
         &PathBuf::from("./generated-docs"),
     );
 
+    if let Err(report) = generated {
+        eprintln!("{report}");
+
+        std::process::exit(1);
+    }
+
     Ok(())
 }
 
@@ -129,6 +129,8 @@ RocSingleTagPayload : [
 RocFn : {
     functionName : Str,
     externName : Str,
+    externSizeName : Str,
+    externResultSizeName : Str,
     args : List TypeId,
     lambdaSet : TypeId,
     ret : TypeId,
@@ -1,4 +1,4 @@
-module [Types, shape, size, alignment, target, walkShapes, entryPoints]
+module [Types, shape, size, alignment, fieldOffsets, target, walkShapes, entryPoints]
 
 import Shape exposing [Shape]
 import TypeId exposing [TypeId, typeIDfromU64, typeIDtoU64]
@@ -13,6 +13,9 @@ Types := {
     types : List Shape,
     sizes : List U32,
     aligns : List U32,
+    ## Where each field of a struct or tag union payload is, in the order of its fields.
+    ## This is empty for other types.
+    fieldOffsets : List (List U32),
 
     # Needed to check for duplicates
     typesByName : List Tuple1,
@@ -67,3 +70,12 @@ size = \@Types types, id ->
             idStr = Num.toStr (typeIDtoU64 id)
 
             crash "TypeId #$(idStr) was not found in Types. This should never happen, and means there was a bug in `roc glue`. If you have time, please open an issue at <https://github.com/roc-lang/roc/issues>"
+
+fieldOffsets : Types, TypeId -> List U32
+fieldOffsets = \@Types types, id ->
+    when List.get types.fieldOffsets (typeIDtoU64 id) is
+        Ok answer -> answer
+        Err OutOfBounds ->
+            idStr = Num.toStr (typeIDtoU64 id)
+
+            crash "TypeId #$(idStr) was not found in Types. This should never happen, and means there was a bug in `roc glue`. If you have time, please open an issue at <https://github.com/roc-lang/roc/issues>"
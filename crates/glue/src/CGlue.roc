@@ -0,0 +1,1671 @@
+app "c-glue"
+    packages { pf: "../platform/main.roc" }
+    imports [
+        pf.Types.{ Types },
+        pf.Shape.{ Shape, RocFn },
+        pf.File.{ File },
+        pf.TypeId.{ TypeId },
+        "../static/roc_std.h" as rocStdH : Str,
+    ]
+    provides [makeGlue] to pf
+
+makeGlue : List Types -> Result (List File) Str
+makeGlue = \typesByArch ->
+    archSections =
+        List.walk typesByArch "" \content, types ->
+            arch = (Types.target types).architecture
+            directive = if Str.isEmpty content then "#if" else "#elif"
+            section = convertTypesToSection types
+
+            Str.concat content "$(directive) $(archCondition arch)\n\n$(section)\n"
+
+    Ok [{ name: "roc_app.h", content: headerContent archSections }]
+
+## The only file the glue generates. It includes all of roc_std.h, so the host doesn't need
+## anything else, followed by the app's types for each architecture.
+headerContent : Str -> Str
+headerContent = \archSections ->
+    """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+    //
+    // Include this header from the host to use the app's types and entry points, e.g.
+    //
+    //     #include "roc_app/roc_app.h"
+    //
+    // Every type has the layout Roc uses on the architecture the host is compiled for,
+    // and the header fails to compile if the C compiler ever disagrees with that layout.
+
+    #ifndef ROC_APP_H
+    #define ROC_APP_H
+
+    $(rocStdH)
+    $(archSections)#else
+    #error "The glue for this platform was not generated for this architecture."
+    #endif
+
+    #endif // ROC_APP_H
+
+    """
+
+## The types are declared first, so that pointers to them can be used anywhere. Then they're
+## defined in dependency order, and finally come the functions, which can use any type.
+convertTypesToSection : Types -> Str
+convertTypesToSection = \types ->
+    declarations =
+        Types.walkShapes types { content: "", names: Set.empty {} } \state, type, id ->
+            declareType state types type id
+        |> .content
+
+    generated =
+        definitionOrder types
+        |> List.walk emptyGenerated \state, id -> defineType state types (Types.shape types id) id
+        |> generateEntryPoints types
+
+    """
+    $(declarations)$(generated.definitions)
+    #ifdef __cplusplus
+    extern "C" {
+    #endif
+
+    $(generated.externs)
+    #ifdef __cplusplus
+    }
+    #endif
+
+    $(generated.prototypes)$(generated.functions)
+    """
+
+emptyGenerated = {
+    definitions: "",
+    externs: "",
+    prototypes: "",
+    functions: "",
+    names: Set.empty {},
+}
+
+## Declare every type which can be referred to by name before anything else, so that
+## types which only point to each other (such as recursive tag unions) can be in any order.
+## Types which are only ever used through pointers are completely defined here.
+declareType = \state, types, type, id ->
+    when type is
+        Struct { name } | TagUnion (SingleTagStruct { name }) ->
+            escapedName = escapeKW name
+
+            addDeclaration state escapedName "typedef struct $(escapedName) $(escapedName);\n"
+
+        TagUnionPayload { name } ->
+            structName = payloadTypeName name
+
+            addDeclaration state structName "typedef struct $(structName) $(structName);\n"
+
+        TagUnion (Enumeration { name, tags, size }) ->
+            escapedName = escapeKW name
+
+            addDeclaration state escapedName (enumDecl escapedName tags size)
+
+        TagUnion (NonRecursive { name, tags, discriminantSize }) ->
+            escapedName = escapeKW name
+
+            if List.isEmpty tags then
+                state
+            else
+                tagNames = List.map tags .name
+                discriminant = enumDecl "discriminant_$(escapedName)" tagNames discriminantSize
+
+                addDeclaration state escapedName "$(discriminant)\ntypedef union $(escapedName) $(escapedName);\n"
+
+        TagUnion (Recursive { name, tags, discriminantSize }) | TagUnion (NullableWrapped { name, tags, discriminantSize }) ->
+            escapedName = escapeKW name
+
+            if List.isEmpty tags then
+                state
+            else
+                tagNames = List.map tags .name
+                discriminant = enumDecl "discriminant_$(escapedName)" tagNames discriminantSize
+                pointer = pointerDecl escapedName "A pointer to a `union union_$(escapedName)`, with the discriminant stored in its lowest bits."
+
+                addDeclaration state escapedName "$(discriminant)\n$(pointer)"
+
+        TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull }) ->
+            escapedName = escapeKW name
+            payloadType = typeName types nonNullPayload
+
+            tagNames =
+                when whichTagIsNull is
+                    FirstTagIsNull -> [nullTag, nonNullTag]
+                    SecondTagIsNull -> [nonNullTag, nullTag]
+
+            discriminant = enumDecl "discriminant_$(escapedName)" tagNames 1
+            pointer = pointerDecl escapedName "A pointer to the `$(payloadType)` of a `$(nonNullTag)`, or null for `$(nullTag)`."
+
+            addDeclaration state escapedName "$(discriminant)\n$(pointer)"
+
+        TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
+            escapedName = escapeKW name
+            payloadType = typeName types payload
+
+            addDeclaration state escapedName (pointerDecl escapedName "A pointer to the `$(payloadType)` of a `$(tagName)`.")
+
+        Function rocFn ->
+            if rocFn.isToplevel then
+                state
+            else
+                name = escapeKW rocFn.functionName
+
+                declaration =
+                    """
+                    // A Roc closure. The host owns its captured values, and frees them with `$(name)_deinit`.
+                    typedef struct $(name) {
+                        uint8_t* closure_data;
+                    } $(name);
+
+                    """
+
+                addDeclaration state name declaration
+
+        RocResult _ _ ->
+            name = identifier types id
+
+            addDeclaration state name "typedef struct $(name) $(name);\n"
+
+        RocDict key value ->
+            name = dictItemName types key value
+
+            addDeclaration state name "typedef struct $(name) $(name);\n"
+
+        RecursivePointer _
+        | Unit
+        | Unsized
+        | EmptyTagUnion
+        | Num _
+        | Bool
+        | RocStr
+        | RocSet _
+        | RocList _
+        | RocBox _ ->
+            # These are either C primitives, defined in roc_std.h, or pointers.
+            state
+
+addDeclaration = \state, name, declaration ->
+    once state name \newState ->
+        { newState & content: Str.concat newState.content "$(declaration)\n" }
+
+## Types like `List Str` can have more than one id, but must only be generated once.
+once = \state, name, generate ->
+    if Set.contains state.names name then
+        state
+    else
+        generate { state & names: Set.insert state.names name }
+
+## C needs each type to be defined before any type which contains it, so this orders
+## the types so that everything a type stores by value comes before it. Types which
+## it only points to have already been declared, so they can come in any order.
+definitionOrder : Types -> List TypeId
+definitionOrder = \types ->
+    Types.walkShapes types { visited: Set.empty {}, order: [] } \state, _, id ->
+        visitType state types id
+    |> .order
+
+visitType = \state, types, id ->
+    if Set.contains state.visited id then
+        state
+    else
+        afterDependencies =
+            List.walk (valueDependencies types id) { state & visited: Set.insert state.visited id } \newState, dependency ->
+                visitType newState types dependency
+
+        { afterDependencies & order: List.append afterDependencies.order id }
+
+valueDependencies : Types, TypeId -> List TypeId
+valueDependencies = \types, id ->
+    when Types.shape types id is
+        Struct { fields } | TagUnionPayload { fields } ->
+            structFieldList fields |> List.map .id
+
+        TagUnion (NonRecursive { tags }) | TagUnion (Recursive { tags }) | TagUnion (NullableWrapped { tags }) ->
+            List.keepOks tags \{ payload } ->
+                when payload is
+                    Some payloadId -> Ok payloadId
+                    None -> Err NoPayload
+
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.map fields .id
+
+        TagUnion (SingleTagStruct { payload: HasClosure fields }) ->
+            List.map fields .id
+
+        RocResult ok err ->
+            [ok, err]
+
+        RocDict key value ->
+            [key, value]
+
+        _ ->
+            []
+
+defineType = \state, types, type, id ->
+    when type is
+        Struct { name, fields } ->
+            once state name \newState ->
+                generateStruct newState types id name (structFieldList fields)
+
+        TagUnionPayload { name, fields } ->
+            structName = payloadTypeName name
+
+            once state structName \newState ->
+                generateStruct newState types id structName (structFieldList (nameTagUnionPayloadFields fields))
+
+        TagUnion (NonRecursive { name, tags, discriminantOffset }) ->
+            if List.isEmpty tags then
+                state
+            else
+                once state name \newState ->
+                    generateNonRecursiveTagUnion newState types id name tags discriminantOffset
+
+        TagUnion (Recursive { name, tags }) ->
+            if List.isEmpty tags then
+                state
+            else
+                once state name \newState ->
+                    generateRecursiveTagUnion newState types id name tags None
+
+        TagUnion (NullableWrapped { name, indexOfNullTag, tags }) ->
+            once state name \newState ->
+                generateRecursiveTagUnion newState types id name tags (Some indexOfNullTag)
+
+        TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload }) ->
+            once state name \newState ->
+                generateNullableUnwrapped newState types id name nullTag nonNullTag nonNullPayload
+
+        TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
+            once state name \newState ->
+                generateNonNullableUnwrapped newState types id name tagName payload
+
+        TagUnion (SingleTagStruct { name, tagName, payload }) ->
+            once state name \newState ->
+                generateSingleTagStruct newState types id name tagName payload
+
+        Function rocFn ->
+            if rocFn.isToplevel then
+                state
+            else
+                once state rocFn.functionName \newState ->
+                    generateFunction newState types rocFn
+
+        RocList elem ->
+            name = identifier types id
+
+            once state name \newState ->
+                generateListFunctions newState name (element types elem)
+
+        Unsized ->
+            name = identifier types id
+
+            once state name \newState ->
+                generateListFunctions newState name unsizedElement
+
+        RocSet elem ->
+            name = identifier types id
+
+            once state name \newState ->
+                generateSetFunctions newState name (element types elem)
+
+        RocDict key value ->
+            name = identifier types id
+
+            once state name \newState ->
+                generateDict newState types name key value
+
+        RocBox elem ->
+            name = identifier types id
+
+            once state name \newState ->
+                generateBoxFunctions newState types name elem
+
+        RocResult ok err ->
+            name = identifier types id
+
+            once state name \newState ->
+                generateResult newState types id name ok err
+
+        TagUnion (Enumeration _)
+        | RecursivePointer _
+        | Unit
+        | EmptyTagUnion
+        | Num _
+        | Bool
+        | RocStr ->
+            # These were completely declared already, or are defined in roc_std.h
+            state
+
+addDefinition = \state, definition ->
+    { state & definitions: Str.concat state.definitions "$(definition)\n" }
+
+addExtern = \state, prototype ->
+    { state & externs: Str.concat state.externs "$(prototype);\n" }
+
+## Add a `static inline` function. All the prototypes come before any of the functions,
+## so the functions can call each other regardless of the order they're generated in.
+addFunction = \state, signature, body ->
+    { state &
+        prototypes: Str.concat state.prototypes "static inline $(signature);\n",
+        functions: Str.concat state.functions "\nstatic inline $(signature) {\n$(body)}\n",
+    }
+
+generateEntryPoints = \state, types ->
+    List.walk (Types.entryPoints types) state \accum, T name id -> generateEntryPoint accum types name id
+
+generateEntryPoint = \state, types, name, id ->
+    (args, retId) =
+        when Types.shape types id is
+            Function rocFn -> (rocFn.args, rocFn.ret)
+            _ -> ([], id)
+
+    publicArguments =
+        toArgStr args types \argId, _shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            "$(type) arg$(indexStr)"
+
+    externArguments =
+        toArgStr args types \argId, shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            if isPassedByReference types shape then
+                "const $(type)* arg$(indexStr)"
+            else
+                "$(type) arg$(indexStr)"
+        |> prependComma
+
+    externCallArguments =
+        toArgStr args types \_argId, shape, index ->
+            indexStr = Num.toStr index
+
+            if isPassedByReference types shape then
+                "&arg$(indexStr)"
+            else
+                "arg$(indexStr)"
+        |> prependComma
+
+    signature = \ret -> "$(ret) roc_$(name)($(paramList publicArguments))"
+
+    when Types.shape types retId is
+        Function _ ->
+            ret = typeName types retId
+
+            body =
+                """
+                    $(ret) result;
+                    result.closure_data = (uint8_t*)malloc((size_t)roc__$(name)_1_exposed_size());
+                    roc__$(name)_1_exposed_generic(result.closure_data$(externCallArguments));
+
+                    return result;
+
+                """
+
+            # The closure's type is named after a type variable, so give hosts a name for it
+            # which stays the same when the app changes.
+            alias =
+                """
+                typedef $(ret) roc_$(name)_result;
+                #define roc_$(name)_result_call $(ret)_call
+                #define roc_$(name)_result_deinit $(ret)_deinit
+
+                """
+
+            state
+            |> addDefinition alias
+            |> addExtern "void roc__$(name)_1_exposed_generic(uint8_t* closure_data$(externArguments))"
+            |> addExtern "int64_t roc__$(name)_1_exposed_size(void)"
+            |> addFunction (signature ret) body
+
+        retShape if isZeroSizedShape retShape ->
+            body =
+                """
+                    uint8_t ret;
+                    roc__$(name)_1_exposed_generic(&ret$(externCallArguments));
+
+                """
+
+            state
+            |> addExtern "void roc__$(name)_1_exposed_generic(void* ret$(externArguments))"
+            |> addFunction (signature "void") body
+
+        _ ->
+            ret = typeName types retId
+
+            body =
+                """
+                    $(ret) ret;
+                    roc__$(name)_1_exposed_generic(&ret$(externCallArguments));
+
+                    return ret;
+
+                """
+
+            state
+            |> addExtern "void roc__$(name)_1_exposed_generic($(ret)* ret$(externArguments))"
+            |> addFunction (signature ret) body
+
+generateFunction = \state, types, rocFn ->
+    name = escapeKW rocFn.functionName
+    externName = rocFn.externName
+
+    publicArguments =
+        toArgStr rocFn.args types \argId, _shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            "$(type) arg$(indexStr)"
+        |> prependComma
+
+    externDefArguments =
+        withoutUnit =
+            toArgStr rocFn.args types \argId, _shape, index ->
+                type = typeName types argId
+                indexStr = Num.toStr index
+
+                "const $(type)* arg$(indexStr)"
+
+        if Str.isEmpty withoutUnit then
+            # These always have a first argument that's a pointer, even if it's to nothing.
+            "const void* arg0"
+        else
+            withoutUnit
+
+    externCallArguments =
+        withoutUnit =
+            toArgStr rocFn.args types \_argId, _shape, index ->
+                indexStr = Num.toStr index
+
+                "&arg$(indexStr)"
+
+        if Str.isEmpty withoutUnit then
+            "NULL"
+        else
+            withoutUnit
+
+    (ret, outputParam, callBody) =
+        if isZeroSized types rocFn.ret then
+            (
+                "void",
+                "void* output",
+                """
+                    uint8_t output;
+                    $(externName)($(externCallArguments), self.closure_data, &output);
+
+                """,
+            )
+        else
+            retType = typeName types rocFn.ret
+
+            (
+                retType,
+                "$(retType)* output",
+                """
+                    $(retType) output;
+                    $(externName)($(externCallArguments), self.closure_data, &output);
+
+                    return output;
+
+                """,
+            )
+
+    deinitBody =
+        """
+            // Frees the memory holding the closure's captured values, once it won't be called again.
+            free(self.closure_data);
+
+        """
+
+    state
+    |> addExtern "void $(externName)($(externDefArguments), const uint8_t* closure_data, $(outputParam))"
+    |> addFunction "$(ret) $(name)_call($(name) self$(publicArguments))" callBody
+    |> addFunction "void $(name)_deinit($(name) self)" deinitBody
+
+generateStruct = \state, types, id, name, fields ->
+    escapedName = escapeKW name
+    storedFields = List.dropIf fields \{ id: fieldId } -> isZeroSized types fieldId
+
+    definition =
+        structDecl "struct $(escapedName)" (structMembers types storedFields)
+        |> Str.concat (layoutChecks types id escapedName)
+        |> Str.concat (closureFieldNames types escapedName storedFields)
+
+    state
+    |> addDefinition definition
+    |> addRefcountFunctions types id escapedName storedFields
+
+structMembers = \types, fields ->
+    List.walk fields "" \accum, { name, id } ->
+        type = typeName types id
+        fieldName = escapeKW name
+
+        Str.concat accum "$(indent)$(type) $(fieldName);\n"
+
+## Closure types are named after type variables, which change whenever the app does, so each
+## closure field also gets names for its functions after the type and field, e.g. `Greeter_f1_call`.
+closureFieldNames = \types, name, fields ->
+    List.walk fields "" \accum, { name: fieldName, id: fieldId } ->
+        when Types.shape types fieldId is
+            Function { functionName } ->
+                functionType = escapeKW functionName
+                prefix = "$(name)_$(escapeKW fieldName)"
+
+                Str.concat accum "#define $(prefix)_call $(functionType)_call\n#define $(prefix)_deinit $(functionType)_deinit\n"
+
+            _ ->
+                accum
+
+structFieldList = \structFields ->
+    when structFields is
+        HasNoClosure fields -> fields
+        HasClosure fields -> List.map fields \{ name, id } -> { name, id }
+
+## Payloads are named after their tag, e.g. `MyUnion_MyVariant`, which is also the name
+## of the tag's constructor, so the payload's struct gets a prefix like the discriminant's.
+payloadTypeName : Str -> Str
+payloadTypeName = \name -> "payload_$(name)"
+
+nameTagUnionPayloadFields = \payloadFields ->
+    # Tag union payloads have numbered fields, so we prefix them
+    # with an "f" because C identifiers can't start with a number.
+    when payloadFields is
+        HasNoClosure fields ->
+            renamedFields = List.map fields \{ name, id } -> { name: "f$(name)", id }
+            HasNoClosure renamedFields
+
+        HasClosure fields ->
+            renamedFields = List.map fields \{ name, id, accessors } -> { name: "f$(name)", id, accessors }
+            HasClosure renamedFields
+
+## An integer type for the tag ids, and a constant for each tag, named after the type.
+## C enums have the size of an int, so they don't have the layout Roc uses.
+enumDecl : Str, List Str, U32 -> Str
+enumDecl = \name, tags, tagBytes ->
+    values =
+        List.walkWithIndex tags "" \accum, tagName, index ->
+            indexStr = Num.toStr index
+
+            Str.concat accum "$(indent)$(name)_$(tagName) = $(indexStr),\n"
+
+    "typedef $(unsignedInt tagBytes) $(name);\nenum {\n$(values)};\n"
+
+unsignedInt : U32 -> Str
+unsignedInt = \bytes ->
+    # Recursive tag unions keep the discriminant in the pointer, and can have a size of 0.
+    bits = Num.max bytes 1 * 8 |> Num.toStr
+
+    "uint$(bits)_t"
+
+pointerDecl : Str, Str -> Str
+pointerDecl = \name, comment ->
+    """
+    // $(comment)
+    typedef struct $(name) {
+        void* pointer;
+    } $(name);
+
+    """
+
+generateNonRecursiveTagUnion = \state, types, id, name, tags, discriminantOffset ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_$(escapedName)"
+    unionName = "union union_$(escapedName)"
+
+    unionMembers =
+        List.walk tags "" \accum, { name: tagName, payload } ->
+            when storedPayload types payload is
+                Some payloadId ->
+                    type = typeName types payloadId
+
+                    Str.concat accum "$(indent)$(type) $(tagName);\n"
+
+                None ->
+                    accum
+
+    # Roc stores the discriminant right after the largest payload, which can be inside
+    # the union's trailing padding. So rather than putting the discriminant after the
+    # union in a struct, overlay it on the union at the exact offset Roc uses.
+    padding =
+        if discriminantOffset == 0 then
+            ""
+        else
+            "$(indent)$(indent)uint8_t padding[$(Num.toStr discriminantOffset)];\n"
+
+    discriminantStorage =
+        "$(indent)struct {\n$(padding)$(indent)$(indent)$(discriminantName) tag;\n$(indent)} discriminant_storage;\n"
+
+    definition =
+        if Str.isEmpty unionMembers then
+            structDecl "union $(escapedName)" discriminantStorage
+        else
+            structDecl unionName unionMembers
+            |> Str.concat "\n"
+            |> Str.concat (structDecl "union $(escapedName)" "$(indent)$(unionName) payload;\n$(discriminantStorage)")
+
+    discriminantBody =
+        """
+            return self.discriminant_storage.tag;
+
+        """
+
+    withTags =
+        List.walk tags state \accum, { name: tagName, payload } ->
+            tagConstant = "$(discriminantName)_$(tagName)"
+
+            { params, assignments } =
+                when storedPayload types payload is
+                    Some payloadId -> payloadConstructor types payloadId
+                    None -> { params: "", assignments: \_ -> "" }
+
+            # Write the discriminant's bytes directly, because they can overlap the payload's padding.
+            constructorBody =
+                """
+                    $(escapedName) result;
+                    memset(&result, 0, sizeof(result));
+                $(assignments "result.payload.$(tagName)")
+                    $(discriminantName) tag = $(tagConstant);
+                    memcpy(&result.discriminant_storage.tag, &tag, sizeof(tag));
+
+                    return result;
+
+                """
+
+            withConstructor =
+                accum
+                |> addFunction "$(escapedName) $(escapedName)_$(tagName)($(paramList params))" constructorBody
+                |> addIsTagFunction escapedName tagName tagConstant
+
+            when storedPayload types payload is
+                Some payloadId ->
+                    payloadType = typeName types payloadId
+
+                    getterBody =
+                        """
+                            assert($(escapedName)_is_$(tagName)(self));
+                            return self.payload.$(tagName);
+
+                        """
+
+                    addFunction withConstructor "$(payloadType) $(escapedName)_get_$(tagName)($(escapedName) self)" getterBody
+
+                None ->
+                    withConstructor
+
+    withRefcounting =
+        if isRefcounted types (Types.shape types id) then
+            increfs =
+                refcountCases types discriminantName tags \tagName, payloadId ->
+                    "$(identifier types payloadId)_incref(self.payload.$(tagName), amount);"
+
+            decrefs =
+                refcountCases types discriminantName tags \tagName, payloadId ->
+                    "$(identifier types payloadId)_decref(self.payload.$(tagName));"
+
+            withTags
+            |> addFunction "void $(escapedName)_incref($(escapedName) self, size_t amount)" (switchOnDiscriminant escapedName increfs)
+            |> addFunction "void $(escapedName)_decref($(escapedName) self)" (switchOnDiscriminant escapedName decrefs)
+        else
+            withTags
+
+    withRefcounting
+    |> addDefinition (Str.concat definition (layoutChecks types id escapedName))
+    |> addFunction "$(discriminantName) $(escapedName)_discriminant($(escapedName) self)" discriminantBody
+
+generateRecursiveTagUnion = \state, types, id, tagUnionName, tags, nullTagIndex ->
+    escapedName = escapeKW tagUnionName
+    discriminantName = "discriminant_$(escapedName)"
+    unionName = "union union_$(escapedName)"
+
+    isNullTag = \index -> Some (Num.intCast index) == nullTagIndex
+
+    unionMembers =
+        List.walkWithIndex tags "" \accum, { name: tagName, payload }, index ->
+            when storedPayload types payload is
+                Some payloadId if !(isNullTag index) ->
+                    type = typeName types payloadId
+
+                    Str.concat accum "$(indent)$(type) $(tagName);\n"
+
+                _ ->
+                    accum
+
+    hasUnion = !(Str.isEmpty unionMembers)
+
+    (unionPointerType, unionSize, unionAlignment) =
+        if hasUnion then
+            ("$(unionName)*", "sizeof($(unionName))", "ROC_ALIGNOF($(unionName))")
+        else
+            ("void*", "0", "1")
+
+    definition =
+        if hasUnion then
+            structDecl unionName unionMembers
+            |> Str.concat (layoutChecks types id escapedName)
+        else
+            layoutChecks types id escapedName
+
+    nullDiscriminant =
+        when nullTagIndex is
+            Some index ->
+                nullTagName = List.get tags (Num.intCast index) |> Result.map .name |> Result.withDefault ""
+
+                """
+                    if (address == 0) {
+                        return $(discriminantName)_$(nullTagName);
+                    }
+
+
+                """
+
+            None ->
+                ""
+
+    discriminantBody =
+        """
+            uintptr_t address = (uintptr_t)self.pointer;
+        $(nullDiscriminant)    return ($(discriminantName))(address & ROC_TAG_MASK);
+
+        """
+
+    unionPointerBody =
+        """
+            return ($(unionPointerType))((uintptr_t)self.pointer & ~ROC_TAG_MASK);
+
+        """
+
+    withHelpers =
+        state
+        |> addDefinition definition
+        |> addFunction "$(discriminantName) $(escapedName)_discriminant($(escapedName) self)" discriminantBody
+        |> addFunction "$(unionPointerType) $(escapedName)_union_pointer($(escapedName) self)" unionPointerBody
+
+    withTags =
+        List.walkWithIndex tags withHelpers \accum, { name: tagName, payload }, index ->
+            tagConstant = "$(discriminantName)_$(tagName)"
+            signature = \params -> "$(escapedName) $(escapedName)_$(tagName)($(paramList params))"
+
+            if isNullTag index then
+                body =
+                    """
+                        $(escapedName) result;
+                        result.pointer = NULL;
+
+                        return result;
+
+                    """
+
+                accum
+                |> addFunction (signature "") body
+                |> addIsTagFunction escapedName tagName tagConstant
+            else
+                { params, assignments } =
+                    when storedPayload types payload is
+                        Some payloadId -> payloadConstructor types payloadId
+                        None -> { params: "", assignments: \_ -> "" }
+
+                constructorBody =
+                    """
+                        $(unionPointerType) contents = ($(unionPointerType))roc_alloc_refcounted($(unionSize), $(unionAlignment));
+                    $(assignments "contents->$(tagName)")
+                        $(escapedName) result;
+                        result.pointer = (void*)((uintptr_t)contents | $(tagConstant));
+
+                        return result;
+
+                    """
+
+                withConstructor =
+                    accum
+                    |> addFunction (signature params) constructorBody
+                    |> addIsTagFunction escapedName tagName tagConstant
+
+                when storedPayload types payload is
+                    Some payloadId ->
+                        payloadType = typeName types payloadId
+
+                        getterBody =
+                            """
+                                assert($(escapedName)_is_$(tagName)(self));
+                                return $(escapedName)_union_pointer(self)->$(tagName);
+
+                            """
+
+                        addFunction withConstructor "$(payloadType) $(escapedName)_get_$(tagName)($(escapedName) self)" getterBody
+
+                    None ->
+                        withConstructor
+
+    payloadDecrefs =
+        tags
+        |> List.mapWithIndex \tag, index -> if isNullTag index then { tag & payload: None } else tag
+        |> \nonNullTags ->
+            refcountCases types discriminantName nonNullTags \tagName, payloadId ->
+                "$(identifier types payloadId)_decref($(escapedName)_union_pointer(self)->$(tagName));"
+
+    decrefPayload =
+        if Str.isEmpty payloadDecrefs then
+            ""
+        else
+            switch = switchOnDiscriminant escapedName payloadDecrefs |> indentLines
+
+            "$(indent)if (roc_is_unique(self.pointer)) {\n$(switch)$(indent)}\n\n"
+
+    increfBody =
+        """
+            roc_incref(self.pointer, amount);
+
+        """
+
+    decrefBody =
+        """
+            if (self.pointer == NULL) {
+                return;
+            }
+
+        $(decrefPayload)    roc_decref(self.pointer, $(unionAlignment));
+
+        """
+
+    withTags
+    |> addFunction "void $(escapedName)_incref($(escapedName) self, size_t amount)" increfBody
+    |> addFunction "void $(escapedName)_decref($(escapedName) self)" decrefBody
+
+generateNullableUnwrapped = \state, types, id, name, nullTag, nonNullTag, nonNullPayload ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_$(escapedName)"
+    payloadType = typeName types nonNullPayload
+    { params, assignments } = payloadConstructor types nonNullPayload
+
+    discriminantBody =
+        """
+            return self.pointer == NULL ? $(discriminantName)_$(nullTag) : $(discriminantName)_$(nonNullTag);
+
+        """
+
+    nullConstructorBody =
+        """
+            $(escapedName) result;
+            result.pointer = NULL;
+
+            return result;
+
+        """
+
+    getterBody =
+        """
+            assert($(escapedName)_is_$(nonNullTag)(self));
+            return *($(payloadType)*)self.pointer;
+
+        """
+
+    decrefPayload =
+        if isRefcounted types (Types.shape types nonNullPayload) then
+            """
+                if (self.pointer != NULL && roc_is_unique(self.pointer)) {
+                    $(identifier types nonNullPayload)_decref(*($(payloadType)*)self.pointer);
+                }
+
+
+            """
+        else
+            ""
+
+    state
+    |> addDefinition (layoutChecks types id escapedName)
+    |> addFunction "$(discriminantName) $(escapedName)_discriminant($(escapedName) self)" discriminantBody
+    |> addFunction "$(escapedName) $(escapedName)_$(nullTag)(void)" nullConstructorBody
+    |> addIsTagFunction escapedName nullTag "$(discriminantName)_$(nullTag)"
+    |> addFunction "$(escapedName) $(escapedName)_$(nonNullTag)($(paramList params))" (boxedConstructorBody escapedName payloadType assignments)
+    |> addIsTagFunction escapedName nonNullTag "$(discriminantName)_$(nonNullTag)"
+    |> addFunction "$(payloadType) $(escapedName)_get_$(nonNullTag)($(escapedName) self)" getterBody
+    |> addPointerRefcountFunctions escapedName payloadType decrefPayload
+
+generateNonNullableUnwrapped = \state, types, id, name, tagName, payload ->
+    escapedName = escapeKW name
+    payloadType = typeName types payload
+    { params, assignments } = payloadConstructor types payload
+
+    getterBody =
+        """
+            return *($(payloadType)*)self.pointer;
+
+        """
+
+    decrefPayload =
+        if isRefcounted types (Types.shape types payload) then
+            """
+                if (roc_is_unique(self.pointer)) {
+                    $(identifier types payload)_decref(*($(payloadType)*)self.pointer);
+                }
+
+
+            """
+        else
+            ""
+
+    state
+    |> addDefinition (layoutChecks types id escapedName)
+    |> addFunction "$(escapedName) $(escapedName)_$(tagName)($(paramList params))" (boxedConstructorBody escapedName payloadType assignments)
+    |> addFunction "$(payloadType) $(escapedName)_get_$(tagName)($(escapedName) self)" getterBody
+    |> addPointerRefcountFunctions escapedName payloadType decrefPayload
+
+## The body of a constructor which moves the payload into a new Roc allocation.
+boxedConstructorBody = \name, payloadType, assignments ->
+    """
+        $(payloadType)* contents = ($(payloadType)*)roc_alloc_refcounted(sizeof($(payloadType)), ROC_ALIGNOF($(payloadType)));
+    $(assignments "(*contents)")
+        $(name) result;
+        result.pointer = contents;
+
+        return result;
+
+    """
+
+addPointerRefcountFunctions = \state, name, payloadType, decrefPayload ->
+    increfBody =
+        """
+            roc_incref(self.pointer, amount);
+
+        """
+
+    decrefBody =
+        """
+        $(decrefPayload)    roc_decref(self.pointer, ROC_ALIGNOF($(payloadType)));
+
+        """
+
+    state
+    |> addFunction "void $(name)_incref($(name) self, size_t amount)" increfBody
+    |> addFunction "void $(name)_decref($(name) self)" decrefBody
+
+generateSingleTagStruct = \state, types, id, name, tagName, payload ->
+    # Store single-tag unions as structs rather than unions,
+    # because they have only one alternative. However, still
+    # offer the usual tag union APIs.
+    escapedName = escapeKW name
+
+    # A closure is stored like any other field, as a value of its function's type.
+    fields =
+        structFieldList payload
+        |> List.mapWithIndex \{ id: fieldId }, index ->
+            indexStr = Num.toStr index
+
+            { name: "f$(indexStr)", id: fieldId }
+        |> List.dropIf \{ id: fieldId } -> isZeroSized types fieldId
+
+    params =
+        fields
+        |> List.map \{ name: fieldName, id: fieldId } ->
+            fieldType = typeName types fieldId
+
+            "$(fieldType) $(fieldName)"
+        |> Str.joinWith ", "
+
+    assignments =
+        List.walk fields "" \accum, { name: fieldName } ->
+            Str.concat accum "$(indent)result.$(fieldName) = $(fieldName);\n"
+
+    constructorBody =
+        """
+            $(escapedName) result;
+            memset(&result, 0, sizeof(result));
+        $(assignments)
+            return result;
+
+        """
+
+    definition =
+        structDecl "struct $(escapedName)" (structMembers types fields)
+        |> Str.concat (layoutChecks types id escapedName)
+        |> Str.concat (closureFieldNames types escapedName fields)
+
+    state
+    |> addDefinition definition
+    |> addFunction "$(escapedName) $(escapedName)_$(tagName)($(paramList params))" constructorBody
+    |> addRefcountFunctions types id escapedName fields
+
+## A list's elements aren't typed in C, so each type of list the app uses gets its own
+## functions for converting to and from arrays, and for releasing its elements.
+generateListFunctions = \state, name, elem ->
+    withRefcounting =
+        state
+        |> addFunction "void $(name)_incref(RocList self, size_t amount)" "$(indent)roc_list_incref(self, amount);\n"
+        |> addFunction "void $(name)_decref(RocList self)" (listDecrefBody "self" elem)
+
+    if elem.isZeroSized then
+        withRefcounting
+    else
+        elementsBody =
+            """
+                return (const $(elem.type)*)self.bytes;
+
+            """
+
+        fromArrayBody =
+            """
+                return roc_list_from_array(elements, length, sizeof($(elem.type)), $(Num.toStr elem.alignment));
+
+            """
+
+        withRefcounting
+        |> addFunction "const $(elem.type)* $(name)_elements(RocList self)" elementsBody
+        |> addFunction "RocList $(name)_from_array(const $(elem.type)* elements, size_t length)" fromArrayBody
+
+listDecrefBody = \list, elem ->
+    decrefElements =
+        if elem.isRefcounted then
+            # The last reference owns the elements, so they need to be released too.
+            # A seamless slice doesn't know about the elements outside of it, so in
+            # that case we leave the elements alone rather than leaking only some of them.
+            """
+                if (!roc_list_is_seamless_slice($(list)) && roc_list_is_unique($(list))) {
+                    const $(elem.type)* elements = (const $(elem.type)*)$(list).bytes;
+                    for (size_t index = 0; index < $(list).length; index++) {
+                        $(elem.identifier)_decref(elements[index]);
+                    }
+                }
+
+
+            """
+        else
+            ""
+
+    "$(decrefElements)$(indent)roc_list_decref($(list), $(Num.toStr elem.alignment));\n"
+
+generateSetFunctions = \state, name, elem ->
+    state
+    |> addFunction "void $(name)_incref(RocSet self, size_t amount)" "$(indent)roc_list_incref(self.elements, amount);\n"
+    |> addFunction "void $(name)_decref(RocSet self)" (listDecrefBody "self.elements" elem)
+
+generateDict = \state, types, name, key, value ->
+    itemName = dictItemName types key value
+    keyElem = element types key
+    valueElem = element types value
+
+    # The field with the larger alignment comes first, with the key winning ties,
+    # which matches the layout of Roc's dictionaries.
+    fields =
+        (if keyElem.alignment >= valueElem.alignment then [{ name: "key", id: key }, { name: "value", id: value }] else [{ name: "value", id: value }, { name: "key", id: key }])
+        |> List.dropIf \{ id } -> isZeroSized types id
+
+    refcountedFields = List.keepIf fields \{ id } -> isRefcounted types (Types.shape types id)
+
+    item = {
+        identifier: itemName,
+        type: itemName,
+        alignment: Num.max keyElem.alignment valueElem.alignment,
+        isRefcounted: !(List.isEmpty refcountedFields),
+        isZeroSized: List.isEmpty fields,
+    }
+
+    itemsBody =
+        """
+            return (const $(itemName)*)self.items.bytes;
+
+        """
+
+    withItem =
+        state
+        |> addDefinition (structDecl "struct $(itemName)" (structMembers types fields))
+        |> addFieldRefcountFunctions types itemName refcountedFields
+        |> addFunction "void $(name)_incref(RocDict self, size_t amount)" "$(indent)roc_list_incref(self.items, amount);\n"
+        |> addFunction "void $(name)_decref(RocDict self)" (listDecrefBody "self.items" item)
+
+    if item.isZeroSized then
+        withItem
+    else
+        addFunction withItem "const $(itemName)* $(name)_items(RocDict self)" itemsBody
+
+dictItemName = \types, key, value ->
+    keyName = identifier types key
+    valueName = identifier types value
+
+    "RocDictItem_$(keyName)_$(valueName)"
+
+generateBoxFunctions = \state, types, name, elem ->
+    elemType = typeName types elem
+    alignment = Num.toStr (Types.alignment types elem)
+
+    decrefContents =
+        if isRefcounted types (Types.shape types elem) then
+            """
+                if (roc_is_unique(self)) {
+                    $(identifier types elem)_decref(*self);
+                }
+
+
+            """
+        else
+            ""
+
+    initBody =
+        """
+            $(elemType)* contents = ($(elemType)*)roc_alloc_refcounted(sizeof($(elemType)), $(alignment));
+            *contents = value;
+
+            return contents;
+
+        """
+
+    withRefcounting =
+        state
+        |> addFunction "void $(name)_incref($(elemType)* self, size_t amount)" "$(indent)roc_incref(self, amount);\n"
+        |> addFunction "void $(name)_decref($(elemType)* self)" "$(decrefContents)$(indent)roc_decref(self, $(alignment));\n"
+
+    if isZeroSized types elem then
+        withRefcounting
+    else
+        addFunction withRefcounting "$(elemType)* $(name)_init($(elemType) value)" initBody
+
+generateResult = \state, types, id, name, ok, err ->
+    variants = [
+        { tagName: "ok", tag: "RocResultTag_Ok", payloadId: ok },
+        { tagName: "err", tag: "RocResultTag_Err", payloadId: err },
+    ]
+
+    unionMembers =
+        List.walk variants "" \accum, { tagName, payloadId } ->
+            if isZeroSized types payloadId then
+                accum
+            else
+                Str.concat accum "$(indent)$(indent)$(typeName types payloadId) $(tagName);\n"
+
+    members =
+        if Str.isEmpty unionMembers then
+            "$(indent)RocResultTag tag;\n"
+        else
+            "$(indent)union {\n$(unionMembers)$(indent)} payload;\n$(indent)RocResultTag tag;\n"
+
+    withFunctions =
+        List.walk variants state \accum, { tagName, tag, payloadId } ->
+            isTagBody =
+                """
+                    return self.tag == $(tag);
+
+                """
+
+            withIsTag = addFunction accum "bool $(name)_is_$(tagName)($(name) self)" isTagBody
+
+            if isZeroSized types payloadId then
+                constructorBody =
+                    """
+                        $(name) result;
+                        memset(&result, 0, sizeof(result));
+                        result.tag = $(tag);
+
+                        return result;
+
+                    """
+
+                addFunction withIsTag "$(name) $(name)_$(tagName)(void)" constructorBody
+            else
+                payloadType = typeName types payloadId
+
+                constructorBody =
+                    """
+                        $(name) result;
+                        memset(&result, 0, sizeof(result));
+                        result.payload.$(tagName) = value;
+                        result.tag = $(tag);
+
+                        return result;
+
+                    """
+
+                getterBody =
+                    """
+                        assert($(name)_is_$(tagName)(self));
+                        return self.payload.$(tagName);
+
+                    """
+
+                withIsTag
+                |> addFunction "$(name) $(name)_$(tagName)($(payloadType) value)" constructorBody
+                |> addFunction "$(payloadType) $(name)_get_$(tagName)($(name) self)" getterBody
+
+    withRefcounting =
+        if isRefcounted types (Types.shape types id) then
+            cases = \call ->
+                List.walk variants "" \accum, { tagName, tag, payloadId } ->
+                    if isRefcounted types (Types.shape types payloadId) then
+                        Str.concat accum "$(indent)case $(tag):\n$(indent)$(indent)$(call tagName payloadId)\n$(indent)$(indent)break;\n"
+                    else
+                        accum
+
+            switch = \body -> "$(indent)switch (self.tag) {\n$(body)$(indent)default:\n$(indent)$(indent)break;\n$(indent)}\n"
+
+            increfs = cases \tagName, payloadId -> "$(identifier types payloadId)_incref(self.payload.$(tagName), amount);"
+            decrefs = cases \tagName, payloadId -> "$(identifier types payloadId)_decref(self.payload.$(tagName));"
+
+            withFunctions
+            |> addFunction "void $(name)_incref($(name) self, size_t amount)" (switch increfs)
+            |> addFunction "void $(name)_decref($(name) self)" (switch decrefs)
+        else
+            withFunctions
+
+    definition =
+        structDecl "struct $(name)" members
+        |> Str.concat (layoutChecks types id name)
+
+    addDefinition withRefcounting definition
+
+## The parameters and the assignments of a constructor of a tag with this payload.
+## Payloads with several values take them as separate parameters named f0, f1, etc.
+payloadConstructor : Types, TypeId -> { params : Str, assignments : Str -> Str }
+payloadConstructor = \types, payloadId ->
+    when Types.shape types payloadId is
+        TagUnionPayload { fields } ->
+            namedFields =
+                structFieldList (nameTagUnionPayloadFields fields)
+                |> List.dropIf \{ id } -> isZeroSized types id
+
+            params =
+                namedFields
+                |> List.map \{ name, id } ->
+                    type = typeName types id
+
+                    "$(type) $(name)"
+                |> Str.joinWith ", "
+
+            assignments = \target ->
+                List.walk namedFields "" \accum, { name } ->
+                    Str.concat accum "$(indent)$(target).$(name) = $(name);\n"
+
+            { params, assignments }
+
+        _ ->
+            type = typeName types payloadId
+
+            { params: "$(type) payload", assignments: \target -> "$(indent)$(target) = payload;\n" }
+
+addIsTagFunction = \state, typeStr, tagName, tagConstant ->
+    body =
+        """
+            return $(typeStr)_discriminant(self) == $(tagConstant);
+
+        """
+
+    addFunction state "bool $(typeStr)_is_$(tagName)($(typeStr) self)" body
+
+## The cases of a `switch` on the discriminant, for the tags whose payloads point into the heap.
+refcountCases = \types, discriminantName, tags, call ->
+    List.walk tags "" \accum, { name: tagName, payload } ->
+        when storedPayload types payload is
+            Some payloadId if isRefcounted types (Types.shape types payloadId) ->
+                Str.concat accum "$(indent)case $(discriminantName)_$(tagName):\n$(indent)$(indent)$(call tagName payloadId)\n$(indent)$(indent)break;\n"
+
+            _ ->
+                accum
+
+switchOnDiscriminant = \typeStr, cases ->
+    "$(indent)switch ($(typeStr)_discriminant(self)) {\n$(cases)$(indent)default:\n$(indent)$(indent)break;\n$(indent)}\n"
+
+## `_incref` and `_decref` functions which forward to the fields that point into the heap,
+## or nothing at all if none of them do.
+addRefcountFunctions = \state, types, id, name, fields ->
+    if isRefcounted types (Types.shape types id) then
+        refcountedFields = List.keepIf fields \{ id: fieldId } -> isRefcounted types (Types.shape types fieldId)
+
+        addFieldRefcountFunctions state types name refcountedFields
+    else
+        state
+
+addFieldRefcountFunctions = \state, types, name, refcountedFields ->
+    if List.isEmpty refcountedFields then
+        state
+    else
+        increfs =
+            List.walk refcountedFields "" \accum, { name: fieldName, id } ->
+                Str.concat accum "$(indent)$(identifier types id)_incref(self.$(escapeKW fieldName), amount);\n"
+
+        decrefs =
+            List.walk refcountedFields "" \accum, { name: fieldName, id } ->
+                Str.concat accum "$(indent)$(identifier types id)_decref(self.$(escapeKW fieldName));\n"
+
+        state
+        |> addFunction "void $(name)_incref($(name) self, size_t amount)" increfs
+        |> addFunction "void $(name)_decref($(name) self)" decrefs
+
+structDecl : Str, Str -> Str
+structDecl = \kind, members ->
+    body =
+        if Str.isEmpty members then
+            # C doesn't allow empty structs. Roc never stores these, because they have a size of 0.
+            "$(indent)char unused;\n"
+        else
+            members
+
+    "$(kind) {\n$(body)};\n"
+
+## Fail the build if the C compiler's layout for a type ever disagrees with the one Roc uses.
+layoutChecks : Types, TypeId, Str -> Str
+layoutChecks = \types, id, name ->
+    size = Types.size types id
+    sizeStr = Num.toStr size
+    alignment = Num.toStr (Types.alignment types id)
+
+    # Zero-sized types don't have a meaningful layout to check.
+    if size == 0 then
+        ""
+    else
+        """
+        ROC_STATIC_ASSERT(sizeof($(name)) == $(sizeStr), "Roc expects $(name) to be $(sizeStr) bytes");
+        ROC_STATIC_ASSERT(ROC_ALIGNOF($(name)) == $(alignment), "Roc expects $(name) to have an alignment of $(alignment)");
+
+        """
+
+## What the functions for a list, set or dictionary need to know about its elements.
+element = \types, id -> {
+    identifier: identifier types id,
+    type: typeName types id,
+    alignment: Types.alignment types id,
+    isRefcounted: isRefcounted types (Types.shape types id),
+    isZeroSized: isZeroSized types id,
+}
+
+unsizedElement = {
+    identifier: "U8",
+    type: "uint8_t",
+    alignment: 1,
+    isRefcounted: Bool.false,
+    isZeroSized: Bool.false,
+}
+
+## The payload of a tag, if it has one which takes up any space.
+storedPayload = \types, payload ->
+    when payload is
+        Some payloadId if !(isZeroSized types payloadId) -> Some payloadId
+        _ -> None
+
+isZeroSized : Types, TypeId -> Bool
+isZeroSized = \types, id -> isZeroSizedShape (Types.shape types id)
+
+isZeroSizedShape : Shape -> Bool
+isZeroSizedShape = \shape ->
+    when shape is
+        Unit | EmptyTagUnion -> Bool.true
+        _ -> Bool.false
+
+## Whether values of this type point into the heap, so the type has `_incref` and `_decref` functions.
+isRefcounted : Types, Shape -> Bool
+isRefcounted = \types, type ->
+    when type is
+        # The host owns the captured values of closures, and frees them with `_deinit`.
+        Function _ -> Bool.false
+        # unsized values are heap-allocated
+        Unsized -> Bool.true
+        Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.false
+        RocStr | RocList _ | RocDict _ _ | RocSet _ | RocBox _ | TagUnion (NullableUnwrapped _) | TagUnion (NullableWrapped _) | TagUnion (Recursive _) | TagUnion (NonNullableUnwrapped _) | RecursivePointer _ -> Bool.true
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+        TagUnion (SingleTagStruct { payload: HasClosure fields }) ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+        TagUnion (NonRecursive { tags }) ->
+            List.any tags \{ payload } ->
+                when payload is
+                    Some id -> isRefcounted types (Types.shape types id)
+                    None -> Bool.false
+
+        RocResult okId errId ->
+            isRefcounted types (Types.shape types okId)
+            || isRefcounted types (Types.shape types errId)
+
+        Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+        Struct { fields: HasClosure fields } | TagUnionPayload { fields: HasClosure fields } ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+## Whether the `roc__*` entry points take an argument of this type by pointer rather than by value.
+isPassedByReference : Types, Shape -> Bool
+isPassedByReference = \types, type ->
+    when type is
+        Function rocFn ->
+            runtimeRepresentation = Types.shape types rocFn.lambdaSet
+            isPassedByReference types runtimeRepresentation
+
+        _ ->
+            isRefcounted types type
+
+typeName = \types, id ->
+    when Types.shape types id is
+        Unit -> "RocUnit"
+        Unsized -> "RocList"
+        EmptyTagUnion -> "RocUnit"
+        RocStr -> "RocStr"
+        Bool -> "bool"
+        Num U8 -> "uint8_t"
+        Num U16 -> "uint16_t"
+        Num U32 -> "uint32_t"
+        Num U64 -> "uint64_t"
+        Num U128 -> "roc_u128"
+        Num I8 -> "int8_t"
+        Num I16 -> "int16_t"
+        Num I32 -> "int32_t"
+        Num I64 -> "int64_t"
+        Num I128 -> "roc_i128"
+        Num F32 -> "float"
+        Num F64 -> "double"
+        Num Dec -> "RocDec"
+        RocDict _ _ -> "RocDict"
+        RocSet _ -> "RocSet"
+        RocList _ -> "RocList"
+        RocBox elem ->
+            elemName = typeName types elem
+
+            "$(elemName)*"
+
+        RocResult _ _ ->
+            identifier types id
+
+        RecursivePointer content ->
+            typeName types content
+
+        Struct { name } -> escapeKW name
+        TagUnionPayload { name } -> payloadTypeName name
+        TagUnion (NonRecursive { name }) -> escapeKW name
+        TagUnion (Recursive { name }) -> escapeKW name
+        TagUnion (Enumeration { name }) -> escapeKW name
+        TagUnion (NullableWrapped { name }) -> escapeKW name
+        TagUnion (NullableUnwrapped { name }) -> escapeKW name
+        TagUnion (NonNullableUnwrapped { name }) -> escapeKW name
+        TagUnion (SingleTagStruct { name }) -> escapeKW name
+        Function { functionName } -> escapeKW functionName
+
+## A name for the type which can be used in C identifiers, such as `RocList_RocStr`.
+## The names of a type's functions start with this, e.g. `RocList_RocStr_decref`.
+identifier = \types, id ->
+    when Types.shape types id is
+        Unit | EmptyTagUnion -> "RocUnit"
+        Unsized -> "RocList_U8"
+        RocStr -> "RocStr"
+        Bool -> "Bool"
+        Num U8 -> "U8"
+        Num U16 -> "U16"
+        Num U32 -> "U32"
+        Num U64 -> "U64"
+        Num U128 -> "U128"
+        Num I8 -> "I8"
+        Num I16 -> "I16"
+        Num I32 -> "I32"
+        Num I64 -> "I64"
+        Num I128 -> "I128"
+        Num F32 -> "F32"
+        Num F64 -> "F64"
+        Num Dec -> "RocDec"
+        RocDict key value ->
+            keyName = identifier types key
+            valueName = identifier types value
+
+            "RocDict_$(keyName)_$(valueName)"
+
+        RocSet elem ->
+            elemName = identifier types elem
+
+            "RocSet_$(elemName)"
+
+        RocList elem ->
+            elemName = identifier types elem
+
+            "RocList_$(elemName)"
+
+        RocBox elem ->
+            elemName = identifier types elem
+
+            "RocBox_$(elemName)"
+
+        RocResult ok err ->
+            okName = identifier types ok
+            errName = identifier types err
+
+            "RocResult_$(okName)_$(errName)"
+
+        RecursivePointer content ->
+            identifier types content
+
+        _ ->
+            typeName types id
+
+## The preprocessor condition for compiling for this architecture, with GCC, Clang or MSVC.
+archCondition = \arch ->
+    when arch is
+        Aarch32 ->
+            "defined(__arm__) || defined(_M_ARM)"
+
+        Aarch64 ->
+            "defined(__aarch64__) || defined(_M_ARM64)"
+
+        Wasm32 ->
+            "defined(__wasm32__)"
+
+        X86x32 ->
+            "defined(__i386__) || defined(_M_IX86)"
+
+        X86x64 ->
+            "defined(__x86_64__) || defined(_M_X64)"
+
+indent = "    "
+
+## Put every line one level deeper.
+indentLines : Str -> Str
+indentLines = \lines ->
+    lines
+    |> Str.split "\n"
+    |> List.map \line -> if Str.isEmpty line then line else "$(indent)$(line)"
+    |> Str.joinWith "\n"
+
+paramList : Str -> Str
+paramList = \params ->
+    if Str.isEmpty params then
+        "void"
+    else
+        params
+
+# C and C++ keywords, which the header has to work with both of
+reservedKeywords = Set.fromList [
+    "alignas",
+    "alignof",
+    "and",
+    "asm",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "constexpr",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "explicit",
+    "export",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "mutable",
+    "namespace",
+    "new",
+    "not",
+    "nullptr",
+    "operator",
+    "or",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typeid",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+    "xor",
+]
+
+escapeKW = \input ->
+    # C has no way to use a keyword as an identifier, so add an underscore to it.
+    if Set.contains reservedKeywords input then
+        "$(input)_"
+    else
+        input
+
+toArgStr : List TypeId, Types, (TypeId, Shape, U64 -> Str) -> Str
+toArgStr = \args, types, fmt ->
+    List.walkWithIndex args "" \state, argId, index ->
+        shape = Types.shape types argId
+
+        # Drop `{}` args; nothing will get passed anyway.
+        if isZeroSizedShape shape then
+            state
+        else
+            argStr = fmt argId shape index
+
+            if Str.isEmpty state then
+                argStr # Don't prepend a comma if this is the first one
+            else
+                state
+                |> Str.concat ", "
+                |> Str.concat argStr
+
+prependComma : Str -> Str
+prependComma = \args ->
+    if Str.isEmpty args then
+        ""
+    else
+        ", $(args)"
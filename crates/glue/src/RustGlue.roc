@@ -59,39 +59,59 @@ convertTypesToFile = \types ->
     content =
         Types.walkShapes types fileHeader \buf, type, id ->
             when type is
+                Struct { name, fields: HasClosure fields } ->
+                    generateOpaqueStruct buf types id name fields
+
                 Struct { name, fields } ->
                     generateStruct buf types id name fields Public
 
+                TagUnionPayload { name, fields: HasClosure fields } ->
+                    payloadFields = List.map fields \field -> { field & name: "f$(field.name)" }
+
+                    generateOpaqueStruct buf types id name payloadFields
+
                 TagUnionPayload { name, fields } ->
                     generateStruct buf types id name (nameTagUnionPayloadFields fields) Public
 
                 TagUnion (Enumeration { name, tags, size }) ->
                     generateEnumeration buf types type name tags size
+                    |> generateLayoutChecks types id (escapeKW name)
 
                 TagUnion (NonRecursive { name, tags, discriminantSize, discriminantOffset }) ->
                     if !(List.isEmpty tags) then
                         generateNonRecursiveTagUnion buf types id name tags discriminantSize discriminantOffset
+                        |> generateLayoutChecks types id (escapeKW name)
                     else
                         buf
 
                 TagUnion (Recursive { name, tags, discriminantSize, discriminantOffset }) ->
-                    if !(List.isEmpty tags) then
-                        generateRecursiveTagUnion buf types id name tags discriminantSize discriminantOffset None
-                    else
+                    if List.isEmpty tags then
                         buf
+                    else if hasRuntimeSizedPayload types tags then
+                        generateOpaqueRecursiveTagUnion buf types name tags discriminantSize None
+                        |> generateLayoutChecks types id (escapeKW name)
+                    else
+                        generateRecursiveTagUnion buf types id name tags discriminantSize discriminantOffset None
+                        |> generateLayoutChecks types id (escapeKW name)
 
                 TagUnion (NullableWrapped { name, indexOfNullTag, tags, discriminantSize, discriminantOffset }) ->
-                    # TODO: generate this as `TypeName(*mut u8)` if the payload contains functions / unsized types
-                    generateRecursiveTagUnion buf types id name tags discriminantSize discriminantOffset (Some indexOfNullTag)
+                    if hasRuntimeSizedPayload types tags then
+                        generateOpaqueRecursiveTagUnion buf types name tags discriminantSize (Some indexOfNullTag)
+                        |> generateLayoutChecks types id (escapeKW name)
+                    else
+                        generateRecursiveTagUnion buf types id name tags discriminantSize discriminantOffset (Some indexOfNullTag)
+                        |> generateLayoutChecks types id (escapeKW name)
 
                 TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull }) ->
-                    generateNullableUnwrapped buf types id name nullTag nonNullTag nonNullPayload whichTagIsNull
+                    generateNullableUnwrapped buf types name nullTag nonNullTag nonNullPayload whichTagIsNull
+                    |> generateLayoutChecks types id (escapeKW name)
 
                 TagUnion (SingleTagStruct { name, tagName, payload }) ->
-                    generateSingleTagStruct buf types name tagName payload
+                    generateSingleTagStruct buf types id name tagName payload
 
                 TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
                     generateNonNullableUnwrapped buf types name tagName payload 0 0 None
+                    |> generateLayoutChecks types id (escapeKW name)
 
                 Function rocFn ->
                     if rocFn.isToplevel then
@@ -104,8 +124,10 @@ convertTypesToFile = \types ->
                     # so no extra work needs to happen.
                     buf
 
+                Unsized ->
+                    generateOpaque buf types id "RocUnsized" unsizedDocComment "" unsizedRelease
+
                 Unit
-                | Unsized
                 | EmptyTagUnion
                 | Num _
                 | Bool
@@ -151,28 +173,31 @@ generateEntryPoint = \buf, types, name, id ->
                 ret = typeName types id
                 "() -> $(ret)"
 
-    (externSignature, returnTypeName, returnsFn) =
+    (externSignature, returnTypeName, runtimeSizedReturn) =
         when Types.shape types id is
             Function rocFn ->
                 arguments =
                     toArgStr rocFn.args types \argId, shape, _index ->
                         type = typeName types argId
 
-                        if canDeriveCopy types shape then
-                            "_: $(type)"
-                        else
-                            "_: &mut core::mem::ManuallyDrop<$(type)>"
+                        when runtimeSizedPointer shape is
+                            Some _ -> "_: *mut u8"
+                            None if canDeriveCopy types shape -> "_: $(type)"
+                            None -> "_: &mut core::mem::ManuallyDrop<$(type)>"
 
                 ret = typeName types rocFn.ret
-                when Types.shape types rocFn.ret is
-                    Function _ ->
-                        ("(_: *mut u8, $(arguments))", ret, Bool.true)
-                    _ ->
-                        ("(_: *mut $(ret), $(arguments))", ret, Bool.false)
+                retShape = Types.shape types rocFn.ret
+
+                when runtimeSizedPointer retShape is
+                    Some field ->
+                        ("(_: *mut u8, $(arguments))", ret, Some field)
+
+                    None ->
+                        ("(_: *mut $(ret), $(arguments))", ret, None)
 
             _ ->
                 ret = typeName types id
-                ("(_: *mut $(ret))", ret, Bool.false)
+                ("(_: *mut $(ret))", ret, None)
 
     externArguments =
         when Types.shape types id is
@@ -180,61 +205,65 @@ generateEntryPoint = \buf, types, name, id ->
                 toArgStr rocFn.args types \_argId, shape, index ->
                     indexStr = Num.toStr index
 
-                    if canDeriveCopy types shape then
-                        "arg$(indexStr)"
-                    else
-                        "&mut core::mem::ManuallyDrop::new(arg$(indexStr))"
+                    when runtimeSizedPointer shape is
+                        Some field -> "arg$(indexStr).$(field)"
+                        None if canDeriveCopy types shape -> "arg$(indexStr)"
+                        None -> "&mut core::mem::ManuallyDrop::new(arg$(indexStr))"
 
             _ ->
                 ""
 
-    if returnsFn then
-        """
-        $(buf)
-
-        pub fn $(name)$(publicSignature) {
-            extern "C" {
-                fn roc__$(name)_1_exposed_generic$(externSignature);
-                fn roc__$(name)_1_exposed_size() -> i64;
-            }
+    when runtimeSizedReturn is
+        Some field ->
+            # Only the Roc application knows how big the returned value is, so ask it.
+            """
+            $(buf)
 
-            unsafe {
-                let capacity = roc__$(name)_1_exposed_size() as usize;
+            pub fn $(name)$(publicSignature) {
+                extern "C" {
+                    fn roc__$(name)_1_exposed_generic$(externSignature);
+                    fn roc__$(name)_1_exposed_size() -> i64;
+                }
 
-                let mut ret = $(returnTypeName) {
-                    closure_data: Vec::with_capacity(capacity),
-                };
-                ret.closure_data.resize(capacity, 0);
+                unsafe {
+                    let ret = $(returnTypeName)::uninit(roc__$(name)_1_exposed_size() as usize);
 
-                roc__$(name)_1_exposed_generic(ret.closure_data.as_mut_ptr(), $(externArguments));
+                    roc__$(name)_1_exposed_generic(ret.$(field), $(externArguments));
 
-                ret
+                    ret
+                }
             }
-        }
-        """
-    else
-        """
-        $(buf)
+            """
 
-        pub fn $(name)$(publicSignature) {
-            extern "C" {
-                fn roc__$(name)_1_exposed_generic$(externSignature);
-            }
+        None ->
+            """
+            $(buf)
 
-            let mut ret = core::mem::MaybeUninit::uninit();
+            pub fn $(name)$(publicSignature) {
+                extern "C" {
+                    fn roc__$(name)_1_exposed_generic$(externSignature);
+                }
 
-            unsafe {
-                roc__$(name)_1_exposed_generic(ret.as_mut_ptr(), $(externArguments));
+                let mut ret = core::mem::MaybeUninit::uninit();
 
-                ret.assume_init()
+                unsafe {
+                    roc__$(name)_1_exposed_generic(ret.as_mut_ptr(), $(externArguments));
+
+                    ret.assume_init()
+                }
             }
-        }
-        """
+            """
 
 generateFunction : Str, Types, RocFn -> Str
 generateFunction = \buf, types, rocFn ->
     name = rocFn.functionName
     externName = rocFn.externName
+    sizeName = rocFn.externSizeName
+    resultSizeName = rocFn.externResultSizeName
+    # Like the size functions, this is named after the closure's caller.
+    dropName = Str.replaceLast externName "_caller" "_drop"
+
+    closureAlignment = Num.max 1 (Types.alignment types rocFn.lambdaSet) |> Num.toStr
 
     publicArguments =
         toArgStr rocFn.args types \argId, _shape, index ->
@@ -243,13 +272,21 @@ generateFunction = \buf, types, rocFn ->
 
             "arg$(indexStr): $(type)"
 
+    argumentNames =
+        toArgStr rocFn.args types \_argId, _shape, index ->
+            indexStr = Num.toStr index
+
+            "arg$(indexStr)"
+
     externDefArguments =
         withoutUnit =
-            toArgStr rocFn.args types \argId, _shape, index ->
+            toArgStr rocFn.args types \argId, shape, index ->
                 type = typeName types argId
                 indexStr = Num.toStr index
 
-                "arg$(indexStr): *const $(type)"
+                when runtimeSizedPointer shape is
+                    Some _ -> "arg$(indexStr): *const u8"
+                    None -> "arg$(indexStr): *const $(type)"
 
         if Str.isEmpty withoutUnit then
             # These always have a first argument that's a pointer, even if it's to nothing.
@@ -259,10 +296,12 @@ generateFunction = \buf, types, rocFn ->
 
     externCallArguments =
         withoutUnit =
-            toArgStr rocFn.args types \_argId, _shape, index ->
+            toArgStr rocFn.args types \_argId, shape, index ->
                 indexStr = Num.toStr index
 
-                "&arg$(indexStr)"
+                when runtimeSizedPointer shape is
+                    Some field -> "arg$(indexStr).$(field)"
+                    None -> "&*arg$(indexStr)"
 
         if Str.isEmpty withoutUnit then
             # These always have a first argument that's a pointer, even if it's to nothing.
@@ -270,34 +309,392 @@ generateFunction = \buf, types, rocFn ->
         else
             withoutUnit
 
+    # Roc takes ownership of the arguments, so they mustn't be dropped here too.
+    # The buffers of runtime-sized arguments are still freed, because Roc only
+    # takes ownership of what's in them.
+    argumentMoves =
+        List.walkWithIndex rocFn.args "" \accum, argId, index ->
+            shape = Types.shape types argId
+            indexStr = Num.toStr index
+
+            when runtimeSizedPointer shape is
+                None if !(isUnit shape) ->
+                    Str.concat accum "$(indent)$(indent)let arg$(indexStr) = core::mem::ManuallyDrop::new(arg$(indexStr));\n"
+
+                _ ->
+                    accum
+
     publicComma = if Str.isEmpty publicArguments then "" else ", "
 
     ret = typeName types rocFn.ret
 
+    (outputParam, callBody) =
+        when runtimeSizedPointer (Types.shape types rocFn.ret) is
+            Some field ->
+                (
+                    "output: *mut u8",
+                    """
+                            extern "C" {
+                                fn $(resultSizeName)() -> i64;
+                            }
+
+                            unsafe {
+                                let output = $(ret)::uninit($(resultSizeName)() as usize);
+
+                                $(externName)($(externCallArguments), closure.closure_data, output.$(field));
+
+                                output
+                            }
+                    """,
+                )
+
+            None ->
+                (
+                    "output: *mut $(ret)",
+                    """
+                            let mut output = core::mem::MaybeUninit::uninit();
+
+                            unsafe {
+                                $(externName)($(externCallArguments), closure.closure_data, output.as_mut_ptr());
+
+                                output.assume_init()
+                            }
+                    """,
+                )
+
     """
     $(buf)
 
-    #[repr(C)]
+    /// A Roc closure. Only the Roc application knows what it captures, so the captured
+    /// values are kept in a buffer of `closure_size()` bytes, which Roc writes them into.
     #[derive(Debug)]
     pub struct $(name) {
-        closure_data: Vec<u8>,
+        closure_data: *mut u8,
+        closure_size: usize,
     }
 
     impl $(name) {
-        pub fn force_thunk(mut self$(publicComma)$(publicArguments)) -> $(ret) {
+        /// The number of bytes the captured values take up, which can change whenever the app does.
+        pub fn closure_size() -> usize {
+            extern "C" {
+                fn $(sizeName)() -> i64;
+            }
+
+            unsafe { $(sizeName)() as usize }
+        }
+
+        /// The alignment the buffer of captured values needs.
+        pub const fn closure_alignment() -> usize {
+            $(closureAlignment)
+        }
+
+        /// Allocate a buffer of `size` bytes for Roc to write the captured values into.
+        fn uninit(size: usize) -> Self {
+            let layout = Self::closure_layout(size);
+            let closure_data = unsafe { std::alloc::alloc_zeroed(layout) };
+
+            if closure_data.is_null() {
+                std::alloc::handle_alloc_error(layout);
+            }
+
+            Self {
+                closure_data,
+                closure_size: size,
+            }
+        }
+
+        fn closure_layout(size: usize) -> std::alloc::Layout {
+            // Allocations can't be empty, so closures which capture nothing get a byte anyway.
+            std::alloc::Layout::from_size_align(size.max(1), Self::closure_alignment()).unwrap()
+        }
+
+        /// Call the closure. Roc takes ownership of the arguments and of the captured values,
+        /// so this consumes the closure.
+        pub fn call(self$(publicComma)$(publicArguments)) -> $(ret) {
+            extern "C" {
+                fn $(externName)($(externDefArguments), closure_data: *mut u8, $(outputParam));
+            }
+
+            // Only the buffer is freed afterwards, because the captured values belong to Roc now.
+            let closure = core::mem::ManuallyDrop::new(self);
+    $(argumentMoves)
+            let output = {
+    $(callBody)
+            };
+
+            unsafe { std::alloc::dealloc(closure.closure_data, Self::closure_layout(closure.closure_size)) };
+
+            output
+        }
+
+        pub fn force_thunk(self$(publicComma)$(publicArguments)) -> $(ret) {
+            self.call($(argumentNames))
+        }
+    }
+
+    impl Drop for $(name) {
+        /// Release the captured values of a closure which was never called, and free the buffer.
+        fn drop(&mut self) {
             extern "C" {
-                fn $(externName)($(externDefArguments), closure_data: *mut u8, output: *mut $(ret));
+                fn $(dropName)(closure_data: *mut u8);
+            }
+
+            unsafe {
+                $(dropName)(self.closure_data);
+
+                std::alloc::dealloc(self.closure_data, Self::closure_layout(self.closure_size));
             }
+        }
+    }
+    """
+
+## Values of these types have a size which only the Roc application knows, because they
+## contain closures. Rust can't store them inline, so they're wrapped in a type which owns a
+## buffer for Roc to write them into, and this returns the field holding a pointer to it.
+runtimeSizedPointer : Shape -> [Some Str, None]
+runtimeSizedPointer = \shape ->
+    when shape is
+        Function { isToplevel } if !isToplevel -> Some "closure_data"
+        Unsized -> Some "0"
+        Struct { fields: HasClosure _ } | TagUnionPayload { fields: HasClosure _ } -> Some "0"
+        TagUnion (SingleTagStruct { payload: HasClosure _ }) -> Some "0"
+        _ -> None
+
+hasRuntimeSizedPayload : Types, List { name : Str, payload : [Some TypeId, None] } -> Bool
+hasRuntimeSizedPayload = \types, tags ->
+    List.any tags \{ payload } ->
+        when payload is
+            Some id -> runtimeSizedPointer (Types.shape types id) != None
+            None -> Bool.false
+
+## A pointer to a Roc allocation holding a value whose layout only the Roc application knows.
+## `release` is run when the last reference to the value is dropped.
+generateOpaque : Str, Types, TypeId, Str, Str, Str, Str -> Str
+generateOpaque = \buf, types, id, name, docComment, methods, release ->
+    alignment = Num.max 1 (Types.alignment types id) |> Num.toStr
+
+    """
+    $(buf)
+
+    $(docComment)
+    #[repr(transparent)]
+    #[derive(Debug)]
+    pub struct $(name)(*mut u8);
 
-            let mut output = core::mem::MaybeUninit::uninit();
+    impl $(name) {
+        /// The alignment of the allocation, which also leaves room for the refcount before the value.
+        fn allocation_alignment() -> usize {
+            core::cmp::max($(alignment), core::mem::align_of::<roc_std::Storage>())
+        }
+
+        /// Allocate space for a value of `size` bytes, for Roc to write it into.
+        fn uninit(size: usize) -> Self {
+            let alignment = Self::allocation_alignment();
 
             unsafe {
-                $(externName)($(externCallArguments), self.closure_data.as_mut_ptr(), output.as_mut_ptr());
+                let allocation = roc_std::roc_alloc(alignment + size, alignment as u32).cast::<u8>();
+
+                if allocation.is_null() {
+                    panic!("Failed to allocate a $(name)");
+                }
+
+                // Roc keeps the refcount right before the value.
+                let data = allocation.add(alignment);
+                data.cast::<roc_std::Storage>()
+                    .sub(1)
+                    .write(roc_std::Storage::new_reference_counted());
+
+                Self(data)
+            }
+        }
+
+        fn incref(&self) {
+            let storage = unsafe { &*self.0.cast::<core::cell::Cell<roc_std::Storage>>().sub(1) };
+            let mut new_storage = storage.get();
+
+            new_storage.increment_reference_count();
+            storage.set(new_storage);
+        }
+
+        /// Give up a reference to the value, unless it's the last one, in which case this returns
+        /// `true` and the reference is left for the caller to release.
+        fn decref(&self) -> bool {
+            let storage = unsafe { &*self.0.cast::<core::cell::Cell<roc_std::Storage>>().sub(1) };
+            let mut new_storage = storage.get();
+
+            let is_last = new_storage.decrease();
+            storage.set(new_storage);
+
+            is_last
+        }
+    $(methods)}
 
-                output.assume_init()
+    impl Drop for $(name) {
+        fn drop(&mut self) {
+            if self.decref() {
+    $(release)
             }
         }
     }
+
+    """
+
+generateOpaqueStruct = \buf, types, id, name, fields ->
+    escapedName = escapeKW name
+
+    docComment =
+        """
+        /// This contains closures, so only the Roc application knows its layout. It points to a
+        /// Roc allocation holding the value, and its fields are read with getters Roc exports.
+        """
+
+    getters =
+        List.walk fields "" \accum, { name: fieldName, id: fieldId, accessors } ->
+            Str.concat accum (generateGetter types (escapeKW fieldName) fieldId accessors.getter)
+
+    # Only Roc knows how to release what the value holds. A getter takes ownership of a
+    # reference to the value, so handing it the last one makes Roc release the value.
+    release =
+        when List.first fields is
+            Ok { name: fieldName } ->
+                methodName = escapeKW fieldName
+
+                "$(indent)$(indent)$(indent)drop(unsafe { self.take_$(methodName)() });"
+
+            Err ListWasEmpty ->
+                crash "Opaque structs always have a field, since they contain closures."
+
+    generateOpaque buf types id escapedName docComment getters release
+
+generateGetter = \types, methodName, fieldId, getter ->
+    fieldType = typeName types fieldId
+
+    takeBody =
+        when runtimeSizedPointer (Types.shape types fieldId) is
+            Some field ->
+                """
+                        extern "C" {
+                            fn $(getter)_generic(output: *mut u8, value: *mut u8);
+                            fn $(getter)_size() -> i64;
+                        }
+
+                        let output = $(fieldType)::uninit($(getter)_size() as usize);
+
+                        $(getter)_generic(output.$(field), self.0);
+
+                        output
+                """
+
+            None ->
+                """
+                        extern "C" {
+                            fn $(getter)_generic(output: *mut $(fieldType), value: *mut u8);
+                        }
+
+                        let mut output = core::mem::MaybeUninit::uninit();
+
+                        $(getter)_generic(output.as_mut_ptr(), self.0);
+
+                        output.assume_init()
+                """
+
+    """
+
+        pub fn $(methodName)(&self) -> $(fieldType) {
+            // The getter takes ownership of a reference to the value.
+            self.incref();
+
+            unsafe { self.take_$(methodName)() }
+        }
+
+        /// Hand one of our references to the value to Roc's getter, which releases it.
+        unsafe fn take_$(methodName)(&self) -> $(fieldType) {
+    $(takeBody)
+        }
+
+    """
+
+## A recursive tag union whose payloads contain closures. It's a pointer like any other
+## recursive tag union, but only the Roc application knows the layout of what it points to.
+generateOpaqueRecursiveTagUnion = \buf, types, tagUnionName, tags, discriminantSize, nullTagIndex ->
+    escapedName = escapeKW tagUnionName
+    discriminantName = "discriminant_$(escapedName)"
+    tagNames = List.map tags .name
+
+    discriminants =
+        tagNames
+        |> Str.joinWith ", "
+        |> \b -> "[ $(b) ]"
+
+    nullTagId =
+        when nullTagIndex is
+            Some index ->
+                n = Num.toStr index
+                "discriminants[$(n)]"
+
+            None ->
+                """
+                unreachable!("this pointer cannot be NULL")
+                """
+
+    isTagFunctions =
+        List.walk tagNames "" \accum, tagName ->
+            Str.concat
+                accum
+                """
+
+                    pub fn is_$(tagName)(&self) -> bool {
+                        matches!(self.discriminant(), $(discriminantName)::$(tagName))
+                    }
+
+                """
+
+    buf
+    |> generateDiscriminant types discriminantName tagNames discriminantSize
+    |> Str.concat
+        """
+        /// The payloads of this tag union contain closures, so only the Roc application knows
+        /// their layout. This is a pointer to them, with the discriminant in its lowest bits.
+        #[repr(transparent)]
+        #[derive(Debug)]
+        pub struct $(escapedName)(*mut u8);
+
+        impl $(escapedName) {
+            $(discriminantDocComment)
+            pub fn discriminant(&self) -> $(discriminantName) {
+                let discriminants = {
+                    use $(discriminantName)::*;
+
+                    $(discriminants)
+                };
+
+                if self.0.is_null() {
+                    $(nullTagId)
+                } else {
+                    match std::mem::size_of::<usize>() {
+                        4 => discriminants[self.0 as usize & 0b011],
+                        8 => discriminants[self.0 as usize & 0b111],
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        $(isTagFunctions)}
+
+
+        """
+
+unsizedDocComment =
+    """
+    /// A value whose size only the Roc application knows, such as the captured values of a
+    /// closure. It points to a Roc allocation holding the value. Roc exports nothing to release
+    /// what the value holds, so dropping the last reference to it only frees the allocation.
+    """
+
+unsizedRelease =
+    """
+                let alignment = Self::allocation_alignment();
+
+                unsafe { roc_std::roc_dealloc(self.0.sub(alignment).cast(), alignment as u32) };
     """
 
 generateStruct : Str, Types, TypeId, _, _, _ -> Str
@@ -320,11 +717,18 @@ generateStruct = \buf, types, id, name, structFields, visibility ->
 
     structType = Types.shape types id
 
+    layoutFields =
+        when structFields is
+            HasClosure xs -> List.map xs \field -> { name: field.name, id: field.id }
+            HasNoClosure xs -> xs
+
     buf
     |> generateDeriveStr types structType IncludeDebug
     |> Str.concat "#[repr($(repr))]\n$(pub)struct $(escapedName) {\n"
     |> generateStructFields types Public structFields
     |> Str.concat "}\n\n"
+    |> generateLayoutChecks types id escapedName
+    |> generateFieldOffsetChecks types id escapedName layoutFields
 
 generateStructFields = \buf, types, visibility, structFields ->
     when structFields is
@@ -346,6 +750,41 @@ generateStructFieldWithoutClosure = \types, visibility ->
 
         Str.concat accum "$(indent)$(pub) $(escapedFieldName): $(typeStr),\n"
 
+## Check at compile time that the generated type has the size and alignment the compiler
+## gave it on this target, so that a mismatch fails the host's build instead of corrupting memory.
+generateLayoutChecks = \buf, types, id, name ->
+    size = Num.toStr (Types.size types id)
+    alignment = Num.toStr (Types.alignment types id)
+
+    buf
+    |> Str.concat "const _SIZE_CHECK_$(name): () = assert!(core::mem::size_of::<$(name)>() == $(size));\n"
+    |> Str.concat "const _ALIGN_CHECK_$(name): () = assert!(core::mem::align_of::<$(name)>() == $(alignment));\n\n"
+
+## Check each field is at the offset the compiler's layout gives it on this target.
+generateFieldOffsetChecks = \buf, types, id, name, fields ->
+    if List.len fields <= 1 then
+        # A single field is always at offset 0.
+        buf
+    else
+        checks =
+            List.map2 fields (Types.fieldOffsets types id) \{ name: fieldName }, fieldOffset ->
+                escapedFieldName = escapeKW fieldName
+                offsetStr = Num.toStr fieldOffset
+
+                """
+                const _OFFSET_CHECK_$(name)_$(fieldName): () = {
+                    let value = core::mem::MaybeUninit::<$(name)>::uninit();
+                    let base = value.as_ptr();
+                    let offset = unsafe { core::ptr::addr_of!((*base).$(escapedFieldName)).cast::<u8>().offset_from(base.cast::<u8>()) };
+
+                    assert!(offset == $(offsetStr));
+                };
+
+                """
+            |> Str.joinWith ""
+
+        "$(buf)$(checks)\n"
+
 nameTagUnionPayloadFields = \payloadFields ->
     # Tag union payloads have numbered fields, so we prefix them
     # with an "f" because Rust doesn't allow struct fields to be numbers.
@@ -686,8 +1125,6 @@ generateNonRecursiveTagUnion = \buf, types, id, name, tags, discriminantSize, di
         |> nextMultipleOf alignOfUnion
         |> Num.toStr
 
-    sizeOfSelf = Num.toStr (Types.size types id)
-    alignOfSelf = Num.toStr (Types.alignment types id)
     shape = Types.shape types id
 
     # TODO: this value can be different than the alignment of `id`
@@ -709,9 +1146,6 @@ generateNonRecursiveTagUnion = \buf, types, id, name, tags, discriminantSize, di
         const _SIZE_CHECK_$(unionName): () = assert!(core::mem::size_of::<$(unionName)>() == $(sizeOfUnionStr));
         const _ALIGN_CHECK_$(unionName): () = assert!(core::mem::align_of::<$(unionName)>() == $(alignOfUnionStr));
 
-        const _SIZE_CHECK_$(escapedName): () = assert!(core::mem::size_of::<$(escapedName)>() == $(sizeOfSelf));
-        const _ALIGN_CHECK_$(escapedName): () = assert!(core::mem::align_of::<$(escapedName)>() == $(alignOfSelf));
-
         impl $(escapedName) {
             $(discriminantDocComment)
             pub fn discriminant(&self) -> $(discriminantName) {
@@ -1164,9 +1598,6 @@ generateRecursiveTagUnion = \buf, types, id, tagUnionName, tags, discriminantSiz
         else
             ""
 
-    sizeOfSelf = Num.toStr (Types.size types id)
-    alignOfSelf = Num.toStr (Types.alignment types id)
-
     buf
     |> generateDiscriminant types discriminantName tagNames discriminantSize
     |> Str.concat
@@ -1174,9 +1605,6 @@ generateRecursiveTagUnion = \buf, types, id, tagUnionName, tags, discriminantSiz
         #[repr(transparent)]
         pub struct $(escapedName)(*mut $(unionName));
 
-        const _SIZE_CHECK_$(escapedName): () = assert!(core::mem::size_of::<$(escapedName)>() == $(sizeOfSelf));
-        const _ALIGN_CHECK_$(escapedName): () = assert!(core::mem::align_of::<$(escapedName)>() == $(alignOfSelf));
-
         impl $(escapedName) {
             pub fn discriminant(&self) -> discriminant_$(escapedName) {
                 let discriminants = {
@@ -1374,8 +1802,8 @@ commaSeparated = \buf, items, step ->
     |> List.walk { buf, count: 0 } help
     |> .buf
 
-generateNullableUnwrapped : Str, Types, TypeId, Str, Str, Str, TypeId, [FirstTagIsNull, SecondTagIsNull] -> Str
-generateNullableUnwrapped = \buf, types, tagUnionid, name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull ->
+generateNullableUnwrapped : Str, Types, Str, Str, Str, TypeId, [FirstTagIsNull, SecondTagIsNull] -> Str
+generateNullableUnwrapped = \buf, types, name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull ->
     payloadFields =
         when Types.shape types nonNullPayload is
             TagUnionPayload { fields } ->
@@ -1424,9 +1852,6 @@ generateNullableUnwrapped = \buf, types, tagUnionid, name, nullTag, nonNullTag,
                 }
                 """
 
-    sizeOfSelf = Num.toStr (Types.size types tagUnionid)
-    alignOfSelf = Num.toStr (Types.alignment types tagUnionid)
-
     """
     $(buf)
 
@@ -1436,9 +1861,6 @@ generateNullableUnwrapped = \buf, types, tagUnionid, name, nullTag, nonNullTag,
 
     $(discriminant)
 
-    const _SIZE_CHECK_$(name): () = assert!(core::mem::size_of::<$(name)>() == $(sizeOfSelf));
-    const _ALIGN_CHECK_$(name): () = assert!(core::mem::align_of::<$(name)>() == $(alignOfSelf));
-
     impl $(name) {
         pub fn $(nullTag)() -> Self {
             Self(core::ptr::null_mut())
@@ -1528,7 +1950,7 @@ generateNullableUnwrapped = \buf, types, tagUnionid, name, nullTag, nonNullTag,
     }
     """
 
-generateSingleTagStruct = \buf, types, name, tagName, payload ->
+generateSingleTagStruct = \buf, types, typeId, name, tagName, payload ->
     # Store single-tag unions as structs rather than enums,
     # because they have only one alternative. However, still
     # offer the usual tag union APIs.
@@ -1545,12 +1967,12 @@ generateSingleTagStruct = \buf, types, name, tagName, payload ->
 
     when payload is
         HasNoClosure fields ->
-            asStructFields =
+            payloadFields =
                 List.mapWithIndex fields \{ id }, index ->
                     indexStr = Num.toStr index
 
                     { name: "f$(indexStr)", id }
-                |> HasNoClosure
+            asStructFields = HasNoClosure payloadFields
             asStructType =
                 Struct {
                     name,
@@ -1565,9 +1987,18 @@ generateSingleTagStruct = \buf, types, name, tagName, payload ->
                     generateZeroElementSingleTagStruct b escapedName tagName
                 else
                     generateMultiElementSingleTagStruct b types escapedName tagName fields asStructFields
+            |> generateLayoutChecks types typeId escapedName
+            |> generateFieldOffsetChecks types typeId escapedName payloadFields
+
+        HasClosure fields ->
+            # For these, the names are the getters which Roc generated for each field.
+            payloadFields =
+                List.mapWithIndex fields \{ name: getter, id: fieldId }, index ->
+                    indexStr = Num.toStr index
 
-        HasClosure _ ->
-            Str.concat buf "\\TODO: SingleTagStruct with closures"
+                    { name: "f$(indexStr)", id: fieldId, accessors: { getter } }
+
+            generateOpaqueStruct buf types typeId name payloadFields
 
 generateMultiElementSingleTagStruct = \buf, types, name, tagName, payloadFields, asStructFields ->
     buf
@@ -1765,10 +2196,8 @@ canSupportEqHashOrd = \types, type ->
 canSupportPartialEqOrd : Types, Shape -> Bool
 canSupportPartialEqOrd = \types, type ->
     when type is
-        Function rocFn ->
-            runtimeRepresentation = Types.shape types rocFn.lambdaSet
-            canSupportPartialEqOrd types runtimeRepresentation
-
+        # closures can only be called, not inspected
+        Function _ -> Bool.false
         Unsized -> Bool.false
         Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.true
         RocStr -> Bool.true
@@ -1822,8 +2251,8 @@ canSupportPartialEqOrd = \types, type ->
         Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
             List.all fields \{ id } -> canSupportPartialEqOrd types (Types.shape types id)
 
-        Struct { fields: HasClosure fields } | TagUnionPayload { fields: HasClosure fields } ->
-            List.all fields \{ id } -> canSupportPartialEqOrd types (Types.shape types id)
+        Struct { fields: HasClosure _ } | TagUnionPayload { fields: HasClosure _ } ->
+            Bool.false
 
 cannotSupportCopy : Types, Shape -> Bool
 cannotSupportCopy = \types, type ->
@@ -1832,19 +2261,15 @@ cannotSupportCopy = \types, type ->
 canDeriveCopy : Types, Shape -> Bool
 canDeriveCopy = \types, type ->
     when type is
-        Function rocFn ->
-            runtimeRepresentation = Types.shape types rocFn.lambdaSet
-            canDeriveCopy types runtimeRepresentation
-
-        # unsized values are heap-allocated
-        Unsized -> Bool.false
+        # closures and unsized values are heap-allocated
+        Function _ | Unsized -> Bool.false
         Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.true
         RocStr | RocList _ | RocDict _ _ | RocSet _ | RocBox _ | TagUnion (NullableUnwrapped _) | TagUnion (NullableWrapped _) | TagUnion (Recursive _) | TagUnion (NonNullableUnwrapped _) | RecursivePointer _ -> Bool.false
         TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
             List.all fields \{ id } -> canDeriveCopy types (Types.shape types id)
 
-        TagUnion (SingleTagStruct { payload: HasClosure fields }) ->
-            List.all fields \{ id } -> canDeriveCopy types (Types.shape types id)
+        TagUnion (SingleTagStruct { payload: HasClosure _ }) ->
+            Bool.false
 
         TagUnion (NonRecursive { tags }) ->
             List.all tags \{ payload } ->
@@ -1859,8 +2284,8 @@ canDeriveCopy = \types, type ->
         Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
             List.all fields \{ id } -> canDeriveCopy types (Types.shape types id)
 
-        Struct { fields: HasClosure fields } | TagUnionPayload { fields: HasClosure fields } ->
-            List.all fields \{ id } -> canDeriveCopy types (Types.shape types id)
+        Struct { fields: HasClosure _ } | TagUnionPayload { fields: HasClosure _ } ->
+            Bool.false
 
 cannotSupportDefault = \types, type ->
     when type is
@@ -1956,7 +2381,7 @@ hasFloatHelp = \types, type, doNotRecurse ->
 typeName = \types, id ->
     when Types.shape types id is
         Unit -> "()"
-        Unsized -> "roc_std::RocList<u8>"
+        Unsized -> "RocUnsized"
         EmptyTagUnion -> "std::convert::Infallible"
         RocStr -> "roc_std::RocStr"
         Bool -> "bool"
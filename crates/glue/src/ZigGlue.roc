@@ -2,7 +2,10 @@ app "zig-glue"
     packages { pf: "../platform/main.roc" }
     imports [
         pf.Types.{ Types },
+        pf.Shape.{ Shape, RocFn },
         pf.File.{ File },
+        pf.TypeId.{ TypeId },
+        "../static/roc_std.zig" as rocStdZig : Str,
         "../../compiler/builtins/bitcode/src/list.zig" as rocStdList : Str,
         "../../compiler/builtins/bitcode/src/str.zig" as rocStdStr : Str,
         "../../compiler/builtins/bitcode/src/utils.zig" as rocStdUtils : Str,
@@ -11,25 +14,1115 @@ app "zig-glue"
 
 makeGlue : List Types -> Result (List File) Str
 makeGlue = \typesByArch ->
+    archImports =
+        List.walk typesByArch "" \content, types ->
+            arch = (Types.target types).architecture
+            archStr = archName arch
+
+            Str.concat content "$(indent).$(archStr) => @import(\"$(archStr).zig\"),\n"
+
     typesByArch
     |> List.map convertTypesToFile
+    |> List.append { name: "main.zig", content: mainFileContent archImports }
     |> List.concat staticFiles
     |> Ok
 
 ## These are always included, and don't depend on the specifics of the app.
 staticFiles : List File
 staticFiles = [
+    { name: "roc_std.zig", content: rocStdZig },
     { name: "list.zig", content: rocStdList },
     { name: "str.zig", content: rocStdStr },
     { name: "utils.zig", content: rocStdUtils },
 ]
 
+## The file the host imports. It re-exports the glue for whichever architecture the host is built for.
+mainFileContent : Str -> Str
+mainFileContent = \archImports ->
+    """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+    //
+    // Import this file from the host to use the app's types and entry points, e.g.
+    //
+    //     const roc_app = @import("roc_app/main.zig");
+
+    const builtin = @import("builtin");
+
+    pub usingnamespace switch (builtin.cpu.arch) {
+    $(archImports)    else => @compileError("The glue for this platform was not generated for this architecture."),
+    };
+
+    """
+
 convertTypesToFile : Types -> File
-convertTypesToFile = \_ -> { name: "main.zig", content }
+convertTypesToFile = \types ->
+    content =
+        Types.walkShapes types fileHeader \buf, type, id ->
+            when type is
+                Struct { name, fields } ->
+                    generateStruct buf types id name (structFieldList fields)
+
+                TagUnionPayload { name, fields } ->
+                    generateStruct buf types id name (structFieldList (nameTagUnionPayloadFields fields))
+
+                TagUnion (Enumeration { name, tags, size }) ->
+                    generateEnumeration buf types id name tags size
+
+                TagUnion (NonRecursive { name, tags, discriminantSize, discriminantOffset }) ->
+                    if !(List.isEmpty tags) then
+                        generateNonRecursiveTagUnion buf types id name tags discriminantSize discriminantOffset
+                    else
+                        buf
+
+                TagUnion (Recursive { name, tags, discriminantSize }) ->
+                    if !(List.isEmpty tags) then
+                        generateRecursiveTagUnion buf types id name tags discriminantSize None
+                    else
+                        buf
+
+                TagUnion (NullableWrapped { name, indexOfNullTag, tags, discriminantSize }) ->
+                    generateRecursiveTagUnion buf types id name tags discriminantSize (Some indexOfNullTag)
+
+                TagUnion (NullableUnwrapped { name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull }) ->
+                    generateNullableUnwrapped buf types id name nullTag nonNullTag nonNullPayload whichTagIsNull
+
+                TagUnion (SingleTagStruct { name, tagName, payload }) ->
+                    generateSingleTagStruct buf types id name tagName payload
+
+                TagUnion (NonNullableUnwrapped { name, tagName, payload }) ->
+                    generateNonNullableUnwrapped buf types id name tagName payload
+
+                Function rocFn ->
+                    if rocFn.isToplevel then
+                        buf
+                    else
+                        generateFunction buf types rocFn
+
+                RecursivePointer _ ->
+                    # This is recursively pointing to a type that should already have been added,
+                    # so no extra work needs to happen.
+                    buf
+
+                Unit
+                | Unsized
+                | EmptyTagUnion
+                | Num _
+                | Bool
+                | RocResult _ _
+                | RocStr
+                | RocDict _ _
+                | RocSet _
+                | RocList _
+                | RocBox _ ->
+                    # These types are either Zig primitives or defined in roc_std.zig
+                    buf
+
+    arch = (Types.target types).architecture
+    archStr = archName arch
+
+    {
+        name: "$(archStr).zig",
+        content: content |> generateEntryPoints types,
+    }
+
+generateEntryPoints : Str, Types -> Str
+generateEntryPoints = \buf, types ->
+    List.walk (Types.entryPoints types) buf \accum, T name id -> generateEntryPoint accum types name id
+
+generateEntryPoint : Str, Types, Str, TypeId -> Str
+generateEntryPoint = \buf, types, name, id ->
+    (args, retId) =
+        when Types.shape types id is
+            Function rocFn -> (rocFn.args, rocFn.ret)
+            _ -> ([], id)
+
+    publicArguments =
+        toArgStr args types \argId, _shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            "arg$(indexStr): $(type)"
+
+    # Values which point into the heap are passed by reference, everything else by value.
+    externArguments =
+        toArgStr args types \argId, shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            if isRefcounted types shape then
+                "arg$(indexStr): *const $(type)"
+            else
+                "arg$(indexStr): $(type)"
+        |> prependComma
+
+    externCallArguments =
+        toArgStr args types \_argId, shape, index ->
+            indexStr = Num.toStr index
+
+            if isRefcounted types shape then
+                "&arg$(indexStr)"
+            else
+                "arg$(indexStr)"
+        |> prependComma
+
+    ret = typeName types retId
+
+    when Types.shape types retId is
+        Function _ ->
+            """
+            $(buf)
+            pub fn $(name)($(publicArguments)) $(ret) {
+                const size: usize = @intCast(roc__$(name)_1_exposed_size());
+                const closure_data = std.heap.c_allocator.alloc(u8, size) catch @panic("Out of memory while allocating a Roc closure");
+                roc__$(name)_1_exposed_generic(closure_data.ptr$(externCallArguments));
+
+                return .{ .closure_data = closure_data };
+            }
+
+            extern fn roc__$(name)_1_exposed_generic(closure_data: [*]u8$(externArguments)) void;
+            extern fn roc__$(name)_1_exposed_size() i64;
+
+            """
+
+        _ ->
+            """
+            $(buf)
+            pub fn $(name)($(publicArguments)) $(ret) {
+                var ret: $(ret) = undefined;
+                roc__$(name)_1_exposed_generic(&ret$(externCallArguments));
+
+                return ret;
+            }
+
+            extern fn roc__$(name)_1_exposed_generic(ret: *$(ret)$(externArguments)) void;
+
+            """
+
+generateFunction : Str, Types, RocFn -> Str
+generateFunction = \buf, types, rocFn ->
+    name = escapeKW rocFn.functionName
+    externName = rocFn.externName
+
+    publicArguments =
+        toArgStr rocFn.args types \argId, _shape, index ->
+            type = typeName types argId
+            indexStr = Num.toStr index
+
+            "arg$(indexStr): $(type)"
+        |> prependComma
+
+    externDefArguments =
+        withoutUnit =
+            toArgStr rocFn.args types \argId, _shape, index ->
+                type = typeName types argId
+                indexStr = Num.toStr index
+
+                "arg$(indexStr): *const $(type)"
+
+        if Str.isEmpty withoutUnit then
+            # These always have a first argument that's a pointer, even if it's to nothing.
+            "arg0: *const roc_std.RocUnit"
+        else
+            withoutUnit
+
+    externCallArguments =
+        withoutUnit =
+            toArgStr rocFn.args types \_argId, _shape, index ->
+                indexStr = Num.toStr index
+
+                "&arg$(indexStr)"
+
+        if Str.isEmpty withoutUnit then
+            # These always have a first argument that's a pointer, even if it's to nothing.
+            "&roc_std.RocUnit{}"
+        else
+            withoutUnit
+
+    ret = typeName types rocFn.ret
 
-content =
     """
-    // ⚠️ GENERATED CODE ⚠️ 
-    //
-    // This package is generated by the `roc glue` CLI command
+    $(buf)
+    pub const $(name) = struct {
+        closure_data: []u8,
+
+        pub fn call(self: $(name)$(publicArguments)) $(ret) {
+            var output: $(ret) = undefined;
+            $(externName)($(externCallArguments), self.closure_data.ptr, &output);
+
+            return output;
+        }
+
+        /// Frees the memory holding the closure's captured values, once it won't be called again.
+        pub fn deinit(self: $(name)) void {
+            std.heap.c_allocator.free(self.closure_data);
+        }
+
+        extern fn $(externName)($(externDefArguments), closure_data: [*]u8, output: *$(ret)) void;
+    };
+
+    """
+
+generateStruct : Str, Types, TypeId, Str, List { name : Str, id : TypeId } -> Str
+generateStruct = \buf, types, id, name, fields ->
+    escapedName = escapeKW name
+
+    members =
+        [generateStructFields types fields]
+        |> List.concat (refcountMethods types (Types.shape types id) escapedName fields)
+
+    buf
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl escapedName "extern struct" members)
+    |> Str.concat (layoutChecks types id escapedName)
+
+generateStructFields : Types, List { name : Str, id : TypeId } -> Str
+generateStructFields = \types, fields ->
+    List.walk fields "" \accum, { name: fieldName, id } ->
+        typeStr = typeName types id
+        escapedFieldName = escapeKW fieldName
+
+        Str.concat accum "$(indent)$(escapedFieldName): $(typeStr),\n"
+
+structFieldList = \structFields ->
+    when structFields is
+        HasNoClosure fields -> fields
+        HasClosure fields -> List.map fields \{ name, id } -> { name, id }
+
+nameTagUnionPayloadFields = \payloadFields ->
+    # Tag union payloads have numbered fields, so we prefix them
+    # with an "f" because Zig identifiers can't start with a number.
+    when payloadFields is
+        HasNoClosure fields ->
+            renamedFields = List.map fields \{ name, id } -> { name: "f$(name)", id }
+            HasNoClosure renamedFields
+
+        HasClosure fields ->
+            renamedFields = List.map fields \{ name, id, accessors } -> { name: "f$(name)", id, accessors }
+            HasClosure renamedFields
+
+generateEnumeration = \buf, types, id, name, tags, tagBytes ->
+    escapedName = escapeKW name
+
+    buf
+    |> Str.concat "\n"
+    |> Str.concat (enumDecl escapedName tags tagBytes)
+    |> Str.concat (layoutChecks types id escapedName)
+
+## The discriminant of a tag union, with the same values as the tag ids Roc uses.
+enumDecl : Str, List Str, U32 -> Str
+enumDecl = \name, tags, tagBytes ->
+    reprBits = tagBytes * 8 |> Num.toStr
+
+    values =
+        List.walkWithIndex tags "" \accum, tagName, index ->
+            indexStr = Num.toStr index
+
+            Str.concat accum "$(indent)$(tagName) = $(indexStr),\n"
+
+    "pub const $(name) = enum(u$(reprBits)) {\n$(values)};\n"
+
+generateNonRecursiveTagUnion : Str, Types, TypeId, Str, List { name : Str, payload : [Some TypeId, None] }, U32, U32 -> Str
+generateNonRecursiveTagUnion = \buf, types, id, name, tags, discriminantSize, discriminantOffset ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_$(escapedName)"
+    unionName = "union_$(escapedName)"
+    tagNames = List.map tags \{ name: n } -> n
+    discriminantOffsetStr = Num.toStr discriminantOffset
+
+    unionFields =
+        List.walk tags "" \accum, { name: tagName, payload } ->
+            when payload is
+                Some payloadId ->
+                    typeStr = typeName types payloadId
+
+                    Str.concat accum "$(indent)$(tagName): $(typeStr),\n"
+
+                None ->
+                    accum
+
+    # Roc stores the discriminant right after the largest payload, which can be inside
+    # the union's trailing padding. So rather than putting the discriminant after the
+    # union in a struct, overlay it on the union at the exact offset Roc uses.
+    fields =
+        """
+            payload: $(unionName),
+            discriminant_storage: extern struct {
+                padding: [$(discriminantOffsetStr)]u8,
+                tag: $(discriminantName),
+            },
+
+        """
+
+    helpers =
+        """
+            $(discriminantDocComment)
+            pub fn discriminant(self: $(escapedName)) $(discriminantName) {
+                return self.discriminant_storage.tag;
+            }
+
+            fn init(payload: $(unionName), tag: $(discriminantName)) $(escapedName) {
+                var result = $(escapedName){ .payload = payload };
+                result.discriminant_storage.tag = tag;
+
+                return result;
+            }
+
+        """
+
+    tagMethods =
+        List.walk tags [] \methods, { name: tagName, payload } ->
+            when payload is
+                Some payloadId ->
+                    payloadType = typeName types payloadId
+                    { params, value } = payloadConstructor types payloadId
+
+                    List.concat methods [
+                        """
+                            pub fn $(tagName)($(params)) $(escapedName) {
+                                return init(.{ .$(tagName) = $(value) }, .$(tagName));
+                            }
+
+                        """,
+                        isTagMethod escapedName tagName,
+                        """
+                            /// Returns the payload of `$(tagName)`. Asserts that this holds a `$(tagName)`.
+                            pub fn get$(tagName)(self: $(escapedName)) $(payloadType) {
+                                std.debug.assert(self.is$(tagName)());
+                                return self.payload.$(tagName);
+                            }
+
+                        """,
+                    ]
+
+                None ->
+                    List.concat methods [
+                        """
+                            pub fn $(tagName)() $(escapedName) {
+                                return init(undefined, .$(tagName));
+                            }
+
+                        """,
+                        isTagMethod escapedName tagName,
+                    ]
+
+    refcounting =
+        if isRefcounted types (Types.shape types id) then
+            increfs =
+                List.walk tags "" \accum, { name: tagName, payload } ->
+                    when payload is
+                        Some payloadId ->
+                            typeStr = typeName types payloadId
+
+                            Str.concat accum "$(indent)$(indent)$(indent).$(tagName) => roc_std.increfValue($(typeStr), self.payload.$(tagName), amount),\n"
+
+                        None ->
+                            Str.concat accum "$(indent)$(indent)$(indent).$(tagName) => {},\n"
+
+            decrefs =
+                List.walk tags "" \accum, { name: tagName, payload } ->
+                    when payload is
+                        Some payloadId ->
+                            typeStr = typeName types payloadId
+
+                            Str.concat accum "$(indent)$(indent)$(indent).$(tagName) => roc_std.decrefValue($(typeStr), self.payload.$(tagName)),\n"
+
+                        None ->
+                            Str.concat accum "$(indent)$(indent)$(indent).$(tagName) => {},\n"
+
+            [
+                """
+                    pub fn incref(self: $(escapedName), amount: usize) void {
+                        switch (self.discriminant()) {
+                $(increfs)        }
+                    }
+
+                """,
+                """
+                    pub fn decref(self: $(escapedName)) void {
+                        switch (self.discriminant()) {
+                $(decrefs)        }
+                    }
+
+                """,
+            ]
+        else
+            []
+
+    members =
+        [fields, helpers]
+        |> List.concat tagMethods
+        |> List.concat refcounting
+
+    buf
+    |> Str.concat "\n"
+    |> Str.concat (enumDecl discriminantName tagNames discriminantSize)
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl unionName "extern union" [unionFields])
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl escapedName "extern union" members)
+    |> Str.concat (layoutChecks types id escapedName)
+
+generateRecursiveTagUnion = \buf, types, id, tagUnionName, tags, discriminantSize, nullTagIndex ->
+    escapedName = escapeKW tagUnionName
+    discriminantName = "discriminant_$(escapedName)"
+    unionName = "union_$(escapedName)"
+    tagNames = List.map tags \{ name: n } -> n
+
+    # The discriminant lives in the low bits of the pointer rather than in memory,
+    # but it still needs at least one byte to be a valid Zig enum.
+    discriminantBytes = if discriminantSize == 0 then 1 else discriminantSize
+
+    isNullTag = \index -> Some (Num.intCast index) == nullTagIndex
+
+    unionFields =
+        List.walkWithIndex tags "" \accum, { name: tagName, payload }, index ->
+            if isNullTag index then
+                accum
+            else
+                typeStr =
+                    when payload is
+                        Some payloadId -> typeName types payloadId
+                        None -> "roc_std.RocUnit"
+
+                Str.concat accum "$(indent)$(tagName): $(typeStr),\n"
+
+    nullDiscriminant =
+        when nullTagIndex is
+            Some index ->
+                nullTagName = List.get tagNames (Num.intCast index) |> Result.withDefault ""
+
+                "return .$(nullTagName);"
+
+            None ->
+                "unreachable;"
+
+    helpers =
+        """
+            pointer: ?*anyopaque,
+
+            const tag_mask: usize = if (@sizeOf(usize) == 8) 0b111 else 0b011;
+
+            $(discriminantDocComment)
+            pub fn discriminant(self: $(escapedName)) $(discriminantName) {
+                const address = @intFromPtr(self.pointer);
+                if (address == 0) {
+                    $(nullDiscriminant)
+                }
+
+                return @enumFromInt(address & tag_mask);
+            }
+
+            fn unionPointer(self: $(escapedName)) *$(unionName) {
+                return @ptrFromInt(@intFromPtr(self.pointer) & ~tag_mask);
+            }
+
+        """
+
+    tagMethods =
+        List.walkWithIndex tags [] \methods, { name: tagName, payload }, index ->
+            if isNullTag index then
+                List.concat methods [
+                    """
+                        pub fn $(tagName)() $(escapedName) {
+                            return .{ .pointer = null };
+                        }
+
+                    """,
+                    isTagMethod escapedName tagName,
+                ]
+            else
+                { params, value } =
+                    when payload is
+                        Some payloadId -> payloadConstructor types payloadId
+                        None -> { params: "", value: ".{}" }
+
+                constructor =
+                    """
+                        pub fn $(tagName)($(params)) $(escapedName) {
+                            const box = roc_std.RocBox($(unionName)).init(.{ .$(tagName) = $(value) });
+                            const tag_id = @intFromEnum($(discriminantName).$(tagName));
+
+                            return .{ .pointer = @ptrFromInt(@intFromPtr(box.contents) | tag_id) };
+                        }
+
+                    """
+
+                when payload is
+                    Some payloadId ->
+                        payloadType = typeName types payloadId
+
+                        List.concat methods [
+                            constructor,
+                            isTagMethod escapedName tagName,
+                            """
+                                /// Returns the payload of `$(tagName)`. Asserts that this holds a `$(tagName)`.
+                                pub fn get$(tagName)(self: $(escapedName)) $(payloadType) {
+                                    std.debug.assert(self.is$(tagName)());
+                                    return self.unionPointer().$(tagName);
+                                }
+
+                            """,
+                        ]
+
+                    None ->
+                        List.concat methods [constructor, isTagMethod escapedName tagName]
+
+    payloadDecrefs =
+        List.walkWithIndex tags "" \accum, { name: tagName, payload }, index ->
+            when payload is
+                Some payloadId if !(isNullTag index) ->
+                    typeStr = typeName types payloadId
+
+                    Str.concat accum "$(indent)$(indent)$(indent)$(indent).$(tagName) => roc_std.decrefValue($(typeStr), self.unionPointer().$(tagName)),\n"
+
+                _ ->
+                    Str.concat accum "$(indent)$(indent)$(indent)$(indent).$(tagName) => {},\n"
+
+    refcounting = [
+        """
+            pub fn incref(self: $(escapedName), amount: usize) void {
+                roc_std.increfPointer(self.pointer, amount);
+            }
+
+        """,
+        """
+            pub fn decref(self: $(escapedName)) void {
+                if (self.pointer == null) {
+                    return;
+                }
+
+                if (roc_std.isUniquePointer(self.pointer)) {
+                    switch (self.discriminant()) {
+        $(payloadDecrefs)            }
+                }
+
+                roc_std.decrefPointer(self.pointer, @alignOf($(unionName)));
+            }
+
+        """,
+    ]
+
+    members =
+        [helpers]
+        |> List.concat tagMethods
+        |> List.concat refcounting
+
+    buf
+    |> Str.concat "\n"
+    |> Str.concat (enumDecl discriminantName tagNames discriminantBytes)
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl unionName "extern union" [unionFields])
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl escapedName "extern struct" members)
+    |> Str.concat (layoutChecks types id escapedName)
+
+generateNullableUnwrapped : Str, Types, TypeId, Str, Str, Str, TypeId, [FirstTagIsNull, SecondTagIsNull] -> Str
+generateNullableUnwrapped = \buf, types, id, name, nullTag, nonNullTag, nonNullPayload, whichTagIsNull ->
+    escapedName = escapeKW name
+    discriminantName = "discriminant_$(escapedName)"
+    payloadType = typeName types nonNullPayload
+    { params, value } = payloadConstructor types nonNullPayload
+
+    tagNames =
+        when whichTagIsNull is
+            FirstTagIsNull -> [nullTag, nonNullTag]
+            SecondTagIsNull -> [nonNullTag, nullTag]
+
+    members = [
+        """
+            pointer: ?*anyopaque,
+
+            $(discriminantDocComment)
+            pub fn discriminant(self: $(escapedName)) $(discriminantName) {
+                return if (self.pointer == null) .$(nullTag) else .$(nonNullTag);
+            }
+
+        """,
+        """
+            pub fn $(nullTag)() $(escapedName) {
+                return .{ .pointer = null };
+            }
+
+        """,
+        isTagMethod escapedName nullTag,
+        """
+            pub fn $(nonNullTag)($(params)) $(escapedName) {
+                const box = roc_std.RocBox($(payloadType)).init($(value));
+
+                return .{ .pointer = box.contents };
+            }
+
+        """,
+        isTagMethod escapedName nonNullTag,
+        """
+            /// Returns the payload of `$(nonNullTag)`. Asserts that this holds a `$(nonNullTag)`.
+            pub fn get$(nonNullTag)(self: $(escapedName)) $(payloadType) {
+                std.debug.assert(self.is$(nonNullTag)());
+                const payload: *$(payloadType) = @ptrCast(@alignCast(self.pointer.?));
+
+                return payload.*;
+            }
+
+        """,
+        """
+            pub fn incref(self: $(escapedName), amount: usize) void {
+                roc_std.increfPointer(self.pointer, amount);
+            }
+
+        """,
+        """
+            pub fn decref(self: $(escapedName)) void {
+                if (self.is$(nonNullTag)() and roc_std.isUniquePointer(self.pointer)) {
+                    roc_std.decrefValue($(payloadType), self.get$(nonNullTag)());
+                }
+
+                roc_std.decrefPointer(self.pointer, @alignOf($(payloadType)));
+            }
+
+        """,
+    ]
+
+    buf
+    |> Str.concat "\n"
+    |> Str.concat (enumDecl discriminantName tagNames 1)
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl escapedName "extern struct" members)
+    |> Str.concat (layoutChecks types id escapedName)
+
+generateNonNullableUnwrapped = \buf, types, id, name, tagName, payload ->
+    escapedName = escapeKW name
+    payloadType = typeName types payload
+    { params, value } = payloadConstructor types payload
+
+    members = [
+        """
+            pointer: *anyopaque,
+
+        """,
+        """
+            pub fn $(tagName)($(params)) $(escapedName) {
+                const box = roc_std.RocBox($(payloadType)).init($(value));
+
+                return .{ .pointer = box.contents };
+            }
+
+        """,
+        """
+            /// Returns the payload of `$(tagName)`.
+            pub fn get$(tagName)(self: $(escapedName)) $(payloadType) {
+                const payload: *$(payloadType) = @ptrCast(@alignCast(self.pointer));
+
+                return payload.*;
+            }
+
+        """,
+        """
+            pub fn incref(self: $(escapedName), amount: usize) void {
+                roc_std.increfPointer(self.pointer, amount);
+            }
+
+        """,
+        """
+            pub fn decref(self: $(escapedName)) void {
+                if (roc_std.isUniquePointer(self.pointer)) {
+                    roc_std.decrefValue($(payloadType), self.get$(tagName)());
+                }
+
+                roc_std.decrefPointer(self.pointer, @alignOf($(payloadType)));
+            }
+
+        """,
+    ]
+
+    buf
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl escapedName "extern struct" members)
+    |> Str.concat (layoutChecks types id escapedName)
+
+generateSingleTagStruct = \buf, types, id, name, tagName, payload ->
+    # Store single-tag unions as structs rather than enums,
+    # because they have only one alternative. However, still
+    # offer the usual tag union APIs.
+    escapedName = escapeKW name
+
+    # A closure is stored like any other field, as a value of its function's type.
+    fields =
+        List.mapWithIndex (structFieldList payload) \{ id: fieldId }, index ->
+            indexStr = Num.toStr index
+
+            { name: "f$(indexStr)", id: fieldId }
+
+    params =
+        fields
+        |> List.map \{ name: fieldName, id: fieldId } ->
+            fieldType = typeName types fieldId
+
+            "$(fieldName): $(fieldType)"
+        |> Str.joinWith ", "
+
+    inits =
+        fields
+        |> List.map \{ name: fieldName } ->
+            ".$(fieldName) = $(fieldName)"
+        |> Str.joinWith ", "
+
+    value = if List.isEmpty fields then ".{}" else ".{ $(inits) }"
+
+    constructor =
+        """
+            pub fn $(tagName)($(params)) $(escapedName) {
+                return $(value);
+            }
+
+        """
+
+    members =
+        [generateStructFields types fields, constructor]
+        |> List.concat (refcountMethods types (Types.shape types id) escapedName fields)
+
+    checks =
+        # Zero-sized types don't have a meaningful alignment to check.
+        if List.isEmpty fields then
+            ""
+        else
+            layoutChecks types id escapedName
+
+    buf
+    |> Str.concat "\n"
+    |> Str.concat (containerDecl escapedName "extern struct" members)
+    |> Str.concat checks
+
+## The parameters and initializer for a constructor of a tag with this payload.
+## Payloads with several values take them as separate parameters named f0, f1, etc.
+payloadConstructor : Types, TypeId -> { params : Str, value : Str }
+payloadConstructor = \types, payloadId ->
+    when Types.shape types payloadId is
+        TagUnionPayload { fields } ->
+            namedFields = structFieldList (nameTagUnionPayloadFields fields)
+
+            params =
+                namedFields
+                |> List.map \{ name, id } ->
+                    type = typeName types id
+
+                    "$(name): $(type)"
+                |> Str.joinWith ", "
+
+            inits =
+                namedFields
+                |> List.map \{ name } ->
+                    ".$(name) = $(name)"
+                |> Str.joinWith ", "
+
+            value = if List.isEmpty namedFields then ".{}" else ".{ $(inits) }"
+
+            { params, value }
+
+        _ ->
+            type = typeName types payloadId
+
+            { params: "payload: $(type)", value: "payload" }
+
+isTagMethod : Str, Str -> Str
+isTagMethod = \typeStr, tagName ->
+    """
+        pub fn is$(tagName)(self: $(typeStr)) bool {
+            return self.discriminant() == .$(tagName);
+        }
+
+    """
+
+## `incref` and `decref` methods which forward to the fields that point into the heap,
+## or nothing at all if none of them do.
+refcountMethods : Types, Shape, Str, List { name : Str, id : TypeId } -> List Str
+refcountMethods = \types, shape, selfType, fields ->
+    if isRefcounted types shape then
+        refcountedFields = List.keepIf fields \{ id } -> isRefcounted types (Types.shape types id)
+
+        increfs =
+            List.walk refcountedFields "" \accum, { name, id } ->
+                typeStr = typeName types id
+                fieldName = escapeKW name
+
+                Str.concat accum "$(indent)$(indent)roc_std.increfValue($(typeStr), self.$(fieldName), amount);\n"
+
+        decrefs =
+            List.walk refcountedFields "" \accum, { name, id } ->
+                typeStr = typeName types id
+                fieldName = escapeKW name
+
+                Str.concat accum "$(indent)$(indent)roc_std.decrefValue($(typeStr), self.$(fieldName));\n"
+
+        [
+            "$(indent)pub fn incref(self: $(selfType), amount: usize) void {\n$(increfs)$(indent)}\n",
+            "$(indent)pub fn decref(self: $(selfType)) void {\n$(decrefs)$(indent)}\n",
+        ]
+    else
+        []
+
+## A `pub const` declaration of a struct, union or enum, with a blank line between each member.
+containerDecl : Str, Str, List Str -> Str
+containerDecl = \name, kind, members ->
+    body = Str.joinWith members "\n"
+
+    "pub const $(name) = $(kind) {\n$(body)};\n"
+
+## Fail the build if Zig's layout for a type ever disagrees with the one Roc uses.
+layoutChecks : Types, TypeId, Str -> Str
+layoutChecks = \types, id, name ->
+    size = Num.toStr (Types.size types id)
+    alignment = Num.toStr (Types.alignment types id)
+
+    checks =
+        """
+        comptime {
+            if (@sizeOf($(name)) != $(size)) @compileError("Roc expects $(name) to be $(size) bytes");
+            if (@alignOf($(name)) != $(alignment)) @compileError("Roc expects $(name) to have an alignment of $(alignment)");
+        }
+
+        """
+
+    Str.concat "\n" checks
+
+## Whether values of this type point into the heap, so the type needs `incref` and `decref`.
+isRefcounted : Types, Shape -> Bool
+isRefcounted = \types, type ->
+    when type is
+        Function rocFn ->
+            runtimeRepresentation = Types.shape types rocFn.lambdaSet
+            isRefcounted types runtimeRepresentation
+
+        # unsized values are heap-allocated
+        Unsized -> Bool.true
+        Unit | EmptyTagUnion | Bool | Num _ | TagUnion (Enumeration _) -> Bool.false
+        RocStr | RocList _ | RocDict _ _ | RocSet _ | RocBox _ | TagUnion (NullableUnwrapped _) | TagUnion (NullableWrapped _) | TagUnion (Recursive _) | TagUnion (NonNullableUnwrapped _) | RecursivePointer _ -> Bool.true
+        TagUnion (SingleTagStruct { payload: HasNoClosure fields }) ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+        TagUnion (SingleTagStruct { payload: HasClosure fields }) ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+        TagUnion (NonRecursive { tags }) ->
+            List.any tags \{ payload } ->
+                when payload is
+                    Some id -> isRefcounted types (Types.shape types id)
+                    None -> Bool.false
+
+        RocResult okId errId ->
+            isRefcounted types (Types.shape types okId)
+            || isRefcounted types (Types.shape types errId)
+
+        Struct { fields: HasNoClosure fields } | TagUnionPayload { fields: HasNoClosure fields } ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+        Struct { fields: HasClosure fields } | TagUnionPayload { fields: HasClosure fields } ->
+            List.any fields \{ id } -> isRefcounted types (Types.shape types id)
+
+typeName = \types, id ->
+    when Types.shape types id is
+        Unit -> "roc_std.RocUnit"
+        Unsized -> "roc_std.RocList(u8)"
+        EmptyTagUnion -> "roc_std.RocUnit"
+        RocStr -> "roc_std.RocStr"
+        Bool -> "bool"
+        Num U8 -> "u8"
+        Num U16 -> "u16"
+        Num U32 -> "u32"
+        Num U64 -> "u64"
+        Num U128 -> "u128"
+        Num I8 -> "i8"
+        Num I16 -> "i16"
+        Num I32 -> "i32"
+        Num I64 -> "i64"
+        Num I128 -> "i128"
+        Num F32 -> "f32"
+        Num F64 -> "f64"
+        Num Dec -> "roc_std.RocDec"
+        RocDict key value ->
+            keyName = typeName types key
+            valueName = typeName types value
+
+            "roc_std.RocDict($(keyName), $(valueName))"
+
+        RocSet elem ->
+            elemName = typeName types elem
+
+            "roc_std.RocSet($(elemName))"
+
+        RocList elem ->
+            elemName = typeName types elem
+
+            "roc_std.RocList($(elemName))"
+
+        RocBox elem ->
+            elemName = typeName types elem
+
+            "roc_std.RocBox($(elemName))"
+
+        RocResult ok err ->
+            okName = typeName types ok
+            errName = typeName types err
+
+            "roc_std.RocResult($(okName), $(errName))"
+
+        RecursivePointer content ->
+            typeName types content
+
+        Struct { name } -> escapeKW name
+        TagUnionPayload { name } -> escapeKW name
+        TagUnion (NonRecursive { name }) -> escapeKW name
+        TagUnion (Recursive { name }) -> escapeKW name
+        TagUnion (Enumeration { name }) -> escapeKW name
+        TagUnion (NullableWrapped { name }) -> escapeKW name
+        TagUnion (NullableUnwrapped { name }) -> escapeKW name
+        TagUnion (NonNullableUnwrapped { name }) -> escapeKW name
+        TagUnion (SingleTagStruct { name }) -> escapeKW name
+        Function { functionName } -> escapeKW functionName
+
+archName = \arch ->
+    # These match the names of Zig's `std.Target.Cpu.Arch` tags
+    when arch is
+        Aarch32 ->
+            "arm"
+
+        Aarch64 ->
+            "aarch64"
+
+        Wasm32 ->
+            "wasm32"
+
+        X86x32 ->
+            "x86"
+
+        X86x64 ->
+            "x86_64"
+
+fileHeader =
     """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+    const std = @import("std");
+    pub const roc_std = @import("roc_std.zig");
+
+    """
+
+indent = "    "
+discriminantDocComment = "/// Returns which variant this tag union holds. Note that this never includes a payload!"
+
+reservedKeywords = Set.fromList [
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anytype",
+    "asm",
+    "async",
+    "await",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "linksection",
+    "noalias",
+    "noinline",
+    "nosuspend",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "try",
+    "union",
+    "unreachable",
+    "usingnamespace",
+    "var",
+    "volatile",
+    "while",
+    # Primitive types and values can't be used as identifiers either
+    "anyerror",
+    "anyopaque",
+    "bool",
+    "false",
+    "noreturn",
+    "null",
+    "true",
+    "type",
+    "undefined",
+    "void",
+    "isize",
+    "usize",
+    "f16",
+    "f32",
+    "f64",
+    "f80",
+    "f128",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+]
+
+escapeKW = \input ->
+    # Zig's @"..." syntax allows any string to be used as an identifier.
+    if Set.contains reservedKeywords input then
+        "@\"$(input)\""
+    else
+        input
+
+isUnit : Shape -> Bool
+isUnit = \shape ->
+    when shape is
+        Unit -> Bool.true
+        _ -> Bool.false
+
+toArgStr : List TypeId, Types, (TypeId, Shape, U64 -> Str) -> Str
+toArgStr = \args, types, fmt ->
+    List.walkWithIndex args "" \state, argId, index ->
+        shape = Types.shape types argId
+
+        # Drop `{}` args; nothing will get passed anyway.
+        if isUnit shape then
+            state
+        else
+            argStr = fmt argId shape index
+
+            if Str.isEmpty state then
+                argStr # Don't prepend a comma if this is the first one
+            else
+                state
+                |> Str.concat ", "
+                |> Str.concat argStr
+
+prependComma : Str -> Str
+prependComma = \args ->
+    if Str.isEmpty args then
+        ""
+    else
+        ", $(args)"
@@ -16,14 +16,13 @@ use roc_load::{ExecutionMode, FunctionKind, LoadConfig, LoadedModule, LoadingPro
 use roc_mono::ir::{generate_glue_procs, CrashTag, GlueProc, OptLevel};
 use roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner};
 use roc_packaging::cache::{self, RocCacheDir};
-use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_reporting::report::{to_file_problem_report_string, RenderTarget, DEFAULT_PALETTE};
 use roc_target::{Architecture, Target, TargetFromTripleError::TripleUnsupported};
 use roc_types::subs::{Subs, Variable};
 use std::fs::File;
-use std::io::{self, ErrorKind, Write};
+use std::io::{self, Write};
 use std::mem::ManuallyDrop;
 use std::path::{Component, Path, PathBuf};
-use std::process;
 use strum::IntoEnumIterator;
 use target_lexicon::Triple;
 
@@ -94,7 +93,8 @@ pub fn generate(
                 ),
                 Err(_) => {
                     eprintln!("`roc glue` was unable to create a tempdir.");
-                    std::process::exit(1);
+
+                    return Ok(1);
                 }
             };
 
@@ -135,7 +135,14 @@ pub fn generate(
 
                     // NOTE: DO NOT DROP LIB! the return value will include static roc strings that
                     // are only kept alive when the dynamic library is not unloaded!
-                    let files = call_roc_make_glue(&lib, backend, roc_types);
+                    let files = match call_roc_make_glue(&lib, backend, roc_types) {
+                        Ok(files) => files,
+                        Err(err) => {
+                            eprintln!("Glue generation failed: {err}");
+
+                            return Ok(1);
+                        }
+                    };
 
                     for roc_type::File { name, content } in &files {
                         let valid_name = PathBuf::from(name.as_str())
@@ -144,39 +151,42 @@ pub fn generate(
                         if !valid_name || name.is_empty() {
                             eprintln!("File name was invalid: {:?}", &name);
 
-                            process::exit(1);
+                            return Ok(1);
                         }
                         let full_path = output_path.join(name.as_str());
                         if let Some(dir_path) = full_path.parent() {
-                            std::fs::create_dir_all(dir_path).unwrap_or_else(|err| {
+                            if let Err(err) = std::fs::create_dir_all(dir_path) {
                                 eprintln!(
                                     "Unable to create output directory {} - {:?}",
                                     dir_path.display(),
                                     err
                                 );
 
-                                process::exit(1);
-                            });
+                                return Ok(1);
+                            }
                         }
-                        let mut file = File::create(&full_path).unwrap_or_else(|err| {
-                            eprintln!(
-                                "Unable to create output file {} - {:?}",
-                                full_path.display(),
-                                err
-                            );
+                        let mut file = match File::create(&full_path) {
+                            Ok(file) => file,
+                            Err(err) => {
+                                eprintln!(
+                                    "Unable to create output file {} - {:?}",
+                                    full_path.display(),
+                                    err
+                                );
 
-                            process::exit(1);
-                        });
+                                return Ok(1);
+                            }
+                        };
 
-                        file.write_all(content.as_bytes()).unwrap_or_else(|err| {
+                        if let Err(err) = file.write_all(content.as_bytes()) {
                             eprintln!(
                                 "Unable to write bindings to output file {} - {:?}",
                                 full_path.display(),
                                 err
                             );
 
-                            process::exit(1);
-                        });
+                            return Ok(1);
+                        }
                     }
 
                     println!(
@@ -198,28 +208,20 @@ pub fn generate(
 
             answer
         }
-        Err(err) => match err.kind() {
-            ErrorKind::NotFound => {
-                eprintln!("Platform module file not found: {}", input_path.display());
-                process::exit(1);
-            }
-            error => {
-                eprintln!(
-                    "Error loading platform module file {} - {:?}",
-                    input_path.display(),
-                    error
-                );
-                process::exit(1);
-            }
-        },
+        Err(report) => {
+            eprintln!("{report}");
+
+            Ok(1)
+        }
     }
 }
 
+/// Runs the glue spec, failing with the message it gave if it returned an `Err`.
 fn call_roc_make_glue(
     lib: &Library,
     backend: CodeGenBackend,
     roc_types: roc_std::RocList<roc_type::Types>,
-) -> roc_std::RocList<roc_type::File> {
+) -> Result<roc_std::RocList<roc_type::File>, roc_std::RocStr> {
     let roc_call_result = match backend {
         CodeGenBackend::Assembly(_) => {
             type MakeGlueReturnType = RocCallResult<
@@ -268,13 +270,7 @@ fn call_roc_make_glue(
             CrashTag::Roc => panic!(r#"Roc failed with message: "{msg}""#),
             CrashTag::User => panic!(r#"User crash with message: "{msg}""#),
         },
-        Ok(files_or_error) => match Result::from(files_or_error) {
-            Err(err) => {
-                eprintln!("Glue generation failed: {err}");
-                process::exit(1);
-            }
-            Ok(files) => files,
-        },
+        Ok(files_or_error) => Result::from(files_or_error),
     }
 }
 
@@ -393,17 +389,20 @@ fn number_lambda_sets(subs: &Subs, initial: Variable) -> Vec<Variable> {
     lambda_sets
 }
 
+/// Loads the types exposed to the host by the platform, for each architecture. Fails with the
+/// report to show if the platform could not be loaded.
 pub fn load_types(
     full_file_path: PathBuf,
     threading: Threading,
     ignore_errors: IgnoreErrors,
     target: Target,
-) -> Result<Vec<Types>, io::Error> {
+) -> Result<Vec<Types>, String> {
     // TODO the function kind may need to be parameterizable.
     let function_kind = FunctionKind::LambdaSet;
     let arena = &Bump::new();
     let LoadedModule {
         module_id: home,
+        mut syntax_problems,
         mut can_problems,
         mut type_problems,
         mut declarations_by_id,
@@ -425,16 +424,19 @@ pub fn load_types(
             exec_mode: ExecutionMode::Check,
         },
     )
-    .unwrap_or_else(|problem| match problem {
-        LoadingProblem::FormattedReport(report) => {
-            eprintln!("{report}");
-
-            process::exit(1);
+    .map_err(|problem| match problem {
+        LoadingProblem::FormattedReport(report) => report,
+        LoadingProblem::FileProblem { filename, error } => {
+            to_file_problem_report_string(filename, error)
         }
         problem => {
             todo!("{:?}", problem);
         }
-    });
+    })?;
+
+    if let Some(reports) = syntax_problems.remove(&home) {
+        return Err(reports.join("\n"));
+    }
 
     let decls = declarations_by_id.remove(&home).unwrap();
     let subs = solved.inner_mut();
@@ -43,6 +43,7 @@ pub struct Types {
     pub aligns: roc_std::RocList<u32>,
     pub deps: roc_std::RocList<Tuple2>,
     pub entrypoints: roc_std::RocList<Tuple1>,
+    pub fieldOffsets: roc_std::RocList<roc_std::RocList<u32>>,
     pub sizes: roc_std::RocList<u32>,
     pub types: roc_std::RocList<RocType>,
     pub typesByName: roc_std::RocList<Tuple1>,
@@ -567,6 +568,8 @@ impl core::fmt::Debug for RocNum {
 pub struct RocFn {
     pub args: roc_std::RocList<u32>,
     pub externName: roc_std::RocStr,
+    pub externResultSizeName: roc_std::RocStr,
+    pub externSizeName: roc_std::RocStr,
     pub functionName: roc_std::RocStr,
     pub lambdaSet: u32,
     pub ret: u32,
@@ -714,6 +717,8 @@ struct RocType_RocDict {
 pub struct RocFn {
     pub args: roc_std::RocList<u64>,
     pub externName: roc_std::RocStr,
+    pub externResultSizeName: roc_std::RocStr,
+    pub externSizeName: roc_std::RocStr,
     pub functionName: roc_std::RocStr,
     pub lambdaSet: u64,
     pub ret: u64,
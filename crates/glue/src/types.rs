@@ -55,6 +55,9 @@ pub struct Types {
     types: Vec<RocType>,
     sizes: Vec<u32>,
     aligns: Vec<u32>,
+    /// The offset of each field of a struct or tag union payload, in the order of its fields.
+    /// This is empty for other types.
+    field_offsets: Vec<Vec<u32>>,
 
     entry_points: Vec<(String, TypeId)>,
 
@@ -75,16 +78,19 @@ impl Types {
         let mut types = Vec::with_capacity(cap);
         let mut sizes = Vec::with_capacity(cap);
         let mut aligns = Vec::with_capacity(cap);
+        let mut field_offsets = Vec::with_capacity(cap);
 
         types.push(RocType::Unit);
         sizes.push(1);
         aligns.push(1);
+        field_offsets.push(Vec::new());
 
         Self {
             target,
             types,
             sizes,
             aligns,
+            field_offsets,
             types_by_name: FnvHashMap::with_capacity_and_hasher(10, Default::default()),
             entry_points: Vec::new(),
             deps: VecMap::with_capacity(cap),
@@ -436,6 +442,9 @@ impl Types {
                     lambda_set: lambda_a,
                     ret: ret_a,
                     is_toplevel: is_toplevel_a,
+                    // these are named after the extern, so they're equal when it is
+                    extern_size_name: _,
+                    extern_result_size_name: _,
                 }),
                 Function(RocFn {
                     function_name: name_b,
@@ -444,6 +453,8 @@ impl Types {
                     lambda_set: lambda_b,
                     ret: ret_b,
                     is_toplevel: is_toplevel_b,
+                    extern_size_name: _,
+                    extern_result_size_name: _,
                 }),
             ) => {
                 // for functions, the name is actually important because two functions
@@ -558,10 +569,34 @@ impl Types {
         self.types.push(typ);
         self.sizes.push(size);
         self.aligns.push(align);
+        self.field_offsets.push(Vec::new());
 
         id
     }
 
+    /// Record where the compiler puts each of a struct's fields, given their layouts in order.
+    /// Like the compiler, this puts each field right after the previous one, which leaves no
+    /// padding between them because the fields are sorted by alignment.
+    pub fn set_field_offsets<'a>(
+        &mut self,
+        interner: &TLLayoutInterner<'a>,
+        id: TypeId,
+        field_layouts: impl IntoIterator<Item = InLayout<'a>>,
+    ) {
+        let mut offset = 0;
+
+        self.field_offsets[id.0] = field_layouts
+            .into_iter()
+            .map(|field_layout| {
+                let field_offset = offset;
+
+                offset += interner.stack_size(field_layout);
+
+                field_offset
+            })
+            .collect();
+    }
+
     pub fn depends(&mut self, id: TypeId, depends_on: TypeId) {
         self.deps.get_or_insert(id, Vec::new).push(depends_on);
     }
@@ -604,6 +639,13 @@ impl Types {
         }
     }
 
+    pub fn field_offsets(&self, id: TypeId) -> &[u32] {
+        match self.field_offsets.get(id.0) {
+            Some(offsets) => offsets,
+            None => unreachable!(),
+        }
+    }
+
     pub fn replace(&mut self, id: TypeId, typ: RocType) {
         debug_assert!(self.types.get(id.0).is_some());
 
@@ -667,6 +709,11 @@ impl From<&Types> for roc_type::Types {
             aligns: types.aligns.as_slice().into(),
             deps,
             entrypoints,
+            fieldOffsets: types
+                .field_offsets
+                .iter()
+                .map(|offsets| offsets.as_slice().into())
+                .collect(),
             sizes: types.sizes.as_slice().into(),
             types: types.types.iter().map(roc_type::RocType::from).collect(),
             typesByName: types_by_name,
@@ -702,6 +749,8 @@ impl From<&RocType> for roc_type::RocType {
             RocType::Function(RocFn {
                 function_name,
                 extern_name,
+                extern_size_name,
+                extern_result_size_name,
                 args,
                 lambda_set,
                 ret,
@@ -710,6 +759,8 @@ impl From<&RocType> for roc_type::RocType {
                 args: args.iter().map(|arg| arg.0 as _).collect(),
                 functionName: function_name.as_str().into(),
                 externName: extern_name.as_str().into(),
+                externSizeName: extern_size_name.as_str().into(),
+                externResultSizeName: extern_result_size_name.as_str().into(),
                 ret: ret.0 as _,
                 lambdaSet: lambda_set.0 as _,
                 isToplevel: *is_toplevel,
@@ -979,6 +1030,10 @@ impl RocStructFields {
 pub struct RocFn {
     pub function_name: String,
     pub extern_name: String,
+    /// Returns the number of bytes the captured values take up
+    pub extern_size_name: String,
+    /// Returns the number of bytes the return value takes up
+    pub extern_result_size_name: String,
     pub is_toplevel: bool,
     pub args: Vec<TypeId>,
     pub lambda_set: TypeId,
@@ -1088,6 +1143,16 @@ pub enum RocSingleTagPayload {
     },
 }
 
+impl RocSingleTagPayload {
+    /// The number of values in the payload.
+    fn len(&self) -> usize {
+        match self {
+            RocSingleTagPayload::HasClosure { payload_getters } => payload_getters.len(),
+            RocSingleTagPayload::HasNoClosure { payload_fields } => payload_fields.len(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RocTagUnion {
     Enumeration {
@@ -1268,13 +1333,24 @@ fn add_function_type<'a>(
 
     let name = format!("RocFunction_{closure_var:?}");
 
-    let extern_name = match env.lambda_set_ids.get(&closure_var) {
-        Some(id) => format!("roc__mainForHost_{}_caller", id.0),
-        None => {
-            debug_assert!(is_toplevel);
-            String::from("this_extern_should_not_be_used_this_is_a_bug")
-        }
-    };
+    let (extern_name, extern_size_name, extern_result_size_name) =
+        match env.lambda_set_ids.get(&closure_var) {
+            Some(id) => {
+                let def_name = format!("roc__mainForHost_{}", id.0);
+
+                (
+                    format!("{def_name}_caller"),
+                    format!("{def_name}_size"),
+                    format!("{def_name}_result_size"),
+                )
+            }
+            None => {
+                debug_assert!(is_toplevel);
+                let bug = String::from("this_extern_should_not_be_used_this_is_a_bug");
+
+                (bug.clone(), bug.clone(), bug)
+            }
+        };
 
     for arg_var in args {
         let arg_layout = env
@@ -1311,6 +1387,8 @@ fn add_function_type<'a>(
         RocType::Function(RocFn {
             function_name: name,
             extern_name,
+            extern_size_name,
+            extern_result_size_name,
             args: arg_type_ids.clone(),
             lambda_set: lambda_set_type_id,
             ret: ret_type_id,
@@ -1837,6 +1915,11 @@ where
         )
     });
 
+    let field_layouts = bumpalo::collections::Vec::from_iter_in(
+        sortables.iter().map(|(_, _, field_layout)| *field_layout),
+        arena,
+    );
+
     // This layout should have an entry in glue_procs_by_layout iff it
     // contains closures, but we'll double-check that with a debug_assert.
     let layout = env.layout_cache.interner.get(in_layout);
@@ -1878,12 +1961,16 @@ where
         }
     };
 
-    types.add_named(
+    let type_id = types.add_named(
         &env.layout_cache.interner,
         name.clone(),
         to_type(name, struct_fields),
         in_layout,
-    )
+    );
+
+    types.set_field_offsets(&env.layout_cache.interner, type_id, field_layouts);
+
+    type_id
 }
 
 trait UnionTag: Label + std::fmt::Debug {
@@ -2134,9 +2221,27 @@ fn add_tag_union<'a>(
         layout,
     );
 
+    // A single-tag union with several values in its payload is stored as a struct of them.
+    let payload_layouts = match (&tag_union_type, env.layout_cache.get_repr(layout)) {
+        (RocTagUnion::SingleTagStruct { payload, .. }, LayoutRepr::Struct(field_layouts))
+            if payload.len() == field_layouts.len() =>
+        {
+            Some(field_layouts)
+        }
+        _ => None,
+    };
+
     let typ = RocType::TagUnion(tag_union_type);
     let type_id = types.add_named(&env.layout_cache.interner, name, typ, layout);
 
+    if let Some(field_layouts) = payload_layouts {
+        types.set_field_offsets(
+            &env.layout_cache.interner,
+            type_id,
+            field_layouts.iter().copied(),
+        );
+    }
+
     if let Some(rec_var) = rec_root {
         env.known_recursive_types.insert(rec_var, type_id);
     }
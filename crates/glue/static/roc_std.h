@@ -0,0 +1,329 @@
+// ⚠️ GENERATED CODE ⚠️ - this was copied into the glue by the `roc glue` CLI command
+//
+// C versions of Roc's builtin types, with the same memory layout as the ones the
+// Roc compiler uses. Every type which can point into the heap has `_incref` and
+// `_decref` functions, and so do the types generated by CGlue.roc, e.g.
+//
+//     ROC_INCREF(RocStr, str);
+//     ROC_DECREF(RocStr, str);
+//
+// Values passed to Roc are consumed by Roc, and values Roc returns are owned by
+// the host, so the host is responsible for decrementing their refcounts when it's done.
+
+#ifndef ROC_STD_H
+#define ROC_STD_H
+
+#include <assert.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+#ifdef __cplusplus
+#define ROC_STATIC_ASSERT(condition, message) static_assert(condition, message)
+#define ROC_ALIGNOF(type) alignof(type)
+#else
+#define ROC_STATIC_ASSERT(condition, message) _Static_assert(condition, message)
+#define ROC_ALIGNOF(type) _Alignof(type)
+#endif
+
+// Increment the refcount of a value of the given type, e.g. `ROC_INCREF(RocStr, str)`.
+#define ROC_INCREF(type, value) type##_incref((value), 1)
+
+// Decrement the refcount of a value of the given type, freeing it once
+// nothing references it anymore, e.g. `ROC_DECREF(RocList_RocStr, list)`.
+#define ROC_DECREF(type, value) type##_decref(value)
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// The host has to provide these, because Roc uses them too.
+void* roc_alloc(size_t size, unsigned int alignment);
+void roc_dealloc(void* ptr, unsigned int alignment);
+
+#ifdef __cplusplus
+}
+#endif
+
+#if defined(__SIZEOF_INT128__)
+typedef __int128 roc_i128;
+typedef unsigned __int128 roc_u128;
+#else
+typedef struct roc_i128 {
+    uint64_t low;
+    int64_t high;
+} roc_i128;
+
+typedef struct roc_u128 {
+    uint64_t low;
+    uint64_t high;
+} roc_u128;
+#endif
+
+// A Roc `Dec`: a fixed-point decimal with 18 decimal places.
+typedef struct RocDec {
+    roc_i128 num;
+} RocDec;
+
+// Zero-sized values, such as `{}` and tags without payloads, never need to be
+// stored, so this is never defined; only pointers to it are ever used.
+typedef struct RocUnit RocUnit;
+
+// Refcounting of Roc's heap allocations.
+//
+// The refcount is stored right before the data, and is `INTPTR_MIN` when there is a
+// single reference. A refcount of 0 means the value lives for the whole program,
+// e.g. a string literal, so it's never changed. Any tag id stored in the low bits
+// of a pointer is ignored, and null pointers are left alone.
+
+#define ROC_REFCOUNT_ONE INTPTR_MIN
+#define ROC_REFCOUNT_STATIC 0
+#define ROC_TAG_MASK ((uintptr_t)(sizeof(void*) == 8 ? 0x7 : 0x3))
+
+static inline intptr_t* roc_refcount_ptr(const void* data) {
+    uintptr_t address = (uintptr_t)data & ~ROC_TAG_MASK;
+
+    return (intptr_t*)address - 1;
+}
+
+static inline bool roc_is_unique(const void* data) {
+    if (data == NULL) {
+        return true;
+    }
+
+    return *roc_refcount_ptr(data) == ROC_REFCOUNT_ONE;
+}
+
+static inline void roc_incref(const void* data, size_t amount) {
+    if (data == NULL) {
+        return;
+    }
+
+    intptr_t* refcount = roc_refcount_ptr(data);
+    if (*refcount != ROC_REFCOUNT_STATIC) {
+        *refcount += (intptr_t)amount;
+    }
+}
+
+static inline void roc_decref(const void* data, uint32_t alignment) {
+    if (data == NULL) {
+        return;
+    }
+
+    intptr_t* refcount = roc_refcount_ptr(data);
+    if (*refcount == ROC_REFCOUNT_STATIC) {
+        return;
+    }
+
+    if (*refcount == ROC_REFCOUNT_ONE) {
+        size_t extra_bytes = alignment > sizeof(intptr_t) ? alignment : sizeof(intptr_t);
+        roc_dealloc((char*)refcount - (extra_bytes - sizeof(intptr_t)), alignment);
+    } else {
+        *refcount -= 1;
+    }
+}
+
+// Allocate `size` bytes with a refcount of 1, the same way Roc does. Returns a pointer to the data.
+static inline void* roc_alloc_refcounted(size_t size, uint32_t alignment) {
+    size_t extra_bytes = alignment > sizeof(intptr_t) ? alignment : sizeof(intptr_t);
+    char* allocation = (char*)roc_alloc(extra_bytes + size, (unsigned int)extra_bytes);
+    char* data = allocation + extra_bytes;
+
+    *roc_refcount_ptr(data) = ROC_REFCOUNT_ONE;
+
+    return data;
+}
+
+// The bit which marks seamless slices, which point into another string or list's allocation.
+#define ROC_SEAMLESS_SLICE_BIT ((size_t)INTPTR_MIN)
+
+// RocStr
+
+typedef struct RocStr {
+    char* bytes;
+    size_t length;
+    // For big strings, contains the capacity.
+    // For seamless slices, contains the pointer to the original allocation, shifted right by one.
+    // For small strings, the highest bit is set, and the bytes are stored in the struct itself.
+    size_t capacity_or_alloc_ptr;
+} RocStr;
+
+static inline RocStr roc_str_empty(void) {
+    RocStr str;
+    str.bytes = NULL;
+    str.length = 0;
+    str.capacity_or_alloc_ptr = ROC_SEAMLESS_SLICE_BIT;
+
+    return str;
+}
+
+static inline bool roc_str_is_small(const RocStr* str) {
+    return (intptr_t)str->capacity_or_alloc_ptr < 0;
+}
+
+static inline bool roc_str_is_seamless_slice(const RocStr* str) {
+    return !roc_str_is_small(str) && (intptr_t)str->length < 0;
+}
+
+static inline size_t roc_str_len(const RocStr* str) {
+    if (roc_str_is_small(str)) {
+        return ((const unsigned char*)str)[sizeof(RocStr) - 1] ^ 0x80;
+    } else {
+        return str->length & ~ROC_SEAMLESS_SLICE_BIT;
+    }
+}
+
+// The string's bytes, which are not null-terminated.
+static inline const char* roc_str_bytes(const RocStr* str) {
+    if (roc_str_is_small(str)) {
+        return (const char*)str;
+    } else {
+        return str->bytes;
+    }
+}
+
+// Copy `length` bytes of UTF-8 into a new string.
+static inline RocStr roc_str_from_bytes(const char* bytes, size_t length) {
+    RocStr str = roc_str_empty();
+
+    if (length < sizeof(RocStr)) {
+        memcpy(&str, bytes, length);
+        ((unsigned char*)&str)[sizeof(RocStr) - 1] = (unsigned char)length | 0x80;
+    } else {
+        str.bytes = (char*)roc_alloc_refcounted(length, sizeof(size_t));
+        str.length = length;
+        str.capacity_or_alloc_ptr = length;
+        memcpy(str.bytes, bytes, length);
+    }
+
+    return str;
+}
+
+// Copy a null-terminated UTF-8 string into a new string.
+static inline RocStr roc_str_from_cstr(const char* cstr) {
+    return roc_str_from_bytes(cstr, strlen(cstr));
+}
+
+static inline const char* roc_str_allocation_ptr(const RocStr* str) {
+    if (roc_str_is_seamless_slice(str)) {
+        return (const char*)(str->capacity_or_alloc_ptr << 1);
+    } else {
+        return str->bytes;
+    }
+}
+
+static inline bool roc_str_is_unique(const RocStr* str) {
+    return roc_str_is_small(str) || roc_is_unique(roc_str_allocation_ptr(str));
+}
+
+static inline void RocStr_incref(RocStr str, size_t amount) {
+    if (!roc_str_is_small(&str)) {
+        roc_incref(roc_str_allocation_ptr(&str), amount);
+    }
+}
+
+static inline void RocStr_decref(RocStr str) {
+    if (!roc_str_is_small(&str) && str.capacity_or_alloc_ptr != 0) {
+        roc_decref(roc_str_allocation_ptr(&str), sizeof(size_t));
+    }
+}
+
+// RocList
+//
+// The elements aren't typed in C, so the glue generates a `RocList_<Element>_decref`
+// for each type of list the app uses, which also releases the elements.
+
+typedef struct RocList {
+    void* bytes;
+    size_t length;
+    // For normal lists, contains the capacity.
+    // For seamless slices, contains the pointer to the original allocation, shifted right by one,
+    // with the highest bit set.
+    size_t capacity_or_alloc_ptr;
+} RocList;
+
+static inline RocList roc_list_empty(void) {
+    RocList list;
+    list.bytes = NULL;
+    list.length = 0;
+    list.capacity_or_alloc_ptr = 0;
+
+    return list;
+}
+
+// Copy `length` elements of `element_size` bytes each into a new list. The list takes
+// ownership of the elements, so don't decrement their refcounts afterwards.
+static inline RocList roc_list_from_array(const void* elements, size_t length, size_t element_size, uint32_t alignment) {
+    RocList list = roc_list_empty();
+
+    if (length > 0) {
+        list.bytes = roc_alloc_refcounted(length * element_size, alignment);
+        list.length = length;
+        list.capacity_or_alloc_ptr = length;
+        memcpy(list.bytes, elements, length * element_size);
+    }
+
+    return list;
+}
+
+static inline size_t roc_list_len(RocList list) {
+    return list.length;
+}
+
+static inline bool roc_list_is_seamless_slice(RocList list) {
+    return (intptr_t)list.capacity_or_alloc_ptr < 0;
+}
+
+static inline const void* roc_list_allocation_ptr(RocList list) {
+    if (roc_list_is_seamless_slice(list)) {
+        return (const void*)(list.capacity_or_alloc_ptr << 1);
+    } else {
+        return list.bytes;
+    }
+}
+
+static inline bool roc_list_is_unique(RocList list) {
+    return list.capacity_or_alloc_ptr == 0 || roc_is_unique(roc_list_allocation_ptr(list));
+}
+
+static inline void roc_list_incref(RocList list, size_t amount) {
+    if (list.capacity_or_alloc_ptr != 0) {
+        roc_incref(roc_list_allocation_ptr(list), amount);
+    }
+}
+
+// Decrement the refcount of the list itself, without touching its elements.
+static inline void roc_list_decref(RocList list, uint32_t alignment) {
+    if (list.capacity_or_alloc_ptr != 0) {
+        roc_decref(roc_list_allocation_ptr(list), alignment);
+    }
+}
+
+// RocDict and RocSet
+//
+// A dictionary is a list of items, each holding a key and a value, and a set has the
+// same layout as a dictionary whose values are all `{}`. The glue generates a
+// `RocDictItem_<Key>_<Value>` struct for each type of dictionary the app uses.
+
+typedef struct RocDict {
+    RocList items;
+} RocDict;
+
+typedef struct RocSet {
+    RocList elements;
+} RocSet;
+
+// RocResult
+//
+// The glue generates a `RocResult_<Ok>_<Err>` struct for each type of result the app uses,
+// which stores its payload followed by one of these tags.
+
+typedef uint8_t RocResultTag;
+enum {
+    RocResultTag_Err = 0,
+    RocResultTag_Ok = 1,
+};
+
+#endif // ROC_STD_H
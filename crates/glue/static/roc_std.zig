@@ -0,0 +1,363 @@
+// ⚠️ GENERATED CODE ⚠️ - this file was copied into place by the `roc glue` CLI command
+//
+// Zig versions of Roc's builtin types, with the same memory layout as the ones
+// the Roc compiler uses. Every type in here which can point into the heap has
+// `incref` and `decref` methods, and so do the types generated by ZigGlue.roc.
+//
+// Values passed to Roc are consumed by Roc, and values Roc returns are owned by
+// the host, so the host is responsible for calling `decref` on them when it's done.
+
+const std = @import("std");
+const list = @import("list.zig");
+const str = @import("str.zig");
+const utils = @import("utils.zig");
+
+pub const RocStr = str.RocStr;
+
+/// The payload of tags which don't have one, and the type of `{}` fields.
+pub const RocUnit = extern struct {};
+
+/// A Roc `Dec`: a fixed-point decimal with 18 decimal places.
+pub const RocDec = extern struct {
+    num: i128,
+
+    pub const decimal_places: u5 = 18;
+    pub const one_point_zero_i128: i128 = 1_000_000_000_000_000_000;
+
+    pub fn fromF64(num: f64) RocDec {
+        return .{ .num = @as(i128, @intFromFloat(num * @as(f64, @floatFromInt(one_point_zero_i128)))) };
+    }
+
+    pub fn toF64(self: RocDec) f64 {
+        return @as(f64, @floatFromInt(self.num)) / @as(f64, @floatFromInt(one_point_zero_i128));
+    }
+};
+
+/// Whether values of this type point into the heap, and so need to be refcounted.
+pub fn isRefcounted(comptime T: type) bool {
+    return switch (@typeInfo(T)) {
+        .Struct, .Union, .Enum => @hasDecl(T, "decref"),
+        else => false,
+    };
+}
+
+/// Increment the refcount of any heap allocations in `value`. Does nothing for plain data.
+pub fn increfValue(comptime T: type, value: T, amount: usize) void {
+    if (comptime isRefcounted(T)) {
+        value.incref(amount);
+    }
+}
+
+/// Decrement the refcount of any heap allocations in `value`, freeing the ones which
+/// are no longer referenced. Does nothing for plain data.
+pub fn decrefValue(comptime T: type, value: T) void {
+    if (comptime isRefcounted(T)) {
+        value.decref();
+    }
+}
+
+// Refcounting for pointers to Roc allocations, such as the ones recursive tag unions use.
+// Any tag id stored in the low bits of the pointer is ignored, and null pointers are left alone.
+
+pub fn isUniquePointer(pointer: ?*anyopaque) bool {
+    return utils.isUnique(@ptrCast(pointer));
+}
+
+pub fn increfPointer(pointer: ?*anyopaque, amount: usize) void {
+    utils.increfDataPtrC(@ptrCast(pointer), @intCast(amount));
+}
+
+pub fn decrefPointer(pointer: ?*anyopaque, alignment: u32) void {
+    utils.decrefDataPtrC(@ptrCast(pointer), alignment);
+}
+
+pub fn RocList(comptime T: type) type {
+    return extern struct {
+        bytes: ?[*]T,
+        length: usize,
+        // For normal lists, contains the capacity.
+        // For seamless slices contains the pointer to the original allocation.
+        capacity_or_alloc_ptr: usize,
+
+        const Self = @This();
+        const alignment: u32 = @alignOf(T);
+
+        pub fn empty() Self {
+            return .{ .bytes = null, .length = 0, .capacity_or_alloc_ptr = 0 };
+        }
+
+        /// Copy the elements into a new Roc allocation. The list takes ownership of
+        /// the elements, so don't decref them afterwards.
+        pub fn fromSlice(slice: []const T) Self {
+            return fromBuiltin(list.RocList.fromSlice(T, slice));
+        }
+
+        pub fn len(self: Self) usize {
+            return self.length;
+        }
+
+        pub fn isEmpty(self: Self) bool {
+            return self.length == 0;
+        }
+
+        pub fn asSlice(self: Self) []const T {
+            if (self.bytes) |bytes| {
+                return bytes[0..self.length];
+            } else {
+                return &[_]T{};
+            }
+        }
+
+        pub fn get(self: Self, index: usize) T {
+            return self.asSlice()[index];
+        }
+
+        pub fn isUnique(self: Self) bool {
+            return self.toBuiltin().isUnique();
+        }
+
+        pub fn incref(self: Self, amount: usize) void {
+            utils.increfDataPtrC(self.toBuiltin().getAllocationPtr(), @intCast(amount));
+        }
+
+        pub fn decref(self: Self) void {
+            const builtin = self.toBuiltin();
+
+            // The last reference owns the elements, so they need to be released too.
+            // A seamless slice doesn't know about the elements outside of it, so
+            // in that case we leave the elements alone rather than leaking only some of them.
+            if (comptime isRefcounted(T)) {
+                if (!builtin.isSeamlessSlice() and builtin.isUnique()) {
+                    for (self.asSlice()) |element| {
+                        decrefValue(T, element);
+                    }
+                }
+            }
+
+            builtin.decref(alignment);
+        }
+
+        fn toBuiltin(self: Self) list.RocList {
+            return .{
+                .bytes = @ptrCast(self.bytes),
+                .length = self.length,
+                .capacity_or_alloc_ptr = self.capacity_or_alloc_ptr,
+            };
+        }
+
+        fn fromBuiltin(builtin: list.RocList) Self {
+            return .{
+                .bytes = @ptrCast(@alignCast(builtin.bytes)),
+                .length = builtin.length,
+                .capacity_or_alloc_ptr = builtin.capacity_or_alloc_ptr,
+            };
+        }
+    };
+}
+
+/// A pointer to a refcounted heap allocation holding a single `T`.
+pub fn RocBox(comptime T: type) type {
+    return extern struct {
+        contents: *T,
+
+        const Self = @This();
+        const alignment: u32 = @alignOf(T);
+
+        /// Move `value` into a new Roc allocation.
+        pub fn init(value: T) Self {
+            const bytes = utils.allocateWithRefcount(@sizeOf(T), alignment);
+            const contents: *T = @ptrCast(@alignCast(bytes));
+            contents.* = value;
+
+            return .{ .contents = contents };
+        }
+
+        pub fn get(self: Self) T {
+            return self.contents.*;
+        }
+
+        pub fn isUnique(self: Self) bool {
+            return utils.isUnique(@ptrCast(self.contents));
+        }
+
+        pub fn incref(self: Self, amount: usize) void {
+            utils.increfDataPtrC(@ptrCast(self.contents), @intCast(amount));
+        }
+
+        pub fn decref(self: Self) void {
+            if (comptime isRefcounted(T)) {
+                if (self.isUnique()) {
+                    decrefValue(T, self.contents.*);
+                }
+            }
+
+            utils.decrefDataPtrC(@ptrCast(self.contents), alignment);
+        }
+    };
+}
+
+/// One entry in a `RocDict`. The field with the larger alignment comes first,
+/// with the key winning ties, which matches the layout of Roc's dictionaries.
+pub fn RocDictItem(comptime K: type, comptime V: type) type {
+    if (@alignOf(K) >= @alignOf(V)) {
+        return extern struct {
+            key: K,
+            value: V,
+
+            const Self = @This();
+
+            pub fn incref(self: Self, amount: usize) void {
+                increfValue(K, self.key, amount);
+                increfValue(V, self.value, amount);
+            }
+
+            pub fn decref(self: Self) void {
+                decrefValue(K, self.key);
+                decrefValue(V, self.value);
+            }
+        };
+    } else {
+        return extern struct {
+            value: V,
+            key: K,
+
+            const Self = @This();
+
+            pub fn incref(self: Self, amount: usize) void {
+                increfValue(K, self.key, amount);
+                increfValue(V, self.value, amount);
+            }
+
+            pub fn decref(self: Self) void {
+                decrefValue(K, self.key);
+                decrefValue(V, self.value);
+            }
+        };
+    }
+}
+
+pub fn RocDict(comptime K: type, comptime V: type) type {
+    return extern struct {
+        items: RocList(Item),
+
+        const Self = @This();
+        pub const Item = RocDictItem(K, V);
+
+        pub fn empty() Self {
+            return .{ .items = RocList(Item).empty() };
+        }
+
+        /// The dictionary takes ownership of the items, and trusts the caller that no key appears twice.
+        pub fn fromSlice(items: []const Item) Self {
+            return .{ .items = RocList(Item).fromSlice(items) };
+        }
+
+        pub fn len(self: Self) usize {
+            return self.items.len();
+        }
+
+        pub fn asSlice(self: Self) []const Item {
+            return self.items.asSlice();
+        }
+
+        pub fn incref(self: Self, amount: usize) void {
+            self.items.incref(amount);
+        }
+
+        pub fn decref(self: Self) void {
+            self.items.decref();
+        }
+    };
+}
+
+/// A set has the same layout as a dictionary whose values are all `{}`,
+/// which is the same as a list of its elements.
+pub fn RocSet(comptime T: type) type {
+    return extern struct {
+        elements: RocList(T),
+
+        const Self = @This();
+
+        pub fn empty() Self {
+            return .{ .elements = RocList(T).empty() };
+        }
+
+        /// The set takes ownership of the elements, and trusts the caller that there are no duplicates.
+        pub fn fromSlice(elements: []const T) Self {
+            return .{ .elements = RocList(T).fromSlice(elements) };
+        }
+
+        pub fn len(self: Self) usize {
+            return self.elements.len();
+        }
+
+        pub fn asSlice(self: Self) []const T {
+            return self.elements.asSlice();
+        }
+
+        pub fn incref(self: Self, amount: usize) void {
+            self.elements.incref(amount);
+        }
+
+        pub fn decref(self: Self) void {
+            self.elements.decref();
+        }
+    };
+}
+
+pub const RocResultTag = enum(u8) {
+    RocErr = 0,
+    RocOk = 1,
+};
+
+pub fn RocResult(comptime T: type, comptime E: type) type {
+    return extern struct {
+        payload: extern union {
+            ok: T,
+            err: E,
+        },
+        tag: RocResultTag,
+
+        const Self = @This();
+
+        pub fn ok(value: T) Self {
+            return .{ .payload = .{ .ok = value }, .tag = .RocOk };
+        }
+
+        pub fn err(value: E) Self {
+            return .{ .payload = .{ .err = value }, .tag = .RocErr };
+        }
+
+        pub fn isOk(self: Self) bool {
+            return self.tag == .RocOk;
+        }
+
+        pub fn isErr(self: Self) bool {
+            return self.tag == .RocErr;
+        }
+
+        pub fn getOk(self: Self) T {
+            std.debug.assert(self.isOk());
+            return self.payload.ok;
+        }
+
+        pub fn getErr(self: Self) E {
+            std.debug.assert(self.isErr());
+            return self.payload.err;
+        }
+
+        pub fn incref(self: Self, amount: usize) void {
+            switch (self.tag) {
+                .RocOk => increfValue(T, self.payload.ok, amount),
+                .RocErr => increfValue(E, self.payload.err, amount),
+            }
+        }
+
+        pub fn decref(self: Self) void {
+            switch (self.tag) {
+                .RocOk => decrefValue(T, self.payload.ok),
+                .RocErr => decrefValue(E, self.payload.err),
+            }
+        }
+    };
+}
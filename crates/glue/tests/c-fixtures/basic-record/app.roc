@@ -0,0 +1,6 @@
+app "app"
+    packages { pf: "platform.roc" }
+    imports []
+    provides [main] to pf
+
+main = { a: 1995, b: 42 }
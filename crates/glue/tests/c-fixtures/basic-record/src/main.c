@@ -0,0 +1,9 @@
+void run(void) {
+    MyRcd record = roc_mainForHost();
+
+    // The U128 field comes first, because it has the larger alignment.
+    assert(offsetof(MyRcd, b) == 0);
+    assert(offsetof(MyRcd, a) == 16);
+
+    printf("Record was: { a: %llu, b: %llu }\n", (unsigned long long)record.a, (unsigned long long)record.b);
+}
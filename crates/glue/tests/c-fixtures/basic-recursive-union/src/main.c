@@ -0,0 +1,47 @@
+// Print the expression as it would be written in Roc, with parentheses
+// around it if it's the payload of another tag.
+static void print_expr(Expr expr, bool nested) {
+    if (nested) printf("(");
+
+    switch (Expr_discriminant(expr)) {
+    case discriminant_Expr_String: {
+        RocStr str = Expr_get_String(expr).f0;
+        printf("String \"%.*s\"", (int)roc_str_len(&str), roc_str_bytes(&str));
+        break;
+    }
+    case discriminant_Expr_Concat: {
+        payload_Expr_Concat payload = Expr_get_Concat(expr);
+
+        printf("Concat ");
+        print_expr(payload.f0, true);
+        printf(" ");
+        print_expr(payload.f1, true);
+        break;
+    }
+    }
+
+    if (nested) printf(")");
+}
+
+void run(void) {
+    Expr tag_union = roc_mainForHost();
+
+    Expr examples[] = {
+        Expr_Concat(Expr_String(roc_str_from_cstr("Hello, ")), Expr_String(roc_str_from_cstr("World!"))),
+        Expr_String(roc_str_from_cstr("this is a test")),
+    };
+
+    printf("tag_union was: ");
+    print_expr(tag_union, false);
+    printf("\n");
+
+    for (size_t index = 0; index < sizeof(examples) / sizeof(examples[0]); index++) {
+        printf("Constructed: ");
+        print_expr(examples[index], false);
+        printf("\n");
+
+        ROC_DECREF(Expr, examples[index]);
+    }
+
+    ROC_DECREF(Expr, tag_union);
+}
@@ -0,0 +1,7 @@
+void run(void) {
+    roc_mainForHost_result closure = roc_mainForHost(42);
+
+    printf("Answer was: %lld\n", (long long)roc_mainForHost_result_call(closure));
+
+    roc_mainForHost_result_deinit(closure);
+}
@@ -0,0 +1,31 @@
+// Print the tree as it would be written in Roc.
+static void print_tree(StrRoseTree tree) {
+    payload_StrRoseTree_Tree payload = StrRoseTree_get_Tree(tree);
+    const StrRoseTree* children = RocList_StrRoseTree_elements(payload.f1);
+
+    printf("Tree \"%.*s\" [", (int)roc_str_len(&payload.f0), roc_str_bytes(&payload.f0));
+    for (size_t index = 0; index < roc_list_len(payload.f1); index++) {
+        if (index > 0) {
+            printf(", ");
+        }
+        print_tree(children[index]);
+    }
+    printf("]");
+}
+
+void run(void) {
+    StrRoseTree tag_union = roc_mainForHost();
+
+    assert(roc_list_len(StrRoseTree_get_Tree(tag_union).f1) == 2);
+
+    StrRoseTree tree = StrRoseTree_Tree(roc_str_from_cstr("foo"), roc_list_empty());
+
+    printf("tag_union was: ");
+    print_tree(tag_union);
+    printf("\nConstructed: ");
+    print_tree(tree);
+    printf("\n");
+
+    ROC_DECREF(StrRoseTree, tree);
+    ROC_DECREF(StrRoseTree, tag_union);
+}
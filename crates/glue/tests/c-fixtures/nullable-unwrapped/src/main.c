@@ -0,0 +1,44 @@
+// Print the list as it would be written in Roc, with parentheses
+// around it if it's the payload of another tag.
+static void print_list(StrConsList list, bool nested) {
+    switch (StrConsList_discriminant(list)) {
+    case discriminant_StrConsList_Nil:
+        printf("Nil");
+        break;
+    case discriminant_StrConsList_Cons: {
+        payload_StrConsList_Cons payload = StrConsList_get_Cons(list);
+
+        if (nested) printf("(");
+        printf("Cons \"%.*s\" ", (int)roc_str_len(&payload.f0), roc_str_bytes(&payload.f0));
+        print_list(payload.f1, true);
+        if (nested) printf(")");
+        break;
+    }
+    }
+}
+
+void run(void) {
+    StrConsList tag_union = roc_mainForHost();
+
+    StrConsList examples[] = {
+        StrConsList_Cons(roc_str_from_cstr("small str"), StrConsList_Nil()),
+        StrConsList_Nil(),
+    };
+
+    assert(StrConsList_is_Cons(examples[0]));
+    assert(StrConsList_is_Nil(examples[1]));
+
+    printf("tag_union was: ");
+    print_list(tag_union, false);
+    printf("\n");
+
+    for (size_t index = 0; index < sizeof(examples) / sizeof(examples[0]); index++) {
+        printf("Constructed: ");
+        print_list(examples[index], false);
+        printf("\n");
+
+        ROC_DECREF(StrConsList, examples[index]);
+    }
+
+    ROC_DECREF(StrConsList, tag_union);
+}
@@ -0,0 +1,53 @@
+// Print the tree as it would be written in Roc, with parentheses
+// around it if it's the payload of another tag.
+static void print_tree(StrFingerTree tree, bool nested) {
+    switch (StrFingerTree_discriminant(tree)) {
+    case discriminant_StrFingerTree_Empty:
+        printf("Empty");
+        break;
+    case discriminant_StrFingerTree_Single: {
+        RocStr str = StrFingerTree_get_Single(tree).f0;
+
+        if (nested) printf("(");
+        printf("Single \"%.*s\"", (int)roc_str_len(&str), roc_str_bytes(&str));
+        if (nested) printf(")");
+        break;
+    }
+    case discriminant_StrFingerTree_More: {
+        payload_StrFingerTree_More payload = StrFingerTree_get_More(tree);
+
+        if (nested) printf("(");
+        printf("More \"%.*s\" ", (int)roc_str_len(&payload.f0), roc_str_bytes(&payload.f0));
+        print_tree(payload.f1, true);
+        if (nested) printf(")");
+        break;
+    }
+    }
+}
+
+void run(void) {
+    StrFingerTree tag_union = roc_mainForHost();
+
+    assert(StrFingerTree_is_Empty(StrFingerTree_Empty()));
+
+    StrFingerTree examples[] = {
+        StrFingerTree_More(roc_str_from_cstr("small str"), StrFingerTree_Single(roc_str_from_cstr("other str"))),
+        StrFingerTree_More(roc_str_from_cstr("small str"), StrFingerTree_Empty()),
+        StrFingerTree_Single(roc_str_from_cstr("small str")),
+        StrFingerTree_Empty(),
+    };
+
+    printf("tag_union was: ");
+    print_tree(tag_union, false);
+    printf("\n");
+
+    for (size_t index = 0; index < sizeof(examples) / sizeof(examples[0]); index++) {
+        printf("Constructed: ");
+        print_tree(examples[index], false);
+        printf("\n");
+
+        ROC_DECREF(StrFingerTree, examples[index]);
+    }
+
+    ROC_DECREF(StrFingerTree, tag_union);
+}
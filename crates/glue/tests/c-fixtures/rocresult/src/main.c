@@ -0,0 +1,15 @@
+void run(void) {
+    RocResult_RocStr_I32 string = roc_mainForHost(true);
+
+    assert(RocResult_RocStr_I32_is_ok(string));
+    RocStr ok = RocResult_RocStr_I32_get_ok(string);
+    printf("Answer was: Ok \"%.*s\"\n", (int)roc_str_len(&ok), roc_str_bytes(&ok));
+
+    RocResult_RocStr_I32 integer = roc_mainForHost(false);
+
+    assert(RocResult_RocStr_I32_is_err(integer));
+    printf("Answer was: Err %d\n", (int)RocResult_RocStr_I32_get_err(integer));
+
+    ROC_DECREF(RocResult_RocStr_I32, string);
+    ROC_DECREF(RocResult_RocStr_I32, integer);
+}
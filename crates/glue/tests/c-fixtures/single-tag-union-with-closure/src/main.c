@@ -0,0 +1,13 @@
+void run(void) {
+    Greeter greeter = roc_mainForHost(roc_str_from_cstr("!"));
+
+    RocStr name = greeter.f0;
+    RocStr answer = Greeter_f1_call(greeter.f1, roc_str_from_cstr("Hello"));
+    printf("Greeter \"%.*s\" answered: \"%.*s\"\n",
+           (int)roc_str_len(&name), roc_str_bytes(&name),
+           (int)roc_str_len(&answer), roc_str_bytes(&answer));
+
+    RocStr_decref(answer);
+    Greeter_f1_deinit(greeter.f1);
+    RocStr_decref(name);
+}
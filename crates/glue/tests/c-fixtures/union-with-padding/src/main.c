@@ -0,0 +1,47 @@
+// Print the tag union as it would be written in Roc.
+static void print_tag_union(NonRecursive tag_union) {
+    switch (NonRecursive_discriminant(tag_union)) {
+    case discriminant_NonRecursive_Foo: {
+        RocStr str = NonRecursive_get_Foo(tag_union);
+        printf("Foo \"%.*s\"", (int)roc_str_len(&str), roc_str_bytes(&str));
+        break;
+    }
+    case discriminant_NonRecursive_Bar:
+        printf("Bar %llu", (unsigned long long)NonRecursive_get_Bar(tag_union));
+        break;
+    case discriminant_NonRecursive_Blah:
+        printf("Blah %d", (int)NonRecursive_get_Blah(tag_union));
+        break;
+    case discriminant_NonRecursive_Baz:
+        printf("Baz");
+        break;
+    }
+}
+
+void run(void) {
+    NonRecursive tag_union = roc_mainForHost();
+
+    assert(NonRecursive_is_Foo(tag_union));
+
+    NonRecursive examples[] = {
+        NonRecursive_Foo(roc_str_from_cstr("small str")),
+        NonRecursive_Foo(roc_str_from_cstr("A long enough string to not be small")),
+        NonRecursive_Bar(123),
+        NonRecursive_Baz(),
+        NonRecursive_Blah(456),
+    };
+
+    printf("tag_union was: ");
+    print_tag_union(tag_union);
+    printf("\n");
+
+    for (size_t index = 0; index < sizeof(examples) / sizeof(examples[0]); index++) {
+        printf("Constructed: ");
+        print_tag_union(examples[index]);
+        printf("\n");
+
+        ROC_DECREF(NonRecursive, examples[index]);
+    }
+
+    ROC_DECREF(NonRecursive, tag_union);
+}
@@ -0,0 +1,91 @@
+// ⚠️ READ THIS BEFORE MODIFYING THIS FILE! ⚠️
+//
+// This file is a fixture template. If the file you're looking at is
+// in the fixture-templates/ directory, then you're all set - go ahead
+// and modify it, and it will modify all the fixture tests.
+//
+// If this file is in the c-fixtures/ directory, on the other hand, then
+// it is gitignored and will be overwritten the next time tests run.
+// So you probably don't want to modify it by hand! Instead, modify the
+// file with the same name in the fixture-templates/ directory.
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#ifndef _WIN32
+#include <sys/mman.h> // for mmap
+#include <sys/stat.h> // for shm_open's modes
+#include <fcntl.h>    // for shm_open's flags
+#include <unistd.h>   // for getppid
+#endif
+
+#include "test_glue/roc_app.h"
+
+// `roc` compiles only this file, so the fixture's source is included into it.
+#include "src/main.c"
+
+int main(void) {
+    run();
+
+    return 0;
+}
+
+// Externs required by roc_std.h and by the Roc app
+
+void* roc_alloc(size_t size, unsigned int alignment) {
+    (void)alignment;
+    return malloc(size);
+}
+
+void* roc_realloc(void* ptr, size_t new_size, size_t old_size, unsigned int alignment) {
+    (void)old_size;
+    (void)alignment;
+    return realloc(ptr, new_size);
+}
+
+void roc_dealloc(void* ptr, unsigned int alignment) {
+    (void)alignment;
+    free(ptr);
+}
+
+void roc_panic(const RocStr* msg, uint32_t tag_id) {
+    const char* source = tag_id == 0 ? "Roc standard library" : "Application";
+    fprintf(stderr, "%s hit a panic: %.*s\n", source, (int)roc_str_len(msg), roc_str_bytes(msg));
+    exit(1);
+}
+
+void roc_dbg(const RocStr* loc, const RocStr* msg, const RocStr* src) {
+    fprintf(stderr, "[%.*s] %.*s = %.*s\n",
+            (int)roc_str_len(loc), roc_str_bytes(loc),
+            (int)roc_str_len(src), roc_str_bytes(src),
+            (int)roc_str_len(msg), roc_str_bytes(msg));
+}
+
+void* roc_memset(void* dst, int value, size_t size) {
+    return memset(dst, value, size);
+}
+
+int roc_shm_open(char* name, int oflag, int mode) {
+#ifdef _WIN32
+    return 0;
+#else
+    return shm_open(name, oflag, mode);
+#endif
+}
+
+void* roc_mmap(void* addr, int length, int prot, int flags, int fd, int offset) {
+#ifdef _WIN32
+    return addr;
+#else
+    return mmap(addr, length, prot, flags, fd, offset);
+#endif
+}
+
+int roc_getppid(void) {
+#ifdef _WIN32
+    return 0;
+#else
+    return getppid();
+#endif
+}
@@ -0,0 +1,30 @@
+// ⚠️ READ THIS BEFORE MODIFYING THIS FILE! ⚠️
+//
+// This file is a fixture template. If the file you're looking at is
+// in the fixture-templates/ directory, then you're all set - go ahead
+// and modify it, and it will modify all the fixture tests.
+//
+// If this file is in the zig-fixtures/ directory, on the other hand, then
+// it is gitignored and will be overwritten the next time tests run.
+// So you probably don't want to modify it by hand! Instead, modify the
+// file with the same name in the fixture-templates/ directory.
+
+const std = @import("std");
+
+// Compiles the host and the generated glue on their own, without the Roc app.
+// This means problems in the generated Zig (including its layout checks)
+// are reported by the Zig compiler directly, rather than as a failed `roc` build.
+pub fn build(b: *std.Build) void {
+    const target = b.standardTargetOptions(.{});
+    const optimize = b.standardOptimizeOption(.{});
+
+    const host = b.addObject(.{
+        .name = "host",
+        .root_source_file = .{ .path = "host.zig" },
+        .target = target,
+        .optimize = optimize,
+    });
+    host.linkLibC();
+
+    b.getInstallStep().dependOn(&host.step);
+}
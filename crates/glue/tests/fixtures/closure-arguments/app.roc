@@ -0,0 +1,8 @@
+app "app"
+    packages { pf: "platform.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str -> (I64 -> Str)
+main = \greeting ->
+    \n -> "$(greeting) $(Num.toStr n)"
@@ -0,0 +1,9 @@
+platform "test-platform"
+    requires {} { main : Str -> (I64 -> Str) }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+mainForHost : Str -> (I64 -> Str)
+mainForHost = \greeting -> main greeting
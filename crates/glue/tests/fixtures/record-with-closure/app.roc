@@ -0,0 +1,11 @@
+app "app"
+    packages { pf: "platform.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str -> { name : Str, greet : Str -> Str }
+main = \punctuation ->
+    {
+        name: "Roc",
+        greet: \greeting -> "$(greeting), Roc$(punctuation)",
+    }
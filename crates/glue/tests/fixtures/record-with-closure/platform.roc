@@ -0,0 +1,11 @@
+platform "test-platform"
+    requires {} { main : Str -> { name : Str, greet : Str -> Str } }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+Greeter : { name : Str, greet : Str -> Str }
+
+mainForHost : Str -> Greeter
+mainForHost = \punctuation -> main punctuation
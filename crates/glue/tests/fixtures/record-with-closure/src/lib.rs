@@ -0,0 +1,87 @@
+use roc_app;
+use roc_std::RocStr;
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+/// The number of Roc allocations which haven't been freed yet
+static LIVE_ALLOCATIONS: AtomicIsize = AtomicIsize::new(0);
+
+#[no_mangle]
+pub extern "C" fn rust_main() {
+    {
+        let greeter = roc_app::mainForHost(RocStr::from("!"));
+
+        let answer = greeter.greet().call(RocStr::from("Hello"));
+
+        println!("Name was: {:?}", greeter.name()); // Debug
+        println!("Answer was: {:?}", answer); // Debug
+    }
+
+    {
+        // The closure captures this, so it's only freed if dropping the closure releases it
+        let punctuation = RocStr::from("! (too long to be a small string)");
+        let greeter = roc_app::mainForHost(punctuation);
+        let greet = greeter.greet();
+
+        drop(greeter);
+        drop(greet);
+    }
+
+    println!(
+        "Allocations left after dropping: {}",
+        LIVE_ALLOCATIONS.load(Ordering::SeqCst)
+    );
+}
+
+// Externs required by roc_std and by the Roc app
+
+use core::ffi::c_void;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_alloc(size: usize, _alignment: u32) -> *mut c_void {
+    LIVE_ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+
+    return libc::malloc(size);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_realloc(
+    c_ptr: *mut c_void,
+    new_size: usize,
+    _old_size: usize,
+    _alignment: u32,
+) -> *mut c_void {
+    return libc::realloc(c_ptr, new_size);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
+    LIVE_ALLOCATIONS.fetch_sub(1, Ordering::SeqCst);
+
+    return libc::free(c_ptr);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_panic(msg: *mut RocStr, tag_id: u32) {
+    match tag_id {
+        0 => {
+            eprintln!("Roc standard library hit a panic: {}", &*msg);
+        }
+        1 => {
+            eprintln!("Application hit a panic: {}", &*msg);
+        }
+        _ => unreachable!(),
+    }
+    std::process::exit(1);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_dbg(loc: *mut RocStr, msg: *mut RocStr, src: *mut RocStr) {
+    eprintln!("[{}] {} = {}", &*loc, &*src, &*msg);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut c_void {
+    libc::memset(dst, c, n)
+}
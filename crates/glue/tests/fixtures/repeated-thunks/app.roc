@@ -0,0 +1,8 @@
+app "app"
+    packages { pf: "platform.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str -> ({} -> Str)
+main = \name ->
+    \{} -> "Hello, $(name)!"
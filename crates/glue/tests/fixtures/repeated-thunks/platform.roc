@@ -0,0 +1,9 @@
+platform "test-platform"
+    requires {} { main : Str -> ({} -> Str) }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+mainForHost : Str -> ({} -> Str)
+mainForHost = \name -> main name
@@ -1,16 +1,23 @@
 use std::env;
 use std::path::PathBuf;
 
+/// The directory of the fixture `name` for the glue language `lang`, e.g. `"zig"` for
+/// crates/glue/tests/zig-fixtures/{name}. The Rust fixtures are in crates/glue/tests/fixtures/.
 #[allow(dead_code)]
-pub fn fixtures_dir(dir_name: &str) -> PathBuf {
+pub fn glue_fixtures_dir(lang: &str, name: &str) -> PathBuf {
     let mut path = root_dir();
 
-    // Descend into cli/tests/fixtures/{dir_name}
     path.push("crates");
     path.push("glue");
     path.push("tests");
-    path.push("fixtures");
-    path.push(dir_name);
+
+    if lang == "rust" {
+        path.push("fixtures");
+    } else {
+        path.push(format!("{lang}-fixtures"));
+    }
+
+    path.push(name);
 
     path
 }
@@ -11,7 +11,7 @@ mod helpers;
 
 #[cfg(test)]
 mod glue_cli_run {
-    use crate::helpers::fixtures_dir;
+    use crate::helpers::glue_fixtures_dir;
     use cli_utils::helpers::{has_error, run_glue, run_roc, Out};
     use std::fs;
     use std::path::{Path, PathBuf};
@@ -24,11 +24,43 @@ mod glue_cli_run {
     #[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
     const TEST_LEGACY_LINKER: bool = false;
 
-    /// This macro does two things.
+    /// The language to generate glue for, which decides the glue spec,
+    /// the fixture template, and which directory the fixtures are in.
+    #[derive(Clone, Copy)]
+    enum Glue {
+        Rust,
+        Zig,
+        C,
+    }
+
+    impl Glue {
+        fn spec_file_name(self) -> &'static str {
+            match self {
+                Glue::Rust => "RustGlue.roc",
+                Glue::Zig => "ZigGlue.roc",
+                Glue::C => "CGlue.roc",
+            }
+        }
+
+        /// The name of the language, which names its fixture template and fixtures directories.
+        fn lang(self) -> &'static str {
+            match self {
+                Glue::Rust => "rust",
+                Glue::Zig => "zig",
+                Glue::C => "c",
+            }
+        }
+
+        fn fixtures_dir(self, dir_name: &str) -> PathBuf {
+            glue_fixtures_dir(self.lang(), dir_name)
+        }
+    }
+
+    /// This macro does two things, for the fixtures of the given glue language.
     ///
     /// First, it generates and runs a separate test for each of the given
     /// expected stdout endings. Each of these should test a particular .roc file
-    /// in the fixtures/ directory. The fixtures themselves run assertions too, but
+    /// in the fixtures/ (or zig-fixtures/, or c-fixtures/) directory. The fixtures themselves run assertions too, but
     /// the stdout check verifies that we're actually running the code we think we are;
     /// without it, it would be possible that the fixtures are just exiting without running
     /// any assertions, and we would have no way to find out!
@@ -39,14 +71,14 @@ mod glue_cli_run {
     /// will fail if we ever add a new .roc file to fixtures/ and forget to
     /// add a test for it here!
     macro_rules! fixtures {
-        ($($test_name:ident:$fixture_dir:expr => $ends_with:expr,)+) => {
+        ($glue:expr; $($test_name:ident:$fixture_dir:expr => $ends_with:expr,)+) => {
             $(
                 #[test]
                 #[allow(non_snake_case)]
                 fn $test_name() {
-                    let dir = fixtures_dir($fixture_dir);
+                    let dir = $glue.fixtures_dir($fixture_dir);
 
-                    generate_glue_for(&dir, std::iter::empty());
+                    generate_glue_for(&dir, $glue, std::iter::empty());
 
                     fn validate<'a, I: IntoIterator<Item = &'a str>>(dir: PathBuf, args: I) {
                         let out = run_app(&dir.join("app.roc"), args);
@@ -67,7 +99,7 @@ mod glue_cli_run {
                     let test_name_str = stringify!($test_name);
 
                     // TODO after #5924 is fixed; remove this
-                    let skip_on_linux_surgical_linker = ["closures", "option", "nullable_wrapped", "enumeration", "nested_record"];
+                    let skip_on_linux_surgical_linker = ["closures", "closure_arguments", "record_with_closure", "single_tag_union_with_closure", "repeated_thunks", "option", "nullable_wrapped", "enumeration", "nested_record"];
 
                     // Validate linux with the default linker.
                     if !(cfg!(target_os = "linux") && (skip_on_linux_surgical_linker.contains(&test_name_str))) {
@@ -91,12 +123,13 @@ mod glue_cli_run {
                     all_fixtures.insert($fixture_dir.to_string());
                 )*
 
-                check_for_tests(&mut all_fixtures);
+                check_for_tests($glue, &mut all_fixtures);
             }
         }
     }
 
     fixtures! {
+        Glue::Rust;
         basic_record:"basic-record" => "Record was: MyRcd { b: 42, a: 1995 }\n",
         nested_record:"nested-record" => "Record was: Outer { y: \"foo\", z: [1, 2], x: Inner { b: 24.0, a: 5 } }\n",
         enumeration:"enumeration" => "tag_union was: MyEnum::Foo, Bar is: MyEnum::Bar, Baz is: MyEnum::Baz\n",
@@ -158,6 +191,22 @@ mod glue_cli_run {
         closures:"closures" => indoc!(r#"
             Answer was: 672
         "#),
+        closure_arguments:"closure-arguments" => indoc!(r#"
+            Answer was: "Hello 42"
+        "#),
+        record_with_closure:"record-with-closure" => indoc!(r#"
+            Name was: "Roc"
+            Answer was: "Hello, Roc!"
+            Allocations left after dropping: 0
+        "#),
+        single_tag_union_with_closure:"single-tag-union-with-closure" => indoc!(r#"
+            Name was: "Roc"
+            Answer was: "Hello, Roc!"
+        "#),
+        repeated_thunks:"repeated-thunks" => indoc!(r#"
+            Answer was: "Hello, Roc!"
+            Answer was: "Hello, a name which is too long to be a small string!"
+        "#),
         rocresult:"rocresult" => indoc!(r#"
             Answer was: RocOk(ManuallyDrop { value: "Hello World!" })
             Answer was: RocErr(ManuallyDrop { value: 42 })
@@ -168,10 +217,111 @@ mod glue_cli_run {
         "#),
     }
 
-    fn check_for_tests(all_fixtures: &mut roc_collections::VecSet<String>) {
+    mod zig {
+        use super::*;
+
+        // These also check that each fixture's host and glue compile with `zig build`,
+        // before building the app with `roc`.
+        fixtures! {
+            Glue::Zig;
+            basic_record:"basic-record" => "Record was: { a: 1995, b: 42 }\n",
+            union_with_padding:"union-with-padding" => indoc!(r#"
+                tag_union was: Foo "This is a test"
+                Constructed: Foo "small str"
+                Constructed: Foo "A long enough string to not be small"
+                Constructed: Bar 123
+                Constructed: Baz
+                Constructed: Blah 456
+            "#),
+            basic_recursive_union:"basic-recursive-union" => indoc!(r#"
+                tag_union was: Concat (String "Hello, ") (String "World!")
+                Constructed: Concat (String "Hello, ") (String "World!")
+                Constructed: String "this is a test"
+            "#),
+            nullable_wrapped:"nullable-wrapped" => indoc!(r#"
+                tag_union was: More "foo" (More "bar" Empty)
+                Constructed: More "small str" (Single "other str")
+                Constructed: More "small str" Empty
+                Constructed: Single "small str"
+                Constructed: Empty
+            "#),
+            nullable_unwrapped:"nullable-unwrapped" => indoc!(r#"
+                tag_union was: Cons "World!" (Cons "Hello " Nil)
+                Constructed: Cons "small str" Nil
+                Constructed: Nil
+            "#),
+            nonnullable_unwrapped:"nonnullable-unwrapped" => indoc!(r#"
+                tag_union was: Tree "root" [Tree "leaf1" [], Tree "leaf2" []]
+                Constructed: Tree "foo" []
+            "#),
+            rocresult:"rocresult" => indoc!(r#"
+                Answer was: Ok "Hello World!"
+                Answer was: Err 42
+            "#),
+            closures:"closures" => indoc!(r#"
+                Answer was: 672
+            "#),
+            single_tag_union_with_closure:"single-tag-union-with-closure" => indoc!(r#"
+                Greeter "Roc" answered: "Hello, Roc!"
+            "#),
+        }
+    }
+
+    mod c {
+        use super::*;
+
+        // `roc` compiles each fixture's host.c, which includes the generated header, and
+        // `check_c_layouts` compiles the header for every architecture, so any layout
+        // assertion in the header which fails also fails the fixture's test.
+        fixtures! {
+            Glue::C;
+            basic_record:"basic-record" => "Record was: { a: 1995, b: 42 }\n",
+            union_with_padding:"union-with-padding" => indoc!(r#"
+                tag_union was: Foo "This is a test"
+                Constructed: Foo "small str"
+                Constructed: Foo "A long enough string to not be small"
+                Constructed: Bar 123
+                Constructed: Baz
+                Constructed: Blah 456
+            "#),
+            basic_recursive_union:"basic-recursive-union" => indoc!(r#"
+                tag_union was: Concat (String "Hello, ") (String "World!")
+                Constructed: Concat (String "Hello, ") (String "World!")
+                Constructed: String "this is a test"
+            "#),
+            nullable_wrapped:"nullable-wrapped" => indoc!(r#"
+                tag_union was: More "foo" (More "bar" Empty)
+                Constructed: More "small str" (Single "other str")
+                Constructed: More "small str" Empty
+                Constructed: Single "small str"
+                Constructed: Empty
+            "#),
+            nullable_unwrapped:"nullable-unwrapped" => indoc!(r#"
+                tag_union was: Cons "World!" (Cons "Hello " Nil)
+                Constructed: Cons "small str" Nil
+                Constructed: Nil
+            "#),
+            nonnullable_unwrapped:"nonnullable-unwrapped" => indoc!(r#"
+                tag_union was: Tree "root" [Tree "leaf1" [], Tree "leaf2" []]
+                Constructed: Tree "foo" []
+            "#),
+            rocresult:"rocresult" => indoc!(r#"
+                Answer was: Ok "Hello World!"
+                Answer was: Err 42
+            "#),
+            closures:"closures" => indoc!(r#"
+                Answer was: 672
+            "#),
+            single_tag_union_with_closure:"single-tag-union-with-closure" => indoc!(r#"
+                Greeter "Roc" answered: "Hello, Roc!"
+            "#),
+        }
+    }
+
+    fn check_for_tests(glue: Glue, all_fixtures: &mut roc_collections::VecSet<String>) {
         use roc_collections::VecSet;
 
-        let fixtures = fixtures_dir("");
+        let fixtures = glue.fixtures_dir("");
         let entries = std::fs::read_dir(fixtures.as_path()).unwrap_or_else(|err| {
             panic!(
                 "Error trying to read {} as a fixtures directory: {}",
@@ -200,6 +350,7 @@ mod glue_cli_run {
 
     fn generate_glue_for<'a, I: IntoIterator<Item = &'a str>>(
         platform_dir: &'a Path,
+        glue: Glue,
         args: I,
     ) -> Out {
         let platform_module_path = platform_dir.join("platform.roc");
@@ -211,8 +362,9 @@ mod glue_cli_run {
             .unwrap()
             .join("fixture-templates");
 
-        // Copy the rust template from the templates directory into the fixture dir.
-        dircpy::CopyBuilder::new(fixture_templates_dir.join("rust"), platform_dir)
+        // Copy the template from the templates directory into the fixture dir.
+        let template_dir = fixture_templates_dir.join(glue.lang());
+        dircpy::CopyBuilder::new(template_dir, platform_dir)
             .overwrite(true) // overwrite any files that were already present
             .run()
             .unwrap();
@@ -223,20 +375,20 @@ mod glue_cli_run {
                 .expect("Unable to remove test_glue dir in order to regenerate it in the test");
         }
 
-        let rust_glue_spec = fixture_templates_dir
+        let glue_spec = fixture_templates_dir
             .parent()
             .unwrap()
             .parent()
             .unwrap()
             .join("src")
-            .join("RustGlue.roc");
+            .join(glue.spec_file_name());
 
         // Generate a fresh test_glue for this platform
         let glue_out = run_glue(
             // converting these all to String avoids lifetime issues
             std::iter::once("glue".to_string()).chain(
                 args.into_iter().map(|arg| arg.to_string()).chain([
-                    rust_glue_spec.to_str().unwrap().to_string(),
+                    glue_spec.to_str().unwrap().to_string(),
                     glue_dir.to_str().unwrap().to_string(),
                     platform_module_path.to_str().unwrap().to_string(),
                 ]),
@@ -252,9 +404,145 @@ mod glue_cli_run {
 
         assert!(glue_out.status.success(), "bad status {glue_out:?}");
 
+        match glue {
+            Glue::Zig => zig_build(platform_dir),
+            Glue::Rust => check_layouts(platform_dir),
+            Glue::C => check_c_layouts(platform_dir),
+        }
+
         glue_out
     }
 
+    /// The architectures `roc glue` generates Rust types for, with a Linux target for each.
+    const LAYOUT_TARGETS: [(&str, &str); 4] = [
+        ("x86", "i686-unknown-linux-gnu"),
+        ("x86_64", "x86_64-unknown-linux-gnu"),
+        ("aarch64", "aarch64-unknown-linux-gnu"),
+        ("wasm32", "wasm32-unknown-unknown"),
+    ];
+
+    /// The generated Rust glue asserts at compile time that each type has the size, alignment
+    /// and field offsets which the compiler's layouts give it on that type's architecture.
+    /// Check the glue for every architecture rather than only the one the tests run on,
+    /// so that a layout mismatch on any of them fails the fixture's test. The targets are
+    /// installed through rust-toolchain.toml.
+    fn check_layouts(platform_dir: &Path) {
+        // The layouts `roc glue` gets from the compiler depend on the operating system,
+        // and these targets are for Linux.
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+
+        let lib_rs = platform_dir.join("test_glue/roc_app/src/lib.rs");
+        let lib_rs = fs::read_to_string(&lib_rs)
+            .unwrap_or_else(|err| panic!("Unable to read {}: {}", lib_rs.display(), err));
+        let installed_targets = installed_rust_targets();
+
+        for (arch, target) in LAYOUT_TARGETS {
+            assert!(
+                lib_rs.contains(&format!("mod {arch};")),
+                "The glue generated in {} has no types for {arch}",
+                platform_dir.display()
+            );
+
+            assert!(
+                installed_targets
+                    .iter()
+                    .any(|installed| installed == target),
+                "The Rust target {target} isn't installed, so the glue's layouts can't be checked for {arch}. It is listed in rust-toolchain.toml; install it with `rustup target add {target}`."
+            );
+
+            let out = roc_command_utils::cargo()
+                .args(["check", "--package", "roc_app", "--target", target])
+                .current_dir(platform_dir)
+                .output()
+                .expect("Unable to run `cargo check`");
+
+            assert!(
+                out.status.success(),
+                "The glue generated in {} does not match the compiler's layouts on {target}:\n\n{}",
+                platform_dir.display(),
+                String::from_utf8_lossy(&out.stderr)
+            );
+        }
+    }
+
+    /// The targets which the current toolchain has a standard library for. This also works for
+    /// toolchains which weren't installed by rustup, like the one in our nix flake.
+    fn installed_rust_targets() -> Vec<String> {
+        let out = std::process::Command::new("rustc")
+            .args(["--print", "sysroot"])
+            .output()
+            .expect("Unable to run `rustc --print sysroot`");
+        let sysroot = String::from_utf8_lossy(&out.stdout);
+        let rustlib = Path::new(sysroot.trim()).join("lib/rustlib");
+
+        match fs::read_dir(&rustlib) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().join("lib").is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect(),
+            Err(err) => panic!("Unable to read {}: {}", rustlib.display(), err),
+        }
+    }
+
+    /// The architectures `roc glue` generates C types for, with a target for each which the
+    /// C compiler bundled with Zig can compile for without any system headers.
+    const C_LAYOUT_TARGETS: [(&str, &str); 4] = [
+        ("x86", "x86-linux-musl"),
+        ("x86_64", "x86_64-linux-musl"),
+        ("aarch64", "aarch64-linux-musl"),
+        ("wasm32", "wasm32-wasi-musl"),
+    ];
+
+    /// The generated C header has a `_Static_assert` for the size and alignment which the
+    /// compiler's layouts give each type on that type's architecture. Compile it for every
+    /// architecture rather than only the one the host is built for, so that a layout mismatch
+    /// on any of them fails the fixture's test.
+    fn check_c_layouts(platform_dir: &Path) {
+        // The layouts `roc glue` gets from the compiler depend on the operating system,
+        // and these targets are for Linux.
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+
+        let header = platform_dir.join("test_glue/roc_app.h");
+
+        for (arch, target) in C_LAYOUT_TARGETS {
+            let out = roc_command_utils::zig()
+                .args(["cc", "-fsyntax-only", "-x", "c", "-target", target])
+                .arg(&header)
+                .current_dir(platform_dir)
+                .output()
+                .expect("Unable to run `zig cc`");
+
+            assert!(
+                out.status.success(),
+                "The glue generated in {} does not match the compiler's layouts on {arch}:\n\n{}",
+                platform_dir.display(),
+                String::from_utf8_lossy(&out.stderr)
+            );
+        }
+    }
+
+    /// Compile the fixture's host and its freshly generated glue with the Zig compiler,
+    /// so that errors in the generated Zig are reported directly.
+    fn zig_build(platform_dir: &Path) {
+        let out = roc_command_utils::zig()
+            .arg("build")
+            .current_dir(platform_dir)
+            .output()
+            .expect("Unable to run `zig build`");
+
+        assert!(
+            out.status.success(),
+            "`zig build` failed in {}:\n\n{}",
+            platform_dir.display(),
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+
     fn run_app<'a, 'b, I: IntoIterator<Item = &'a str>>(app_file: &'b Path, args: I) -> Out {
         // Generate test_glue for this platform
         let compile_out = run_roc(
@@ -0,0 +1,12 @@
+const std = @import("std");
+const roc_app = @import("../test_glue/main.zig");
+
+pub fn run(stdout: anytype) !void {
+    const record = roc_app.mainForHost();
+
+    // The U128 field comes first, because it has the larger alignment.
+    try std.testing.expectEqual(@as(usize, 0), @offsetOf(roc_app.MyRcd, "b"));
+    try std.testing.expectEqual(@as(usize, 16), @offsetOf(roc_app.MyRcd, "a"));
+
+    try stdout.print("Record was: {{ a: {d}, b: {d} }}\n", .{ record.a, record.b });
+}
@@ -0,0 +1,46 @@
+const std = @import("std");
+const roc_app = @import("../test_glue/main.zig");
+const Expr = roc_app.Expr;
+const RocStr = roc_app.roc_std.RocStr;
+
+/// Print the expression as it would be written in Roc, with parentheses
+/// around it if it's the payload of another tag.
+fn printExpr(stdout: anytype, expr: Expr, nested: bool) @TypeOf(stdout).Error!void {
+    if (nested) try stdout.writeAll("(");
+
+    switch (expr.discriminant()) {
+        .String => try stdout.print("String \"{s}\"", .{expr.getString().f0.asSlice()}),
+        .Concat => {
+            const payload = expr.getConcat();
+
+            try stdout.writeAll("Concat ");
+            try printExpr(stdout, payload.f0, true);
+            try stdout.writeAll(" ");
+            try printExpr(stdout, payload.f1, true);
+        },
+    }
+
+    if (nested) try stdout.writeAll(")");
+}
+
+pub fn run(stdout: anytype) !void {
+    const tag_union = roc_app.mainForHost();
+    defer tag_union.decref();
+
+    const examples = [_]Expr{
+        Expr.Concat(Expr.String(RocStr.fromSlice("Hello, ")), Expr.String(RocStr.fromSlice("World!"))),
+        Expr.String(RocStr.fromSlice("this is a test")),
+    };
+
+    try stdout.writeAll("tag_union was: ");
+    try printExpr(stdout, tag_union, false);
+    try stdout.writeAll("\n");
+
+    for (examples) |example| {
+        try stdout.writeAll("Constructed: ");
+        try printExpr(stdout, example, false);
+        try stdout.writeAll("\n");
+
+        example.decref();
+    }
+}
@@ -0,0 +1,8 @@
+const roc_app = @import("../test_glue/main.zig");
+
+pub fn run(stdout: anytype) !void {
+    const closure = roc_app.mainForHost(42);
+    defer closure.deinit();
+
+    try stdout.print("Answer was: {d}\n", .{closure.call()});
+}
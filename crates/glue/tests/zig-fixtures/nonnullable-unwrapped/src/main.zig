@@ -0,0 +1,35 @@
+const std = @import("std");
+const roc_app = @import("../test_glue/main.zig");
+const StrRoseTree = roc_app.StrRoseTree;
+const RocList = roc_app.roc_std.RocList;
+const RocStr = roc_app.roc_std.RocStr;
+
+/// Print the tree as it would be written in Roc.
+fn printTree(stdout: anytype, tree: StrRoseTree) @TypeOf(stdout).Error!void {
+    const payload = tree.getTree();
+
+    try stdout.print("Tree \"{s}\" [", .{payload.f0.asSlice()});
+    for (payload.f1.asSlice(), 0..) |child, index| {
+        if (index > 0) {
+            try stdout.writeAll(", ");
+        }
+        try printTree(stdout, child);
+    }
+    try stdout.writeAll("]");
+}
+
+pub fn run(stdout: anytype) !void {
+    const tag_union = roc_app.mainForHost();
+    defer tag_union.decref();
+
+    try std.testing.expectEqual(@as(usize, 2), tag_union.getTree().f1.len());
+
+    const tree = StrRoseTree.Tree(RocStr.fromSlice("foo"), RocList(StrRoseTree).empty());
+    defer tree.decref();
+
+    try stdout.writeAll("tag_union was: ");
+    try printTree(stdout, tag_union);
+    try stdout.writeAll("\nConstructed: ");
+    try printTree(stdout, tree);
+    try stdout.writeAll("\n");
+}
@@ -0,0 +1,45 @@
+const std = @import("std");
+const roc_app = @import("../test_glue/main.zig");
+const StrConsList = roc_app.StrConsList;
+const RocStr = roc_app.roc_std.RocStr;
+
+/// Print the list as it would be written in Roc, with parentheses
+/// around it if it's the payload of another tag.
+fn printList(stdout: anytype, list: StrConsList, nested: bool) @TypeOf(stdout).Error!void {
+    switch (list.discriminant()) {
+        .Nil => try stdout.writeAll("Nil"),
+        .Cons => {
+            const payload = list.getCons();
+
+            if (nested) try stdout.writeAll("(");
+            try stdout.print("Cons \"{s}\" ", .{payload.f0.asSlice()});
+            try printList(stdout, payload.f1, true);
+            if (nested) try stdout.writeAll(")");
+        },
+    }
+}
+
+pub fn run(stdout: anytype) !void {
+    const tag_union = roc_app.mainForHost();
+    defer tag_union.decref();
+
+    const examples = [_]StrConsList{
+        StrConsList.Cons(RocStr.fromSlice("small str"), StrConsList.Nil()),
+        StrConsList.Nil(),
+    };
+
+    try std.testing.expect(examples[0].isCons());
+    try std.testing.expect(examples[1].isNil());
+
+    try stdout.writeAll("tag_union was: ");
+    try printList(stdout, tag_union, false);
+    try stdout.writeAll("\n");
+
+    for (examples) |example| {
+        try stdout.writeAll("Constructed: ");
+        try printList(stdout, example, false);
+        try stdout.writeAll("\n");
+
+        example.decref();
+    }
+}
@@ -0,0 +1,51 @@
+const std = @import("std");
+const roc_app = @import("../test_glue/main.zig");
+const StrFingerTree = roc_app.StrFingerTree;
+const RocStr = roc_app.roc_std.RocStr;
+
+/// Print the tree as it would be written in Roc, with parentheses
+/// around it if it's the payload of another tag.
+fn printTree(stdout: anytype, tree: StrFingerTree, nested: bool) @TypeOf(stdout).Error!void {
+    switch (tree.discriminant()) {
+        .Empty => try stdout.writeAll("Empty"),
+        .Single => {
+            if (nested) try stdout.writeAll("(");
+            try stdout.print("Single \"{s}\"", .{tree.getSingle().f0.asSlice()});
+            if (nested) try stdout.writeAll(")");
+        },
+        .More => {
+            const payload = tree.getMore();
+
+            if (nested) try stdout.writeAll("(");
+            try stdout.print("More \"{s}\" ", .{payload.f0.asSlice()});
+            try printTree(stdout, payload.f1, true);
+            if (nested) try stdout.writeAll(")");
+        },
+    }
+}
+
+pub fn run(stdout: anytype) !void {
+    const tag_union = roc_app.mainForHost();
+    defer tag_union.decref();
+
+    try std.testing.expect(StrFingerTree.Empty().isEmpty());
+
+    const examples = [_]StrFingerTree{
+        StrFingerTree.More(RocStr.fromSlice("small str"), StrFingerTree.Single(RocStr.fromSlice("other str"))),
+        StrFingerTree.More(RocStr.fromSlice("small str"), StrFingerTree.Empty()),
+        StrFingerTree.Single(RocStr.fromSlice("small str")),
+        StrFingerTree.Empty(),
+    };
+
+    try stdout.writeAll("tag_union was: ");
+    try printTree(stdout, tag_union, false);
+    try stdout.writeAll("\n");
+
+    for (examples) |example| {
+        try stdout.writeAll("Constructed: ");
+        try printTree(stdout, example, false);
+        try stdout.writeAll("\n");
+
+        example.decref();
+    }
+}
@@ -0,0 +1,16 @@
+const std = @import("std");
+const roc_app = @import("../test_glue/main.zig");
+
+pub fn run(stdout: anytype) !void {
+    const string = roc_app.mainForHost(true);
+    defer string.decref();
+
+    try std.testing.expect(string.isOk());
+    try stdout.print("Answer was: Ok \"{s}\"\n", .{string.getOk().asSlice()});
+
+    const integer = roc_app.mainForHost(false);
+    defer integer.decref();
+
+    try std.testing.expect(integer.isErr());
+    try stdout.print("Answer was: Err {d}\n", .{integer.getErr()});
+}
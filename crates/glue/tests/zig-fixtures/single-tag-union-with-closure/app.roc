@@ -0,0 +1,8 @@
+app "app"
+    packages { pf: "platform.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str -> [Greeter Str (Str -> Str)]
+main = \punctuation ->
+    Greeter "Roc" \greeting -> "$(greeting), Roc$(punctuation)"
@@ -0,0 +1,11 @@
+platform "test-platform"
+    requires {} { main : Str -> [Greeter Str (Str -> Str)] }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+Greeter : [Greeter Str (Str -> Str)]
+
+mainForHost : Str -> Greeter
+mainForHost = \punctuation -> main punctuation
@@ -0,0 +1,13 @@
+const roc_app = @import("../test_glue/main.zig");
+const RocStr = roc_app.roc_std.RocStr;
+
+pub fn run(stdout: anytype) !void {
+    const greeter = roc_app.mainForHost(RocStr.fromSlice("!"));
+    defer greeter.decref();
+    defer greeter.f1.deinit();
+
+    const answer = greeter.f1.call(RocStr.fromSlice("Hello"));
+    defer answer.decref();
+
+    try stdout.print("Greeter \"{s}\" answered: \"{s}\"\n", .{ greeter.f0.asSlice(), answer.asSlice() });
+}
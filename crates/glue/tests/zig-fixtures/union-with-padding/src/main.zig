@@ -0,0 +1,41 @@
+const std = @import("std");
+const roc_app = @import("../test_glue/main.zig");
+const NonRecursive = roc_app.NonRecursive;
+const RocStr = roc_app.roc_std.RocStr;
+
+/// Print the tag union as it would be written in Roc.
+fn printTagUnion(stdout: anytype, tag_union: NonRecursive) !void {
+    switch (tag_union.discriminant()) {
+        .Foo => try stdout.print("Foo \"{s}\"", .{tag_union.getFoo().asSlice()}),
+        .Bar => try stdout.print("Bar {d}", .{tag_union.getBar()}),
+        .Blah => try stdout.print("Blah {d}", .{tag_union.getBlah()}),
+        .Baz => try stdout.writeAll("Baz"),
+    }
+}
+
+pub fn run(stdout: anytype) !void {
+    const tag_union = roc_app.mainForHost();
+    defer tag_union.decref();
+
+    try std.testing.expect(tag_union.isFoo());
+
+    const examples = [_]NonRecursive{
+        NonRecursive.Foo(RocStr.fromSlice("small str")),
+        NonRecursive.Foo(RocStr.fromSlice("A long enough string to not be small")),
+        NonRecursive.Bar(123),
+        NonRecursive.Baz(),
+        NonRecursive.Blah(456),
+    };
+
+    try stdout.writeAll("tag_union was: ");
+    try printTagUnion(stdout, tag_union);
+    try stdout.writeAll("\n");
+
+    for (examples) |example| {
+        try stdout.writeAll("Constructed: ");
+        try printTagUnion(stdout, example);
+        try stdout.writeAll("\n");
+
+        example.decref();
+    }
+}
@@ -20,16 +20,27 @@ use roc_types::subs::{Subs, Variable};
 use tower_lsp::lsp_types::{Diagnostic, SemanticTokenType, Url};
 
 mod analysed_doc;
+mod code_actions;
 mod completion;
+mod inlay_hints;
 mod parse_ast;
+mod references;
 mod semantic_tokens;
+mod signature_help;
+mod symbols;
 mod tokens;
 mod utils;
 
 use crate::convert::diag::{IntoLspDiagnostic, ProblemFmt};
 
 pub(crate) use self::analysed_doc::{AnalyzedDocument, DocInfo};
-use self::{analysed_doc::ModuleIdToUrl, tokens::Token};
+pub(crate) use self::references::ReferenceTarget;
+use self::{
+    analysed_doc::ModuleIdToUrl,
+    code_actions::{QuickFix, QuickFixes},
+    parse_ast::Ast,
+    tokens::Token,
+};
 
 pub const HIGHLIGHT_TOKENS_LEGEND: &[SemanticTokenType] = Token::LEGEND;
 
@@ -98,6 +109,7 @@ pub(super) struct AnalyzedModule {
 pub struct AnalysisResult {
     module: Option<AnalyzedModule>,
     diagnostics: Vec<Diagnostic>,
+    quick_fixes: Vec<QuickFix>,
 }
 
 pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
@@ -131,6 +143,7 @@ pub(crate) fn global_analysis(doc_info: DocInfo) -> Vec<AnalyzedDocument> {
                 analysis_result: AnalysisResult {
                     module: None,
                     diagnostics: all_problems,
+                    quick_fixes: vec![],
                 },
             };
 
@@ -314,7 +327,13 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
         };
 
         let line_info = LineInfo::new(&source);
-        let diagnostics = self.build_diagnostics(&path, &source, &line_info, module_id);
+        let (diagnostics, quick_fixes) = self.build_diagnostics(
+            &path,
+            &source,
+            &line_info,
+            module_id,
+            &analyzed_module.declarations,
+        );
 
         AnalyzedDocument {
             doc_info: DocInfo {
@@ -326,6 +345,7 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
             analysis_result: AnalysisResult {
                 module: Some(analyzed_module),
                 diagnostics,
+                quick_fixes,
             },
         }
     }
@@ -356,34 +376,66 @@ impl<'a> AnalyzedDocumentBuilder<'a> {
         source: &str,
         line_info: &LineInfo,
         module_id: ModuleId,
-    ) -> Vec<Diagnostic> {
+        declarations: &Declarations,
+    ) -> (Vec<Diagnostic>, Vec<QuickFix>) {
         let lines: Vec<_> = source.lines().collect();
 
         let alloc = RocDocAllocator::new(&lines, module_id, self.interns);
 
         let mut all_problems = Vec::new();
+        let mut quick_fixes = Vec::new();
         let fmt = ProblemFmt {
             alloc: &alloc,
+            source,
             line_info,
             path: source_path,
         };
 
+        let arena = Bump::new();
+        let ast = Ast::parse(&arena, source).ok();
+        let fixes = QuickFixes {
+            source,
+            ast: ast.as_ref(),
+            declarations,
+            alloc: &alloc,
+        };
+
+        let syntax_errors = ast
+            .iter()
+            .flat_map(|ast| ast.syntax_errors().iter().cloned());
+
+        for syntax_error in syntax_errors {
+            if let Some(diag) = syntax_error.into_lsp_diagnostic(&fmt) {
+                all_problems.push(diag);
+            }
+        }
+
         let can_problems = self.can_problems.remove(&module_id).unwrap_or_default();
 
         let type_problems = self.type_problems.remove(&module_id).unwrap_or_default();
 
         for can_problem in can_problems {
+            let fix = fixes.for_can_problem(&can_problem);
+
             if let Some(diag) = can_problem.into_lsp_diagnostic(&fmt) {
+                if let Some(fix) = fix {
+                    quick_fixes.push(fix.into_quick_fix(diag.clone(), line_info));
+                }
                 all_problems.push(diag);
             }
         }
 
         for type_problem in type_problems {
+            let fix = fixes.for_type_problem(&type_problem);
+
             if let Some(diag) = type_problem.into_lsp_diagnostic(&fmt) {
+                if let Some(fix) = fix {
+                    quick_fixes.push(fix.into_quick_fix(diag.clone(), line_info));
+                }
                 all_problems.push(diag);
             }
         }
 
-        all_problems
+        (all_problems, quick_fixes)
     }
 }
@@ -3,13 +3,19 @@ use std::collections::HashMap;
 
 use bumpalo::Bump;
 
-use roc_module::symbol::{ModuleId, Symbol};
+use roc_can::traverse::{find_record_field_references, find_symbol_references, FieldLabelAt};
+use roc_module::{
+    ident::{Lowercase, ModuleName},
+    symbol::{ModuleId, Symbol},
+};
 
-use roc_region::all::LineInfo;
+use roc_region::all::{LineInfo, Region};
 
 use tower_lsp::lsp_types::{
-    CompletionItem, Diagnostic, GotoDefinitionResponse, Hover, HoverContents, LanguageString,
-    Location, MarkedString, Position, Range, SemanticTokens, SemanticTokensResult, TextEdit, Url,
+    CodeActionOrCommand, CompletionItem, Diagnostic, DocumentSymbolResponse,
+    GotoDefinitionResponse, Hover, HoverContents, InlayHint, LanguageString, Location,
+    MarkedString, Position, Range, SemanticTokens, SemanticTokensResult, SignatureHelp,
+    SymbolInformation, TextEdit, Url,
 };
 
 use crate::{
@@ -18,8 +24,16 @@ use crate::{
 };
 
 use super::{
+    code_actions::insert_annotation,
+    inlay_hints::inlay_hints,
     parse_ast::Ast,
+    references::{
+        field_references, lowercase_identifier_at, record_labels, symbol_references, ModuleNames,
+        Reference, ReferenceTarget,
+    },
     semantic_tokens::arrange_semantic_tokens,
+    signature_help::signature_help,
+    symbols::{document_symbols, workspace_symbols},
     utils::{format_var_type, is_roc_identifier_char},
     AnalysisResult, AnalyzedModule,
 };
@@ -89,6 +103,12 @@ impl DocInfo {
         let arena = &Bump::new();
 
         let ast = Ast::parse(arena, source).ok()?;
+
+        // Defs with syntax errors can't be formatted
+        if !ast.syntax_errors().is_empty() {
+            return None;
+        }
+
         let fmt = ast.fmt();
 
         if source == fmt.as_str() {
@@ -114,6 +134,32 @@ impl DocInfo {
             data,
         }))
     }
+
+    pub fn document_symbols(&self) -> Option<DocumentSymbolResponse> {
+        let source = &self.source;
+        let arena = &Bump::new();
+
+        let ast = Ast::parse(arena, source).ok()?;
+        let symbols = document_symbols(&ast, source, &self.line_info);
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    /// The symbols in this document matching `query`, contained in the module named after the file.
+    pub fn workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let Some(DocumentSymbolResponse::Nested(symbols)) = self.document_symbols() else {
+            return vec![];
+        };
+
+        let module_name = self
+            .url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|file_name| file_name.strip_suffix(".roc"))
+            .unwrap_or_default();
+
+        workspace_symbols(&self.url, module_name, symbols, query)
+    }
 }
 
 impl AnalyzedDocument {
@@ -204,6 +250,86 @@ impl AnalyzedDocument {
         })
     }
 
+    pub fn inlay_hints(&self, range: Range, max_length: usize) -> Option<Vec<InlayHint>> {
+        let line_info = self.line_info();
+
+        // Editors may ask for hints past the end of the document.
+        let end_of_document = roc_region::all::Position::new(self.doc_info.source.len() as u32);
+        let to_roc_position = |position: Position| {
+            if position.line < line_info.num_lines() {
+                position.to_roc_position(line_info).min(end_of_document)
+            } else {
+                end_of_document
+            }
+        };
+
+        let region = Region::new(to_roc_position(range.start), to_roc_position(range.end));
+
+        let AnalyzedModule {
+            subs,
+            declarations,
+            module_id,
+            interns,
+            ..
+        } = self.module()?;
+
+        Some(inlay_hints(
+            region,
+            declarations,
+            subs,
+            module_id,
+            interns,
+            line_info,
+            max_length,
+        ))
+    }
+
+    pub fn signature_help(&self, position: Position) -> Option<SignatureHelp> {
+        let line_info = self.line_info();
+
+        if position.line >= line_info.num_lines() {
+            return None;
+        }
+
+        signature_help(
+            position.to_roc_position(line_info),
+            &self.doc_info.source,
+            self.module()?,
+        )
+    }
+
+    /// The quick-fixes for the problems within `range`, and the refactorings available at its
+    /// start.
+    pub fn code_actions(&self, range: Range) -> Vec<CodeActionOrCommand> {
+        let url = self.url();
+        let line_info = self.line_info();
+
+        let quick_fixes = self
+            .analysis_result
+            .quick_fixes
+            .iter()
+            .filter(|fix| fix.applies_to(range))
+            .map(|fix| fix.to_code_action(url));
+
+        let insert_annotation = self
+            .module()
+            .filter(|_| range.start.line < line_info.num_lines())
+            .and_then(|module| {
+                insert_annotation(
+                    url,
+                    range.start.to_roc_position(line_info),
+                    &self.doc_info.source,
+                    line_info,
+                    module,
+                )
+            });
+
+        quick_fixes
+            .chain(insert_annotation)
+            .map(CodeActionOrCommand::CodeAction)
+            .collect()
+    }
+
     pub fn definition(&self, symbol: Symbol) -> Option<GotoDefinitionResponse> {
         let AnalyzedModule { declarations, .. } = self.module()?;
 
@@ -218,6 +344,272 @@ impl AnalyzedDocument {
         self.module()?.module_id_to_url.get(&module_id).cloned()
     }
 
+    fn module_id_by_name(&self, module_name: &str) -> Option<ModuleId> {
+        let module_name = ModuleName::from(module_name);
+
+        self.module()?.interns.module_ids.get_id(&module_name)
+    }
+
+    /// Finds the symbol of a value exposed by another module, by name.
+    fn exposed_symbol(&self, module_name: &str, name: &str) -> Option<Symbol> {
+        let AnalyzedModule {
+            interns,
+            modules_info,
+            ..
+        } = self.module()?;
+
+        let module_id = self.module_id_by_name(module_name)?;
+
+        modules_info
+            .exposed_by_module
+            .get(&module_id)?
+            .iter()
+            .map(|(symbol, _)| *symbol)
+            .find(|symbol| symbol.as_str(interns) == name)
+    }
+
+    fn value_target(&self, symbol: Symbol) -> Option<ReferenceTarget> {
+        let AnalyzedModule {
+            interns,
+            modules_info,
+            ..
+        } = self.module()?;
+
+        let module_id = symbol.module_id();
+        let exposed = modules_info
+            .exposed_by_module
+            .get(&module_id)
+            .map_or(false, |exposed| exposed.iter().any(|(s, _)| *s == symbol));
+
+        Some(ReferenceTarget::Value {
+            module_url: self.module_url(module_id)?,
+            module_name: interns.module_name(module_id).as_str().to_string(),
+            name: symbol.as_str(interns).to_string(),
+            symbol,
+            origin_url: self.url().clone(),
+            exposed,
+        })
+    }
+
+    /// Finds the value, type or record field at `position` that can be referenced from elsewhere.
+    pub(crate) fn reference_target(&self, position: Position) -> Option<ReferenceTarget> {
+        let AnalyzedModule {
+            module_id,
+            interns,
+            subs,
+            declarations,
+            ..
+        } = self.module()?;
+
+        let source = self.doc_info.source.as_str();
+        let roc_position = position.to_roc_position(self.line_info());
+        let own_module_name = interns.module_name(*module_id).as_str();
+
+        let arena = Bump::new();
+        let names = Ast::parse(&arena, source)
+            .ok()
+            .map(|ast| ModuleNames::from_ast(&ast, source));
+
+        if let Some(names) = &names {
+            if let Some((module_name, name)) = names.type_at(roc_position, own_module_name) {
+                let module_id = self.module_id_by_name(module_name)?;
+
+                return Some(ReferenceTarget::Type {
+                    module_url: self.module_url(module_id)?,
+                    module_name: module_name.to_string(),
+                    name: name.to_string(),
+                });
+            }
+
+            if let Some((module_name, name)) = names.exposed_value_at(roc_position, own_module_name)
+            {
+                return self.value_target(self.exposed_symbol(module_name, name)?);
+            }
+        }
+
+        let field_target = lowercase_identifier_at(source, roc_position).and_then(|label_name| {
+            let label = Lowercase::from(label_name.value);
+
+            let field_at_position = find_record_field_references(&label, declarations)
+                .into_iter()
+                .find(|reference| {
+                    field_references(
+                        source,
+                        label_name.value,
+                        [(reference.region, reference.label_at)],
+                    )
+                    .iter()
+                    .any(|found| found.region.contains_pos(roc_position))
+                });
+
+            // In a pun like `{ foo }`, we rename the variable rather than the field.
+            if let Some(reference) = &field_at_position {
+                if matches!(
+                    reference.label_at,
+                    FieldLabelAt::Pun | FieldLabelAt::OptionalPun
+                ) {
+                    return None;
+                }
+            }
+
+            let record_labels = field_at_position
+                .and_then(|reference| record_labels(subs, reference.record_var))
+                .or_else(|| {
+                    let (labels, _) =
+                        names
+                            .as_ref()?
+                            .record_annotations
+                            .iter()
+                            .find(|(_, fields)| {
+                                fields.iter().any(|field| {
+                                    field.value == label_name.value
+                                        && field.region.contains_pos(roc_position)
+                                })
+                            })?;
+
+                    Some(labels.iter().copied().map(Lowercase::from).collect())
+                })?;
+
+            Some(ReferenceTarget::Field {
+                label,
+                record_labels,
+            })
+        });
+
+        field_target.or_else(|| self.value_target(self.symbol_at(position)?))
+    }
+
+    /// Finds every reference to `target` in this document.
+    pub(crate) fn references(&self, target: &ReferenceTarget) -> Vec<Reference> {
+        let Some(AnalyzedModule {
+            subs, declarations, ..
+        }) = self.module()
+        else {
+            return vec![];
+        };
+
+        let source = self.doc_info.source.as_str();
+        let arena = Bump::new();
+        let names = Ast::parse(&arena, source)
+            .ok()
+            .map(|ast| ModuleNames::from_ast(&ast, source));
+
+        let mut references = match target {
+            ReferenceTarget::Value {
+                module_url,
+                module_name,
+                name,
+                symbol,
+                origin_url,
+                exposed,
+            } => {
+                let is_self = self.url() == module_url;
+
+                // Symbols from another analysis can't be compared with ours.
+                let symbol = if self.url() == origin_url {
+                    Some(*symbol)
+                } else if *exposed {
+                    self.exposed_symbol(module_name, name)
+                } else {
+                    None
+                };
+
+                let mut references = symbol
+                    .map(|symbol| {
+                        symbol_references(
+                            source,
+                            name,
+                            find_symbol_references(symbol, declarations),
+                        )
+                    })
+                    .unwrap_or_default();
+
+                if let Some(names) = &names {
+                    references.extend(names.value_references(module_name, name, is_self));
+                }
+
+                references
+            }
+            ReferenceTarget::Type {
+                module_url,
+                module_name,
+                name,
+            } => match &names {
+                Some(names) => names
+                    .type_references(module_name, name, self.url() == module_url)
+                    .collect(),
+                None => vec![],
+            },
+            ReferenceTarget::Field {
+                label,
+                record_labels: labels,
+            } => {
+                let occurrences = find_record_field_references(label, declarations)
+                    .into_iter()
+                    .filter(|reference| {
+                        record_labels(subs, reference.record_var).as_ref() == Some(labels)
+                    })
+                    .map(|reference| (reference.region, reference.label_at));
+
+                let mut references = field_references(source, label.as_str(), occurrences);
+
+                if let Some(names) = &names {
+                    references.extend(names.field_references(label.as_str(), labels));
+                }
+
+                references
+            }
+        };
+
+        references.sort_by_key(|reference| reference.region.start());
+        references.dedup_by_key(|reference| reference.region);
+        references
+    }
+
+    pub(crate) fn reference_locations(&self, target: &ReferenceTarget) -> Vec<Location> {
+        self.references(target)
+            .into_iter()
+            .map(|reference| self.location(reference.region.to_range(self.line_info())))
+            .collect()
+    }
+
+    /// The range of the name at `position` that renaming `target` would change.
+    pub(crate) fn rename_range(
+        &self,
+        target: &ReferenceTarget,
+        position: Position,
+    ) -> Result<Option<Range>, String> {
+        let roc_position = position.to_roc_position(self.line_info());
+
+        let Some(reference) = self
+            .references(target)
+            .into_iter()
+            .find(|reference| reference.region.contains_pos(roc_position))
+        else {
+            return Ok(None);
+        };
+
+        reference.new_text(target.name(), target.name())?;
+
+        Ok(Some(reference.region.to_range(self.line_info())))
+    }
+
+    pub(crate) fn rename_edits(
+        &self,
+        target: &ReferenceTarget,
+        new_name: &str,
+    ) -> Result<Vec<TextEdit>, String> {
+        self.references(target)
+            .into_iter()
+            .map(|reference| {
+                Ok(TextEdit {
+                    range: reference.region.to_range(self.line_info()),
+                    new_text: reference.new_text(target.name(), new_name)?,
+                })
+            })
+            .collect()
+    }
+
     pub fn completion_items(
         &self,
         position: Position,
@@ -0,0 +1,420 @@
+//! Code actions: quick-fixes for the problems the compiler reports, and inserting the inferred
+//! type of a definition as its annotation.
+
+use std::collections::HashMap;
+
+use roc_can::{
+    def::Def,
+    expr::{Declarations, Expr},
+    pattern::Pattern,
+    traverse::{walk_decl, walk_decls, walk_def, walk_expr, DeclarationInfo, Visitor},
+};
+use roc_collections::MutSet;
+use roc_exhaustive::Context;
+use roc_module::symbol::Symbol;
+use roc_parse::ast::{self, Defs, RecursiveValueDefIter, ValueDef};
+use roc_problem::can::{Problem, RuntimeError};
+use roc_region::all::{LineInfo, Position, Region};
+use roc_reporting::{error::r#type::missing_branch_pattern_to_doc, report::RocDocAllocator};
+use roc_solve_problem::TypeError;
+use roc_types::subs::Variable;
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, Diagnostic, Range, TextEdit, Url, WorkspaceEdit,
+};
+
+use crate::convert::ToRange;
+
+use super::{parse_ast::Ast, utils::format_var_type, AnalyzedModule};
+
+/// A fix for one of the problems reported for a document.
+#[derive(Debug, Clone)]
+pub(super) struct QuickFix {
+    title: String,
+    diagnostic: Diagnostic,
+    edits: Vec<TextEdit>,
+}
+
+impl QuickFix {
+    /// Whether the problem this fixes is within `range`.
+    pub(super) fn applies_to(&self, range: Range) -> bool {
+        let problem = self.diagnostic.range;
+
+        problem.start <= range.end && range.start <= problem.end
+    }
+
+    pub(super) fn to_code_action(&self, url: &Url) -> CodeAction {
+        CodeAction {
+            title: self.title.clone(),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![self.diagnostic.clone()]),
+            edit: Some(workspace_edit(url, self.edits.clone())),
+            command: None,
+            is_preferred: Some(true),
+            disabled: None,
+            data: None,
+        }
+    }
+}
+
+/// An edit to the source, before it is attached to the diagnostic it fixes.
+pub(super) struct Fix {
+    title: String,
+    edits: Vec<(Region, String)>,
+}
+
+impl Fix {
+    fn new(title: String, region: Region, new_text: String) -> Self {
+        Self {
+            title,
+            edits: vec![(region, new_text)],
+        }
+    }
+
+    pub(super) fn into_quick_fix(self, diagnostic: Diagnostic, line_info: &LineInfo) -> QuickFix {
+        QuickFix {
+            title: self.title,
+            diagnostic,
+            edits: to_text_edits(self.edits, line_info),
+        }
+    }
+}
+
+/// Works out fixes for the problems in one module.
+pub(super) struct QuickFixes<'a> {
+    pub(super) source: &'a str,
+    pub(super) ast: Option<&'a Ast<'a>>,
+    pub(super) declarations: &'a Declarations,
+    pub(super) alloc: &'a RocDocAllocator<'a>,
+}
+
+impl QuickFixes<'_> {
+    pub(super) fn for_can_problem(&self, problem: &Problem) -> Option<Fix> {
+        match problem {
+            Problem::UnusedDef(_, region) => {
+                let def_region = self.def_region(*region)?;
+
+                Some(Fix::new(
+                    format!("Remove unused `{}`", self.text(*region)),
+                    removal_region(self.source, def_region),
+                    String::new(),
+                ))
+            }
+            Problem::UnusedImport(_, region) => Some(Fix::new(
+                format!("Remove unused import `{}`", self.text(*region)),
+                removal_region(self.source, *region),
+                String::new(),
+            )),
+            Problem::UnusedModuleImport(_, region) => Some(Fix::new(
+                "Remove unused import".to_string(),
+                removal_region(self.source, *region),
+                String::new(),
+            )),
+            Problem::RuntimeError(RuntimeError::ModuleNotImported {
+                module_name,
+                module_exists: true,
+                ..
+            }) => {
+                let (position, new_text) = self.import_insertion(module_name.as_str())?;
+
+                Some(Fix::new(
+                    format!("Add `import {}`", module_name.as_str()),
+                    Region::new(position, position),
+                    new_text,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn for_type_problem(&self, problem: &TypeError) -> Option<Fix> {
+        match problem {
+            TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(
+                region,
+                Context::BadCase,
+                missing,
+            )) => {
+                let mut visitor = WhenAt {
+                    region: *region,
+                    first_pattern: None,
+                };
+                visitor.visit_decls(self.declarations);
+                let first_pattern = visitor.first_pattern?;
+
+                // The first branch may be on the same line as `when`, so only its
+                // line's leading whitespace is an indent.
+                let line_start = line_start(self.source, first_pattern.byte_offset());
+                let line = &self.source[line_start..first_pattern.byte_offset()];
+                let indent = &line[..line.len() - line.trim_start().len()];
+
+                let branches: String = missing
+                    .iter()
+                    .map(|pattern| {
+                        let doc = missing_branch_pattern_to_doc(self.alloc, pattern.clone());
+
+                        format!("\n{}{} -> crash \"todo\"", indent, doc.1.pretty(200))
+                    })
+                    .collect();
+
+                Some(Fix::new(
+                    "Add missing `when` branches".to_string(),
+                    Region::new(region.end(), region.end()),
+                    branches,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn text(&self, region: Region) -> &str {
+        self.source
+            .get(region.start().byte_offset()..region.end().byte_offset())
+            .unwrap_or_default()
+    }
+
+    /// The region of the def that introduces `pattern_region`, if the whole def can be removed.
+    fn def_region(&self, pattern_region: Region) -> Option<Region> {
+        RecursiveValueDefIter::new(self.ast?.defs()).find_map(|(value_def, region)| {
+            let pattern = match value_def {
+                ValueDef::Body(pattern, _) => pattern,
+                ValueDef::AnnotatedBody { body_pattern, .. } => body_pattern,
+                _ => return None,
+            };
+
+            // Destructures may define other names that are still used.
+            (is_identifier(&pattern.value) && pattern.region.contains(&pattern_region))
+                .then_some(*region)
+        })
+    }
+
+    /// Where to put a new `import` statement: after the last one, or otherwise above the
+    /// first definition.
+    fn import_insertion(&self, module_name: &str) -> Option<(Position, String)> {
+        let defs: &Defs = self.ast?.defs();
+        let mut last_import = None;
+
+        for (def, region) in defs.defs().zip(defs.regions.iter()) {
+            match def {
+                // Imports in the header are desugared to import statements too, so we only
+                // consider those actually written as statements.
+                Err(ValueDef::ModuleImport(_) | ValueDef::IngestedFileImport(_)) => {
+                    if self.text(*region).starts_with("import") {
+                        last_import = Some(*region);
+                    }
+                }
+                _ => {
+                    return match last_import {
+                        Some(import) => Some((
+                            Position::new(line_end(self.source, import.end().byte_offset()) as u32),
+                            format!("import {module_name}\n"),
+                        )),
+                        None => Some((
+                            Position::new(
+                                line_start(self.source, region.start().byte_offset()) as u32
+                            ),
+                            format!("import {module_name}\n\n"),
+                        )),
+                    };
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn is_identifier(pattern: &ast::Pattern<'_>) -> bool {
+    match pattern {
+        ast::Pattern::Identifier { .. } => true,
+        ast::Pattern::SpaceBefore(pattern, _) | ast::Pattern::SpaceAfter(pattern, _) => {
+            is_identifier(pattern)
+        }
+        _ => false,
+    }
+}
+
+/// Finds the first pattern of the `when` whose condition and branches span `region`.
+struct WhenAt {
+    region: Region,
+    first_pattern: Option<Position>,
+}
+
+impl Visitor for WhenAt {
+    fn visit_expr(&mut self, expr: &Expr, _region: Region, var: Variable) {
+        if let Expr::When {
+            loc_cond, branches, ..
+        } = expr
+        {
+            if let (Some(first), Some(last)) = (branches.first(), branches.last()) {
+                if Region::span_across(&loc_cond.region, &last.value.region) == self.region {
+                    self.first_pattern = Some(first.pattern_region().start());
+                }
+            }
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
+
+/// Finds the unannotated def whose name is at `position`.
+struct UnannotatedDefAt {
+    position: Position,
+    annotated: MutSet<Symbol>,
+    found: Option<(Region, Variable)>,
+}
+
+impl Visitor for UnannotatedDefAt {
+    fn visit_decls(&mut self, decls: &Declarations) {
+        for (loc_symbol, annotation) in decls.symbols.iter().zip(decls.annotations.iter()) {
+            if annotation.is_some() {
+                self.annotated.insert(loc_symbol.value);
+            }
+        }
+
+        walk_decls(self, decls);
+    }
+
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        match decl {
+            DeclarationInfo::Value {
+                loc_symbol,
+                expr_var,
+                ..
+            }
+            | DeclarationInfo::Function {
+                loc_symbol,
+                expr_var,
+                ..
+            } if loc_symbol.region.contains_pos(self.position)
+                && !self.annotated.contains(&loc_symbol.value) =>
+            {
+                self.found = Some((loc_symbol.region, expr_var));
+            }
+            _ => {}
+        }
+
+        walk_decl(self, decl);
+    }
+
+    fn visit_def(&mut self, def: &Def) {
+        if let (Pattern::Identifier(_), None) = (&def.loc_pattern.value, &def.annotation) {
+            if def.loc_pattern.region.contains_pos(self.position) {
+                self.found = Some((def.loc_pattern.region, def.expr_var));
+            }
+        }
+
+        walk_def(self, def);
+    }
+}
+
+/// Adds the inferred type of the unannotated def at `position` as its annotation.
+pub(super) fn insert_annotation(
+    url: &Url,
+    position: Position,
+    source: &str,
+    line_info: &LineInfo,
+    module: &AnalyzedModule,
+) -> Option<CodeAction> {
+    let AnalyzedModule {
+        subs,
+        declarations,
+        module_id,
+        interns,
+        ..
+    } = module;
+
+    let mut visitor = UnannotatedDefAt {
+        position,
+        annotated: MutSet::default(),
+        found: None,
+    };
+    visitor.visit_decls(declarations);
+    let (region, var) = visitor.found?;
+
+    let type_str = format_var_type(var, &mut subs.clone(), module_id, interns);
+    // Types containing errors would not be valid annotations.
+    if type_str.contains('?') {
+        return None;
+    }
+    let type_str = type_str.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let name = source.get(region.start().byte_offset()..region.end().byte_offset())?;
+    let line_start = line_start(source, region.start().byte_offset());
+    let indent = &source[line_start..region.start().byte_offset()];
+
+    let line_start = Position::new(line_start as u32);
+    let edit = (
+        Region::new(line_start, line_start),
+        format!("{indent}{name} : {type_str}\n"),
+    );
+
+    Some(CodeAction {
+        title: format!("Add type annotation to `{name}`"),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        diagnostics: None,
+        edit: Some(workspace_edit(url, to_text_edits(vec![edit], line_info))),
+        command: None,
+        is_preferred: None,
+        disabled: None,
+        data: None,
+    })
+}
+
+fn to_text_edits(edits: Vec<(Region, String)>, line_info: &LineInfo) -> Vec<TextEdit> {
+    edits
+        .into_iter()
+        .map(|(region, new_text)| TextEdit {
+            range: region.to_range(line_info),
+            new_text,
+        })
+        .collect()
+}
+
+fn workspace_edit(url: &Url, edits: Vec<TextEdit>) -> WorkspaceEdit {
+    WorkspaceEdit::new(HashMap::from([(url.clone(), edits)]))
+}
+
+/// The offset of the start of the line that `offset` is on.
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1)
+}
+
+/// The offset just after the end of the line that `offset` is on, including its newline.
+fn line_end(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .find('\n')
+        .map_or(source.len(), |newline| offset + newline + 1)
+}
+
+/// What to delete to remove `region`: the lines it's on if there's nothing else on them, or
+/// otherwise the item itself along with the comma separating it from the next or previous item.
+fn removal_region(source: &str, region: Region) -> Region {
+    let start = region.start().byte_offset();
+    let end = region.end().byte_offset();
+    let between = |start: usize, end: usize| {
+        Region::new(Position::new(start as u32), Position::new(end as u32))
+    };
+
+    let line_start = line_start(source, start);
+    let line_end = line_end(source, end);
+    let after = source[end..line_end].trim();
+
+    if source[line_start..start].trim().is_empty() && (after.is_empty() || after == ",") {
+        return between(line_start, line_end);
+    }
+
+    let rest = source[end..].trim_start_matches([' ', '\t']);
+    if let Some(rest) = rest.strip_prefix(',') {
+        let rest = rest.trim_start_matches([' ', '\t']);
+
+        return between(start, source.len() - rest.len());
+    }
+
+    let preceding = source[..start].trim_end();
+    if preceding.ends_with(',') {
+        return between(preceding.len() - 1, end);
+    }
+
+    region
+}
@@ -17,7 +17,7 @@ use self::visitor::CompletionVisitor;
 
 use super::{utils::format_var_type, ModulesInfo};
 mod formatting;
-mod visitor;
+pub(super) mod visitor;
 
 fn get_completions(
     position: Position,
@@ -30,6 +30,7 @@ fn get_completions(
         found_declarations: Vec::new(),
         interns,
         prefix,
+        found_calls: Vec::new(),
     };
     visitor.visit_decls(decls);
     visitor.found_declarations
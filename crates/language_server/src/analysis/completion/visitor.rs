@@ -7,7 +7,10 @@ use roc_can::{
     traverse::{walk_decl, walk_def, walk_expr, DeclarationInfo, Visitor},
 };
 
-use roc_module::symbol::{Interns, Symbol};
+use roc_module::{
+    called_via::{BinOp, CalledVia},
+    symbol::{Interns, Symbol},
+};
 use roc_region::all::{Loc, Position, Region};
 use roc_types::subs::Variable;
 
@@ -16,6 +19,18 @@ pub(crate) struct CompletionVisitor<'a> {
     pub(crate) found_declarations: Vec<(Symbol, Variable)>,
     pub(crate) interns: &'a Interns,
     pub(crate) prefix: String,
+    /// The calls, and the functions that may be about to be called, around the position, from
+    /// the outermost to the innermost. These are used for signature help.
+    pub(crate) found_calls: Vec<FoundCall>,
+}
+
+/// A function applied to arguments, or a function on its own when `args` is [None].
+pub(crate) struct FoundCall {
+    pub(crate) region: Region,
+    pub(crate) function: Region,
+    pub(crate) fn_var: Variable,
+    pub(crate) symbol: Option<Symbol>,
+    pub(crate) args: Option<Vec<Region>>,
 }
 
 impl Visitor for CompletionVisitor<'_> {
@@ -28,6 +43,8 @@ impl Visitor for CompletionVisitor<'_> {
             let mut res = self.expression_defs(expr);
             self.found_declarations.append(&mut res);
 
+            self.found_calls.extend(found_call(expr, region, var));
+
             walk_expr(self, expr, var);
         }
     }
@@ -252,3 +269,34 @@ impl CompletionVisitor<'_> {
         }
     }
 }
+
+fn found_call(expr: &Expr, region: Region, var: Variable) -> Option<FoundCall> {
+    match expr {
+        Expr::Call(fun, args, CalledVia::Space | CalledVia::BinOp(BinOp::Pizza)) => {
+            let (fn_var, loc_fn, _, _) = &**fun;
+
+            Some(FoundCall {
+                region,
+                function: loc_fn.region,
+                fn_var: *fn_var,
+                symbol: called_symbol(&loc_fn.value),
+                args: Some(args.iter().map(|(_, arg)| arg.region).collect()),
+            })
+        }
+        Expr::Var(symbol, _) | Expr::AbilityMember(symbol, _, _) => Some(FoundCall {
+            region,
+            function: region,
+            fn_var: var,
+            symbol: Some(*symbol),
+            args: None,
+        }),
+        _ => None,
+    }
+}
+
+fn called_symbol(expr: &Expr) -> Option<Symbol> {
+    match expr {
+        Expr::Var(symbol, _) | Expr::AbilityMember(symbol, _, _) => Some(*symbol),
+        _ => None,
+    }
+}
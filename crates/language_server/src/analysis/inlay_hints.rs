@@ -0,0 +1,125 @@
+use roc_can::{
+    def::Def,
+    expr::Declarations,
+    pattern::{DestructType, Pattern, RecordDestruct},
+    traverse::{walk_decls, walk_def, walk_pattern, walk_record_destruct, Visitor},
+};
+use roc_collections::MutSet;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_region::all::{LineInfo, Position, Region};
+use roc_types::subs::{Subs, Variable};
+use tower_lsp::lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, InlayHintTooltip};
+
+use super::utils::format_var_type;
+
+/// Finds the names bound by unannotated defs, lambda arguments and patterns, along with the
+/// variable holding their type.
+struct BindingVisitor {
+    /// The symbols of annotated defs, which already show their type.
+    annotated: MutSet<Symbol>,
+    found: Vec<(Position, Variable)>,
+}
+
+impl BindingVisitor {
+    fn push(&mut self, symbol: Symbol, region: Region, var: Variable) {
+        if !self.annotated.contains(&symbol) {
+            self.found.push((region.end(), var));
+        }
+    }
+}
+
+impl Visitor for BindingVisitor {
+    fn visit_decls(&mut self, decls: &Declarations) {
+        for (loc_symbol, annotation) in decls.symbols.iter().zip(decls.annotations.iter()) {
+            if annotation.is_some() {
+                self.annotated.insert(loc_symbol.value);
+            }
+        }
+
+        walk_decls(self, decls);
+    }
+
+    fn visit_def(&mut self, def: &Def) {
+        if def.annotation.is_some() {
+            self.annotated.extend(def.pattern_vars.keys().copied());
+        }
+
+        walk_def(self, def);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, opt_var: Option<Variable>) {
+        match (pattern, opt_var) {
+            (Pattern::Identifier(symbol), Some(var))
+            | (Pattern::Shadowed(_, _, symbol), Some(var))
+            | (Pattern::As(_, symbol), Some(var)) => self.push(*symbol, region, var),
+            _ => {}
+        }
+
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+        if let DestructType::Required = destruct.typ {
+            self.push(destruct.symbol, region, destruct.var);
+        }
+
+        walk_record_destruct(self, destruct);
+    }
+}
+
+/// Type hints for the bindings in `decls` that don't have an annotation, within `range`.
+///
+/// Types longer than `max_length` characters are cut short, with the full type in the tooltip.
+pub(super) fn inlay_hints(
+    range: Region,
+    decls: &Declarations,
+    subs: &Subs,
+    module_id: &ModuleId,
+    interns: &Interns,
+    line_info: &LineInfo,
+    max_length: usize,
+) -> Vec<InlayHint> {
+    let mut visitor = BindingVisitor {
+        annotated: MutSet::default(),
+        found: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+    visitor.found.sort_by_key(|(position, _)| *position);
+
+    let mut subs = subs.clone();
+
+    visitor
+        .found
+        .into_iter()
+        .filter(|(position, _)| range.contains_pos(*position))
+        .map(|(position, var)| {
+            let type_str = format_var_type(var, &mut subs, module_id, interns);
+            // Hints are shown inline, so multiline types are collapsed onto one line.
+            let type_str = type_str.split_whitespace().collect::<Vec<_>>().join(" ");
+
+            let (label, tooltip) = if type_str.chars().count() > max_length {
+                let shortened: String = type_str.chars().take(max_length).collect();
+
+                (
+                    format!(": {}…", shortened.trim_end()),
+                    Some(InlayHintTooltip::String(type_str)),
+                )
+            } else {
+                (format!(": {}", type_str), None)
+            };
+
+            let lc = line_info.convert_pos(position);
+
+            InlayHint {
+                position: tower_lsp::lsp_types::Position::new(lc.line, lc.column),
+                label: InlayHintLabel::String(label),
+                kind: Some(InlayHintKind::TYPE),
+                text_edits: None,
+                tooltip,
+                padding_left: None,
+                padding_right: None,
+                data: None,
+            }
+        })
+        .collect()
+}
@@ -2,7 +2,7 @@ use bumpalo::Bump;
 use roc_fmt::Buf;
 use roc_parse::{
     ast::{Defs, Module},
-    module::parse_module_defs,
+    module::parse_module_defs_recovering,
     parser::SyntaxError,
 };
 use roc_region::all::Loc;
@@ -17,9 +17,12 @@ pub struct Ast<'a> {
     arena: &'a Bump,
     module: Module<'a>,
     defs: Defs<'a>,
+    syntax_errors: Vec<Loc<SyntaxError<'a>>>,
 }
 
 impl<'a> Ast<'a> {
+    /// Parses a module, skipping over any defs with syntax errors. Only fails if the header
+    /// can't be parsed.
     pub fn parse(arena: &'a Bump, src: &'a str) -> Result<Ast<'a>, SyntaxError<'a>> {
         use roc_parse::{module::parse_header, state::State};
 
@@ -28,15 +31,29 @@ impl<'a> Ast<'a> {
 
         let (module, defs) = module.upgrade_header_imports(arena);
 
-        let defs = parse_module_defs(arena, state, defs)?;
+        let (defs, syntax_errors) = parse_module_defs_recovering(arena, state, defs);
 
         Ok(Ast {
             module,
             defs,
+            syntax_errors,
             arena,
         })
     }
 
+    pub fn module(&self) -> &Module<'a> {
+        &self.module
+    }
+
+    pub fn defs(&self) -> &Defs<'a> {
+        &self.defs
+    }
+
+    /// The syntax errors in the defs that were skipped, with the region of each def.
+    pub fn syntax_errors(&self) -> &[Loc<SyntaxError<'a>>] {
+        &self.syntax_errors
+    }
+
     pub fn fmt(&self) -> FormattedAst<'a> {
         let mut buf = Buf::new_in(self.arena);
 
@@ -0,0 +1,673 @@
+//! Finding the references to values, types and record fields across documents, and computing
+//! the edits needed to rename them.
+
+use roc_can::traverse::{FieldLabelAt, SymbolReference};
+use roc_module::{ident::Lowercase, symbol::Symbol};
+use roc_parse::{
+    ast::{AssignedField, Defs, Header, Module, Spaced, Tag, TypeAnnotation, TypeDef, ValueDef},
+    header::ExposedName,
+    keyword::KEYWORDS,
+};
+use roc_region::all::{Loc, Position, Region};
+use roc_types::subs::{Content, FlatType, Subs, Variable};
+use tower_lsp::lsp_types::Url;
+
+use super::{parse_ast::Ast, tokens::Token};
+
+/// Something that can be referred to from many places, and renamed in all of them at once.
+#[derive(Debug, Clone)]
+pub(crate) enum ReferenceTarget {
+    Value {
+        /// The url of the module that defines the value.
+        module_url: Url,
+        /// The name of the module that defines the value.
+        module_name: String,
+        name: String,
+        /// The symbol of the value, as canonicalized in the document the target was found in.
+        symbol: Symbol,
+        /// The url of the document the target was found in. Symbols are only stable within one
+        /// analysis, so other documents must resolve the value by its module and name.
+        origin_url: Url,
+        /// Whether the value is exposed by its module, and so may be referenced by other modules.
+        exposed: bool,
+    },
+    /// A type alias, opaque type or ability.
+    Type {
+        module_url: Url,
+        module_name: String,
+        name: String,
+    },
+    /// Record fields are structural, so we consider two fields to be the same if they have the
+    /// same label and belong to records with the same set of labels.
+    Field {
+        label: Lowercase,
+        record_labels: Vec<Lowercase>,
+    },
+}
+
+impl ReferenceTarget {
+    pub fn name(&self) -> &str {
+        match self {
+            ReferenceTarget::Value { name, .. } | ReferenceTarget::Type { name, .. } => name,
+            ReferenceTarget::Field { label, .. } => label.as_str(),
+        }
+    }
+
+    /// Checks that `new_name` is an identifier that this target can be renamed to.
+    pub fn validate_new_name(&self, new_name: &str) -> Result<(), String> {
+        let valid = match self {
+            ReferenceTarget::Value { .. } | ReferenceTarget::Field { .. } => {
+                is_identifier(new_name, char::is_lowercase) && !KEYWORDS.contains(&new_name)
+            }
+            ReferenceTarget::Type { .. } => is_identifier(new_name, char::is_uppercase),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "`{}` is not a valid name for `{}`",
+                new_name,
+                self.name()
+            ))
+        }
+    }
+}
+
+fn is_identifier(name: &str, is_valid_start: impl Fn(char) -> bool) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().map_or(false, is_valid_start)
+        && chars.all(|c| c.is_alphabetic() || c.is_ascii_digit())
+}
+
+/// How the name at a [Reference] changes when it is renamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RenameEdit {
+    /// Replace the name with the new name.
+    Replace,
+    /// The name is a record field pun like `{ foo }` and we are renaming the variable:
+    /// `{ foo: newName }`.
+    KeepLabel,
+    /// The name is a record field pun like `{ foo }` and we are renaming the label:
+    /// `{ newLabel: foo }`.
+    KeepVariable,
+    /// The name can't be renamed without restructuring the code around it.
+    Unsupported(&'static str),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Reference {
+    /// The region of just the name being referenced.
+    pub region: Region,
+    pub edit: RenameEdit,
+}
+
+impl Reference {
+    fn replace(region: Region) -> Self {
+        Self {
+            region,
+            edit: RenameEdit::Replace,
+        }
+    }
+
+    pub fn new_text(&self, old_name: &str, new_name: &str) -> Result<String, String> {
+        match self.edit {
+            RenameEdit::Replace => Ok(new_name.to_string()),
+            RenameEdit::KeepLabel => Ok(format!("{}: {}", old_name, new_name)),
+            RenameEdit::KeepVariable => Ok(format!("{}: {}", new_name, old_name)),
+            RenameEdit::Unsupported(reason) => Err(reason.to_string()),
+        }
+    }
+}
+
+fn is_identifier_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || !c.is_ascii()
+}
+
+fn text(source: &str, region: Region) -> Option<&str> {
+    source.get(region.start().byte_offset()..region.end().byte_offset())
+}
+
+/// The lowercase identifier at `position`, e.g. to find the record field label under the cursor.
+pub(crate) fn lowercase_identifier_at(source: &str, position: Position) -> Option<Loc<&str>> {
+    let bytes = source.as_bytes();
+    let offset = position.byte_offset().min(bytes.len());
+
+    let start = bytes[..offset]
+        .iter()
+        .rev()
+        .take_while(|&&c| is_identifier_char(c))
+        .count();
+    let end = bytes[offset..]
+        .iter()
+        .take_while(|&&c| is_identifier_char(c))
+        .count();
+
+    let region = Region::new(
+        Position::new((offset - start) as u32),
+        Position::new((offset + end) as u32),
+    );
+    let name = text(source, region)?;
+
+    name.starts_with(char::is_lowercase)
+        .then_some(Loc::at(region, name))
+}
+
+/// The region of `name` if `region` ends with it, e.g. `foo` in `Module.foo` or `x as foo`.
+fn name_at_end(source: &str, region: Region, name: &str) -> Option<Region> {
+    let start = region.end().offset.checked_sub(name.len() as u32)?;
+    let name_region = Region::new(Position::new(start), region.end());
+
+    let before = start.checked_sub(1).map(|i| source.as_bytes()[i as usize]);
+    let is_whole_word = !before.map_or(false, is_identifier_char);
+
+    (name_region.start() >= region.start() && is_whole_word && text(source, name_region)? == name)
+        .then_some(name_region)
+}
+
+/// The region of `name` if `region` starts with it, e.g. `foo` in `foo: value`.
+fn name_at_start(source: &str, region: Region, name: &str) -> Option<Region> {
+    let name_region = Region::new(
+        region.start(),
+        region.start().bump_column(name.len() as u32),
+    );
+
+    let after = source
+        .as_bytes()
+        .get(name_region.end().byte_offset())
+        .copied();
+    let is_whole_word = !after.map_or(false, is_identifier_char);
+
+    (name_region.end() <= region.end() && is_whole_word && text(source, name_region)? == name)
+        .then_some(name_region)
+}
+
+/// The region of `name` in a record update `{ name & field: value }`.
+fn record_update_name(source: &str, region: Region, name: &str) -> Option<Region> {
+    let inner = text(source, region)?.strip_prefix('{')?;
+    let skipped = 1 + inner.len() - inner.trim_start().len();
+    let start = region.start().bump_column(skipped as u32);
+
+    name_at_start(source, Region::new(start, region.end()), name)
+}
+
+/// The region of the name in `name : Type`, given the region of `Type`.
+fn annotation_name(source: &str, annotation_region: Region, name: &str) -> Option<Region> {
+    let before = source
+        .get(..annotation_region.start().byte_offset())?
+        .trim_end()
+        .strip_suffix(':')?
+        .trim_end();
+
+    let end = Position::new(before.len() as u32);
+
+    name_at_end(source, Region::new(Position::zero(), end), name)
+}
+
+pub(crate) fn symbol_references(
+    source: &str,
+    name: &str,
+    references: impl IntoIterator<Item = Loc<SymbolReference>>,
+) -> Vec<Reference> {
+    references
+        .into_iter()
+        .filter_map(|Loc { region, value }| {
+            let (region, edit) = match value {
+                SymbolReference::Name => (name_at_end(source, region, name)?, RenameEdit::Replace),
+                SymbolReference::RecordUpdate => (
+                    record_update_name(source, region, name)?,
+                    RenameEdit::Replace,
+                ),
+                SymbolReference::RecordPun => {
+                    (name_at_end(source, region, name)?, RenameEdit::KeepLabel)
+                }
+                SymbolReference::OptionalRecordField => (
+                    name_at_start(source, region, name)?,
+                    RenameEdit::Unsupported(
+                        "Values bound by optional record field destructures can't be renamed",
+                    ),
+                ),
+                SymbolReference::Annotation => {
+                    (annotation_name(source, region, name)?, RenameEdit::Replace)
+                }
+            };
+
+            Some(Reference { region, edit })
+        })
+        .collect()
+}
+
+pub(crate) fn field_references(
+    source: &str,
+    label: &str,
+    references: impl IntoIterator<Item = (Region, FieldLabelAt)>,
+) -> Vec<Reference> {
+    references
+        .into_iter()
+        .filter_map(|(region, label_at)| {
+            let (region, edit) = match label_at {
+                FieldLabelAt::Start => (name_at_start(source, region, label)?, RenameEdit::Replace),
+                FieldLabelAt::End => (name_at_end(source, region, label)?, RenameEdit::Replace),
+                FieldLabelAt::Pun => (
+                    name_at_start(source, region, label)?,
+                    RenameEdit::KeepVariable,
+                ),
+                FieldLabelAt::OptionalPun => (
+                    name_at_start(source, region, label)?,
+                    RenameEdit::Unsupported("Optional record field destructures can't be renamed"),
+                ),
+            };
+
+            Some(Reference { region, edit })
+        })
+        .collect()
+}
+
+/// The labels of a record type, sorted, or [None] if `var` isn't a record.
+pub(crate) fn record_labels(subs: &Subs, var: Variable) -> Option<Vec<Lowercase>> {
+    match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::Record(fields, ext)) => {
+            let mut labels = fields
+                .unsorted_iterator(subs, *ext)
+                .ok()?
+                .map(|(label, _)| label.clone())
+                .collect::<Vec<_>>();
+            labels.sort();
+
+            Some(labels)
+        }
+        Content::Alias(_, _, real_var, _)
+        | Content::RecursionVar {
+            structure: real_var,
+            ..
+        } => record_labels(subs, *real_var),
+        _ => None,
+    }
+}
+
+/// An `import` of another module.
+#[derive(Debug)]
+pub(crate) struct ImportNames<'a> {
+    /// The name of the imported module, without its package shorthand.
+    pub module: &'a str,
+    pub alias: Option<&'a str>,
+    pub exposed: Vec<Loc<&'a str>>,
+}
+
+impl ImportNames<'_> {
+    fn imports(&self, module_name: &str) -> bool {
+        self.module == module_name
+    }
+
+    fn is_qualified_by(&self, qualifier: &str) -> bool {
+        match self.alias {
+            Some(alias) => alias == qualifier,
+            None => self.module == qualifier,
+        }
+    }
+}
+
+/// The names in a module that aren't part of its canonical declarations: what it exposes and
+/// imports, and all the type names it mentions.
+pub(crate) struct ModuleNames<'a> {
+    pub exposes: Vec<Loc<&'a str>>,
+    pub imports: Vec<ImportNames<'a>>,
+    /// The names of the types defined at the top level of the module.
+    pub type_defs: Vec<&'a str>,
+    /// All (possibly qualified) type names, e.g. `Foo`, `Module.Foo` or the opaque ref `@Foo`.
+    pub types: Vec<Loc<&'a str>>,
+    /// Every record type in a top-level annotation: its sorted labels, and the region of each label.
+    pub record_annotations: Vec<(Vec<&'a str>, Vec<Loc<&'a str>>)>,
+}
+
+impl<'a> ModuleNames<'a> {
+    pub fn from_ast(ast: &Ast<'a>, source: &'a str) -> Self {
+        let exposes = header_exposes(ast.module());
+        let defs = ast.defs();
+
+        let imports = defs
+            .value_defs
+            .iter()
+            .filter_map(|value_def| match value_def {
+                ValueDef::ModuleImport(import) => Some(ImportNames {
+                    module: import.name.value.name.into(),
+                    alias: import
+                        .alias
+                        .and_then(|alias| text(source, alias.item.region)),
+                    exposed: import
+                        .exposed
+                        .iter()
+                        .flat_map(|exposed| exposed.item.iter())
+                        .map(exposed_name)
+                        .collect(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        let type_defs = defs
+            .type_defs
+            .iter()
+            .map(|type_def| match type_def {
+                TypeDef::Alias { header, .. }
+                | TypeDef::Opaque { header, .. }
+                | TypeDef::Ability { header, .. } => header.name.value,
+            })
+            .collect();
+
+        let types = ast
+            .semantic_tokens()
+            .into_iter()
+            .filter(|token| matches!(token.value, Token::Type | Token::Ability))
+            .filter_map(|token| Some(Loc::at(token.region, text(source, token.region)?)))
+            .collect();
+
+        let mut record_annotations = Vec::new();
+        for annotation in top_level_annotations(defs) {
+            collect_record_annotations(annotation, &mut record_annotations);
+        }
+
+        Self {
+            exposes,
+            imports,
+            type_defs,
+            types,
+            record_annotations,
+        }
+    }
+
+    /// References to a value in the module's `exposes` and `import ... exposing` lists.
+    ///
+    /// `is_self` says whether this module is the one defining the value.
+    pub fn value_references<'b>(
+        &'b self,
+        module_name: &'b str,
+        name: &'b str,
+        is_self: bool,
+    ) -> impl Iterator<Item = Reference> + 'b {
+        let exposes = self.exposes.iter().filter(move |_| is_self);
+        let imported = self
+            .imports
+            .iter()
+            .filter(move |import| import.imports(module_name))
+            .flat_map(|import| import.exposed.iter());
+
+        exposes
+            .chain(imported)
+            .filter(move |loc_name| loc_name.value == name)
+            .map(|loc_name| Reference::replace(loc_name.region))
+    }
+
+    /// References to a type, wherever its name appears.
+    ///
+    /// `is_self` says whether this module is the one defining the type.
+    pub fn type_references<'b>(
+        &'b self,
+        module_name: &'b str,
+        name: &'b str,
+        is_self: bool,
+    ) -> impl Iterator<Item = Reference> + 'b {
+        let is_exposed_to_self = is_self && self.type_defs.contains(&name)
+            || self
+                .imports
+                .iter()
+                .filter(|import| import.imports(module_name))
+                .any(|import| import.exposed.iter().any(|exposed| exposed.value == name));
+
+        let types =
+            self.types
+                .iter()
+                .filter(move |loc_type| match loc_type.value.rsplit_once('.') {
+                    Some((qualifier, type_name)) => {
+                        type_name == name
+                            && self.imports.iter().any(|import| {
+                                import.imports(module_name) && import.is_qualified_by(qualifier)
+                            })
+                    }
+                    None => match loc_type.value.strip_prefix('@') {
+                        Some(opaque_name) => is_self && opaque_name == name,
+                        None => is_exposed_to_self && loc_type.value == name,
+                    },
+                });
+
+        let exposes = self.exposes.iter().filter(move |_| is_self);
+        let imported = self
+            .imports
+            .iter()
+            .filter(move |import| import.imports(module_name))
+            .flat_map(|import| import.exposed.iter());
+
+        types
+            .chain(exposes.filter(move |loc_name| loc_name.value == name))
+            .chain(imported.filter(move |loc_name| loc_name.value == name))
+            .map(move |loc_name| {
+                let region = name_at_end_of_text(loc_name, name);
+                Reference::replace(region)
+            })
+    }
+
+    /// References to a record field in top-level annotations.
+    pub fn field_references<'b>(
+        &'b self,
+        label: &'b str,
+        record_labels: &'b [Lowercase],
+    ) -> impl Iterator<Item = Reference> + 'b {
+        self.record_annotations
+            .iter()
+            .filter(move |(labels, _)| {
+                labels.len() == record_labels.len()
+                    && labels
+                        .iter()
+                        .zip(record_labels)
+                        .all(|(a, b)| *a == b.as_str())
+            })
+            .flat_map(|(_, fields)| fields.iter())
+            .filter(move |field| field.value == label)
+            .map(|field| Reference::replace(field.region))
+    }
+
+    /// Resolves the type name at `position` to the name of the module defining it, and the
+    /// unqualified name of the type. Returns [None] if the module can't be determined from this
+    /// module alone, e.g. because the type is a builtin.
+    pub fn type_at<'b>(
+        &'b self,
+        position: Position,
+        own_module_name: &'b str,
+    ) -> Option<(&'b str, &'a str)> {
+        let loc_type = self
+            .types
+            .iter()
+            .chain(self.exposes.iter())
+            .chain(self.imports.iter().flat_map(|import| import.exposed.iter()))
+            .find(|loc_type| loc_type.region.contains_pos(position))?;
+
+        if !loc_type
+            .value
+            .starts_with(|c: char| c.is_uppercase() || c == '@')
+        {
+            return None;
+        }
+
+        if let Some(import) = self
+            .imports
+            .iter()
+            .find(|import| import.exposed.iter().any(|exposed| exposed == loc_type))
+        {
+            return Some((import.module, loc_type.value));
+        }
+
+        match loc_type.value.rsplit_once('.') {
+            Some((qualifier, type_name)) => {
+                let import = self
+                    .imports
+                    .iter()
+                    .find(|import| import.is_qualified_by(qualifier))?;
+
+                Some((import.module, type_name))
+            }
+            None => {
+                let name = loc_type.value.trim_start_matches('@');
+
+                if self.type_defs.contains(&name) {
+                    Some((own_module_name, name))
+                } else {
+                    let import = self.imports.iter().find(|import| {
+                        import.exposed.iter().any(|exposed| exposed.value == name)
+                    })?;
+
+                    Some((import.module, name))
+                }
+            }
+        }
+    }
+
+    /// Resolves a value name in the `exposes` or `import ... exposing` lists at `position` to
+    /// the name of the module defining it, and the name of the value.
+    pub fn exposed_value_at<'b>(
+        &'b self,
+        position: Position,
+        own_module_name: &'b str,
+    ) -> Option<(&'b str, &'a str)> {
+        let is_value = |loc_name: &&Loc<&str>| {
+            loc_name.region.contains_pos(position) && loc_name.value.starts_with(char::is_lowercase)
+        };
+
+        if let Some(exposed) = self.exposes.iter().find(is_value) {
+            return Some((own_module_name, exposed.value));
+        }
+
+        self.imports.iter().find_map(|import| {
+            let exposed = import.exposed.iter().find(is_value)?;
+            Some((import.module, exposed.value))
+        })
+    }
+}
+
+fn name_at_end_of_text(loc_name: &Loc<&str>, name: &str) -> Region {
+    let len = name.len() as u32;
+    let end = loc_name.region.end();
+
+    Region::new(end.sub(len), end)
+}
+
+fn header_exposes<'a>(module: &Module<'a>) -> Vec<Loc<&'a str>> {
+    let exposes: &[Loc<Spaced<'a, ExposedName<'a>>>] = match &module.header {
+        Header::Module(header) => header.exposes.items,
+        Header::Hosted(header) => header.exposes.item.items,
+        Header::App(_) | Header::Package(_) | Header::Platform(_) => &[],
+    };
+
+    let mut names: Vec<_> = exposes.iter().map(exposed_name).collect();
+
+    if let Header::App(header) = &module.header {
+        names.extend(header.provides.items.iter().map(exposed_name));
+    }
+
+    names
+}
+
+fn exposed_name<'a>(name: &Loc<Spaced<'a, ExposedName<'a>>>) -> Loc<&'a str> {
+    name.map(|spaced| (*spaced.item()).into())
+}
+
+fn top_level_annotations<'a, 'b>(
+    defs: &'b Defs<'a>,
+) -> impl Iterator<Item = &'b Loc<TypeAnnotation<'a>>> + 'b {
+    let type_annotations = defs.type_defs.iter().flat_map(|type_def| match type_def {
+        TypeDef::Alias { ann, .. } => vec![ann],
+        TypeDef::Opaque { typ, .. } => vec![typ],
+        TypeDef::Ability { members, .. } => members.iter().map(|member| &member.typ).collect(),
+    });
+
+    let value_annotations = defs
+        .value_defs
+        .iter()
+        .filter_map(|value_def| match value_def {
+            ValueDef::Annotation(_, ann_type) => Some(ann_type),
+            ValueDef::AnnotatedBody { ann_type, .. } => Some(*ann_type),
+            _ => None,
+        });
+
+    type_annotations.chain(value_annotations)
+}
+
+fn collect_record_annotations<'a>(
+    annotation: &Loc<TypeAnnotation<'a>>,
+    found: &mut Vec<(Vec<&'a str>, Vec<Loc<&'a str>>)>,
+) {
+    let mut recurse = |ann: &Loc<TypeAnnotation<'a>>| collect_record_annotations(ann, found);
+
+    match annotation.value {
+        TypeAnnotation::Function(args, ret) => {
+            args.iter().for_each(&mut recurse);
+            recurse(ret);
+        }
+        TypeAnnotation::Apply(_, _, args) => args.iter().for_each(recurse),
+        TypeAnnotation::As(ann, _, _) => recurse(ann),
+        TypeAnnotation::Record { fields, ext } => {
+            let mut labels = Vec::with_capacity(fields.len());
+            let mut field_types = Vec::with_capacity(fields.len());
+
+            for field in fields.iter() {
+                let mut current = &field.value;
+                loop {
+                    match current {
+                        AssignedField::RequiredValue(label, _, typ)
+                        | AssignedField::OptionalValue(label, _, typ) => {
+                            labels.push(*label);
+                            field_types.push(*typ);
+                            break;
+                        }
+                        AssignedField::LabelOnly(label) => {
+                            labels.push(*label);
+                            break;
+                        }
+                        AssignedField::SpaceBefore(inner, _)
+                        | AssignedField::SpaceAfter(inner, _) => current = inner,
+                        AssignedField::Malformed(_) => break,
+                    }
+                }
+            }
+
+            let mut names: Vec<_> = labels.iter().map(|label| label.value).collect();
+            names.sort_unstable();
+            found.push((names, labels));
+
+            for typ in field_types {
+                collect_record_annotations(typ, found);
+            }
+            if let Some(ext) = ext {
+                collect_record_annotations(ext, found);
+            }
+        }
+        TypeAnnotation::Tuple { elems, ext } => {
+            elems.iter().for_each(&mut recurse);
+            ext.iter().for_each(|ext| recurse(ext));
+        }
+        TypeAnnotation::TagUnion { tags, ext } => {
+            for tag in tags.iter() {
+                let mut current = &tag.value;
+                loop {
+                    match current {
+                        Tag::Apply { args, .. } => {
+                            args.iter().for_each(&mut recurse);
+                            break;
+                        }
+                        Tag::SpaceBefore(inner, _) | Tag::SpaceAfter(inner, _) => current = inner,
+                        Tag::Malformed(_) => break,
+                    }
+                }
+            }
+            ext.iter().for_each(|ext| recurse(ext));
+        }
+        TypeAnnotation::Where(ann, _) => recurse(ann),
+        TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+            recurse(&Loc::at(annotation.region, *inner))
+        }
+        TypeAnnotation::BoundVariable(_)
+        | TypeAnnotation::Inferred
+        | TypeAnnotation::Wildcard
+        | TypeAnnotation::Malformed(_) => {}
+    }
+}
@@ -0,0 +1,170 @@
+//! Signature help: the type of the function being called around the cursor, with the argument
+//! being written highlighted.
+
+use roc_can::{expr::Declarations, traverse::Visitor};
+use roc_module::symbol::Interns;
+use roc_region::all::Position;
+use roc_types::subs::{Content, FlatType, Subs, Variable};
+use tower_lsp::lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp,
+    SignatureInformation,
+};
+
+use super::{
+    completion::visitor::{CompletionVisitor, FoundCall},
+    utils::format_var_type,
+    AnalyzedModule,
+};
+
+fn is_function(subs: &Subs, var: Variable) -> bool {
+    match subs.get_content_without_compacting(var) {
+        Content::Structure(FlatType::Func(..)) => true,
+        Content::Alias(_, _, real_var, _) => is_function(subs, *real_var),
+        _ => false,
+    }
+}
+
+/// The signature of the function called around `position`.
+pub(super) fn signature_help(
+    position: Position,
+    source: &str,
+    module: &AnalyzedModule,
+) -> Option<SignatureHelp> {
+    let AnalyzedModule {
+        subs,
+        declarations,
+        module_id,
+        interns,
+        modules_info,
+        ..
+    } = module;
+
+    let FoundCall {
+        function,
+        fn_var,
+        symbol,
+        args,
+        ..
+    } = find_call(position, source, declarations, subs, interns)?;
+    let args = args.unwrap_or_default();
+
+    let name = source.get(function.start().byte_offset()..function.end().byte_offset())?;
+    let type_str = format_var_type(fn_var, &mut subs.clone(), module_id, interns);
+    let type_str = type_str.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let label = format!("{name} : {type_str}");
+    let parameters = parameter_offsets(&label, name.len() + 3)?
+        .into_iter()
+        .map(|(start, end)| ParameterInformation {
+            label: ParameterLabel::LabelOffsets([
+                utf16_len(&label[..start]),
+                utf16_len(&label[..end]),
+            ]),
+            documentation: None,
+        })
+        .collect();
+
+    let documentation = symbol
+        .and_then(|symbol| {
+            modules_info
+                .get_docs(&symbol.module_id())?
+                .get_doc_for_symbol(&symbol)
+        })
+        .map(|docs| {
+            Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: docs.trim().to_string(),
+            })
+        });
+
+    // The arguments before the cursor have been written already.
+    let active_parameter = args.iter().filter(|arg| arg.end() < position).count() as u32;
+
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation,
+            parameters: Some(parameters),
+            active_parameter: Some(active_parameter),
+        }],
+        active_signature: Some(0),
+        active_parameter: Some(active_parameter),
+    })
+}
+
+/// Finds the innermost call whose arguments the cursor is in, or failing that a function that
+/// is about to be called.
+fn find_call(
+    position: Position,
+    source: &str,
+    decls: &Declarations,
+    subs: &Subs,
+    interns: &Interns,
+) -> Option<FoundCall> {
+    // While starting to write the next argument, the cursor is after the call with only spaces in
+    // between, so we look for the call that ends before them.
+    let spaces = source
+        .get(..position.byte_offset())?
+        .bytes()
+        .rev()
+        .take_while(|byte| *byte == b' ')
+        .count();
+    let call_position = Position::new(position.offset - spaces as u32);
+
+    let mut visitor = CompletionVisitor {
+        position: call_position,
+        found_declarations: Vec::new(),
+        interns,
+        prefix: String::new(),
+        found_calls: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+
+    let (calls, functions): (Vec<_>, Vec<_>) = visitor
+        .found_calls
+        .into_iter()
+        .filter(|call| call.region.start() < position)
+        .partition(|call| call.args.is_some());
+
+    calls.into_iter().last().or_else(|| {
+        // Before the first argument is written there is no call yet, only the function.
+        functions
+            .into_iter()
+            .last()
+            .filter(|function| is_function(subs, function.fn_var))
+    })
+}
+
+/// The byte offsets of the parameters of the function type in `label` starting at `start`,
+/// which are separated by commas outside of any brackets, up to the arrow to the return type.
+fn parameter_offsets(label: &str, start: usize) -> Option<Vec<(usize, usize)>> {
+    let mut parameters = Vec::new();
+    let mut parameter_start = start;
+    let mut depth = 0;
+
+    let bytes = label.as_bytes();
+
+    for (index, byte) in bytes.iter().enumerate().skip(start) {
+        match byte {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b',' if depth == 0 => {
+                parameters.push((parameter_start, index));
+                parameter_start = index + 2;
+            }
+            b'-' if depth == 0 && bytes.get(index + 1) == Some(&b'>') => {
+                parameters.push((parameter_start, index - 1));
+
+                return Some(parameters);
+            }
+            _ => {}
+        }
+    }
+
+    // Not a function after all.
+    None
+}
+
+fn utf16_len(str: &str) -> u32 {
+    str.encode_utf16().count() as u32
+}
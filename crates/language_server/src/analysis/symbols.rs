@@ -0,0 +1,319 @@
+//! An outline of the definitions in a module, for the document and workspace symbol requests.
+
+use roc_parse::ast::{Defs, Expr, Pattern, TypeAnnotation, TypeDef, ValueDef};
+use roc_region::all::{LineInfo, Region};
+use tower_lsp::lsp_types::{DocumentSymbol, Location, SymbolInformation, SymbolKind, Url};
+
+use crate::convert::ToRange;
+
+use super::parse_ast::Ast;
+
+struct SymbolBuilder<'a> {
+    source: &'a str,
+    line_info: &'a LineInfo,
+}
+
+/// The top-level definitions of a module, with the abilities' members and the `expect`s nested
+/// in each definition as children.
+pub(super) fn document_symbols(
+    ast: &Ast<'_>,
+    source: &str,
+    line_info: &LineInfo,
+) -> Vec<DocumentSymbol> {
+    let builder = SymbolBuilder { source, line_info };
+
+    builder.defs_symbols(ast.defs())
+}
+
+/// Flattens document symbols into a list of symbol information, keeping those whose name
+/// fuzzily matches `query`.
+pub(super) fn workspace_symbols(
+    url: &Url,
+    container_name: &str,
+    symbols: Vec<DocumentSymbol>,
+    query: &str,
+) -> Vec<SymbolInformation> {
+    let mut found = Vec::new();
+    let mut stack: Vec<_> = symbols
+        .into_iter()
+        .rev()
+        .map(|symbol| (container_name.to_string(), symbol))
+        .collect();
+
+    while let Some((container_name, symbol)) = stack.pop() {
+        let DocumentSymbol {
+            name,
+            kind,
+            range,
+            children,
+            ..
+        } = symbol;
+
+        if let Some(children) = children {
+            stack.extend(
+                children
+                    .into_iter()
+                    .rev()
+                    .map(|child| (name.clone(), child)),
+            );
+        }
+
+        if fuzzy_matches(&name, query) {
+            #[allow(deprecated)]
+            found.push(SymbolInformation {
+                name,
+                kind,
+                tags: None,
+                deprecated: None,
+                location: Location::new(url.clone(), range),
+                container_name: Some(container_name),
+            });
+        }
+    }
+
+    found
+}
+
+/// Whether all the characters of `query` appear in `name` in order, ignoring case.
+fn fuzzy_matches(name: &str, query: &str) -> bool {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase);
+
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|query_char| name_chars.any(|name_char| name_char == query_char))
+}
+
+impl SymbolBuilder<'_> {
+    fn text(&self, region: Region) -> &str {
+        self.source
+            .get(region.start().byte_offset()..region.end().byte_offset())
+            .unwrap_or_default()
+    }
+
+    /// The text of `region` on a single line, so it can be used as a name or detail.
+    fn single_line_text(&self, region: Region) -> String {
+        self.text(region)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn symbol(
+        &self,
+        name: String,
+        detail: Option<String>,
+        kind: SymbolKind,
+        region: Region,
+        name_region: Region,
+        children: Vec<DocumentSymbol>,
+    ) -> DocumentSymbol {
+        #[allow(deprecated)]
+        DocumentSymbol {
+            name,
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: region.to_range(self.line_info),
+            selection_range: name_region.to_range(self.line_info),
+            children: (!children.is_empty()).then_some(children),
+        }
+    }
+
+    fn defs_symbols(&self, defs: &Defs<'_>) -> Vec<DocumentSymbol> {
+        defs.defs()
+            .zip(defs.regions.iter())
+            .filter_map(|(def, region)| match def {
+                Ok(type_def) => Some(self.type_def_symbol(type_def, *region)),
+                Err(value_def) => self.value_def_symbol(value_def, *region),
+            })
+            .collect()
+    }
+
+    fn type_def_symbol(&self, type_def: &TypeDef<'_>, region: Region) -> DocumentSymbol {
+        match type_def {
+            TypeDef::Alias { header, ann } => self.symbol(
+                header.name.value.to_string(),
+                Some(self.single_line_text(ann.region)),
+                SymbolKind::STRUCT,
+                region,
+                header.name.region,
+                vec![],
+            ),
+            TypeDef::Opaque { header, .. } => self.symbol(
+                header.name.value.to_string(),
+                None,
+                SymbolKind::CLASS,
+                region,
+                header.name.region,
+                vec![],
+            ),
+            TypeDef::Ability {
+                header, members, ..
+            } => {
+                let members = members
+                    .iter()
+                    .map(|member| {
+                        self.symbol(
+                            member.name.value.item().to_string(),
+                            Some(self.single_line_text(member.typ.region)),
+                            SymbolKind::METHOD,
+                            member.region(),
+                            member.name.region,
+                            vec![],
+                        )
+                    })
+                    .collect();
+
+                self.symbol(
+                    header.name.value.to_string(),
+                    None,
+                    SymbolKind::INTERFACE,
+                    region,
+                    header.name.region,
+                    members,
+                )
+            }
+        }
+    }
+
+    fn value_def_symbol(&self, value_def: &ValueDef<'_>, region: Region) -> Option<DocumentSymbol> {
+        match value_def {
+            ValueDef::Annotation(pattern, annotation) => Some(self.symbol(
+                self.pattern_name(&pattern.value, pattern.region),
+                Some(self.single_line_text(annotation.region)),
+                annotation_kind(&annotation.value),
+                region,
+                pattern.region,
+                vec![],
+            )),
+            ValueDef::Body(pattern, body) => Some(self.symbol(
+                self.pattern_name(&pattern.value, pattern.region),
+                None,
+                body_kind(&body.value),
+                region,
+                pattern.region,
+                self.nested_expects(&body.value),
+            )),
+            ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                body_expr,
+                ..
+            } => Some(self.symbol(
+                self.pattern_name(&ann_pattern.value, ann_pattern.region),
+                Some(self.single_line_text(ann_type.region)),
+                annotation_kind(&ann_type.value),
+                region,
+                ann_pattern.region,
+                self.nested_expects(&body_expr.value),
+            )),
+            ValueDef::Expect { condition, .. } => {
+                Some(self.expect_symbol("expect", condition.region, region))
+            }
+            ValueDef::ExpectFx { condition, .. } => {
+                Some(self.expect_symbol("expect-fx", condition.region, region))
+            }
+            ValueDef::Dbg { .. }
+            | ValueDef::ModuleImport(_)
+            | ValueDef::IngestedFileImport(_)
+            | ValueDef::Stmt(_) => None,
+        }
+    }
+
+    fn expect_symbol(&self, keyword: &str, condition: Region, region: Region) -> DocumentSymbol {
+        self.symbol(
+            keyword.to_string(),
+            Some(self.single_line_text(condition)),
+            SymbolKind::EVENT,
+            region,
+            condition,
+            vec![],
+        )
+    }
+
+    fn pattern_name(&self, pattern: &Pattern<'_>, region: Region) -> String {
+        match pattern {
+            Pattern::Identifier { ident } => ident.to_string(),
+            Pattern::SpaceBefore(pattern, _) | Pattern::SpaceAfter(pattern, _) => {
+                self.pattern_name(pattern, region)
+            }
+            // Destructures define several names, so we name them by the whole pattern.
+            _ => self.single_line_text(region),
+        }
+    }
+
+    /// The `expect`s in the body of a definition, including those in nested definitions.
+    fn nested_expects(&self, expr: &Expr<'_>) -> Vec<DocumentSymbol> {
+        let mut found = Vec::new();
+        self.collect_nested_expects(expr, &mut found);
+        found
+    }
+
+    fn collect_nested_expects(&self, expr: &Expr<'_>, found: &mut Vec<DocumentSymbol>) {
+        match expr {
+            Expr::SpaceBefore(expr, _) | Expr::SpaceAfter(expr, _) | Expr::ParensAround(expr) => {
+                self.collect_nested_expects(expr, found)
+            }
+            Expr::Closure(_, body) => self.collect_nested_expects(&body.value, found),
+            Expr::If(branches, final_else) => {
+                for (_, then) in branches.iter() {
+                    self.collect_nested_expects(&then.value, found);
+                }
+
+                self.collect_nested_expects(&final_else.value, found);
+            }
+            Expr::When(_, branches) => {
+                for branch in branches.iter() {
+                    self.collect_nested_expects(&branch.value.value, found);
+                }
+            }
+            Expr::Expect(condition, continuation) => {
+                found.push(self.expect_symbol("expect", condition.region, condition.region));
+                self.collect_nested_expects(&continuation.value, found);
+            }
+            Expr::Defs(defs, final_expr) => {
+                for (def, region) in defs.defs().zip(defs.regions.iter()) {
+                    match def {
+                        Err(ValueDef::Expect { condition, .. }) => {
+                            found.push(self.expect_symbol("expect", condition.region, *region))
+                        }
+                        Err(ValueDef::ExpectFx { condition, .. }) => {
+                            found.push(self.expect_symbol("expect-fx", condition.region, *region))
+                        }
+                        Err(ValueDef::Body(_, body))
+                        | Err(ValueDef::AnnotatedBody {
+                            body_expr: body, ..
+                        }) => self.collect_nested_expects(&body.value, found),
+                        _ => {}
+                    }
+                }
+
+                self.collect_nested_expects(&final_expr.value, found);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn annotation_kind(annotation: &TypeAnnotation<'_>) -> SymbolKind {
+    match annotation {
+        TypeAnnotation::Function(..) => SymbolKind::FUNCTION,
+        TypeAnnotation::SpaceBefore(annotation, _) | TypeAnnotation::SpaceAfter(annotation, _) => {
+            annotation_kind(annotation)
+        }
+        TypeAnnotation::Where(annotation, _) => annotation_kind(&annotation.value),
+        _ => SymbolKind::CONSTANT,
+    }
+}
+
+fn body_kind(expr: &Expr<'_>) -> SymbolKind {
+    match expr {
+        Expr::Closure(..) => SymbolKind::FUNCTION,
+        Expr::SpaceBefore(expr, _) | Expr::SpaceAfter(expr, _) | Expr::ParensAround(expr) => {
+            body_kind(expr)
+        }
+        _ => SymbolKind::CONSTANT,
+    }
+}
@@ -74,6 +74,17 @@ fn field_token(region: Region, arena: &Bump) -> BumpVec<Loc<Token>> {
     onetoken(Token::Field, region, arena)
 }
 
+/// The region of the (possibly qualified) name of an applied type like `Module.Name a b`.
+pub(super) fn apply_name_region(region: Region, module: &str, name: &str) -> Region {
+    let len = if module.is_empty() {
+        name.len()
+    } else {
+        module.len() + 1 + name.len()
+    };
+
+    Region::new(region.start(), region.start().bump_column(len as u32))
+}
+
 trait HasToken {
     fn token(&self) -> Token;
 }
@@ -403,7 +414,14 @@ impl IterTokens for Loc<TypeAnnotation<'_>> {
             TypeAnnotation::Function(params, ret) => (params.iter_tokens(arena).into_iter())
                 .chain(ret.iter_tokens(arena))
                 .collect_in(arena),
-            TypeAnnotation::Apply(_mod, _type, args) => args.iter_tokens(arena),
+            TypeAnnotation::Apply(module, name, args) => (onetoken(
+                Token::Type,
+                apply_name_region(self.region, module, name),
+                arena,
+            )
+            .into_iter())
+            .chain(args.iter_tokens(arena))
+            .collect_in(arena),
             TypeAnnotation::BoundVariable(_) => onetoken(Token::Type, self.region, arena),
             TypeAnnotation::As(ty, _, as_ty) => (ty.iter_tokens(arena).into_iter())
                 .chain(as_ty.iter_tokens(arena))
@@ -649,7 +667,15 @@ impl IterTokens for ValueDef<'_> {
             } => (onetoken(Token::Comment, *preceding_comment, arena).into_iter())
                 .chain(condition.iter_tokens(arena))
                 .collect_in(arena),
-            ValueDef::ModuleImport(import) => onetoken(Token::Import, import.name.region, arena),
+            ValueDef::ModuleImport(import) => (onetoken(Token::Import, import.name.region, arena)
+                .into_iter())
+            .chain(
+                import
+                    .exposed
+                    .iter()
+                    .flat_map(|e| e.item.iter_tokens(arena)),
+            )
+            .collect_in(arena),
             ValueDef::IngestedFileImport(import) => {
                 onetoken(Token::Import, import.name.item.region, arena)
             }
@@ -733,7 +759,8 @@ impl IterTokens for Loc<Expr<'_>> {
             | Expr::MalformedClosure
             | Expr::PrecedenceConflict(_)
             | Expr::EmptyDefsFinal
-            | Expr::MalformedSuffixed(_) => {
+            | Expr::MalformedSuffixed(_)
+            | Expr::Malformed(_) => {
                 bumpvec![in arena;]
             }
         }
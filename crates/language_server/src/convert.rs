@@ -72,7 +72,8 @@ pub(crate) mod diag {
     use std::path::Path;
 
     use roc_load::LoadingProblem;
-    use roc_region::all::{LineInfo, Region};
+    use roc_parse::parser::{SourceError, SyntaxError};
+    use roc_region::all::{LineInfo, Loc, Region};
     use roc_solve_problem::TypeError;
 
     use roc_problem::Severity;
@@ -184,10 +185,49 @@ pub(crate) mod diag {
 
     pub struct ProblemFmt<'a> {
         pub alloc: &'a RocDocAllocator<'a>,
+        pub source: &'a str,
         pub line_info: &'a LineInfo,
         pub path: &'a Path,
     }
 
+    impl<'a> IntoLspDiagnostic<'a> for Loc<SyntaxError<'a>> {
+        type Feed = ProblemFmt<'a>;
+
+        fn into_lsp_diagnostic(self, fmt: &'a ProblemFmt<'a>) -> Option<Diagnostic> {
+            // The error is reported on the whole def that was skipped because of it
+            let range = self.region.to_range(fmt.line_info);
+
+            let problem = SourceError {
+                problem: self.value,
+                bytes: fmt.source.as_bytes(),
+            };
+
+            let report = roc_reporting::report::parse_problem(
+                fmt.alloc,
+                fmt.line_info,
+                fmt.path.to_path_buf(),
+                0,
+                problem.into_file_error(fmt.path.to_path_buf()),
+            );
+
+            let severity = report.severity.into_lsp_severity();
+            let mut msg = String::new();
+            report.render_language_server(&mut msg, fmt.alloc);
+
+            Some(Diagnostic {
+                range,
+                severity: Some(severity),
+                code: None,
+                code_description: None,
+                source: None,
+                message: msg,
+                related_information: None,
+                tags: None,
+                data: None,
+            })
+        }
+    }
+
     impl<'a> IntoLspDiagnostic<'a> for roc_problem::can::Problem {
         type Feed = ProblemFmt<'a>;
 
@@ -9,11 +9,12 @@ use std::{
 use tokio::sync::{Mutex, MutexGuard};
 
 use tower_lsp::lsp_types::{
-    CompletionResponse, Diagnostic, GotoDefinitionResponse, Hover, Position, SemanticTokensResult,
-    TextEdit, Url,
+    CodeActionResponse, CompletionResponse, Diagnostic, DocumentSymbolResponse,
+    GotoDefinitionResponse, Hover, InlayHint, Location, Position, PrepareRenameResponse, Range,
+    SemanticTokensResult, SignatureHelp, SymbolInformation, TextEdit, Url, WorkspaceEdit,
 };
 
-use crate::analysis::{AnalyzedDocument, DocInfo};
+use crate::analysis::{AnalyzedDocument, DocInfo, ReferenceTarget};
 
 #[derive(Debug)]
 pub(crate) struct DocumentPair {
@@ -164,6 +165,22 @@ impl Registry {
         .ok()
     }
 
+    /// The latest analysis of every document we know of, falling back to the last good one for
+    /// documents that are still being analysed.
+    async fn all_documents(&self) -> Vec<Arc<AnalyzedDocument>> {
+        self.documents
+            .lock()
+            .await
+            .values()
+            .map(|pair| {
+                pair.latest_document
+                    .get()
+                    .unwrap_or(&pair.last_good_document)
+                    .clone()
+            })
+            .collect()
+    }
+
     pub async fn diagnostics(&self, url: &Url) -> Vec<Diagnostic> {
         let Some(document) = self.latest_document_by_url(url).await else {
             return vec![];
@@ -175,6 +192,29 @@ impl Registry {
         self.latest_document_by_url(url).await?.hover(position)
     }
 
+    pub async fn signature_help(&self, url: &Url, position: Position) -> Option<SignatureHelp> {
+        self.latest_document_by_url(url)
+            .await?
+            .signature_help(position)
+    }
+
+    pub async fn inlay_hints(
+        &self,
+        url: &Url,
+        range: Range,
+        max_length: usize,
+    ) -> Option<Vec<InlayHint>> {
+        self.latest_document_by_url(url)
+            .await?
+            .inlay_hints(range, max_length)
+    }
+
+    pub async fn code_actions(&self, url: &Url, range: Range) -> Option<CodeActionResponse> {
+        let document = self.latest_document_by_url(url).await?;
+
+        Some(document.code_actions(range))
+    }
+
     pub async fn goto_definition(
         &self,
         url: &Url,
@@ -187,6 +227,90 @@ impl Registry {
         def_document.definition(symbol)
     }
 
+    pub async fn references(&self, url: &Url, position: Position) -> Option<Vec<Location>> {
+        let document = self.latest_document_by_url(url).await?;
+        let target = document.reference_target(position)?;
+
+        let locations = self
+            .all_documents()
+            .await
+            .iter()
+            .flat_map(|document| document.reference_locations(&target))
+            .collect();
+
+        Some(locations)
+    }
+
+    /// Finds what a rename at `position` would change, and checks that it can be renamed.
+    async fn rename_target(
+        &self,
+        url: &Url,
+        position: Position,
+    ) -> Result<Option<(Arc<AnalyzedDocument>, ReferenceTarget)>, String> {
+        let Some(document) = self.latest_document_by_url(url).await else {
+            return Ok(None);
+        };
+        let Some(target) = document.reference_target(position) else {
+            return Ok(None);
+        };
+
+        match &target {
+            ReferenceTarget::Value { module_url, .. }
+            | ReferenceTarget::Type { module_url, .. }
+                if is_in_package_cache(module_url) =>
+            {
+                Err(format!(
+                    "`{}` is defined in a downloaded package and can't be renamed",
+                    target.name()
+                ))
+            }
+            _ => Ok(Some((document, target))),
+        }
+    }
+
+    pub async fn prepare_rename(
+        &self,
+        url: &Url,
+        position: Position,
+    ) -> Result<Option<PrepareRenameResponse>, String> {
+        let Some((document, target)) = self.rename_target(url, position).await? else {
+            return Ok(None);
+        };
+
+        let range = document.rename_range(&target, position)?;
+
+        Ok(
+            range.map(|range| PrepareRenameResponse::RangeWithPlaceholder {
+                range,
+                placeholder: target.name().to_string(),
+            }),
+        )
+    }
+
+    pub async fn rename(
+        &self,
+        url: &Url,
+        position: Position,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceEdit>, String> {
+        let Some((_, target)) = self.rename_target(url, position).await? else {
+            return Ok(None);
+        };
+
+        target.validate_new_name(new_name)?;
+
+        let mut changes = HashMap::new();
+        for document in self.all_documents().await {
+            let edits = document.rename_edits(&target, new_name)?;
+
+            if !edits.is_empty() {
+                changes.insert(document.url().clone(), edits);
+            }
+        }
+
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
     pub async fn formatting(&self, url: &Url) -> Option<Vec<TextEdit>> {
         let document = self.document_info_by_url(url).await?;
         document.format()
@@ -196,6 +320,30 @@ impl Registry {
         let document = self.document_info_by_url(url).await?;
         document.semantic_tokens()
     }
+    pub async fn document_symbols(&self, url: &Url) -> Option<DocumentSymbolResponse> {
+        let document = self.document_info_by_url(url).await?;
+        document.document_symbols()
+    }
+
+    /// Searches every module in the loaded module graph, since analysing a document adds all of
+    /// the modules it imports to the registry, not only the ones open in the editor.
+    pub async fn workspace_symbols(&self, query: &str) -> Option<Vec<SymbolInformation>> {
+        let documents: Vec<_> = self
+            .documents
+            .lock()
+            .await
+            .values()
+            .map(|pair| pair.info.clone())
+            .collect();
+
+        let symbols = documents
+            .iter()
+            .flat_map(|document| document.workspace_symbols(query))
+            .collect();
+
+        Some(symbols)
+    }
+
     pub async fn completion_items(
         &self,
         url: &Url,
@@ -218,3 +366,9 @@ impl Registry {
         Some(CompletionResponse::Array(completions))
     }
 }
+
+fn is_in_package_cache(url: &Url) -> bool {
+    url.to_file_path().map_or(false, |path| {
+        path.starts_with(roc_packaging::cache::roc_cache_dir())
+    })
+}
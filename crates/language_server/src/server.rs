@@ -23,12 +23,14 @@ struct RocServer {
 
 struct RocServerConfig {
     pub debounce_ms: Duration,
+    pub inlay_hint_max_length: usize,
 }
 
 impl Default for RocServerConfig {
     fn default() -> Self {
         Self {
             debounce_ms: Duration::from_millis(100),
+            inlay_hint_max_length: 50,
         }
     }
 }
@@ -56,6 +58,8 @@ impl RocServer {
         };
         let config = RocServerConfig {
             debounce_ms: Duration::from_millis(read_env_num("ROCLS_DEBOUNCE_MS").unwrap_or(100)),
+            inlay_hint_max_length: read_env_num("ROCLS_INLAY_HINT_MAX_LENGTH").unwrap_or(50)
+                as usize,
         };
         Self {
             state: RocServerState::new(config, Registry::new(registry_config)),
@@ -103,6 +107,43 @@ impl RocServer {
                 work_done_progress: None,
             },
         };
+        let references_provider = OneOf::Left(true);
+        let rename_provider = RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
+        let document_symbol_provider = DocumentSymbolOptions {
+            label: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
+        let signature_help_provider = SignatureHelpOptions {
+            // Arguments are separated by spaces, so we show the signature again for each one.
+            trigger_characters: Some(vec![" ".to_string()]),
+            retrigger_characters: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
+        let code_action_provider = CodeActionOptions {
+            code_action_kinds: Some(vec![
+                CodeActionKind::QUICKFIX,
+                CodeActionKind::REFACTOR_REWRITE,
+            ]),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+            resolve_provider: None,
+        };
+        let workspace_symbol_provider = WorkspaceSymbolOptions {
+            resolve_provider: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        };
         ServerCapabilities {
             text_document_sync: Some(text_document_sync),
             hover_provider: Some(hover_provider),
@@ -110,6 +151,13 @@ impl RocServer {
             document_formatting_provider: Some(OneOf::Right(document_formatting_provider)),
             semantic_tokens_provider: Some(semantic_tokens_provider),
             completion_provider: Some(completion_provider),
+            references_provider: Some(references_provider),
+            rename_provider: Some(OneOf::Right(rename_provider)),
+            document_symbol_provider: Some(OneOf::Right(document_symbol_provider)),
+            workspace_symbol_provider: Some(OneOf::Right(workspace_symbol_provider)),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            signature_help_provider: Some(signature_help_provider),
+            code_action_provider: Some(CodeActionProviderCapability::Options(code_action_provider)),
             ..ServerCapabilities::default()
         }
     }
@@ -276,6 +324,52 @@ impl LanguageServer for RocServer {
         unwind_async(self.state.registry.hover(&text_document.uri, position)).await
     }
 
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let SignatureHelpParams {
+            text_document_position_params:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            context: _,
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .signature_help(&text_document.uri, position),
+        )
+        .await
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let InlayHintParams {
+            text_document,
+            range,
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.inlay_hints(
+            &text_document.uri,
+            range,
+            self.state.config.inlay_hint_max_length,
+        ))
+        .await
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let CodeActionParams {
+            text_document,
+            range,
+            context: _,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.code_actions(&text_document.uri, range)).await
+    }
+
     async fn goto_definition(
         &self,
         params: GotoDefinitionParams,
@@ -321,6 +415,32 @@ impl LanguageServer for RocServer {
         unwind_async(self.state.registry.semantic_tokens(&text_document.uri)).await
     }
 
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let DocumentSymbolParams {
+            text_document,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.document_symbols(&text_document.uri)).await
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let WorkspaceSymbolParams {
+            query,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.workspace_symbols(&query)).await
+    }
+
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
         let doc = params.text_document_position;
         trace!("Got completion request.");
@@ -332,6 +452,60 @@ impl LanguageServer for RocServer {
         )
         .await
     }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let ReferenceParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            // Declarations are always included, as they can't be told apart from other references
+            context: _,
+            work_done_progress_params: _,
+            partial_result_params: _,
+        } = params;
+
+        unwind_async(self.state.registry.references(&text_document.uri, position)).await
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .prepare_rename(&text_document.uri, position),
+        )
+        .await?
+        .map_err(jsonrpc::Error::invalid_params)
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let RenameParams {
+            text_document_position:
+                TextDocumentPositionParams {
+                    text_document,
+                    position,
+                },
+            new_name,
+            work_done_progress_params: _,
+        } = params;
+
+        unwind_async(
+            self.state
+                .registry
+                .rename(&text_document.uri, position, &new_name),
+        )
+        .await?
+        .map_err(jsonrpc::Error::invalid_params)
+    }
 }
 
 async fn unwind_async<Fut, T>(future: Fut) -> tower_lsp::jsonrpc::Result<T>
@@ -612,4 +786,929 @@ mod tests {
         "#]]
         .assert_debug_eq(&actual);
     }
+
+    fn ranges(locations: Option<Vec<Location>>) -> Option<Vec<(u32, u32, u32)>> {
+        locations.map(|locations| {
+            locations
+                .into_iter()
+                .map(|location| {
+                    let Range { start, end } = location.range;
+                    (start.line, start.character, end.character)
+                })
+                .collect()
+        })
+    }
+
+    fn rename_edits(edit: Option<WorkspaceEdit>) -> Option<Vec<(u32, u32, u32, String)>> {
+        let mut edits: Vec<_> = edit?
+            .changes?
+            .into_values()
+            .flatten()
+            .map(|edit| {
+                let Range { start, end } = edit.range;
+                (start.line, start.character, end.character, edit.new_text)
+            })
+            .collect();
+        edits.sort();
+
+        Some(edits)
+    }
+
+    #[tokio::test]
+    async fn test_references_value() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            value : Str
+            value = "hi"
+
+            main =
+              other = value
+              Str.concat value other
+            "#};
+
+        let (inner, url) = test_setup(doc).await;
+        let actual = inner.registry.references(&url, Position::new(8, 14)).await;
+
+        expect![[r#"
+            Some(
+                [
+                    (
+                        3,
+                        0,
+                        5,
+                    ),
+                    (
+                        4,
+                        0,
+                        5,
+                    ),
+                    (
+                        7,
+                        10,
+                        15,
+                    ),
+                    (
+                        8,
+                        13,
+                        18,
+                    ),
+                ],
+            )
+        "#]]
+        .assert_debug_eq(&ranges(actual));
+    }
+
+    #[tokio::test]
+    async fn test_rename_record_pun() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            main =
+              name = "Roc"
+              { name }
+            "#};
+
+        let (inner, url) = test_setup(doc).await;
+        let actual = inner
+            .registry
+            .rename(&url, Position::new(4, 3), "language")
+            .await
+            .map(rename_edits);
+
+        expect![[r#"
+            Ok(
+                Some(
+                    [
+                        (
+                            4,
+                            2,
+                            6,
+                            "language",
+                        ),
+                        (
+                            5,
+                            4,
+                            8,
+                            "name: language",
+                        ),
+                    ],
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_rename_record_field() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            Person : { name : Str, age : U8 }
+
+            greet : Person -> Str
+            greet = \person -> person.name
+
+            main =
+              name = "Roc"
+              greet { name, age: 1 }
+            "#};
+
+        let (inner, url) = test_setup(doc).await;
+        let actual = inner
+            .registry
+            .rename(&url, Position::new(6, 28), "fullName")
+            .await
+            .map(rename_edits);
+
+        expect![[r#"
+            Ok(
+                Some(
+                    [
+                        (
+                            3,
+                            11,
+                            15,
+                            "fullName",
+                        ),
+                        (
+                            6,
+                            26,
+                            30,
+                            "fullName",
+                        ),
+                        (
+                            10,
+                            10,
+                            14,
+                            "fullName: name",
+                        ),
+                    ],
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_rename_invalid_name() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            main = 1
+            "#};
+
+        let (inner, url) = test_setup(doc).await;
+        let actual = inner
+            .registry
+            .rename(&url, Position::new(3, 0), "Main")
+            .await;
+
+        expect![[r#"
+            Err(
+                "`Main` is not a valid name for `main`",
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    /// The document symbols of a document, one per line, indented by how deeply they're nested.
+    async fn document_outline(registry: &Registry, url: &Url) -> Vec<String> {
+        let symbols = match registry.document_symbols(url).await {
+            Some(DocumentSymbolResponse::Nested(symbols)) => symbols,
+            _ => vec![],
+        };
+
+        fn outline(symbols: Vec<DocumentSymbol>, depth: usize, lines: &mut Vec<String>) {
+            for symbol in symbols {
+                lines.push(format!(
+                    "{}{:?} {} ({}) {}",
+                    "  ".repeat(depth),
+                    symbol.kind,
+                    symbol.name,
+                    symbol.selection_range.start.line,
+                    symbol.detail.unwrap_or_default()
+                ));
+                outline(symbol.children.unwrap_or_default(), depth + 1, lines);
+            }
+        }
+
+        let mut lines = vec![];
+        outline(symbols, 0, &mut lines);
+
+        lines
+    }
+
+    #[tokio::test]
+    async fn test_document_symbols() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            Shape : [Circle F64, Square F64]
+
+            Id := U64
+
+            Describe implements
+                describe : a -> Str where a implements Describe
+
+            area : Shape -> F64
+            area = \shape ->
+                expect 1 == 1
+
+                when shape is
+                    Circle r -> r * r * 3.14
+                    Square s -> s * s
+
+            expect area (Square 2) == 4
+            "#};
+
+        let (inner, url) = test_setup(doc).await;
+        let lines = document_outline(&inner.registry, &url).await;
+
+        expect![[r#"
+            [
+                "Struct Shape (3) [Circle F64, Square F64]",
+                "Class Id (5) ",
+                "Interface Describe (7) ",
+                "  Method describe (8) a -> Str where a implements Describe",
+                "Function area (10) Shape -> F64",
+                "  Event expect (12) 1 == 1",
+                "Event expect (18) area (Square 2) == 4",
+            ]
+        "#]]
+        .assert_debug_eq(&lines);
+    }
+
+    #[tokio::test]
+    async fn test_document_symbols_expects_in_branches() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            sign = \n ->
+                when n is
+                    0 ->
+                        expect n == 0
+
+                        "zero"
+
+                    _ ->
+                        if n > 0 then
+                            expect n > 0
+
+                            "positive"
+                        else
+                            expect n < 0
+
+                            "negative"
+            "#};
+
+        let (inner, url) = test_setup(doc).await;
+        let lines = document_outline(&inner.registry, &url).await;
+
+        expect![[r#"
+            [
+                "Function sign (3) ",
+                "  Event expect (6) n == 0",
+                "  Event expect (12) n > 0",
+                "  Event expect (16) n < 0",
+            ]
+        "#]]
+        .assert_debug_eq(&lines);
+    }
+
+    #[tokio::test]
+    async fn test_workspace_symbols() {
+        let doc = DOC_LIT.to_string()
+            + indoc! {r#"
+            parseNumber = \str -> Str.toU64 str
+
+            printNumber = \num -> Num.toStr num
+
+            main = parseNumber "1"
+            "#};
+
+        let (inner, _) = test_setup(doc).await;
+        let names = inner
+            .registry
+            .workspace_symbols("pnum")
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.container_name))
+            .collect::<Vec<_>>();
+
+        expect![[r#"
+            [
+                (
+                    "parseNumber",
+                    Some(
+                        "Test",
+                    ),
+                ),
+                (
+                    "printNumber",
+                    Some(
+                        "Test",
+                    ),
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&names);
+    }
+
+    async fn inlay_hint_labels(doc: &str, max_length: usize) -> Option<Vec<(u32, u32, String)>> {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + doc).await;
+        let range = Range::new(Position::new(0, 0), Position::new(100, 0));
+
+        let hints = inner.registry.inlay_hints(&url, range, max_length).await?;
+
+        let labels = hints
+            .into_iter()
+            .map(|hint| match hint.label {
+                InlayHintLabel::String(label) => {
+                    (hint.position.line, hint.position.character, label)
+                }
+                InlayHintLabel::LabelParts(_) => unreachable!(),
+            })
+            .collect();
+
+        Some(labels)
+    }
+
+    #[tokio::test]
+    async fn test_workspace_symbols_in_imported_modules() {
+        let dir = std::env::temp_dir().join("roc_language_server_workspace_symbols");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("Parse.roc"),
+            indoc! {r#"
+                interface Parse
+                  exposes [parseNumber]
+                  imports []
+
+                parseNumber = \str -> Str.toU64 str
+                "#},
+        )
+        .unwrap();
+
+        let doc = indoc! {r#"
+            interface Print
+              exposes []
+              imports [Parse]
+
+            printNumber = \num -> Num.toStr num
+
+            main = Parse.parseNumber "1"
+            "#};
+
+        // Only this module is opened, but the module it imports is searched too.
+        let url = Url::from_file_path(dir.join("Print.roc")).unwrap();
+        let inner = RocServerState::new(RocServerConfig::default(), Registry::default());
+        inner.change(&url, doc.to_string(), 0).await.unwrap();
+
+        let mut names = inner
+            .registry
+            .workspace_symbols("pnum")
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.container_name))
+            .collect::<Vec<_>>();
+        names.sort();
+
+        expect![[r#"
+            [
+                (
+                    "parseNumber",
+                    Some(
+                        "Parse",
+                    ),
+                ),
+                (
+                    "printNumber",
+                    Some(
+                        "Print",
+                    ),
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&names);
+    }
+
+    #[tokio::test]
+    async fn test_inlay_hints() {
+        let actual = inlay_hint_labels(
+            indoc! {r#"
+            annotated : Str
+            annotated = "hi"
+
+            add = \a, b -> a + b
+
+            main =
+              { x, y: second } = { x: 1u8, y: "two" }
+              when [x] is
+                [first] -> (add first 1, second)
+                _ -> (0, annotated)
+            "#},
+            50,
+        )
+        .await;
+
+        expect![[r#"
+            Some(
+                [
+                    (
+                        6,
+                        3,
+                        ": Num a, Num a -> Num a",
+                    ),
+                    (
+                        6,
+                        8,
+                        ": Num a",
+                    ),
+                    (
+                        6,
+                        11,
+                        ": Num a",
+                    ),
+                    (
+                        8,
+                        4,
+                        ": ( U8, Str )*",
+                    ),
+                    (
+                        9,
+                        5,
+                        ": U8",
+                    ),
+                    (
+                        9,
+                        16,
+                        ": Str",
+                    ),
+                    (
+                        11,
+                        10,
+                        ": U8",
+                    ),
+                ],
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_inlay_hints_max_length() {
+        let actual = inlay_hint_labels(
+            indoc! {r#"
+            person = { name: "Roc", age: 5u8, favoriteColors: ["purple"] }
+            "#},
+            20,
+        )
+        .await;
+
+        expect![[r#"
+            Some(
+                [
+                    (
+                        3,
+                        6,
+                        ": { age : U8, favorite…",
+                    ),
+                ],
+            )
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    /// Applies each code action offered for `range`, giving its title and the resulting document.
+    async fn code_action_results(doc: &str, range: Range) -> String {
+        let source = DOC_LIT.to_string() + doc;
+        let (inner, url) = test_setup(source.clone()).await;
+
+        let actions = inner
+            .registry
+            .code_actions(&url, range)
+            .await
+            .unwrap_or_default();
+
+        actions
+            .into_iter()
+            .map(|action| {
+                let CodeActionOrCommand::CodeAction(action) = action else {
+                    unreachable!()
+                };
+                let edits = action
+                    .edit
+                    .and_then(|edit| edit.changes)
+                    .and_then(|mut changes| changes.remove(&url))
+                    .unwrap_or_default();
+                let result = apply_edits(&source, edits);
+
+                format!("{}:\n{}", action.title, &result[DOC_LIT.len()..])
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn apply_edits(source: &str, mut edits: Vec<TextEdit>) -> String {
+        let offset = |position: Position| {
+            let line_start: usize = source
+                .split_inclusive('\n')
+                .take(position.line as usize)
+                .map(str::len)
+                .sum();
+
+            line_start + position.character as usize
+        };
+
+        edits.sort_by_key(|edit| std::cmp::Reverse(offset(edit.range.start)));
+
+        let mut result = source.to_string();
+        for edit in edits {
+            result.replace_range(
+                offset(edit.range.start)..offset(edit.range.end),
+                &edit.new_text,
+            );
+        }
+
+        result
+    }
+
+    #[tokio::test]
+    async fn test_code_action_unused_def() {
+        let actual = code_action_results(
+            indoc! {r#"
+            main =
+              unused = 1
+              2
+            "#},
+            Range::new(Position::new(4, 4), Position::new(4, 4)),
+        )
+        .await;
+
+        expect![[r#"
+            Remove unused `unused`:
+            main =
+              2
+
+            Add type annotation to `unused`:
+            main =
+              unused : Num *
+              unused = 1
+              2
+        "#]]
+        .assert_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_code_action_unused_import() {
+        let actual = code_action_results(
+            indoc! {r#"
+            import Dict exposing [Dict, empty]
+            import Set
+
+            main = empty {}
+            "#},
+            Range::new(Position::new(3, 0), Position::new(4, 0)),
+        )
+        .await;
+
+        expect![[r#"
+            Remove unused import `Dict`:
+            import Dict exposing [empty]
+            import Set
+
+            main = empty {}
+
+            Remove unused import:
+            import Dict exposing [Dict, empty]
+
+            main = empty {}
+        "#]]
+        .assert_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_code_action_missing_branches() {
+        let actual = code_action_results(
+            indoc! {r#"
+            describe : [Red, Green, Blue] -> Str
+            describe = \color ->
+                when color is
+                    Red -> "red"
+            "#},
+            Range::new(Position::new(4, 0), Position::new(6, 8)),
+        )
+        .await;
+
+        expect![[r#"
+            Remove unused `describe`:
+
+            Add missing `when` branches:
+            describe : [Red, Green, Blue] -> Str
+            describe = \color ->
+                when color is
+                    Red -> "red"
+                    Blue -> crash "todo"
+                    Green -> crash "todo"
+        "#]]
+        .assert_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_code_action_missing_branches_single_line() {
+        let actual = code_action_results(
+            indoc! {r#"
+            describe : [Red, Green] -> Str
+            describe = \color ->
+                when color is Red -> "red"
+            "#},
+            Range::new(Position::new(3, 0), Position::new(6, 0)),
+        )
+        .await;
+
+        expect![[r#"
+            Remove unused `describe`:
+
+            Add missing `when` branches:
+            describe : [Red, Green] -> Str
+            describe = \color ->
+                when color is Red -> "red"
+                Green -> crash "todo"
+        "#]]
+        .assert_eq(&actual);
+    }
+
+    /// The edits of the code action with the given title, as their range and new text. The
+    /// document can use `Dep`, which imports `Util` without the document importing it.
+    async fn code_action_edits(
+        dir_name: &str,
+        doc: &str,
+        range: Range,
+        title: &str,
+    ) -> Vec<(Position, Position, String)> {
+        let dir = std::env::temp_dir().join(dir_name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Util.roc"), "module [answer]\n\nanswer = 42\n").unwrap();
+        std::fs::write(
+            dir.join("Dep.roc"),
+            "module [double]\n\nimport Util\n\ndouble = \\n -> n * Util.answer\n",
+        )
+        .unwrap();
+
+        let url = Url::from_file_path(dir.join("Main.roc")).unwrap();
+        let inner = RocServerState::new(RocServerConfig::default(), Registry::default());
+        inner.change(&url, doc.to_string(), 0).await.unwrap();
+
+        let actions = inner
+            .registry
+            .code_actions(&url, range)
+            .await
+            .unwrap_or_default();
+
+        let action = actions
+            .into_iter()
+            .find_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) if action.title == title => Some(action),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("There was no code action titled {title:?}"));
+
+        action
+            .edit
+            .and_then(|edit| edit.changes)
+            .and_then(|mut changes| changes.remove(&url))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|edit| (edit.range.start, edit.range.end, edit.new_text))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_code_action_add_import() {
+        let edits = code_action_edits(
+            "roc_language_server_add_import",
+            indoc! {r#"
+            interface Main
+              exposes []
+              imports [Dep]
+
+            main = Dep.double Util.answer
+            "#},
+            Range::new(Position::new(4, 0), Position::new(5, 0)),
+            "Add `import Util`",
+        )
+        .await;
+
+        // Imports in the header aren't import statements, so the import goes before the first
+        // def, followed by a blank line.
+        expect![[r#"
+            [
+                (
+                    Position {
+                        line: 4,
+                        character: 0,
+                    },
+                    Position {
+                        line: 4,
+                        character: 0,
+                    },
+                    "import Util\n\n",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&edits);
+    }
+
+    #[tokio::test]
+    async fn test_code_action_add_import_after_imports() {
+        let edits = code_action_edits(
+            "roc_language_server_add_import_after_imports",
+            indoc! {r#"
+            module [main]
+
+            import Dep
+
+            main = Dep.double Util.answer
+            "#},
+            Range::new(Position::new(4, 0), Position::new(5, 0)),
+            "Add `import Util`",
+        )
+        .await;
+
+        // The import goes on the line after the last import.
+        expect![[r#"
+            [
+                (
+                    Position {
+                        line: 3,
+                        character: 0,
+                    },
+                    Position {
+                        line: 3,
+                        character: 0,
+                    },
+                    "import Util\n",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&edits);
+    }
+
+    /// The signature shown at each of `positions`, with the active parameter marked by `<` and `>`.
+    async fn signature_help_labels(doc: &str, positions: &[Position]) -> Vec<Option<String>> {
+        let (inner, url) = test_setup(DOC_LIT.to_string() + doc).await;
+
+        let mut labels = Vec::new();
+        for position in positions {
+            let label = inner
+                .registry
+                .signature_help(&url, *position)
+                .await
+                .map(|mut help| {
+                    let signature = help.signatures.remove(0);
+                    let mut label = signature.label;
+
+                    let active = signature.active_parameter.unwrap_or_default() as usize;
+                    if let Some(ParameterLabel::LabelOffsets([start, end])) = signature
+                        .parameters
+                        .unwrap_or_default()
+                        .get(active)
+                        .map(|parameter| parameter.label.clone())
+                    {
+                        label.insert(end as usize, '>');
+                        label.insert(start as usize, '<');
+                    }
+
+                    match signature.documentation {
+                        Some(Documentation::MarkupContent(docs)) => {
+                            format!("{} -- {}", label, docs.value)
+                        }
+                        _ => label,
+                    }
+                });
+
+            labels.push(label);
+        }
+
+        labels
+    }
+
+    #[tokio::test]
+    async fn test_signature_help() {
+        let actual = signature_help_labels(
+            indoc! {r#"
+            ## Joins a name and a greeting.
+            greet : Str, Str -> Str
+            greet = \greeting, name -> Str.concat greeting name
+
+            main = { a: greet "Hi" "Roc", b: ["a"] |> List.map (\x -> x) }
+
+            partial = greet
+            "#},
+            &[
+                Position::new(7, 15),
+                Position::new(7, 23),
+                Position::new(7, 52),
+                Position::new(9, 15),
+            ],
+        )
+        .await;
+
+        expect![[r#"
+            [
+                Some(
+                    "greet : <Str>, Str -> Str -- Joins a name and a greeting.",
+                ),
+                Some(
+                    "greet : Str, <Str> -> Str -- Joins a name and a greeting.",
+                ),
+                Some(
+                    "List.map : List Str, <(Str -> Str)> -> List Str -- Convert each element in the list to something new, by calling a conversion\nfunction on each of them. Then return a new list of the converted values.\n```roc\nexpect List.map [1, 2, 3] (\\num -> num + 1) == [2, 3, 4]\n\nexpect List.map [\"\", \"a\", \"bc\"] Str.isEmpty == [Bool.true, Bool.false, Bool.false]\n```",
+                ),
+                Some(
+                    "greet : <Str>, Str -> Str -- Joins a name and a greeting.",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&actual);
+    }
+
+    #[tokio::test]
+    async fn test_recover_from_syntax_error() {
+        let source = indoc! {r#"
+            broken = [1, 2
+
+            square = \x -> x * x
+
+            four = square 2
+            "#};
+
+        let (inner, url) = test_setup(DOC_LIT.to_string() + source).await;
+        let diagnostics: Vec<_> = inner
+            .registry
+            .diagnostics(&url)
+            .await
+            .into_iter()
+            .map(|diagnostic| {
+                let title = diagnostic
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+
+                (
+                    diagnostic.range.start.line,
+                    diagnostic.range.end.line,
+                    title,
+                )
+            })
+            .collect();
+
+        let hints = inlay_hint_labels(source, 50).await;
+
+        expect![[r#"
+            (
+                [
+                    (
+                        3,
+                        3,
+                        "UNFINISHED LIST",
+                    ),
+                    (
+                        3,
+                        3,
+                        "UNUSED DEFINITION",
+                    ),
+                    (
+                        7,
+                        7,
+                        "UNUSED DEFINITION",
+                    ),
+                ],
+                Some(
+                    [
+                        (
+                            3,
+                            6,
+                            ": *",
+                        ),
+                        (
+                            5,
+                            6,
+                            ": Num a -> Num a",
+                        ),
+                        (
+                            5,
+                            11,
+                            ": Num a",
+                        ),
+                        (
+                            7,
+                            4,
+                            ": Num *",
+                        ),
+                    ],
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&(diagnostics, hints));
+    }
 }
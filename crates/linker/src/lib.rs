@@ -177,6 +177,7 @@ impl ExposedSymbols {
             for (i, _) in exported_closure_types.enumerate() {
                 custom_names.extend([
                     format!("roc__{sym}_{i}_caller"),
+                    format!("roc__{sym}_{i}_drop"),
                     format!("roc__{sym}_{i}_size"),
                     format!("roc__{sym}_{i}_result_size"),
                 ]);
@@ -195,7 +196,10 @@ impl ExposedSymbols {
         for (top_level_value, lambda_set_id) in &exposed_to_host.lambda_sets {
             let sym = top_level_value.as_str(interns);
             let id = lambda_set_id.0;
-            custom_names.extend([format!("roc__{sym}_{id}_caller")]);
+            custom_names.extend([
+                format!("roc__{sym}_{id}_caller"),
+                format!("roc__{sym}_{id}_drop"),
+            ]);
         }
 
         // on windows (PE) binary search is used on the symbols,
@@ -218,6 +222,7 @@ impl ExposedSymbols {
             for closure_type in &self.exported_closure_types {
                 custom_names.extend([
                     format!("roc__{sym}_1_{closure_type}_caller"),
+                    format!("roc__{sym}_1_{closure_type}_drop"),
                     format!("roc__{sym}_1_{closure_type}_size"),
                     format!("roc__{sym}_1_{closure_type}_result_size"),
                 ]);
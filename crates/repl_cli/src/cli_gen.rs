@@ -2,7 +2,7 @@ use bumpalo::Bump;
 use inkwell::context::Context;
 use libloading::Library;
 use roc_build::link::llvm_module_to_dylib;
-use roc_collections::all::MutSet;
+use roc_collections::all::{MutMap, MutSet};
 use roc_error_macros::internal_error;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
@@ -11,19 +11,30 @@ use roc_load::{EntryPoint, MonomorphizedModule};
 use roc_mono::ir::OptLevel;
 use roc_mono::layout::STLayoutInterner;
 use roc_parse::ast::Expr;
-use roc_repl_eval::eval::jit_to_ast;
+use roc_repl_eval::eval::{jit_to_ast, jit_to_bool};
 use roc_repl_eval::gen::{format_answer, ReplOutput};
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_std::RocStr;
 use roc_target::Target;
 use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 use roc_types::subs::Subs;
+use std::time::{Duration, Instant};
 
 pub fn eval_llvm(
-    mut loaded: MonomorphizedModule<'_>,
+    loaded: MonomorphizedModule<'_>,
     target: Target,
     opt_level: OptLevel,
 ) -> Option<ReplOutput> {
+    eval_llvm_timed(loaded, target, opt_level).map(|(output, _)| output)
+}
+
+/// Like [eval_llvm], but also returns how long the compiled code took to run. This excludes the
+/// time spent compiling it.
+pub fn eval_llvm_timed(
+    mut loaded: MonomorphizedModule<'_>,
+    target: Target,
+    opt_level: OptLevel,
+) -> Option<(ReplOutput, Duration)> {
     let arena = Bump::new();
 
     debug_assert_eq!(loaded.exposed_to_host.top_level_values.len(), 1);
@@ -52,23 +63,13 @@ pub fn eval_llvm(
 
     let interns = loaded.interns.clone();
 
-    #[cfg(not(all(
-        any(target_os = "linux", target_os = "macos"),
-        any(target_arch = "x86_64", target_arch = "aarch64")
-    )))]
     let (lib, main_fn_name, subs, layout_interner) =
-        mono_module_to_dylib_llvm(&arena, target, loaded, opt_level)
-            .expect("we produce a valid Dylib");
+        mono_module_to_dylib(&arena, target, loaded, opt_level).expect("we produce a valid Dylib");
 
-    #[cfg(all(
-        any(target_os = "linux", target_os = "macos"),
-        any(target_arch = "x86_64", target_arch = "aarch64")
-    ))]
-    let (lib, main_fn_name, subs, layout_interner) =
-        mono_module_to_dylib_asm(&arena, target, loaded, opt_level)
-            .expect("we produce a valid Dylib");
-
-    let mut app = CliApp { lib };
+    let mut app = CliApp {
+        lib,
+        run_time: Duration::ZERO,
+    };
 
     let expr = jit_to_ast(
         &arena,
@@ -84,14 +85,39 @@ pub fn eval_llvm(
 
     let expr_str = format_answer(&arena, expr).to_string();
 
-    Some(ReplOutput {
-        expr: expr_str,
-        expr_type: expr_type_str,
-    })
+    Some((
+        ReplOutput {
+            expr: expr_str,
+            expr_type: expr_type_str,
+        },
+        app.run_time,
+    ))
+}
+
+/// Compile and run the condition of an `expect`, and return whether it held.
+/// This is [None] if the app crashed.
+pub fn eval_llvm_condition(
+    loaded: MonomorphizedModule<'_>,
+    target: Target,
+    opt_level: OptLevel,
+) -> Option<bool> {
+    let arena = Bump::new();
+
+    let (lib, main_fn_name, _, _) =
+        mono_module_to_dylib(&arena, target, loaded, opt_level).expect("we produce a valid Dylib");
+
+    let mut app = CliApp {
+        lib,
+        run_time: Duration::ZERO,
+    };
+
+    jit_to_bool(&mut app, main_fn_name)
 }
 
 struct CliApp {
     lib: Library,
+    /// Time spent running the app's main function and reading its result
+    run_time: Duration,
 }
 
 struct CliMemory;
@@ -106,7 +132,11 @@ impl<'a> ReplApp<'a> for CliApp {
         F: FnMut(&'a Self::Memory, Return) -> Expr<'a>,
         Self::Memory: 'a,
     {
-        run_jit_function!(self.lib, main_fn_name, Return, |v| transform(&CliMemory, v))
+        let start = Instant::now();
+        let expr = run_jit_function!(self.lib, main_fn_name, Return, |v| transform(&CliMemory, v));
+        self.run_time += start.elapsed();
+
+        expr
     }
 
     /// Run user code that returns a struct or union, whose size is provided as an argument
@@ -121,7 +151,12 @@ impl<'a> ReplApp<'a> for CliApp {
         Self::Memory: 'a,
     {
         let mut t = |v| transform(&CliMemory, v);
-        run_jit_function_dynamic_type!(self.lib, main_fn_name, ret_bytes, t)
+
+        let start = Instant::now();
+        let result = run_jit_function_dynamic_type!(self.lib, main_fn_name, ret_bytes, t);
+        self.run_time += start.elapsed();
+
+        result
     }
 }
 
@@ -169,6 +204,25 @@ impl ReplAppMemory for CliMemory {
     }
 }
 
+fn mono_module_to_dylib<'a>(
+    arena: &'a Bump,
+    target: Target,
+    loaded: MonomorphizedModule<'a>,
+    opt_level: OptLevel,
+) -> Result<(libloading::Library, &'a str, Subs, STLayoutInterner<'a>), libloading::Error> {
+    #[cfg(not(all(
+        any(target_os = "linux", target_os = "macos"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    return mono_module_to_dylib_llvm(arena, target, loaded, opt_level);
+
+    #[cfg(all(
+        any(target_os = "linux", target_os = "macos"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    return mono_module_to_dylib_asm(arena, target, loaded, opt_level);
+}
+
 #[cfg_attr(
     all(
         any(target_os = "linux", target_os = "macos"),
@@ -183,12 +237,15 @@ fn mono_module_to_dylib_llvm<'a>(
     opt_level: OptLevel,
 ) -> Result<(libloading::Library, &'a str, Subs, STLayoutInterner<'a>), libloading::Error> {
     let MonomorphizedModule {
+        module_id,
         procedures,
         host_exposed_lambda_sets,
+        symbol_regions,
         entry_point,
         interns,
         subs,
         layout_interner,
+        sources,
         ..
     } = loaded;
 
@@ -202,7 +259,11 @@ fn mono_module_to_dylib_llvm<'a>(
     let (module_pass, function_pass) =
         roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
-    let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let (root_path, _) = &sources[&module_id];
+    let (dibuilder, compile_unit) =
+        roc_gen_llvm::llvm::build::Env::new_debug_info(module, root_path);
+    let debug_sources =
+        roc_gen_llvm::llvm::debug_info::DebugSources::new(&dibuilder, &sources, &symbol_regions);
 
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
@@ -210,6 +271,7 @@ fn mono_module_to_dylib_llvm<'a>(
         builder: &builder,
         dibuilder: &dibuilder,
         compile_unit: &compile_unit,
+        debug_sources: &debug_sources,
         context: &context,
         interns,
         module,
@@ -309,6 +371,7 @@ fn mono_module_to_dylib_asm<'a>(
         exposed_to_host: exposed_to_host.top_level_values.keys().copied().collect(),
         lazy_literals,
         mode: roc_gen_dev::AssemblyBackendMode::Repl,
+        sources: MutMap::default(),
     };
 
     let module_object =
@@ -5,19 +5,29 @@ use bumpalo::Bump;
 use const_format::concatcp;
 use roc_load::MonomorphizedModule;
 use roc_mono::ir::OptLevel;
-use roc_repl_eval::gen::Problems;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_repl_eval::gen::{Problems, ReplPackage};
 use roc_repl_ui::colors::{CYAN, END_COL};
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
-use roc_repl_ui::{format_output, is_incomplete, CONT_PROMPT, PROMPT, SHORT_INSTRUCTIONS, TIPS};
-use roc_reporting::report::{to_file_problem_report_string, ANSI_STYLE_CODES, DEFAULT_PALETTE};
+use roc_repl_ui::{
+    expect_failed, format_dbg_output, format_expect_output, format_output, format_time_output,
+    is_incomplete, CONT_PROMPT, PROMPT, SHORT_INSTRUCTIONS, TIPS,
+};
+use roc_reporting::report::{
+    to_file_problem_report_string, to_https_problem_report_string, ANSI_STYLE_CODES,
+    DEFAULT_PALETTE,
+};
 use roc_target::Target;
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
 use rustyline_derive::{Completer, Helper, Hinter};
 use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
 use target_lexicon::Triple;
 
-use crate::cli_gen::eval_llvm;
+use crate::cli_gen::{eval_llvm, eval_llvm_condition, eval_llvm_timed};
 
 pub const WELCOME_MESSAGE: &str = concatcp!(
     "\n  The rockin' ",
@@ -34,17 +44,36 @@ pub struct ReplHelper {
     state: ReplState,
 }
 
-pub fn main() -> i32 {
+/// Start the REPL. Each of `packages` is a package or platform, given as
+/// `shorthand=path/or/url/main.roc`, whose modules can be imported.
+pub fn main(packages: &[&str]) -> i32 {
     use rustyline::error::ReadlineError;
     use rustyline::Editor;
 
+    let cache_dir = cache::roc_cache_dir();
+    let packages: Result<Vec<_>, _> = packages
+        .iter()
+        .map(|arg| resolve_package(arg, RocCacheDir::Persistent(cache_dir.as_path())))
+        .collect();
+
+    let packages = match packages {
+        Ok(packages) => packages,
+        Err(report) => {
+            eprintln!("{report}");
+            return 1;
+        }
+    };
+
     // To debug rustyline:
     // <UNCOMMENT> env_logger::init();
     // <RUN WITH:> RUST_LOG=rustyline=debug cargo run repl 2> debug.log
     print!("{WELCOME_MESSAGE}{SHORT_INSTRUCTIONS}");
 
     let mut editor = Editor::<ReplHelper>::new();
-    let repl_helper = ReplHelper::default();
+    let repl_helper = ReplHelper {
+        validator: InputValidator::default(),
+        state: ReplState::with_packages(packages),
+    };
     editor.set_helper(Some(repl_helper));
     let target = Triple::host().into();
     let mut arena = Bump::new();
@@ -71,6 +100,30 @@ pub fn main() -> i32 {
                             println!("{output}");
                         }
                     }
+                    ReplAction::Dbg {
+                        src,
+                        opt_mono,
+                        problems,
+                    } => {
+                        println!("{}", evaluate_dbg(src, opt_mono, problems, target));
+                    }
+                    ReplAction::Expect {
+                        src,
+                        opt_mono,
+                        problems,
+                        lookups,
+                    } => {
+                        println!(
+                            "{}",
+                            evaluate_expect(src, opt_mono, problems, lookups, repl_state, target)
+                        );
+                    }
+                    ReplAction::Time { opt_mono, problems } => {
+                        println!("{}", evaluate_timed(opt_mono, problems, target));
+                    }
+                    ReplAction::Output(output) => {
+                        println!("{output}");
+                    }
                     ReplAction::Exit => {
                         return 0;
                     }
@@ -103,6 +156,44 @@ pub fn main() -> i32 {
     }
 }
 
+/// Find the root module of a `--package`, downloading it into `roc_cache_dir` first if it's a
+/// URL that isn't cached yet. Problems are returned as a report to print.
+pub fn resolve_package(arg: &str, roc_cache_dir: RocCacheDir<'_>) -> Result<ReplPackage, String> {
+    const DEFAULT_SHORTHAND: &str = "pkg";
+    const DEFAULT_MAIN_NAME: &str = "main.roc";
+
+    let (shorthand, src) = match arg.split_once('=') {
+        Some((shorthand, src)) if !shorthand.contains(['/', '\\', ':']) => (shorthand, src),
+        _ => (DEFAULT_SHORTHAND, arg),
+    };
+
+    let root_module = if src.starts_with("https://") {
+        match cache::install_package(roc_cache_dir, src) {
+            // You can optionally specify the root module using the URL fragment,
+            // e.g. #foo.roc (defaults to main.roc)
+            Ok((package_dir, opt_root_module)) => {
+                package_dir.join(opt_root_module.unwrap_or(DEFAULT_MAIN_NAME))
+            }
+            Err(problem) => {
+                return Err(to_https_problem_report_string(
+                    src,
+                    problem,
+                    PathBuf::from(src),
+                ));
+            }
+        }
+    } else {
+        // The REPL's module has no directory of its own, so make the path absolute.
+        fs::canonicalize(src)
+            .map_err(|err| to_file_problem_report_string(PathBuf::from(src), err.kind()))?
+    };
+
+    Ok(ReplPackage {
+        shorthand: shorthand.to_string(),
+        root_module,
+    })
+}
+
 pub fn evaluate(
     opt_mono: Option<MonomorphizedModule<'_>>,
     problems: Problems,
@@ -112,6 +203,61 @@ pub fn evaluate(
     format_output(ANSI_STYLE_CODES, opt_output, problems)
 }
 
+pub fn evaluate_timed(
+    opt_mono: Option<MonomorphizedModule<'_>>,
+    problems: Problems,
+    target: Target,
+) -> String {
+    // Only time running the code, not compiling it
+    let (opt_output, elapsed) = opt_mono
+        .and_then(|mono| eval_llvm_timed(mono, target, OptLevel::Normal))
+        .map_or((None, Duration::ZERO), |(output, elapsed)| {
+            (Some(output), elapsed)
+        });
+
+    format_time_output(ANSI_STYLE_CODES, opt_output, problems, elapsed)
+}
+
+pub fn evaluate_dbg(
+    src: &str,
+    opt_mono: Option<MonomorphizedModule<'_>>,
+    problems: Problems,
+    target: Target,
+) -> String {
+    let opt_output = opt_mono.and_then(|mono| eval_llvm(mono, target, OptLevel::Normal));
+    format_dbg_output(ANSI_STYLE_CODES, src, opt_output, problems)
+}
+
+pub fn evaluate_expect(
+    src: &str,
+    opt_mono: Option<MonomorphizedModule<'_>>,
+    problems: Problems,
+    lookups: Vec<&str>,
+    repl_state: &ReplState,
+    target: Target,
+) -> String {
+    let opt_held = opt_mono.and_then(|mono| eval_llvm_condition(mono, target, OptLevel::Normal));
+
+    // The looked up values are only needed to report a failure, so only compile them then.
+    let lookups = if expect_failed(opt_held, &problems) {
+        let arena = Bump::new();
+
+        lookups
+            .into_iter()
+            .filter_map(|ident| {
+                let mono = repl_state.compile_lookup(&arena, ident, target, DEFAULT_PALETTE)?;
+                let output = eval_llvm(mono, target, OptLevel::Normal)?;
+
+                Some((ident, output))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    format_expect_output(ANSI_STYLE_CODES, src, opt_held, problems, lookups)
+}
+
 #[derive(Default)]
 struct InputValidator {}
 
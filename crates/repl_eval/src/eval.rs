@@ -86,6 +86,20 @@ pub fn jit_to_ast<'a, A: ReplApp<'a>>(
     }
 }
 
+/// JIT execute the given main function, which returns a `Bool`, and return its result.
+/// This is [None] if the app crashed.
+pub fn jit_to_bool<'a, A: ReplApp<'a>>(app: &mut A, main_fn_name: &str) -> Option<bool> {
+    let mut result = None;
+
+    app.call_function(main_fn_name, |_, value: bool| {
+        result = Some(value);
+
+        Expr::Record(Collection::empty())
+    });
+
+    result
+}
+
 #[derive(Debug)]
 enum NewtypeKind {
     Tag(TagName),
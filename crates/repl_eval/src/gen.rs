@@ -5,13 +5,18 @@ use roc_problem::Severity;
 use roc_reporting::report::Palette;
 use std::path::PathBuf;
 
+use roc_collections::MutMap;
 use roc_fmt::annotation::Formattable;
 use roc_fmt::annotation::{Newlines, Parens};
-use roc_load::{LoadingProblem, MonomorphizedModule};
+use roc_load::{LoadedModule, LoadingProblem, MonomorphizedModule};
+use roc_module::symbol::{Interns, ModuleId};
 use roc_parse::ast::Expr;
 use roc_region::all::LineInfo;
-use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
+use roc_reporting::report::{
+    can_problem, to_file_problem_report_string, type_problem, RocDocAllocator,
+};
 use roc_solve::FunctionKind;
+use roc_solve_problem::TypeError;
 use roc_target::Target;
 
 #[derive(Debug)]
@@ -33,6 +38,15 @@ pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'_>) -> &'a str {
     }
 }
 
+/// A package whose modules can be imported in the REPL by its shorthand, as in
+/// `import pkg.Module`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplPackage {
+    pub shorthand: String,
+    /// The package's root module on disk. Packages given by URL have been downloaded already.
+    pub root_module: PathBuf,
+}
+
 #[derive(Default, Debug)]
 pub struct Problems {
     pub errors: Vec<String>,
@@ -47,6 +61,7 @@ impl Problems {
 
 pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
+    packages: &[ReplPackage],
     defs: I,
     expr: &str,
     target: Target,
@@ -54,7 +69,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
 ) -> (Option<MonomorphizedModule<'a>>, Problems) {
     let filename = PathBuf::from("replfile.roc");
     let src_dir = PathBuf::from(".");
-    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr);
+    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, packages, defs, expr);
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
         filename,
@@ -80,17 +95,8 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
                 (m.can_problems, m.type_problems)
             );
         }
-        Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
-            return (
-                None,
-                Problems {
-                    errors: vec![report],
-                    warnings: Vec::new(),
-                },
-            );
-        }
-        Err(e) => {
-            todo!("error while loading module: {:?}", e)
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return (None, loading_problem_report(problem));
         }
     };
 
@@ -102,6 +108,103 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         ..
     } = &mut loaded;
 
+    let problems = module_problems(
+        module_src,
+        bytes_before_expr,
+        sources,
+        can_problems,
+        type_problems,
+        interns,
+        palette,
+    );
+
+    (Some(loaded), problems)
+}
+
+/// Like [compile_to_mono], but only type checks the module, for when the expression's type is
+/// needed without evaluating it.
+pub fn compile_to_typed<'a, 'i, I: Iterator<Item = &'i str>>(
+    arena: &'a Bump,
+    packages: &[ReplPackage],
+    defs: I,
+    expr: &str,
+    target: Target,
+    palette: Palette,
+) -> (Option<LoadedModule>, Problems) {
+    let filename = PathBuf::from("replfile.roc");
+    let src_dir = PathBuf::from(".");
+    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, packages, defs, expr);
+    let loaded = roc_load::load_and_typecheck_str(
+        arena,
+        filename,
+        module_src,
+        src_dir,
+        None,
+        target,
+        FunctionKind::LambdaSet,
+        roc_reporting::report::RenderTarget::ColorTerminal,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        palette,
+    );
+
+    let mut loaded = match loaded {
+        Ok(v) => v,
+        Err(problem) => return (None, loading_problem_report(problem)),
+    };
+
+    let LoadedModule {
+        interns,
+        sources,
+        syntax_problems,
+        can_problems,
+        type_problems,
+        ..
+    } = &mut loaded;
+
+    let mut problems = module_problems(
+        module_src,
+        bytes_before_expr,
+        sources,
+        can_problems,
+        type_problems,
+        interns,
+        palette,
+    );
+
+    // Type checking recovers from syntax errors, which were reported already.
+    for reports in syntax_problems.drain().map(|(_, reports)| reports) {
+        problems.errors.splice(0..0, reports);
+    }
+
+    (Some(loaded), problems)
+}
+
+fn loading_problem_report(problem: LoadingProblem) -> Problems {
+    let report = match problem {
+        LoadingProblem::FormattedReport(report) => report,
+        LoadingProblem::FileProblem { filename, error } => {
+            to_file_problem_report_string(filename, error)
+        }
+        // TODO: tighten up the types here, we should always end up with a
+        // formatted report from load.
+        problem => format!("Failed with error: {problem:?}"),
+    };
+
+    Problems {
+        errors: vec![report],
+        warnings: Vec::new(),
+    }
+}
+
+fn module_problems(
+    module_src: &str,
+    bytes_before_expr: usize,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    interns: &Interns,
+    palette: Palette,
+) -> Problems {
     let mut problems = Problems::default();
 
     let errors = &mut problems.errors;
@@ -165,19 +268,39 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         }
     }
 
-    (Some(loaded), problems)
+    problems
 }
 
 fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
+    packages: &[ReplPackage],
     defs: I,
     expr: &str,
 ) -> (usize, &'a str) {
-    const REPL_MODULE_HEADER: &str = "app \"app\" provides [replOutput] to \"./platform\"\n\n";
     const REPL_MODULE_MAIN_DEF: &str = "replOutput =\n";
     const INDENT: &str = "    ";
 
-    let mut buffer = bumpalo::collections::string::String::from_str_in(REPL_MODULE_HEADER, arena);
+    let mut buffer = bumpalo::collections::string::String::from_str_in("app \"app\" ", arena);
+
+    if !packages.is_empty() {
+        buffer.push_str("packages {");
+
+        for (index, package) in packages.iter().enumerate() {
+            if index > 0 {
+                buffer.push(',');
+            }
+
+            // Escape the path, so that it can go in a string literal.
+            let path = package.root_module.to_string_lossy();
+            let path = path.replace('\\', "\\\\").replace('"', "\\\"");
+
+            buffer.push_str(&format!(" {}: \"{path}\"", package.shorthand));
+        }
+
+        buffer.push_str(" } ");
+    }
+
+    buffer.push_str("provides [replOutput] to \"./platform\"\n\n");
 
     for line in defs {
         // don't indent the defs
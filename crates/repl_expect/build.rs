@@ -0,0 +1,81 @@
+use roc_command_utils::zig;
+use std::env;
+use std::path::PathBuf;
+
+use wasi_libc_sys::{WASI_COMPILER_RT_PATH, WASI_LIBC_PATH};
+
+const HOST_FILENAME: &str = "wasm_expect_host";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // The host is only needed to run expects with the Wasm backend
+    if env::var("CARGO_FEATURE_RUN_WASM32").is_ok() {
+        build_wasm_expect_host();
+    }
+}
+
+fn build_wasm_expect_host() {
+    let source_path = format!("src/{HOST_FILENAME}.c");
+    println!("cargo:rerun-if-changed={source_path}");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    // Zig can produce *either* an object containing relocations OR an object containing libc code
+    // But we want both, so we have to compile twice with different flags, then link them
+    let mut host_obj = PathBuf::from(&out_dir).join(format!("{HOST_FILENAME}_obj"));
+    host_obj.set_extension("wasm");
+
+    run_zig(&[
+        "build-lib",
+        "-target",
+        "wasm32-wasi-musl",
+        "-lc",
+        &source_path,
+        &format!("-femit-bin={}", host_obj.to_str().unwrap()),
+    ]);
+
+    let mut outfile = PathBuf::from(&out_dir).join(HOST_FILENAME);
+    outfile.set_extension("wasm");
+
+    let builtins_host_tempfile = roc_bitcode::host_wasm_tempfile()
+        .expect("failed to write host builtins object to tempfile");
+
+    run_zig(&[
+        "wasm-ld",
+        builtins_host_tempfile.path().to_str().unwrap(),
+        host_obj.to_str().unwrap(),
+        WASI_COMPILER_RT_PATH,
+        WASI_LIBC_PATH,
+        "-o",
+        outfile.to_str().unwrap(),
+        "--no-entry",
+        "--relocatable",
+    ]);
+
+    // Extend the lifetime of the tempfile so it doesn't get dropped
+    // (and thus deleted) before the Zig process is done using it!
+    let _ = builtins_host_tempfile;
+}
+
+// Run cargo with -vv to see commands printed out
+fn run_zig(args: &[&str]) {
+    let mut zig_cmd = zig();
+
+    let full_zig_cmd = zig_cmd.args(args);
+    println!("{full_zig_cmd:?}");
+
+    let zig_cmd_output = full_zig_cmd.output().unwrap();
+
+    if !zig_cmd_output.status.success() {
+        eprintln!(
+            "stdout:\n{}",
+            String::from_utf8_lossy(&zig_cmd_output.stdout)
+        );
+        eprintln!(
+            "stderr:\n{}",
+            String::from_utf8_lossy(&zig_cmd_output.stderr)
+        );
+        panic!("zig call failed with status {:?}", zig_cmd_output.status);
+    }
+}
@@ -5,6 +5,8 @@ use roc_target::Target;
 
 pub(crate) struct ExpectMemory {
     pub(crate) start: *const u8,
+    /// The size of a pointer in the app that wrote the values, which may not be the host's
+    pub(crate) ptr_size: usize,
 }
 
 macro_rules! deref_number {
@@ -32,37 +34,58 @@ impl ReplAppMemory for ExpectMemory {
     deref_number!(deref_u32, u32);
     deref_number!(deref_u64, u64);
     deref_number!(deref_u128, u128);
-    deref_number!(deref_usize, usize);
+
+    fn deref_usize(&self, addr: usize) -> usize {
+        match self.ptr_size {
+            4 => self.deref_u32(addr) as usize,
+            _ => self.deref_u64(addr) as usize,
+        }
+    }
 
     deref_number!(deref_i8, i8);
     deref_number!(deref_i16, i16);
     deref_number!(deref_i32, i32);
     deref_number!(deref_i64, i64);
     deref_number!(deref_i128, i128);
-    deref_number!(deref_isize, isize);
+
+    fn deref_isize(&self, addr: usize) -> isize {
+        match self.ptr_size {
+            4 => self.deref_i32(addr) as isize,
+            _ => self.deref_i64(addr) as isize,
+        }
+    }
 
     deref_number!(deref_f32, f32);
     deref_number!(deref_f64, f64);
 
     fn deref_str(&self, addr: usize) -> &str {
-        const WIDTH: usize = 3 * std::mem::size_of::<usize>();
+        let width = 3 * self.ptr_size;
 
-        let last_byte_addr = addr + WIDTH - 1;
+        let last_byte_addr = addr + width - 1;
         let last_byte = self.deref_i8(last_byte_addr);
 
         let is_small = last_byte < 0;
 
-        if is_small {
+        if is_small && self.ptr_size == std::mem::size_of::<usize>() {
             let ptr = unsafe { self.start.add(addr) };
             let roc_str: &RocStr = unsafe { &*ptr.cast() };
 
             roc_str.as_str()
+        } else if is_small {
+            // We can't use RocStr for an app with a different pointer size
+            let length = (last_byte & 0x7f) as usize;
+
+            unsafe {
+                let ptr = self.start.add(addr);
+                let slice = std::slice::from_raw_parts(ptr, length);
+
+                std::str::from_utf8_unchecked(slice)
+            }
         } else {
             let offset = self.deref_usize(addr);
             let seamless_slice_mask = usize::MAX >> 1;
-            let length =
-                self.deref_usize(addr + std::mem::size_of::<usize>()) & seamless_slice_mask;
-            let _capacity = self.deref_usize(addr + 2 * std::mem::size_of::<usize>());
+            let length = self.deref_usize(addr + self.ptr_size) & seamless_slice_mask;
+            let _capacity = self.deref_usize(addr + 2 * self.ptr_size);
 
             unsafe {
                 let ptr = self.start.add(offset);
@@ -102,18 +125,26 @@ impl<'a> ReplApp<'a> for ExpectReplApp<'a> {
         let result: Return = unsafe {
             let ptr = self.memory.start.add(self.offset);
             let ptr: *const Return = std::mem::transmute(ptr);
-            ptr.read()
+            ptr.read_unaligned()
         };
 
         transform(self.memory, result)
     }
 
-    fn call_function_returns_roc_list<F>(&mut self, main_fn_name: &str, transform: F) -> Expr<'a>
+    fn call_function_returns_roc_list<F>(
+        &mut self,
+        _main_fn_name: &str,
+        mut transform: F,
+    ) -> Expr<'a>
     where
         F: FnMut(&'a Self::Memory, (usize, usize, usize)) -> Expr<'a>,
         Self::Memory: 'a,
     {
-        self.call_function(main_fn_name, transform)
+        let ptr_size = self.memory.ptr_size;
+        let [elements, length, capacity] =
+            [0, 1, 2].map(|i| self.memory.deref_usize(self.offset + i * ptr_size));
+
+        transform(self.memory, (elements, length, capacity))
     }
 
     fn call_function_returns_roc_str<T, F>(
@@ -126,7 +157,8 @@ impl<'a> ReplApp<'a> for ExpectReplApp<'a> {
         F: Fn(&'a Self::Memory, usize) -> T,
         Self::Memory: 'a,
     {
-        self.call_function_dynamic_size(main_fn_name, 24, transform)
+        let ret_bytes = 3 * self.memory.ptr_size;
+        self.call_function_dynamic_size(main_fn_name, ret_bytes, transform)
     }
 
     /// Run user code that returns a struct or union, whose size is provided as an argument
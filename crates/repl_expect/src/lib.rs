@@ -35,7 +35,10 @@ pub fn get_values<'a>(
     let mut result = Vec::with_capacity(number_of_lookups);
     let mut result_vars = Vec::with_capacity(number_of_lookups);
 
-    let memory = ExpectMemory { start };
+    let memory = ExpectMemory {
+        start,
+        ptr_size: target.ptr_size(),
+    };
 
     let app = ExpectReplApp {
         memory: arena.alloc(memory),
@@ -45,13 +48,13 @@ pub fn get_values<'a>(
     let app = arena.alloc(app);
 
     for i in 0..number_of_lookups {
-        let size_of_lookup_header = 8 /* pointer to value */ + 4 /* type variable */;
+        let size_of_lookup_header = target.ptr_size() /* pointer to value */ + 4 /* type variable */;
 
         let start = app
             .memory
             .deref_usize(start_offset + i * size_of_lookup_header);
         let variable = app.memory.deref_u32(
-            start_offset + i * size_of_lookup_header + 8, /* skip the pointer */
+            start_offset + i * size_of_lookup_header + target.ptr_size(), /* skip the pointer */
         );
         let variable = unsafe { Variable::from_index(variable) };
 
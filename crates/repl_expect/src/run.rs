@@ -9,19 +9,20 @@ use std::{
 use bumpalo::collections::Vec as BumpVec;
 use bumpalo::Bump;
 use inkwell::context::Context;
-use roc_build::link::llvm_module_to_dylib;
+use roc_build::link::{dev_object_to_dylib, llvm_module_to_dylib};
 use roc_can::expr::ExpectLookup;
 use roc_collections::{MutMap, MutSet, VecMap};
 use roc_error_macros::internal_error;
+use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::{
     llvm::{build::LlvmBackendMode, externs::add_default_roc_externs},
     run_roc::RocCallResult,
     run_roc_dylib,
 };
-use roc_load::{Expectations, MonomorphizedModule};
+use roc_load::{Expectations, MonomorphizedModule, ToplevelExpects};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::{
-    ir::OptLevel,
+    ir::{CrashTag, OptLevel},
     layout::{GlobalLayoutInterner, STLayoutInterner},
 };
 use roc_region::all::Region;
@@ -248,15 +249,44 @@ fn run_expect_pure<'a, W: std::io::Write>(
     shared_memory: &mut ExpectMemory,
     expect: ToplevelExpect<'_>,
 ) -> std::io::Result<bool> {
-    use roc_gen_llvm::try_run_jit_function;
-
     let sequence = ExpectSequence::new(shared_memory.ptr.cast());
 
-    let result: Result<(), (String, _)> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+    let result = call_toplevel_expect(lib, expect);
 
     let shared_memory_ptr: *const u8 = shared_memory.ptr.cast();
 
-    if result.is_err() || sequence.count_failures() > 0 {
+    report_toplevel_expect(
+        writer,
+        render_target,
+        arena,
+        interns,
+        layout_interner,
+        expectations,
+        expect,
+        result.map_err(|(roc_panic_message, _roc_panic_tag)| roc_panic_message),
+        Target::from(target_lexicon::Triple::host()),
+        shared_memory_ptr,
+        sequence.count_failures(),
+    )
+}
+
+/// Render a toplevel expect that crashed, or the failures it wrote to the expect buffer.
+/// Returns whether the expect passed.
+#[allow(clippy::too_many_arguments)]
+fn report_toplevel_expect<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    expect: ToplevelExpect<'_>,
+    result: Result<(), String>,
+    target: Target,
+    buffer: *const u8,
+    failure_count: usize,
+) -> std::io::Result<bool> {
+    if result.is_err() || failure_count > 0 {
         let module_id = expect.symbol.module_id();
         let data = expectations.get_mut(&module_id).unwrap();
 
@@ -266,12 +296,12 @@ fn run_expect_pure<'a, W: std::io::Write>(
 
         let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
-        if let Err((roc_panic_message, _roc_panic_tag)) = result {
+        if let Err(roc_panic_message) = result {
             renderer.render_panic(writer, &roc_panic_message, expect.region)?;
         } else {
-            let mut offset = ExpectSequence::START_OFFSET;
+            let mut offset = ExpectSequence::start_offset(target);
 
-            for _ in 0..sequence.count_failures() {
+            for _ in 0..failure_count {
                 offset = render_expect_failure(
                     writer,
                     &renderer,
@@ -280,7 +310,8 @@ fn run_expect_pure<'a, W: std::io::Write>(
                     expectations,
                     interns,
                     layout_interner,
-                    shared_memory_ptr,
+                    target,
+                    buffer,
                     offset,
                 )?;
             }
@@ -314,16 +345,13 @@ fn run_expect_fx<'a, W: std::io::Write>(
         0 => unsafe {
             // we are the child
 
-            use roc_gen_llvm::try_run_jit_function;
-
             let mut child_memory = parent_memory.reuse_mmap().unwrap();
 
             let sequence = ExpectSequence::new(child_memory.ptr);
 
             child_memory.set_shared_buffer(lib);
 
-            let result: Result<(), (String, _)> =
-                try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+            let result = call_toplevel_expect(lib, expect);
 
             if let Err((msg, _)) = result {
                 internal_error!("roc panic {msg}");
@@ -381,6 +409,7 @@ fn run_expect_fx<'a, W: std::io::Write>(
                             expectations,
                             interns,
                             layout_interner,
+                            Target::from(target_lexicon::Triple::host()),
                             parent_memory.ptr,
                             ExpectSequence::START_OFFSET,
                         )?;
@@ -429,6 +458,7 @@ pub fn render_expects_in_memory<'a>(
         expectations,
         interns,
         layout_interner,
+        Target::from(target_lexicon::Triple::host()),
         shared_ptr,
         ExpectSequence::START_OFFSET,
     )
@@ -464,12 +494,10 @@ fn render_expect_failure<'a>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     interns: &'a Interns,
     layout_interner: &GlobalLayoutInterner<'a>,
+    target: Target,
     start: *const u8,
     offset: usize,
 ) -> std::io::Result<usize> {
-    // we always run programs as the host
-    let target = target_lexicon::Triple::host().into();
-
     let frame = ExpectFrame::at_offset(start, offset);
     let module_id = frame.module_id;
 
@@ -516,6 +544,12 @@ struct ExpectSequence {
 impl ExpectSequence {
     const START_OFFSET: usize = 8 + 8 + 8;
 
+    /// The header is the number of failures, the offset of the next free byte, and a lock,
+    /// which are each the size of a pointer in the app
+    const fn start_offset(target: Target) -> usize {
+        3 * target.ptr_size()
+    }
+
     const COUNT_INDEX: usize = 0;
     const OFFSET_INDEX: usize = 1;
     const LOCK_INDEX: usize = 2;
@@ -584,7 +618,8 @@ impl ExpectFrame {
         let region: Region = unsafe { std::mem::transmute(region_bytes) };
 
         let module_id_bytes: [u8; 4] = unsafe { *(start.add(offset + 8).cast()) };
-        let module_id: ModuleId = unsafe { std::mem::transmute(module_id_bytes) };
+        let module_id = ModuleId::from_u32(u32::from_ne_bytes(module_id_bytes))
+            .unwrap_or_else(|| internal_error!("invalid module id in the expect buffer"));
 
         // skip to frame
         let start_offset = offset + 8 + 4;
@@ -597,11 +632,44 @@ impl ExpectFrame {
     }
 }
 
+/// The backend that compiled the toplevel expects, which decides how to call them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectBackend {
+    Llvm,
+    Dev,
+    /// Run in the Wasm interpreter, rather than loaded from a dylib
+    Wasm32,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct ToplevelExpect<'a> {
     pub name: &'a str,
     pub symbol: Symbol,
     pub region: Region,
+    pub backend: ExpectBackend,
+}
+
+/// Run a toplevel expect, catching any crash
+fn call_toplevel_expect(
+    lib: &libloading::Library,
+    expect: ToplevelExpect<'_>,
+) -> Result<(), (String, CrashTag)> {
+    use roc_gen_llvm::try_run_jit_function;
+
+    match expect.backend {
+        ExpectBackend::Llvm => try_run_jit_function!(lib, expect.name, (), |v: ()| v),
+        ExpectBackend::Dev => unsafe {
+            // The dev backend returns the RocCallResult, rather than writing it to a pointer
+            let expect_fn: libloading::Symbol<unsafe extern "C" fn() -> RocCallResult<()>> = lib
+                .get(expect.name.as_bytes())
+                .unwrap_or_else(|_| internal_error!("no function for expect {}", expect.name));
+
+            expect_fn().into()
+        },
+        ExpectBackend::Wasm32 => {
+            internal_error!("Wasm expects are run by `run_toplevel_expects_wasm32`")
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -625,10 +693,13 @@ pub fn expect_mono_module_to_dylib<'a>(
     libloading::Error,
 > {
     let MonomorphizedModule {
+        module_id,
         toplevel_expects,
         procedures,
+        symbol_regions,
         interns,
         layout_interner,
+        sources,
         ..
     } = loaded;
 
@@ -642,7 +713,11 @@ pub fn expect_mono_module_to_dylib<'a>(
     let (module_pass, _function_pass) =
         roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
-    let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let (root_path, _) = &sources[&module_id];
+    let (dibuilder, compile_unit) =
+        roc_gen_llvm::llvm::build::Env::new_debug_info(module, root_path);
+    let debug_sources =
+        roc_gen_llvm::llvm::debug_info::DebugSources::new(&dibuilder, &sources, &symbol_regions);
 
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
@@ -650,6 +725,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         builder: &builder,
         dibuilder: &dibuilder,
         compile_unit: &compile_unit,
+        debug_sources: &debug_sources,
         context: &context,
         interns,
         module,
@@ -688,12 +764,114 @@ pub fn expect_mono_module_to_dylib<'a>(
         procedures,
     );
 
+    let modules_expects =
+        expect_functions(arena, toplevel_expects, &expect_names, ExpectBackend::Llvm);
+
+    env.dibuilder.finalize();
+
+    // Uncomment this to see the module's un-optimized LLVM instruction output:
+    // env.module.print_to_stderr();
+
+    module_pass.run_on(env.module);
+
+    // Uncomment this to see the module's optimized LLVM instruction output:
+    // env.module.print_to_stderr();
+
+    // Verify the module
+    if let Err(errors) = env.module.verify() {
+        let path = std::env::temp_dir().join("test.ll");
+        env.module.print_to_file(&path).unwrap();
+        internal_error!(
+            "Errors defining module:\n{}\n\nUncomment things nearby to see more details. IR written to `{:?}`",
+            errors.to_string(), path,
+        );
+    }
+
+    if let Ok(path) = std::env::var("ROC_DEBUG_LLVM") {
+        env.module.print_to_file(path).unwrap();
+    }
+
+    llvm_module_to_dylib(env.module, target, opt_level)
+        .map(|dy_lib| (dy_lib, modules_expects, layout_interner))
+}
+
+/// Like `expect_mono_module_to_dylib`, but builds the expects with the dev backend
+pub fn expect_mono_module_to_dylib_dev<'a>(
+    arena: &'a Bump,
+    target: Target,
+    loaded: MonomorphizedModule<'a>,
+) -> Result<
+    (
+        libloading::Library,
+        MutMap<ModuleId, ExpectFunctions<'a>>,
+        STLayoutInterner<'a>,
+    ),
+    libloading::Error,
+> {
+    let MonomorphizedModule {
+        module_id,
+        toplevel_expects,
+        procedures,
+        mut interns,
+        mut layout_interner,
+        ..
+    } = loaded;
+
+    // In the CliTest mode, every exposed symbol is a toplevel expect
+    let exposed_to_host = toplevel_expects
+        .values()
+        .flat_map(|expects| expects.pure.keys().chain(expects.fx.keys()))
+        .copied()
+        .collect();
+
+    let env = roc_gen_dev::Env {
+        arena,
+        module_id,
+        exposed_to_host,
+        lazy_literals: false,
+        mode: AssemblyBackendMode::CliTest,
+        sources: MutMap::default(),
+    };
+
+    let module_object =
+        roc_gen_dev::build_module(&env, &mut interns, &mut layout_interner, target, procedures);
+    let module_out = module_object
+        .write()
+        .expect("failed to build output object");
+
+    let expect_names = toplevel_expects
+        .iter()
+        .map(|(module_id, expects)| {
+            let names = expects.pure.keys().chain(expects.fx.keys()).map(|symbol| {
+                let name = roc_gen_dev::toplevel_expect_name(&interns, *symbol);
+                &*arena.alloc_str(&name)
+            });
+
+            (*module_id, BumpVec::from_iter_in(names, arena))
+        })
+        .collect();
+
+    let modules_expects =
+        expect_functions(arena, toplevel_expects, &expect_names, ExpectBackend::Dev);
+
+    dev_object_to_dylib(&module_out, target)
+        .map(|dy_lib| (dy_lib, modules_expects, layout_interner))
+}
+
+/// Pair up each toplevel expect with the name of the function that runs it.
+/// The names of the pure expects come before those of the effectful ones.
+fn expect_functions<'a>(
+    arena: &'a Bump,
+    toplevel_expects: MutMap<ModuleId, ToplevelExpects>,
+    expect_names: &MutMap<ModuleId, BumpVec<'a, &'a str>>,
+    backend: ExpectBackend,
+) -> MutMap<ModuleId, ExpectFunctions<'a>> {
     let mut modules_expects: MutMap<ModuleId, ExpectFunctions> = MutMap::default();
 
     for (module_id, expects) in toplevel_expects.into_iter() {
         let expect_names = expect_names.get(&module_id).unwrap();
 
-        let expects_fx = bumpalo::collections::Vec::from_iter_in(
+        let expects_fx = BumpVec::from_iter_in(
             expects
                 .fx
                 .into_iter()
@@ -702,20 +880,22 @@ pub fn expect_mono_module_to_dylib<'a>(
                     symbol,
                     region,
                     name,
+                    backend,
                 }),
-            env.arena,
+            arena,
         );
 
         let expects_pure =
-            bumpalo::collections::Vec::from_iter_in(
+            BumpVec::from_iter_in(
                 expects.pure.into_iter().zip(expect_names.iter()).map(
                     |((symbol, region), name)| ToplevelExpect {
                         symbol,
                         region,
                         name,
+                        backend,
                     },
                 ),
-                env.arena,
+                arena,
             );
 
         let expect_funs = ExpectFunctions {
@@ -726,30 +906,195 @@ pub fn expect_mono_module_to_dylib<'a>(
         modules_expects.insert(module_id, expect_funs);
     }
 
-    env.dibuilder.finalize();
+    modules_expects
+}
 
-    // Uncomment this to see the module's un-optimized LLVM instruction output:
-    // env.module.print_to_stderr();
+/// The Wasm host for `expect_mono_module_to_wasm32`, with the builtins linked in
+#[cfg(feature = "run-wasm32")]
+const WASM_EXPECT_HOST: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/wasm_expect_host.wasm"));
 
-    module_pass.run_on(env.module);
+/// Exported by the Wasm host. Allocates the buffer that failed expects write their lookups to.
+#[cfg(feature = "run-wasm32")]
+const INIT_EXPECT_BUFFER: &str = "init_expect_buffer";
 
-    // Uncomment this to see the module's optimized LLVM instruction output:
-    // env.module.print_to_stderr();
+/// Like `expect_mono_module_to_dylib`, but builds the expects with the Wasm backend.
+/// Returns a Wasm module that exports each of the toplevel expects by name.
+#[cfg(feature = "run-wasm32")]
+pub fn expect_mono_module_to_wasm32<'a>(
+    arena: &'a Bump,
+    loaded: MonomorphizedModule<'a>,
+) -> (
+    std::vec::Vec<u8>,
+    MutMap<ModuleId, ExpectFunctions<'a>>,
+    STLayoutInterner<'a>,
+) {
+    use roc_wasm_module::{Export, ExportType};
 
-    // Verify the module
-    if let Err(errors) = env.module.verify() {
-        let path = std::env::temp_dir().join("test.ll");
-        env.module.print_to_file(&path).unwrap();
+    let MonomorphizedModule {
+        module_id,
+        toplevel_expects,
+        procedures,
+        mut interns,
+        mut layout_interner,
+        ..
+    } = loaded;
+
+    // In the CliTest mode, every exposed symbol is a toplevel expect
+    let exposed_to_host = toplevel_expects
+        .values()
+        .flat_map(|expects| expects.pure.keys().chain(expects.fx.keys()))
+        .copied()
+        .collect();
+
+    let env = roc_gen_wasm::Env {
+        arena,
+        module_id,
+        exposed_to_host,
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+    };
+
+    let host_module = roc_gen_wasm::parse_host(arena, WASM_EXPECT_HOST).unwrap_or_else(|e| {
         internal_error!(
-            "Errors defining module:\n{}\n\nUncomment things nearby to see more details. IR written to `{:?}`",
-            errors.to_string(), path,
-        );
+            "I ran into a problem with the Wasm expect host, at offset 0x{:x}:\n{}",
+            e.offset,
+            e.message
+        )
+    });
+
+    let (mut module, called_fns, exposed_fns) = roc_gen_wasm::build_app_module_exposing_all(
+        &env,
+        &mut layout_interner,
+        &mut interns,
+        host_module,
+        procedures,
+    );
+
+    for (symbol, fn_index) in exposed_fns {
+        let name = roc_gen_dev::toplevel_expect_name(&interns, symbol);
+        module.export.append(Export {
+            name: arena.alloc_str(&name),
+            ty: ExportType::Func,
+            index: fn_index,
+        });
     }
 
-    if let Ok(path) = std::env::var("ROC_DEBUG_LLVM") {
-        env.module.print_to_file(path).unwrap();
+    let init_buffer_fn_index = module
+        .names
+        .function_names
+        .iter()
+        .find(|(_, name)| *name == INIT_EXPECT_BUFFER)
+        .map(|(fn_index, _)| *fn_index)
+        .unwrap_or_else(|| internal_error!("The Wasm expect host has no {INIT_EXPECT_BUFFER}"));
+    module.export.append(Export {
+        name: INIT_EXPECT_BUFFER,
+        ty: ExportType::Func,
+        index: init_buffer_fn_index,
+    });
+
+    module.eliminate_dead_code(arena, called_fns);
+
+    let mut wasm_bytes = std::vec::Vec::with_capacity(module.size());
+    module.serialize(&mut wasm_bytes);
+
+    let expect_names = toplevel_expects
+        .iter()
+        .map(|(module_id, expects)| {
+            let names = expects.pure.keys().chain(expects.fx.keys()).map(|symbol| {
+                let name = roc_gen_dev::toplevel_expect_name(&interns, *symbol);
+                &*arena.alloc_str(&name)
+            });
+
+            (*module_id, BumpVec::from_iter_in(names, arena))
+        })
+        .collect();
+
+    let modules_expects = expect_functions(
+        arena,
+        toplevel_expects,
+        &expect_names,
+        ExpectBackend::Wasm32,
+    );
+
+    (wasm_bytes, modules_expects, layout_interner)
+}
+
+/// Like `run_toplevel_expects`, but runs expects built by `expect_mono_module_to_wasm32`
+/// in the Wasm interpreter.
+#[cfg(feature = "run-wasm32")]
+#[allow(clippy::too_many_arguments)]
+pub fn run_toplevel_expects_wasm32<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    wasm_bytes: &[u8],
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    expects: ExpectFunctions<'_>,
+) -> std::io::Result<(usize, usize)> {
+    use roc_wasm_interp::{DefaultImportDispatcher, Instance, Value};
+    use roc_wasm_module::WasmModule;
+
+    const BUFFER_SIZE: usize = ExpectMemory::SHM_SIZE;
+
+    let module_arena = Bump::new();
+    let require_relocatable = false;
+    let module = WasmModule::preload(&module_arena, wasm_bytes, require_relocatable)
+        .unwrap_or_else(|e| internal_error!("Invalid Wasm module for expects: {e:?}"));
+
+    let mut failed = 0;
+    let mut passed = 0;
+
+    // Effectful expects need no separate process, since the interpreter is sandboxed anyway
+    for expect in expects.fx.into_iter().chain(expects.pure) {
+        // A crash stops the whole app, so each expect gets a new instance
+        let is_debug_mode = false;
+        let mut instance = Instance::for_module(
+            &module_arena,
+            &module,
+            DefaultImportDispatcher::default(),
+            is_debug_mode,
+        )
+        .unwrap_or_else(|e| internal_error!("{e}"));
+
+        let buffer_addr = instance
+            .call_export(INIT_EXPECT_BUFFER, [Value::I32(BUFFER_SIZE as i32)])
+            .ok()
+            .flatten()
+            .and_then(|value| value.expect_i32().ok())
+            .unwrap_or_else(|| internal_error!("No address from {INIT_EXPECT_BUFFER}"))
+            as usize;
+
+        let result = match instance.call_export(expect.name, []) {
+            Ok(_) => match instance.import_dispatcher.panic_message.take() {
+                Some(roc_panic_message) => Err(roc_panic_message),
+                None => Ok(()),
+            },
+            Err(interpreter_error) => Err(interpreter_error),
+        };
+
+        let buffer = &instance.memory[buffer_addr..][..BUFFER_SIZE];
+        let failure_count = u32::from_le_bytes(buffer[..4].try_into().unwrap()) as usize;
+
+        let has_passed = report_toplevel_expect(
+            writer,
+            render_target,
+            arena,
+            interns,
+            layout_interner,
+            expectations,
+            expect,
+            result,
+            Target::Wasm32,
+            buffer.as_ptr(),
+            failure_count,
+        )?;
+
+        match has_passed {
+            true => passed += 1,
+            false => failed += 1,
+        }
     }
 
-    llvm_module_to_dylib(env.module, target, opt_level)
-        .map(|dy_lib| (dy_lib, modules_expects, layout_interner))
+    Ok((failed, passed))
 }
@@ -0,0 +1,73 @@
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Host for running toplevel expects with the Wasm backend, in `roc test --target wasm32`.
+// Reporting back to `roc test` goes through imports, which roc_wasm_interp's
+// DefaultImportDispatcher implements.
+
+__attribute__((import_module("env"), import_name("roc_panic")))
+extern void host_panic(void *msg, unsigned int panic_tag);
+
+__attribute__((import_module("env"), import_name("roc_dbg")))
+extern void host_dbg(void *loc, void *msg, void *src);
+
+__attribute__((import_module("env"), import_name("roc_expect_failed")))
+extern void host_expect_failed(unsigned int region_start, unsigned int region_end, unsigned int module_id);
+
+extern size_t set_shared_buffer(uint8_t *ptr, size_t length);
+
+// `roc test` passes us the size of the buffer that failed expects write their lookups to,
+// and we tell it where the buffer is, so it can render the failures.
+uint8_t *init_expect_buffer(size_t length)
+{
+    uint8_t *buffer = calloc(length, 1);
+
+    // The header is the number of failures, the offset of the next free byte, and a lock
+    size_t *header = (size_t *)buffer;
+    header[1] = 3 * sizeof(size_t);
+
+    set_shared_buffer(buffer, length);
+
+    return buffer;
+}
+
+//--------------------------
+
+void *roc_alloc(size_t size, unsigned int alignment)
+{
+    return malloc(size);
+}
+
+void *roc_realloc(void *ptr, size_t new_size, size_t old_size,
+                  unsigned int alignment)
+{
+    return realloc(ptr, new_size);
+}
+
+void roc_dealloc(void *ptr, unsigned int alignment)
+{
+    free(ptr);
+}
+
+void *roc_memset(void *str, int c, size_t n)
+{
+    return memset(str, c, n);
+}
+
+//--------------------------
+
+void roc_panic(void *msg, unsigned int panic_tag)
+{
+    host_panic(msg, panic_tag);
+}
+
+void roc_dbg(void *loc, void *msg, void *src)
+{
+    host_dbg(loc, msg, src);
+}
+
+void roc_expect_failed(unsigned int region_start, unsigned int region_end, unsigned int module_id)
+{
+    host_expect_failed(region_start, region_end, module_id);
+}
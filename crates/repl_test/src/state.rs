@@ -1,6 +1,8 @@
 use bumpalo::Bump;
 use indoc::indoc;
-use roc_repl_cli::{evaluate, ReplHelper};
+use roc_packaging::cache::RocCacheDir;
+use roc_repl_cli::{evaluate, evaluate_dbg, evaluate_expect, resolve_package, ReplHelper};
+use roc_repl_eval::gen::ReplPackage;
 use roc_repl_ui::is_incomplete;
 use roc_repl_ui::repl_state::{ReplAction, ReplState};
 use roc_reporting::report::DEFAULT_PALETTE;
@@ -145,6 +147,256 @@ fn standalone_annotation() {
     assert!(matches!(action, ReplAction::Nothing));
 }
 
+#[test]
+fn destructuring_defs() {
+    let mut state = ReplState::new();
+
+    complete(
+        "point = { x: 1, y: 2 }",
+        &mut state,
+        "{ x: 1, y: 2 } : { x : Num *, y : Num * }",
+    );
+    complete(
+        "{ x, y } = point",
+        &mut state,
+        "{ x: 1, y: 2 } : { x : Num *, y : Num * }",
+    );
+    complete(
+        "(a, b) = (\"hi\", x + y)",
+        &mut state,
+        "{ a: \"hi\", b: 3 } : { a : Str, b : Num * }",
+    );
+    complete("b * 2", &mut state, "6 : Num *");
+}
+
+#[test]
+fn dbg_statement() {
+    let mut state = ReplState::new();
+
+    complete("x = [1, 2]", &mut state, "[1, 2] : List (Num *)");
+    statement("dbg List.len x", &mut state, "[repl] List.len x = 2");
+}
+
+#[test]
+fn expect_passes() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    statement("expect x == 5", &mut state, "The expectation passed.");
+}
+
+#[test]
+fn expect_fails() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    complete("name = \"Roc\"", &mut state, "\"Roc\" : Str");
+
+    let expected: &str = indoc!(
+        r#"
+        ── EXPECT FAILED ───────────────────────────────────────────────────────────────
+
+        This expectation failed:
+
+            expect x + 1 == 5 && Str.isEmpty name
+
+        When it failed, these variables had these values:
+
+        x : Num *
+        x = 5
+
+        name : Str
+        name = "Roc""#
+    );
+
+    statement(
+        "expect x + 1 == 5 && Str.isEmpty name",
+        &mut state,
+        expected,
+    );
+}
+
+#[test]
+fn type_command() {
+    let mut state = ReplState::new();
+
+    complete(
+        "inc = \\n -> n + 1",
+        &mut state,
+        "<function> : Num a -> Num a",
+    );
+    command(":type inc 2", &mut state, "inc 2 : Num *");
+    command(
+        ":type List.map",
+        &mut state,
+        "List.map : List a, (a -> b) -> List b",
+    );
+}
+
+#[test]
+fn doc_command() {
+    let mut state = ReplState::new();
+
+    complete(
+        "inc = \\n -> n + 1",
+        &mut state,
+        "<function> : Num a -> Num a",
+    );
+    command(
+        ":doc inc",
+        &mut state,
+        "inc : Num a -> Num a\n\nThere are no docs for this definition.",
+    );
+    command(
+        ":doc 1 + 1",
+        &mut state,
+        "1 + 1 is not a name, so it has no docs. Try something like List.map",
+    );
+}
+
+#[test]
+fn doc_command_with_doc_comments() {
+    let mut state = ReplState::new();
+
+    complete(
+        "## Adds one to a number.\n##\n## Works on any number type.\ninc = \\n -> n + 1",
+        &mut state,
+        "<function> : Num a -> Num a",
+    );
+    command(
+        ":doc inc",
+        &mut state,
+        "inc : Num a -> Num a\n\nAdds one to a number.\n\nWorks on any number type.",
+    );
+}
+
+#[test]
+fn doc_command_builtin() {
+    let mut state = ReplState::new();
+
+    command(
+        ":doc Bool.true",
+        &mut state,
+        "Bool.true : Bool\n\nThe boolean true value.",
+    );
+}
+
+#[test]
+fn defs_and_reset() {
+    let mut state = ReplState::new();
+
+    command(":defs", &mut state, "There are no past definitions yet.");
+    complete("x = 5", &mut state, "5 : Num *");
+    complete("name = \"Roc\"", &mut state, "\"Roc\" : Str");
+    command(":defs", &mut state, "x : Num *\nname : Str");
+    command(":reset", &mut state, "Cleared all past definitions.");
+    command(":defs", &mut state, "There are no past definitions yet.");
+}
+
+#[test]
+fn save_and_load() {
+    let path = std::env::temp_dir().join("roc_repl_test_save_and_load.roc");
+    let path_str = path.to_str().unwrap();
+
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, "5 : Num *");
+    complete("y = x + 1", &mut state, "6 : Num *");
+    command(
+        &format!(":save {path_str}"),
+        &mut state,
+        &format!("Saved 2 past definitions to {path_str}"),
+    );
+
+    let mut state = ReplState::new();
+
+    command(
+        &format!(":load {path_str}"),
+        &mut state,
+        &format!("Loaded 2 definitions from {path_str}"),
+    );
+    complete("x + y", &mut state, "11 : Num *");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn package_import() {
+    let root_module = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join("package")
+        .join("main.roc");
+
+    let mut state = ReplState::with_packages(vec![ReplPackage {
+        shorthand: "pkg".to_string(),
+        root_module,
+    }]);
+
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let action = state.step(&arena, "import pkg.Greeting", target, DEFAULT_PALETTE);
+    assert!(matches!(action, ReplAction::Nothing));
+
+    complete(
+        "Greeting.hello \"Roc\"",
+        &mut state,
+        "\"Hello, Roc!\" : Str",
+    );
+}
+
+#[test]
+fn url_package_import() {
+    // Put the package where it would have been downloaded to, so it's found without a download
+    let cache_dir = std::env::temp_dir().join("roc_repl_test_url_package_import");
+    let package_dir = cache_dir
+        .join("example.com")
+        .join("roc-packages")
+        .join("zL9JDx3RIs5x6bK0RvBwuKSjDHWyzfoCVNNNlVgYLqE");
+    let fixture_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join("package");
+
+    std::fs::create_dir_all(&package_dir).unwrap();
+
+    for file_name in ["main.roc", "Greeting.roc"] {
+        std::fs::copy(fixture_dir.join(file_name), package_dir.join(file_name)).unwrap();
+    }
+
+    let package = resolve_package(
+        "pkg=https://example.com/roc-packages/zL9JDx3RIs5x6bK0RvBwuKSjDHWyzfoCVNNNlVgYLqE.tar.br",
+        RocCacheDir::Persistent(cache_dir.as_path()),
+    )
+    .unwrap();
+
+    assert_eq!(package.root_module, package_dir.join("main.roc"));
+
+    let mut state = ReplState::with_packages(vec![package]);
+
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let action = state.step(&arena, "import pkg.Greeting", target, DEFAULT_PALETTE);
+    assert!(matches!(action, ReplAction::Nothing));
+
+    complete(
+        "Greeting.hello \"Roc\"",
+        &mut state,
+        "\"Hello, Roc!\" : Str",
+    );
+
+    std::fs::remove_dir_all(cache_dir).unwrap();
+}
+
+#[test]
+fn unknown_package_import() {
+    let mut state = ReplState::new();
+
+    command(
+        "import pkg.Greeting",
+        &mut state,
+        "There is no package with the shorthand pkg. Start the REPL with roc repl --package pkg=path/to/main.roc to import its modules.",
+    );
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
@@ -173,6 +425,59 @@ fn complete(input: &str, state: &mut ReplState, expected_start: &str) {
     }
 }
 
+/// step the given `dbg` or `expect` statement, then check the output
+/// with ANSI escape codes stripped.
+fn statement(input: &str, state: &mut ReplState, expected: &str) {
+    assert!(!is_incomplete(input));
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let action = state.step(&arena, input, target, DEFAULT_PALETTE);
+
+    let string = match action {
+        ReplAction::Dbg {
+            src,
+            opt_mono,
+            problems,
+        } => evaluate_dbg(src, opt_mono, problems, target),
+        ReplAction::Expect {
+            src,
+            opt_mono,
+            problems,
+            lookups,
+        } => evaluate_expect(src, opt_mono, problems, lookups, state, target),
+        _ => {
+            panic!("Unexpected action: {:?}", action);
+        }
+    };
+
+    let escaped =
+        std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap()).unwrap();
+
+    assert_eq!(expected, escaped);
+}
+
+/// step the given REPL command, then check the output it prints
+/// with ANSI escape codes stripped.
+fn command(input: &str, state: &mut ReplState, expected: &str) {
+    assert!(!is_incomplete(input));
+    let arena = Bump::new();
+    let target = Triple::host().into();
+    let action = state.step(&arena, input, target, DEFAULT_PALETTE);
+
+    match action {
+        ReplAction::Output(string) => {
+            let escaped =
+                std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap())
+                    .unwrap();
+
+            assert_eq!(expected, escaped);
+        }
+        _ => {
+            panic!("Unexpected action: {:?}", action);
+        }
+    }
+}
+
 fn incomplete(input: &mut String) {
     assert!(is_incomplete(input));
 
@@ -1,6 +1,6 @@
 use roc_reporting::report::{StyleCodes, ANSI_STYLE_CODES, HTML_STYLE_CODES};
 
-const STYLE_CODES: StyleCodes = if cfg!(target_family = "wasm") {
+pub(crate) const STYLE_CODES: StyleCodes = if cfg!(target_family = "wasm") {
     HTML_STYLE_CODES
 } else {
     ANSI_STYLE_CODES
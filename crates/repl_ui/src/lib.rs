@@ -9,7 +9,8 @@ use const_format::concatcp;
 use repl_state::{parse_src, ParseOutcome};
 use roc_parse::ast::{Expr, ValueDef};
 use roc_repl_eval::gen::{Problems, ReplOutput};
-use roc_reporting::report::StyleCodes;
+use roc_reporting::report::{pretty_header, StyleCodes};
+use std::time::Duration;
 
 // TODO add link to repl tutorial (does not yet exist).
 pub const TIPS: &str = concatcp!(
@@ -49,7 +50,54 @@ pub const TIPS: &str = concatcp!(
             END_COL,
             " shows this text again\n",
         )
-    }
+    },
+    "\nCommands:\n\n",
+    CYAN,
+    "  - ",
+    END_COL,
+    GREEN,
+    ":type <expr>",
+    END_COL,
+    " shows the type of an expression without evaluating it\n",
+    CYAN,
+    "  - ",
+    END_COL,
+    GREEN,
+    ":doc <name>",
+    END_COL,
+    " shows the docs of a builtin or imported definition\n",
+    CYAN,
+    "  - ",
+    END_COL,
+    GREEN,
+    ":defs",
+    END_COL,
+    " lists the definitions made so far\n",
+    CYAN,
+    "  - ",
+    END_COL,
+    GREEN,
+    ":reset",
+    END_COL,
+    " forgets all the definitions made so far\n",
+    CYAN,
+    "  - ",
+    END_COL,
+    GREEN,
+    ":save <file>",
+    END_COL,
+    " and ",
+    GREEN,
+    ":load <file>",
+    END_COL,
+    " save the definitions to a file and load them again\n",
+    CYAN,
+    "  - ",
+    END_COL,
+    GREEN,
+    ":time <expr>",
+    END_COL,
+    " evaluates an expression and shows how long it took\n",
 );
 
 // For when nothing is entered in the repl
@@ -80,15 +128,18 @@ pub fn is_incomplete(input: &str) -> bool {
         | ParseOutcome::ValueDef(_)
         | ParseOutcome::TypeDef(_)
         | ParseOutcome::SyntaxErr
-        | ParseOutcome::Expr(_) => false,
+        | ParseOutcome::Expr(_)
+        | ParseOutcome::Type(_)
+        | ParseOutcome::Doc(_)
+        | ParseOutcome::Defs
+        | ParseOutcome::Reset
+        | ParseOutcome::Save(_)
+        | ParseOutcome::Load(_)
+        | ParseOutcome::Time(_) => false,
     }
 }
 
-pub fn format_output(
-    style_codes: StyleCodes,
-    opt_output: Option<ReplOutput>,
-    problems: Problems,
-) -> String {
+fn format_problems(problems: &Problems) -> String {
     let mut buf = String::new();
 
     for message in problems.errors.iter().chain(problems.warnings.iter()) {
@@ -101,6 +152,16 @@ pub fn format_output(
         buf.push('\n');
     }
 
+    buf
+}
+
+pub fn format_output(
+    style_codes: StyleCodes,
+    opt_output: Option<ReplOutput>,
+    problems: Problems,
+) -> String {
+    let mut buf = format_problems(&problems);
+
     if let Some(ReplOutput { expr, expr_type }) = opt_output {
         // If expr was empty, it was a type annotation or ability declaration;
         // don't print anything!
@@ -124,3 +185,103 @@ pub fn format_output(
 
     buf
 }
+
+/// Formats the value of a `dbg` entered at the prompt the way `dbg` prints it in a program,
+/// e.g. `[repl] x = 5`
+pub fn format_dbg_output(
+    style_codes: StyleCodes,
+    src: &str,
+    opt_output: Option<ReplOutput>,
+    problems: Problems,
+) -> String {
+    let mut buf = format_problems(&problems);
+
+    if let Some(ReplOutput { expr, .. }) = opt_output {
+        if problems.errors.is_empty() {
+            buf.push('\n');
+            buf.push_str(style_codes.cyan);
+            buf.push_str("[repl] ");
+            buf.push_str(style_codes.reset);
+            buf.push_str(src);
+            buf.push_str(" = ");
+            buf.push_str(&expr);
+        }
+    }
+
+    buf
+}
+
+/// Whether the condition of an `expect` entered at the prompt evaluated to false,
+/// in which case the values it looked up should be evaluated for the report.
+/// `opt_held` is the value of the condition, if it was evaluated.
+pub fn expect_failed(opt_held: Option<bool>, problems: &Problems) -> bool {
+    problems.errors.is_empty() && opt_held == Some(false)
+}
+
+/// Formats the outcome of an `expect` entered at the prompt. A failure is reported like
+/// `roc test` does, along with the values of the past defs the expectation looked up.
+pub fn format_expect_output(
+    style_codes: StyleCodes,
+    src: &str,
+    opt_held: Option<bool>,
+    problems: Problems,
+    lookups: Vec<(&str, ReplOutput)>,
+) -> String {
+    let failed = expect_failed(opt_held, &problems);
+    let mut buf = format_problems(&problems);
+
+    if failed {
+        buf.push('\n');
+        buf.push_str(style_codes.cyan);
+        buf.push_str(&pretty_header("EXPECT FAILED"));
+        buf.push_str(style_codes.reset);
+        buf.push_str("\n\nThis expectation failed:\n\n");
+
+        for line in src.lines() {
+            buf.push_str("    ");
+            buf.push_str(line);
+            buf.push('\n');
+        }
+
+        if !lookups.is_empty() {
+            buf.push_str("\nWhen it failed, these variables had these values:\n");
+
+            for (ident, ReplOutput { expr, expr_type }) in lookups {
+                buf.push('\n');
+                buf.push_str(ident);
+                buf.push_str(" : ");
+                buf.push_str(&expr_type);
+                buf.push('\n');
+                buf.push_str(ident);
+                buf.push_str(" = ");
+                buf.push_str(&expr);
+                buf.push('\n');
+            }
+        }
+    } else if opt_held.is_some() && problems.errors.is_empty() {
+        buf.push('\n');
+        buf.push_str(style_codes.green);
+        buf.push_str("The expectation passed.");
+        buf.push_str(style_codes.reset);
+    }
+
+    buf
+}
+
+/// Formats the output of `:time <expr>`, followed by how long the evaluation took.
+pub fn format_time_output(
+    style_codes: StyleCodes,
+    opt_output: Option<ReplOutput>,
+    problems: Problems,
+    elapsed: Duration,
+) -> String {
+    let evaluated = opt_output.is_some() && problems.errors.is_empty();
+    let (cyan, reset) = (style_codes.cyan, style_codes.reset);
+    let mut buf = format_output(style_codes, opt_output, problems);
+
+    if evaluated {
+        buf.push_str(&format!("\n{cyan}Evaluated in {elapsed:.2?}{reset}"));
+    }
+
+    buf
+}
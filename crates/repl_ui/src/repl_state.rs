@@ -2,27 +2,38 @@ use std::path::PathBuf;
 use std::{fs, io};
 
 use bumpalo::Bump;
+use roc_can as can;
 use roc_collections::MutSet;
-use roc_load::MonomorphizedModule;
-use roc_parse::ast::{Expr, Pattern, StrLiteral, TypeDef, TypeHeader, ValueDef};
-use roc_parse::expr::{parse_single_def, ExprParseOptions, SingleDef};
-use roc_parse::parser::Parser;
-use roc_parse::parser::{EClosure, EExpr, EPattern};
+use roc_load::{LoadedModule, MonomorphizedModule};
+use roc_module::symbol::Symbol;
+use roc_parse::ast::{Defs, Expr, Pattern, StrLiteral, TypeDef, TypeHeader, ValueDef};
+use roc_parse::blankspace::space0_before_e;
+use roc_parse::expr::{loc_expr, parse_single_def, ExprParseOptions, SingleDef};
+use roc_parse::highlight::Token;
+use roc_parse::module::parse_module_defs;
+use roc_parse::parser::{keyword, Parser};
+use roc_parse::parser::{EClosure, EExpect, EExpr, EPattern};
 use roc_parse::parser::{EWhen, Either};
 use roc_parse::state::State;
 use roc_parse::{join_alias_to_body, join_ann_to_body};
-use roc_region::all::Loc;
-use roc_repl_eval::gen::{compile_to_mono, Problems};
-use roc_reporting::report::Palette;
+use roc_region::all::{Loc, Region};
+use roc_repl_eval::gen::{compile_to_mono, compile_to_typed, Problems, ReplOutput, ReplPackage};
+use roc_reporting::report::{to_file_problem_report_string, Palette};
 use roc_target::Target;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::Variable;
+
+use crate::colors::STYLE_CODES;
+use crate::format_output;
 
 #[derive(Debug, Clone, PartialEq)]
 enum PastDef {
-    Def { ident: String, src: String },
+    Def { idents: Vec<String>, src: String },
     Import(String),
 }
 
 pub struct ReplState {
+    packages: Vec<ReplPackage>,
     past_defs: Vec<PastDef>,
     past_def_idents: MutSet<String>,
 }
@@ -40,6 +51,27 @@ pub enum ReplAction<'a> {
         opt_mono: Option<MonomorphizedModule<'a>>,
         problems: Problems,
     },
+    /// Print the value of a `dbg` entered at the prompt.
+    Dbg {
+        src: &'a str,
+        opt_mono: Option<MonomorphizedModule<'a>>,
+        problems: Problems,
+    },
+    /// Report whether an `expect` entered at the prompt passed. If it failed, each of the past
+    /// defs it looks up can be compiled with [ReplState::compile_lookup], to show its value.
+    Expect {
+        src: &'a str,
+        opt_mono: Option<MonomorphizedModule<'a>>,
+        problems: Problems,
+        lookups: Vec<&'a str>,
+    },
+    /// Evaluate like [ReplAction::Eval], and report how long it took.
+    Time {
+        opt_mono: Option<MonomorphizedModule<'a>>,
+        problems: Problems,
+    },
+    /// Print the output of a command like `:type`, which was already formatted.
+    Output(String),
     Exit,
     Help,
     FileProblem {
@@ -51,7 +83,13 @@ pub enum ReplAction<'a> {
 
 impl ReplState {
     pub fn new() -> Self {
+        Self::with_packages(Vec::new())
+    }
+
+    /// A REPL whose `import`s can refer to the modules of the given packages.
+    pub fn with_packages(packages: Vec<ReplPackage>) -> Self {
         Self {
+            packages,
             past_defs: Default::default(),
             past_def_idents: Default::default(),
         }
@@ -68,6 +106,24 @@ impl ReplState {
         let src: &str = match parse_src(arena, line) {
             ParseOutcome::Empty | ParseOutcome::Help => return ReplAction::Help,
             ParseOutcome::Exit => return ReplAction::Exit,
+            ParseOutcome::Type(src) => return self.type_of(arena, src, target, palette),
+            ParseOutcome::Doc(name) => return self.doc(arena, name, target, palette),
+            ParseOutcome::Defs => return self.defs(arena, target, palette),
+            ParseOutcome::Reset => {
+                self.past_defs.clear();
+                self.past_def_idents.clear();
+
+                return ReplAction::Output("Cleared all past definitions.".to_string());
+            }
+            ParseOutcome::Save(filename) => return self.save(PathBuf::from(filename)),
+            ParseOutcome::Load(filename) => {
+                return self.load(arena, PathBuf::from(filename), target, palette)
+            }
+            ParseOutcome::Time(src) => {
+                let (opt_mono, problems) = self.compile(arena, src, target, palette);
+
+                return ReplAction::Time { opt_mono, problems };
+            }
             ParseOutcome::Expr(_) | ParseOutcome::Incomplete | ParseOutcome::SyntaxErr => {
                 pending_past_def = None;
 
@@ -87,7 +143,7 @@ impl ReplState {
                         _,
                     ) => {
                         // Record the standalone type annotation for future use.
-                        self.add_past_def(ident.trim_end().to_string(), line.to_string());
+                        self.add_past_def(vec![ident.trim_end().to_string()], line.to_string());
 
                         // Return early without running eval, since standalone annotations
                         // cannot be evaluated as expressions.
@@ -110,7 +166,7 @@ impl ReplState {
                             },
                         ..
                     } => {
-                        pending_past_def = Some((ident.to_string(), line.to_string()));
+                        pending_past_def = Some((vec![ident.to_string()], line.to_string()));
 
                         // Recreate the body of the def and then evaluate it as a lookup.
                         // We do this so that any errors will get reported as part of this expr;
@@ -127,25 +183,125 @@ impl ReplState {
 
                         buf.into_bump_str()
                     }
-                    ValueDef::Annotation(_, _)
-                    | ValueDef::Body(_, _)
-                    | ValueDef::AnnotatedBody { .. } => {
-                        todo!("handle pattern other than identifier (which repl doesn't support).\
-                              \nTip: this error can be triggered when trying to define a variable with a character that is not allowed, \
-                              like starting with an uppercase character or using underdash (_).")
+                    ValueDef::Annotation(_, _) => {
+                        // A standalone annotation of a destructuring pattern, like `(a, b) : (Str, Str)`,
+                        // can't be recorded, because top-level destructuring isn't supported.
+                        return ReplAction::Nothing;
                     }
-                    ValueDef::Dbg { .. } => {
-                        todo!("handle receiving a `dbg` - what should the repl do for that?")
+                    ValueDef::Body(body_pattern, _)
+                    | ValueDef::AnnotatedBody { body_pattern, .. } => {
+                        // A destructuring def like `{ x, y } = point` or `(a, b) = pair`.
+                        let mut bindings = Vec::new();
+
+                        if let ValueDef::AnnotatedBody { ann_pattern, .. } = &value_def {
+                            pattern_bindings(
+                                &ann_pattern.value,
+                                ann_pattern.region,
+                                false,
+                                &mut bindings,
+                            );
+                        }
+
+                        let ann_bindings = bindings.len();
+
+                        pattern_bindings(
+                            &body_pattern.value,
+                            body_pattern.region,
+                            false,
+                            &mut bindings,
+                        );
+
+                        let idents: Vec<&str> = bindings[ann_bindings..]
+                            .iter()
+                            .map(|binding| binding.ident)
+                            .collect();
+
+                        pending_past_def = Some((
+                            idents.iter().map(|ident| ident.to_string()).collect(),
+                            destructuring_past_def(line, &bindings, &idents),
+                        ));
+
+                        // Evaluate a record of all the names the def binds, so each of their values is shown.
+                        let mut buf =
+                            bumpalo::collections::string::String::from_str_in(line, arena);
+
+                        if idents.is_empty() {
+                            buf.push_str("\n{}");
+                        } else {
+                            buf.push_str("\n{ ");
+                            buf.push_str(&idents.join(", "));
+                            buf.push_str(" }");
+                        }
+
+                        buf.into_bump_str()
                     }
-                    ValueDef::Expect { .. } => {
-                        todo!("handle receiving an `expect` - what should the repl do for that?")
+                    ValueDef::Dbg { condition, .. } => {
+                        let src = arena.alloc_str(region_src(line, condition.region));
+                        let (opt_mono, problems) = self.compile(arena, src, target, palette);
+
+                        return ReplAction::Dbg {
+                            src,
+                            opt_mono,
+                            problems,
+                        };
                     }
-                    ValueDef::ExpectFx { .. } => {
-                        todo!("handle receiving an `expect-fx` - what should the repl do for that?")
+                    ValueDef::Expect { condition, .. } | ValueDef::ExpectFx { condition, .. } => {
+                        let condition_src = region_src(line, condition.region);
+
+                        // Wrapping the condition in an `if` reports a condition that isn't a Bool
+                        // the same way it would be reported in an `expect`.
+                        let expr = format!("if {condition_src} then Bool.true else Bool.false");
+                        let (opt_mono, problems) = self.compile(arena, &expr, target, palette);
+
+                        let lookups = self
+                            .lookups(condition_src)
+                            .into_iter()
+                            .map(|ident| &*arena.alloc_str(ident))
+                            .collect();
+
+                        return ReplAction::Expect {
+                            src: arena.alloc_str(line.trim()),
+                            opt_mono,
+                            problems,
+                            lookups,
+                        };
                     }
                     ValueDef::ModuleImport(import) => match import.name.value.package {
-                        Some(_) => {
-                            todo!("handle importing a module from a package")
+                        Some(shorthand) => {
+                            if !self
+                                .packages
+                                .iter()
+                                .any(|package| package.shorthand == shorthand)
+                            {
+                                let (cyan, reset) = (STYLE_CODES.cyan, STYLE_CODES.reset);
+
+                                return ReplAction::Output(format!(
+                                    "There is no package with the shorthand {cyan}{shorthand}{reset}. Start the REPL with {cyan}roc repl --package {shorthand}=path/to/main.roc{reset} to import its modules."
+                                ));
+                            }
+
+                            // Check the package exposes the module before we add it to past
+                            // defs, for the same reason we check files exist below.
+                            let (_, problems) = compile_to_typed(
+                                arena,
+                                &self.packages,
+                                self.past_def_srcs().chain([line]),
+                                "{}",
+                                target,
+                                palette,
+                            );
+
+                            if !problems.errors.is_empty() {
+                                return ReplAction::Output(format_output(
+                                    STYLE_CODES,
+                                    None,
+                                    problems,
+                                ));
+                            }
+
+                            self.past_defs.push(PastDef::Import(line.to_string()));
+
+                            return ReplAction::Nothing;
                         }
                         None => {
                             let mut filename = PathBuf::new();
@@ -186,7 +342,12 @@ impl ReplState {
 
                         return ReplAction::Nothing;
                     }
-                    ValueDef::Stmt(_) => todo!(),
+                    ValueDef::Stmt(_) => {
+                        // A standalone statement is evaluated like any other expression.
+                        pending_past_def = None;
+
+                        line
+                    }
                 }
             }
             ParseOutcome::TypeDef(TypeDef::Alias {
@@ -214,7 +375,7 @@ impl ReplState {
                 ..
             }) => {
                 // Record the type for future use.
-                self.add_past_def(ident.trim_end().to_string(), line.to_string());
+                self.add_past_def(vec![ident.trim_end().to_string()], line.to_string());
 
                 // Return early without running eval, since none of these
                 // can be evaluated as expressions.
@@ -222,33 +383,529 @@ impl ReplState {
             }
         };
 
-        let (opt_mono, problems) = compile_to_mono(
+        let (opt_mono, problems) = self.compile(arena, src, target, palette);
+
+        if let Some((idents, src)) = pending_past_def {
+            self.add_past_def(idents, src);
+        }
+
+        ReplAction::Eval { opt_mono, problems }
+    }
+
+    /// Compile `src` as the expression to evaluate, after all the past defs.
+    fn compile<'a>(
+        &self,
+        arena: &'a Bump,
+        src: &str,
+        target: Target,
+        palette: Palette,
+    ) -> (Option<MonomorphizedModule<'a>>, Problems) {
+        compile_to_mono(
             arena,
-            self.past_defs.iter().map(|past_def| match past_def {
-                PastDef::Def { ident: _, src } => src.as_str(),
-                PastDef::Import(src) => src.as_str(),
-            }),
+            &self.packages,
+            self.past_def_srcs(),
+            src,
+            target,
+            palette,
+        )
+    }
+
+    /// Compile a past def looked up by a failed `expect`, so its value can be shown.
+    pub fn compile_lookup<'a>(
+        &self,
+        arena: &'a Bump,
+        ident: &str,
+        target: Target,
+        palette: Palette,
+    ) -> Option<MonomorphizedModule<'a>> {
+        let (opt_mono, _) = self.compile(arena, ident, target, palette);
+
+        opt_mono
+    }
+
+    /// Type check `src` as the expression to evaluate, after all the past defs, without compiling it.
+    fn check(
+        &self,
+        arena: &Bump,
+        src: &str,
+        target: Target,
+        palette: Palette,
+    ) -> (Option<LoadedModule>, Problems) {
+        compile_to_typed(
+            arena,
+            &self.packages,
+            self.past_def_srcs(),
             src,
             target,
             palette,
-        );
+        )
+    }
+
+    fn past_def_srcs(&self) -> impl Iterator<Item = &str> {
+        self.past_defs.iter().map(|past_def| match past_def {
+            PastDef::Def { idents: _, src } => src.as_str(),
+            PastDef::Import(src) => src.as_str(),
+        })
+    }
+
+    /// `:type <expr>` prints the type of the expression, without evaluating it.
+    fn type_of<'a>(
+        &self,
+        arena: &'a Bump,
+        src: &str,
+        target: Target,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        let (opt_loaded, problems) = self.check(arena, src, target, palette);
+
+        let opt_output = opt_loaded.and_then(|mut loaded| {
+            let (_, var) = repl_output(&loaded)?;
+
+            Some(ReplOutput {
+                expr: src.to_string(),
+                expr_type: print_type(&mut loaded, var),
+            })
+        });
+
+        ReplAction::Output(format_output(STYLE_CODES, opt_output, problems))
+    }
+
+    /// `:doc <name>` prints the type and doc comment of a builtin or imported definition.
+    fn doc<'a>(
+        &self,
+        arena: &'a Bump,
+        name: &str,
+        target: Target,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        let (opt_loaded, problems) = self.check(arena, name, target, palette);
 
-        if let Some((ident, src)) = pending_past_def {
-            self.add_past_def(ident, src);
+        if !problems.errors.is_empty() {
+            return ReplAction::Output(format_output(STYLE_CODES, None, problems));
         }
 
-        ReplAction::Eval { opt_mono, problems }
+        let Some(mut loaded) = opt_loaded else {
+            return ReplAction::Nothing;
+        };
+
+        let Some((output_symbol, var)) = repl_output(&loaded) else {
+            return ReplAction::Nothing;
+        };
+
+        // The expression is just the name, so the body of its def is a lookup of the symbol
+        // the name refers to.
+        let opt_symbol = loaded
+            .declarations_by_id
+            .get(&loaded.module_id)
+            .and_then(|decls| {
+                let index = decls
+                    .symbols
+                    .iter()
+                    .position(|symbol| symbol.value == output_symbol)?;
+
+                match decls.expressions[index].value {
+                    can::expr::Expr::Var(symbol, _)
+                    | can::expr::Expr::AbilityMember(symbol, _, _) => Some(symbol),
+                    _ => None,
+                }
+            });
+
+        let Some(symbol) = opt_symbol else {
+            return ReplAction::Output(format!(
+                "{name} is not a name, so it has no docs. Try something like {}List.map{}",
+                STYLE_CODES.cyan, STYLE_CODES.reset
+            ));
+        };
+
+        let docs = if symbol.module_id() == loaded.module_id {
+            self.past_def_docs(symbol.as_str(&loaded.interns))
+        } else {
+            loaded
+                .docs_by_module
+                .get(&symbol.module_id())
+                .and_then(|module_docs| module_docs.get_doc_for_symbol(&symbol))
+        };
+
+        let opt_output = Some(ReplOutput {
+            expr: name.to_string(),
+            expr_type: print_type(&mut loaded, var),
+        });
+
+        let mut buf = format_output(STYLE_CODES, opt_output, problems);
+
+        buf.push_str("\n\n");
+
+        match docs {
+            Some(docs) => buf.push_str(docs.trim()),
+            None => buf.push_str("There are no docs for this definition."),
+        }
+
+        ReplAction::Output(buf)
     }
 
-    fn add_past_def(&mut self, ident: String, src: String) {
+    /// `:defs` lists the past defs, with the types of the values.
+    fn defs<'a>(&self, arena: &'a Bump, target: Target, palette: Palette) -> ReplAction<'a> {
+        if self.past_defs.is_empty() {
+            return ReplAction::Output("There are no past definitions yet.".to_string());
+        }
+
+        let (opt_loaded, _) = self.check(arena, "{}", target, palette);
+        let mut lines = Vec::new();
+
+        for past_def in self.past_defs.iter() {
+            match past_def {
+                PastDef::Def { idents, src } => {
+                    for ident in idents {
+                        let opt_var = opt_loaded
+                            .as_ref()
+                            .and_then(|loaded| past_def_var(loaded, ident));
+
+                        match (opt_loaded.as_ref(), opt_var) {
+                            (Some(loaded), Some(var)) => {
+                                let mut subs = loaded.solved.inner().clone();
+                                let expr_type = name_and_print_var(
+                                    var,
+                                    &mut subs,
+                                    loaded.module_id,
+                                    &loaded.interns,
+                                    DebugPrint::NOTHING,
+                                );
+
+                                lines.push(format!(
+                                    "{ident}{} : {}{expr_type}",
+                                    STYLE_CODES.green, STYLE_CODES.reset
+                                ));
+                            }
+                            // Type definitions, and values whose type is unknown because of an error
+                            _ => lines.push(src.clone()),
+                        }
+                    }
+                }
+                PastDef::Import(src) => lines.push(src.clone()),
+            }
+        }
+
+        lines.dedup();
+
+        ReplAction::Output(lines.join("\n"))
+    }
+
+    /// `:save <file>` writes the past defs to a file, which `:load` can replay later.
+    fn save<'a>(&self, filename: PathBuf) -> ReplAction<'a> {
+        let mut src = self.past_def_srcs().collect::<Vec<_>>().join("\n\n");
+
+        src.push('\n');
+
+        match fs::write(&filename, src) {
+            Ok(()) => ReplAction::Output(format!(
+                "Saved {} past definitions to {}",
+                self.past_defs.len(),
+                filename.display()
+            )),
+            Err(err) => ReplAction::FileProblem {
+                filename,
+                error: err.kind(),
+            },
+        }
+    }
+
+    /// `:load <file>` replays each of the defs in a file, as if they were entered at the prompt.
+    fn load<'a>(
+        &mut self,
+        arena: &'a Bump,
+        filename: PathBuf,
+        target: Target,
+        palette: Palette,
+    ) -> ReplAction<'a> {
+        let src = match fs::read_to_string(&filename) {
+            Ok(src) => arena.alloc_str(&src),
+            Err(err) => {
+                return ReplAction::FileProblem {
+                    filename,
+                    error: err.kind(),
+                }
+            }
+        };
+
+        let defs = match parse_module_defs(arena, State::new(src.as_bytes()), Defs::default()) {
+            Ok(defs) => defs,
+            Err(_) => {
+                return ReplAction::Output(format!(
+                    "I could not parse the definitions in {}",
+                    filename.display()
+                ))
+            }
+        };
+
+        let mut problems = Problems::default();
+
+        for region in defs.regions.iter() {
+            let def_src = &src[region.start().offset as usize..region.end().offset as usize];
+
+            match self.step(arena, def_src, target, palette) {
+                ReplAction::Eval {
+                    problems: def_problems,
+                    ..
+                } => {
+                    problems.errors.extend(def_problems.errors);
+                }
+                ReplAction::FileProblem { filename, error } => {
+                    problems
+                        .errors
+                        .push(to_file_problem_report_string(filename, error));
+                }
+                _ => {}
+            }
+        }
+
+        let mut buf = format_output(STYLE_CODES, None, problems);
+
+        buf.push_str(&format!(
+            "\nLoaded {} definitions from {}",
+            defs.regions.len(),
+            filename.display()
+        ));
+
+        ReplAction::Output(buf)
+    }
+
+    /// The past defs looked up in `src`, in the order they first appear.
+    fn lookups<'s>(&self, src: &'s str) -> Vec<&'s str> {
+        let tokens = roc_parse::highlight::highlight(src);
+        let mut lookups = Vec::new();
+
+        for (index, token) in tokens.iter().enumerate() {
+            // Skip record fields and qualified names like `point.x` and `List.len`
+            let is_access = index > 0 && tokens[index - 1].value == Token::Decimal;
+
+            if token.value == Token::LowerIdent && !is_access {
+                let ident = region_src(src, token.region);
+
+                if self.past_def_idents.contains(ident) && !lookups.contains(&ident) {
+                    lookups.push(ident);
+                }
+            }
+        }
+
+        lookups
+    }
+
+    /// The loader doesn't generate docs for app modules, like the one the REPL compiles, so the
+    /// docs for a past def are read from the doc comments at the start of its source.
+    fn past_def_docs(&self, ident: &str) -> Option<String> {
+        let src = self
+            .past_defs
+            .iter()
+            .rev()
+            .find_map(|past_def| match past_def {
+                PastDef::Def { idents, src } if idents.iter().any(|other| other == ident) => {
+                    Some(src)
+                }
+                _ => None,
+            })?;
+
+        let mut docs = String::new();
+
+        for line in src.lines().map(str::trim_start) {
+            match line.strip_prefix("##") {
+                Some(doc) if !doc.starts_with('#') => {
+                    docs.push_str(doc.strip_prefix(' ').unwrap_or(doc));
+                    docs.push('\n');
+                }
+                _ => break,
+            }
+        }
+
+        (!docs.is_empty()).then_some(docs)
+    }
+
+    fn add_past_def(&mut self, idents: Vec<String>, src: String) {
         let existing_idents = &mut self.past_def_idents;
 
-        existing_idents.insert(ident.clone());
+        existing_idents.extend(idents.iter().cloned());
 
-        self.past_defs.push(PastDef::Def { ident, src });
+        self.past_defs.push(PastDef::Def { idents, src });
     }
 }
 
+/// The symbol and type of the def holding the expression being evaluated.
+fn repl_output(loaded: &LoadedModule) -> Option<(Symbol, Variable)> {
+    loaded
+        .exposed_to_host
+        .iter()
+        .next()
+        .map(|(symbol, var)| (*symbol, *var))
+}
+
+fn print_type(loaded: &mut LoadedModule, var: Variable) -> String {
+    name_and_print_var(
+        var,
+        loaded.solved.inner_mut(),
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    )
+}
+
+/// The type of the top-level def of a past def's name, if it's a value.
+fn past_def_var(loaded: &LoadedModule, ident: &str) -> Option<Variable> {
+    let ident_id = loaded
+        .interns
+        .all_ident_ids
+        .get(&loaded.module_id)?
+        .get_id(ident)?;
+    let symbol = Symbol::new(loaded.module_id, ident_id);
+    let decls = loaded.declarations_by_id.get(&loaded.module_id)?;
+    let index = decls
+        .symbols
+        .iter()
+        .position(|loc_symbol| loc_symbol.value == symbol)?;
+
+    Some(decls.variables[index])
+}
+
+fn region_src(src: &str, region: Region) -> &str {
+    &src[region.start().offset as usize..region.end().offset as usize]
+}
+
+/// A name bound by the pattern of a destructuring def
+struct Binding<'p> {
+    ident: &'p str,
+    region: Region,
+    /// Whether this is a record field pun like the `x` in `{ x }`, which has to be expanded
+    /// to `x: newName` to rename it
+    is_pun: bool,
+}
+
+fn pattern_bindings<'p>(
+    pattern: &Pattern<'p>,
+    region: Region,
+    in_record: bool,
+    bindings: &mut Vec<Binding<'p>>,
+) {
+    match pattern {
+        Pattern::Identifier { ident } => bindings.push(Binding {
+            ident,
+            region,
+            is_pun: in_record,
+        }),
+        Pattern::Apply(_, args) => {
+            for arg in args.iter() {
+                pattern_bindings(&arg.value, arg.region, false, bindings);
+            }
+        }
+        Pattern::RecordDestructure(fields) => {
+            for field in fields.iter() {
+                pattern_bindings(&field.value, field.region, true, bindings);
+            }
+        }
+        Pattern::Tuple(items) | Pattern::List(items) => {
+            for item in items.iter() {
+                pattern_bindings(&item.value, item.region, false, bindings);
+            }
+        }
+        Pattern::RequiredField(_, loc_pattern) => {
+            pattern_bindings(&loc_pattern.value, loc_pattern.region, false, bindings)
+        }
+        Pattern::ListRest(Some((_, pattern_as))) => bindings.push(Binding {
+            ident: pattern_as.identifier.value,
+            region: pattern_as.identifier.region,
+            is_pun: false,
+        }),
+        Pattern::As(loc_pattern, pattern_as) => {
+            pattern_bindings(&loc_pattern.value, loc_pattern.region, false, bindings);
+
+            bindings.push(Binding {
+                ident: pattern_as.identifier.value,
+                region: pattern_as.identifier.region,
+                is_pun: false,
+            });
+        }
+        Pattern::SpaceBefore(pattern, _) | Pattern::SpaceAfter(pattern, _) => {
+            pattern_bindings(pattern, region, in_record, bindings)
+        }
+        // Optional record fields only have defaults in function arguments.
+        Pattern::OptionalField(_, _)
+        | Pattern::QualifiedIdentifier { .. }
+        | Pattern::Tag(_)
+        | Pattern::OpaqueRef(_)
+        | Pattern::NumLiteral(_)
+        | Pattern::NonBase10Literal { .. }
+        | Pattern::FloatLiteral(_)
+        | Pattern::StrLiteral(_)
+        | Pattern::Underscore(_)
+        | Pattern::SingleQuote(_)
+        | Pattern::ListRest(None)
+        | Pattern::Malformed(_)
+        | Pattern::MalformedIdent(_, _) => {}
+    }
+}
+
+/// The past def for a destructuring def like `{ x, y } = point`. Top-level destructuring isn't
+/// supported, so each name gets its own def which destructures the body again:
+///
+/// ```roc
+/// x =
+///     { x: replX, y: replY } = point
+///     replX
+/// ```
+///
+/// The names are renamed inside, since they would otherwise shadow the top-level defs.
+fn destructuring_past_def(line: &str, bindings: &[Binding<'_>], idents: &[&str]) -> String {
+    let mut renamed = String::with_capacity(line.len());
+    let mut offset = 0;
+
+    for binding in bindings {
+        let start = binding.region.start().offset as usize;
+
+        renamed.push_str(&line[offset..start]);
+
+        if binding.is_pun {
+            renamed.push_str(binding.ident);
+            renamed.push_str(": ");
+        }
+
+        renamed.push_str(&repl_name(binding.ident));
+
+        offset = binding.region.end().offset as usize;
+    }
+
+    renamed.push_str(&line[offset..]);
+
+    let defs: Vec<String> = idents
+        .iter()
+        .map(|ident| {
+            let mut def = format!("{ident} =\n");
+
+            for line in renamed.lines() {
+                def.push_str("    ");
+                def.push_str(line);
+                def.push('\n');
+            }
+
+            def.push_str("    ");
+            def.push_str(&repl_name(ident));
+
+            def
+        })
+        .collect();
+
+    defs.join("\n\n")
+}
+
+fn repl_name(ident: &str) -> String {
+    let mut name = String::from("repl");
+    let mut chars = ident.chars();
+
+    if let Some(first) = chars.next() {
+        name.push(first.to_ascii_uppercase());
+    }
+
+    name.push_str(chars.as_str());
+
+    name
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ParseOutcome<'a> {
     ValueDef(ValueDef<'a>),
@@ -259,6 +916,20 @@ pub enum ParseOutcome<'a> {
     Empty,
     Help,
     Exit,
+    /// `:type <expr>`
+    Type(&'a str),
+    /// `:doc <name>`
+    Doc(&'a str),
+    /// `:defs`
+    Defs,
+    /// `:reset`
+    Reset,
+    /// `:save <file>`
+    Save(&'a str),
+    /// `:load <file>`
+    Load(&'a str),
+    /// `:time <expr>`
+    Time(&'a str),
 }
 
 pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
@@ -270,10 +941,16 @@ pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
         // If you really need to evaluate `exit` for some reason,
         // you can do `foo = exit` and then evaluate `foo` instead.
         ":exit" | ":quit" | ":q" | "exit" | "quit" | "exit()" | "quit()" => ParseOutcome::Exit,
+        ":defs" => ParseOutcome::Defs,
+        ":reset" => ParseOutcome::Reset,
         _ => {
+            if let Some(outcome) = parse_command(line.trim()) {
+                return outcome;
+            }
+
             let src_bytes = line.as_bytes();
 
-            match roc_parse::expr::loc_expr(true).parse(arena, State::new(src_bytes), 0) {
+            match loc_expr(true).parse(arena, State::new(src_bytes), 0) {
                 Ok((_, loc_expr, _)) => ParseOutcome::Expr(loc_expr.value),
                 Err((roc_parse::parser::Progress::MadeProgress, EExpr::Start(_))) => {
                     ParseOutcome::Empty
@@ -282,8 +959,11 @@ pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
                 Err((_, EExpr::Closure(EClosure::Body(_, _), _)))
                 | Err((_, EExpr::When(EWhen::Pattern(EPattern::Start(_), _), _)))
                 | Err((_, EExpr::Record(_, _)))
-                | Err((_, EExpr::Start(_)))
                 | Err((_, EExpr::IndentStart(_))) => ParseOutcome::Incomplete,
+                Err((_, EExpr::Start(_))) => match parse_statement(arena, src_bytes) {
+                    Some(value_def) => ParseOutcome::ValueDef(value_def),
+                    None => ParseOutcome::Incomplete,
+                },
                 Err((_, EExpr::DefMissingFinalExpr(_)))
                 | Err((_, EExpr::DefMissingFinalExpr2(_, _))) => {
                     // This indicates that we had an attempted def; re-parse it as a single-line def.
@@ -432,3 +1112,72 @@ pub fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
         }
     }
 }
+
+/// Parse a `dbg`, `expect` or `expect-fx` statement, which can't start an expression.
+fn parse_statement<'a>(arena: &'a Bump, src_bytes: &'a [u8]) -> Option<ValueDef<'a>> {
+    let state = State::new(src_bytes);
+
+    if let Ok((_, _, state)) =
+        keyword(roc_parse::keyword::DBG, EExpect::Dbg).parse(arena, state.clone(), 0)
+    {
+        let (_, condition, state) = space0_before_e(loc_expr(true), EExpr::IndentStart)
+            .parse(arena, state, 0)
+            .ok()?;
+
+        // The whole input has to be the condition, or it's still being written.
+        if !src_bytes[state.pos().offset as usize..]
+            .iter()
+            .all(u8::is_ascii_whitespace)
+        {
+            return None;
+        }
+
+        return Some(ValueDef::Dbg {
+            condition: arena.alloc(condition),
+            preceding_comment: Region::zero(),
+        });
+    }
+
+    match parse_single_def(
+        ExprParseOptions {
+            accept_multi_backpassing: true,
+            check_for_arrow: true,
+        },
+        0,
+        arena,
+        state,
+    ) {
+        Ok((
+            _,
+            Some(SingleDef {
+                type_or_value:
+                    Either::Second(value_def @ (ValueDef::Expect { .. } | ValueDef::ExpectFx { .. })),
+                ..
+            }),
+            _,
+        )) => Some(value_def),
+        _ => None,
+    }
+}
+
+/// Parse a meta-command which takes an argument, like `:type <expr>`.
+fn parse_command(line: &str) -> Option<ParseOutcome<'_>> {
+    let (command, arg) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let arg = arg.trim();
+
+    let outcome = match command {
+        ":type" => ParseOutcome::Type(arg),
+        ":doc" => ParseOutcome::Doc(arg),
+        ":save" => ParseOutcome::Save(arg),
+        ":load" => ParseOutcome::Load(arg),
+        ":time" => ParseOutcome::Time(arg),
+        _ => return None,
+    };
+
+    if arg.is_empty() {
+        // Show how to use the command.
+        Some(ParseOutcome::Help)
+    } else {
+        Some(outcome)
+    }
+}
@@ -14,6 +14,9 @@ extern "C" {
 
     pub fn js_get_result_and_memory(buffer_alloc_addr: *mut u8) -> usize;
 
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    pub fn js_now_ms() -> f64;
+
     #[wasm_bindgen(js_namespace = console)]
     fn log(s: &str);
 }
@@ -26,6 +26,11 @@ pub fn js_get_result_and_memory(buffer_alloc_addr: *mut u8) -> usize {
     unsafe { test_get_result_and_memory(buffer_alloc_addr) }
 }
 
+/// The test interpreter has no clock, so everything takes no time at all
+pub fn js_now_ms() -> f64 {
+    0.0
+}
+
 /// Entrypoint for tests using WASI and a CLI interpreter
 /// - Synchronous API, to avoid the need to run an async executor across the Wasm/native boundary.
 /// - Uses an extra callback to allocate & copy the input string (in the browser version, wasm_bindgen does this)
@@ -1,25 +1,28 @@
 use bumpalo::{collections::vec::Vec, Bump};
 use roc_reporting::report::{DEFAULT_PALETTE_HTML, HTML_STYLE_CODES};
-use std::{cell::RefCell, mem::size_of};
+use std::{cell::RefCell, mem::size_of, time::Duration};
 
-use roc_collections::all::MutSet;
+use roc_collections::all::{MutMap, MutSet};
 use roc_gen_wasm::wasm32_result;
 use roc_load::MonomorphizedModule;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::{Proc, ProcLayout};
+use roc_mono::layout::STLayoutInterner;
 use roc_parse::ast::Expr;
 use roc_repl_eval::{
-    eval::jit_to_ast,
+    eval::{jit_to_ast, jit_to_bool},
     gen::{format_answer, ReplOutput},
     ReplApp, ReplAppMemory,
 };
 use roc_repl_ui::{
-    format_output,
+    expect_failed, format_dbg_output, format_expect_output, format_output, format_time_output,
     repl_state::{ReplAction, ReplState},
     TIPS,
 };
 use roc_target::Target;
 use roc_types::pretty_print::{name_and_print_var, DebugPrint};
 
-use crate::{js_create_app, js_get_result_and_memory, js_run_app};
+use crate::{js_create_app, js_get_result_and_memory, js_now_ms, js_run_app};
 
 const WRAPPER_NAME: &str = "wrapper";
 
@@ -31,6 +34,8 @@ std::thread_local! {
 
 pub struct WasmReplApp<'a> {
     arena: &'a Bump,
+    /// Time spent running the app's main function and copying out its memory
+    run_time: Duration,
 }
 
 /// A copy of the app's memory, made after running the main function
@@ -134,11 +139,13 @@ impl<'a> ReplApp<'a> for WasmReplApp<'a> {
         F: FnMut(&'a Self::Memory, Return) -> Expr<'a>,
         Self::Memory: 'a,
     {
+        let start = js_now_ms();
         let app_final_memory_size: usize = js_run_app();
 
         let copied_bytes: &mut [u8] = self.allocate_buffer(app_final_memory_size);
 
         let app_result_addr = js_get_result_and_memory(copied_bytes.as_mut_ptr());
+        self.run_time += elapsed_since(start);
 
         let result_bytes = &copied_bytes[app_result_addr..];
         let result: Return = unsafe {
@@ -165,11 +172,13 @@ impl<'a> ReplApp<'a> for WasmReplApp<'a> {
         F: FnMut(&'a Self::Memory, usize) -> T,
         Self::Memory: 'a,
     {
+        let start = js_now_ms();
         let app_final_memory_size: usize = js_run_app();
 
         let copied_bytes: &mut [u8] = self.allocate_buffer(app_final_memory_size);
 
         let app_result_addr = js_get_result_and_memory(copied_bytes.as_mut_ptr());
+        self.run_time += elapsed_since(start);
         let mem = self.arena.alloc(WasmMemory { copied_bytes });
 
         Some(transform(mem, app_result_addr))
@@ -207,6 +216,19 @@ pub async fn entrypoint_from_js(src: String) -> String {
             "The web version of the REPL cannot import files... for now!".to_string()
         }
         ReplAction::Nothing => String::new(),
+        ReplAction::Output(output) => output,
+        ReplAction::Time { opt_mono, problems } => {
+            // Only time running the code, not compiling it
+            let (opt_output, elapsed) = match opt_mono {
+                Some(mono) => match eval_wasm_timed(arena, target, mono).await {
+                    Some((output, elapsed)) => (Some(output), elapsed),
+                    None => (None, Duration::ZERO),
+                },
+                None => (None, Duration::ZERO),
+            };
+
+            format_time_output(HTML_STYLE_CODES, opt_output, problems, elapsed)
+        }
         ReplAction::Eval { opt_mono, problems } => {
             let opt_output = match opt_mono {
                 Some(mono) => eval_wasm(arena, target, mono).await,
@@ -215,6 +237,49 @@ pub async fn entrypoint_from_js(src: String) -> String {
 
             format_output(HTML_STYLE_CODES, opt_output, problems)
         }
+        ReplAction::Dbg {
+            src,
+            opt_mono,
+            problems,
+        } => {
+            let opt_output = match opt_mono {
+                Some(mono) => eval_wasm(arena, target, mono).await,
+                None => None,
+            };
+
+            format_dbg_output(HTML_STYLE_CODES, src, opt_output, problems)
+        }
+        ReplAction::Expect {
+            src,
+            opt_mono,
+            problems,
+            lookups,
+        } => {
+            let opt_held = match opt_mono {
+                Some(mono) => eval_wasm_condition(arena, mono).await,
+                None => None,
+            };
+
+            // The looked up values are only needed to report a failure, so only compile them then.
+            let mut outputs = std::vec::Vec::new();
+
+            if expect_failed(opt_held, &problems) {
+                for ident in lookups {
+                    let opt_mono = REPL_STATE.with(|repl_state_cell| {
+                        let repl_state = repl_state_cell.borrow();
+                        repl_state.compile_lookup(arena, ident, target, DEFAULT_PALETTE_HTML)
+                    });
+
+                    if let Some(mono) = opt_mono {
+                        if let Some(output) = eval_wasm(arena, target, mono).await {
+                            outputs.push((ident, output));
+                        }
+                    }
+                }
+            }
+
+            format_expect_output(HTML_STYLE_CODES, src, opt_held, problems, outputs)
+        }
     }
 }
 
@@ -223,6 +288,18 @@ async fn eval_wasm<'a>(
     target: Target,
     mono: MonomorphizedModule<'a>,
 ) -> Option<ReplOutput> {
+    eval_wasm_timed(arena, target, mono)
+        .await
+        .map(|(output, _)| output)
+}
+
+/// Like [eval_wasm], but also returns how long the app took to run. This excludes the time spent
+/// compiling and instantiating it.
+async fn eval_wasm_timed<'a>(
+    arena: &'a Bump,
+    target: Target,
+    mono: MonomorphizedModule<'a>,
+) -> Option<(ReplOutput, Duration)> {
     let MonomorphizedModule {
         module_id,
         procedures,
@@ -249,59 +326,32 @@ async fn eval_wasm<'a>(
 
     let (_, main_fn_layout) = *procedures.keys().find(|(s, _)| *s == main_fn_symbol)?;
 
-    let app_module_bytes = {
-        let env = roc_gen_wasm::Env {
-            arena,
-            module_id,
-            stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
-            exposed_to_host: exposed_to_host
-                .top_level_values
-                .keys()
-                .copied()
-                .collect::<MutSet<_>>(),
-        };
-
-        let (mut module, mut called_fns, main_fn_index) = {
-            let host_module = roc_gen_wasm::parse_host(env.arena, PRE_LINKED_BINARY).unwrap();
-            roc_gen_wasm::build_app_module(
-                &env,
-                &mut layout_interner,
-                &mut interns, // NOTE: must drop this mutable ref before jit_to_ast
-                host_module,
-                procedures,
-            )
-        };
-
-        wasm32_result::insert_wrapper_for_layout(
-            arena,
-            &layout_interner,
-            &mut module,
-            WRAPPER_NAME,
-            main_fn_index,
-            main_fn_layout.result,
-        );
-        called_fns.push(true);
-
-        module.eliminate_dead_code(env.arena, called_fns);
-
-        let mut buffer = Vec::with_capacity_in(module.size(), arena);
-        module.serialize(&mut buffer);
-
-        buffer
-    };
+    let app_module_bytes = build_app_module_bytes(
+        arena,
+        module_id,
+        procedures,
+        &mut interns, // NOTE: must drop this mutable ref before jit_to_ast
+        exposed_to_host.top_level_values.keys().copied().collect(),
+        &mut layout_interner,
+        main_fn_layout,
+    );
 
     // Send the compiled binary out to JS, which will asynchronously create an executable WebAssembly instance
     match js_create_app(&app_module_bytes).await {
         Ok(()) => {}
         Err(js_exception) => {
-            return Some(ReplOutput {
+            let output = ReplOutput {
                 expr: format!("<span class='color-red'>{js_exception:?}</span>"),
                 expr_type: String::new(),
-            })
+            };
+            return Some((output, Duration::ZERO));
         }
     }
 
-    let mut app = WasmReplApp { arena };
+    let mut app = WasmReplApp {
+        arena,
+        run_time: Duration::ZERO,
+    };
 
     // Run the app and transform the result value to an AST `Expr`
     // Restore type constructor names, and other user-facing info that was erased during compilation.
@@ -320,5 +370,84 @@ async fn eval_wasm<'a>(
     // Transform the Expr to a string
     let expr = format_answer(arena, res_answer).to_string();
 
-    Some(ReplOutput { expr, expr_type })
+    Some((ReplOutput { expr, expr_type }, app.run_time))
+}
+
+/// Compile and run the condition of an `expect`, and return whether it held.
+/// This is [None] if the app could not be created, or crashed.
+async fn eval_wasm_condition<'a>(arena: &'a Bump, mono: MonomorphizedModule<'a>) -> Option<bool> {
+    let MonomorphizedModule {
+        module_id,
+        procedures,
+        mut interns,
+        exposed_to_host,
+        mut layout_interner,
+        ..
+    } = mono;
+
+    debug_assert_eq!(exposed_to_host.top_level_values.len(), 1);
+    let main_fn_symbol = *exposed_to_host.top_level_values.keys().next().unwrap();
+    let (_, main_fn_layout) = *procedures.keys().find(|(s, _)| *s == main_fn_symbol)?;
+
+    let app_module_bytes = build_app_module_bytes(
+        arena,
+        module_id,
+        procedures,
+        &mut interns,
+        exposed_to_host.top_level_values.keys().copied().collect(),
+        &mut layout_interner,
+        main_fn_layout,
+    );
+
+    js_create_app(&app_module_bytes).await.ok()?;
+
+    let mut app = WasmReplApp {
+        arena,
+        run_time: Duration::ZERO,
+    };
+
+    jit_to_bool(&mut app, "")
+}
+
+fn build_app_module_bytes<'a>(
+    arena: &'a Bump,
+    module_id: ModuleId,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    interns: &mut Interns,
+    exposed_to_host: MutSet<Symbol>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    main_fn_layout: ProcLayout<'a>,
+) -> Vec<'a, u8> {
+    let env = roc_gen_wasm::Env {
+        arena,
+        module_id,
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        exposed_to_host,
+    };
+
+    let (mut module, mut called_fns, main_fn_index) = {
+        let host_module = roc_gen_wasm::parse_host(env.arena, PRE_LINKED_BINARY).unwrap();
+        roc_gen_wasm::build_app_module(&env, layout_interner, interns, host_module, procedures)
+    };
+
+    wasm32_result::insert_wrapper_for_layout(
+        arena,
+        layout_interner,
+        &mut module,
+        WRAPPER_NAME,
+        main_fn_index,
+        main_fn_layout.result,
+    );
+    called_fns.push(true);
+
+    module.eliminate_dead_code(env.arena, called_fns);
+
+    let mut buffer = Vec::with_capacity_in(module.size(), arena);
+    module.serialize(&mut buffer);
+
+    buffer
+}
+
+fn elapsed_since(start_ms: f64) -> Duration {
+    Duration::from_secs_f64((js_now_ms() - start_ms).max(0.0) / 1000.0)
 }
@@ -67,8 +67,7 @@ void roc_panic(void *ptr, unsigned int panic_tag)
     abort();
 }
 
-// TODO: add a way to send dbg to js.
-void roc_debug(void* loc, void* msg) {}
+// roc_dbg is left as an import, and implemented in JS
 
 //--------------------------
 
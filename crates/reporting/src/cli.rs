@@ -63,6 +63,7 @@ impl Problems {
 pub fn report_problems(
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
     interns: &Interns,
+    syntax_problems: &mut MutMap<ModuleId, Vec<String>>,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
 ) -> Problems {
@@ -72,6 +73,10 @@ pub fn report_problems(
     let palette = DEFAULT_PALETTE;
     let mut total_problems = 0;
 
+    for problems in syntax_problems.values() {
+        total_problems += problems.len();
+    }
+
     for problems in can_problems.values() {
         total_problems += problems.len();
     }
@@ -96,6 +101,9 @@ pub fn report_problems(
         // Report parsing and canonicalization problems
         let alloc = RocDocAllocator::new(&src_lines, *home, interns);
 
+        // Syntax errors were rendered when they were recovered from, and are always errors
+        errors.extend(syntax_problems.remove(home).unwrap_or_default());
+
         let problems = type_problems.remove(home).unwrap_or_default();
 
         for problem in problems {
@@ -158,7 +166,7 @@ pub fn report_problems(
         }
     }
 
-    debug_assert!(can_problems.is_empty() && type_problems.is_empty(), "After reporting problems, there were {:?} can_problems and {:?} type_problems that could not be reported because they did not have corresponding entries in `sources`.", can_problems.len(), type_problems.len());
+    debug_assert!(syntax_problems.is_empty() && can_problems.is_empty() && type_problems.is_empty(), "After reporting problems, there were {:?} syntax_problems, {:?} can_problems and {:?} type_problems that could not be reported because they did not have corresponding entries in `sources`.", syntax_problems.len(), can_problems.len(), type_problems.len());
     debug_assert_eq!(errors.len() + warnings.len(), total_problems);
 
     let problems_reported;
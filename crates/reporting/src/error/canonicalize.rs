@@ -2010,6 +2010,15 @@ fn pretty_runtime_error<'b>(
         RuntimeError::MalformedSuffixed(_) => {
             todo!("error for malformed suffix");
         }
+        RuntimeError::MalformedExpr(region) => {
+            doc = alloc.stack([
+                alloc.reflow("I could not parse this definition:"),
+                alloc.region(lines.convert_region(region), severity),
+                alloc.reflow("It will crash if it is ever used."),
+            ]);
+
+            title = SYNTAX_PROBLEM;
+        }
         RuntimeError::InvalidFloat(sign @ FloatErrorKind::PositiveInfinity, region, _raw_str)
         | RuntimeError::InvalidFloat(sign @ FloatErrorKind::NegativeInfinity, region, _raw_str) => {
             let tip = alloc
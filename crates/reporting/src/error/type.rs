@@ -5034,6 +5034,23 @@ fn exhaustive_pattern_to_doc<'b>(
     pattern_to_doc_help(alloc, pattern, false)
 }
 
+/// A pattern that a `when` does not cover, written the way it would appear in a new branch.
+pub fn missing_branch_pattern_to_doc<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    pattern: roc_exhaustive::Pattern,
+) -> RocDocBuilder<'b> {
+    match pattern {
+        // #Guard <fake-condition-tag> <unexhausted-pattern>
+        roc_exhaustive::Pattern::Ctor(union, _, mut args)
+            if matches!(union.render_as, roc_exhaustive::RenderAs::Guard) =>
+        {
+            debug_assert!(args.len() == 2);
+            pattern_to_doc_help(alloc, args.swap_remove(1), false)
+        }
+        _ => pattern_to_doc_help(alloc, pattern, false),
+    }
+}
+
 const AFTER_TAG_INDENT: &str = "    ";
 const TAG_INDENT: usize = 4;
 const RECORD_FIELD_INDENT: usize = 4;
@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+
+# https://vaneyckt.io/posts/safer_bash_scripts_with_set_euxo_pipefail/
+set -euxo pipefail
+
+# Copy the official WebAssembly spec test scripts into testsuite/
+# Usage: ./fetch_testsuite.sh [GIT_REF]
+# Without GIT_REF, re-fetches the commit pinned in testsuite/COMMIT (or main if there is none)
+
+SCRIPT_DIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+PINNED_REF=$(cat "$SCRIPT_DIR/testsuite/COMMIT" 2>/dev/null || echo main)
+GIT_REF=${1:-$PINNED_REF}
+CLONE_DIR=$(mktemp -d)
+trap 'rm -rf "$CLONE_DIR"' EXIT
+
+git clone --quiet https://github.com/WebAssembly/testsuite.git "$CLONE_DIR"
+git -C "$CLONE_DIR" checkout --quiet "$GIT_REF"
+
+mkdir -p "$SCRIPT_DIR/testsuite"
+cp "$CLONE_DIR"/*.wast "$SCRIPT_DIR/testsuite/"
+git -C "$CLONE_DIR" rev-parse HEAD > "$SCRIPT_DIR/testsuite/COMMIT"
+
+echo "Copied WebAssembly test suite at commit $(cat "$SCRIPT_DIR/testsuite/COMMIT")"
@@ -0,0 +1,52 @@
+;; Blocks with result values, and branches that carry them
+
+(module
+  (func $fact (export "fact") (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 1))
+      (else
+        (i32.mul (local.get 0) (call $fact (i32.sub (local.get 0) (i32.const 1)))))))
+
+  (func (export "select_if") (param i32) (result i32)
+    (if (result i32) (local.get 0)
+      (then (i32.const 10))
+      (else (i32.const 20))))
+
+  (func (export "if_no_else") (param i32) (result i32)
+    (local i32)
+    (local.set 1 (i32.const 1))
+    (if (local.get 0)
+      (then (local.set 1 (i32.const 2))))
+    (local.get 1))
+
+  (func (export "br_block") (param i32) (result i32)
+    (i32.add
+      (i32.const 100)
+      (block (result i32)
+        (drop (br_if 0 (i32.const 1) (local.get 0)))
+        (i32.const 2))))
+
+  (func (export "br_function") (param i32) (result i32)
+    (drop (br_if 0 (i32.const 3) (local.get 0)))
+    (i32.const 4))
+)
+
+(assert_return (invoke "fact" (i32.const 0)) (i32.const 1))
+(assert_return (invoke "fact" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "fact" (i32.const 5)) (i32.const 120))
+
+(assert_return (invoke "select_if" (i32.const 0)) (i32.const 20))
+(assert_return (invoke "select_if" (i32.const 1)) (i32.const 10))
+(assert_return (invoke "select_if" (i32.const 0)) (i32.const 20))
+
+;; The second call of each uses the branch cache
+(assert_return (invoke "if_no_else" (i32.const 0)) (i32.const 1))
+(assert_return (invoke "if_no_else" (i32.const 0)) (i32.const 1))
+(assert_return (invoke "if_no_else" (i32.const 1)) (i32.const 2))
+
+(assert_return (invoke "br_block" (i32.const 0)) (i32.const 102))
+(assert_return (invoke "br_block" (i32.const 1)) (i32.const 101))
+(assert_return (invoke "br_block" (i32.const 1)) (i32.const 101))
+
+(assert_return (invoke "br_function" (i32.const 0)) (i32.const 4))
+(assert_return (invoke "br_function" (i32.const 1)) (i32.const 3))
@@ -0,0 +1,117 @@
+;; Branches out of nested blocks and loops, in the same format as the official spec tests
+
+(module
+  ;; Branch out of several blocks at once, carrying a value
+  (func (export "nested") (param i32) (result i32)
+    (block $outer (result i32)
+      (block $middle
+        (block $inner
+          (br_if $inner (i32.eqz (local.get 0)))
+          (br_if $middle (i32.eq (local.get 0) (i32.const 1)))
+          (br $outer (i32.const 30)))
+        (br $outer (i32.const 10)))
+      (i32.const 20)))
+
+  ;; Values below the block's stack height are discarded when branching
+  (func (export "discard") (result i32)
+    (i32.add
+      (i32.const 1)
+      (block (result i32)
+        (drop (i32.const 100))
+        (i32.const 200)
+        (i32.const 300)
+        (br 0 (i32.const 2)))))
+
+  ;; `br_table`, including the default target
+  (func (export "table") (param i32) (result i32)
+    (block $default
+      (block $two
+        (block $one
+          (block $zero
+            (br_table $zero $one $two $default (local.get 0)))
+          (return (i32.const 100)))
+        (return (i32.const 101)))
+      (return (i32.const 102)))
+    (i32.const 103))
+
+  ;; `br_table` carrying a value
+  (func (export "table_value") (param i32) (result i32)
+    (i32.add
+      (i32.const 1)
+      (block $a (result i32)
+        (i32.mul
+          (i32.const 10)
+          (block $b (result i32)
+            (br_table $a $b (i32.const 5) (local.get 0)))))))
+
+  ;; Sum 1..n with a loop. `br_if` to the loop goes back to the start.
+  (func (export "sum") (param i32) (result i32)
+    (local i32)
+    (block $done
+      (loop $again
+        (br_if $done (i32.eqz (local.get 0)))
+        (local.set 1 (i32.add (local.get 1) (local.get 0)))
+        (local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+        (br $again)))
+    (local.get 1))
+
+  ;; A loop's label has no result type, even if the loop does
+  (func (export "loop_result") (param i32) (result i32)
+    (loop $again (result i32)
+      (local.set 0 (i32.add (local.get 0) (i32.const 1)))
+      (br_if $again (i32.lt_u (local.get 0) (i32.const 10)))
+      (local.get 0)))
+
+  ;; Branching out of an `if` from inside a nested block
+  (func (export "if_br") (param i32) (result i32)
+    (if (result i32) (local.get 0)
+      (then
+        (block
+          (br 1 (i32.const 7)))
+        (i32.const 8))
+      (else
+        (block
+          (br_if 1 (i32.const 9) (i32.const 1)))
+        (i32.const 10))))
+
+  ;; `br` to the function body returns
+  (func (export "br_return") (result i32)
+    (block
+      (loop
+        (br 2 (i32.const 42))))
+    (i32.const 0))
+)
+
+;; Each is called more than once, so later calls go through the branch cache
+(assert_return (invoke "nested" (i32.const 0)) (i32.const 10))
+(assert_return (invoke "nested" (i32.const 1)) (i32.const 20))
+(assert_return (invoke "nested" (i32.const 2)) (i32.const 30))
+(assert_return (invoke "nested" (i32.const 0)) (i32.const 10))
+(assert_return (invoke "nested" (i32.const 1)) (i32.const 20))
+
+(assert_return (invoke "discard") (i32.const 3))
+(assert_return (invoke "discard") (i32.const 3))
+
+(assert_return (invoke "table" (i32.const 0)) (i32.const 100))
+(assert_return (invoke "table" (i32.const 1)) (i32.const 101))
+(assert_return (invoke "table" (i32.const 2)) (i32.const 102))
+(assert_return (invoke "table" (i32.const 3)) (i32.const 103))
+(assert_return (invoke "table" (i32.const 99)) (i32.const 103))
+(assert_return (invoke "table" (i32.const 1)) (i32.const 101))
+
+(assert_return (invoke "table_value" (i32.const 0)) (i32.const 6))
+(assert_return (invoke "table_value" (i32.const 1)) (i32.const 51))
+(assert_return (invoke "table_value" (i32.const 7)) (i32.const 51))
+
+(assert_return (invoke "sum" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "sum" (i32.const 10)) (i32.const 55))
+
+(assert_return (invoke "loop_result" (i32.const 0)) (i32.const 10))
+(assert_return (invoke "loop_result" (i32.const 20)) (i32.const 21))
+
+(assert_return (invoke "if_br" (i32.const 1)) (i32.const 7))
+(assert_return (invoke "if_br" (i32.const 0)) (i32.const 9))
+(assert_return (invoke "if_br" (i32.const 1)) (i32.const 7))
+
+(assert_return (invoke "br_return") (i32.const 42))
+(assert_return (invoke "br_return") (i32.const 42))
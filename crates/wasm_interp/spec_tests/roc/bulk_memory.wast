@@ -0,0 +1,102 @@
+;; Bulk memory instructions, in the same format as the official spec tests
+
+;; memory.init and data.drop
+(module
+  (memory 1)
+  (data (i32.const 0) "\00\01\02\03")
+  (data "hello")
+
+  (func (export "init_active") (param i32 i32 i32)
+    (memory.init 0 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init") (param i32 i32 i32)
+    (memory.init 1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "drop")
+    (data.drop 1))
+  (func (export "load8_u") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+)
+
+(assert_return (invoke "load8_u" (i32.const 3)) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 100)) (i32.const 0))
+
+(invoke "init" (i32.const 100) (i32.const 1) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 99)) (i32.const 0))
+(assert_return (invoke "load8_u" (i32.const 100)) (i32.const 101))
+(assert_return (invoke "load8_u" (i32.const 101)) (i32.const 108))
+(assert_return (invoke "load8_u" (i32.const 102)) (i32.const 108))
+(assert_return (invoke "load8_u" (i32.const 103)) (i32.const 0))
+
+;; Reading past the end of the segment
+(assert_trap (invoke "init" (i32.const 0) (i32.const 3) (i32.const 3))
+  "out of bounds memory access")
+
+;; Writing past the end of memory traps before writing anything
+(assert_trap (invoke "init" (i32.const 65535) (i32.const 0) (i32.const 2))
+  "out of bounds memory access")
+(assert_return (invoke "load8_u" (i32.const 65535)) (i32.const 0))
+
+;; Zero-length copies at the very end are fine
+(invoke "init" (i32.const 65536) (i32.const 5) (i32.const 0))
+
+;; Active segments are dropped once they've been copied into memory
+(invoke "init_active" (i32.const 0) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "init_active" (i32.const 0) (i32.const 0) (i32.const 1))
+  "out of bounds memory access")
+
+;; Dropped passive segments act as if they were empty
+(invoke "drop")
+(invoke "drop")
+(invoke "init" (i32.const 0) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "init" (i32.const 0) (i32.const 0) (i32.const 1))
+  "out of bounds memory access")
+
+;; memory.copy and memory.fill
+(module
+  (memory 1)
+  (data (i32.const 10) "\01\02\03\04\05")
+
+  (func (export "copy") (param i32 i32 i32)
+    (memory.copy (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "fill") (param i32 i32 i32)
+    (memory.fill (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "load8_u") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+)
+
+;; Overlapping, copying forwards
+(invoke "copy" (i32.const 12) (i32.const 10) (i32.const 5))
+(assert_return (invoke "load8_u" (i32.const 10)) (i32.const 1))
+(assert_return (invoke "load8_u" (i32.const 11)) (i32.const 2))
+(assert_return (invoke "load8_u" (i32.const 12)) (i32.const 1))
+(assert_return (invoke "load8_u" (i32.const 13)) (i32.const 2))
+(assert_return (invoke "load8_u" (i32.const 14)) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 15)) (i32.const 4))
+(assert_return (invoke "load8_u" (i32.const 16)) (i32.const 5))
+
+;; Overlapping, copying backwards
+(invoke "copy" (i32.const 10) (i32.const 12) (i32.const 5))
+(assert_return (invoke "load8_u" (i32.const 10)) (i32.const 1))
+(assert_return (invoke "load8_u" (i32.const 11)) (i32.const 2))
+(assert_return (invoke "load8_u" (i32.const 12)) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 13)) (i32.const 4))
+(assert_return (invoke "load8_u" (i32.const 14)) (i32.const 5))
+
+(assert_trap (invoke "copy" (i32.const 65535) (i32.const 0) (i32.const 2))
+  "out of bounds memory access")
+(assert_trap (invoke "copy" (i32.const 0) (i32.const 65535) (i32.const 2))
+  "out of bounds memory access")
+(assert_trap (invoke "copy" (i32.const 0) (i32.const -1) (i32.const 1))
+  "out of bounds memory access")
+(invoke "copy" (i32.const 65536) (i32.const 65536) (i32.const 0))
+
+;; Only the low byte of the value is used
+(invoke "fill" (i32.const 20) (i32.const 0x1ff) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 19)) (i32.const 0))
+(assert_return (invoke "load8_u" (i32.const 20)) (i32.const 255))
+(assert_return (invoke "load8_u" (i32.const 22)) (i32.const 255))
+(assert_return (invoke "load8_u" (i32.const 23)) (i32.const 0))
+
+(assert_trap (invoke "fill" (i32.const 65530) (i32.const 1) (i32.const 7))
+  "out of bounds memory access")
+(assert_return (invoke "load8_u" (i32.const 65530)) (i32.const 0))
+(invoke "fill" (i32.const 65536) (i32.const 1) (i32.const 0))
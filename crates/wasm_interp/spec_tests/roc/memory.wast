@@ -0,0 +1,89 @@
+;; Loads, stores and memory size, in the same format as the official spec tests
+
+(module
+  (memory 1 3)
+  (data (i32.const 0) "\01\82\83\84\85\86\87\88")
+
+  (func (export "size") (result i32)
+    (memory.size))
+  (func (export "grow") (param i32) (result i32)
+    (memory.grow (local.get 0)))
+
+  (func (export "i32.load8_s") (param i32) (result i32)
+    (i32.load8_s (local.get 0)))
+  (func (export "i32.load8_u") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+  (func (export "i32.load16_s") (param i32) (result i32)
+    (i32.load16_s (local.get 0)))
+  (func (export "i32.load16_u") (param i32) (result i32)
+    (i32.load16_u (local.get 0)))
+  (func (export "i32.load") (param i32) (result i32)
+    (i32.load (local.get 0)))
+  (func (export "i32.load_offset") (param i32) (result i32)
+    (i32.load offset=4 (local.get 0)))
+  (func (export "i64.load32_s") (param i32) (result i64)
+    (i64.load32_s (local.get 0)))
+  (func (export "i64.load32_u") (param i32) (result i64)
+    (i64.load32_u (local.get 0)))
+  (func (export "i64.load") (param i32) (result i64)
+    (i64.load (local.get 0)))
+
+  (func (export "i32.store8") (param i32 i32)
+    (i32.store8 (local.get 0) (local.get 1)))
+  (func (export "i32.store16") (param i32 i32)
+    (i32.store16 (local.get 0) (local.get 1)))
+  (func (export "i32.store") (param i32 i32)
+    (i32.store (local.get 0) (local.get 1)))
+  (func (export "i64.store32") (param i32 i64)
+    (i64.store32 (local.get 0) (local.get 1)))
+  (func (export "f64.store") (param i32 f64)
+    (f64.store (local.get 0) (local.get 1)))
+  (func (export "f64.load") (param i32) (result f64)
+    (f64.load (local.get 0)))
+)
+
+;; Sign and zero extension
+(assert_return (invoke "i32.load8_s" (i32.const 0)) (i32.const 1))
+(assert_return (invoke "i32.load8_s" (i32.const 1)) (i32.const -126))
+(assert_return (invoke "i32.load8_u" (i32.const 1)) (i32.const 130))
+(assert_return (invoke "i32.load16_s" (i32.const 1)) (i32.const -31870))
+(assert_return (invoke "i32.load16_u" (i32.const 1)) (i32.const 33666))
+(assert_return (invoke "i32.load" (i32.const 0)) (i32.const 0x84838201))
+(assert_return (invoke "i32.load_offset" (i32.const 0)) (i32.const 0x88878685))
+(assert_return (invoke "i64.load32_s" (i32.const 4)) (i64.const 0xffffffff88878685))
+(assert_return (invoke "i64.load32_u" (i32.const 4)) (i64.const 0x88878685))
+(assert_return (invoke "i64.load" (i32.const 0)) (i64.const 0x8887868584838201))
+
+;; Narrow stores only write the low bytes
+(invoke "i32.store" (i32.const 16) (i32.const 0))
+(invoke "i32.store8" (i32.const 16) (i32.const 0x1234))
+(assert_return (invoke "i32.load" (i32.const 16)) (i32.const 0x34))
+(invoke "i32.store16" (i32.const 16) (i32.const 0x56789))
+(assert_return (invoke "i32.load" (i32.const 16)) (i32.const 0x6789))
+(invoke "i64.store32" (i32.const 16) (i64.const 0x1122334455667788))
+(assert_return (invoke "i64.load" (i32.const 16)) (i64.const 0x55667788))
+
+;; Unaligned access is allowed
+(invoke "f64.store" (i32.const 33) (f64.const 1.5))
+(assert_return (invoke "f64.load" (i32.const 33)) (f64.const 1.5))
+
+;; The last bytes of memory are in bounds, but no further
+(invoke "i32.store" (i32.const 65532) (i32.const 42))
+(assert_return (invoke "i32.load" (i32.const 65532)) (i32.const 42))
+(assert_trap (invoke "i32.load" (i32.const 65533)) "out of bounds memory access")
+(assert_trap (invoke "i32.store" (i32.const 65536) (i32.const 0)) "out of bounds memory access")
+(assert_trap (invoke "i32.load_offset" (i32.const 65532)) "out of bounds memory access")
+(assert_trap (invoke "i32.load8_u" (i32.const -1)) "out of bounds memory access")
+(assert_trap (invoke "i32.load_offset" (i32.const -1)) "out of bounds memory access")
+
+;; Growing memory, up to the maximum
+(assert_return (invoke "size") (i32.const 1))
+(assert_return (invoke "grow" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "size") (i32.const 2))
+(assert_return (invoke "i32.load" (i32.const 65536)) (i32.const 0))
+(assert_return (invoke "grow" (i32.const 0)) (i32.const 2))
+(assert_return (invoke "grow" (i32.const 2)) (i32.const -1))
+(assert_return (invoke "size") (i32.const 2))
+(assert_return (invoke "grow" (i32.const 1)) (i32.const 2))
+(assert_return (invoke "size") (i32.const 3))
+(assert_trap (invoke "i32.load" (i32.const 196608)) "out of bounds memory access")
@@ -0,0 +1,390 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::iter::once;
+
+use roc_wasm_module::opcodes::OpCode;
+use roc_wasm_module::{ExportType, WasmModule};
+
+use crate::{ImportDispatcher, Instance};
+
+const HELP: &str = "\
+Commands:
+  s, step                Run one instruction, stepping into calls
+  n, next                Run one instruction, stepping over calls
+  f, finish              Run until the current function returns
+  c, continue            Run until the next breakpoint
+  b, break FUNCTION      Stop whenever FUNCTION is called. Use its name or index.
+  d, delete [FUNCTION]   Remove a breakpoint, or all of them
+  bt, backtrace          Show the call stack
+  stack                  Show the value stack of the current function
+  locals                 Show the arguments and locals of the current function
+  globals                Show the global variables
+  x, memory ADDR [LEN]   Dump LEN bytes of memory, starting at ADDR (default 64 bytes)
+  h, help                Show this message
+  q, quit                Stop the program
+Press Enter to repeat the previous command.";
+
+const DEFAULT_MEMORY_DUMP_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunMode {
+    /// Stop before the next instruction
+    Step,
+    /// Stop before the next instruction at this call depth or shallower
+    StepOver(usize),
+    /// Stop before the next instruction shallower than this call depth
+    Finish(usize),
+    /// Stop only at breakpoints
+    Continue,
+}
+
+/// Where the debugger reads its commands from
+enum Input {
+    /// Lock stdin only while reading a command, so the app can read from it too.
+    /// The app's `fd_read` shares the same buffer, so neither of us swallows the other's input.
+    Stdin,
+    Reader(Box<dyn BufRead>),
+}
+
+impl Input {
+    fn read_line(&mut self, line: &mut String) -> io::Result<usize> {
+        match self {
+            Input::Stdin => io::stdin().read_line(line),
+            Input::Reader(reader) => reader.read_line(line),
+        }
+    }
+}
+
+/// Interactive debugger, for stepping through a WebAssembly program one instruction at a time.
+/// Reads commands from `input` and writes everything to `output`.
+pub struct Debugger {
+    input: Input,
+    output: Box<dyn Write>,
+    /// Function indices to stop at when they are called
+    breakpoints: Vec<u32>,
+    mode: RunMode,
+    /// Call depth at the previous instruction, so we can tell when a function has just been entered
+    previous_depth: Option<usize>,
+    last_command: String,
+}
+
+impl fmt::Debug for Debugger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debugger")
+            .field("breakpoints", &self.breakpoints)
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Debugger {
+    pub fn new(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        Self::with_input(Input::Reader(input), output)
+    }
+
+    /// Debug on the terminal. Output goes to stderr, to keep it apart from the program's own output.
+    pub fn stdio() -> Self {
+        Self::with_input(Input::Stdin, Box::new(io::stderr()))
+    }
+
+    fn with_input(input: Input, output: Box<dyn Write>) -> Self {
+        Debugger {
+            input,
+            output,
+            breakpoints: vec![],
+            mode: RunMode::Step,
+            previous_depth: None,
+            last_command: String::new(),
+        }
+    }
+
+    /// Called before every instruction. Returns false if the user wants to stop the program.
+    pub(crate) fn before_instruction<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        module: &WasmModule<'_>,
+    ) -> bool {
+        let depth = inst.previous_frames.len();
+        let entered_function = !matches!(self.previous_depth, Some(prev) if depth <= prev);
+        self.previous_depth = Some(depth);
+
+        let fn_index = inst.current_frame.fn_index as u32;
+        let hit_breakpoint = entered_function && self.breakpoints.contains(&fn_index);
+        let should_stop = hit_breakpoint
+            || match self.mode {
+                RunMode::Step => true,
+                RunMode::StepOver(target_depth) => depth <= target_depth,
+                RunMode::Finish(target_depth) => depth < target_depth,
+                RunMode::Continue => false,
+            };
+
+        if !should_stop {
+            return true;
+        }
+
+        let result = if hit_breakpoint {
+            writeln!(self.output, "Breakpoint hit")
+        } else {
+            Ok(())
+        };
+
+        // If we can't talk to the user, there's no point in keeping the program running
+        result
+            .and_then(|_| self.print_location(inst, module))
+            .and_then(|_| self.prompt(inst, module))
+            .unwrap_or(false)
+    }
+
+    /// Called when the program hits an error, to let the user look around before it exits
+    pub(crate) fn on_error<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        module: &WasmModule<'_>,
+        message: &str,
+    ) {
+        let _ = writeln!(self.output, "{message}")
+            .and_then(|_| {
+                writeln!(
+                    self.output,
+                    "The program can't continue, but you can inspect its state before it exits."
+                )
+            })
+            .and_then(|_| self.prompt(inst, module));
+    }
+
+    fn prompt<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        module: &WasmModule<'_>,
+    ) -> io::Result<bool> {
+        let depth = inst.previous_frames.len();
+        loop {
+            write!(self.output, "(wasm) ")?;
+            self.output.flush()?;
+
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                // No more input. Let the program run to the end.
+                writeln!(self.output)?;
+                self.mode = RunMode::Continue;
+                self.breakpoints.clear();
+                return Ok(true);
+            }
+            if !line.trim().is_empty() {
+                self.last_command = line.trim().to_string();
+            }
+
+            let command = self.last_command.clone();
+            let mut words = command.split_whitespace();
+            match words.next() {
+                None => {}
+                Some("s" | "step") => {
+                    self.mode = RunMode::Step;
+                    return Ok(true);
+                }
+                Some("n" | "next") => {
+                    self.mode = RunMode::StepOver(depth);
+                    return Ok(true);
+                }
+                Some("f" | "finish") => {
+                    self.mode = RunMode::Finish(depth);
+                    return Ok(true);
+                }
+                Some("c" | "continue") => {
+                    self.mode = RunMode::Continue;
+                    return Ok(true);
+                }
+                Some("q" | "quit") => return Ok(false),
+                Some("b" | "break") => match words.next() {
+                    Some(name) => match find_function(inst, module, name) {
+                        Some(fn_index) => {
+                            if !self.breakpoints.contains(&fn_index) {
+                                self.breakpoints.push(fn_index);
+                            }
+                            let fn_name = inst.debug_fn_name(fn_index as usize);
+                            writeln!(self.output, "Breakpoint set at func[{fn_index}] {fn_name}")?;
+                        }
+                        None => writeln!(self.output, "I couldn't find a function called {name}")?,
+                    },
+                    None => writeln!(self.output, "Which function? For example `break main`")?,
+                },
+                Some("d" | "delete") => match words.next() {
+                    Some(name) => match find_function(inst, module, name) {
+                        Some(fn_index) if self.breakpoints.contains(&fn_index) => {
+                            self.breakpoints.retain(|b| *b != fn_index);
+                            writeln!(self.output, "Deleted breakpoint at func[{fn_index}]")?;
+                        }
+                        _ => writeln!(self.output, "There's no breakpoint at {name}")?,
+                    },
+                    None => {
+                        self.breakpoints.clear();
+                        writeln!(self.output, "Deleted all breakpoints")?;
+                    }
+                },
+                Some("bt" | "backtrace") => self.print_backtrace(inst, module)?,
+                Some("stack") => self.print_stack(inst)?,
+                Some("locals") => self.print_locals(inst)?,
+                Some("globals") => self.print_globals(inst)?,
+                Some("x" | "memory") => {
+                    let addr = words.next().and_then(parse_number);
+                    let len = words
+                        .next()
+                        .map_or(Some(DEFAULT_MEMORY_DUMP_LEN), parse_number);
+                    match (addr, len) {
+                        (Some(addr), Some(len)) => self.print_memory(inst, addr, len)?,
+                        _ => writeln!(self.output, "Usage: memory ADDR [LEN]")?,
+                    }
+                }
+                Some("h" | "help") => writeln!(self.output, "{HELP}")?,
+                Some(other) => writeln!(
+                    self.output,
+                    "Unknown command `{other}`. Type `help` to see the commands."
+                )?,
+            }
+        }
+    }
+
+    fn print_location<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        module: &WasmModule<'_>,
+    ) -> io::Result<()> {
+        let fn_index = inst.current_frame.fn_index;
+        let fn_name = inst.debug_fn_name(fn_index);
+        let op_code = OpCode::from(module.code.bytes[inst.program_counter]);
+        let file_offset = inst.program_counter + module.code.section_offset as usize;
+        writeln!(
+            self.output,
+            "func[{fn_index}] {fn_name}  {file_offset:06x}  {op_code:?}"
+        )
+    }
+
+    /// Innermost frame first, with the address of the current instruction in each function
+    fn print_backtrace<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        module: &WasmModule<'_>,
+    ) -> io::Result<()> {
+        let frames = inst.previous_frames.iter().chain(once(&inst.current_frame));
+        let execution_addrs = frames
+            .clone()
+            .skip(1)
+            .map(|f| inst.debug_return_addr_to_call_addr(f.return_addr))
+            .chain(once(inst.program_counter));
+        let frames_with_addrs: Vec<_> = frames.zip(execution_addrs).collect();
+
+        for (depth, (frame, addr)) in frames_with_addrs.into_iter().rev().enumerate() {
+            let fn_index = frame.fn_index;
+            let fn_name = inst.debug_fn_name(fn_index);
+            let file_offset = addr + module.code.section_offset as usize;
+            writeln!(
+                self.output,
+                "#{depth} func[{fn_index}] {fn_name}  {file_offset:06x}"
+            )?;
+        }
+        Ok(())
+    }
+
+    fn print_stack<I: ImportDispatcher>(&mut self, inst: &Instance<'_, I>) -> io::Result<()> {
+        let frame = &inst.current_frame;
+        let stack_start = frame.locals_start + frame.locals_count;
+        let values: Vec<_> = inst.value_store.iter().skip(stack_start).collect();
+        writeln!(self.output, "{values:?}")
+    }
+
+    fn print_locals<I: ImportDispatcher>(&mut self, inst: &Instance<'_, I>) -> io::Result<()> {
+        let frame = &inst.current_frame;
+        let arg_count = inst.debug_arg_count(frame.fn_index);
+        for local_index in 0..frame.locals_count {
+            let label = if local_index < arg_count {
+                "arg  "
+            } else {
+                "local"
+            };
+            let value = frame.get_local(&inst.value_store, local_index as u32);
+            writeln!(self.output, "{label} {local_index}: {value:?}")?;
+        }
+        Ok(())
+    }
+
+    fn print_globals<I: ImportDispatcher>(&mut self, inst: &Instance<'_, I>) -> io::Result<()> {
+        for (index, value) in inst.globals.iter().enumerate() {
+            writeln!(self.output, "global {index}: {value:?}")?;
+        }
+        Ok(())
+    }
+
+    /// Hex dump, 16 bytes per line, with printable ASCII on the right
+    fn print_memory<I: ImportDispatcher>(
+        &mut self,
+        inst: &Instance<'_, I>,
+        addr: usize,
+        len: usize,
+    ) -> io::Result<()> {
+        let memory_size = inst.memory.len();
+        if addr >= memory_size {
+            return writeln!(
+                self.output,
+                "Address {addr:#x} is out of bounds. Memory size is {memory_size:#x}."
+            );
+        }
+        let end = memory_size.min(addr.saturating_add(len));
+
+        for (row_index, row) in inst.memory[addr..end].chunks(16).enumerate() {
+            write!(self.output, "{:08x} ", addr + 16 * row_index)?;
+            for byte in row {
+                write!(self.output, " {byte:02x}")?;
+            }
+            let padding = 3 * (16 - row.len());
+            let ascii: String = row
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() || *b == b' ' {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            writeln!(self.output, "{:padding$}  |{ascii}|", "")?;
+        }
+        Ok(())
+    }
+}
+
+/// Find a function by name (from the debug info or the exports), by index, or by `func[index]`
+fn find_function<I: ImportDispatcher>(
+    inst: &Instance<'_, I>,
+    module: &WasmModule<'_>,
+    name: &str,
+) -> Option<u32> {
+    let function_count = inst.import_count as u32 + module.code.function_count;
+
+    let index_str = name
+        .strip_prefix("func[")
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or(name);
+    if let Ok(index) = index_str.parse::<u32>() {
+        return (index < function_count).then_some(index);
+    }
+
+    let mut names = module.names.function_names.iter();
+    names
+        .find_map(|(index, fn_name)| (*fn_name == name).then_some(*index))
+        .or_else(|| {
+            module
+                .export
+                .exports
+                .iter()
+                .find(|ex| ex.ty == ExportType::Func && ex.name == name)
+                .map(|ex| ex.index)
+        })
+}
+
+/// Parse a decimal or `0x`-prefixed hex number
+fn parse_number(s: &str) -> Option<usize> {
+    match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
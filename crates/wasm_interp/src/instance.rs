@@ -4,11 +4,13 @@ use std::iter::{self, once, Iterator};
 
 use roc_wasm_module::opcodes::{MemoryInstruction, OpCode};
 use roc_wasm_module::parse::{Parse, SkipBytes};
-use roc_wasm_module::sections::{ImportDesc, MemorySection, SignatureParamsIter};
+use roc_wasm_module::sections::{DataMode, ImportDesc, MemorySection, SignatureParamsIter};
 use roc_wasm_module::{ExportType, WasmModule};
 use roc_wasm_module::{Value, ValueType};
 
+use crate::debugger::Debugger;
 use crate::frame::Frame;
+use crate::profiler::Profiler;
 use crate::value_store::ValueStore;
 use crate::{Error, ImportDispatcher};
 
@@ -18,10 +20,13 @@ pub enum Action {
     Break,
 }
 
+/// Block type immediate for a `block` or `if` with no result value
+const EMPTY_BLOCK_TYPE: u32 = 0x40;
+
 #[derive(Debug, Clone, Copy)]
 enum BlockType {
     Loop(usize),         // Loop block, with start address to loop back to
-    Normal,              // Block created by `block` instruction
+    Normal(bool),        // Block created by `block` or `if`. Holds whether it has a result value
     Locals(usize),       // Special "block" for locals. Holds function index for debug
     FunctionBody(usize), // Special block surrounding the function body. Holds function index for debug
 }
@@ -37,6 +42,8 @@ struct BranchCacheEntry {
     addr: u32,
     argument: u32,
     target: u32,
+    /// For a false `if` condition: true if the `if` has no `else`, so the jump leaves the block
+    ends_block: bool,
 }
 
 #[derive(Debug)]
@@ -47,11 +54,14 @@ pub struct Instance<'a, I: ImportDispatcher> {
     /// The current call frame
     pub(crate) current_frame: Frame,
     /// Previous call frames
-    previous_frames: Vec<'a, Frame>,
+    pub(crate) previous_frames: Vec<'a, Frame>,
     /// The WebAssembly stack machine's stack of values
     pub(crate) value_store: ValueStore<'a>,
     /// Values of any global variables
     pub(crate) globals: Vec<'a, Value>,
+    /// Contents of each data segment that `memory.init` can still copy from.
+    /// Dropped segments, including all active segments, are empty.
+    data_segments: Vec<'a, &'a [u8]>,
     /// Index in the code section of the current instruction
     pub(crate) program_counter: usize,
     /// One entry per nested block. For loops, stores the address of the first instruction.
@@ -59,13 +69,17 @@ pub struct Instance<'a, I: ImportDispatcher> {
     /// Cache for branching instructions, split into buckets for each function.
     branch_cache: Vec<'a, Vec<'a, BranchCacheEntry>>,
     /// Number of imports in the module
-    import_count: usize,
+    pub(crate) import_count: usize,
     /// Import dispatcher from user code
     pub import_dispatcher: I,
     /// Temporary storage for import arguments
     import_arguments: Vec<'a, Value>,
     /// temporary storage for output using the --debug option
     debug_string: Option<String>,
+    /// Interactive debugger, if enabled
+    debugger: Option<Debugger>,
+    /// Instruction counting profiler, if enabled
+    profiler: Option<Profiler>,
 }
 
 impl<'a, I: ImportDispatcher> Instance<'a, I> {
@@ -88,6 +102,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             previous_frames: Vec::new_in(arena),
             value_store: ValueStore::new(arena),
             globals: Vec::from_iter_in(globals, arena),
+            data_segments: Vec::new_in(arena),
             program_counter,
             blocks: Vec::new_in(arena),
             branch_cache: bumpalo::vec![in arena; bumpalo::vec![in arena]],
@@ -95,6 +110,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string: Some(String::new()),
+            debugger: None,
+            profiler: None,
         }
     }
 
@@ -126,6 +143,21 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         let globals = module.global.initial_values(arena);
 
+        // Active segments are dropped as soon as they've been loaded into memory
+        let data_segments = {
+            let segments = module.data.segments().map_err(|e| {
+                format!(
+                    "Error parsing Data section at offset {:#x}:\n{}",
+                    e.offset, e.message
+                )
+            })?;
+            let contents = segments.into_iter().map(|(mode, init)| match mode {
+                DataMode::Passive => init,
+                DataMode::Active { .. } => &[],
+            });
+            Vec::from_iter_in(contents, arena)
+        };
+
         // We don't handle non-function import types (memories, tables, and globals),
         // and it's nice for lookups to assume they're all functions, so let's assert that.
         let all_imports_are_functions = module.import.imports.iter().all(|imp| imp.is_function());
@@ -156,6 +188,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             previous_frames: Vec::new_in(arena),
             value_store,
             globals,
+            data_segments,
             program_counter: usize::MAX,
             blocks: Vec::new_in(arena),
             branch_cache,
@@ -163,9 +196,24 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             import_dispatcher,
             import_arguments: Vec::new_in(arena),
             debug_string,
+            debugger: None,
+            profiler: None,
         })
     }
 
+    /// Pause before the first instruction and let the user step through the program
+    pub fn set_debugger(&mut self, debugger: Debugger) {
+        self.debugger = Some(debugger);
+    }
+
+    pub fn set_profiler(&mut self, profiler: Profiler) {
+        self.profiler = Some(profiler);
+    }
+
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
     pub fn call_export<A>(&mut self, fn_name: &str, arg_values: A) -> Result<Option<Value>, String>
     where
         A: IntoIterator<Item = Value>,
@@ -266,6 +314,9 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         let internal_fn_index = fn_index - self.import_count;
 
+        // Discard any values left over from a previous call that trapped
+        self.value_store.truncate(0);
+
         self.program_counter = {
             let mut cursor = module.code.function_offsets[internal_fn_index] as usize;
             let _start_fn_byte_length = u32::parse((), &module.code.bytes, &mut cursor);
@@ -316,8 +367,21 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             ty: BlockType::FunctionBody(fn_index),
             vstack: self.value_store.depth(),
         });
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.start(fn_index);
+        }
 
         loop {
+            if let Some(mut debugger) = self.debugger.take() {
+                let keep_running = debugger.before_instruction(self, module);
+                self.debugger = Some(debugger);
+                if !keep_running {
+                    return Err("The program was stopped from the debugger.".into());
+                }
+            }
+            if let Some(profiler) = self.profiler.as_mut() {
+                profiler.instruction();
+            }
             match self.execute_next_instruction(module) {
                 Ok(Action::Continue) => {}
                 Ok(Action::Break) => {
@@ -327,11 +391,20 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                     let file_offset = self.program_counter + module.code.section_offset as usize;
                     let mut message = e.to_string_at(file_offset);
                     self.debug_stack_trace(&mut message).unwrap();
+                    if let Some(mut debugger) = self.debugger.take() {
+                        debugger.on_error(self, module, &message);
+                        self.debugger = Some(debugger);
+                    }
                     return Err(message);
                 }
             };
         }
 
+        if self.import_dispatcher.exit_code().is_some() {
+            // The app exited without returning. Callers can get the exit code from the dispatcher.
+            return Ok(None);
+        }
+
         let return_value = if !self.value_store.is_empty() {
             Some(self.value_store.pop())
         } else {
@@ -378,6 +451,10 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
 
         // self.debug_values_and_blocks("end do_return");
 
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.exit();
+        }
+
         if let Some(caller_frame) = self.previous_frames.pop() {
             self.current_frame = caller_frame;
             Action::Continue
@@ -387,23 +464,26 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         }
     }
 
-    fn get_load_address(&mut self, module: &WasmModule<'a>) -> Result<u32, Error> {
+    /// Get the address for a load of `size` bytes, checking that all of them are in bounds
+    fn get_load_address(&mut self, module: &WasmModule<'a>, size: usize) -> Result<u32, Error> {
         // Alignment is not used in the execution steps from the spec! Maybe it's just an optimization hint?
         // https://webassembly.github.io/spec/core/exec/instructions.html#memory-instructions
         // Also note: in the text format we can specify the useless `align=` but not the useful `offset=`!
         let _alignment = self.fetch_immediate_u32(module);
         let offset = self.fetch_immediate_u32(module);
         let base_addr = self.value_store.pop_u32()?;
-        let addr = base_addr + offset;
-        let memory_size = self.memory.len() as u32;
-        if addr >= memory_size {
-            Err(Error::MemoryAccessOutOfBounds(addr, memory_size))
-        } else {
-            Ok(addr)
-        }
+        // The effective address is 33 bits, so it can't wrap around
+        let addr = base_addr as usize + offset as usize;
+        self.check_memory_range(addr, size)?;
+        Ok(addr as u32)
     }
 
-    fn get_store_addr_value(&mut self, module: &WasmModule<'a>) -> Result<(usize, Value), Error> {
+    /// Get the address and value for a store of `size` bytes, checking that all of them are in bounds
+    fn get_store_addr_value(
+        &mut self,
+        module: &WasmModule<'a>,
+        size: usize,
+    ) -> Result<(usize, Value), Error> {
         // Alignment is not used in the execution steps from the spec! Maybe it's just an optimization hint?
         // https://webassembly.github.io/spec/core/exec/instructions.html#memory-instructions
         // Also note: in the text format we can specify the useless `align=` but not the useful `offset=`!
@@ -411,12 +491,23 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         let offset = self.fetch_immediate_u32(module);
         let value = self.value_store.pop();
         let base_addr = self.value_store.pop_u32()?;
-        let addr = base_addr + offset;
-        let memory_size = self.memory.len() as u32;
-        if addr >= memory_size {
-            Err(Error::MemoryAccessOutOfBounds(addr, memory_size))
+        // The effective address is 33 bits, so it can't wrap around
+        let addr = base_addr as usize + offset as usize;
+        self.check_memory_range(addr, size)?;
+        Ok((addr, value))
+    }
+
+    /// Bulk memory instructions trap if any part of the range is out of bounds, even if it's empty
+    fn check_memory_range(&self, start: usize, size: usize) -> Result<(), Error> {
+        let memory_size = self.memory.len();
+        if start + size > memory_size {
+            let first_bad_addr = start.max(memory_size).min(u32::MAX as usize);
+            Err(Error::MemoryAccessOutOfBounds(
+                first_bad_addr as u32,
+                memory_size as u32,
+            ))
         } else {
-            Ok((addr as usize, value))
+            Ok(())
         }
     }
 
@@ -426,7 +517,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         }
     }
 
-    fn do_break(&mut self, relative_blocks_outward: u32, module: &WasmModule<'a>) {
+    fn do_break(&mut self, relative_blocks_outward: u32, module: &WasmModule<'a>) -> Action {
         let block_index = self.blocks.len() - 1 - relative_blocks_outward as usize;
         let Block { ty, vstack } = self.blocks[block_index];
         match ty {
@@ -435,12 +526,20 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 self.value_store.truncate(vstack);
                 self.program_counter = start_addr;
             }
-            BlockType::FunctionBody(_) | BlockType::Normal => {
+            BlockType::Normal(has_result) => {
+                // Branching out of a block carries its result value with it
+                let result = has_result.then(|| self.value_store.peek());
                 self.break_forward(relative_blocks_outward, module);
                 self.value_store.truncate(vstack);
+                if let Some(value) = result {
+                    self.value_store.push(value);
+                }
             }
+            // Branching out of the function body is the same as returning
+            BlockType::FunctionBody(_) => return self.do_return(),
             BlockType::Locals(_) => unreachable!(),
         }
+        Action::Continue
     }
 
     // Break to an outer block, going forward in the program
@@ -478,6 +577,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 addr,
                 argument: relative_blocks_outward,
                 target: self.program_counter as u32,
+                ends_block: false,
             });
         }
         self.blocks.truncate(target_block_depth);
@@ -488,7 +588,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         expected_signature: Option<u32>,
         fn_index: usize,
         module: &WasmModule<'a>,
-    ) -> Result<(), Error> {
+    ) -> Result<Action, Error> {
         // self.debug_values_and_blocks(&format!("start do_call {}", fn_index));
 
         let (signature_index, opt_import) = if fn_index < self.import_count {
@@ -518,6 +618,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             self.debug_call(n_args, ret_type);
         }
 
+        if let Some(profiler) = self.profiler.as_mut() {
+            if opt_import.is_some() {
+                profiler.import_call(fn_index);
+            } else {
+                profiler.enter(fn_index);
+            }
+        }
+
         if let Some(import) = opt_import {
             self.import_arguments.clear();
             self.import_arguments
@@ -543,6 +651,9 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             if let Some(debug_string) = self.debug_string.as_mut() {
                 write!(debug_string, " {}.{}", import.module, import.name).unwrap();
             }
+            if self.import_dispatcher.exit_code().is_some() {
+                return Ok(Action::Break);
+            }
         } else {
             let return_addr = self.program_counter;
             // set PC to start of function bytes
@@ -577,7 +688,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         }
         // self.debug_values_and_blocks("end do_call");
 
-        Ok(())
+        Ok(Action::Continue)
     }
 
     fn debug_call(&mut self, n_args: usize, return_type: Option<ValueType>) {
@@ -624,9 +735,9 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             }
             NOP => {}
             BLOCK => {
-                self.fetch_immediate_u32(module); // blocktype (ignored)
+                let has_result = self.fetch_immediate_u32(module) != EMPTY_BLOCK_TYPE;
                 self.blocks.push(Block {
-                    ty: BlockType::Normal,
+                    ty: BlockType::Normal(has_result),
                     vstack: self.value_store.depth(),
                 });
             }
@@ -638,10 +749,10 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 });
             }
             IF => {
-                self.fetch_immediate_u32(module); // blocktype (ignored)
+                let has_result = self.fetch_immediate_u32(module) != EMPTY_BLOCK_TYPE;
                 let condition = self.value_store.pop_i32()?;
                 self.blocks.push(Block {
-                    ty: BlockType::Normal,
+                    ty: BlockType::Normal(has_result),
                     vstack: self.value_store.depth(),
                 });
                 if condition == 0 {
@@ -651,7 +762,12 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                         .find(|entry| entry.addr == addr);
                     if let Some(entry) = cache_result {
                         self.program_counter = entry.target as usize;
+                        if entry.ends_block {
+                            // `if` without `else`
+                            self.blocks.pop();
+                        }
                     } else {
+                        let mut ends_block = false;
                         let target_depth = self.blocks.len();
                         let mut depth = target_depth;
                         loop {
@@ -666,6 +782,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                                     if depth == target_depth {
                                         // `if` without `else`
                                         self.blocks.pop();
+                                        ends_block = true;
                                         break;
                                     } else {
                                         depth -= 1;
@@ -683,6 +800,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                             addr,
                             argument: 0,
                             target: self.program_counter as u32,
+                            ends_block,
                         });
                     }
                 }
@@ -691,7 +809,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 // We only reach this point when we finish executing the "then" block of an IF statement
                 // (For a false condition, we would have skipped past the ELSE when we saw the IF)
                 // We don't want to execute the ELSE block, so we skip it, just like `br 0` would.
-                self.do_break(0, module);
+                action = self.do_break(0, module);
             }
             END => {
                 if self.blocks.len() == (self.current_frame.body_block_index + 1) {
@@ -704,13 +822,13 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
             }
             BR => {
                 let relative_blocks_outward = self.fetch_immediate_u32(module);
-                self.do_break(relative_blocks_outward, module);
+                action = self.do_break(relative_blocks_outward, module);
             }
             BRIF => {
                 let relative_blocks_outward = self.fetch_immediate_u32(module);
                 let condition = self.value_store.pop_i32()?;
                 if condition != 0 {
-                    self.do_break(relative_blocks_outward, module);
+                    action = self.do_break(relative_blocks_outward, module);
                 }
             }
             BRTABLE => {
@@ -725,14 +843,14 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 }
                 let fallback = self.fetch_immediate_u32(module);
                 let relative_blocks_outward = selected.unwrap_or(fallback);
-                self.do_break(relative_blocks_outward, module);
+                action = self.do_break(relative_blocks_outward, module);
             }
             RETURN => {
                 action = self.do_return();
             }
             CALL => {
                 let fn_index = self.fetch_immediate_u32(module) as usize;
-                self.do_call(None, fn_index, module)?;
+                action = self.do_call(None, fn_index, module)?;
             }
             CALLINDIRECT => {
                 let expected_signature = self.fetch_immediate_u32(module);
@@ -752,7 +870,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                     )
                 });
 
-                self.do_call(Some(expected_signature), fn_index as usize, module)?;
+                action = self.do_call(Some(expected_signature), fn_index as usize, module)?;
             }
             DROP => {
                 self.value_store.pop();
@@ -795,149 +913,149 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 self.globals[index as usize] = self.value_store.pop();
             }
             I32LOAD => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 4)? as usize;
                 let mut bytes = [0; 4];
                 bytes.copy_from_slice(&self.memory[addr..][..4]);
                 let value = i32::from_le_bytes(bytes);
                 self.value_store.push(Value::I32(value));
             }
             I64LOAD => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 8)? as usize;
                 let mut bytes = [0; 8];
                 bytes.copy_from_slice(&self.memory[addr..][..8]);
                 let value = i64::from_le_bytes(bytes);
                 self.value_store.push(Value::I64(value));
             }
             F32LOAD => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 4)? as usize;
                 let mut bytes = [0; 4];
                 bytes.copy_from_slice(&self.memory[addr..][..4]);
                 let value = f32::from_le_bytes(bytes);
                 self.value_store.push(Value::F32(value));
             }
             F64LOAD => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 8)? as usize;
                 let mut bytes = [0; 8];
                 bytes.copy_from_slice(&self.memory[addr..][..8]);
                 let value = f64::from_le_bytes(bytes);
                 self.value_store.push(Value::F64(value));
             }
             I32LOAD8S => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 1)? as usize;
                 let mut bytes = [0; 1];
                 bytes.copy_from_slice(&self.memory[addr..][..1]);
                 let value = i8::from_le_bytes(bytes);
                 self.value_store.push(Value::I32(value as i32));
             }
             I32LOAD8U => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 1)? as usize;
                 let value = self.memory[addr];
                 self.value_store.push(Value::I32(value as i32));
             }
             I32LOAD16S => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 2)? as usize;
                 let mut bytes = [0; 2];
                 bytes.copy_from_slice(&self.memory[addr..][..2]);
                 let value = i16::from_le_bytes(bytes);
                 self.value_store.push(Value::I32(value as i32));
             }
             I32LOAD16U => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 2)? as usize;
                 let mut bytes = [0; 2];
                 bytes.copy_from_slice(&self.memory[addr..][..2]);
                 let value = u16::from_le_bytes(bytes);
                 self.value_store.push(Value::I32(value as i32));
             }
             I64LOAD8S => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 1)? as usize;
                 let mut bytes = [0; 1];
                 bytes.copy_from_slice(&self.memory[addr..][..1]);
                 let value = i8::from_le_bytes(bytes);
                 self.value_store.push(Value::I64(value as i64));
             }
             I64LOAD8U => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 1)? as usize;
                 let value = self.memory[addr];
                 self.value_store.push(Value::I64(value as i64));
             }
             I64LOAD16S => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 2)? as usize;
                 let mut bytes = [0; 2];
                 bytes.copy_from_slice(&self.memory[addr..][..2]);
                 let value = i16::from_le_bytes(bytes);
                 self.value_store.push(Value::I64(value as i64));
             }
             I64LOAD16U => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 2)? as usize;
                 let mut bytes = [0; 2];
                 bytes.copy_from_slice(&self.memory[addr..][..2]);
                 let value = u16::from_le_bytes(bytes);
                 self.value_store.push(Value::I64(value as i64));
             }
             I64LOAD32S => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 4)? as usize;
                 let mut bytes = [0; 4];
                 bytes.copy_from_slice(&self.memory[addr..][..4]);
                 let value = i32::from_le_bytes(bytes);
                 self.value_store.push(Value::I64(value as i64));
             }
             I64LOAD32U => {
-                let addr = self.get_load_address(module)? as usize;
+                let addr = self.get_load_address(module, 4)? as usize;
                 let mut bytes = [0; 4];
                 bytes.copy_from_slice(&self.memory[addr..][..4]);
                 let value = u32::from_le_bytes(bytes);
                 self.value_store.push(Value::I64(value as i64));
             }
             I32STORE => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 4)?;
                 let unwrapped = value.expect_i32().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..4];
                 target.copy_from_slice(&unwrapped.to_le_bytes());
             }
             I64STORE => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 8)?;
                 let unwrapped = value.expect_i64().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..8];
                 target.copy_from_slice(&unwrapped.to_le_bytes());
             }
             F32STORE => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 4)?;
                 let unwrapped = value.expect_f32().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..4];
                 target.copy_from_slice(&unwrapped.to_le_bytes());
             }
             F64STORE => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 8)?;
                 let unwrapped = value.expect_f64().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..8];
                 target.copy_from_slice(&unwrapped.to_le_bytes());
             }
             I32STORE8 => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 1)?;
                 let unwrapped = value.expect_i32().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..1];
                 target.copy_from_slice(&unwrapped.to_le_bytes()[..1]);
             }
             I32STORE16 => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 2)?;
                 let unwrapped = value.expect_i32().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..2];
                 target.copy_from_slice(&unwrapped.to_le_bytes()[..2]);
             }
             I64STORE8 => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 1)?;
                 let unwrapped = value.expect_i64().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..1];
                 target.copy_from_slice(&unwrapped.to_le_bytes()[..1]);
             }
             I64STORE16 => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 2)?;
                 let unwrapped = value.expect_i64().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..2];
                 target.copy_from_slice(&unwrapped.to_le_bytes()[..2]);
             }
             I64STORE32 => {
-                let (addr, value) = self.get_store_addr_value(module)?;
+                let (addr, value) = self.get_store_addr_value(module, 4)?;
                 let unwrapped = value.expect_i64().map_err(Error::from)?;
                 let target = &mut self.memory[addr..][..4];
                 target.copy_from_slice(&unwrapped.to_le_bytes()[..4]);
@@ -973,8 +1091,34 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 // the first argument determines exactly which memory operation we have
                 match MemoryInstruction::try_from(module.code.bytes[self.program_counter]) {
                     Ok(op) => match op {
-                        MemoryInstruction::MemoryInit => todo!("WASM instruction: memory.init"),
-                        MemoryInstruction::DataDrop => todo!("WASM instruction: data.drop"),
+                        MemoryInstruction::MemoryInit => {
+                            self.program_counter += 1;
+                            let data_index = self.fetch_immediate_u32(module);
+                            // skip the zero byte for the memory index
+                            self.program_counter += 1;
+
+                            let size = self.value_store.pop_u32()? as usize;
+                            let source = self.value_store.pop_u32()? as usize;
+                            let destination = self.value_store.pop_u32()? as usize;
+
+                            let segment = self.data_segments[data_index as usize];
+                            if source + size > segment.len() {
+                                return Err(Error::DataSegmentOutOfBounds(
+                                    data_index,
+                                    (source + size) as u64,
+                                    segment.len() as u32,
+                                ));
+                            }
+                            self.check_memory_range(destination, size)?;
+
+                            self.memory[destination..][..size]
+                                .copy_from_slice(&segment[source..][..size]);
+                        }
+                        MemoryInstruction::DataDrop => {
+                            self.program_counter += 1;
+                            let data_index = self.fetch_immediate_u32(module);
+                            self.data_segments[data_index as usize] = &[];
+                        }
                         MemoryInstruction::MemoryCopy => {
                             let size = self.value_store.pop_u32()? as usize;
                             let source = self.value_store.pop_u32()? as usize;
@@ -984,6 +1128,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                             // in future versions of WebAssembly this byte may be used to index additional memories
                             self.program_counter += 1 + 2;
 
+                            self.check_memory_range(source, size)?;
+                            self.check_memory_range(destination, size)?;
                             self.memory.copy_within(source..source + size, destination)
                         }
                         MemoryInstruction::MemoryFill => {
@@ -995,6 +1141,7 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                             // in future versions of WebAssembly this byte may be used to index additional memories
                             self.program_counter += 1 + 1;
 
+                            self.check_memory_range(destination, size)?;
                             self.memory[destination..][..size].fill(byte_value);
                         }
                     },
@@ -1841,26 +1988,8 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 ..
             } = frame;
 
-            let arg_count = {
-                let signature_index = if *fn_index < self.import_count {
-                    match self.module.import.imports[*fn_index].description {
-                        ImportDesc::Func { signature_index } => signature_index,
-                        _ => unreachable!(),
-                    }
-                } else {
-                    self.module.function.signatures[fn_index - self.import_count]
-                };
-                self.module.types.look_up(signature_index).0.len()
-            };
-
-            let fn_name = self
-                .module
-                .names
-                .function_names
-                .iter()
-                .find(|(idx, _)| *idx == *fn_index as u32)
-                .map(|(_, name)| *name)
-                .unwrap_or("");
+            let arg_count = self.debug_arg_count(*fn_index);
+            let fn_name = self.debug_fn_name(*fn_index);
 
             // Function and address match wasm-objdump formatting, for easy copy & find
             writeln!(buffer, "func[{fn_index}]  {fn_name}")?;
@@ -1896,8 +2025,31 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
         Ok(())
     }
 
+    pub(crate) fn debug_arg_count(&self, fn_index: usize) -> usize {
+        let signature_index = if fn_index < self.import_count {
+            match self.module.import.imports[fn_index].description {
+                ImportDesc::Func { signature_index } => signature_index,
+                _ => unreachable!(),
+            }
+        } else {
+            self.module.function.signatures[fn_index - self.import_count]
+        };
+        self.module.types.look_up(signature_index).0.len()
+    }
+
+    /// Look up a function's name in the debug info, if it has one
+    pub(crate) fn debug_fn_name(&self, fn_index: usize) -> &'a str {
+        self.module
+            .names
+            .function_names
+            .iter()
+            .find(|(idx, _)| *idx == fn_index as u32)
+            .map(|(_, name)| *name)
+            .unwrap_or("")
+    }
+
     // Call address is more intuitive than the return address in the stack trace. Search backward for it.
-    fn debug_return_addr_to_call_addr(&self, return_addr: usize) -> usize {
+    pub(crate) fn debug_return_addr_to_call_addr(&self, return_addr: usize) -> usize {
         // return_addr is pointing at the next instruction after the CALL/CALLINDIRECT.
         // Just before that is the LEB-128 function index or type index.
         // The last LEB-128 byte is <128, but the others are >=128 so we can't mistake them for CALL/CALLINDIRECT
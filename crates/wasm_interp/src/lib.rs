@@ -1,5 +1,7 @@
+mod debugger;
 mod frame;
 mod instance;
+mod profiler;
 #[cfg(test)]
 mod tests;
 
@@ -7,7 +9,9 @@ mod value_store;
 pub mod wasi;
 
 // Main external interface
+pub use debugger::Debugger;
 pub use instance::Instance;
+pub use profiler::Profiler;
 pub use wasi::{WasiDispatcher, WasiFile};
 
 pub use roc_wasm_module::Value;
@@ -22,24 +26,48 @@ pub trait ImportDispatcher {
         arguments: &[Value],
         memory: &mut [u8],
     ) -> Option<Value>;
+
+    /// The exit code, if the app has asked to exit. The interpreter checks this after every import call.
+    fn exit_code(&self) -> Option<i32> {
+        None
+    }
+}
+
+/// Module name for imports that the Roc compiler expects the host to provide
+pub const ENV_MODULE_NAME: &str = "env";
+
+/// A failed `expect`, as reported by the app through `env.roc_expect_failed`.
+/// The values it looked up are in the buffer that the host passed to `set_shared_buffer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpectFailure {
+    pub region_start: u32,
+    pub region_end: u32,
+    pub module_id: u32,
 }
 
 impl Default for DefaultImportDispatcher<'_> {
     fn default() -> Self {
         DefaultImportDispatcher {
             wasi: WasiDispatcher::new(&[]),
+            expect_failures: vec![],
+            panic_message: None,
         }
     }
 }
 
 pub struct DefaultImportDispatcher<'a> {
     pub wasi: WasiDispatcher<'a>,
+    pub expect_failures: std::vec::Vec<ExpectFailure>,
+    /// The message from `env.roc_panic`, if the app has crashed. This stops the app, like an exit.
+    pub panic_message: Option<String>,
 }
 
 impl<'a> DefaultImportDispatcher<'a> {
     pub fn new(args: &'a [&'a [u8]]) -> Self {
         DefaultImportDispatcher {
             wasi: WasiDispatcher::new(args),
+            expect_failures: vec![],
+            panic_message: None,
         }
     }
 }
@@ -54,10 +82,62 @@ impl<'a> ImportDispatcher for DefaultImportDispatcher<'a> {
     ) -> Option<Value> {
         if module_name == wasi::MODULE_NAME {
             self.wasi.dispatch(function_name, arguments, memory)
+        } else if module_name == ENV_MODULE_NAME && function_name == "roc_dbg" {
+            // fn roc_dbg(loc: *RocStr, msg: *RocStr, src: *RocStr)
+            let [loc, msg, src] = [0, 1, 2].map(|i| {
+                let addr = arguments[i].expect_i32().unwrap() as usize;
+                decode_roc_str(memory, addr)
+            });
+            eprintln!("[{loc}] {src} = {msg}");
+            None
+        } else if module_name == ENV_MODULE_NAME && function_name == "roc_expect_failed" {
+            // fn roc_expect_failed(region_start: u32, region_end: u32, module_id: u32)
+            let [region_start, region_end, module_id] =
+                [0, 1, 2].map(|i| arguments[i].expect_i32().unwrap() as u32);
+            self.expect_failures.push(ExpectFailure {
+                region_start,
+                region_end,
+                module_id,
+            });
+            None
+        } else if module_name == ENV_MODULE_NAME && function_name == "roc_panic" {
+            // fn roc_panic(msg: *RocStr, tag: u32)
+            let msg_addr = arguments[0].expect_i32().unwrap() as usize;
+            self.panic_message = Some(decode_roc_str(memory, msg_addr));
+            None
         } else {
             panic!("DefaultImportDispatcher does not implement {module_name}.{function_name}");
         }
     }
+
+    fn exit_code(&self) -> Option<i32> {
+        match self.panic_message {
+            Some(_) => Some(1),
+            None => self.wasi.exit_code,
+        }
+    }
+}
+
+/// Read a RocStr from the memory of a 32-bit Wasm app
+pub fn decode_roc_str(memory: &[u8], addr: usize) -> String {
+    const SMALL_STR_BIT: u8 = 0b1000_0000;
+    const SEAMLESS_SLICE_BIT: u32 = 1 << 31;
+
+    let last_byte = memory[addr + 11];
+    let bytes = if last_byte & SMALL_STR_BIT != 0 {
+        let len = (last_byte & !SMALL_STR_BIT) as usize;
+        &memory[addr..][..len]
+    } else {
+        let read_u32 = |offset: usize| {
+            let bytes = &memory[addr + offset..][..4];
+            u32::from_le_bytes(bytes.try_into().unwrap())
+        };
+        let ptr = read_u32(0) as usize;
+        let len = (read_u32(4) & !SEAMLESS_SLICE_BIT) as usize;
+        &memory[ptr..][..len]
+    };
+
+    String::from_utf8_lossy(bytes).into_owned()
 }
 
 /// Errors that can happen while interpreting the program
@@ -67,6 +147,7 @@ pub(crate) enum Error {
     Type(ValueType, ValueType),
     StackEmpty,
     MemoryAccessOutOfBounds(u32, u32),
+    DataSegmentOutOfBounds(u32, u64, u32),
     UnreachableOp,
 }
 
@@ -89,6 +170,11 @@ impl Error {
                     file_offset, addr, memory_size-1
                 )
             }
+            Error::DataSegmentOutOfBounds(data_index, end, segment_len) => {
+                format!(
+                    "ERROR: A `memory.init` instruction at file offset {file_offset:#x} tried to copy data segment {data_index} up to offset {end:#x}, but the segment's length is {segment_len:#x}\n"
+                )
+            }
             Error::UnreachableOp => {
                 format!("WebAssembly `unreachable` instruction at file offset {file_offset:#x}.\n")
             }
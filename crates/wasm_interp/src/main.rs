@@ -4,14 +4,18 @@ use clap::{Arg, Command};
 use std::fs;
 use std::io;
 use std::iter::once;
+use std::path::Path;
 use std::process;
 
-use roc_wasm_interp::{DefaultImportDispatcher, Instance};
+use roc_wasm_interp::{Debugger, DefaultImportDispatcher, Instance, Profiler};
 use roc_wasm_module::WasmModule;
 
 pub const FLAG_FUNCTION: &str = "function";
 pub const FLAG_DEBUG: &str = "debug";
+pub const FLAG_DEBUGGER: &str = "debugger";
 pub const FLAG_HEX: &str = "hex";
+pub const FLAG_DIR: &str = "dir";
+pub const FLAG_PROFILE: &str = "profile";
 pub const WASM_FILE: &str = "WASM_FILE";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
 
@@ -32,12 +36,31 @@ fn main() -> io::Result<()> {
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_debugger = Arg::new(FLAG_DEBUGGER)
+        .long(FLAG_DEBUGGER)
+        .help("Pause before the first instruction and step through the program interactively.\nType `help` at the prompt to see the commands.")
+        .action(ArgAction::SetTrue)
+        .required(false);
+
     let flag_hex = Arg::new(FLAG_HEX)
         .long(FLAG_HEX)
         .help("If the called function returns a value, print it in hexadecimal format.")
         .action(ArgAction::SetTrue)
         .required(false);
 
+    let flag_dir = Arg::new(FLAG_DIR)
+        .long(FLAG_DIR)
+        .help("Give the app access to a host directory, as HOST_DIR:GUEST_DIR\nIf there's no `:`, the app sees the directory under the same name as the host.\nCan be repeated.")
+        .value_name("HOST_DIR:GUEST_DIR")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_profile = Arg::new(FLAG_PROFILE)
+        .long(FLAG_PROFILE)
+        .help("Count the instructions and calls in each function, and print a summary to stderr.\nAlso write instruction counts per call stack to FILE, in the folded stacks format for flame graph tools.")
+        .value_name("FILE")
+        .required(false);
+
     let wasm_file_to_run = Arg::new(WASM_FILE)
         .help("The .wasm file to run")
         .required(true);
@@ -50,7 +73,10 @@ fn main() -> io::Result<()> {
         .about("Run the given .wasm file")
         .arg(flag_function)
         .arg(flag_debug)
+        .arg(flag_debugger)
         .arg(flag_hex)
+        .arg(flag_dir)
+        .arg(flag_profile)
         .arg(wasm_file_to_run)
         .arg(args_for_app);
 
@@ -59,7 +85,9 @@ fn main() -> io::Result<()> {
     let matches = app.get_matches();
     let start_fn_name = matches.get_one::<String>(FLAG_FUNCTION).unwrap();
     let is_debug_mode = matches.get_flag(FLAG_DEBUG);
+    let is_debugger_mode = matches.get_flag(FLAG_DEBUGGER);
     let is_hex_format = matches.get_flag(FLAG_HEX);
+    let profile_path = matches.get_one::<String>(FLAG_PROFILE);
     let start_arg_strings = matches.get_many::<String>(ARGS_FOR_APP).unwrap_or_default();
     let wasm_path = matches.get_one::<String>(WASM_FILE).unwrap();
     // WASI expects the .wasm file to be argv[0]
@@ -87,17 +115,41 @@ fn main() -> io::Result<()> {
 
     // Create an execution instance
 
-    let dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+    let mut dispatcher = DefaultImportDispatcher::new(&wasi_argv);
+    for dir in matches.get_many::<String>(FLAG_DIR).unwrap_or_default() {
+        let (host_dir, guest_dir) = dir.split_once(':').unwrap_or((dir, dir));
+        if let Err(e) = dispatcher.wasi.preopen_dir(Path::new(host_dir), guest_dir) {
+            eprintln!("I couldn't give the app access to the directory {host_dir}: {e}");
+            process::exit(1);
+        }
+    }
     let mut inst =
         Instance::for_module(&arena, &module, dispatcher, is_debug_mode).unwrap_or_else(|e| {
             eprintln!("{e}");
             process::exit(2);
         });
 
+    if is_debugger_mode {
+        inst.set_debugger(Debugger::stdio());
+    }
+
+    if profile_path.is_some() {
+        inst.set_profiler(Profiler::new());
+    }
+
     // Run
 
     let result = inst.call_export_from_cli(&module, start_fn_name, &wasi_argv);
 
+    // Write the profile, even if the program failed
+
+    if let (Some(path), Some(profiler)) = (profile_path, inst.profiler()) {
+        profiler.write_summary(&module, &mut io::stderr())?;
+        let mut folded_stacks = std::vec::Vec::new();
+        profiler.write_folded_stacks(&module, &mut folded_stacks)?;
+        fs::write(path, folded_stacks)?;
+    }
+
     // Print out return value, if any
 
     match result {
@@ -115,5 +167,24 @@ fn main() -> io::Result<()> {
         }
     }
 
+    // Report any `expect`s that failed while the app was running
+
+    let expect_failures = &inst.import_dispatcher.expect_failures;
+    if !expect_failures.is_empty() {
+        for failure in expect_failures {
+            eprintln!(
+                "An expectation failed in module {} at byte offsets {}..{}",
+                failure.module_id, failure.region_start, failure.region_end
+            );
+        }
+        process::exit(4);
+    }
+
+    // Exit with the code the app passed to `proc_exit`, now that the profile has been written
+
+    if let Some(exit_code) = inst.import_dispatcher.wasi.exit_code {
+        process::exit(exit_code);
+    }
+
     Ok(())
 }
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use roc_wasm_module::WasmModule;
+
+/// Counts the instructions executed in each function and call stack.
+///
+/// Instruction counts don't depend on the machine or its load, so they're good for comparing
+/// the code generated by different versions of the compiler. Unlike timings, they don't
+/// account for some instructions being slower than others.
+#[derive(Debug)]
+pub struct Profiler {
+    /// Every call stack we've seen, as a tree. Node 0 is the root and has no function.
+    nodes: Vec<StackNode>,
+    /// Look up a node by its parent node and function index
+    children: HashMap<(usize, usize), usize>,
+    /// Node for the call stack we're currently executing
+    current_node: usize,
+    /// Stats for each function, by function index
+    functions: Vec<FunctionStats>,
+    total_instructions: u64,
+}
+
+#[derive(Debug)]
+struct StackNode {
+    fn_index: usize,
+    parent: usize,
+    /// Instructions executed with exactly this call stack
+    instructions: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+struct FunctionStats {
+    calls: u64,
+    /// Instructions executed in this function itself
+    exclusive: u64,
+    /// Instructions executed in this function and everything it called.
+    /// Recursive calls are only counted once.
+    inclusive: u64,
+    /// Number of calls to this function that are currently on the stack
+    active_calls: u32,
+    /// Total instruction count when the outermost active call started
+    inclusive_start: u64,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler {
+            nodes: vec![StackNode {
+                fn_index: usize::MAX,
+                parent: 0,
+                instructions: 0,
+            }],
+            children: HashMap::new(),
+            current_node: 0,
+            functions: vec![],
+            total_instructions: 0,
+        }
+    }
+
+    pub fn total_instructions(&self) -> u64 {
+        self.total_instructions
+    }
+
+    /// Called when an exported function is called from outside the module.
+    /// Anything left on the stack by a previous call that trapped is unwound first.
+    pub(crate) fn start(&mut self, fn_index: usize) {
+        while self.current_node != 0 {
+            self.exit();
+        }
+        self.enter(fn_index);
+    }
+
+    /// Called when a WebAssembly function is called
+    pub(crate) fn enter(&mut self, fn_index: usize) {
+        let parent = self.current_node;
+        let next_node = self.nodes.len();
+        self.current_node = *self.children.entry((parent, fn_index)).or_insert(next_node);
+        if self.current_node == next_node {
+            self.nodes.push(StackNode {
+                fn_index,
+                parent,
+                instructions: 0,
+            });
+        }
+
+        let total = self.total_instructions;
+        let stats = self.function_stats(fn_index);
+        stats.calls += 1;
+        if stats.active_calls == 0 {
+            stats.inclusive_start = total;
+        }
+        stats.active_calls += 1;
+    }
+
+    /// Called when a WebAssembly function returns
+    pub(crate) fn exit(&mut self) {
+        let StackNode {
+            fn_index, parent, ..
+        } = self.nodes[self.current_node];
+        self.current_node = parent;
+
+        let total = self.total_instructions;
+        let stats = &mut self.functions[fn_index];
+        stats.active_calls -= 1;
+        if stats.active_calls == 0 {
+            stats.inclusive += total - stats.inclusive_start;
+        }
+    }
+
+    /// Called when an imported function is called. It doesn't execute any WebAssembly
+    /// instructions, so it only appears in the call counts.
+    pub(crate) fn import_call(&mut self, fn_index: usize) {
+        self.function_stats(fn_index).calls += 1;
+    }
+
+    /// Called before every instruction
+    pub(crate) fn instruction(&mut self) {
+        self.total_instructions += 1;
+        let node = &mut self.nodes[self.current_node];
+        node.instructions += 1;
+        self.functions[node.fn_index].exclusive += 1;
+    }
+
+    fn function_stats(&mut self, fn_index: usize) -> &mut FunctionStats {
+        if fn_index >= self.functions.len() {
+            self.functions
+                .resize(fn_index + 1, FunctionStats::default());
+        }
+        &mut self.functions[fn_index]
+    }
+
+    /// Inclusive instruction count, including any calls that are still on the stack
+    fn inclusive(&self, stats: &FunctionStats) -> u64 {
+        if stats.active_calls > 0 {
+            stats.inclusive + self.total_instructions - stats.inclusive_start
+        } else {
+            stats.inclusive
+        }
+    }
+
+    /// Write a table of call counts and instruction counts for each function that was called,
+    /// with the most expensive functions first.
+    pub fn write_summary(&self, module: &WasmModule, output: &mut impl Write) -> io::Result<()> {
+        let mut called: Vec<(usize, &FunctionStats)> = self
+            .functions
+            .iter()
+            .enumerate()
+            .filter(|(_, stats)| stats.calls > 0)
+            .collect();
+        called.sort_by_key(|(fn_index, stats)| (u64::MAX - stats.exclusive, *fn_index));
+
+        writeln!(output, "Executed {} instructions", self.total_instructions)?;
+        writeln!(output)?;
+        writeln!(
+            output,
+            "{:>12} {:>14} {:>14}  function",
+            "calls", "inclusive", "exclusive"
+        )?;
+        for (fn_index, stats) in called {
+            writeln!(
+                output,
+                "{:>12} {:>14} {:>14}  {}",
+                stats.calls,
+                self.inclusive(stats),
+                stats.exclusive,
+                function_name(module, fn_index)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write instruction counts for each call stack, in the "folded stacks" format used by
+    /// flame graph tools like `inferno-flamegraph` and `flamegraph.pl`.
+    pub fn write_folded_stacks(
+        &self,
+        module: &WasmModule,
+        output: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut lines = Vec::with_capacity(self.nodes.len());
+        for (node_index, node) in self.nodes.iter().enumerate().skip(1) {
+            if node.instructions == 0 {
+                continue;
+            }
+            let mut names = vec![];
+            let mut index = node_index;
+            while index != 0 {
+                names.push(function_name(module, self.nodes[index].fn_index));
+                index = self.nodes[index].parent;
+            }
+            names.reverse();
+            lines.push(format!("{} {}", names.join(";"), node.instructions));
+        }
+        lines.sort();
+
+        for line in lines {
+            writeln!(output, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Function name from the Name section, or from the Import section for imports.
+/// Semicolons separate the functions in a folded stack, so we can't have any in the name.
+fn function_name(module: &WasmModule, fn_index: usize) -> String {
+    let debug_name = module
+        .names
+        .function_names
+        .iter()
+        .find(|(index, _)| *index as usize == fn_index)
+        .map(|(_, name)| name.to_string());
+
+    let name = debug_name.unwrap_or_else(|| {
+        let import = module
+            .import
+            .imports
+            .iter()
+            .filter(|import| import.is_function())
+            .nth(fn_index);
+        match import {
+            Some(import) => format!("{}.{}", import.module, import.name),
+            None => format!("func[{fn_index}]"),
+        }
+    });
+
+    name.replace(';', ":")
+}
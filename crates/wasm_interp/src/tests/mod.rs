@@ -2,11 +2,15 @@
 
 mod test_basics;
 mod test_convert;
+mod test_debugger;
 mod test_f32;
 mod test_f64;
 mod test_i32;
 mod test_i64;
 mod test_mem;
+mod test_profiler;
+mod test_spec;
+mod test_wasi;
 
 use crate::{DefaultImportDispatcher, Instance};
 use bumpalo::{collections::Vec, Bump};
@@ -5,9 +5,9 @@ use crate::tests::{
     const_value, create_exported_function_no_locals, create_exported_function_with_locals,
     default_state,
 };
-use crate::{DefaultImportDispatcher, ImportDispatcher, Instance};
+use crate::{decode_roc_str, DefaultImportDispatcher, ExpectFailure, ImportDispatcher, Instance};
 use bumpalo::{collections::Vec, Bump};
-use roc_wasm_module::sections::{Import, ImportDesc};
+use roc_wasm_module::sections::{Import, ImportDesc, MemorySection};
 use roc_wasm_module::{
     opcodes::OpCode, sections::ElementSegment, Export, ExportType, SerialBuffer, Serialize,
     Signature, Value, ValueType, WasmModule,
@@ -573,6 +573,150 @@ fn test_call_import() {
     assert_eq!(return_val, Value::I32(234));
 }
 
+#[test]
+fn test_call_import_expect_failed() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    let start_fn_name = "test";
+
+    // Function 0 is the import
+    module.import.imports.push(Import {
+        module: "env",
+        name: "roc_expect_failed",
+        description: ImportDesc::Func { signature_index: 0 },
+    });
+    module.types.insert(Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32, ValueType::I32],
+        ret_type: None,
+    });
+
+    // Function 1, which calls the import
+    module.code.function_count = 1;
+    let func0_offset = module.code.bytes.len() as u32;
+    module.code.function_offsets.push(func0_offset);
+    module.add_function_signature(Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: None,
+    });
+    module.export.append(Export {
+        name: start_fn_name,
+        ty: ExportType::Func,
+        index: 1,
+    });
+    [
+        0, // no locals
+        OpCode::I32CONST as u8,
+        12, // region start
+        OpCode::I32CONST as u8,
+        34, // region end
+        OpCode::I32CONST as u8,
+        5, // module id
+        OpCode::CALL as u8,
+        0, // function 0
+        OpCode::END as u8,
+    ]
+    .serialize(&mut module.code.bytes);
+
+    let is_debug_mode = false;
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        is_debug_mode,
+    )
+    .unwrap();
+
+    let return_val = inst.call_export(start_fn_name, []).unwrap();
+
+    assert_eq!(return_val, None);
+    assert_eq!(
+        inst.import_dispatcher.expect_failures,
+        [ExpectFailure {
+            region_start: 12,
+            region_end: 34,
+            module_id: 5,
+        }]
+    );
+}
+
+#[test]
+fn test_call_import_panic() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+    let start_fn_name = "test";
+
+    // Function 0 is the import
+    module.import.imports.push(Import {
+        module: "env",
+        name: "roc_panic",
+        description: ImportDesc::Func { signature_index: 0 },
+    });
+    module.types.insert(Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
+        ret_type: None,
+    });
+
+    // Function 1, which calls the import and would then trap if it kept running
+    module.code.function_count = 1;
+    let func0_offset = module.code.bytes.len() as u32;
+    module.code.function_offsets.push(func0_offset);
+    module.add_function_signature(Signature {
+        param_types: Vec::new_in(&arena),
+        ret_type: None,
+    });
+    module.export.append(Export {
+        name: start_fn_name,
+        ty: ExportType::Func,
+        index: 1,
+    });
+    [
+        0, // no locals
+        OpCode::I32CONST as u8,
+        0, // address of the message, an empty RocStr
+        OpCode::I32CONST as u8,
+        0, // panic tag
+        OpCode::CALL as u8,
+        0, // function 0
+        OpCode::UNREACHABLE as u8,
+        OpCode::END as u8,
+    ]
+    .serialize(&mut module.code.bytes);
+
+    let is_debug_mode = false;
+    let mut inst = Instance::for_module(
+        &arena,
+        &module,
+        DefaultImportDispatcher::default(),
+        is_debug_mode,
+    )
+    .unwrap();
+
+    let return_val = inst.call_export(start_fn_name, []).unwrap();
+
+    assert_eq!(return_val, None);
+    assert_eq!(inst.import_dispatcher.panic_message.as_deref(), Some(""));
+    assert_eq!(inst.import_dispatcher.exit_code(), Some(1));
+}
+
+#[test]
+fn test_decode_roc_str() {
+    let mut memory = [0u8; 64];
+
+    // small string, stored inline with its length in the last byte
+    memory[0..5].copy_from_slice(b"hello");
+    memory[11] = 0b1000_0000 | 5;
+    assert_eq!(decode_roc_str(&memory, 0), "hello");
+
+    // big string, stored as (pointer, length, capacity)
+    let text = b"this is a big string";
+    memory[32..][..text.len()].copy_from_slice(text);
+    memory[16..20].copy_from_slice(&32u32.to_le_bytes());
+    memory[20..24].copy_from_slice(&(text.len() as u32).to_le_bytes());
+    memory[24..28].copy_from_slice(&(text.len() as u32).to_le_bytes());
+    assert_eq!(decode_roc_str(&memory, 16), "this is a big string");
+}
+
 #[test]
 fn test_call_return_no_args() {
     let arena = Bump::new();
@@ -0,0 +1,133 @@
+use super::create_exported_function_no_locals;
+use crate::{Debugger, DefaultImportDispatcher, Instance};
+use bumpalo::Bump;
+use roc_wasm_module::{
+    opcodes::OpCode, sections::MemorySection, Serialize, Signature, Value, ValueType, WasmModule,
+};
+use std::cell::RefCell;
+use std::io::{self, Cursor, Write};
+use std::rc::Rc;
+
+/// Debugger output that the test can still read after the Debugger has taken ownership of it
+#[derive(Clone, Default)]
+struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedOutput {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+/// Module with `two_plus_two` calling `add`, then run it with some debugger commands
+fn run_with_commands(commands: &str) -> (Result<Option<Value>, String>, String) {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    // Function 0: calculate 2+2
+    let signature0 = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "two_plus_two", signature0, |buf| {
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(2);
+        buf.push(OpCode::I32CONST as u8);
+        buf.push(2);
+        buf.push(OpCode::CALL as u8);
+        buf.push(1);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 1: add two numbers
+    let func1_offset = module.code.bytes.len() as u32;
+    module.code.function_offsets.push(func1_offset);
+    module.code.function_count += 1;
+    module.add_function_signature(Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
+        ret_type: Some(ValueType::I32),
+    });
+    [
+        0, // no locals
+        OpCode::GETLOCAL as u8,
+        0,
+        OpCode::GETLOCAL as u8,
+        1,
+        OpCode::I32ADD as u8,
+        OpCode::END as u8,
+    ]
+    .serialize(&mut module.code.bytes);
+
+    module.names.function_names.push((0, "two_plus_two"));
+    module.names.function_names.push((1, "add"));
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    inst.memory[0..4].copy_from_slice(b"Roc!");
+
+    let output = SharedOutput::default();
+    let input = Cursor::new(commands.to_string().into_bytes());
+    inst.set_debugger(Debugger::new(Box::new(input), Box::new(output.clone())));
+
+    let result = inst.call_export("two_plus_two", []);
+    (result, output.text())
+}
+
+#[test]
+fn test_debugger_breakpoint() {
+    let (result, output) = run_with_commands("break add\ncontinue\nbt\nlocals\ncontinue\n");
+
+    assert_eq!(result, Ok(Some(Value::I32(4))));
+    assert!(output.contains("Breakpoint set at func[1] add"));
+    assert!(output.contains("Breakpoint hit\nfunc[1] add"));
+    assert!(output.contains("#0 func[1] add"));
+    assert!(output.contains("#1 func[0] two_plus_two"));
+    assert!(output.contains("arg   0: I32(2)\narg   1: I32(2)"));
+}
+
+#[test]
+fn test_debugger_step_next_finish() {
+    // Step twice (the second time by just pressing Enter), then step over the call
+    let (result, output) = run_with_commands("s\n\nn\nstack\nbreak 1\nd\n");
+    assert_eq!(result, Ok(Some(Value::I32(4))));
+    assert!(output.contains("I32CONST"));
+    assert!(output.contains("CALL"));
+    assert!(!output.contains("GETLOCAL"));
+    assert!(output.contains("[I32(4)]"));
+
+    let (result, output) = run_with_commands("b add\nc\nfinish\nstack\nc\n");
+    assert_eq!(result, Ok(Some(Value::I32(4))));
+    assert!(output.contains("func[0] two_plus_two"));
+    assert!(output.contains("[I32(4)]"));
+}
+
+#[test]
+fn test_debugger_memory() {
+    let (_, output) = run_with_commands("x 0 4\nmemory 0x0 2\nc\n");
+    assert!(output.contains("00000000  52 6f 63 21"));
+    assert!(output.contains("|Roc!|"));
+    assert!(output.contains("|Ro|"));
+}
+
+#[test]
+fn test_debugger_quit() {
+    let (result, _) = run_with_commands("quit\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_debugger_unknown_function() {
+    let (result, output) = run_with_commands("break nope\nc\n");
+    assert_eq!(result, Ok(Some(Value::I32(4))));
+    assert!(output.contains("I couldn't find a function called nope"));
+}
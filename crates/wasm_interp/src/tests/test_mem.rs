@@ -2,7 +2,7 @@ use super::create_exported_function_no_locals;
 use crate::{DefaultImportDispatcher, Instance};
 use bumpalo::{collections::Vec, Bump};
 use roc_wasm_module::{
-    opcodes::OpCode,
+    opcodes::{MemoryInstruction, OpCode},
     sections::{DataMode, DataSegment, MemorySection},
     ConstExpr, SerialBuffer, Signature, Value, ValueType, WasmModule,
 };
@@ -119,6 +119,91 @@ fn test_memory_copy() {
     assert_eq!(dest_slice, &[0xAA; SIZE as usize])
 }
 
+#[test]
+fn test_memory_copy_out_of_bounds() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let pages = 1;
+    let pc = 0;
+    module.memory = MemorySection::new(&arena, pages * MemorySection::PAGE_SIZE);
+
+    let bytes = [OpCode::MEMORY as u8, 10, 0x0, 0x0];
+    module.code.bytes.extend(bytes);
+
+    let mut state = Instance::new(&arena, pages, pc, [], DefaultImportDispatcher::default());
+
+    // The source range ends one byte past the end of memory
+    let memory_size = MemorySection::PAGE_SIZE as i32;
+    state.value_store.push(Value::I32(0));
+    state.value_store.push(Value::I32(memory_size - 3));
+    state.value_store.push(Value::I32(4));
+
+    assert!(state.execute_next_instruction(&module).is_err());
+}
+
+#[test]
+fn test_memory_init_data_drop() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    let start_fn_name = "test";
+    let destination = 0x10;
+
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    // Data segment indices count active segments too, even though memory.init can't use them
+    module.data.append_segment(DataSegment {
+        mode: DataMode::active_at(0x100),
+        init: Vec::from_iter_in(*b"active", &arena),
+    });
+    module.data.append_segment(DataSegment {
+        mode: DataMode::Passive,
+        init: Vec::from_iter_in(*b"passive", &arena),
+    });
+
+    let signature = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_type: Some(ValueType::I32),
+    };
+
+    create_exported_function_no_locals(&mut module, start_fn_name, signature, |buf| {
+        // memory.init 1 (destination, source offset 1, size 4)
+        buf.append_u8(OpCode::I32CONST as u8);
+        buf.encode_u32(destination);
+        buf.append_u8(OpCode::I32CONST as u8);
+        buf.encode_u32(1);
+        buf.append_u8(OpCode::I32CONST as u8);
+        buf.encode_u32(4);
+        buf.append_u8(OpCode::MEMORY as u8);
+        buf.append_u8(MemoryInstruction::MemoryInit as u8);
+        buf.encode_u32(1);
+        buf.append_u8(0);
+
+        // data.drop 1
+        buf.append_u8(OpCode::MEMORY as u8);
+        buf.append_u8(MemoryInstruction::DataDrop as u8);
+        buf.encode_u32(1);
+
+        buf.append_u8(OpCode::I32CONST as u8);
+        buf.encode_u32(destination);
+        buf.append_u8(OpCode::I32LOAD as u8);
+        buf.encode_u32(0); // align
+        buf.encode_u32(0); // offset
+        buf.append_u8(OpCode::END as u8);
+    });
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+
+    let result = inst.call_export(start_fn_name, []).unwrap().unwrap();
+    assert_eq!(result, Value::I32(i32::from_le_bytes(*b"assi")));
+    assert_eq!(&inst.memory[0x100..][..6], b"active");
+
+    // The segment has been dropped, so copying from it again is out of bounds
+    assert!(inst.call_export(start_fn_name, []).is_err());
+}
+
 fn test_load(load_op: OpCode, ty: ValueType, data: &[u8], addr: u32, offset: u32) -> Value {
     let arena = Bump::new();
     let mut module = WasmModule::new(&arena);
@@ -0,0 +1,203 @@
+use super::create_exported_function_no_locals;
+use crate::{wasi, DefaultImportDispatcher, Instance, Profiler};
+use bumpalo::Bump;
+use roc_wasm_module::sections::{Import, ImportDesc};
+use roc_wasm_module::{opcodes::OpCode, Serialize, Signature, Value, ValueType, WasmModule};
+
+/// Run an exported function with the profiler on, and return its summary and folded stacks
+fn profile(module: &WasmModule, fn_name: &str) -> (Option<Value>, String, String) {
+    let arena = Bump::new();
+    let mut inst =
+        Instance::for_module(&arena, module, DefaultImportDispatcher::default(), false).unwrap();
+    inst.set_profiler(Profiler::new());
+    let result = inst.call_export(fn_name, []).unwrap();
+
+    let profiler = inst.profiler().unwrap();
+    let mut summary = vec![];
+    profiler.write_summary(module, &mut summary).unwrap();
+    let mut folded = vec![];
+    profiler.write_folded_stacks(module, &mut folded).unwrap();
+
+    (
+        result,
+        String::from_utf8(summary).unwrap(),
+        String::from_utf8(folded).unwrap(),
+    )
+}
+
+/// Add a non-exported function to the module
+fn add_function<'a>(module: &mut WasmModule<'a>, signature: Signature<'a>, body: &[u8]) {
+    let offset = module.code.bytes.len() as u32;
+    module.code.function_offsets.push(offset);
+    module.code.function_count += 1;
+    module.add_function_signature(signature);
+    body.serialize(&mut module.code.bytes);
+}
+
+#[test]
+fn test_profiler_counts() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    // Function 0: calculate (2+2)+3
+    let signature0 = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "main", signature0, |buf| {
+        buf.extend([OpCode::I32CONST as u8, 2]);
+        buf.extend([OpCode::I32CONST as u8, 2]);
+        buf.extend([OpCode::CALL as u8, 1]);
+        buf.extend([OpCode::I32CONST as u8, 3]);
+        buf.extend([OpCode::CALL as u8, 1]);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 1: add two numbers
+    let signature1 = Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32, ValueType::I32],
+        ret_type: Some(ValueType::I32),
+    };
+    add_function(
+        &mut module,
+        signature1,
+        &[
+            0, // no locals
+            OpCode::GETLOCAL as u8,
+            0,
+            OpCode::GETLOCAL as u8,
+            1,
+            OpCode::I32ADD as u8,
+            OpCode::END as u8,
+        ],
+    );
+
+    module.names.function_names.push((1, "add"));
+
+    let (result, summary, folded) = profile(&module, "main");
+    assert_eq!(result, Some(Value::I32(7)));
+    assert_eq!(
+        summary,
+        [
+            "Executed 14 instructions",
+            "",
+            "       calls      inclusive      exclusive  function",
+            "           2              8              8  add",
+            "           1             14              6  func[0]",
+            "",
+        ]
+        .join("\n")
+    );
+    assert_eq!(folded, "func[0] 6\nfunc[0];add 8\n");
+}
+
+#[test]
+fn test_profiler_recursion() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    // Function 0: calculate 3 factorial
+    let signature0 = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_type: Some(ValueType::I32),
+    };
+    create_exported_function_no_locals(&mut module, "main", signature0, |buf| {
+        buf.extend([OpCode::I32CONST as u8, 3]);
+        buf.extend([OpCode::CALL as u8, 1]);
+        buf.push(OpCode::END as u8);
+    });
+
+    // Function 1: recursive factorial
+    let signature1 = Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32],
+        ret_type: Some(ValueType::I32),
+    };
+    add_function(
+        &mut module,
+        signature1,
+        &[
+            0, // no locals
+            OpCode::GETLOCAL as u8,
+            0,
+            OpCode::I32EQZ as u8,
+            OpCode::IF as u8,
+            ValueType::I32 as u8,
+            OpCode::I32CONST as u8,
+            1,
+            OpCode::ELSE as u8,
+            OpCode::GETLOCAL as u8,
+            0,
+            OpCode::GETLOCAL as u8,
+            0,
+            OpCode::I32CONST as u8,
+            1,
+            OpCode::I32SUB as u8,
+            OpCode::CALL as u8,
+            1,
+            OpCode::I32MUL as u8,
+            OpCode::END as u8,
+            OpCode::END as u8,
+        ],
+    );
+
+    module.names.function_names.push((0, "main"));
+    module.names.function_names.push((1, "fact"));
+
+    let (result, summary, folded) = profile(&module, "main");
+    assert_eq!(result, Some(Value::I32(6)));
+
+    // Each recursive call gets its own stack, but is only counted once in the inclusive count
+    let folded_lines: Vec<&str> = folded.lines().collect();
+    assert_eq!(folded_lines.len(), 5);
+    assert_eq!(folded_lines[0], "main 3");
+    assert!(folded_lines[4].starts_with("main;fact;fact;fact;fact "));
+
+    let total: u64 = folded_lines
+        .iter()
+        .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+        .sum();
+    assert!(summary.starts_with(&format!("Executed {total} instructions")));
+    let fact_line = format!("           4 {:>14} {:>14}  fact", total - 3, total - 3);
+    assert!(summary.contains(&fact_line), "{summary}");
+}
+
+#[test]
+fn test_profiler_proc_exit() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+
+    // Function 0 is WASI `proc_exit`
+    module.import.imports.push(Import {
+        module: wasi::MODULE_NAME,
+        name: "proc_exit",
+        description: ImportDesc::Func { signature_index: 0 },
+    });
+    module.types.insert(Signature {
+        param_types: bumpalo::vec![in &arena; ValueType::I32],
+        ret_type: None,
+    });
+
+    // Function 1 exits before it gets to the `unreachable`
+    let signature1 = Signature {
+        param_types: bumpalo::vec![in &arena],
+        ret_type: None,
+    };
+    create_exported_function_no_locals(&mut module, "_start", signature1, |buf| {
+        buf.extend([OpCode::I32CONST as u8, 3]);
+        buf.extend([OpCode::CALL as u8, 0]);
+        buf.push(OpCode::UNREACHABLE as u8);
+        buf.push(OpCode::END as u8);
+    });
+
+    let mut inst =
+        Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false).unwrap();
+    inst.set_profiler(Profiler::new());
+    assert_eq!(inst.call_export("_start", []), Ok(None));
+    assert_eq!(inst.import_dispatcher.wasi.exit_code, Some(3));
+
+    // The profile is still available, so the CLI can write it before exiting
+    let mut folded = vec![];
+    let profiler = inst.profiler().unwrap();
+    profiler.write_folded_stacks(&module, &mut folded).unwrap();
+    assert_eq!(String::from_utf8(folded).unwrap(), "func[1] 2\n");
+}
@@ -0,0 +1,395 @@
+//! Run WebAssembly spec test scripts (`.wast` files) through the interpreter.
+//!
+//! The scripts live in `spec_tests/` at the root of this crate. See the README there
+//! for how to vendor the official test suite. Every script gets run, and we print a
+//! summary of how many assertions passed. The test fails if a script is missing, or if
+//! a script listed in `MUST_PASS` has a failing assertion, so that we can track progress
+//! on the rest of the suite without breaking CI.
+
+use crate::{ImportDispatcher, Instance};
+use bumpalo::Bump;
+use roc_wasm_module::{ExportType, Value};
+use std::fmt::Write;
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use wast::core::{NanPattern, WastArgCore, WastRetCore};
+use wast::parser::{self, ParseBuffer};
+use wast::{QuoteWat, Wast, WastArg, WastDirective, WastExecute, WastInvoke, WastRet};
+
+/// Official test suite scripts for the features the interpreter supports.
+/// They are run from `spec_tests/testsuite/`, at the commit in `spec_tests/testsuite/COMMIT`.
+const OFFICIAL_SCRIPTS: &[&str] = &[
+    "address",
+    "align",
+    "block",
+    "br",
+    "br_if",
+    "br_table",
+    "bulk",
+    "call",
+    "call_indirect",
+    "const",
+    "conversions",
+    "endianness",
+    "f32",
+    "f32_bitwise",
+    "f32_cmp",
+    "f64",
+    "f64_bitwise",
+    "f64_cmp",
+    "fac",
+    "float_exprs",
+    "float_memory",
+    "forward",
+    "func",
+    "i32",
+    "i64",
+    "if",
+    "int_exprs",
+    "labels",
+    "left-to-right",
+    "load",
+    "local_get",
+    "local_set",
+    "local_tee",
+    "loop",
+    "memory",
+    "memory_copy",
+    "memory_fill",
+    "memory_grow",
+    "memory_init",
+    "memory_size",
+    "nop",
+    "return",
+    "select",
+    "stack",
+    "store",
+    "switch",
+    "traps",
+    "unreachable",
+    "unwind",
+];
+
+/// Our own scripts, in `spec_tests/roc/`
+const ROC_SCRIPTS: &[&str] = &["blocks", "br", "bulk_memory", "memory"];
+
+/// Scripts that must have no failing assertions
+const MUST_PASS: &[&str] = &["roc/blocks", "roc/br", "roc/bulk_memory", "roc/memory"];
+
+#[test]
+fn test_spec_scripts() {
+    let spec_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("spec_tests");
+    let scripts = Iterator::chain(
+        OFFICIAL_SCRIPTS.iter().map(|name| ("testsuite", name)),
+        ROC_SCRIPTS.iter().map(|name| ("roc", name)),
+    );
+
+    let mut summary = String::new();
+    let mut missing = vec![];
+    let mut must_pass_failures = vec![];
+
+    for (dir, name) in scripts {
+        let script_name = format!("{dir}/{name}");
+        let path: PathBuf = spec_dir.join(dir).join(format!("{name}.wast"));
+        let Ok(text) = fs::read_to_string(&path) else {
+            missing.push(script_name);
+            continue;
+        };
+
+        let results = run_script(&text);
+        writeln!(
+            summary,
+            "{:<24} {:>6} passed {:>6} failed {:>6} skipped",
+            script_name,
+            results.passed,
+            results.failures.len(),
+            results.skipped
+        )
+        .unwrap();
+
+        if MUST_PASS.contains(&script_name.as_str()) {
+            for failure in results.failures {
+                must_pass_failures.push(format!("{script_name}.wast:{failure}"));
+            }
+        }
+    }
+
+    println!("\nWebAssembly spec test results\n\n{summary}");
+
+    assert!(
+        missing.is_empty(),
+        "Missing spec test scripts (see spec_tests/README.md): {}",
+        missing.join(", ")
+    );
+    assert!(
+        must_pass_failures.is_empty(),
+        "Failing spec test assertions:\n{}",
+        must_pass_failures.join("\n")
+    );
+}
+
+/// Spec test scripts import a few functions from a "spectest" module, for printing
+struct SpecTestDispatcher;
+
+impl ImportDispatcher for SpecTestDispatcher {
+    fn dispatch(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        _arguments: &[Value],
+        _memory: &mut [u8],
+    ) -> Option<Value> {
+        if module_name == "spectest" && function_name.starts_with("print") {
+            None
+        } else {
+            panic!("Unknown import {module_name}.{function_name}")
+        }
+    }
+}
+
+#[derive(Default)]
+struct ScriptResults {
+    passed: usize,
+    skipped: usize,
+    /// Line number and description of each failed assertion
+    failures: Vec<String>,
+}
+
+/// The outcome of a single directive in a script
+enum Outcome {
+    Pass,
+    Skip,
+    Fail(String),
+}
+
+fn run_script(text: &str) -> ScriptResults {
+    let mut results = ScriptResults::default();
+
+    let buf = match ParseBuffer::new(text) {
+        Ok(buf) => buf,
+        Err(e) => {
+            results.failures.push(format!("0: {e}"));
+            return results;
+        }
+    };
+    let wast: Wast = match parser::parse(&buf) {
+        Ok(wast) => wast,
+        Err(e) => {
+            results.failures.push(format!("0: {e}"));
+            return results;
+        }
+    };
+
+    let arena = Bump::new();
+    let mut instance: Option<Instance<SpecTestDispatcher>> = None;
+
+    for directive in wast.directives {
+        let (line, _) = directive.span().linecol_in(text);
+
+        // The interpreter might panic on things it doesn't support. Treat that as a failure.
+        let outcome = catch_unwind(AssertUnwindSafe(|| {
+            run_directive(&arena, &mut instance, directive)
+        }))
+        .unwrap_or_else(|panic| {
+            let message = panic
+                .downcast_ref::<String>()
+                .map(|s| s.as_str())
+                .or_else(|| panic.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown panic");
+            Outcome::Fail(format!("interpreter panicked: {message}"))
+        });
+
+        match outcome {
+            Outcome::Pass => results.passed += 1,
+            Outcome::Skip => results.skipped += 1,
+            Outcome::Fail(message) => results.failures.push(format!("{}: {message}", line + 1)),
+        }
+    }
+
+    results
+}
+
+fn run_directive<'a>(
+    arena: &'a Bump,
+    instance: &mut Option<Instance<'a, SpecTestDispatcher>>,
+    directive: WastDirective,
+) -> Outcome {
+    match directive {
+        WastDirective::Wat(mut module) => {
+            // Any assertions after a module that fails to load will also fail
+            *instance = None;
+            match instantiate(arena, &mut module) {
+                Ok(inst) => {
+                    *instance = Some(inst);
+                    Outcome::Pass
+                }
+                Err(e) => Outcome::Fail(format!("failed to load module: {e}")),
+            }
+        }
+
+        WastDirective::Invoke(invoke) => match invoke_export(instance, &invoke) {
+            Err(outcome) => outcome,
+            Ok(Ok(_)) => Outcome::Pass,
+            Ok(Err(e)) => Outcome::Fail(format!("unexpected trap: {e}")),
+        },
+
+        WastDirective::AssertReturn { exec, results, .. } => {
+            let result = match exec {
+                WastExecute::Invoke(invoke) => match invoke_export(instance, &invoke) {
+                    Err(outcome) => return outcome,
+                    Ok(result) => result,
+                },
+                WastExecute::Get {
+                    module: None,
+                    global,
+                } => match get_global(instance, global) {
+                    Some(value) => Ok(Some(value)),
+                    None => return Outcome::Fail(format!("no exported global {global}")),
+                },
+                WastExecute::Get { .. } | WastExecute::Wat(_) => return Outcome::Skip,
+            };
+
+            // The interpreter doesn't support multiple return values
+            let expected = match results.as_slice() {
+                [] => None,
+                [expected] => Some(expected),
+                _ => return Outcome::Skip,
+            };
+
+            match (result, expected) {
+                (Err(e), _) => Outcome::Fail(format!("unexpected trap: {e}")),
+                (Ok(None), None) => Outcome::Pass,
+                (Ok(Some(actual)), Some(expected)) => match return_matches(expected, actual) {
+                    Some(true) => Outcome::Pass,
+                    Some(false) => {
+                        Outcome::Fail(format!("expected {expected:?} but got {actual:?}"))
+                    }
+                    None => Outcome::Skip,
+                },
+                (Ok(actual), expected) => {
+                    Outcome::Fail(format!("expected {expected:?} but got {actual:?}"))
+                }
+            }
+        }
+
+        WastDirective::AssertTrap { exec, message, .. } => match exec {
+            WastExecute::Invoke(invoke) => match invoke_export(instance, &invoke) {
+                Err(outcome) => outcome,
+                Ok(Err(_)) => Outcome::Pass,
+                Ok(Ok(value)) => {
+                    Outcome::Fail(format!("expected trap \"{message}\" but got {value:?}"))
+                }
+            },
+            // We don't run start functions, so instantiation can't trap
+            WastExecute::Wat(_) | WastExecute::Get { .. } => Outcome::Skip,
+        },
+
+        // Validation, linking, and resource exhaustion are out of scope for the interpreter.
+        // It assumes that the Roc compiler gives it valid modules.
+        WastDirective::AssertMalformed { .. }
+        | WastDirective::AssertInvalid { .. }
+        | WastDirective::AssertUnlinkable { .. }
+        | WastDirective::AssertExhaustion { .. }
+        | WastDirective::AssertException { .. }
+        | WastDirective::Register { .. } => Outcome::Skip,
+    }
+}
+
+fn instantiate<'a>(
+    arena: &'a Bump,
+    module: &mut QuoteWat,
+) -> Result<Instance<'a, SpecTestDispatcher>, String> {
+    let bytes = module.encode().map_err(|e| e.to_string())?;
+    Instance::from_bytes(arena, &bytes, SpecTestDispatcher, false)
+}
+
+/// Call an exported function of the current module.
+/// The outer Err is for invocations we can't run, the inner one for traps.
+#[allow(clippy::type_complexity)]
+fn invoke_export(
+    instance: &mut Option<Instance<SpecTestDispatcher>>,
+    invoke: &WastInvoke,
+) -> Result<Result<Option<Value>, String>, Outcome> {
+    // Named modules are only used to test linking, which we don't support
+    if invoke.module.is_some() {
+        return Err(Outcome::Skip);
+    }
+    let Some(instance) = instance else {
+        return Err(Outcome::Fail("no module loaded".into()));
+    };
+    let mut args = Vec::with_capacity(invoke.args.len());
+    for arg in invoke.args.iter() {
+        match arg_value(arg) {
+            Some(value) => args.push(value),
+            None => return Err(Outcome::Skip),
+        }
+    }
+    Ok(instance.call_export(invoke.name, args))
+}
+
+fn get_global(instance: &Option<Instance<SpecTestDispatcher>>, name: &str) -> Option<Value> {
+    let instance = instance.as_ref()?;
+    let export = instance
+        .module
+        .export
+        .exports
+        .iter()
+        .find(|export| export.ty == ExportType::Global && export.name == name)?;
+    instance.globals.get(export.index as usize).copied()
+}
+
+fn arg_value(arg: &WastArg) -> Option<Value> {
+    match arg {
+        WastArg::Core(WastArgCore::I32(x)) => Some(Value::I32(*x)),
+        WastArg::Core(WastArgCore::I64(x)) => Some(Value::I64(*x)),
+        WastArg::Core(WastArgCore::F32(x)) => Some(Value::F32(f32::from_bits(x.bits))),
+        WastArg::Core(WastArgCore::F64(x)) => Some(Value::F64(f64::from_bits(x.bits))),
+        _ => None,
+    }
+}
+
+/// Check a return value against the expected one. Floats are compared bit-for-bit.
+/// Returns None for value types the interpreter doesn't support.
+fn return_matches(expected: &WastRet, actual: Value) -> Option<bool> {
+    match expected {
+        WastRet::Core(expected) => core_return_matches(expected, actual),
+        _ => None,
+    }
+}
+
+fn core_return_matches(expected: &WastRetCore, actual: Value) -> Option<bool> {
+    let matches = match (expected, actual) {
+        (WastRetCore::I32(x), Value::I32(y)) => *x == y,
+        (WastRetCore::I64(x), Value::I64(y)) => *x == y,
+        (WastRetCore::F32(pattern), Value::F32(y)) => {
+            let bits = y.to_bits();
+            match pattern {
+                NanPattern::Value(x) => x.bits == bits,
+                NanPattern::CanonicalNan => bits & 0x7fff_ffff == 0x7fc0_0000,
+                NanPattern::ArithmeticNan => y.is_nan() && bits & 0x0040_0000 != 0,
+            }
+        }
+        (WastRetCore::F64(pattern), Value::F64(y)) => {
+            let bits = y.to_bits();
+            match pattern {
+                NanPattern::Value(x) => x.bits == bits,
+                NanPattern::CanonicalNan => bits & 0x7fff_ffff_ffff_ffff == 0x7ff8_0000_0000_0000,
+                NanPattern::ArithmeticNan => y.is_nan() && bits & 0x0008_0000_0000_0000 != 0,
+            }
+        }
+        (WastRetCore::Either(options), _) => {
+            let any_match = options
+                .iter()
+                .filter_map(|option| core_return_matches(option, actual))
+                .any(|m| m);
+            return Some(any_match);
+        }
+        (
+            WastRetCore::I32(_) | WastRetCore::I64(_) | WastRetCore::F32(_) | WastRetCore::F64(_),
+            _,
+        ) => false,
+        _ => return None,
+    };
+    Some(matches)
+}
@@ -0,0 +1,325 @@
+use crate::wasi::{Errno, WasiDispatcher};
+use crate::Value;
+use std::fs;
+
+const PREOPEN_FD: i32 = 3;
+const PATH_ADDR: usize = 0x100;
+const IOVS_ADDR: usize = 0x200;
+const OUT_ADDR: usize = 0x300;
+const BUF_ADDR: usize = 0x400;
+
+const OFLAGS_CREAT: i32 = 1;
+const RIGHTS_READ_WRITE: i64 = (1 << 1) | (1 << 6);
+
+/// Call a WASI function and return its error code
+fn call(wasi: &mut WasiDispatcher, name: &str, args: &[Value], memory: &mut [u8]) -> i32 {
+    let result = wasi.dispatch(name, args, memory).unwrap();
+    result.expect_i32().unwrap()
+}
+
+fn read_u32(memory: &[u8], addr: usize) -> u32 {
+    u32::from_le_bytes(memory[addr..][..4].try_into().unwrap())
+}
+
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    u64::from_le_bytes(memory[addr..][..8].try_into().unwrap())
+}
+
+/// Write a path into memory and return the [pointer, length] arguments for it
+fn path_args(memory: &mut [u8], path: &str) -> [Value; 2] {
+    memory[PATH_ADDR..][..path.len()].copy_from_slice(path.as_bytes());
+    [Value::I32(PATH_ADDR as i32), Value::I32(path.len() as i32)]
+}
+
+fn path_open(
+    wasi: &mut WasiDispatcher,
+    memory: &mut [u8],
+    path: &str,
+    oflags: i32,
+) -> Result<i32, i32> {
+    let [path_ptr, path_len] = path_args(memory, path);
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(1), // follow symlinks
+        path_ptr,
+        path_len,
+        Value::I32(oflags),
+        Value::I64(RIGHTS_READ_WRITE),
+        Value::I64(RIGHTS_READ_WRITE),
+        Value::I32(0),
+        Value::I32(OUT_ADDR as i32),
+    ];
+    match call(wasi, "path_open", &args, memory) {
+        0 => Ok(read_u32(memory, OUT_ADDR) as i32),
+        errno => Err(errno),
+    }
+}
+
+/// Make a symlink at `link` in the preopened directory, containing `target`
+fn path_symlink(wasi: &mut WasiDispatcher, memory: &mut [u8], target: &str, link: &str) -> i32 {
+    memory[BUF_ADDR..][..target.len()].copy_from_slice(target.as_bytes());
+    let [link_ptr, link_len] = path_args(memory, link);
+    let args = [
+        Value::I32(BUF_ADDR as i32),
+        Value::I32(target.len() as i32),
+        Value::I32(PREOPEN_FD),
+        link_ptr,
+        link_len,
+    ];
+    call(wasi, "path_symlink", &args, memory)
+}
+
+/// Read or write a single buffer, using an iovec at a fixed address
+fn fd_io(wasi: &mut WasiDispatcher, memory: &mut [u8], name: &str, fd: i32, len: usize) -> u32 {
+    memory[IOVS_ADDR..][..4].copy_from_slice(&(BUF_ADDR as u32).to_le_bytes());
+    memory[IOVS_ADDR + 4..][..4].copy_from_slice(&(len as u32).to_le_bytes());
+    let args = [
+        Value::I32(fd),
+        Value::I32(IOVS_ADDR as i32),
+        Value::I32(1),
+        Value::I32(OUT_ADDR as i32),
+    ];
+    assert_eq!(call(wasi, name, &args, memory), Errno::Success as i32);
+    read_u32(memory, OUT_ADDR)
+}
+
+#[test]
+fn test_preopen_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+
+    let args = [Value::I32(PREOPEN_FD), Value::I32(OUT_ADDR as i32)];
+    assert_eq!(
+        call(&mut wasi, "fd_prestat_get", &args, &mut memory),
+        Errno::Badf as i32
+    );
+
+    wasi.preopen_dir(dir.path(), "/sandbox").unwrap();
+
+    assert_eq!(
+        call(&mut wasi, "fd_prestat_get", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u32(&memory, OUT_ADDR), 0); // directory
+    assert_eq!(read_u32(&memory, OUT_ADDR + 4), "/sandbox".len() as u32);
+
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(BUF_ADDR as i32),
+        Value::I32("/sandbox".len() as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, "fd_prestat_dir_name", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(&memory[BUF_ADDR..][.."/sandbox".len()], b"/sandbox");
+}
+
+#[test]
+fn test_write_seek_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+    wasi.preopen_dir(dir.path(), ".").unwrap();
+
+    let fd = path_open(&mut wasi, &mut memory, "hello.txt", OFLAGS_CREAT).unwrap();
+    assert_eq!(fd, PREOPEN_FD + 1);
+
+    memory[BUF_ADDR..][..5].copy_from_slice(b"hello");
+    assert_eq!(fd_io(&mut wasi, &mut memory, "fd_write", fd, 5), 5);
+
+    let args = [
+        Value::I32(fd),
+        Value::I64(1),
+        Value::I32(0), // whence = SET
+        Value::I32(OUT_ADDR as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, "fd_seek", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(read_u64(&memory, OUT_ADDR), 1);
+
+    memory[BUF_ADDR..][..5].fill(0);
+    assert_eq!(fd_io(&mut wasi, &mut memory, "fd_read", fd, 5), 4);
+    assert_eq!(&memory[BUF_ADDR..][..4], b"ello");
+
+    let args = [Value::I32(fd)];
+    assert_eq!(
+        call(&mut wasi, "fd_close", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(
+        call(&mut wasi, "fd_close", &args, &mut memory),
+        Errno::Badf as i32
+    );
+
+    assert_eq!(fs::read(dir.path().join("hello.txt")).unwrap(), b"hello");
+}
+
+#[test]
+fn test_sandbox() {
+    let parent = tempfile::tempdir().unwrap();
+    let sandbox = parent.path().join("sandbox");
+    fs::create_dir(&sandbox).unwrap();
+    fs::write(parent.path().join("secret.txt"), "secret").unwrap();
+
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+    wasi.preopen_dir(&sandbox, ".").unwrap();
+
+    for path in ["../secret.txt", "a/../../secret.txt", "/etc/passwd"] {
+        assert_eq!(
+            path_open(&mut wasi, &mut memory, path, 0),
+            Err(Errno::Notcapable as i32)
+        );
+    }
+    assert_eq!(
+        path_open(&mut wasi, &mut memory, "missing.txt", 0),
+        Err(Errno::Noent as i32)
+    );
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(parent.path().join("secret.txt"), sandbox.join("link")).unwrap();
+        assert_eq!(
+            path_open(&mut wasi, &mut memory, "link", 0),
+            Err(Errno::Notcapable as i32)
+        );
+
+        // The app can't make links which lead outside the sandbox
+        for target in ["/etc", "..", "a/../../secret.txt"] {
+            assert_eq!(
+                path_symlink(&mut wasi, &mut memory, target, "x"),
+                Errno::Notcapable as i32
+            );
+            assert!(fs::symlink_metadata(sandbox.join("x")).is_err());
+        }
+        assert_eq!(
+            path_symlink(&mut wasi, &mut memory, "inside.txt", "x"),
+            Errno::Success as i32
+        );
+
+        // Creating a file through a link to a directory outside the sandbox
+        std::os::unix::fs::symlink(parent.path(), sandbox.join("out")).unwrap();
+        assert_eq!(
+            path_open(&mut wasi, &mut memory, "out/newfile", OFLAGS_CREAT),
+            Err(Errno::Notcapable as i32)
+        );
+        assert!(!parent.path().join("newfile").exists());
+
+        // Creating a file through a dangling link to somewhere outside the sandbox
+        std::os::unix::fs::symlink(parent.path().join("created.txt"), sandbox.join("dangling"))
+            .unwrap();
+        assert_eq!(
+            path_open(&mut wasi, &mut memory, "dangling", OFLAGS_CREAT),
+            Err(Errno::Notcapable as i32)
+        );
+        assert!(!parent.path().join("created.txt").exists());
+    }
+}
+
+#[test]
+fn test_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+    wasi.preopen_dir(dir.path(), ".").unwrap();
+
+    let [path_ptr, path_len] = path_args(&mut memory, "subdir");
+    let args = [Value::I32(PREOPEN_FD), path_ptr, path_len];
+    assert_eq!(
+        call(&mut wasi, "path_create_directory", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert!(dir.path().join("subdir").is_dir());
+
+    let [path_ptr, path_len] = path_args(&mut memory, "subdir");
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(0),
+        path_ptr,
+        path_len,
+        Value::I32(OUT_ADDR as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, "path_filestat_get", &args, &mut memory),
+        Errno::Success as i32
+    );
+    assert_eq!(memory[OUT_ADDR + 16], 3); // directory
+
+    fs::write(dir.path().join("file.txt"), "").unwrap();
+    let args = [
+        Value::I32(PREOPEN_FD),
+        Value::I32(BUF_ADDR as i32),
+        Value::I32(0x400),
+        Value::I64(0),
+        Value::I32(OUT_ADDR as i32),
+    ];
+    assert_eq!(
+        call(&mut wasi, "fd_readdir", &args, &mut memory),
+        Errno::Success as i32
+    );
+    let bufused = read_u32(&memory, OUT_ADDR) as usize;
+
+    let mut names = vec![];
+    let mut offset = 0;
+    while offset < bufused {
+        let namlen = read_u32(&memory, BUF_ADDR + offset + 16) as usize;
+        let name = &memory[BUF_ADDR + offset + 24..][..namlen];
+        names.push(String::from_utf8(name.to_vec()).unwrap());
+        offset += 24 + namlen;
+    }
+    assert_eq!(names, [".", "..", "file.txt", "subdir"]);
+}
+
+#[test]
+fn test_clocks() {
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+
+    let realtime = [Value::I32(0), Value::I64(1), Value::I32(OUT_ADDR as i32)];
+    assert_eq!(
+        call(&mut wasi, "clock_time_get", &realtime, &mut memory),
+        Errno::Success as i32
+    );
+    // Some time after 2020
+    assert!(read_u64(&memory, OUT_ADDR) > 1_577_836_800_000_000_000);
+
+    let monotonic = [Value::I32(1), Value::I64(1), Value::I32(OUT_ADDR as i32)];
+    assert_eq!(
+        call(&mut wasi, "clock_time_get", &monotonic, &mut memory),
+        Errno::Success as i32
+    );
+    let first = read_u64(&memory, OUT_ADDR);
+    assert_eq!(
+        call(&mut wasi, "clock_time_get", &monotonic, &mut memory),
+        Errno::Success as i32
+    );
+    assert!(read_u64(&memory, OUT_ADDR) >= first);
+
+    let invalid = [Value::I32(99), Value::I64(1), Value::I32(OUT_ADDR as i32)];
+    assert_eq!(
+        call(&mut wasi, "clock_time_get", &invalid, &mut memory),
+        Errno::Inval as i32
+    );
+}
+
+#[test]
+fn test_unsupported() {
+    let mut wasi = WasiDispatcher::default();
+    let mut memory = vec![0; 0x1000];
+
+    let raise = [Value::I32(2)];
+    assert_eq!(
+        call(&mut wasi, "proc_raise", &raise, &mut memory),
+        Errno::Nosys as i32
+    );
+
+    let shutdown = [Value::I32(0), Value::I32(0)];
+    assert_eq!(
+        call(&mut wasi, "sock_shutdown", &shutdown, &mut memory),
+        Errno::Nosys as i32
+    );
+}
@@ -1,7 +1,9 @@
 use rand::prelude::*;
 use roc_wasm_module::Value;
-use std::io::{self, Read, StderrLock, StdoutLock, Write};
-use std::process::exit;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom, StderrLock, StdoutLock, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub const MODULE_NAME: &str = "wasi_snapshot_preview1";
 
@@ -9,6 +11,10 @@ pub struct WasiDispatcher<'a> {
     pub args: &'a [&'a [u8]],
     pub rng: ThreadRng,
     pub files: Vec<WasiFile>,
+    /// Set when the app calls `proc_exit`. The interpreter stops running the app at that point.
+    pub exit_code: Option<i32>,
+    /// Reference point for the monotonic and CPU-time clocks
+    start_time: Instant,
 }
 
 impl Default for WasiDispatcher<'_> {
@@ -22,14 +28,136 @@ pub enum WasiFile {
     WriteOnly(Vec<u8>),
     ReadWrite(Vec<u8>),
     HostSystemFile,
+    /// A file on the host, opened by the app with `path_open`
+    HostFile(fs::File),
+    /// A host directory that the app can open paths relative to
+    Directory(WasiDir),
+    /// A file descriptor that the app has closed. Its slot can be reused.
+    Closed,
+}
+
+/// A directory on the host filesystem, sandboxed inside a preopened directory.
+/// The app can never use it to reach anything outside of `sandbox_root`.
+pub struct WasiDir {
+    /// Canonical host path of this directory
+    host_path: PathBuf,
+    /// Canonical host path of the preopened directory this one belongs to
+    sandbox_root: PathBuf,
+    /// The name the app knows this directory by, if it was preopened
+    preopen_name: Option<String>,
+}
+
+impl WasiDir {
+    /// Resolve a path from the app, relative to this directory, into a host path
+    fn resolve(&self, path: &str, follow_symlinks: bool) -> Result<PathBuf, Errno> {
+        self.resolve_from(&self.host_path, path, follow_symlinks)
+    }
+
+    /// Resolve a path from the app, relative to `base`, which must be inside the sandbox
+    fn resolve_from(
+        &self,
+        base: &Path,
+        path: &str,
+        follow_symlinks: bool,
+    ) -> Result<PathBuf, Errno> {
+        let mut resolved = base.to_path_buf();
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(name) => resolved.push(name),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if resolved == self.sandbox_root {
+                        return Err(Errno::Notcapable);
+                    }
+                    resolved.pop();
+                }
+                Component::RootDir | Component::Prefix(_) => return Err(Errno::Notcapable),
+            }
+        }
+
+        // The path is lexically inside the sandbox, but symlinks could still lead outside it
+        let must_be_inside = if follow_symlinks {
+            Some(resolved.as_path())
+        } else if resolved != self.sandbox_root {
+            resolved.parent()
+        } else {
+            None
+        };
+        if let Some(path) = must_be_inside {
+            self.check_inside(path)?;
+        }
+
+        Ok(resolved)
+    }
+
+    /// Check that a host path really is inside the sandbox once symlinks are followed.
+    /// The path may not exist yet, for example if the app is about to create it, so this
+    /// checks the nearest ancestor which does exist. Anything that can't be checked is refused.
+    fn check_inside(&self, path: &Path) -> Result<(), Errno> {
+        // `symlink_metadata` finds dangling symlinks too, which `canonicalize` then refuses,
+        // rather than skipping past them to an ancestor which is inside the sandbox.
+        let existing = path
+            .ancestors()
+            .find(|ancestor| fs::symlink_metadata(ancestor).is_ok())
+            .ok_or(Errno::Notcapable)?;
+
+        match existing.canonicalize() {
+            Ok(real_path) if real_path.starts_with(&self.sandbox_root) => Ok(()),
+            _ => Err(Errno::Notcapable),
+        }
+    }
 }
 
 enum WriteLock<'a> {
     StdOut(StdoutLock<'a>),
     Stderr(StderrLock<'a>),
     RegularFile(&'a mut Vec<u8>),
+    HostFile(&'a mut fs::File),
 }
 
+// https://github.com/WebAssembly/WASI/blob/main/legacy/preview1/witx/typenames.witx
+const CLOCK_REALTIME: i32 = 0;
+const CLOCK_MONOTONIC: i32 = 1;
+const CLOCK_PROCESS_CPUTIME: i32 = 2;
+const CLOCK_THREAD_CPUTIME: i32 = 3;
+
+const FILETYPE_UNKNOWN: u8 = 0;
+const FILETYPE_CHARACTER_DEVICE: u8 = 2;
+const FILETYPE_DIRECTORY: u8 = 3;
+const FILETYPE_REGULAR_FILE: u8 = 4;
+const FILETYPE_SYMBOLIC_LINK: u8 = 7;
+
+const RIGHTS_FD_READ: u64 = 1 << 1;
+const RIGHTS_FD_WRITE: u64 = 1 << 6;
+const RIGHTS_ALL: u64 = (1 << 30) - 1;
+
+const LOOKUPFLAGS_SYMLINK_FOLLOW: i32 = 1;
+
+const OFLAGS_CREAT: i32 = 1 << 0;
+const OFLAGS_DIRECTORY: i32 = 1 << 1;
+const OFLAGS_EXCL: i32 = 1 << 2;
+const OFLAGS_TRUNC: i32 = 1 << 3;
+
+const FDFLAGS_APPEND: i32 = 1 << 0;
+
+const FSTFLAGS_ATIM: i32 = 1 << 0;
+const FSTFLAGS_ATIM_NOW: i32 = 1 << 1;
+const FSTFLAGS_MTIM: i32 = 1 << 2;
+const FSTFLAGS_MTIM_NOW: i32 = 1 << 3;
+
+const WHENCE_SET: i32 = 0;
+const WHENCE_CUR: i32 = 1;
+const WHENCE_END: i32 = 2;
+
+const EVENTTYPE_CLOCK: u8 = 0;
+const SUBCLOCKFLAGS_ABSTIME: u16 = 1;
+
+const FDSTAT_SIZE: usize = 24;
+const FILESTAT_SIZE: usize = 64;
+const DIRENT_SIZE: usize = 24;
+const SUBSCRIPTION_SIZE: usize = 48;
+const EVENT_SIZE: usize = 32;
+
 /// Implementation of WASI syscalls
 /// References for other engines:
 /// https://github.com/wasmerio/wasmer/blob/ef8d2f651ed29b4b06fdc2070eb8189922c54d82/lib/wasi/src/syscalls/mod.rs
@@ -44,6 +172,81 @@ impl<'a> WasiDispatcher<'a> {
                 WasiFile::HostSystemFile,
                 WasiFile::HostSystemFile,
             ],
+            exit_code: None,
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Give the app access to a host directory, which it will see as `guest_path`.
+    /// Everything the app opens through this directory stays inside it.
+    pub fn preopen_dir(&mut self, host_path: &Path, guest_path: &str) -> io::Result<()> {
+        let host_path = host_path.canonicalize()?;
+        if !host_path.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a directory", host_path.display()),
+            ));
+        }
+        self.add_file(WasiFile::Directory(WasiDir {
+            sandbox_root: host_path.clone(),
+            host_path,
+            preopen_name: Some(guest_path.to_string()),
+        }));
+        Ok(())
+    }
+
+    /// Store a newly-opened file, reusing the lowest closed file descriptor if there is one
+    fn add_file(&mut self, file: WasiFile) -> usize {
+        match self
+            .files
+            .iter()
+            .position(|f| matches!(f, WasiFile::Closed))
+        {
+            Some(fd) => {
+                self.files[fd] = file;
+                fd
+            }
+            None => {
+                self.files.push(file);
+                self.files.len() - 1
+            }
+        }
+    }
+
+    fn get_file(&mut self, fd: usize) -> Result<&mut WasiFile, Errno> {
+        match self.files.get_mut(fd) {
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(file) => Ok(file),
+        }
+    }
+
+    fn get_host_file(&mut self, fd: usize) -> Result<&mut fs::File, Errno> {
+        match self.get_file(fd)? {
+            WasiFile::HostFile(file) => Ok(file),
+            WasiFile::Directory(_) => Err(Errno::Isdir),
+            _ => Err(Errno::Spipe),
+        }
+    }
+
+    fn get_dir(&self, fd: usize) -> Result<&WasiDir, Errno> {
+        match self.files.get(fd) {
+            Some(WasiFile::Directory(dir)) => Ok(dir),
+            None | Some(WasiFile::Closed) => Err(Errno::Badf),
+            Some(_) => Err(Errno::Notdir),
+        }
+    }
+
+    fn clock_time(&self, clock_id: i32) -> Result<u64, Errno> {
+        match clock_id {
+            CLOCK_REALTIME => Ok(SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64),
+            // We have no access to real CPU time, but wall time since startup is an upper bound
+            CLOCK_MONOTONIC | CLOCK_PROCESS_CPUTIME | CLOCK_THREAD_CPUTIME => {
+                Ok(self.start_time.elapsed().as_nanos() as u64)
+            }
+            _ => Err(Errno::Inval),
         }
     }
 
@@ -107,13 +310,51 @@ impl<'a> WasiDispatcher<'a> {
 
                 success_code
             }
-            "clock_res_get" => success_code, // this dummy implementation seems to be good enough for some functions
-            "clock_time_get" => success_code,
-            "fd_advise" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_allocate" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_close" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_datasync" => todo!("WASI {}({:?})", function_name, arguments),
+            "clock_res_get" => {
+                let clock_id = arguments[0].expect_i32().unwrap();
+                // Out param: clock resolution in nanoseconds
+                let ptr_resolution = arguments[1].expect_i32().unwrap() as usize;
+                if let Err(e) = self.clock_time(clock_id) {
+                    return errno_result(Err(e));
+                }
+                // Rust's clocks report nanoseconds
+                write_u64(memory, ptr_resolution, 1);
+                success_code
+            }
+            "clock_time_get" => {
+                let clock_id = arguments[0].expect_i32().unwrap();
+                // The maximum lag that the app will accept. We always do our best anyway.
+                let _precision = arguments[1].expect_i64().unwrap();
+                // Out param: time in nanoseconds
+                let ptr_time = arguments[2].expect_i32().unwrap() as usize;
+                match self.clock_time(clock_id) {
+                    Ok(time) => {
+                        write_u64(memory, ptr_time, time);
+                        success_code
+                    }
+                    Err(e) => errno_result(Err(e)),
+                }
+            }
+            "fd_advise" => {
+                // Advice is only a hint for performance, so we can ignore it
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                errno_result(self.get_file(fd).map(|_| ()))
+            }
+            "fd_allocate" => errno_result(self.fd_allocate(arguments)),
+            "fd_close" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                errno_result(self.get_file(fd).map(|file| *file = WasiFile::Closed))
+            }
+            "fd_datasync" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                errno_result(
+                    self.get_host_file(fd)
+                        .and_then(|file| Ok(file.sync_data()?)),
+                )
+            }
             "fd_fdstat_get" => {
+                use WasiFile::*;
+
                 // (i32, i32) -> i32
 
                 // file descriptor
@@ -121,35 +362,63 @@ impl<'a> WasiDispatcher<'a> {
                 // ptr to a wasi_fdstat_t
                 let stat_mut_ptr = arguments[1].expect_i32().unwrap() as usize;
 
-                match fd {
-                    1 => {
-                        // Tell WASI that stdout is a tty (no seek or tell)
-                        // https://github.com/WebAssembly/wasi-libc/blob/659ff414560721b1660a19685110e484a081c3d4/libc-bottom-half/sources/isatty.c
-                        // *Not* a tty if:
-                        //     (statbuf.fs_filetype != __WASI_FILETYPE_CHARACTER_DEVICE ||
-                        //         (statbuf.fs_rights_base & (__WASI_RIGHTS_FD_SEEK | __WASI_RIGHTS_FD_TELL)) != 0)
-                        // So it's sufficient to set:
-                        //     .fs_filetype = __WASI_FILETYPE_CHARACTER_DEVICE
-                        //     .fs_rights_base = 0
-
-                        const WASI_FILETYPE_CHARACTER_DEVICE: u8 = 2;
-                        memory[stat_mut_ptr] = WASI_FILETYPE_CHARACTER_DEVICE;
-
-                        for b in memory[stat_mut_ptr + 1..stat_mut_ptr + 24].iter_mut() {
-                            *b = 0;
-                        }
+                let (filetype, rights) = match self.get_file(fd) {
+                    // Tell WASI that stdio streams are ttys (no seek or tell)
+                    // https://github.com/WebAssembly/wasi-libc/blob/659ff414560721b1660a19685110e484a081c3d4/libc-bottom-half/sources/isatty.c
+                    // *Not* a tty if:
+                    //     (statbuf.fs_filetype != __WASI_FILETYPE_CHARACTER_DEVICE ||
+                    //         (statbuf.fs_rights_base & (__WASI_RIGHTS_FD_SEEK | __WASI_RIGHTS_FD_TELL)) != 0)
+                    // So it's sufficient to set:
+                    //     .fs_filetype = __WASI_FILETYPE_CHARACTER_DEVICE
+                    //     .fs_rights_base = 0
+                    Ok(HostSystemFile) => (FILETYPE_CHARACTER_DEVICE, 0),
+                    Ok(ReadOnly(_) | WriteOnly(_) | ReadWrite(_) | HostFile(_)) => {
+                        (FILETYPE_REGULAR_FILE, RIGHTS_ALL)
                     }
-                    _ => todo!("WASI {}({:?})", function_name, arguments),
-                }
+                    // wasi-libc masks the rights of files opened in a directory
+                    // with the directory's inheriting rights, so they must be set too.
+                    Ok(Directory(_)) => (FILETYPE_DIRECTORY, RIGHTS_ALL),
+                    Ok(Closed) | Err(_) => return errno_result(Err(Errno::Badf)),
+                };
+
+                memory[stat_mut_ptr..][..FDSTAT_SIZE].fill(0);
+                memory[stat_mut_ptr] = filetype;
+                write_u64(memory, stat_mut_ptr + 8, rights); // fs_rights_base
+                write_u64(memory, stat_mut_ptr + 16, rights); // fs_rights_inheriting
 
                 success_code
             }
-            "fd_fdstat_set_flags" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_fdstat_set_rights" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_size" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_pread" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_fdstat_set_flags" => {
+                // We don't support non-blocking or synchronized IO, and we can't
+                // switch append mode on an open file, so ignore the flags.
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                errno_result(self.get_file(fd).map(|_| ()))
+            }
+            "fd_fdstat_set_rights" => {
+                // We don't restrict rights, so there's nothing to drop
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                errno_result(self.get_file(fd).map(|_| ()))
+            }
+            "fd_filestat_get" => errno_result(self.fd_filestat_get(arguments, memory)),
+            "fd_filestat_set_size" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let size = arguments[1].expect_i64().unwrap() as u64;
+                errno_result(
+                    self.get_host_file(fd)
+                        .and_then(|file| Ok(file.set_len(size)?)),
+                )
+            }
+            "fd_filestat_set_times" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let atim = arguments[1].expect_i64().unwrap() as u64;
+                let mtim = arguments[2].expect_i64().unwrap() as u64;
+                let fst_flags = arguments[3].expect_i32().unwrap();
+                errno_result(
+                    self.get_host_file(fd)
+                        .and_then(|file| set_times(file, atim, mtim, fst_flags)),
+                )
+            }
+            "fd_pread" => errno_result(self.fd_pread(arguments, memory)),
             "fd_prestat_get" => {
                 // The preopened file descriptor to query
                 let fd = arguments[0].expect_i32().unwrap() as usize;
@@ -157,19 +426,41 @@ impl<'a> WasiDispatcher<'a> {
                 //  preopen type: 4 bytes, where 0=dir is the only one supported, it seems
                 //  preopen name length: 4 bytes
                 let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
-                memory[ptr_buf..][..8].copy_from_slice(&0u64.to_le_bytes());
-                if fd < self.files.len() {
-                    success_code
-                } else {
-                    println!("WASI warning: file descriptor {fd} does not exist");
-                    Some(Value::I32(Errno::Badf as i32))
+
+                // wasi-libc queries file descriptors from 3 upwards, until it gets `Badf`
+                match self.files.get(fd) {
+                    Some(WasiFile::Directory(WasiDir {
+                        preopen_name: Some(name),
+                        ..
+                    })) => {
+                        write_u32(memory, ptr_buf, 0);
+                        write_u32(memory, ptr_buf + 4, name.len() as u32);
+                        success_code
+                    }
+                    _ => Some(Value::I32(Errno::Badf as i32)),
                 }
             }
             "fd_prestat_dir_name" => {
-                // We're not giving names to any of our files so just return success
-                success_code
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                // Buffer to write the name into. It's not zero-terminated.
+                let ptr_path = arguments[1].expect_i32().unwrap() as usize;
+                let path_len = arguments[2].expect_i32().unwrap() as usize;
+
+                match self.files.get(fd) {
+                    Some(WasiFile::Directory(WasiDir {
+                        preopen_name: Some(name),
+                        ..
+                    })) => {
+                        if path_len < name.len() {
+                            return Some(Value::I32(Errno::Nametoolong as i32));
+                        }
+                        memory[ptr_path..][..name.len()].copy_from_slice(name.as_bytes());
+                        success_code
+                    }
+                    _ => Some(Value::I32(Errno::Badf as i32)),
+                }
             }
-            "fd_pwrite" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_pwrite" => errno_result(self.fd_pwrite(arguments, memory)),
             "fd_read" => {
                 use WasiFile::*;
 
@@ -178,22 +469,14 @@ impl<'a> WasiDispatcher<'a> {
                 // Array of IO vectors
                 let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
                 // Length of array
-                let iovs_len = arguments[2].expect_i32().unwrap();
+                let iovs_len = arguments[2].expect_i32().unwrap() as usize;
                 // Out param: number of bytes read
                 let ptr_nread = arguments[3].expect_i32().unwrap() as usize;
 
-                // https://man7.org/linux/man-pages/man2/readv.2.html
-                // struct iovec {
-                //     void  *iov_base;    /* Starting address */
-                //     size_t iov_len;     /* Number of bytes to transfer */
-                // };
-
                 let mut n_read: usize = 0;
-                match self.files.get(fd) {
+                match self.files.get_mut(fd) {
                     Some(ReadOnly(content) | ReadWrite(content)) => {
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
+                        for (iov_base, iov_len) in read_iovecs(memory, ptr_iovs, iovs_len) {
                             let remaining = content.len() - n_read;
                             let len = remaining.min(iov_len);
                             if len == 0 {
@@ -204,31 +487,66 @@ impl<'a> WasiDispatcher<'a> {
                         }
                     }
                     Some(HostSystemFile) if fd == 0 => {
-                        let mut stdin = io::stdin();
-                        for _ in 0..iovs_len {
-                            let iov_base = read_u32(memory, ptr_iovs) as usize;
-                            let iov_len = read_i32(memory, ptr_iovs + 4) as usize;
-                            match stdin.read(&mut memory[iov_base..][..iov_len]) {
-                                Ok(n) => {
-                                    n_read += n;
-                                }
-                                Err(_) => {
-                                    break;
-                                }
-                            }
+                        match read_into_iovecs(&mut io::stdin(), memory, ptr_iovs, iovs_len) {
+                            Ok(n) => n_read = n,
+                            Err(e) => return errno_result(Err(e)),
+                        }
+                    }
+                    Some(HostFile(file)) => {
+                        match read_into_iovecs(file, memory, ptr_iovs, iovs_len) {
+                            Ok(n) => n_read = n,
+                            Err(e) => return errno_result(Err(e)),
                         }
                     }
+                    Some(Directory(_)) => return Some(Value::I32(Errno::Isdir as i32)),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
                 memory[ptr_nread..][..4].copy_from_slice(&(n_read as u32).to_le_bytes());
                 success_code
             }
-            "fd_readdir" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_renumber" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_seek" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_sync" => todo!("WASI {}({:?})", function_name, arguments),
-            "fd_tell" => todo!("WASI {}({:?})", function_name, arguments),
+            "fd_readdir" => errno_result(self.fd_readdir(arguments, memory)),
+            "fd_renumber" => errno_result(self.fd_renumber(arguments)),
+            "fd_seek" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let offset = arguments[1].expect_i64().unwrap();
+                let whence = arguments[2].expect_i32().unwrap();
+                // Out param: the new offset from the start of the file
+                let ptr_newoffset = arguments[3].expect_i32().unwrap() as usize;
+
+                let pos = match whence {
+                    WHENCE_SET => SeekFrom::Start(offset as u64),
+                    WHENCE_CUR => SeekFrom::Current(offset),
+                    WHENCE_END => SeekFrom::End(offset),
+                    _ => return Some(Value::I32(Errno::Inval as i32)),
+                };
+                match self.get_host_file(fd).and_then(|file| Ok(file.seek(pos)?)) {
+                    Ok(new_offset) => {
+                        write_u64(memory, ptr_newoffset, new_offset);
+                        success_code
+                    }
+                    Err(e) => errno_result(Err(e)),
+                }
+            }
+            "fd_sync" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                errno_result(self.get_host_file(fd).and_then(|file| Ok(file.sync_all()?)))
+            }
+            "fd_tell" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                // Out param: the current offset from the start of the file
+                let ptr_offset = arguments[1].expect_i32().unwrap() as usize;
+                match self
+                    .get_host_file(fd)
+                    .and_then(|file| Ok(file.stream_position()?))
+                {
+                    Ok(offset) => {
+                        write_u64(memory, ptr_offset, offset);
+                        success_code
+                    }
+                    Err(e) => errno_result(Err(e)),
+                }
+            }
             "fd_write" => {
                 use WasiFile::*;
 
@@ -252,6 +570,7 @@ impl<'a> WasiDispatcher<'a> {
                     Some(WriteOnly(content) | ReadWrite(content)) => {
                         WriteLock::RegularFile(content)
                     }
+                    Some(HostFile(file)) => WriteLock::HostFile(file),
                     _ => return Some(Value::I32(Errno::Badf as i32)),
                 };
 
@@ -280,6 +599,7 @@ impl<'a> WasiDispatcher<'a> {
                         WriteLock::StdOut(stdout) => stdout.write_all(bytes),
                         WriteLock::Stderr(stderr) => stderr.write_all(bytes),
                         WriteLock::RegularFile(content) => content.write_all(bytes),
+                        WriteLock::HostFile(file) => file.write_all(bytes),
                     };
                     if write_result.is_err() {
                         break;
@@ -300,39 +620,544 @@ impl<'a> WasiDispatcher<'a> {
                     Err(_) => Some(Value::I32(Errno::Io as i32)),
                 }
             }
-            "path_create_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_get" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_filestat_set_times" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_link" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_open" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_readlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_remove_directory" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_rename" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_symlink" => todo!("WASI {}({:?})", function_name, arguments),
-            "path_unlink_file" => todo!("WASI {}({:?})", function_name, arguments),
-            "poll_oneoff" => todo!("WASI {}({:?})", function_name, arguments),
+            "path_create_directory" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let path = read_path(memory, &arguments[1..3]);
+                errno_result(
+                    path.and_then(|path| self.get_dir(fd)?.resolve(path, false))
+                        .and_then(|host_path| Ok(fs::create_dir(host_path)?)),
+                )
+            }
+            "path_filestat_get" => errno_result(self.path_filestat_get(arguments, memory)),
+            "path_filestat_set_times" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let follow_symlinks =
+                    arguments[1].expect_i32().unwrap() & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+                let path = read_path(memory, &arguments[2..4]);
+                let atim = arguments[4].expect_i64().unwrap() as u64;
+                let mtim = arguments[5].expect_i64().unwrap() as u64;
+                let fst_flags = arguments[6].expect_i32().unwrap();
+                errno_result(
+                    path.and_then(|path| self.get_dir(fd)?.resolve(path, follow_symlinks))
+                        .and_then(|host_path| {
+                            let file = fs::File::open(host_path)?;
+                            set_times(&file, atim, mtim, fst_flags)
+                        }),
+                )
+            }
+            "path_link" => errno_result(self.path_link(arguments, memory)),
+            "path_open" => errno_result(self.path_open(arguments, memory)),
+            "path_readlink" => errno_result(self.path_readlink(arguments, memory)),
+            "path_remove_directory" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let path = read_path(memory, &arguments[1..3]);
+                errno_result(
+                    path.and_then(|path| self.get_dir(fd)?.resolve(path, false))
+                        .and_then(|host_path| Ok(fs::remove_dir(host_path)?)),
+                )
+            }
+            "path_rename" => errno_result(self.path_rename(arguments, memory)),
+            "path_symlink" => errno_result(self.path_symlink(arguments, memory)),
+            "path_unlink_file" => {
+                let fd = arguments[0].expect_i32().unwrap() as usize;
+                let path = read_path(memory, &arguments[1..3]);
+                errno_result(
+                    path.and_then(|path| self.get_dir(fd)?.resolve(path, false))
+                        .and_then(|host_path| Ok(fs::remove_file(host_path)?)),
+                )
+            }
+            "poll_oneoff" => errno_result(self.poll_oneoff(arguments, memory)),
             "proc_exit" => {
-                let exit_code = arguments[0].expect_i32().unwrap();
-                exit(exit_code);
+                self.exit_code = Some(arguments[0].expect_i32().unwrap());
+                None
+            }
+            // We don't emulate signals or sockets
+            "proc_raise" => Some(Value::I32(Errno::Nosys as i32)),
+            "sched_yield" => {
+                std::thread::yield_now();
+                success_code
             }
-            "proc_raise" => todo!("WASI {}({:?})", function_name, arguments),
-            "sched_yield" => todo!("WASI {}({:?})", function_name, arguments),
             "random_get" => {
                 // A pointer to a buffer where the random bytes will be written
                 let ptr_buf = arguments[0].expect_i32().unwrap() as usize;
                 // The number of bytes that will be written
                 let buf_len = arguments[1].expect_i32().unwrap() as usize;
-                for i in 0..buf_len {
-                    memory[ptr_buf + i] = self.rng.gen();
-                }
+                self.rng.fill_bytes(&mut memory[ptr_buf..][..buf_len]);
                 success_code
             }
-            "sock_recv" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_send" => todo!("WASI {}({:?})", function_name, arguments),
-            "sock_shutdown" => todo!("WASI {}({:?})", function_name, arguments),
+            "sock_recv" | "sock_send" | "sock_shutdown" => Some(Value::I32(Errno::Nosys as i32)),
             _ => panic!("Unknown WASI function {function_name}({arguments:?})"),
         }
     }
+
+    fn fd_allocate(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let fd = arguments[0].expect_i32().unwrap() as usize;
+        let offset = arguments[1].expect_i64().unwrap() as u64;
+        let len = arguments[2].expect_i64().unwrap() as u64;
+
+        let file = self.get_host_file(fd)?;
+        let required_len = offset.checked_add(len).ok_or(Errno::Fbig)?;
+        if file.metadata()?.len() < required_len {
+            file.set_len(required_len)?;
+        }
+        Ok(())
+    }
+
+    fn fd_renumber(&mut self, arguments: &[Value]) -> Result<(), Errno> {
+        let from = arguments[0].expect_i32().unwrap() as usize;
+        let to = arguments[1].expect_i32().unwrap() as usize;
+
+        self.get_file(from)?;
+        self.get_file(to)?;
+        if from != to {
+            self.files[to] = std::mem::replace(&mut self.files[from], WasiFile::Closed);
+        }
+        Ok(())
+    }
+
+    fn fd_filestat_get(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arguments[0].expect_i32().unwrap() as usize;
+        // Out param: the file's attributes
+        let ptr_filestat = arguments[1].expect_i32().unwrap() as usize;
+
+        let metadata = match self.get_file(fd)? {
+            WasiFile::HostFile(file) => file.metadata()?,
+            WasiFile::Directory(dir) => fs::metadata(&dir.host_path)?,
+            WasiFile::ReadOnly(content)
+            | WasiFile::WriteOnly(content)
+            | WasiFile::ReadWrite(content) => {
+                memory[ptr_filestat..][..FILESTAT_SIZE].fill(0);
+                memory[ptr_filestat + 16] = FILETYPE_REGULAR_FILE;
+                write_u64(memory, ptr_filestat + 32, content.len() as u64);
+                return Ok(());
+            }
+            WasiFile::HostSystemFile => {
+                memory[ptr_filestat..][..FILESTAT_SIZE].fill(0);
+                memory[ptr_filestat + 16] = FILETYPE_CHARACTER_DEVICE;
+                return Ok(());
+            }
+            WasiFile::Closed => return Err(Errno::Badf),
+        };
+        write_filestat(memory, ptr_filestat, &metadata);
+        Ok(())
+    }
+
+    fn fd_pread(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arguments[0].expect_i32().unwrap() as usize;
+        let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
+        let iovs_len = arguments[2].expect_i32().unwrap() as usize;
+        let offset = arguments[3].expect_i64().unwrap() as u64;
+        // Out param: number of bytes read
+        let ptr_nread = arguments[4].expect_i32().unwrap() as usize;
+
+        // Positional reads must not move the file cursor, so put it back afterwards
+        let file = self.get_host_file(fd)?;
+        let cursor = file.stream_position()?;
+        file.seek(SeekFrom::Start(offset))?;
+        let result = read_into_iovecs(file, memory, ptr_iovs, iovs_len);
+        file.seek(SeekFrom::Start(cursor))?;
+
+        write_u32(memory, ptr_nread, result? as u32);
+        Ok(())
+    }
+
+    fn fd_pwrite(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arguments[0].expect_i32().unwrap() as usize;
+        let ptr_iovs = arguments[1].expect_i32().unwrap() as usize;
+        let iovs_len = arguments[2].expect_i32().unwrap() as usize;
+        let offset = arguments[3].expect_i64().unwrap() as u64;
+        // Out param: number of bytes written
+        let ptr_nwritten = arguments[4].expect_i32().unwrap() as usize;
+
+        // Positional writes must not move the file cursor, so put it back afterwards
+        let file = self.get_host_file(fd)?;
+        let cursor = file.stream_position()?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut n_written = 0;
+        let mut result = Ok(());
+        for (iov_base, iov_len) in read_iovecs(memory, ptr_iovs, iovs_len) {
+            result = file.write_all(&memory[iov_base..][..iov_len]);
+            if result.is_err() {
+                break;
+            }
+            n_written += iov_len;
+        }
+        file.seek(SeekFrom::Start(cursor))?;
+        result?;
+
+        write_u32(memory, ptr_nwritten, n_written as u32);
+        Ok(())
+    }
+
+    fn fd_readdir(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arguments[0].expect_i32().unwrap() as usize;
+        // Buffer to write directory entries into
+        let ptr_buf = arguments[1].expect_i32().unwrap() as usize;
+        let buf_len = arguments[2].expect_i32().unwrap() as usize;
+        // Index of the first entry to write
+        let cookie = arguments[3].expect_i64().unwrap() as u64;
+        // Out param: number of bytes written. If the buffer is full, the app will call us again.
+        let ptr_bufused = arguments[4].expect_i32().unwrap() as usize;
+
+        let dir = self.get_dir(fd)?;
+        let mut entries = vec![
+            (".".into(), FILETYPE_DIRECTORY, 0),
+            ("..".into(), FILETYPE_DIRECTORY, 0),
+        ];
+        let mut children = fs::read_dir(&dir.host_path)?
+            .map(|entry| {
+                let entry = entry?;
+                let filetype = filetype_code(entry.file_type()?);
+                Ok((entry.file_name(), filetype, inode(&entry.metadata()?)))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        // Sort so that cookies are stable between calls
+        children.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.extend(children);
+
+        // Entries are written in full, except the last one, which can be cut off
+        // https://github.com/WebAssembly/WASI/blob/main/legacy/preview1/docs.md#fd_readdir
+        let mut bufused = 0;
+        for (index, (name, filetype, ino)) in entries.iter().enumerate().skip(cookie as usize) {
+            let name = name.to_string_lossy();
+            let mut dirent = [0; DIRENT_SIZE];
+            dirent[0..8].copy_from_slice(&(index as u64 + 1).to_le_bytes()); // d_next
+            dirent[8..16].copy_from_slice(&ino.to_le_bytes()); // d_ino
+            dirent[16..20].copy_from_slice(&(name.len() as u32).to_le_bytes()); // d_namlen
+            dirent[20] = *filetype; // d_type
+
+            for bytes in [&dirent[..], name.as_bytes()] {
+                let len = bytes.len().min(buf_len - bufused);
+                memory[ptr_buf + bufused..][..len].copy_from_slice(&bytes[..len]);
+                bufused += len;
+            }
+            if bufused == buf_len {
+                break;
+            }
+        }
+
+        write_u32(memory, ptr_bufused, bufused as u32);
+        Ok(())
+    }
+
+    fn path_filestat_get(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arguments[0].expect_i32().unwrap() as usize;
+        let follow_symlinks = arguments[1].expect_i32().unwrap() & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+        let path = read_path(memory, &arguments[2..4])?;
+        // Out param: the file's attributes
+        let ptr_filestat = arguments[4].expect_i32().unwrap() as usize;
+
+        let host_path = self.get_dir(fd)?.resolve(path, follow_symlinks)?;
+        let metadata = if follow_symlinks {
+            fs::metadata(host_path)?
+        } else {
+            fs::symlink_metadata(host_path)?
+        };
+        write_filestat(memory, ptr_filestat, &metadata);
+        Ok(())
+    }
+
+    fn path_link(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let old_fd = arguments[0].expect_i32().unwrap() as usize;
+        let follow_symlinks = arguments[1].expect_i32().unwrap() & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+        let old_path = read_path(memory, &arguments[2..4])?;
+        let new_fd = arguments[4].expect_i32().unwrap() as usize;
+        let new_path = read_path(memory, &arguments[5..7])?;
+
+        let old_host_path = self.get_dir(old_fd)?.resolve(old_path, follow_symlinks)?;
+        let new_host_path = self.get_dir(new_fd)?.resolve(new_path, false)?;
+        fs::hard_link(old_host_path, new_host_path)?;
+        Ok(())
+    }
+
+    fn path_open(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let dir_fd = arguments[0].expect_i32().unwrap() as usize;
+        let follow_symlinks = arguments[1].expect_i32().unwrap() & LOOKUPFLAGS_SYMLINK_FOLLOW != 0;
+        let path = read_path(memory, &arguments[2..4])?;
+        let oflags = arguments[4].expect_i32().unwrap();
+        let rights = arguments[5].expect_i64().unwrap() as u64;
+        let _rights_inheriting = arguments[6].expect_i64().unwrap();
+        let fdflags = arguments[7].expect_i32().unwrap();
+        // Out param: the new file descriptor
+        let ptr_fd = arguments[8].expect_i32().unwrap() as usize;
+
+        let dir = self.get_dir(dir_fd)?;
+        let host_path = dir.resolve(path, follow_symlinks)?;
+
+        let file = if oflags & OFLAGS_DIRECTORY != 0 || host_path.is_dir() {
+            if !host_path.is_dir() {
+                return Err(if host_path.exists() {
+                    Errno::Notdir
+                } else {
+                    Errno::Noent
+                });
+            }
+            WasiFile::Directory(WasiDir {
+                host_path: host_path.canonicalize()?,
+                sandbox_root: dir.sandbox_root.clone(),
+                preopen_name: None,
+            })
+        } else {
+            let append = fdflags & FDFLAGS_APPEND != 0;
+            let write = rights & RIGHTS_FD_WRITE != 0 || append;
+            let read = rights & RIGHTS_FD_READ != 0 || !write;
+            let file = fs::OpenOptions::new()
+                .read(read)
+                .write(write)
+                .append(append)
+                .truncate(oflags & OFLAGS_TRUNC != 0)
+                .create(oflags & OFLAGS_CREAT != 0)
+                .create_new(oflags & OFLAGS_CREAT != 0 && oflags & OFLAGS_EXCL != 0)
+                .open(host_path)?;
+            WasiFile::HostFile(file)
+        };
+
+        let fd = self.add_file(file);
+        write_u32(memory, ptr_fd, fd as u32);
+        Ok(())
+    }
+
+    fn path_readlink(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let fd = arguments[0].expect_i32().unwrap() as usize;
+        let path = read_path(memory, &arguments[1..3])?;
+        // Buffer for the link contents. It's not zero-terminated, and may be truncated.
+        let ptr_buf = arguments[3].expect_i32().unwrap() as usize;
+        let buf_len = arguments[4].expect_i32().unwrap() as usize;
+        // Out param: number of bytes written
+        let ptr_bufused = arguments[5].expect_i32().unwrap() as usize;
+
+        let host_path = self.get_dir(fd)?.resolve(path, false)?;
+        let target = fs::read_link(host_path)?;
+        let target = target.to_string_lossy();
+        let len = target.len().min(buf_len);
+        memory[ptr_buf..][..len].copy_from_slice(&target.as_bytes()[..len]);
+        write_u32(memory, ptr_bufused, len as u32);
+        Ok(())
+    }
+
+    fn path_rename(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        let old_fd = arguments[0].expect_i32().unwrap() as usize;
+        let old_path = read_path(memory, &arguments[1..3])?;
+        let new_fd = arguments[3].expect_i32().unwrap() as usize;
+        let new_path = read_path(memory, &arguments[4..6])?;
+
+        let old_host_path = self.get_dir(old_fd)?.resolve(old_path, false)?;
+        let new_host_path = self.get_dir(new_fd)?.resolve(new_path, false)?;
+        fs::rename(old_host_path, new_host_path)?;
+        Ok(())
+    }
+
+    fn path_symlink(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        // The contents of the link, which may be relative to the directory containing it
+        let old_path = read_path(memory, &arguments[0..2])?;
+        let fd = arguments[2].expect_i32().unwrap() as usize;
+        let new_path = read_path(memory, &arguments[3..5])?;
+
+        let dir = self.get_dir(fd)?;
+        let new_host_path = dir.resolve(new_path, false)?;
+
+        // Don't let the app make links it could use to reach outside the sandbox later.
+        // Relative targets are resolved from the directory containing the link.
+        let link_dir = new_host_path.parent().ok_or(Errno::Notcapable)?;
+        dir.resolve_from(link_dir, old_path, true)?;
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(old_path, new_host_path)?;
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (old_path, new_host_path);
+            Err(Errno::Nosys)
+        }
+    }
+
+    fn poll_oneoff(&mut self, arguments: &[Value], memory: &mut [u8]) -> Result<(), Errno> {
+        // Array of subscriptions to wait for
+        let ptr_in = arguments[0].expect_i32().unwrap() as usize;
+        // Array of events that happened. It has room for one per subscription.
+        let ptr_out = arguments[1].expect_i32().unwrap() as usize;
+        let nsubscriptions = arguments[2].expect_i32().unwrap() as usize;
+        // Out param: number of events written
+        let ptr_nevents = arguments[3].expect_i32().unwrap() as usize;
+
+        if nsubscriptions == 0 {
+            return Err(Errno::Inval);
+        }
+
+        // Our files never block, so any fd_read or fd_write subscription is ready immediately.
+        // If there are only clock subscriptions, sleep until the earliest one times out.
+        let subscriptions = (0..nsubscriptions).map(|i| ptr_in + i * SUBSCRIPTION_SIZE);
+        let mut earliest_timeout = u64::MAX;
+        let mut ready = Vec::with_capacity(nsubscriptions);
+        for ptr_sub in subscriptions {
+            let tag = memory[ptr_sub + 8];
+            if tag == EVENTTYPE_CLOCK {
+                let clock_id = read_i32(memory, ptr_sub + 16);
+                let timeout = read_u64(memory, ptr_sub + 24);
+                let flags = read_u16(memory, ptr_sub + 40);
+                let relative_timeout = if flags & SUBCLOCKFLAGS_ABSTIME != 0 {
+                    timeout.saturating_sub(self.clock_time(clock_id)?)
+                } else {
+                    timeout
+                };
+                earliest_timeout = earliest_timeout.min(relative_timeout);
+            } else {
+                ready.push(ptr_sub);
+            }
+        }
+
+        if ready.is_empty() {
+            std::thread::sleep(Duration::from_nanos(earliest_timeout));
+            for ptr_sub in (0..nsubscriptions).map(|i| ptr_in + i * SUBSCRIPTION_SIZE) {
+                let clock_id = read_i32(memory, ptr_sub + 16);
+                let timeout = read_u64(memory, ptr_sub + 24);
+                let flags = read_u16(memory, ptr_sub + 40);
+                let relative_timeout = if flags & SUBCLOCKFLAGS_ABSTIME != 0 {
+                    timeout.saturating_sub(self.clock_time(clock_id)?)
+                } else {
+                    timeout.saturating_sub(earliest_timeout)
+                };
+                if relative_timeout == 0 {
+                    ready.push(ptr_sub);
+                }
+            }
+        }
+
+        for (i, ptr_sub) in ready.iter().enumerate() {
+            let ptr_event = ptr_out + i * EVENT_SIZE;
+            memory[ptr_event..][..EVENT_SIZE].fill(0);
+            memory.copy_within(*ptr_sub..*ptr_sub + 8, ptr_event); // userdata
+            memory[ptr_event + 10] = memory[*ptr_sub + 8]; // type
+        }
+        write_u32(memory, ptr_nevents, ready.len() as u32);
+        Ok(())
+    }
+}
+
+fn errno_result(result: Result<(), Errno>) -> Option<Value> {
+    let errno = result.err().unwrap_or(Errno::Success);
+    Some(Value::I32(errno as i32))
+}
+
+/// Read a path string from app memory, given `[pointer, length]` arguments
+fn read_path<'m>(memory: &'m [u8], arguments: &[Value]) -> Result<&'m str, Errno> {
+    let ptr = arguments[0].expect_i32().unwrap() as usize;
+    let len = arguments[1].expect_i32().unwrap() as usize;
+    std::str::from_utf8(&memory[ptr..][..len]).map_err(|_| Errno::Ilseq)
+}
+
+/// Read an array of iovecs from app memory, as (base, length) pairs
+fn read_iovecs(memory: &[u8], ptr_iovs: usize, iovs_len: usize) -> Vec<(usize, usize)> {
+    // https://man7.org/linux/man-pages/man2/readv.2.html
+    // struct iovec {
+    //     void  *iov_base;    /* Starting address */
+    //     size_t iov_len;     /* Number of bytes to transfer */
+    // };
+    (0..iovs_len)
+        .map(|i| {
+            let ptr_iov = ptr_iovs + 8 * i;
+            let iov_base = read_u32(memory, ptr_iov) as usize;
+            let iov_len = read_u32(memory, ptr_iov + 4) as usize;
+            (iov_base, iov_len)
+        })
+        .collect()
+}
+
+/// Read into each iovec in turn, stopping early on a short read so we don't block
+fn read_into_iovecs(
+    reader: &mut impl Read,
+    memory: &mut [u8],
+    ptr_iovs: usize,
+    iovs_len: usize,
+) -> Result<usize, Errno> {
+    let mut n_read = 0;
+    for (iov_base, iov_len) in read_iovecs(memory, ptr_iovs, iovs_len) {
+        let n = reader.read(&mut memory[iov_base..][..iov_len])?;
+        n_read += n;
+        if n < iov_len {
+            break;
+        }
+    }
+    Ok(n_read)
+}
+
+fn set_times(file: &fs::File, atim: u64, mtim: u64, fst_flags: i32) -> Result<(), Errno> {
+    let time = |nanos: u64, flag: i32, now_flag: i32| {
+        if fst_flags & now_flag != 0 {
+            Some(SystemTime::now())
+        } else if fst_flags & flag != 0 {
+            Some(UNIX_EPOCH + Duration::from_nanos(nanos))
+        } else {
+            None
+        }
+    };
+    let mut times = fs::FileTimes::new();
+    if let Some(t) = time(atim, FSTFLAGS_ATIM, FSTFLAGS_ATIM_NOW) {
+        times = times.set_accessed(t);
+    }
+    if let Some(t) = time(mtim, FSTFLAGS_MTIM, FSTFLAGS_MTIM_NOW) {
+        times = times.set_modified(t);
+    }
+    file.set_times(times)?;
+    Ok(())
+}
+
+fn filetype_code(filetype: fs::FileType) -> u8 {
+    if filetype.is_dir() {
+        FILETYPE_DIRECTORY
+    } else if filetype.is_file() {
+        FILETYPE_REGULAR_FILE
+    } else if filetype.is_symlink() {
+        FILETYPE_SYMBOLIC_LINK
+    } else {
+        FILETYPE_UNKNOWN
+    }
+}
+
+#[cfg(unix)]
+fn inode(metadata: &fs::Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::ino(metadata)
+}
+
+#[cfg(not(unix))]
+fn inode(_metadata: &fs::Metadata) -> u64 {
+    0
+}
+
+/// Write a WASI `filestat` struct into app memory
+fn write_filestat(memory: &mut [u8], ptr_filestat: usize, metadata: &fs::Metadata) {
+    let nanos = |time: io::Result<SystemTime>| {
+        time.ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos() as u64)
+    };
+
+    #[cfg(unix)]
+    let (dev, nlink) = {
+        use std::os::unix::fs::MetadataExt;
+        (metadata.dev(), metadata.nlink())
+    };
+    #[cfg(not(unix))]
+    let (dev, nlink) = (0, 1);
+
+    memory[ptr_filestat..][..FILESTAT_SIZE].fill(0);
+    write_u64(memory, ptr_filestat, dev);
+    write_u64(memory, ptr_filestat + 8, inode(metadata));
+    memory[ptr_filestat + 16] = filetype_code(metadata.file_type());
+    write_u64(memory, ptr_filestat + 24, nlink);
+    write_u64(memory, ptr_filestat + 32, metadata.len());
+    write_u64(memory, ptr_filestat + 40, nanos(metadata.accessed()));
+    write_u64(memory, ptr_filestat + 48, nanos(metadata.modified()));
+    // Creation time is the closest thing to "status change time" that's portable
+    write_u64(memory, ptr_filestat + 56, nanos(metadata.created()));
+}
+
+fn read_u16(memory: &[u8], addr: usize) -> u16 {
+    let mut bytes = [0; 2];
+    bytes.copy_from_slice(&memory[addr..][..2]);
+    u16::from_le_bytes(bytes)
 }
 
 fn read_u32(memory: &[u8], addr: usize) -> u32 {
@@ -347,6 +1172,12 @@ fn read_i32(memory: &[u8], addr: usize) -> i32 {
     i32::from_le_bytes(bytes)
 }
 
+fn read_u64(memory: &[u8], addr: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&memory[addr..][..8]);
+    u64::from_le_bytes(bytes)
+}
+
 fn write_u32(memory: &mut [u8], addr: usize, value: u32) {
     memory[addr..][..4].copy_from_slice(&value.to_le_bytes());
 }
@@ -355,6 +1186,28 @@ fn write_i32(memory: &mut [u8], addr: usize, value: i32) {
     memory[addr..][..4].copy_from_slice(&value.to_le_bytes());
 }
 
+fn write_u64(memory: &mut [u8], addr: usize, value: u64) {
+    memory[addr..][..8].copy_from_slice(&value.to_le_bytes());
+}
+
+impl From<io::Error> for Errno {
+    fn from(error: io::Error) -> Self {
+        use io::ErrorKind::*;
+        match error.kind() {
+            NotFound => Errno::Noent,
+            PermissionDenied => Errno::Access,
+            AlreadyExists => Errno::Exist,
+            WouldBlock => Errno::Again,
+            InvalidInput => Errno::Inval,
+            InvalidData => Errno::Ilseq,
+            Interrupted => Errno::Intr,
+            Unsupported => Errno::Notsup,
+            OutOfMemory => Errno::Nomem,
+            _ => Errno::Io,
+        }
+    }
+}
+
 /// Error codes returned by functions.
 /// Not all of these error codes are returned by the functions provided by this
 /// API; some are used in higher-level library layers, and others are provided
@@ -0,0 +1,68 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use wast::parser::{self, ParseBuffer};
+use wast::Wat;
+
+/// An app that copies a line from stdin to stdout
+const ECHO_APP: &str = r#"
+(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory 1)
+  (func (export "_start")
+    ;; One iovec at 0x100, pointing at a 64-byte buffer at 0x200
+    (i32.store (i32.const 0x100) (i32.const 0x200))
+    (i32.store (i32.const 0x104) (i32.const 64))
+    (drop (call $fd_read (i32.const 0) (i32.const 0x100) (i32.const 1) (i32.const 0x108)))
+    ;; Write out as many bytes as we read
+    (i32.store (i32.const 0x104) (i32.load (i32.const 0x108)))
+    (drop (call $fd_write (i32.const 1) (i32.const 0x100) (i32.const 1) (i32.const 0x108)))))
+"#;
+
+#[test]
+fn test_debugger_with_app_reading_stdin() {
+    let buf = ParseBuffer::new(ECHO_APP).unwrap();
+    let mut wat = parser::parse::<Wat>(&buf).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let wasm_path = dir.path().join("echo.wasm");
+    std::fs::write(&wasm_path, wat.encode().unwrap()).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_roc_wasm_interp"))
+        .arg("--debugger")
+        .arg(&wasm_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // The debugger gets the first line, and the app gets the rest
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"continue\nhello from stdin\n")
+        .unwrap();
+
+    // If the debugger kept stdin locked, the app would hang when it tried to read from it
+    let (sender, receiver) = mpsc::channel();
+    let child_id = child.id();
+    std::thread::spawn(move || sender.send(child.wait_with_output()));
+    let output = match receiver.recv_timeout(Duration::from_secs(30)) {
+        Ok(output) => output.unwrap(),
+        Err(_) => {
+            let _ = Command::new("kill").arg(child_id.to_string()).status();
+            panic!("The app never finished reading stdin");
+        }
+    };
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "hello from stdin\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("(wasm) "));
+}
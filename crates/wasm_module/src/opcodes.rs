@@ -547,12 +547,15 @@ impl SkipBytes for OpCode {
                 match MemoryInstruction::try_from(bytes[*cursor + 1]) {
                     Ok(op) => match op {
                         MemoryInstruction::MemoryInit => {
-                            // memory.init
-                            todo!("WASM instruction: memory.init")
+                            // memory.init x, followed by a zero byte for the memory index
+                            *cursor += 1 + 1;
+                            u32::skip_bytes(bytes, cursor)?;
+                            *cursor += 1;
                         }
                         MemoryInstruction::DataDrop => {
                             // data.drop x
-                            todo!("WASM instruction: data.drop")
+                            *cursor += 1 + 1;
+                            u32::skip_bytes(bytes, cursor)?;
                         }
                         MemoryInstruction::MemoryCopy => {
                             // memory.copy
@@ -1,5 +1,4 @@
 use std::fmt::{Debug, Formatter};
-use std::io::Write;
 
 use bumpalo::collections::vec::Vec;
 use bumpalo::Bump;
@@ -1571,27 +1570,37 @@ impl<'a> DataSection<'a> {
         index
     }
 
-    pub fn load_into(&self, memory: &mut [u8]) -> Result<(), String> {
+    /// The mode and contents of each segment, in index order
+    pub fn segments(&self) -> Result<std::vec::Vec<(DataMode, &[u8])>, ParseError> {
         let mut cursor = 0;
+        let mut segments = std::vec::Vec::with_capacity(self.count as usize);
         for _ in 0..self.count {
-            let mode =
-                DataMode::parse((), &self.bytes, &mut cursor).map_err(|e| format!("{e:?}"))?;
-            let start = match mode {
-                DataMode::Active {
-                    offset: ConstExpr::I32(addr),
-                } => addr as usize,
-                _ => {
-                    continue;
-                }
-            };
-            let len32 = u32::parse((), &self.bytes, &mut cursor).map_err(|e| format!("{e:?}"))?;
-            let len = len32 as usize;
-            let mut target_slice = &mut memory[start..][..len];
-            target_slice
-                .write(&self.bytes[cursor..][..len])
-                .map_err(|e| format!("{e:?}"))?;
+            let mode = DataMode::parse((), &self.bytes, &mut cursor)?;
+            let len = u32::parse((), &self.bytes, &mut cursor)? as usize;
+            segments.push((mode, &self.bytes[cursor..][..len]));
             cursor += len;
         }
+        Ok(segments)
+    }
+
+    /// Copy the active segments into memory. Passive segments are only loaded by `memory.init`.
+    pub fn load_into(&self, memory: &mut [u8]) -> Result<(), String> {
+        let segments = self.segments().map_err(|e| format!("{e:?}"))?;
+        for (mode, init) in segments {
+            if let DataMode::Active {
+                offset: ConstExpr::I32(addr),
+            } = mode
+            {
+                let start = addr as u32 as usize;
+                let target = memory.get_mut(start..start + init.len()).ok_or_else(|| {
+                    format!(
+                        "Data segment at {start:#x} with length {:#x} is out of bounds of memory",
+                        init.len()
+                    )
+                })?;
+                target.copy_from_slice(init);
+            }
+        }
         Ok(())
     }
 }
@@ -1789,30 +1798,27 @@ impl<'a> Parse<&'a Bump> for NameSection<'a> {
             return Ok(Self::new(arena));
         }
 
-        // Find function names subsection
-        let mut found_function_names = false;
-        for _possible_subsection_id in 0..2 {
+        // Find function names subsection, skipping any others (module name, local names, labels...)
+        loop {
+            if *cursor >= section_end {
+                // No function names, only other kinds of names
+                *cursor = section_end;
+                return Ok(Self::new(arena));
+            }
             let subsection_id = module_bytes[*cursor];
             *cursor += 1;
             let subsection_size = u32::parse((), module_bytes, cursor)?;
             if subsection_id == NameSubSections::FunctionNames as u8 {
-                found_function_names = true;
                 break;
             }
             *cursor += subsection_size as usize;
-            if *cursor >= section_end {
+            if *cursor > section_end {
                 return Err(ParseError {
                     message: "Failed to parse Name section".into(),
                     offset: *cursor,
                 });
             }
         }
-        if !found_function_names {
-            return Err(ParseError {
-                message: "Failed to parse Name section".into(),
-                offset: *cursor,
-            });
-        }
 
         let count = u32::parse((), module_bytes, cursor)?;
         let mut section = NameSection {
@@ -246,7 +246,7 @@ async function processInputQueue() {
 
 var ROC_PANIC_INFO = null;
 
-function send_panic_msg_to_js(rocstr_ptr, panic_tag) {
+function decode_roc_str(rocstr_ptr) {
   const { memory } = repl.app.exports;
 
   const rocStrBytes = new Int8Array(memory.buffer, rocstr_ptr, 12);
@@ -271,14 +271,23 @@ function send_panic_msg_to_js(rocstr_ptr, panic_tag) {
     stringBytes = new Uint8Array(memory.buffer, ptr, length);
   }
 
-  const decodedString = repl.textDecoder.decode(stringBytes);
+  return repl.textDecoder.decode(stringBytes);
+}
 
+function send_panic_msg_to_js(rocstr_ptr, panic_tag) {
   ROC_PANIC_INFO = {
-    msg: decodedString,
+    msg: decode_roc_str(rocstr_ptr),
     panic_tag: panic_tag,
   };
 }
 
+function roc_dbg(loc_ptr, msg_ptr, src_ptr) {
+  const loc = decode_roc_str(loc_ptr);
+  const msg = decode_roc_str(msg_ptr);
+  const src = decode_roc_str(src_ptr);
+  console.log(`[${loc}] ${src} = ${msg}`);
+}
+
 // Load Wasm code into the browser's virtual machine, so we can run it later.
 // This operation is async, so we call it before entering any code shared
 // with the command-line REPL, which is sync.
@@ -286,6 +295,7 @@ async function js_create_app(wasm_module_bytes) {
   const { instance } = await WebAssembly.instantiate(wasm_module_bytes, {
     env: {
       send_panic_msg_to_js: send_panic_msg_to_js,
+      roc_dbg: roc_dbg,
     },
   });
 